@@ -0,0 +1,805 @@
+//! Repository configuration (`.git/config`)
+//!
+//! The config file is an INI-like file made of sections and variables:
+//!
+//! ```text
+//! # comment
+//! [core]
+//!     bare = false
+//! [remote "origin"]
+//!     url = https://example.com/repo.git
+//!     fetch = +refs/heads/*:refs/remotes/origin/*
+//! ```
+//!
+//! Besides the repository's own file, the user's global config
+//! (`~/.gitconfig`) can be read through [`Config::global`].
+//!
+//! Variables are addressed by keys of the form `section.name` or
+//! `section.subsection.name`. Section and variable names are
+//! case-insensitive, subsection names are not. A key may hold several
+//! values (multi-valued keys); reads return the last one.
+//!
+//! ## Editing
+//!
+//! The file is kept as a list of lines, so edits only touch the lines they
+//! concern: comments, blank lines and the ordering of every other entry are
+//! preserved when the file is written back.
+//!
+//! Files written on Windows are read as well: a leading UTF-8 byte order
+//! mark is skipped and `\r\n` line endings are accepted. Rewritten files
+//! use `\n`.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const UTF8_BOM: char = '\u{feff}';
+const GLOBAL_CONFIG_ENV: &str = "GIT_CONFIG_GLOBAL";
+const GLOBAL_CONFIG_FILE: &str = ".gitconfig";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+    #[error("bad config line {line} in {path}")]
+    Parse { path: String, line: usize },
+    #[error("key {0} does not exist")]
+    KeyNotFound(String),
+    #[error("key {0} has multiple values")]
+    MultipleValues(String),
+    #[error("bad boolean config value '{value}' for '{key}'")]
+    InvalidBool { key: String, value: String },
+    #[error("bad numeric config value '{value}' for '{key}'")]
+    InvalidInt { key: String, value: String },
+    #[error("failed to read config file at {path}")]
+    ReadConfig {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("could not lock config file {path}: File exists")]
+    Locked { path: String },
+    #[error("failed to write config file at {path}")]
+    WriteConfig {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Repository configuration manager
+///
+/// Every operation loads the config file, applies its change and writes the
+/// file back, so no state is cached between calls.
+#[derive(Debug)]
+pub struct Config {
+    /// Path to the config file (typically `.git/config`)
+    path: Box<Path>,
+}
+
+impl Config {
+    pub fn new(path: Box<Path>) -> Self {
+        Config { path }
+    }
+
+    /// The user's global config
+    ///
+    /// Read from `$GIT_CONFIG_GLOBAL` if set, `~/.gitconfig` otherwise; `None` if
+    /// neither can be located.
+    pub fn global() -> Option<Self> {
+        let path = match std::env::var_os(GLOBAL_CONFIG_ENV) {
+            Some(path) => PathBuf::from(path),
+            None => PathBuf::from(std::env::var_os("HOME")?).join(GLOBAL_CONFIG_FILE),
+        };
+
+        Some(Config::new(path.into_boxed_path()))
+    }
+
+    /// Get the last value of a key
+    pub fn get(&self, key: &str) -> Result<Option<String>, ConfigError> {
+        Ok(self.get_all(key)?.pop())
+    }
+
+    /// Get the last value of a key, from the user's global config if this
+    /// one does not set it
+    pub fn get_or_global(&self, key: &str) -> Result<Option<String>, ConfigError> {
+        match self.get(key)? {
+            Some(value) => Ok(Some(value)),
+            None => Config::global().map_or(Ok(None), |global| global.get(key)),
+        }
+    }
+
+    /// Get the last value of a key as a boolean
+    ///
+    /// Accepts `true`/`yes`/`on`/`1` and `false`/`no`/`off`/`0`/empty, in any case.
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>, ConfigError> {
+        let Some(value) = self.get(key)? else {
+            return Ok(None);
+        };
+
+        match value.to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(Some(true)),
+            "false" | "no" | "off" | "0" | "" => Ok(Some(false)),
+            _ => Err(ConfigError::InvalidBool {
+                key: key.to_string(),
+                value,
+            }),
+        }
+    }
+
+    /// Get the last value of a key as an integer
+    ///
+    /// Accepts an optional `k`, `m` or `g` suffix (any case) scaling the value
+    /// by 1024, 1024² or 1024³.
+    pub fn get_int(&self, key: &str) -> Result<Option<i64>, ConfigError> {
+        let Some(value) = self.get(key)? else {
+            return Ok(None);
+        };
+        let invalid = || ConfigError::InvalidInt {
+            key: key.to_string(),
+            value: value.clone(),
+        };
+
+        let trimmed = value.trim();
+        let (number, scale) = match trimmed.chars().last().map(|c| c.to_ascii_lowercase()) {
+            Some('k') => (&trimmed[..trimmed.len() - 1], 1 << 10),
+            Some('m') => (&trimmed[..trimmed.len() - 1], 1 << 20),
+            Some('g') => (&trimmed[..trimmed.len() - 1], 1 << 30),
+            _ => (trimmed, 1),
+        };
+        let number = number.parse::<i64>().map_err(|_| invalid())?;
+
+        number.checked_mul(scale).map(Some).ok_or_else(invalid)
+    }
+
+    /// Get every value of a key, in file order
+    pub fn get_all(&self, key: &str) -> Result<Vec<String>, ConfigError> {
+        let key = ConfigKey::parse(key)?;
+        let file = self.load()?;
+
+        Ok(file
+            .matching_lines(&key)
+            .into_iter()
+            .filter_map(|index| file.lines[index].value().map(str::to_string))
+            .collect())
+    }
+
+    /// Set a key to a single value
+    ///
+    /// Replaces the existing value in place, or adds the key if it is not set.
+    /// Fails if the key has multiple values.
+    pub fn set(&self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let key = ConfigKey::parse(key)?;
+        let mut file = self.load()?;
+
+        match file.matching_lines(&key).as_slice() {
+            [] => file.add(&key, value),
+            [index] => file.lines[*index] = ConfigLine::variable(&key, value),
+            _ => return Err(ConfigError::MultipleValues(key.to_string())),
+        }
+
+        self.save(&file)
+    }
+
+    /// Append a new value to a key, keeping any existing values
+    pub fn add(&self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let key = ConfigKey::parse(key)?;
+        let mut file = self.load()?;
+
+        file.add(&key, value);
+
+        self.save(&file)
+    }
+
+    /// Remove the single value of a key
+    ///
+    /// Fails if the key is not set or has multiple values.
+    pub fn unset(&self, key: &str) -> Result<(), ConfigError> {
+        let key = ConfigKey::parse(key)?;
+        let mut file = self.load()?;
+
+        match file.matching_lines(&key).as_slice() {
+            [] => return Err(ConfigError::KeyNotFound(key.to_string())),
+            [index] => {
+                file.lines.remove(*index);
+            }
+            _ => return Err(ConfigError::MultipleValues(key.to_string())),
+        }
+
+        self.save(&file)
+    }
+
+    /// Remove every value of a key
+    pub fn unset_all(&self, key: &str) -> Result<(), ConfigError> {
+        let key = ConfigKey::parse(key)?;
+        let mut file = self.load()?;
+
+        let indices = file.matching_lines(&key);
+        if indices.is_empty() {
+            return Err(ConfigError::KeyNotFound(key.to_string()));
+        }
+        for index in indices.into_iter().rev() {
+            file.lines.remove(index);
+        }
+
+        self.save(&file)
+    }
+
+    fn load(&self) -> Result<ConfigFile, ConfigError> {
+        if !self.path.exists() {
+            return Ok(ConfigFile::default());
+        }
+
+        let content = std::fs::read_to_string(&self.path).map_err(|e| ConfigError::ReadConfig {
+            path: self.path.display().to_string(),
+            source: e,
+        })?;
+
+        ConfigFile::parse(&content).map_err(|line| ConfigError::Parse {
+            path: self.path.display().to_string(),
+            line,
+        })
+    }
+
+    fn save(&self, file: &ConfigFile) -> Result<(), ConfigError> {
+        let lock_path = self.path.with_extension("lock");
+        let write_error = |e| ConfigError::WriteConfig {
+            path: self.path.display().to_string(),
+            source: e,
+        };
+
+        // Creating the lock file fails if another process holds it
+        let mut lock = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock_path)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::AlreadyExists => ConfigError::Locked {
+                    path: lock_path.display().to_string(),
+                },
+                _ => write_error(e),
+            })?;
+
+        let written = lock
+            .write_all(file.to_string().as_bytes())
+            .and_then(|_| std::fs::rename(&lock_path, &self.path));
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&lock_path);
+            return Err(write_error(e));
+        }
+
+        Ok(())
+    }
+}
+
+/// A parsed `section[.subsection].name` key
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ConfigKey {
+    section: SectionName,
+    name: String,
+}
+
+impl ConfigKey {
+    fn parse(key: &str) -> Result<Self, ConfigError> {
+        let invalid = || ConfigError::InvalidKey(key.to_string());
+
+        let (section, name) = key.split_once('.').ok_or_else(invalid)?;
+        let (subsection, name) = match name.rsplit_once('.') {
+            Some((subsection, name)) => (Some(subsection.to_string()), name),
+            None => (None, name),
+        };
+
+        if !is_valid_section(section) || !is_valid_name(name) {
+            return Err(invalid());
+        }
+
+        Ok(ConfigKey {
+            section: SectionName {
+                name: section.to_lowercase(),
+                subsection,
+            },
+            name: name.to_lowercase(),
+        })
+    }
+}
+
+impl std::fmt::Display for ConfigKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.section.subsection {
+            Some(subsection) => write!(f, "{}.{}.{}", self.section.name, subsection, self.name),
+            None => write!(f, "{}.{}", self.section.name, self.name),
+        }
+    }
+}
+
+/// A section header, normalized for comparison
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SectionName {
+    /// Lowercased section name
+    name: String,
+    /// Case-sensitive subsection name
+    subsection: Option<String>,
+}
+
+impl std::fmt::Display for SectionName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.subsection {
+            Some(subsection) => write!(f, "[{} \"{}\"]", self.name, escape(subsection)),
+            None => write!(f, "[{}]", self.name),
+        }
+    }
+}
+
+/// A single line of the config file, with its original text
+#[derive(Debug, Clone)]
+enum ConfigLine {
+    /// Blank line or comment
+    Other(String),
+    /// Section header
+    Section { text: String, section: SectionName },
+    /// Variable assignment inside the preceding section
+    Variable {
+        text: String,
+        section: SectionName,
+        name: String,
+        value: String,
+    },
+}
+
+impl ConfigLine {
+    fn variable(key: &ConfigKey, value: &str) -> Self {
+        ConfigLine::Variable {
+            text: format!("\t{} = {}", key.name, format_value(value)),
+            section: key.section.clone(),
+            name: key.name.clone(),
+            value: value.to_string(),
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            ConfigLine::Other(text)
+            | ConfigLine::Section { text, .. }
+            | ConfigLine::Variable { text, .. } => text,
+        }
+    }
+
+    fn section(&self) -> Option<&SectionName> {
+        match self {
+            ConfigLine::Other(_) => None,
+            ConfigLine::Section { section, .. } | ConfigLine::Variable { section, .. } => {
+                Some(section)
+            }
+        }
+    }
+
+    fn value(&self) -> Option<&str> {
+        match self {
+            ConfigLine::Variable { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, key: &ConfigKey) -> bool {
+        matches!(self, ConfigLine::Variable { section, name, .. }
+            if *section == key.section && *name == key.name)
+    }
+}
+
+/// The lines of a config file
+#[derive(Debug, Clone, Default)]
+struct ConfigFile {
+    lines: Vec<ConfigLine>,
+}
+
+impl ConfigFile {
+    /// Parse a config file, returning the 1-based number of a malformed line
+    fn parse(content: &str) -> Result<Self, usize> {
+        let content = content.strip_prefix(UTF8_BOM).unwrap_or(content);
+        let mut lines = Vec::new();
+        let mut current: Option<SectionName> = None;
+
+        for (number, text) in content.lines().enumerate() {
+            let trimmed = text.trim();
+
+            let line = if trimmed.is_empty() || trimmed.starts_with(['#', ';']) {
+                ConfigLine::Other(text.to_string())
+            } else if trimmed.starts_with('[') {
+                let section = parse_section(trimmed).ok_or(number + 1)?;
+                current = Some(section.clone());
+                ConfigLine::Section {
+                    text: text.to_string(),
+                    section,
+                }
+            } else {
+                let section = current.clone().ok_or(number + 1)?;
+                let (name, value) = parse_variable(trimmed).ok_or(number + 1)?;
+                ConfigLine::Variable {
+                    text: text.to_string(),
+                    section,
+                    name,
+                    value,
+                }
+            };
+
+            lines.push(line);
+        }
+
+        Ok(ConfigFile { lines })
+    }
+
+    fn matching_lines(&self, key: &ConfigKey) -> Vec<usize> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.matches(key))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Add a value after the last entry of the key's section
+    ///
+    /// The section is created at the end of the file if it does not exist.
+    fn add(&mut self, key: &ConfigKey, value: &str) {
+        let position = self
+            .lines
+            .iter()
+            .rposition(|line| line.section() == Some(&key.section));
+
+        match position {
+            Some(index) => self
+                .lines
+                .insert(index + 1, ConfigLine::variable(key, value)),
+            None => {
+                self.lines.push(ConfigLine::Section {
+                    text: key.section.to_string(),
+                    section: key.section.clone(),
+                });
+                self.lines.push(ConfigLine::variable(key, value));
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line.text())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn is_valid_section(section: &str) -> bool {
+    !section.is_empty()
+        && section
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+}
+
+fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Parse `[section]`, `[section "subsection"]` or the legacy `[section.subsection]`
+fn parse_section(line: &str) -> Option<SectionName> {
+    let (header, rest) = line.strip_prefix('[')?.split_once(']')?;
+    if !is_comment_or_empty(rest) {
+        return None;
+    }
+
+    match header.split_once(char::is_whitespace) {
+        Some((name, subsection)) => {
+            let subsection = subsection.trim().strip_prefix('"')?.strip_suffix('"')?;
+            if !is_valid_section(name) {
+                return None;
+            }
+
+            Some(SectionName {
+                name: name.to_lowercase(),
+                subsection: Some(unescape(subsection)?),
+            })
+        }
+        None => {
+            let header = header.trim();
+            if !is_valid_section(header) {
+                return None;
+            }
+
+            Some(match header.split_once('.') {
+                Some((name, subsection)) => SectionName {
+                    name: name.to_lowercase(),
+                    subsection: Some(subsection.to_lowercase()),
+                },
+                None => SectionName {
+                    name: header.to_lowercase(),
+                    subsection: None,
+                },
+            })
+        }
+    }
+}
+
+/// Parse `name = value`; a bare `name` is a boolean true
+fn parse_variable(line: &str) -> Option<(String, String)> {
+    let (name, value) = match line.split_once('=') {
+        Some((name, value)) => (name.trim(), parse_value(value)?),
+        None => {
+            let name = line.split(['#', ';']).next().unwrap_or_default().trim();
+            (name, "true".to_string())
+        }
+    };
+
+    is_valid_name(name).then(|| (name.to_lowercase(), value))
+}
+
+/// Decode a value: strip comments and surrounding whitespace, handle quotes and escapes
+fn parse_value(raw: &str) -> Option<String> {
+    let mut value = String::new();
+    let mut pending_whitespace = String::new();
+    let mut in_quotes = false;
+    let mut chars = raw.trim_start().chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '#' | ';' if !in_quotes => break,
+            '\\' => {
+                value.push_str(&std::mem::take(&mut pending_whitespace));
+                value.push(match chars.next()? {
+                    'n' => '\n',
+                    't' => '\t',
+                    'b' => '\u{8}',
+                    c @ ('"' | '\\') => c,
+                    _ => return None,
+                });
+                continue;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                pending_whitespace.push(c);
+                continue;
+            }
+            c => {
+                value.push_str(&std::mem::take(&mut pending_whitespace));
+                value.push(c);
+                continue;
+            }
+        }
+        value.push_str(&std::mem::take(&mut pending_whitespace));
+    }
+
+    (!in_quotes).then_some(value)
+}
+
+/// Encode a value, quoting it when it would not survive parsing as-is
+fn format_value(value: &str) -> String {
+    let escaped = escape(value);
+    let needs_quotes = value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value.contains(['#', ';']);
+
+    if needs_quotes {
+        format!("\"{}\"", escaped)
+    } else {
+        escaped
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
+fn unescape(value: &str) -> Option<String> {
+    let mut result = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            result.push(chars.next()?);
+        } else {
+            result.push(c);
+        }
+    }
+
+    Some(result)
+}
+
+fn is_comment_or_empty(text: &str) -> bool {
+    let text = text.trim();
+    text.is_empty() || text.starts_with(['#', ';'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SAMPLE: &str = "# top comment\n\
+        [core]\n\
+        \tbare = false ; inline comment\n\
+        \n\
+        [remote \"origin\"]\n\
+        \turl = https://example.com/repo.git\n\
+        \tfetch = +refs/heads/*:refs/remotes/origin/*\n";
+
+    fn sample_config() -> (assert_fs::TempDir, Config) {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("config");
+        std::fs::write(&path, SAMPLE).unwrap();
+        (dir, Config::new(path.into_boxed_path()))
+    }
+
+    #[test]
+    fn test_parse_key_with_subsection() {
+        let key = ConfigKey::parse("Remote.origin.URL").unwrap();
+
+        assert_eq!(key.section.name, "remote");
+        assert_eq!(key.section.subsection.as_deref(), Some("origin"));
+        assert_eq!(key.name, "url");
+    }
+
+    #[test]
+    fn test_parse_key_rejects_missing_section() {
+        assert!(ConfigKey::parse("name").is_err());
+        assert!(ConfigKey::parse("core.").is_err());
+    }
+
+    #[test]
+    fn test_parse_value_handles_quotes_comments_and_escapes() {
+        assert_eq!(parse_value(" false ; comment").unwrap(), "false");
+        assert_eq!(
+            parse_value(" \" padded # not a comment \"").unwrap(),
+            " padded # not a comment "
+        );
+        assert_eq!(parse_value(r#" say \"hi\"\n"#).unwrap(), "say \"hi\"\n");
+        assert!(parse_value(" \"unterminated").is_none());
+    }
+
+    #[test]
+    fn test_get_reads_values_case_insensitively() {
+        let (_dir, config) = sample_config();
+
+        assert_eq!(config.get("CORE.Bare").unwrap().as_deref(), Some("false"));
+        assert_eq!(
+            config.get("remote.origin.url").unwrap().as_deref(),
+            Some("https://example.com/repo.git")
+        );
+        assert_eq!(config.get("remote.Origin.url").unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_file_with_bom_and_crlf_line_endings() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("config");
+        std::fs::write(
+            &path,
+            "\u{feff}[core]\r\n\tbare = false\r\n[user]\r\n\tname = A U Thor\r\n",
+        )
+        .unwrap();
+        let config = Config::new(path.clone().into_boxed_path());
+
+        assert_eq!(config.get("core.bare").unwrap().as_deref(), Some("false"));
+        assert_eq!(
+            config.get("user.name").unwrap().as_deref(),
+            Some("A U Thor")
+        );
+
+        config.set("user.email", "author@example.com").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "[core]\n\tbare = false\n[user]\n\tname = A U Thor\n\temail = author@example.com\n"
+        );
+    }
+
+    #[test]
+    fn test_get_bool_parses_boolean_values() {
+        let (_dir, config) = sample_config();
+
+        assert_eq!(config.get_bool("core.bare").unwrap(), Some(false));
+        assert_eq!(config.get_bool("core.missing").unwrap(), None);
+
+        config.set("core.quotePath", "Yes").unwrap();
+        assert_eq!(config.get_bool("core.quotepath").unwrap(), Some(true));
+
+        config.set("core.quotePath", "maybe").unwrap();
+        assert!(matches!(
+            config.get_bool("core.quotePath"),
+            Err(ConfigError::InvalidBool { .. })
+        ));
+    }
+
+    #[test]
+    fn test_get_int_parses_scaled_values() {
+        let (_dir, config) = sample_config();
+
+        config.set("gc.auto", "42").unwrap();
+        assert_eq!(config.get_int("gc.auto").unwrap(), Some(42));
+        config.set("core.bigFileThreshold", "512k").unwrap();
+        assert_eq!(
+            config.get_int("core.bigFileThreshold").unwrap(),
+            Some(512 * 1024)
+        );
+        config.set("gc.auto", "many").unwrap();
+        assert!(matches!(
+            config.get_int("gc.auto"),
+            Err(ConfigError::InvalidInt { .. })
+        ));
+        assert_eq!(config.get_int("gc.missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_replaces_value_in_place() {
+        let (dir, config) = sample_config();
+
+        config.set("core.bare", "true").unwrap();
+
+        let content = std::fs::read_to_string(dir.path().join("config")).unwrap();
+        assert_eq!(
+            content,
+            SAMPLE.replace("\tbare = false ; inline comment", "\tbare = true")
+        );
+    }
+
+    #[test]
+    fn test_set_fails_while_config_is_locked() {
+        let (dir, config) = sample_config();
+        let lock_path = dir.path().join("config.lock");
+        std::fs::write(&lock_path, "held by another writer").unwrap();
+
+        assert!(matches!(
+            config.set("core.bare", "true"),
+            Err(ConfigError::Locked { .. })
+        ));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("config")).unwrap(),
+            SAMPLE
+        );
+        assert_eq!(
+            std::fs::read_to_string(&lock_path).unwrap(),
+            "held by another writer"
+        );
+
+        std::fs::remove_file(&lock_path).unwrap();
+        config.set("core.bare", "true").unwrap();
+        assert!(!lock_path.exists());
+    }
+
+    #[test]
+    fn test_add_creates_missing_section_at_end() {
+        let (dir, config) = sample_config();
+
+        config.add("user.name", "Jane Doe").unwrap();
+
+        let content = std::fs::read_to_string(dir.path().join("config")).unwrap();
+        assert_eq!(content, format!("{}[user]\n\tname = Jane Doe\n", SAMPLE));
+    }
+
+    #[test]
+    fn test_value_round_trips_through_quoting() {
+        let (_dir, config) = sample_config();
+        let value = " tricky \"value\" # with ; comment chars\t";
+
+        config.set("core.editor", value).unwrap();
+
+        assert_eq!(config.get("core.editor").unwrap().as_deref(), Some(value));
+    }
+
+    #[test]
+    fn test_malformed_line_is_reported() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("config");
+        std::fs::write(&path, "orphan = value\n").unwrap();
+
+        let config = Config::new(path.into_boxed_path());
+
+        assert!(matches!(
+            config.get("core.bare"),
+            Err(ConfigError::Parse { line: 1, .. })
+        ));
+    }
+}
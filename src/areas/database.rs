@@ -9,21 +9,34 @@
 //! Objects are stored as:
 //! - Path: `.git/objects/ab/cdef123...` (first 2 chars as directory, rest as filename)
 //! - Content: Compressed (zlib) format containing type, size, and data
+//!
+//! After `gc`, objects may instead live in `.git/objects/pack/pack-<sha>.pack`,
+//! located through the matching `.idx`. Loose objects are looked up first.
 
 use crate::artifacts::diff::tree_diff::TreeDiff;
 use crate::artifacts::log::path_filter::PathFilter;
+use crate::artifacts::objects::MIN_ABBREV_LENGTH;
 use crate::artifacts::objects::blob::Blob;
-use crate::artifacts::objects::commit::{Commit, SlimCommit};
+use crate::artifacts::objects::commit::{Commit, CommitError, SlimCommit};
 use crate::artifacts::objects::object::{Object, ObjectBox, ObjectError, Unpackable};
 use crate::artifacts::objects::object_id::ObjectId;
 use crate::artifacts::objects::object_type::ObjectType;
+use crate::artifacts::objects::tag::Tag;
 use crate::artifacts::objects::tree::Tree;
+use crate::artifacts::pack::PACK_CHECKSUM_SIZE;
+use crate::artifacts::pack::index::PackIndex;
+use crate::artifacts::pack::reader::{
+    PackEntry, PackEntryKind, PackError, PackReader, PackedObject,
+};
+use crate::artifacts::pack::writer::PackWriter;
 use bytes::Bytes;
 use fake::rand;
+use sha1::{Digest, Sha1};
 use std::cell::RefCell;
-use std::collections::HashMap;
-use std::io::{BufRead, Cursor, Read, Write};
+use std::collections::{BTreeSet, HashMap};
+use std::io::{BufRead, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 #[derive(Debug, thiserror::Error)]
 pub enum DatabaseError {
@@ -63,10 +76,18 @@ pub enum DatabaseError {
     Compress(#[source] std::io::Error),
     #[error("failed to decompress object")]
     Decompress(#[source] std::io::Error),
+    #[error("object file {path} is corrupt (object {oid})")]
+    CorruptObject { oid: String, path: String },
     #[error("invalid object path: {0}")]
     InvalidObjectPath(String),
+    #[error("expected {expected} bytes of content but read {actual}")]
+    StreamSizeMismatch { expected: u64, actual: u64 },
+    #[error(transparent)]
+    Pack(#[from] PackError),
     #[error("object {0} is not a commit")]
     NotACommit(String),
+    #[error(transparent)]
+    Commit(#[from] CommitError),
     #[error("commit {0} not found in cache")]
     NotInCache(String),
     #[error(transparent)]
@@ -75,6 +96,37 @@ pub enum DatabaseError {
     Internal(#[from] anyhow::Error),
 }
 
+/// Size of the chunks read by [`Database::hash_blob_stream`]
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Objects found by [`Database::find_objects_by_prefix`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefixMatches {
+    /// Matching object IDs, at most as many as the requested limit
+    pub oids: Vec<ObjectId>,
+    /// More objects share the prefix than were collected
+    pub truncated: bool,
+}
+
+impl PrefixMatches {
+    /// Whether the prefix matches more than one object
+    pub fn is_ambiguous(&self) -> bool {
+        self.oids.len() > 1 || self.truncated
+    }
+
+    /// Record a match, returning `false` once the limit is exceeded and the
+    /// scan can stop
+    fn push(&mut self, oid: ObjectId, limit: usize) -> bool {
+        if self.oids.len() >= limit {
+            self.truncated = true;
+            return false;
+        }
+
+        self.oids.push(oid);
+        true
+    }
+}
+
 /// Cached commit data for efficient borrowing
 ///
 /// This struct stores the essential commit information in a format
@@ -97,9 +149,66 @@ struct CachedCommit {
 pub struct Database {
     /// Path to the objects directory (typically `.git/objects`)
     path: Box<Path>,
+    /// Packs with their parsed indexes, loaded on first use
+    packs: RefCell<Option<Rc<[Pack]>>>,
+}
+
+/// A packfile located through its parsed index
+#[derive(Debug)]
+struct Pack {
+    /// Path to the `.pack` file
+    path: PathBuf,
+    index: PackIndex,
+    /// End offset of each entry, keyed by its start offset
+    entry_ends: HashMap<u64, u64>,
+}
+
+impl Pack {
+    fn load(index_path: &Path) -> Result<Self, DatabaseError> {
+        let index = PackIndex::parse(&std::fs::read(index_path)?)?;
+        let path = index_path.with_extension("pack");
+        let pack_end = std::fs::metadata(&path)?
+            .len()
+            .saturating_sub(PACK_CHECKSUM_SIZE as u64);
+
+        // Entries are stored back to back, so each one ends where the next begins
+        let mut offsets = index
+            .entries()
+            .iter()
+            .map(|entry| entry.offset)
+            .collect::<Vec<_>>();
+        offsets.sort_unstable();
+        let entry_ends = offsets
+            .iter()
+            .copied()
+            .zip(offsets.iter().skip(1).copied().chain([pack_end]))
+            .collect();
+
+        Ok(Pack {
+            path,
+            index,
+            entry_ends,
+        })
+    }
+
+    /// Read the entry starting at `offset`, without loading the rest of the pack
+    fn read_entry(&self, offset: u64) -> Result<PackEntry, DatabaseError> {
+        let end = self
+            .entry_ends
+            .get(&offset)
+            .copied()
+            .ok_or_else(|| PackError::InvalidDelta(format!("no entry at offset {}", offset)))?;
+
+        let mut file = std::fs::File::open(&self.path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut data = vec![0; end.saturating_sub(offset) as usize];
+        file.read_exact(&mut data)?;
+
+        let reader = PackReader::with_start(&data, offset as usize);
+        Ok(reader.read_entry_at(offset as usize)?)
+    }
 }
 
-// TODO: implement packfiles for better performance and storage efficiency
 // TODO: refactor to use async fs operations
 impl Database {
     /// Create a new database instance
@@ -108,7 +217,10 @@ impl Database {
     ///
     /// * `path` - Path to the objects directory (typically `.git/objects`)
     pub fn new(path: Box<Path>) -> Self {
-        Database { path }
+        Database {
+            path,
+            packs: RefCell::new(None),
+        }
     }
 
     /// Get the path to the objects directory
@@ -150,7 +262,16 @@ impl Database {
     pub fn load(&self, object_id: &ObjectId) -> Result<Bytes, DatabaseError> {
         let object_path = self.path.join(object_id.to_path());
 
-        self.read_object(object_path)
+        if !object_path.exists() {
+            if let Some(content) = self.load_packed(object_id)? {
+                return Ok(content);
+            }
+            if object_id.is_empty_tree() {
+                return Ok(Self::empty_tree_content());
+            }
+        }
+
+        self.read_object(object_id, object_path)
     }
 
     /// Store an object in the database
@@ -184,10 +305,155 @@ impl Database {
         Ok(())
     }
 
+    /// Store raw object content under the given type
+    ///
+    /// Used when importing objects whose content is already serialized (e.g.
+    /// objects extracted from a packfile), bypassing the typed object model.
+    ///
+    /// # Returns
+    ///
+    /// The object ID computed from the header and content
+    pub fn store_raw(
+        &self,
+        object_type: &ObjectType,
+        content: &[u8],
+    ) -> Result<ObjectId, DatabaseError> {
+        let mut object_content = format!("{} {}\0", object_type, content.len()).into_bytes();
+        object_content.extend_from_slice(content);
+
+        let object_id = ObjectId::try_parse(format!("{:x}", Sha1::digest(&object_content)))
+            .map_err(ObjectError::from)?;
+        let object_path = self.path.join(object_id.to_path());
+
+        if !object_path.exists() {
+            std::fs::create_dir_all(object_path.parent().ok_or_else(|| {
+                DatabaseError::InvalidObjectPath(object_path.display().to_string())
+            })?)
+            .map_err(|e| DatabaseError::CreateObjectDir {
+                path: object_path.display().to_string(),
+                source: e,
+            })?;
+
+            self.write_object(object_path, object_content.into())?;
+        }
+
+        Ok(object_id)
+    }
+
+    /// Hash a blob read from `reader`, storing it when `write` is set
+    ///
+    /// Used for files above `core.bigFileThreshold`: the content is read in
+    /// fixed-size chunks and compressed straight into a temporary object file,
+    /// which is moved into place once the object ID is known. At most one
+    /// chunk of the content is held in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source of the blob content
+    /// * `size` - Number of bytes `reader` yields, needed for the object header
+    /// * `write` - Whether to also store the blob in the database
+    pub fn hash_blob_stream(
+        &self,
+        mut reader: impl Read,
+        size: u64,
+        write: bool,
+    ) -> Result<ObjectId, DatabaseError> {
+        let header = format!("{} {}\0", ObjectType::Blob, size);
+        let mut digest = Sha1::new();
+        digest.update(header.as_bytes());
+
+        let mut temp_object = if write {
+            std::fs::create_dir_all(&self.path).map_err(|e| DatabaseError::CreateObjectDir {
+                path: self.path.display().to_string(),
+                source: e,
+            })?;
+            let temp_object_path = self.path.join(Self::generate_temp_name());
+            let file = std::fs::File::create(&temp_object_path).map_err(|e| {
+                DatabaseError::OpenObject {
+                    path: temp_object_path.display().to_string(),
+                    source: e,
+                }
+            })?;
+            let mut encoder = flate2::write::ZlibEncoder::new(file, flate2::Compression::default());
+            encoder
+                .write_all(header.as_bytes())
+                .map_err(DatabaseError::Compress)?;
+
+            Some((temp_object_path, encoder))
+        } else {
+            None
+        };
+
+        let mut chunk = vec![0; STREAM_CHUNK_SIZE];
+        let mut read = 0u64;
+        loop {
+            let length = reader.read(&mut chunk)?;
+            if length == 0 {
+                break;
+            }
+
+            digest.update(&chunk[..length]);
+            if let Some((_, encoder)) = temp_object.as_mut() {
+                encoder
+                    .write_all(&chunk[..length])
+                    .map_err(DatabaseError::Compress)?;
+            }
+            read += length as u64;
+        }
+
+        if read != size {
+            if let Some((temp_object_path, _)) = temp_object {
+                let _ = std::fs::remove_file(temp_object_path);
+            }
+            return Err(DatabaseError::StreamSizeMismatch {
+                expected: size,
+                actual: read,
+            });
+        }
+
+        let object_id =
+            ObjectId::try_parse(format!("{:x}", digest.finalize())).map_err(ObjectError::from)?;
+
+        if let Some((temp_object_path, encoder)) = temp_object {
+            encoder.finish().map_err(DatabaseError::Compress)?;
+
+            let object_path = self.path.join(object_id.to_path());
+            if object_path.exists() {
+                std::fs::remove_file(&temp_object_path)?;
+            } else {
+                std::fs::create_dir_all(object_path.parent().ok_or_else(|| {
+                    DatabaseError::InvalidObjectPath(object_path.display().to_string())
+                })?)
+                .map_err(|e| DatabaseError::CreateObjectDir {
+                    path: object_path.display().to_string(),
+                    source: e,
+                })?;
+                std::fs::rename(&temp_object_path, &object_path).map_err(|e| {
+                    DatabaseError::RenameObject {
+                        path: object_path.display().to_string(),
+                        source: e,
+                    }
+                })?;
+            }
+        }
+
+        Ok(object_id)
+    }
+
+    /// Load an object's type and content, without its header
+    pub fn load_raw(&self, object_id: &ObjectId) -> Result<(ObjectType, Bytes), DatabaseError> {
+        let (object_type, mut object_reader) = self.parse_object_as_bytes(object_id)?;
+
+        let mut content = Vec::new();
+        object_reader.read_to_end(&mut content)?;
+
+        Ok((object_type, content.into()))
+    }
+
     /// Parse an object from the database into the appropriate type
     ///
     /// Loads the object, determines its type, and deserializes it into
-    /// the corresponding struct (Blob, Tree, or Commit).
+    /// the corresponding struct (Blob, Tree, Commit, or Tag).
     ///
     /// # Arguments
     ///
@@ -205,6 +471,7 @@ impl Database {
             ObjectType::Commit => Ok(ObjectBox::Commit(Box::new(Commit::deserialize(
                 object_reader,
             )?))),
+            ObjectType::Tag => Ok(ObjectBox::Tag(Box::new(Tag::deserialize(object_reader)?))),
         }
     }
 
@@ -225,6 +492,26 @@ impl Database {
         }
     }
 
+    /// Load the raw content of a blob, if the object is one
+    ///
+    /// Unlike [`Self::parse_object_as_blob`], the content need not be UTF-8,
+    /// so binary files can be read too.
+    pub fn load_blob_bytes(&self, object_id: &ObjectId) -> Result<Option<Bytes>, DatabaseError> {
+        match self.load_raw(object_id)? {
+            (ObjectType::Blob, content) => Ok(Some(content)),
+            _ => Ok(None),
+        }
+    }
+
+    /// ID of a blob holding `content`, without storing it
+    pub fn blob_id(content: &[u8]) -> Result<ObjectId, DatabaseError> {
+        let mut digest = Sha1::new();
+        digest.update(format!("{} {}\0", ObjectType::Blob, content.len()).as_bytes());
+        digest.update(content);
+
+        Ok(ObjectId::try_parse(format!("{:x}", digest.finalize())).map_err(ObjectError::from)?)
+    }
+
     /// Parse an object as a Tree, if it is one
     ///
     /// # Returns
@@ -259,12 +546,74 @@ impl Database {
         }
     }
 
+    /// Parse an object as a Tag, if it is one
+    ///
+    /// # Returns
+    ///
+    /// Some(Tag) if the object is an annotated tag, None otherwise
+    pub fn parse_object_as_tag(&self, object_id: &ObjectId) -> Result<Option<Tag>, DatabaseError> {
+        let (object_type, object_reader) = self.parse_object_as_bytes(object_id)?;
+
+        match object_type {
+            ObjectType::Tag => Ok(Some(Tag::deserialize(object_reader)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Size of an object's content, read from its header alone
+    ///
+    /// Only the start of the zlib stream is inflated, so this stays cheap for
+    /// large blobs.
+    pub fn object_size(&self, object_id: &ObjectId) -> Result<usize, DatabaseError> {
+        let (_, size) = self.read_object_header(object_id)?;
+        Ok(size)
+    }
+
+    /// Read an object's type and content size without loading its content
+    pub fn read_object_header(
+        &self,
+        object_id: &ObjectId,
+    ) -> Result<(ObjectType, usize), DatabaseError> {
+        let (object_type, size) = self.read_raw_object_header(object_id)?;
+        let object_type = ObjectType::try_from(object_type.as_str()).map_err(ObjectError::from)?;
+
+        Ok((object_type, size))
+    }
+
+    /// Read an object's header, keeping its type as written even if unknown
+    pub fn read_raw_object_header(
+        &self,
+        object_id: &ObjectId,
+    ) -> Result<(String, usize), DatabaseError> {
+        let object_path = self.path.join(object_id.to_path());
+
+        if !object_path.exists() {
+            if let Some(content) = self.load_packed(object_id)? {
+                return ObjectType::parse_raw_header(&mut Cursor::new(content))
+                    .map_err(|_| Self::corrupt_object(object_id, &object_path));
+            }
+            if object_id.is_empty_tree() {
+                return Ok((ObjectType::Tree.to_string(), 0));
+            }
+        }
+
+        let file = std::fs::File::open(&object_path).map_err(|e| DatabaseError::ReadObject {
+            path: object_path.display().to_string(),
+            source: e,
+        })?;
+        let mut reader = std::io::BufReader::new(flate2::read::ZlibDecoder::new(
+            std::io::BufReader::new(file),
+        ));
+
+        ObjectType::parse_raw_header(&mut reader)
+            .map_err(|_| Self::corrupt_object(object_id, &object_path))
+    }
+
     fn parse_object_as_bytes(
         &self,
         object_id: &ObjectId,
     ) -> Result<(ObjectType, impl BufRead), DatabaseError> {
-        let object_path = self.path.join(object_id.to_path());
-        let object_content = self.read_object(object_path)?;
+        let object_content = self.load(object_id)?;
         let mut object_reader = Cursor::new(object_content);
 
         let object_type =
@@ -273,18 +622,291 @@ impl Database {
         Ok((object_type, object_reader))
     }
 
-    fn read_object(&self, object_path: PathBuf) -> Result<Bytes, DatabaseError> {
+    /// Read and inflate a loose object
+    ///
+    /// A zlib stream that fails to inflate, or whose content is shorter or
+    /// longer than its header claims (e.g. a truncated file), is reported as
+    /// a corrupt object.
+    fn read_object(
+        &self,
+        object_id: &ObjectId,
+        object_path: PathBuf,
+    ) -> Result<Bytes, DatabaseError> {
         let object_content =
             std::fs::read(&object_path).map_err(|e| DatabaseError::ReadObject {
                 path: object_path.display().to_string(),
                 source: e,
             })?;
 
-        let object_content = Self::decompress(object_content.into())?;
+        let object_content = Self::decompress(object_content.into())
+            .map_err(|_| Self::corrupt_object(object_id, &object_path))?;
+
+        let mut reader = Cursor::new(&object_content);
+        let is_complete = ObjectType::parse_raw_header(&mut reader)
+            .is_ok_and(|(_, size)| object_content.len() - reader.position() as usize == size);
+        if !is_complete {
+            return Err(Self::corrupt_object(object_id, &object_path));
+        }
 
         Ok(object_content)
     }
 
+    /// Directory holding the packfiles and their indexes
+    pub fn packs_path(&self) -> PathBuf {
+        self.path.join("pack")
+    }
+
+    /// Paths of the pack indexes in the database, sorted by name
+    pub fn pack_indexes(&self) -> Result<Vec<PathBuf>, DatabaseError> {
+        let Ok(entries) = std::fs::read_dir(self.packs_path()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut indexes = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "idx"))
+            .filter(|path| path.with_extension("pack").is_file())
+            .collect::<Vec<_>>();
+        indexes.sort();
+
+        Ok(indexes)
+    }
+
+    /// IDs of all loose objects, sorted
+    pub fn loose_objects(&self) -> Result<Vec<ObjectId>, DatabaseError> {
+        let mut objects = Vec::new();
+
+        for dir_entry in std::fs::read_dir(&self.path)? {
+            let dir_entry = dir_entry?;
+            let dir_name = dir_entry.file_name().to_string_lossy().to_string();
+            if dir_name.len() != 2 || !dir_entry.path().is_dir() {
+                continue;
+            }
+
+            for entry in std::fs::read_dir(dir_entry.path())? {
+                let file_name = entry?.file_name().to_string_lossy().to_string();
+                if let Ok(oid) = ObjectId::try_parse(format!("{}{}", dir_name, file_name)) {
+                    objects.push(oid);
+                }
+            }
+        }
+        objects.sort();
+
+        Ok(objects)
+    }
+
+    /// Number of loose objects in the `17/` fan-out directory
+    ///
+    /// Like Git, `gc --auto` samples this one directory to estimate the
+    /// loose object count instead of listing the whole database.
+    pub fn sampled_loose_objects(&self) -> Result<usize, DatabaseError> {
+        let Ok(entries) = std::fs::read_dir(self.path.join("17")) else {
+            return Ok(0);
+        };
+
+        Ok(entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                file_name.len() == 38 && file_name.chars().all(|c| c.is_ascii_hexdigit())
+            })
+            .count())
+    }
+
+    /// IDs of all objects stored in packs, sorted and deduplicated
+    pub fn packed_objects(&self) -> Result<Vec<ObjectId>, DatabaseError> {
+        let mut objects = BTreeSet::new();
+
+        for pack in self.packs()?.iter() {
+            objects.extend(pack.index.entries().iter().map(|entry| entry.oid.clone()));
+        }
+
+        Ok(objects.into_iter().collect())
+    }
+
+    /// Whether the pack with this index is marked with a `.keep` file
+    ///
+    /// Kept packs are never repacked nor removed.
+    pub fn is_kept_pack(index_path: &Path) -> bool {
+        index_path.with_extension("keep").exists()
+    }
+
+    /// Pack the loose objects into a new pack
+    ///
+    /// With `consolidate`, the objects of every pack not marked with a `.keep`
+    /// file go into the new pack as well and those packs are removed. The
+    /// objects are streamed into the pack one at a time, and the loose ones are
+    /// removed once the new pack and its index are in place.
+    ///
+    /// # Returns
+    ///
+    /// The number of packed objects and the name of the new pack, or `None`
+    /// when there was nothing to pack
+    pub fn repack(&self, consolidate: bool) -> Result<Option<(usize, String)>, DatabaseError> {
+        let loose = self.loose_objects()?;
+        let mut oids = loose.iter().cloned().collect::<BTreeSet<_>>();
+        let mut old_indexes = Vec::new();
+        if consolidate {
+            for pack in self.packs()?.iter() {
+                let index_path = pack.path.with_extension("idx");
+                if !Self::is_kept_pack(&index_path) {
+                    oids.extend(pack.index.entries().iter().map(|entry| entry.oid.clone()));
+                    old_indexes.push(index_path);
+                }
+            }
+        }
+        if oids.is_empty() {
+            return Ok(None);
+        }
+
+        std::fs::create_dir_all(self.packs_path()).map_err(|e| DatabaseError::CreateObjectDir {
+            path: self.packs_path().display().to_string(),
+            source: e,
+        })?;
+        let temp_path = self.packs_path().join(Self::generate_temp_name());
+        let index = self.stream_pack(&temp_path, &oids).inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp_path);
+        })?;
+
+        let checksum = index
+            .pack_checksum()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        let name = format!("pack-{}", checksum);
+        let pack_path = self.packs_path().join(format!("{}.pack", name));
+        // The pack goes first so that an index never points to a missing pack
+        std::fs::rename(&temp_path, &pack_path).map_err(|e| DatabaseError::RenameObject {
+            path: pack_path.display().to_string(),
+            source: e,
+        })?;
+        self.write_file_atomically(&pack_path.with_extension("idx"), &index.serialize()?)?;
+        self.packs.take();
+
+        for old_index in old_indexes {
+            if old_index != pack_path.with_extension("idx") {
+                std::fs::remove_file(&old_index)?;
+                std::fs::remove_file(old_index.with_extension("pack"))?;
+            }
+        }
+        for oid in &loose {
+            let object_path = self.path.join(oid.to_path());
+            std::fs::remove_file(&object_path)?;
+            if let Some(dir) = object_path.parent() {
+                // Only succeeds once the fan-out directory is empty
+                let _ = std::fs::remove_dir(dir);
+            }
+        }
+
+        Ok(Some((oids.len(), name)))
+    }
+
+    /// Write the objects to a pack at `path`, loading them one at a time
+    fn stream_pack(
+        &self,
+        path: &Path,
+        oids: &BTreeSet<ObjectId>,
+    ) -> Result<PackIndex, DatabaseError> {
+        let file = std::fs::File::create(path).map_err(|e| DatabaseError::WriteObject {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        let mut writer = PackWriter::new(std::io::BufWriter::new(file), oids.len() as u32)?;
+        for oid in oids {
+            let (object_type, content) = self.load_raw(oid)?;
+            let object = PackedObject {
+                object_type,
+                content: content.to_vec(),
+            };
+            writer.write_object(oid.clone(), &object)?;
+        }
+
+        Ok(writer.finish()?)
+    }
+
+    /// Packs in the database, parsed once and reused by later lookups
+    fn packs(&self) -> Result<Rc<[Pack]>, DatabaseError> {
+        if let Some(packs) = self.packs.borrow().as_ref() {
+            return Ok(packs.clone());
+        }
+
+        let packs = self
+            .pack_indexes()?
+            .iter()
+            .map(|index_path| Pack::load(index_path))
+            .collect::<Result<Rc<[_]>, _>>()?;
+        *self.packs.borrow_mut() = Some(packs.clone());
+
+        Ok(packs)
+    }
+
+    /// Read an object from the packs, with its `<type> <size>\0` header
+    fn load_packed(&self, object_id: &ObjectId) -> Result<Option<Bytes>, DatabaseError> {
+        for pack in self.packs()?.iter() {
+            let Some(offset) = pack.index.offset_of(object_id) else {
+                continue;
+            };
+            let object = self.read_packed(pack, offset)?;
+
+            let mut content =
+                format!("{} {}\0", object.object_type, object.content.len()).into_bytes();
+            content.extend_from_slice(&object.content);
+            return Ok(Some(content.into()));
+        }
+
+        Ok(None)
+    }
+
+    /// Decode the pack entry at `offset`, following its delta chain
+    fn read_packed(&self, pack: &Pack, offset: u64) -> Result<PackedObject, DatabaseError> {
+        let entry = pack.read_entry(offset)?;
+
+        let base = match entry.kind {
+            PackEntryKind::Base(object_type) => {
+                return Ok(PackedObject {
+                    object_type,
+                    content: entry.data,
+                });
+            }
+            PackEntryKind::OfsDelta(base_offset) => self.read_packed(pack, base_offset as u64)?,
+            PackEntryKind::RefDelta(base_oid) => {
+                let (object_type, content) = self.load_raw(&base_oid)?;
+                PackedObject {
+                    object_type,
+                    content: content.to_vec(),
+                }
+            }
+        };
+
+        Ok(PackReader::patch(&base, &entry.data)?)
+    }
+
+    fn write_file_atomically(&self, path: &Path, content: &[u8]) -> Result<(), DatabaseError> {
+        let dir = path
+            .parent()
+            .ok_or_else(|| DatabaseError::InvalidObjectPath(path.display().to_string()))?;
+        let temp_path = dir.join(Self::generate_temp_name());
+
+        std::fs::write(&temp_path, content).map_err(|e| DatabaseError::WriteObject {
+            path: temp_path.display().to_string(),
+            source: e,
+        })?;
+        std::fs::rename(&temp_path, path).map_err(|e| DatabaseError::RenameObject {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+
+        Ok(())
+    }
+
+    fn corrupt_object(object_id: &ObjectId, object_path: &Path) -> DatabaseError {
+        DatabaseError::CorruptObject {
+            oid: object_id.to_string(),
+            path: object_path.display().to_string(),
+        }
+    }
+
     fn write_object(
         &self,
         object_path: PathBuf,
@@ -345,68 +967,79 @@ impl Database {
         Ok(decompressed_content.into())
     }
 
+    /// Serialized content of the empty tree, synthesized when it is not on disk
+    fn empty_tree_content() -> Bytes {
+        Bytes::from_static(b"tree 0\0")
+    }
+
     fn generate_temp_name() -> String {
         format!("tmp-obj-{}", rand::random::<u32>())
     }
 
-    /// Find all objects whose OID starts with the given prefix.
+    /// Find the objects whose OID starts with the given prefix.
     ///
-    /// This method searches the object database for all objects whose OID begins
+    /// This method searches the object database for objects whose OID begins
     /// with the specified prefix. It's used to resolve abbreviated OIDs to their
     /// full form.
     ///
     /// # Arguments
     ///
     /// * `prefix` - A hexadecimal string prefix (e.g., "abc", "a1b2c3")
+    /// * `limit` - Number of matches worth collecting; the scan stops as soon as
+    ///   one more is found
     ///
     /// # Returns
     ///
-    /// A vector of all matching ObjectIds. If no matches are found, returns an empty vector.
-    /// If multiple matches are found, all are returned (indicating an ambiguous prefix).
+    /// At most `limit` matching ObjectIds, with `truncated` set when more objects
+    /// share the prefix. Callers that only need to tell a unique prefix from an
+    /// ambiguous one can pass a limit of 1.
     ///
     /// # Performance
     ///
     /// - For prefixes of 2+ characters, only searches the specific directory
-    /// - For prefixes of 0-1 characters, must search all directories (slower)
-    pub fn find_objects_by_prefix(&self, prefix: &str) -> Result<Vec<ObjectId>, DatabaseError> {
-        let mut matches = Vec::new();
-
-        if prefix.len() >= 2 {
-            let dir_name = &prefix[..2];
-            let file_prefix = &prefix[2..];
-            let dir_path = self.path.join(dir_name);
-
-            if dir_path.exists() && dir_path.is_dir() {
-                for entry in std::fs::read_dir(&dir_path)? {
-                    let entry = entry?;
-                    let file_name = entry.file_name();
-                    let file_name_str = file_name.to_string_lossy();
-
-                    if file_name_str.starts_with(file_prefix) {
-                        let full_oid = format!("{}{}", dir_name, file_name_str);
-                        if let Ok(oid) = ObjectId::try_parse(full_oid) {
-                            matches.push(oid);
-                        }
-                    }
+    /// - For prefixes of 0-1 characters, searches every directory starting with
+    ///   the prefix, until the limit is exceeded
+    /// - Packs are searched through their sorted indexes, so only the matching
+    ///   range of each is visited
+    pub fn find_objects_by_prefix(
+        &self,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<PrefixMatches, DatabaseError> {
+        let mut matches = PrefixMatches::default();
+
+        let dir_names = if prefix.len() >= 2 {
+            vec![prefix[..2].to_string()]
+        } else {
+            (0..=255)
+                .map(|i| format!("{:02x}", i))
+                .filter(|dir_name| dir_name.starts_with(prefix))
+                .collect()
+        };
+
+        for dir_name in dir_names {
+            let dir_path = self.path.join(&dir_name);
+            if !dir_path.is_dir() {
+                continue;
+            }
+
+            for entry in std::fs::read_dir(&dir_path)? {
+                let entry = entry?;
+                let full_oid = format!("{}{}", dir_name, entry.file_name().to_string_lossy());
+
+                if full_oid.starts_with(prefix)
+                    && let Ok(oid) = ObjectId::try_parse(full_oid)
+                    && !matches.push(oid, limit)
+                {
+                    return Ok(matches);
                 }
             }
-        } else {
-            for i in 0..=255 {
-                let dir_name = format!("{:02x}", i);
-                let dir_path = self.path.join(&dir_name);
-
-                if dir_path.exists() && dir_path.is_dir() {
-                    for entry in std::fs::read_dir(&dir_path)? {
-                        let entry = entry?;
-                        let file_name = entry.file_name();
-                        let file_name_str = file_name.to_string_lossy();
-                        let full_oid = format!("{}{}", dir_name, file_name_str);
-
-                        if full_oid.starts_with(prefix) {
-                            let oid = ObjectId::try_parse(full_oid).map_err(ObjectError::from)?;
-                            matches.push(oid);
-                        }
-                    }
+        }
+
+        for pack in self.packs()?.iter() {
+            for entry in pack.index.prefix_matches(prefix) {
+                if !matches.oids.contains(&entry.oid) && !matches.push(entry.oid.clone(), limit) {
+                    return Ok(matches);
                 }
             }
         }
@@ -414,6 +1047,52 @@ impl Database {
         Ok(matches)
     }
 
+    /// Shortest prefix of `oid`, at least `min_length` characters long, that
+    /// no other object in the database shares
+    ///
+    /// Only the loose objects in the same fan-out directory and the pack
+    /// entries sorted next to `oid` can share more than its first two
+    /// characters, so those are the only ones compared.
+    pub fn unique_abbreviation(
+        &self,
+        oid: &ObjectId,
+        min_length: usize,
+    ) -> Result<String, DatabaseError> {
+        let full = oid.as_ref();
+        let shared_length = |other: &str| {
+            full.bytes()
+                .zip(other.bytes())
+                .take_while(|(a, b)| a == b)
+                .count()
+        };
+        let mut shared = 0;
+
+        let dir_path = self.path.join(&full[..2]);
+        if dir_path.is_dir() {
+            for entry in std::fs::read_dir(&dir_path)? {
+                let other = format!("{}{}", &full[..2], entry?.file_name().to_string_lossy());
+                if other != full {
+                    shared = shared.max(shared_length(&other));
+                }
+            }
+        }
+
+        for pack in self.packs()?.iter() {
+            for entry in pack.index.neighbours(oid) {
+                if entry.oid != *oid {
+                    shared = shared.max(shared_length(entry.oid.as_ref()));
+                }
+            }
+        }
+
+        let length = min_length
+            .clamp(MIN_ABBREV_LENGTH, full.len())
+            .max(shared + 1)
+            .min(full.len());
+
+        Ok(full[..length].to_string())
+    }
+
     /// Get the type of an object as a string.
     ///
     /// Returns the object type ("blob", "tree", or "commit") for the given object ID.
@@ -428,9 +1107,43 @@ impl Database {
     ///
     /// A string representing the object type: "blob", "tree", or "commit"
     pub fn get_object_type(&self, object_id: &ObjectId) -> Result<ObjectType, DatabaseError> {
-        let (object_type, _) = self.parse_object_as_bytes(object_id)?;
+        let (object_type, _) = self.read_object_header(object_id)?;
         Ok(object_type)
     }
+
+    /// Load a commit and check that the objects it references have the right type
+    ///
+    /// Parsing already rejects malformed header lines; on top of that the
+    /// tree line must point to a tree and every parent line to a commit.
+    pub fn verify_commit(&self, object_id: &ObjectId) -> Result<Commit, DatabaseError> {
+        let commit = self
+            .parse_object_as_commit(object_id)?
+            .ok_or_else(|| DatabaseError::NotACommit(object_id.to_string()))?;
+
+        let tree_type = self.get_object_type(commit.tree_oid())?;
+        if tree_type != ObjectType::Tree {
+            return Err(CommitError::TreeNotATree {
+                commit: object_id.to_string(),
+                oid: commit.tree_oid().to_string(),
+                object_type: tree_type,
+            }
+            .into());
+        }
+
+        for parent in commit.parents() {
+            let parent_type = self.get_object_type(parent)?;
+            if parent_type != ObjectType::Commit {
+                return Err(CommitError::ParentNotACommit {
+                    commit: object_id.to_string(),
+                    oid: parent.to_string(),
+                    object_type: parent_type,
+                }
+                .into());
+            }
+        }
+
+        Ok(commit)
+    }
 }
 
 /// Commit cache for efficient borrowing during graph traversal algorithms
@@ -558,3 +1271,192 @@ impl Default for CommitCache {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use pretty_assertions::assert_eq;
+
+    /// Content that deflate cannot shrink much, so its object file stays large
+    fn incompressible_content(len: usize) -> String {
+        let mut state = 0x2545_f491_u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                char::from(b'!' + (state % 94) as u8)
+            })
+            .collect()
+    }
+
+    fn store_blob(database: &Database, content: String) -> (Blob, ObjectId) {
+        let blob = Blob::new(content, Default::default());
+        let oid = blob.object_id().unwrap();
+        database.store(blob.clone()).unwrap();
+
+        (blob, oid)
+    }
+
+    #[test]
+    fn test_object_size_matches_decompressed_content() {
+        let dir = TempDir::new().unwrap();
+        let database = Database::new(dir.path().to_path_buf().into_boxed_path());
+        let (blob, oid) = store_blob(&database, incompressible_content(64 * 1024));
+
+        let (object_type, content) = database.load_raw(&oid).unwrap();
+
+        assert_eq!(object_type, ObjectType::Blob);
+        assert_eq!(database.object_size(&oid).unwrap(), content.len());
+        assert_eq!(blob.size().unwrap(), content.len());
+    }
+
+    #[test]
+    fn test_object_size_reads_only_the_header() {
+        let dir = TempDir::new().unwrap();
+        let database = Database::new(dir.path().to_path_buf().into_boxed_path());
+        let (_, oid) = store_blob(&database, incompressible_content(64 * 1024));
+
+        // Cut the object file short: only its header is still readable
+        let object_path = dir.path().join(oid.to_path());
+        let compressed = std::fs::read(&object_path).unwrap();
+        std::fs::write(&object_path, &compressed[..compressed.len() / 4]).unwrap();
+
+        assert!(matches!(
+            database.load(&oid),
+            Err(DatabaseError::CorruptObject { .. })
+        ));
+        assert_eq!(database.object_size(&oid).unwrap(), 64 * 1024);
+        assert_eq!(database.get_object_type(&oid).unwrap(), ObjectType::Blob);
+    }
+
+    #[test]
+    fn test_object_size_of_missing_empty_tree_is_zero() {
+        let dir = TempDir::new().unwrap();
+        let database = Database::new(dir.path().to_path_buf().into_boxed_path());
+        assert_eq!(database.object_size(&ObjectId::empty_tree()).unwrap(), 0);
+    }
+
+    /// Reader yielding its content in small pieces and recording the largest
+    /// buffer it was asked to fill
+    struct ChunkedReader {
+        content: Cursor<Vec<u8>>,
+        largest_request: usize,
+    }
+
+    impl Read for ChunkedReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.largest_request = self.largest_request.max(buf.len());
+            let limit = buf.len().min(1000);
+            self.content.read(&mut buf[..limit])
+        }
+    }
+
+    #[test]
+    fn test_hash_blob_stream_stores_blob_in_bounded_chunks() {
+        let dir = TempDir::new().unwrap();
+        let database = Database::new(dir.path().to_path_buf().into_boxed_path());
+        let content = incompressible_content(4 * STREAM_CHUNK_SIZE + 17);
+        let mut reader = ChunkedReader {
+            content: Cursor::new(content.clone().into_bytes()),
+            largest_request: 0,
+        };
+
+        let oid = database
+            .hash_blob_stream(&mut reader, content.len() as u64, true)
+            .unwrap();
+
+        assert!(reader.largest_request <= STREAM_CHUNK_SIZE);
+        assert_eq!(
+            oid,
+            Blob::new(content.clone(), Default::default())
+                .object_id()
+                .unwrap()
+        );
+        let (object_type, stored) = database.load_raw(&oid).unwrap();
+        assert_eq!(object_type, ObjectType::Blob);
+        assert_eq!(stored, content.as_bytes());
+    }
+
+    #[test]
+    fn test_hash_blob_stream_rejects_size_mismatch() {
+        let dir = TempDir::new().unwrap();
+        let database = Database::new(dir.path().to_path_buf().into_boxed_path());
+
+        assert!(matches!(
+            database.hash_blob_stream(Cursor::new(b"short"), 10, true),
+            Err(DatabaseError::StreamSizeMismatch {
+                expected: 10,
+                actual: 5
+            })
+        ));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_unique_abbreviation_grows_past_shared_prefixes() {
+        let dir = TempDir::new().unwrap();
+        let database = Database::new(dir.path().to_path_buf().into_boxed_path());
+
+        // Find two blobs whose IDs share their first four hex digits
+        let mut seen = HashMap::new();
+        let (first, second) = (0..)
+            .find_map(|i| {
+                let oid = Blob::new(format!("blob {}", i), Default::default())
+                    .object_id()
+                    .unwrap();
+                seen.insert(oid.as_ref()[..4].to_string(), i)
+                    .map(|other| (other, i))
+            })
+            .unwrap();
+        let (_, first) = store_blob(&database, format!("blob {}", first));
+        let (_, second) = store_blob(&database, format!("blob {}", second));
+        let shared = first
+            .as_ref()
+            .chars()
+            .zip(second.as_ref().chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let abbreviation = database.unique_abbreviation(&first, 4).unwrap();
+        assert_eq!(abbreviation, first.as_ref()[..shared + 1]);
+        assert_eq!(
+            database.unique_abbreviation(&first, 12).unwrap().len(),
+            12.max(shared + 1)
+        );
+        assert_eq!(
+            database.unique_abbreviation(&first, 1).unwrap().len(),
+            shared + 1
+        );
+    }
+
+    #[test]
+    fn test_short_prefix_stops_scanning_once_ambiguous() {
+        let dir = TempDir::new().unwrap();
+        let database = Database::new(dir.path().to_path_buf().into_boxed_path());
+
+        // Plenty of objects under every directory starting with "a"; only
+        // their names matter to the lookup
+        for dir_name in (0..16).map(|i| format!("a{:x}", i)) {
+            let object_dir = dir.path().join(&dir_name);
+            std::fs::create_dir(&object_dir).unwrap();
+            for i in 0..64 {
+                std::fs::write(object_dir.join(format!("{:038x}", i)), b"").unwrap();
+            }
+        }
+
+        let matches = database.find_objects_by_prefix("a", 2).unwrap();
+
+        assert!(matches.is_ambiguous());
+        assert!(matches.truncated);
+        assert_eq!(matches.oids.len(), 2);
+        assert!(matches.oids.iter().all(|oid| oid.as_ref().starts_with('a')));
+        assert!(
+            !database
+                .find_objects_by_prefix("b", 2)
+                .unwrap()
+                .is_ambiguous()
+        );
+    }
+}
@@ -7,24 +7,46 @@
 //!
 //! The index file contains:
 //! - Header: Signature, version, and entry count
-//! - Entries: Sorted list of tracked files with metadata
+//! - Entries: Sorted list of tracked files with metadata; a version 4 index
+//!   prefix-compresses their paths and is written back as version 4
+//! - Extensions: Only `TREE` (the cache tree) is kept; other optional ones are dropped
 //! - Checksum: SHA-1 hash of the entire index for integrity verification
 //!
 //! ## Data Structures
 //!
 //! - `entries`: Maps (file path, stage) to their index entries
 //! - `children`: Maps directory paths to their children for efficient tree operations
-
+//! - `cache_tree`: Tree OIDs of directories unchanged since the last commit
+//!
+//! ## Racy Entries
+//!
+//! A file changed in the same second its entry was written can keep the
+//! stat data recorded in the entry, so stat data is only trusted for entries
+//! older than the index file itself (see `is_racy`). Before the index is
+//! written, entries that would look clean this way although the file changed
+//! get their size zeroed (see `Repository::write_index`), which forces the
+//! next comparison to read the content, as Git does.
+
+use crate::artifacts::index::cache_tree::{CacheTree, CacheTreeError, TREE_SIGNATURE};
 use crate::artifacts::index::checksum::{Checksum, ChecksumError};
 use crate::artifacts::index::index_entry::{
-    ENTRY_BLOCK, ENTRY_MIN_SIZE, EntryMetadata, IndexEntry, IndexEntryError, MergeStage,
+    ENTRY_BLOCK, ENTRY_MIN_SIZE, EntryFlags, EntryMetadata, IndexEntry, IndexEntryError,
+    MergeStage, NAME_OFFSET,
 };
 use crate::artifacts::index::index_header::{IndexHeader, IndexHeaderError};
-use crate::artifacts::index::{HEADER_SIZE, SIGNATURE, VERSION};
+use crate::artifacts::index::prefix_compression::{decode_varint, expand_name};
+use crate::artifacts::index::verify::{IndexCorruption, verify_index};
+use crate::artifacts::index::{
+    CHECKSUM_SIZE, EXTENDED_VERSION, EXTENSION_HEADER_SIZE, HEADER_SIZE, PREFIX_COMPRESSED_VERSION,
+    SIGNATURE, VERSION,
+};
 use crate::artifacts::objects::object::{Packable, Unpackable};
+use byteorder::{ByteOrder, NetworkEndian};
 use bytes::Bytes;
-use std::collections::{BTreeMap, BTreeSet};
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ops::DerefMut;
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, thiserror::Error)]
@@ -35,10 +57,18 @@ pub enum IndexError {
     Entry(#[from] IndexEntryError),
     #[error(transparent)]
     Checksum(#[from] ChecksumError),
+    #[error(transparent)]
+    Corrupt(#[from] IndexCorruption),
+    #[error(transparent)]
+    CacheTree(#[from] CacheTreeError),
     #[error("invalid index file signature")]
     InvalidSignature,
     #[error("unsupported index file version: {0}")]
     UnsupportedVersion(u32),
+    #[error("index uses the {0} extension, which is not supported")]
+    UnsupportedExtension(String),
+    #[error("path is not in the index: {0}")]
+    EntryNotFound(String),
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
@@ -57,10 +87,18 @@ pub struct Index {
     entries: BTreeMap<(Box<Path>, MergeStage), IndexEntry>,
     /// Directory hierarchy for efficient parent-child lookups
     children: BTreeMap<Box<Path>, BTreeSet<Box<Path>>>,
+    /// Cached tree OIDs of directories, persisted as the `TREE` extension
+    cache_tree: CacheTree,
     /// Index file header metadata
     header: IndexHeader,
     /// Flag indicating if the index has been modified since loading
     changed: bool,
+    /// Modification time (seconds) of the index file when it was last read
+    /// or written
+    timestamp: Option<i64>,
+    /// Lowercased stage-0 paths and tracked directories mapped to their
+    /// spelling, built on the first case-insensitive lookup after a load
+    case_folded_paths: OnceCell<HashMap<String, Box<Path>>>,
 }
 
 impl Index {
@@ -74,8 +112,11 @@ impl Index {
             path,
             entries: BTreeMap::new(),
             children: BTreeMap::new(),
+            cache_tree: CacheTree::default(),
             header: IndexHeader::new(String::from(SIGNATURE), VERSION, 0),
             changed: false,
+            timestamp: None,
+            case_folded_paths: OnceCell::new(),
         }
     }
 
@@ -148,8 +189,10 @@ impl Index {
     fn clear(&mut self) {
         self.entries.clear();
         self.children.clear();
+        self.cache_tree = CacheTree::default();
         self.header = IndexHeader::empty();
         self.changed = false;
+        self.case_folded_paths.take();
     }
 
     /// Load the index from disk
@@ -174,17 +217,41 @@ impl Index {
         self.clear();
 
         // if the index file is empty, return early
-        if lock.deref_mut().metadata()?.len() == 0 {
+        let metadata = lock.deref_mut().metadata()?;
+        self.timestamp = Some(metadata.mtime());
+        let index_size = metadata.len() as usize;
+        if index_size == 0 {
             return Ok(());
         }
 
         let mut reader = Checksum::new(lock);
         let entries_count = self.parse_header(&mut reader)?;
         self.parse_entries(entries_count, &mut reader)?;
+        self.parse_extensions(index_size, &mut reader)?;
 
         Ok(reader.verify()?)
     }
 
+    /// Check the structure of the index file on disk without loading it
+    ///
+    /// A missing or empty index file is an empty index, as for `rehydrate`.
+    ///
+    /// # Returns
+    ///
+    /// The number of entries in the index
+    pub fn verify(&self) -> Result<u32, IndexError> {
+        if !self.path().exists() {
+            return Ok(0);
+        }
+
+        let bytes = std::fs::read(self.path())?;
+        if bytes.is_empty() {
+            return Ok(0);
+        }
+
+        Ok(verify_index(&bytes)?)
+    }
+
     /// Check if a path is tracked directly in the index (stage-0 entry or directory)
     ///
     /// Returns true if the path is either a clean stage-0 file entry or has children
@@ -195,7 +262,35 @@ impl Index {
             || self.children.contains_key(path)
     }
 
-    fn parse_header(&self, reader: &mut Checksum) -> Result<u32, IndexError> {
+    /// Find how the index spells a tracked path that differs only in case
+    ///
+    /// Used with `core.ignorecase`, where `File.txt` on disk is the tracked
+    /// `file.txt`. Returns the index spelling of a stage-0 entry or tracked
+    /// directory.
+    pub fn find_path_ignoring_case(&self, path: &Path) -> Option<PathBuf> {
+        self.case_folded_paths
+            .get_or_init(|| {
+                let mut paths = HashMap::new();
+                let names = self
+                    .entries
+                    .keys()
+                    .filter(|(_, stage)| *stage == MergeStage::Clean)
+                    .map(|(name, _)| name)
+                    .chain(self.children.keys());
+                for name in names {
+                    paths
+                        .entry(name.to_string_lossy().to_lowercase())
+                        .or_insert_with(|| name.clone());
+                }
+                paths
+            })
+            .get(&path.to_string_lossy().to_lowercase())
+            .map(|name| name.to_path_buf())
+    }
+
+    /// Parse the header, keeping its version so the index is written back
+    /// in the same format
+    fn parse_header(&mut self, reader: &mut Checksum) -> Result<u32, IndexError> {
         let header_bytes = reader.read(HEADER_SIZE)?;
         let header_reader = std::io::Cursor::new(header_bytes.clone());
         let header = IndexHeader::deserialize(header_reader)?;
@@ -204,9 +299,10 @@ impl Index {
             return Err(IndexError::InvalidSignature);
         }
 
-        if header.version != VERSION {
+        if ![VERSION, EXTENDED_VERSION, PREFIX_COMPRESSED_VERSION].contains(&header.version) {
             return Err(IndexError::UnsupportedVersion(header.version));
         }
+        self.header.version = header.version;
 
         Ok(header.entries_count)
     }
@@ -214,16 +310,23 @@ impl Index {
     /// Parse all entries from the index file
     ///
     /// Reads each entry, handling variable-length paths with 8-byte alignment.
+    /// An entry ends with the block holding the NUL terminator of its name.
+    /// Version 4 entries are read by `parse_compressed_entries` instead.
     fn parse_entries(
         &mut self,
         entries_count: u32,
         reader: &mut Checksum,
     ) -> Result<(), IndexError> {
+        if self.header.version == PREFIX_COMPRESSED_VERSION {
+            return self.parse_compressed_entries(entries_count, reader);
+        }
+
         for _ in 0..entries_count {
             let entry_bytes = reader.read(ENTRY_MIN_SIZE)?;
             let mut entry_bytes = entry_bytes.to_vec();
+            let name_offset = IndexEntry::name_offset(&entry_bytes);
 
-            while entry_bytes[entry_bytes.len() - 1] != 0 {
+            while !entry_bytes[name_offset..].contains(&0) {
                 entry_bytes = [entry_bytes, reader.read(ENTRY_BLOCK)?.to_vec()].concat();
             }
 
@@ -239,6 +342,81 @@ impl Index {
         Ok(())
     }
 
+    /// Parse the unpadded entries of a version 4 index
+    ///
+    /// Each name is rebuilt from the name of the entry before it, then the
+    /// entry is parsed as if it had been stored whole.
+    fn parse_compressed_entries(
+        &mut self,
+        entries_count: u32,
+        reader: &mut Checksum,
+    ) -> Result<(), IndexError> {
+        let mut previous_name = Vec::new();
+        for _ in 0..entries_count {
+            let mut entry_bytes = reader.read(NAME_OFFSET)?.to_vec();
+            let name_offset = IndexEntry::name_offset(&entry_bytes);
+            entry_bytes.extend_from_slice(&reader.read(name_offset - NAME_OFFSET)?);
+
+            let mut strip_bytes = vec![reader.read(1)?[0]];
+            while strip_bytes[strip_bytes.len() - 1] & 0x80 != 0 {
+                strip_bytes.push(reader.read(1)?[0]);
+            }
+            let (strip, _) = decode_varint(&strip_bytes).ok_or(ChecksumError::UnexpectedEof)?;
+
+            let mut suffix = Vec::new();
+            loop {
+                match reader.read(1)?[0] {
+                    0 => break,
+                    byte => suffix.push(byte),
+                }
+            }
+
+            let name = expand_name(&previous_name, strip, &suffix).ok_or(
+                IndexEntryError::InvalidNameCompression {
+                    strip,
+                    length: previous_name.len(),
+                },
+            )?;
+            entry_bytes.extend_from_slice(&name);
+            entry_bytes.push(0);
+
+            let entry = IndexEntry::deserialize(std::io::Cursor::new(entry_bytes))?;
+            self.store_entry(&entry)?;
+            previous_name = name;
+        }
+
+        self.header.entries_count = entries_count;
+
+        Ok(())
+    }
+
+    /// Parse the extensions between the last entry and the checksum
+    ///
+    /// Keeps the cache tree and skips the other optional extensions, whose
+    /// signature starts with an uppercase letter; any other one is required
+    /// to read the index correctly.
+    fn parse_extensions(
+        &mut self,
+        index_size: usize,
+        reader: &mut Checksum,
+    ) -> Result<(), IndexError> {
+        while reader.offset() + CHECKSUM_SIZE < index_size {
+            let header = reader.read(EXTENSION_HEADER_SIZE)?;
+            let signature = &header[..4];
+            let data = reader.read(NetworkEndian::read_u32(&header[4..]) as usize)?;
+
+            if signature == TREE_SIGNATURE {
+                self.cache_tree = CacheTree::parse(&data)?;
+            } else if !signature[0].is_ascii_uppercase() {
+                return Err(IndexError::UnsupportedExtension(
+                    String::from_utf8_lossy(signature).into_owned(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Remove any conflicting entries before adding a new entry
     ///
     /// Removes parent directories that might be file entries, and
@@ -269,16 +447,24 @@ impl Index {
     }
 
     fn store_entry(&mut self, entry: &IndexEntry) -> Result<(), IndexError> {
+        // Re-staging unchanged content keeps the cached trees around it valid
+        let key = (entry.name.clone().into_boxed_path(), entry.stage);
+        let unchanged = entry.stage == MergeStage::Clean
+            && self.entries.get(&key).is_some_and(|existing| {
+                existing.oid == entry.oid && existing.metadata.mode == entry.metadata.mode
+            });
+        if !unchanged {
+            self.cache_tree.invalidate(&entry.name);
+        }
+
         let entry_parents = entry
             .parent_dirs()
             .into_iter()
             .map(|parent| parent.to_owned().into_boxed_path())
             .collect::<BTreeSet<_>>();
 
-        self.entries.insert(
-            (entry.name.clone().into_boxed_path(), entry.stage),
-            entry.clone(),
-        );
+        self.entries.insert(key, entry.clone());
+        self.case_folded_paths.take();
 
         for parent in entry_parents {
             self.children
@@ -316,6 +502,8 @@ impl Index {
         }
 
         if removed_any {
+            self.cache_tree.invalidate(path_name);
+            self.case_folded_paths.take();
             for parent in path_name
                 .ancestors()
                 .skip(1)
@@ -335,6 +523,8 @@ impl Index {
     }
 
     pub fn add(&mut self, entry: IndexEntry) -> Result<(), IndexError> {
+        IndexEntry::validate_path(&entry.name)?;
+
         self.discard_conflicts(&entry)?;
         self.store_entry(&entry)?;
 
@@ -364,24 +554,79 @@ impl Index {
 
         let mut writer = Checksum::new(lock);
 
+        // A version 4 index stays one; otherwise version 3 is only needed
+        // when some entry carries extended flags
+        let version = if self.header.version == PREFIX_COMPRESSED_VERSION {
+            PREFIX_COMPRESSED_VERSION
+        } else if self.entries().any(|entry| entry.flags.is_extended()) {
+            EXTENDED_VERSION
+        } else {
+            VERSION
+        };
         self.header = IndexHeader {
+            version,
             entries_count: self.entries.len() as u32,
             ..self.header.clone()
         };
         let header_bytes = self.header.serialize()?;
         writer.write(&header_bytes)?;
 
+        let mut previous_name = Path::new("");
         for entry in self.entries() {
-            let entry_bytes = entry.serialize()?;
+            let entry_bytes = if version == PREFIX_COMPRESSED_VERSION {
+                entry.serialize_compressed(previous_name)?
+            } else {
+                entry.serialize()?
+            };
             writer.write(&entry_bytes)?;
+            previous_name = entry.name.as_path();
+        }
+
+        if !self.cache_tree.is_empty() {
+            let data = self.cache_tree.serialize();
+            writer.write(TREE_SIGNATURE)?;
+            writer.write(&(data.len() as u32).to_be_bytes())?;
+            writer.write(&data)?;
         }
 
         writer.write_checksum()?;
         self.changed = false;
+        self.timestamp = Some(std::fs::metadata(self.path())?.mtime());
 
         Ok(())
     }
 
+    /// Whether the stat data of `entry` cannot be trusted on its own
+    ///
+    /// The file may have changed within the second the index was written,
+    /// leaving the same modification time behind, so such entries must be
+    /// compared by content.
+    pub fn is_racy(&self, entry: &IndexEntry) -> bool {
+        self.timestamp
+            .is_some_and(|timestamp| entry.metadata.mtime >= timestamp)
+    }
+
+    /// Zero the recorded size of `entry`, so that its stat data no longer
+    /// matches any non-empty file and its content gets compared instead
+    pub fn smudge_entry(&mut self, entry: &IndexEntry) {
+        let entry_key = (entry.name.clone().into_boxed_path(), entry.stage);
+        if let Some(existing_entry) = self.entries.get_mut(&entry_key) {
+            existing_entry.metadata.size = 0;
+            self.changed = true;
+        }
+    }
+
+    /// Tree OIDs of directories unchanged since they were last written
+    pub fn cache_tree(&self) -> &CacheTree {
+        &self.cache_tree
+    }
+
+    /// Record the trees just written for the current entries
+    pub fn set_cache_tree(&mut self, cache_tree: CacheTree) {
+        self.cache_tree = cache_tree;
+        self.changed = true;
+    }
+
     pub fn update_entry_stat(&mut self, entry: &IndexEntry, stat: EntryMetadata) {
         let entry_key = (entry.name.clone().into_boxed_path(), entry.stage);
         if let Some(existing_entry) = self.entries.get_mut(&entry_key) {
@@ -390,6 +635,22 @@ impl Index {
         }
     }
 
+    /// Replace the assume-unchanged/skip-worktree flags of a stage-0 entry
+    pub fn update_entry_flags(&mut self, path: &Path, flags: EntryFlags) -> Result<(), IndexError> {
+        let entry_key = (path.to_path_buf().into_boxed_path(), MergeStage::Clean);
+        let entry = self
+            .entries
+            .get_mut(&entry_key)
+            .ok_or_else(|| IndexError::EntryNotFound(path.display().to_string()))?;
+
+        if entry.flags != flags {
+            entry.flags = flags;
+            self.changed = true;
+        }
+
+        Ok(())
+    }
+
     pub fn entries(&self) -> impl Iterator<Item = &IndexEntry> {
         self.entries.values()
     }
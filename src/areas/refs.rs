@@ -10,6 +10,20 @@
 //! - HEAD: Special reference pointing to the current branch or commit
 //! - Branches: refs/heads/* pointing to branch tip commits
 //! - Tags: refs/tags/* pointing to tagged commits
+//! - ORIG_HEAD: Where HEAD was before the last reset
+//!
+//! ## Reflogs
+//!
+//! Moving HEAD (commit, checkout, merge, reset) or creating a branch appends a
+//! line to `logs/<ref>` recording the previous and new OIDs, who moved it and
+//! why:
+//!
+//! ```text
+//! <old oid> <new oid> <name> <<email>> <timestamp> <timezone>\t<message>
+//! ```
+//!
+//! `bit reflog show <ref>` lists them, `<ref>@{n}` names the value the ref
+//! had `n` updates ago, and `@{n}` does the same for the current branch.
 //!
 //! ## File Format
 //!
@@ -18,6 +32,8 @@
 //! - `ref: <path>` for symbolic references
 
 use crate::artifacts::branch::branch_name::{BranchName, BranchNameError, SymRefName};
+use crate::artifacts::branch::reflog::{NULL_OID, ReflogEntry, ReflogError};
+use crate::artifacts::objects::commit::Author;
 use crate::artifacts::objects::object_id::{ObjectId, ObjectIdError};
 use derive_new::new;
 use file_guard::Lock;
@@ -41,6 +57,10 @@ pub enum RefsError {
     BranchAlreadyExists(String),
     #[error("branch {0} does not exist")]
     BranchDoesNotExist(String),
+    #[error("tag '{0}' already exists")]
+    TagAlreadyExists(String),
+    #[error("tag '{0}' not found")]
+    TagNotFound(String),
     #[error("failed to open ref file at {path}")]
     OpenRefFile {
         path: String,
@@ -65,12 +85,28 @@ pub enum RefsError {
         #[source]
         source: std::io::Error,
     },
-    #[error("failed to remove empty branch directory at {path}")]
-    RemoveBranchDir {
+    #[error("failed to delete tag file at {path}")]
+    DeleteTag {
         path: String,
         #[source]
         source: std::io::Error,
     },
+    #[error("failed to remove empty ref directory at {path}")]
+    RemoveRefDir {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("not a git repository (or any parent up to {0})")]
+    NotARepository(String),
+    #[error("invalid HEAD")]
+    InvalidHead,
+    #[error("invalid reflog entry in {path}")]
+    InvalidReflog {
+        path: String,
+        #[source]
+        source: ReflogError,
+    },
     #[error(transparent)]
     Io(#[from] std::io::Error),
 }
@@ -91,8 +127,17 @@ const SYMREF_REGEX: &str = r"^ref: (.+)$";
 /// Name of the HEAD reference
 pub const HEAD_REF_NAME: &str = "HEAD";
 
+/// Name of the ref recording HEAD before the last reset
+pub const ORIG_HEAD: &str = "ORIG_HEAD";
+
+/// Directory (under the git directory) holding the reflogs
+const LOGS_DIR: &str = "logs";
+
 const MERGE_HEAD: &str = "MERGE_HEAD";
 const MERGE_MSG: &str = "MERGE_MSG";
+const BISECT_START: &str = "BISECT_START";
+const BISECT_BAD: &str = "BISECT_BAD";
+const BISECT_GOOD: &str = "BISECT_GOOD";
 
 /// Internal representation of a reference value
 ///
@@ -206,9 +251,19 @@ impl Refs {
     ///
     /// Acquires exclusive lock on the reference file during update.
     fn update_symref(&self, path: &Path, oid: ObjectId) -> Result<(), RefsError> {
+        // The target of a symbolic ref may not exist yet (e.g. an unborn orphan branch)
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| RefsError::CreateRefDir {
+                path: parent.display().to_string(),
+                source: e,
+            })?;
+        }
+
         let mut ref_file = std::fs::OpenOptions::new()
             .read(true)
             .write(true)
+            .create(true)
+            .truncate(false)
             .open(path)
             .map_err(|e| RefsError::OpenRefFile {
                 path: path.display().to_string(),
@@ -243,8 +298,76 @@ impl Refs {
         }
     }
 
-    pub fn update_head(&self, oid: ObjectId) -> Result<(), RefsError> {
-        self.update_symref(self.head_path().as_ref(), oid)
+    /// Point HEAD at a branch that has no commits yet
+    ///
+    /// Used by `checkout --orphan`: HEAD becomes a symbolic ref to the new
+    /// branch, but the branch file itself is only created by the next commit.
+    pub fn set_head_to_unborn_branch(&self, name: &BranchName) -> Result<(), RefsError> {
+        let branch_path = self.heads_path().join(name.as_ref());
+
+        if branch_path.exists() {
+            return Err(RefsError::BranchAlreadyExists(name.to_string()));
+        }
+
+        self.update_ref_file(
+            self.head_path().into(),
+            format!("ref: refs/heads/{}", name.as_ref()),
+        )
+    }
+
+    /// Check that the git directory exists and HEAD holds a symref or an OID
+    ///
+    /// Distinguishes running outside a repository from a missing or corrupted HEAD,
+    /// which would otherwise surface as an opaque I/O or parse error.
+    pub fn verify_head(&self) -> Result<(), RefsError> {
+        if !self.path.is_dir() {
+            let workspace = self.path.parent().unwrap_or(&self.path);
+            return Err(RefsError::NotARepository(workspace.display().to_string()));
+        }
+
+        match SymRefOrOid::read_symref_or_oid(&self.head_path()) {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) | Err(_) => Err(RefsError::InvalidHead),
+        }
+    }
+
+    /// Move HEAD, or the branch it points to, to `oid`
+    ///
+    /// The update is logged with `message` in the reflog of HEAD and, unless
+    /// HEAD is detached, in that of its branch.
+    pub fn update_head(&self, oid: ObjectId, message: &str) -> Result<(), RefsError> {
+        let old_oid = self.read_head()?;
+        self.update_symref(self.head_path().as_ref(), oid.clone())?;
+
+        let head = SymRefName::new(HEAD_REF_NAME.to_string());
+        let current_ref = self.current_ref(None)?;
+        if current_ref != head {
+            self.log_ref_update(&current_ref, old_oid.as_ref(), &oid, message)?;
+        }
+
+        self.log_ref_update(&head, old_oid.as_ref(), &oid, message)
+    }
+
+    /// Point HEAD at `revision` like [`Refs::set_head`] does, logging the
+    /// move with `message` in the reflog of HEAD
+    pub fn switch_head(
+        &self,
+        revision: &str,
+        raw_ref: String,
+        message: &str,
+    ) -> Result<(), RefsError> {
+        let old_oid = self.read_head()?;
+        self.set_head(revision, raw_ref)?;
+
+        match self.read_head()? {
+            Some(new_oid) => self.log_ref_update(
+                &SymRefName::new(HEAD_REF_NAME.to_string()),
+                old_oid.as_ref(),
+                &new_oid,
+                message,
+            ),
+            None => Ok(()),
+        }
     }
 
     pub fn read_head(&self) -> Result<Option<ObjectId>, RefsError> {
@@ -280,11 +403,16 @@ impl Refs {
     }
 
     fn find_path_to_branch(&self, branch_name: BranchName) -> Result<Box<Path>, RefsError> {
-        [self.path.clone(), self.refs_path(), self.heads_path()]
-            .iter()
-            .map(|base_path| base_path.join(branch_name.as_ref()).into_boxed_path())
-            .find(|path| path.exists())
-            .ok_or_else(|| RefsError::BranchNotFound(branch_name.to_string()))
+        [
+            self.path.clone(),
+            self.refs_path(),
+            self.tags_path(),
+            self.heads_path(),
+        ]
+        .iter()
+        .map(|base_path| base_path.join(branch_name.as_ref()).into_boxed_path())
+        .find(|path| path.exists())
+        .ok_or_else(|| RefsError::BranchNotFound(branch_name.to_string()))
     }
 
     fn read_ref_file(&self, path: PathBuf) -> Result<Option<ObjectId>, RefsError> {
@@ -301,14 +429,25 @@ impl Refs {
         }
     }
 
-    pub fn create_branch(&self, name: BranchName, source_oid: ObjectId) -> Result<(), RefsError> {
+    /// Create the branch `name` at `source_oid`, starting its reflog with
+    /// `message`
+    pub fn create_branch(
+        &self,
+        name: BranchName,
+        source_oid: ObjectId,
+        message: &str,
+    ) -> Result<(), RefsError> {
         let branch_path = self.heads_path().join(name.as_ref());
 
         if branch_path.exists() && !name.is_default_branch() {
             return Err(RefsError::BranchAlreadyExists(name.to_string()));
         }
 
-        self.update_ref_file(branch_path, source_oid.as_ref().into())
+        let old_oid = self.read_symref(&branch_path)?;
+        self.update_ref_file(branch_path, source_oid.as_ref().into())?;
+
+        let sym_ref_name = SymRefName::new(format!("refs/heads/{}", name.as_ref()));
+        self.log_ref_update(&sym_ref_name, old_oid.as_ref(), &source_oid, message)
     }
 
     pub fn delete_branch(&self, name: &BranchName) -> Result<ObjectId, RefsError> {
@@ -323,7 +462,16 @@ impl Refs {
                         source: e,
                     }
                 })?;
-                self.prune_branch_empty_parent_dirs(branch_path.as_ref())?;
+                self.prune_empty_parent_dirs(branch_path.as_ref(), &self.heads_path())?;
+
+                // A branch created later under the same name starts a new log
+                let sym_ref_name = SymRefName::new(format!("refs/heads/{}", name.as_ref()));
+                let log_path = self.reflog_path(&sym_ref_name);
+                if log_path.exists() {
+                    std::fs::remove_file(&log_path)?;
+                    let logs_path = self.path.join(LOGS_DIR).join("refs").join("heads");
+                    self.prune_empty_parent_dirs(&log_path, &logs_path)?;
+                }
 
                 Ok(oid)
             }
@@ -331,6 +479,35 @@ impl Refs {
         }
     }
 
+    pub fn branch_exists(&self, name: &BranchName) -> bool {
+        self.heads_path().join(name.as_ref()).is_file()
+    }
+
+    /// Remote-tracking branches named `refs/remotes/<remote>/<name>`, as
+    /// `(remote, tip)` pairs sorted by remote
+    pub fn find_remote_branches(
+        &self,
+        name: &BranchName,
+    ) -> Result<Vec<(String, ObjectId)>, RefsError> {
+        let Ok(remotes) = std::fs::read_dir(self.remotes_path()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut matches = Vec::new();
+        for remote in remotes.filter_map(|entry| entry.ok()) {
+            let ref_path = remote.path().join(name.as_ref());
+            if !ref_path.is_file() {
+                continue;
+            }
+            if let Some(oid) = self.read_symref(&ref_path)? {
+                matches.push((remote.file_name().to_string_lossy().to_string(), oid));
+            }
+        }
+        matches.sort();
+
+        Ok(matches)
+    }
+
     pub fn list_branches(&self) -> Result<Vec<SymRefName>, RefsError> {
         self.list_refs(self.heads_path().as_ref())
     }
@@ -350,12 +527,18 @@ impl Refs {
             .collect::<Vec<_>>())
     }
 
+    /// Map every object pointed at by a ref (branches, tags, remotes and
+    /// HEAD) to the refs pointing at it
+    ///
+    /// Ref files are read directly, since only branch names would get
+    /// through `read_oid`.
     pub fn reverse_refs(&self) -> Result<HashMap<ObjectId, Vec<SymRefName>>, RefsError> {
         Ok(self
             .list_all_refs()?
             .into_iter()
             .fold(HashMap::new(), |mut acc, sym_ref| {
-                if let Ok(Some(oid)) = self.read_oid(&sym_ref) {
+                let ref_path = self.path.join(sym_ref.as_ref_path());
+                if let Ok(Some(oid)) = self.read_symref(&ref_path) {
                     acc.entry(oid).or_insert_with(Vec::new).push(sym_ref);
                 }
                 acc
@@ -370,16 +553,78 @@ impl Refs {
             .collect::<Vec<_>>())
     }
 
-    fn prune_branch_empty_parent_dirs(&self, path: &Path) -> Result<(), RefsError> {
+    /// Point a tag at an object, returning what the tag pointed at before
+    ///
+    /// An existing tag is only moved when `force` is set.
+    pub fn write_tag(
+        &self,
+        name: &BranchName,
+        oid: &ObjectId,
+        force: bool,
+    ) -> Result<Option<ObjectId>, RefsError> {
+        let previous = self.read_tag(name)?;
+        if previous.is_some() && !force {
+            return Err(RefsError::TagAlreadyExists(name.to_string()));
+        }
+
+        self.update_ref_file(self.tags_path().join(name.as_ref()), oid.as_ref().into())?;
+
+        Ok(previous)
+    }
+
+    pub fn read_tag(&self, name: &BranchName) -> Result<Option<ObjectId>, RefsError> {
+        let tag_path = self.tags_path().join(name.as_ref());
+        if !tag_path.is_file() {
+            return Ok(None);
+        }
+
+        self.read_ref_file(tag_path)
+    }
+
+    /// Remove a tag, returning the object it pointed at
+    pub fn delete_tag(&self, name: &BranchName) -> Result<ObjectId, RefsError> {
+        let tag_path = self.tags_path().join(name.as_ref());
+        let oid = self
+            .read_tag(name)?
+            .ok_or_else(|| RefsError::TagNotFound(name.to_string()))?;
+
+        std::fs::remove_file(&tag_path).map_err(|e| RefsError::DeleteTag {
+            path: tag_path.display().to_string(),
+            source: e,
+        })?;
+        self.prune_empty_parent_dirs(&tag_path, &self.tags_path())?;
+
+        Ok(oid)
+    }
+
+    /// Names of all tags (relative to `refs/tags`), sorted
+    pub fn list_tags(&self) -> Result<Vec<String>, RefsError> {
+        let tags_path = self.tags_path();
+        let mut tags = WalkDir::new(&tags_path)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| {
+                let name = entry.path().strip_prefix(&tags_path).ok()?;
+                Some(name.to_string_lossy().to_string())
+            })
+            .collect::<Vec<_>>();
+        tags.sort();
+
+        Ok(tags)
+    }
+
+    /// Remove the now empty directories between `path` and `root`
+    fn prune_empty_parent_dirs(&self, path: &Path, root: &Path) -> Result<(), RefsError> {
         if let Some(parent) = path.parent()
-            && parent != self.heads_path().as_ref()
+            && parent != root
             && parent.read_dir()?.next().is_none()
         {
-            std::fs::remove_dir(parent).map_err(|e| RefsError::RemoveBranchDir {
+            std::fs::remove_dir(parent).map_err(|e| RefsError::RemoveRefDir {
                 path: parent.display().to_string(),
                 source: e,
             })?;
-            self.prune_branch_empty_parent_dirs(parent)?;
+            self.prune_empty_parent_dirs(parent, root)?;
         }
 
         Ok(())
@@ -437,6 +682,189 @@ impl Refs {
         Ok(())
     }
 
+    /// Remember where HEAD pointed before a command moved it
+    pub fn write_orig_head(&self, oid: &ObjectId) -> Result<(), RefsError> {
+        let path = self.path.join(ORIG_HEAD);
+        self.update_ref_file(path, format!("{}\n", oid.as_ref()))
+    }
+
+    /// Append an entry to the reflog of `sym_ref_name` (e.g. `HEAD` or
+    /// `refs/heads/master`), creating the log if needed
+    ///
+    /// # Arguments
+    ///
+    /// * `old_oid` - Previous value of the ref, `None` if it did not exist
+    /// * `new_oid` - New value of the ref
+    /// * `committer` - Identity and time of the update
+    /// * `message` - Reason for the update, e.g. `reset: moving to HEAD~1`
+    pub fn append_reflog(
+        &self,
+        sym_ref_name: &SymRefName,
+        old_oid: Option<&ObjectId>,
+        new_oid: &ObjectId,
+        committer: &Author,
+        message: &str,
+    ) -> Result<(), RefsError> {
+        let path = self.reflog_path(sym_ref_name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| RefsError::CreateRefDir {
+                path: parent.display().to_string(),
+                source: e,
+            })?;
+        }
+
+        let mut log_file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| RefsError::OpenRefFile {
+                path: path.display().to_string(),
+                source: e,
+            })?;
+        let mut lock = file_guard::lock(&mut log_file, Lock::Exclusive, 0, 1)?;
+        writeln!(
+            lock.deref_mut(),
+            "{} {} {}\t{}",
+            old_oid.map_or(NULL_OID, |oid| oid.as_ref()),
+            new_oid.as_ref(),
+            committer.display(),
+            message
+        )?;
+
+        Ok(())
+    }
+
+    /// Log an update of `sym_ref_name` on behalf of the committer from the
+    /// environment
+    fn log_ref_update(
+        &self,
+        sym_ref_name: &SymRefName,
+        old_oid: Option<&ObjectId>,
+        new_oid: &ObjectId,
+        message: &str,
+    ) -> Result<(), RefsError> {
+        // Reflogs are best-effort history: a missing identity must not fail the update
+        let committer = Author::load_from_env()
+            .unwrap_or_else(|_| Author::new("unknown".to_string(), "unknown".to_string()));
+
+        self.append_reflog(sym_ref_name, old_oid, new_oid, &committer, message)
+    }
+
+    pub fn reflog_path(&self, sym_ref_name: &SymRefName) -> PathBuf {
+        self.path.join(LOGS_DIR).join(sym_ref_name.as_ref_path())
+    }
+
+    /// Entries of the reflog of `sym_ref_name`, oldest first
+    ///
+    /// A ref without a reflog has no entries.
+    pub fn read_reflog(&self, sym_ref_name: &SymRefName) -> Result<Vec<ReflogEntry>, RefsError> {
+        let path = self.reflog_path(sym_ref_name);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| RefsError::ReadRefFile {
+            path: path.display().to_string(),
+            source: e,
+        })?;
+        content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                ReflogEntry::try_from(line).map_err(|e| RefsError::InvalidReflog {
+                    path: path.display().to_string(),
+                    source: e,
+                })
+            })
+            .collect()
+    }
+
+    /// Full name of the ref `branch_name` refers to, e.g. `refs/heads/master`
+    /// for `master`, looked up like [`Refs::read_ref`] does
+    pub fn full_ref_name(&self, branch_name: BranchName) -> Result<SymRefName, RefsError> {
+        let path = self.find_path_to_branch(branch_name)?;
+        let relative = path.strip_prefix(&self.path).unwrap_or(&path);
+
+        Ok(SymRefName::new(relative.to_string_lossy().to_string()))
+    }
+
+    /// Record the ref (or detached OID) HEAD pointed at when bisection started
+    pub fn write_bisect_start(&self, original_head: &str) -> Result<(), RefsError> {
+        let path = self.path.join(BISECT_START);
+        self.update_ref_file(path, original_head.to_string())
+    }
+
+    pub fn read_bisect_start(&self) -> Result<Option<String>, RefsError> {
+        let path = self.path.join(BISECT_START);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let content = content.trim().to_string();
+        if content.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(content))
+    }
+
+    pub fn write_bisect_bad(&self, oid: &ObjectId) -> Result<(), RefsError> {
+        let path = self.path.join(BISECT_BAD);
+        self.update_ref_file(path, oid.as_ref().to_string())
+    }
+
+    pub fn read_bisect_bad(&self) -> Result<Option<ObjectId>, RefsError> {
+        let path = self.path.join(BISECT_BAD);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let content = content.trim();
+        if content.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(ObjectId::try_parse(content.to_string())?))
+    }
+
+    /// Append a commit to the known good commits, one OID per line
+    pub fn add_bisect_good(&self, oid: &ObjectId) -> Result<(), RefsError> {
+        let mut good = self.read_bisect_good()?;
+        if good.contains(oid) {
+            return Ok(());
+        }
+        good.push(oid.clone());
+
+        let path = self.path.join(BISECT_GOOD);
+        let content = good
+            .iter()
+            .map(|oid| format!("{}\n", oid.as_ref()))
+            .collect::<String>();
+        self.update_ref_file(path, content)
+    }
+
+    pub fn read_bisect_good(&self) -> Result<Vec<ObjectId>, RefsError> {
+        let path = self.path.join(BISECT_GOOD);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| Ok(ObjectId::try_parse(line.to_string())?))
+            .collect()
+    }
+
+    pub fn clear_bisect_state(&self) -> Result<(), RefsError> {
+        for name in [BISECT_START, BISECT_BAD, BISECT_GOOD] {
+            let path = self.path.join(name);
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn head_path(&self) -> Box<Path> {
         self.path.join("HEAD").into_boxed_path()
     }
@@ -448,6 +876,14 @@ impl Refs {
     pub fn heads_path(&self) -> Box<Path> {
         self.refs_path().join("heads").into_boxed_path()
     }
+
+    pub fn tags_path(&self) -> Box<Path> {
+        self.refs_path().join("tags").into_boxed_path()
+    }
+
+    pub fn remotes_path(&self) -> Box<Path> {
+        self.refs_path().join("remotes").into_boxed_path()
+    }
 }
 
 #[cfg(test)]
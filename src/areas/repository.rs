@@ -17,17 +17,21 @@
 //! The index is wrapped in Arc<Mutex<>> to allow safe concurrent access,
 //! while other components use interior mutability where needed.
 
+use crate::areas::config::Config;
 use crate::areas::database::Database;
 use crate::areas::index::Index;
 use crate::areas::refs::Refs;
 use crate::areas::workspace::Workspace;
 use crate::artifacts::branch::branch_name::SymRefName;
+use crate::artifacts::core::quote::QuoteOptions;
+use crate::artifacts::diff::diff_target::DiffPrefix;
+use crate::artifacts::diff::moved_lines::ColorMoved;
 use crate::artifacts::objects::object_id::ObjectId;
-use crate::artifacts::status::status_info::Status;
-use std::cell::{Ref, RefCell, RefMut};
+use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
 
 /// Git directory name
@@ -39,6 +43,19 @@ const DATABASE_DIR: &str = "objects";
 /// Index file name
 const INDEX_FILE: &str = "index";
 
+/// Config file name
+const CONFIG_FILE: &str = "config";
+
+/// Config key controlling whether non-ASCII bytes in paths are escaped
+const QUOTE_PATH_KEY: &str = "core.quotePath";
+
+/// Config key making the workspace match paths regardless of case
+pub const IGNORE_CASE_KEY: &str = "core.ignorecase";
+/// Config key making checkouts and resets flush the files they write to disk
+///
+/// Separate from `core.fsyncObjectFiles`, which only covers the object database
+const FSYNC_KEY: &str = "core.fsyncWorktreeFiles";
+
 /// Git repository
 ///
 /// Coordinates all repository operations and provides access to the database,
@@ -49,9 +66,9 @@ const INDEX_FILE: &str = "index";
 ///
 /// ```ignore
 /// let repo = Repository::new(PathBuf::from("."), Box::new(stdout()))?;
-/// repo.init().await?;
-/// repo.add(&["file.txt"]).await?;
-/// repo.commit("Initial commit").await?;
+/// repo.init(None).await?;
+/// repo.add(&["file.txt"], false).await?;
+/// repo.commit(Some("Initial commit"), false).await?;
 /// ```
 pub struct Repository {
     /// Repository root path
@@ -66,10 +83,20 @@ pub struct Repository {
     workspace: Workspace,
     /// Reference manager
     refs: Refs,
+    /// Repository configuration
+    config: Config,
     /// Currently checked-out reference (cached)
     current_ref: RefCell<SymRefName>,
     /// Reverse index: OID -> refs that point to it (for decoration)
     reverse_refs: RefCell<HashMap<ObjectId, Vec<SymRefName>>>,
+    /// Path prefixes used in diff headers
+    diff_prefix: RefCell<DiffPrefix>,
+    /// Whether diffs run `diff.<driver>.textconv` commands
+    textconv: Cell<bool>,
+    /// Whether diffs run the `diff.external` program
+    ext_diff: Cell<bool>,
+    /// How diffs highlight lines moved within a file
+    color_moved: Cell<ColorMoved>,
 }
 
 impl Repository {
@@ -82,8 +109,18 @@ impl Repository {
 
         let index = Index::new(path.join(GIT_DIR).join(INDEX_FILE).into_boxed_path());
         let database = Database::new(path.join(GIT_DIR).join(DATABASE_DIR).into_boxed_path());
-        let workspace = Workspace::new(path.clone().into_boxed_path());
         let refs = Refs::new(path.join(GIT_DIR).into_boxed_path());
+        let config = Config::new(path.join(GIT_DIR).join(CONFIG_FILE).into_boxed_path());
+        // An unreadable config is reported by the commands that need it
+        let ignore_case = config
+            .get_bool(IGNORE_CASE_KEY)
+            .ok()
+            .flatten()
+            .unwrap_or(false);
+        let fsync = config.get_bool(FSYNC_KEY).ok().flatten().unwrap_or(false);
+        let workspace = Workspace::new(path.clone().into_boxed_path())
+            .with_ignore_case(ignore_case)
+            .with_fsync(fsync);
         let current_ref = refs.current_ref(None)?;
 
         Ok(Repository {
@@ -93,15 +130,60 @@ impl Repository {
             database,
             workspace,
             refs,
+            config,
             current_ref: RefCell::new(current_ref),
             reverse_refs: RefCell::new(HashMap::new()),
+            diff_prefix: RefCell::new(DiffPrefix::default()),
+            textconv: Cell::new(false),
+            ext_diff: Cell::new(false),
+            color_moved: Cell::new(ColorMoved::No),
         })
     }
 
+    /// Open an existing repository
+    ///
+    /// Unlike `new`, fails early with a clear error when the directory is not a
+    /// repository or its HEAD is missing or unreadable.
+    pub fn open(path: PathBuf, writer: Box<dyn std::io::Write>) -> anyhow::Result<Self> {
+        let path = path.canonicalize()?;
+        Refs::new(path.join(GIT_DIR).into_boxed_path()).verify_head()?;
+
+        Self::new(path, writer)
+    }
+
+    /// Open the repository containing `path`
+    ///
+    /// Walks up from `path` to the first directory holding a `.git` directory,
+    /// so commands also work from inside the working tree or the git directory.
+    pub fn discover(path: PathBuf, writer: Box<dyn std::io::Write>) -> anyhow::Result<Self> {
+        let path = path.canonicalize()?;
+        let root = path
+            .ancestors()
+            .find(|ancestor| ancestor.join(GIT_DIR).is_dir())
+            .map(Path::to_path_buf)
+            .unwrap_or(path);
+
+        Self::open(root, writer)
+    }
+
     pub fn path(&self) -> &Path {
         &self.path
     }
 
+    pub fn git_dir(&self) -> PathBuf {
+        self.path.join(GIT_DIR)
+    }
+
+    /// How paths are quoted in output, following `core.quotePath`
+    ///
+    /// `space` also quotes paths containing spaces, as the short status format does.
+    pub fn quote_options(&self, space: bool) -> anyhow::Result<QuoteOptions> {
+        Ok(QuoteOptions {
+            non_ascii: self.config().get_bool(QUOTE_PATH_KEY)?.unwrap_or(true),
+            space,
+        })
+    }
+
     pub fn writer(&'_ self) -> RefMut<'_, Box<dyn std::io::Write>> {
         self.writer.borrow_mut()
     }
@@ -110,6 +192,14 @@ impl Repository {
         self.index.clone()
     }
 
+    /// Write the index, smudging racily clean entries first
+    pub fn write_index(&self, index: &mut Index) -> anyhow::Result<()> {
+        smudge_racily_clean_entries(index, self.workspace());
+        index.write_updates()?;
+
+        Ok(())
+    }
+
     pub fn database(&self) -> &Database {
         &self.database
     }
@@ -122,8 +212,8 @@ impl Repository {
         &self.refs
     }
 
-    pub fn status(&'_ self) -> Status<'_> {
-        Status::new(self)
+    pub fn config(&self) -> &Config {
+        &self.config
     }
 
     pub fn current_ref(&self) -> Ref<'_, SymRefName> {
@@ -141,4 +231,70 @@ impl Repository {
     pub fn set_reverse_refs(&self, new_reverse_refs: HashMap<ObjectId, Vec<SymRefName>>) {
         *self.reverse_refs.borrow_mut() = new_reverse_refs;
     }
+
+    pub fn diff_prefix(&self) -> Ref<'_, DiffPrefix> {
+        self.diff_prefix.borrow()
+    }
+
+    pub fn set_diff_prefix(&self, new_diff_prefix: DiffPrefix) {
+        *self.diff_prefix.borrow_mut() = new_diff_prefix;
+    }
+
+    pub fn textconv(&self) -> bool {
+        self.textconv.get()
+    }
+
+    pub fn set_textconv(&self, textconv: bool) {
+        self.textconv.set(textconv);
+    }
+
+    pub fn ext_diff(&self) -> bool {
+        self.ext_diff.get()
+    }
+
+    pub fn set_ext_diff(&self, ext_diff: bool) {
+        self.ext_diff.set(ext_diff);
+    }
+
+    pub fn color_moved(&self) -> ColorMoved {
+        self.color_moved.get()
+    }
+
+    pub fn set_color_moved(&self, color_moved: ColorMoved) {
+        self.color_moved.set(color_moved);
+    }
+}
+
+/// Zero the size of entries written in the current second whose stat data
+/// still matches a file whose content changed
+///
+/// Once the index is written, these entries would no longer be racy and
+/// their stat data would hide the change; a zero size never matches a
+/// non-empty file, so the content is compared instead.
+fn smudge_racily_clean_entries(index: &mut Index, workspace: &Workspace) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs() as i64);
+
+    let smudged = index
+        .entries()
+        .filter(|entry| entry.metadata.mtime >= now && !entry.metadata.mode.is_gitlink())
+        .filter(|entry| {
+            workspace
+                .stat_file(&entry.name)
+                .is_ok_and(|stat| entry.stat_match(&stat) && entry.times_match(&stat))
+        })
+        .filter(|entry| {
+            workspace
+                .read_file_bytes(&entry.name)
+                .ok()
+                .and_then(|content| Database::blob_id(&content).ok())
+                .is_some_and(|oid| oid != entry.oid)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for entry in &smudged {
+        index.smudge_entry(entry);
+    }
 }
@@ -6,14 +6,20 @@
 //! ## Responsibilities
 //!
 //! - Reading and writing files
-//! - Listing directories recursively
+//! - Listing directories recursively, with one stat per file
 //! - Tracking file metadata (mode, timestamps)
 //! - Filtering out ignored files and directories (.git, etc.)
+//! - Resolving paths case-insensitively when `core.ignorecase` is set
 //! - Applying checkout migrations (creating, updating, deleting files)
+//! - Creating each parent directory once per batch of writes, and flushing
+//!   written files to disk when `core.fsyncWorktreeFiles` is set
 
 use crate::artifacts::checkout::migration::{ActionType, Migration};
+use crate::artifacts::core::ignore::{IgnoreError, IgnoreFiles};
+use crate::artifacts::index::entry_mode::EntryMode;
 use crate::artifacts::index::index_entry::{EntryMetadata, IndexEntryError};
 use crate::artifacts::objects::blob::Blob;
+use std::collections::HashSet;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
@@ -26,6 +32,8 @@ pub enum WorkspaceError {
     PathNotFound(String),
     #[error("path is not a directory: {0}")]
     NotADirectory(String),
+    #[error("path is outside the repository: {0}")]
+    OutsideWorkspace(String),
     #[error("invalid action type")]
     InvalidActionType,
     #[error("invalid action and entry combination")]
@@ -38,6 +46,8 @@ pub enum WorkspaceError {
         source: std::io::Error,
     },
     #[error(transparent)]
+    Ignore(#[from] IgnoreError),
+    #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Internal(#[from] anyhow::Error),
@@ -46,6 +56,28 @@ pub enum WorkspaceError {
 /// Paths that should always be ignored when scanning the workspace
 const IGNORED_PATHS: [&str; 3] = [".git", ".", ".."];
 
+/// An entry found by [`Workspace::walk`], with its path relative to the
+/// workspace root
+#[derive(Debug, Clone)]
+pub enum WalkEntry {
+    /// A file or symlink, with its stat metadata
+    File(PathBuf, EntryMetadata),
+    /// A directory, yielded before its contents
+    Dir(PathBuf),
+    /// A file or directory matched by the ignore rules, never entered
+    Ignored { path: PathBuf, is_dir: bool },
+}
+
+impl WalkEntry {
+    /// The path and metadata of a file entry
+    pub fn into_file(self) -> Option<(PathBuf, EntryMetadata)> {
+        match self {
+            WalkEntry::File(path, stat) => Some((path, stat)),
+            _ => None,
+        }
+    }
+}
+
 /// Working directory abstraction
 ///
 /// Provides file system operations for the Git working tree.
@@ -54,17 +86,110 @@ const IGNORED_PATHS: [&str; 3] = [".git", ".", ".."];
 pub struct Workspace {
     /// Root path of the working directory
     path: Box<Path>,
+    /// Whether paths that differ only in case name the same file
+    ignore_case: bool,
+    /// Whether written files are flushed to disk before moving on
+    fsync: bool,
+}
+
+/// Directories known to exist during one batch of file writes
+///
+/// Checkouts write many files sharing the same parents; remembering the
+/// directories already created (or found) saves a `create_dir_all` per file.
+#[derive(Debug, Default)]
+pub struct DirCache {
+    /// Directories that exist, created or found
+    known: HashSet<PathBuf>,
+    /// Directories created by this batch, which start out empty
+    created: HashSet<PathBuf>,
+    creations: usize,
+}
+
+impl DirCache {
+    /// Number of `create_dir_all` calls made through this cache
+    pub fn creations(&self) -> usize {
+        self.creations
+    }
+
+    /// Record `dir` and all its ancestors as existing
+    fn insert(&mut self, dir: &Path) {
+        for ancestor in dir.ancestors() {
+            if !self.known.insert(ancestor.to_path_buf()) {
+                break;
+            }
+        }
+    }
 }
 
 impl Workspace {
     pub fn new(path: Box<Path>) -> Self {
-        Workspace { path }
+        Workspace {
+            path,
+            ignore_case: false,
+            fsync: false,
+        }
+    }
+
+    /// Treat paths differing only in case as the same file (`core.ignorecase`)
+    pub fn with_ignore_case(mut self, ignore_case: bool) -> Self {
+        self.ignore_case = ignore_case;
+        self
+    }
+
+    /// Flush every file written by checkouts and resets to disk
+    pub fn with_fsync(mut self, fsync: bool) -> Self {
+        self.fsync = fsync;
+        self
     }
 
     pub fn path(&self) -> &Path {
         &self.path
     }
 
+    pub fn ignore_case(&self) -> bool {
+        self.ignore_case
+    }
+
+    /// Whether `path` (relative to the workspace root) is a directory
+    pub fn is_dir(&self, path: &Path) -> bool {
+        self.full_path(path).is_dir()
+    }
+
+    /// Whether `path` (relative to the workspace root) is a regular file
+    pub fn is_file(&self, path: &Path) -> bool {
+        self.full_path(path).is_file()
+    }
+
+    /// Absolute path of a workspace file
+    ///
+    /// With `ignore_case`, a path that does not exist as spelled is matched
+    /// component by component against the directory entries regardless of
+    /// case, so `File.txt` finds `file.txt` on case-sensitive filesystems too.
+    fn full_path(&self, path: &Path) -> PathBuf {
+        let exact = self.path.join(path);
+        if !self.ignore_case || exact.exists() {
+            return exact;
+        }
+
+        let mut resolved = self.path.to_path_buf();
+        for component in path.components() {
+            let wanted = component.as_os_str().to_string_lossy().to_lowercase();
+            let on_disk = std::fs::read_dir(&resolved).ok().and_then(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.file_name())
+                    .find(|name| name.to_string_lossy().to_lowercase() == wanted)
+            });
+
+            match on_disk {
+                Some(name) => resolved.push(name),
+                None => return exact,
+            }
+        }
+
+        resolved
+    }
+
     /// Parse a file into a Blob object
     ///
     /// Reads the file content and creates a Blob with default mode.
@@ -83,14 +208,14 @@ impl Workspace {
     ///
     /// # Arguments
     ///
-    /// * `dir_path` - Directory to list (None for workspace root)
+    /// * `dir_path` - Directory to list, relative to workspace root (None for the root)
     ///
     /// # Returns
     ///
     /// Vector of paths to children, relative to workspace root
     pub fn list_dir(&self, dir_path: Option<&Path>) -> Result<Vec<PathBuf>, WorkspaceError> {
         let dir_path = match dir_path {
-            Some(p) => std::fs::canonicalize(p)?,
+            Some(p) => std::fs::canonicalize(self.full_path(p))?,
             None => self.path.clone().into(),
         };
 
@@ -110,22 +235,39 @@ impl Workspace {
         }
     }
 
-    /// List all files recursively
+    /// Walk all files and directories recursively, stat'ing each file once
     ///
-    /// Walks the directory tree and returns all non-ignored files.
+    /// Symlinks are yielded as files of their own, never followed. `.git` is
+    /// never entered, and with `ignores`, ignored files and directories are
+    /// yielded as such without descending into them. Entries that vanish or
+    /// cannot be read while walking are skipped.
     ///
     /// # Arguments
     ///
-    /// * `root_file_path` - Starting path (None for workspace root)
+    /// * `root_file_path` - Starting path, absolute or relative to the
+    ///   current directory (None for workspace root)
+    /// * `ignores` - Ignore rules to honour
     ///
     /// # Returns
     ///
-    /// Vector of file paths relative to workspace root
-    // TODO: refactor to use iterator
-    pub fn list_files(
-        &self,
+    /// Iterator over the entries below the starting path, which is itself
+    /// yielded unless it is the workspace root
+    pub fn walk<'w>(
+        &'w self,
+        root_file_path: Option<PathBuf>,
+        ignores: Option<&'w IgnoreFiles>,
+    ) -> Result<impl Iterator<Item = Result<WalkEntry, WorkspaceError>> + 'w, WorkspaceError> {
+        self.walk_pruning(root_file_path, ignores, |_| false)
+    }
+
+    /// Walk like [`Workspace::walk`], but without descending into the
+    /// directories `prune` accepts; they are still yielded themselves
+    pub fn walk_pruning<'w>(
+        &'w self,
         root_file_path: Option<PathBuf>,
-    ) -> Result<Vec<PathBuf>, WorkspaceError> {
+        ignores: Option<&'w IgnoreFiles>,
+        prune: impl Fn(&Path) -> bool + 'w,
+    ) -> Result<impl Iterator<Item = Result<WalkEntry, WorkspaceError>> + 'w, WorkspaceError> {
         let root_file_path = match root_file_path {
             Some(p) => std::fs::canonicalize(p)?,
             None => self.path.clone().into(),
@@ -136,21 +278,68 @@ impl Workspace {
                 root_file_path.display().to_string(),
             ));
         }
-
-        if root_file_path.is_dir() {
-            Ok(WalkDir::new(&root_file_path)
-                .into_iter()
-                .filter_map(|entry| entry.ok())
-                .filter_map(|entry| self.check_if_not_ignored_file_path(entry.path()))
-                .collect::<Vec<_>>())
-        } else {
-            Ok(vec![
-                root_file_path
-                    .strip_prefix(self.path.as_ref())
-                    .map(PathBuf::from)
-                    .unwrap_or_default(),
-            ])
+        if !root_file_path.starts_with(&self.path) {
+            return Err(WorkspaceError::OutsideWorkspace(
+                root_file_path.display().to_string(),
+            ));
         }
+
+        let mut entries = WalkDir::new(&root_file_path)
+            .follow_root_links(false)
+            .into_iter();
+
+        Ok(std::iter::from_fn(move || {
+            loop {
+                let Ok(entry) = entries.next()? else {
+                    continue;
+                };
+                let Ok(path) = entry.path().strip_prefix(self.path.as_ref()) else {
+                    continue;
+                };
+                let is_dir = entry.file_type().is_dir();
+
+                if Self::is_ignored(path) {
+                    if is_dir {
+                        entries.skip_current_dir();
+                    }
+                    continue;
+                }
+                if path.as_os_str().is_empty() {
+                    continue;
+                }
+
+                let path = path.to_path_buf();
+                match ignores.map(|ignores| ignores.is_ignored(&path, is_dir)) {
+                    Some(Ok(true)) => {
+                        if is_dir {
+                            entries.skip_current_dir();
+                        }
+                        return Some(Ok(WalkEntry::Ignored { path, is_dir }));
+                    }
+                    Some(Err(error)) => return Some(Err(error.into())),
+                    Some(Ok(false)) | None => {}
+                }
+
+                if is_dir {
+                    if prune(&path) {
+                        entries.skip_current_dir();
+                    }
+                    return Some(Ok(WalkEntry::Dir(path)));
+                }
+                if !(entry.file_type().is_file() || entry.path_is_symlink()) {
+                    continue;
+                }
+                let Some(stat) = entry
+                    .metadata()
+                    .ok()
+                    .and_then(|metadata| (entry.path(), metadata).try_into().ok())
+                else {
+                    continue;
+                };
+
+                return Some(Ok(WalkEntry::File(path, stat)));
+            }
+        }))
     }
 
     /// Check if a path should be ignored
@@ -176,44 +365,170 @@ impl Workspace {
         }
     }
 
-    fn check_if_not_ignored_file_path(&self, path: &Path) -> Option<PathBuf> {
-        if path.is_file() && !Self::is_ignored(path) {
-            Some(path.strip_prefix(self.path.as_ref()).ok()?.to_path_buf())
-        } else {
-            None
-        }
-    }
-
+    /// Read a file, or the path a symlink points to
     pub fn read_file(&self, file_path: &Path) -> Result<String, WorkspaceError> {
-        let file_path = self.path.join(file_path);
+        let file_path = self.full_path(file_path);
+
+        if file_path.is_symlink() {
+            let target = std::fs::read_link(file_path)?;
+            return Ok(target.to_string_lossy().into_owned());
+        }
 
         let content = std::fs::read_to_string(file_path)?;
 
         Ok(content)
     }
 
+    /// Read a file as raw bytes, or the path a symlink points to
+    ///
+    /// Unlike [`Self::read_file`], the content need not be UTF-8.
+    pub fn read_file_bytes(&self, file_path: &Path) -> Result<Vec<u8>, WorkspaceError> {
+        let file_path = self.full_path(file_path);
+
+        if file_path.is_symlink() {
+            let target = std::fs::read_link(file_path)?;
+            return Ok(target.as_os_str().as_encoded_bytes().to_vec());
+        }
+
+        Ok(std::fs::read(file_path)?)
+    }
+
+    /// Open a file for reading, for content too large to load at once
+    pub fn open_file(&self, file_path: &Path) -> Result<std::fs::File, WorkspaceError> {
+        Ok(std::fs::File::open(self.full_path(file_path))?)
+    }
+
     pub fn write_file(&self, file_path: &Path, content: &[u8]) -> Result<(), WorkspaceError> {
         let full_path = self.path.join(file_path);
         std::fs::write(full_path, content)?;
         Ok(())
     }
 
+    /// Write a tracked file with the given content and mode
+    ///
+    /// Missing parent directories are created and a directory standing where
+    /// the file belongs is replaced.
+    pub fn write_entry_file(
+        &self,
+        file_path: &Path,
+        content: &[u8],
+        mode: &EntryMode,
+    ) -> Result<(), WorkspaceError> {
+        self.write_entry_file_cached(&mut DirCache::default(), file_path, content, mode)
+    }
+
+    /// Like `write_entry_file`, skipping parent directories already in `dirs`
+    pub fn write_entry_file_cached(
+        &self,
+        dirs: &mut DirCache,
+        file_path: &Path,
+        content: &[u8],
+        mode: &EntryMode,
+    ) -> Result<(), WorkspaceError> {
+        let full_path = self.path.join(file_path);
+
+        if full_path.is_symlink() {
+            // Writing through a symlink would change the file it points to
+            std::fs::remove_file(&full_path)?;
+        } else if full_path.is_dir() {
+            std::fs::remove_dir_all(&full_path)?;
+        }
+        if let Some(parent) = file_path.parent() {
+            self.ensure_dir(dirs, parent)?;
+        }
+
+        if mode.is_symlink() {
+            return Ok(write_symlink(&full_path, content)?);
+        }
+        self.write_synced(&full_path, content)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&full_path, std::fs::Permissions::from_mode(mode.as_u32()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Create `dir` with its parents unless the cache already knows it exists
+    fn ensure_dir(&self, dirs: &mut DirCache, dir: &Path) -> Result<(), WorkspaceError> {
+        if dir.as_os_str().is_empty() || dirs.known.contains(dir) {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(self.path.join(dir))?;
+        dirs.creations += 1;
+        dirs.insert(dir);
+        dirs.created.insert(dir.to_path_buf());
+
+        Ok(())
+    }
+
+    /// Write a file, flushing it to disk when `fsync` is enabled
+    fn write_synced(&self, full_path: &Path, content: &[u8]) -> std::io::Result<()> {
+        let mut file = std::fs::File::create(full_path)?;
+        file.write_all(content)?;
+        if self.fsync {
+            file.sync_all()?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove a file along with the parent directories it leaves empty
+    pub fn remove_file(&self, file_path: &Path) -> Result<(), WorkspaceError> {
+        let full_path = self.path.join(file_path);
+
+        if full_path.is_file() || full_path.is_symlink() {
+            std::fs::remove_file(&full_path)?;
+        }
+
+        for parent in file_path
+            .ancestors()
+            .skip(1)
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            // Stops at the first directory that still has entries
+            if std::fs::remove_dir(self.path.join(parent)).is_err() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove a file, or a directory with everything inside it
+    pub fn remove_path(&self, path: &Path) -> Result<(), WorkspaceError> {
+        let full_path = self.path.join(path);
+
+        if full_path.is_dir() {
+            std::fs::remove_dir_all(&full_path)?;
+        } else {
+            std::fs::remove_file(&full_path)?;
+        }
+
+        Ok(())
+    }
+
     pub fn rename_file(&self, from: &Path, to: &Path) -> Result<(), WorkspaceError> {
         std::fs::rename(self.path.join(from), self.path.join(to))?;
         Ok(())
     }
 
+    /// Stat a file without following symlinks, which are entries of their own
     pub fn stat_file(&self, file_path: &Path) -> Result<EntryMetadata, WorkspaceError> {
-        let metadata = std::fs::metadata(self.path.join(file_path))?;
+        let full_path = self.full_path(file_path);
+        let metadata = std::fs::symlink_metadata(&full_path)?;
 
-        Ok((file_path, metadata).try_into()?)
+        Ok((full_path.as_path(), metadata).try_into()?)
     }
 
     // The order of applying migrations is important:
     // For deletions, we first delete files and then remove directories in reverse order.
     // For additions, we first create directories and then add/update files.
     pub fn apply_migration(&self, migration: &Migration) -> Result<(), WorkspaceError> {
-        self.apply_migration_action_set(migration, ActionType::Delete)?;
+        self.apply_migration_action_set(migration, ActionType::Delete, &DirCache::default())?;
         migration
             .rmdirs()
             .iter()
@@ -221,13 +536,14 @@ impl Workspace {
             .map(|dir_path| self.remove_directory(dir_path))
             .collect::<Result<Vec<()>, _>>()?;
 
+        let mut dirs = DirCache::default();
         migration
             .mkdirs()
             .iter()
-            .map(|dir_path| self.make_directory(dir_path))
+            .map(|dir_path| self.make_directory(&mut dirs, dir_path))
             .collect::<Result<Vec<()>, _>>()?;
-        self.apply_migration_action_set(migration, ActionType::Modify)?;
-        self.apply_migration_action_set(migration, ActionType::Add)?;
+        self.apply_migration_action_set(migration, ActionType::Modify, &dirs)?;
+        self.apply_migration_action_set(migration, ActionType::Add, &dirs)?;
 
         Ok(())
     }
@@ -236,6 +552,7 @@ impl Workspace {
         &self,
         migration: &Migration,
         action: ActionType,
+        dirs: &DirCache,
     ) -> Result<(), WorkspaceError> {
         migration
             .actions()
@@ -244,14 +561,23 @@ impl Workspace {
             .iter()
             .map(|(file_path, entry)| {
                 let path = self.path.join(file_path);
-
-                if path.exists() {
-                    let metadata =
-                        std::fs::metadata(&path).map_err(|e| WorkspaceError::FileOperation {
+                // Nothing can stand in the way inside a directory created just
+                // now, unless `mkdirs` made a directory at the file's own path,
+                // which still has to be replaced
+                let in_new_dir = file_path
+                    .parent()
+                    .is_some_and(|parent| dirs.created.contains(parent))
+                    && !dirs.created.contains(file_path);
+
+                // A dangling symlink is still in the way
+                if !in_new_dir && (path.exists() || path.is_symlink()) {
+                    let metadata = std::fs::symlink_metadata(&path).map_err(|e| {
+                        WorkspaceError::FileOperation {
                             operation: "stat",
                             path: file_path.display().to_string(),
                             source: e,
-                        })?;
+                        }
+                    })?;
 
                     if metadata.is_dir() {
                         std::fs::remove_dir_all(&path).map_err(|e| {
@@ -263,7 +589,7 @@ impl Workspace {
                         })?;
                     }
 
-                    if metadata.is_file() {
+                    if metadata.is_file() || metadata.is_symlink() {
                         std::fs::remove_file(&path).map_err(|e| WorkspaceError::FileOperation {
                             operation: "remove file",
                             path: file_path.display().to_string(),
@@ -277,6 +603,16 @@ impl Workspace {
                     (ActionType::Add | ActionType::Modify, Some(entry)) => {
                         let data = migration.load_blob_data(&entry.oid)?;
 
+                        if entry.mode.is_symlink() {
+                            return write_symlink(&path, data.as_bytes()).map_err(|e| {
+                                WorkspaceError::FileOperation {
+                                    operation: "symlink",
+                                    path: file_path.display().to_string(),
+                                    source: e,
+                                }
+                            });
+                        }
+
                         let mut file = std::fs::OpenOptions::new()
                             .write(true)
                             .create(true)
@@ -288,13 +624,13 @@ impl Workspace {
                                 source: e,
                             })?;
 
-                        file.write_all(data.as_bytes()).map_err(|e| {
-                            WorkspaceError::FileOperation {
+                        file.write_all(data.as_bytes())
+                            .and_then(|_| if self.fsync { file.sync_all() } else { Ok(()) })
+                            .map_err(|e| WorkspaceError::FileOperation {
                                 operation: "write",
                                 path: file_path.display().to_string(),
                                 source: e,
-                            }
-                        })?;
+                            })?;
 
                         #[cfg(unix)]
                         {
@@ -326,11 +662,21 @@ impl Workspace {
         Ok(())
     }
 
-    fn make_directory(&self, dir_path: &Path) -> Result<(), WorkspaceError> {
-        let dir_path = self.path.join(dir_path);
-
-        if !dir_path.exists() {
+    /// Create a directory planned by a migration, replacing a file in its way
+    ///
+    /// `mkdirs` lists parents before their children, so a directory whose
+    /// parent was just created cannot exist yet and needs no stat.
+    fn make_directory(&self, dirs: &mut DirCache, relative: &Path) -> Result<(), WorkspaceError> {
+        let dir_path = self.path.join(relative);
+        let parent_created = relative
+            .parent()
+            .is_some_and(|parent| dirs.created.contains(parent));
+        dirs.insert(relative);
+
+        if parent_created || !dir_path.exists() {
             std::fs::create_dir(&dir_path)?;
+            dirs.creations += 1;
+            dirs.created.insert(relative.to_path_buf());
             return Ok(());
         }
 
@@ -342,8 +688,218 @@ impl Workspace {
 
         if !metadata.is_dir() {
             std::fs::create_dir(dir_path)?;
+            dirs.creations += 1;
+            dirs.created.insert(relative.to_path_buf());
         }
 
         Ok(())
     }
 }
+
+/// Create a symlink pointing to `target`, the content of its blob
+///
+/// Without symlink support, the target is written as a plain file instead,
+/// as Git does with `core.symlinks=false`.
+fn write_symlink(full_path: &Path, target: &[u8]) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        std::os::unix::fs::symlink(std::ffi::OsStr::from_bytes(target), full_path)
+    }
+
+    #[cfg(not(unix))]
+    {
+        std::fs::write(full_path, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::index::entry_mode::FileMode;
+    use assert_fs::TempDir;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+    use std::os::unix::fs::MetadataExt;
+
+    fn write_files(workspace: &Workspace, dirs: &mut DirCache, paths: &[&str]) {
+        for path in paths {
+            workspace
+                .write_entry_file_cached(
+                    dirs,
+                    Path::new(path),
+                    b"content",
+                    &EntryMode::File(FileMode::Regular),
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_shared_parents_are_created_once() {
+        let dir = TempDir::new().unwrap();
+        let workspace = Workspace::new(dir.path().into());
+        let mut dirs = DirCache::default();
+
+        let paths = (0..50)
+            .map(|i| format!("a/b/file{}.txt", i))
+            .collect::<Vec<_>>();
+        write_files(
+            &workspace,
+            &mut dirs,
+            &paths.iter().map(String::as_str).collect::<Vec<_>>(),
+        );
+        assert_eq!(dirs.creations(), 1);
+
+        // Known ancestors are not created again, new siblings are
+        write_files(
+            &workspace,
+            &mut dirs,
+            &["a/top.txt", "a/c/file.txt", "root.txt"],
+        );
+        assert_eq!(dirs.creations(), 2);
+        assert!(dir.path().join("a/c/file.txt").is_file());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a/b/file49.txt")).unwrap(),
+            "content"
+        );
+    }
+
+    #[test]
+    fn test_uncached_writes_create_parents_every_time() {
+        let dir = TempDir::new().unwrap();
+        let workspace = Workspace::new(dir.path().into()).with_fsync(true);
+
+        for i in 0..3 {
+            let mut dirs = DirCache::default();
+            write_files(&workspace, &mut dirs, &[&format!("a/b/file{}.txt", i)]);
+            assert_eq!(dirs.creations(), 1);
+        }
+        assert_eq!(
+            std::fs::read_dir(dir.path().join("a/b")).unwrap().count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_walk_skips_git_and_ignored_paths_with_one_stat_per_file() {
+        let dir = TempDir::new().unwrap();
+        let workspace = Workspace::new(dir.path().into());
+        for (path, content) in [
+            ("top.txt", "top"),
+            ("a/b/nested.txt", "nested content"),
+            (".git/config", "[core]"),
+            ("debug.log", "log"),
+            ("build/out.txt", "out"),
+            (".gitignore", "*.log\nbuild/\n"),
+        ] {
+            workspace
+                .write_entry_file(
+                    Path::new(path),
+                    content.as_bytes(),
+                    &EntryMode::File(FileMode::Regular),
+                )
+                .unwrap();
+        }
+        let ignores = IgnoreFiles::load(dir.path()).unwrap();
+
+        let entries = workspace
+            .walk(None, Some(&ignores))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut ignored = entries
+            .iter()
+            .filter_map(|entry| match entry {
+                WalkEntry::Ignored { path, is_dir } => Some((path.clone(), *is_dir)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        ignored.sort();
+        let walked = entries
+            .into_iter()
+            .filter_map(WalkEntry::into_file)
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(
+            walked.keys().collect::<Vec<_>>(),
+            vec![
+                Path::new(".gitignore"),
+                Path::new("a/b/nested.txt"),
+                Path::new("top.txt")
+            ]
+        );
+        assert_eq!(
+            ignored,
+            vec![
+                (PathBuf::from("build"), true),
+                (PathBuf::from("debug.log"), false)
+            ]
+        );
+        let stat = &walked[Path::new("a/b/nested.txt")];
+        let metadata = std::fs::metadata(dir.path().join("a/b/nested.txt")).unwrap();
+        assert_eq!(stat.size, 14);
+        assert_eq!(stat.mode, EntryMode::File(FileMode::Regular));
+        assert_eq!((stat.ino, stat.dev), (metadata.ino(), metadata.dev()));
+        assert_eq!(
+            (stat.mtime, stat.mtime_nsec),
+            (metadata.mtime(), metadata.mtime_nsec())
+        );
+
+        // Without ignore rules, only `.git` is left out
+        let mut unfiltered = workspace
+            .walk(None, None)
+            .unwrap()
+            .filter_map(|entry| entry.unwrap().into_file())
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+        unfiltered.sort();
+        assert_eq!(
+            unfiltered,
+            [
+                ".gitignore",
+                "a/b/nested.txt",
+                "build/out.txt",
+                "debug.log",
+                "top.txt"
+            ]
+            .map(PathBuf::from)
+            .to_vec()
+        );
+    }
+
+    #[test]
+    fn test_walk_pruning_yields_but_does_not_enter_pruned_directories() {
+        let dir = TempDir::new().unwrap();
+        let workspace = Workspace::new(dir.path().into());
+        let mut dirs = DirCache::default();
+        write_files(
+            &workspace,
+            &mut dirs,
+            &[
+                "top.txt",
+                "kept/file.txt",
+                "pruned/file.txt",
+                "pruned/deep/file.txt",
+            ],
+        );
+
+        let mut walked = workspace
+            .walk_pruning(None, None, |path| path == Path::new("pruned"))
+            .unwrap()
+            .map(|entry| match entry.unwrap() {
+                WalkEntry::File(path, _) => path,
+                WalkEntry::Dir(path) => path.join(""),
+                WalkEntry::Ignored { path, .. } => path,
+            })
+            .collect::<Vec<_>>();
+        walked.sort();
+
+        assert_eq!(
+            walked,
+            ["kept/", "kept/file.txt", "pruned/", "top.txt"]
+                .map(PathBuf::from)
+                .to_vec()
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Archive writers for `bit archive`
+//!
+//! Serializes the files of a tree snapshot into a single stream:
+//!
+//! - `tar`: POSIX ustar archives, with pax headers for long paths
+//! - `zip`: Deflate-compressed zip archives
+//!
+//! Both writers only ever append to the output, so an archive can be
+//! streamed to stdout as the tree is walked.
+
+pub mod tar;
+pub mod zip;
+
+use clap::ValueEnum;
+use std::io::Write;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("failed to write archive: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("'{path}' is too large for a {format} archive")]
+    EntryTooLarge { path: String, format: &'static str },
+    #[error("too many entries for a {format} archive")]
+    TooManyEntries { format: &'static str },
+}
+
+/// Output format of an archive
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    #[value(name = "tar", help = "Uncompressed POSIX tar archive")]
+    #[default]
+    Tar,
+    #[value(name = "zip", help = "Deflate-compressed zip archive")]
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Guess the format from an output file name (`.tar` or `.zip`)
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let (_, extension) = name.rsplit_once('.')?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "tar" => Some(Self::Tar),
+            "zip" => Some(Self::Zip),
+            _ => None,
+        }
+    }
+
+    /// Create a writer producing this format into `output`
+    pub fn writer<'w>(&self, output: &'w mut dyn Write) -> Box<dyn ArchiveWriter + 'w> {
+        match self {
+            Self::Tar => Box::new(tar::TarWriter::new(output)),
+            Self::Zip => Box::new(zip::ZipWriter::new(output)),
+        }
+    }
+}
+
+/// What an archive entry is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveEntryKind {
+    File,
+    Executable,
+    /// A symbolic link, whose content is the link target
+    Symlink,
+}
+
+/// A file or symbolic link to store in an archive
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry<'c> {
+    /// Slash-separated path inside the archive, prefix included
+    pub path: String,
+    pub kind: ArchiveEntryKind,
+    pub content: &'c [u8],
+    pub mtime: chrono::DateTime<chrono::FixedOffset>,
+}
+
+impl ArchiveEntry<'_> {
+    /// Unix permission bits stored for the entry
+    pub fn mode(&self) -> u32 {
+        match self.kind {
+            ArchiveEntryKind::File => 0o644,
+            ArchiveEntryKind::Executable => 0o755,
+            ArchiveEntryKind::Symlink => 0o777,
+        }
+    }
+}
+
+pub trait ArchiveWriter {
+    /// Append a file or symbolic link to the archive
+    fn add_entry(&mut self, entry: &ArchiveEntry) -> Result<(), ArchiveError>;
+
+    /// Write the archive trailer; no entries may be added afterwards
+    fn finish(&mut self) -> Result<(), ArchiveError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_format_is_inferred_from_extension() {
+        assert_eq!(
+            ArchiveFormat::from_file_name("out.tar"),
+            Some(ArchiveFormat::Tar)
+        );
+        assert_eq!(
+            ArchiveFormat::from_file_name("release-1.0.ZIP"),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(ArchiveFormat::from_file_name("out.tar.gz"), None);
+        assert_eq!(ArchiveFormat::from_file_name("archive"), None);
+    }
+}
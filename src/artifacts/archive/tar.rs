@@ -0,0 +1,251 @@
+//! POSIX ustar writer
+//!
+//! Every file is a 512-byte header followed by its content padded to a
+//! multiple of 512 bytes, and the archive ends with two zeroed blocks.
+//! Symbolic links have no content: their target goes in the link name field.
+//! Paths and link targets that do not fit their 100-byte fields are preceded
+//! by a pax extended header (`x` entry) carrying them in full.
+
+use crate::artifacts::archive::{ArchiveEntry, ArchiveEntryKind, ArchiveError, ArchiveWriter};
+use std::io::Write;
+
+const BLOCK_SIZE: usize = 512;
+
+const NAME_SIZE: usize = 100;
+
+/// Largest size representable by the 11 octal digits of the size field
+const MAX_ENTRY_SIZE: u64 = 0o77777777777;
+
+const REGULAR_FILE: u8 = b'0';
+
+const SYMLINK: u8 = b'2';
+
+const PAX_HEADER: u8 = b'x';
+
+pub struct TarWriter<'w> {
+    output: &'w mut dyn Write,
+}
+
+impl<'w> TarWriter<'w> {
+    pub fn new(output: &'w mut dyn Write) -> Self {
+        Self { output }
+    }
+
+    /// Write `content` followed by NUL padding up to the next block boundary
+    fn write_padded(&mut self, content: &[u8]) -> Result<(), ArchiveError> {
+        self.output.write_all(content)?;
+
+        let padding = (BLOCK_SIZE - content.len() % BLOCK_SIZE) % BLOCK_SIZE;
+        self.output.write_all(&[0; BLOCK_SIZE][..padding])?;
+
+        Ok(())
+    }
+}
+
+impl ArchiveWriter for TarWriter<'_> {
+    fn add_entry(&mut self, entry: &ArchiveEntry) -> Result<(), ArchiveError> {
+        if entry.content.len() as u64 > MAX_ENTRY_SIZE {
+            return Err(ArchiveError::EntryTooLarge {
+                path: entry.path.clone(),
+                format: "tar",
+            });
+        }
+        let mtime = entry.mtime.timestamp().max(0) as u64;
+        let (kind, link_name, content) = match entry.kind {
+            ArchiveEntryKind::Symlink => (SYMLINK, entry.content, &[][..]),
+            _ => (REGULAR_FILE, &[][..], entry.content),
+        };
+
+        let mut records = Vec::new();
+        if entry.path.len() > NAME_SIZE {
+            records.extend(pax_record("path", entry.path.as_bytes()));
+        }
+        if link_name.len() > NAME_SIZE {
+            records.extend(pax_record("linkpath", link_name));
+        }
+        if !records.is_empty() {
+            let header = header(
+                b"PaxHeader",
+                0o644,
+                records.len() as u64,
+                mtime,
+                PAX_HEADER,
+                b"",
+            );
+            self.output.write_all(&header)?;
+            self.write_padded(&records)?;
+        }
+
+        let header = header(
+            entry.path.as_bytes(),
+            entry.mode(),
+            content.len() as u64,
+            mtime,
+            kind,
+            link_name,
+        );
+        self.output.write_all(&header)?;
+        self.write_padded(content)
+    }
+
+    fn finish(&mut self) -> Result<(), ArchiveError> {
+        self.output.write_all(&[0; 2 * BLOCK_SIZE])?;
+        self.output.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Build a ustar header block
+///
+/// Names and link names longer than their fields are truncated; the
+/// preceding pax header holds them in full.
+fn header(
+    name: &[u8],
+    mode: u32,
+    size: u64,
+    mtime: u64,
+    kind: u8,
+    link_name: &[u8],
+) -> [u8; BLOCK_SIZE] {
+    let mut header = [0; BLOCK_SIZE];
+
+    let name = &name[..name.len().min(NAME_SIZE)];
+    header[..name.len()].copy_from_slice(name);
+    let link_name = &link_name[..link_name.len().min(NAME_SIZE)];
+    header[157..157 + link_name.len()].copy_from_slice(link_name);
+    write_octal(&mut header[100..108], mode as u64);
+    write_octal(&mut header[108..116], 0); // uid
+    write_octal(&mut header[116..124], 0); // gid
+    write_octal(&mut header[124..136], size);
+    write_octal(&mut header[136..148], mtime);
+    header[156] = kind;
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    // The checksum is computed with its own field filled with spaces
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|&byte| byte as u32).sum();
+    header[148..155].copy_from_slice(format!("{:06o}\0", checksum).as_bytes());
+
+    header
+}
+
+/// Fill a numeric field with zero-padded octal digits and a trailing NUL
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = format!("{:0width$o}\0", value, width = field.len() - 1);
+    field.copy_from_slice(digits.as_bytes());
+}
+
+/// Format a pax record, `<length> <key>=<value>\n`
+///
+/// The length counts the whole record, its own digits included.
+fn pax_record(key: &str, value: &[u8]) -> Vec<u8> {
+    let mut body = format!(" {}=", key).into_bytes();
+    body.extend_from_slice(value);
+    body.push(b'\n');
+    let mut length = body.len();
+    while length != body.len() + length.to_string().len() {
+        length = body.len() + length.to_string().len();
+    }
+
+    let mut record = length.to_string().into_bytes();
+    record.extend(body);
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entry<'c>(path: &str, content: &'c [u8], kind: ArchiveEntryKind) -> ArchiveEntry<'c> {
+        ArchiveEntry {
+            path: path.to_string(),
+            kind,
+            content,
+            mtime: chrono::DateTime::from_timestamp(1_700_000_000, 0)
+                .unwrap()
+                .fixed_offset(),
+        }
+    }
+
+    fn archive(entries: &[ArchiveEntry]) -> Vec<u8> {
+        let mut output = Vec::new();
+        let mut writer = TarWriter::new(&mut output);
+        for entry in entries {
+            writer.add_entry(entry).unwrap();
+        }
+        writer.finish().unwrap();
+
+        output
+    }
+
+    #[test]
+    fn test_entry_is_header_and_padded_content() {
+        let bytes = archive(&[entry(
+            "bin/run.sh",
+            b"echo hi\n",
+            ArchiveEntryKind::Executable,
+        )]);
+
+        assert_eq!(bytes.len(), 4 * BLOCK_SIZE);
+        assert_eq!(&bytes[..10], b"bin/run.sh");
+        assert_eq!(&bytes[100..108], b"0000755\0");
+        assert_eq!(&bytes[124..136], b"00000000010\0");
+        assert_eq!(&bytes[257..263], b"ustar\0");
+        assert_eq!(&bytes[BLOCK_SIZE..BLOCK_SIZE + 8], b"echo hi\n");
+        assert!(bytes[2 * BLOCK_SIZE..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_header_checksum_covers_the_block() {
+        let bytes = archive(&[entry("a.txt", b"a", ArchiveEntryKind::File)]);
+        let mut block = bytes[..BLOCK_SIZE].to_vec();
+        let stored = std::str::from_utf8(&block[148..154]).unwrap().to_string();
+        block[148..156].fill(b' ');
+
+        let expected: u32 = block.iter().map(|&byte| byte as u32).sum();
+        assert_eq!(u32::from_str_radix(&stored, 8).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_long_paths_get_a_pax_header() {
+        let path = format!("{}/file.txt", "d".repeat(120));
+        let bytes = archive(&[entry(&path, b"", ArchiveEntryKind::File)]);
+
+        let record = pax_record("path", path.as_bytes());
+        assert_eq!(bytes[156], PAX_HEADER);
+        assert_eq!(&bytes[BLOCK_SIZE..BLOCK_SIZE + record.len()], &record[..]);
+        assert_eq!(bytes[2 * BLOCK_SIZE + 156], REGULAR_FILE);
+    }
+
+    #[test]
+    fn test_symlink_stores_its_target_as_link_name() {
+        let bytes = archive(&[entry("link", b"target.txt", ArchiveEntryKind::Symlink)]);
+
+        assert_eq!(bytes.len(), 3 * BLOCK_SIZE);
+        assert_eq!(bytes[156], SYMLINK);
+        assert_eq!(&bytes[100..108], b"0000777\0");
+        assert_eq!(&bytes[124..136], b"00000000000\0");
+        assert_eq!(&bytes[157..168], b"target.txt\0");
+    }
+
+    #[test]
+    fn test_long_symlink_targets_get_a_pax_header() {
+        let target = "t".repeat(150);
+        let bytes = archive(&[entry("link", target.as_bytes(), ArchiveEntryKind::Symlink)]);
+
+        let record = pax_record("linkpath", target.as_bytes());
+        assert_eq!(bytes[156], PAX_HEADER);
+        assert_eq!(&bytes[BLOCK_SIZE..BLOCK_SIZE + record.len()], &record[..]);
+        assert_eq!(bytes[2 * BLOCK_SIZE + 156], SYMLINK);
+    }
+
+    #[test]
+    fn test_pax_record_length_includes_its_digits() {
+        assert_eq!(pax_record("path", b"a"), b"9 path=a\n".to_vec());
+        // 98 bytes after the length, which then needs a third digit
+        assert_eq!(pax_record("path", "a".repeat(91).as_bytes()).len(), 101);
+    }
+}
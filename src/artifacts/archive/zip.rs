@@ -0,0 +1,282 @@
+//! Zip writer
+//!
+//! Each file is written as a local header followed by its deflated content.
+//! `finish` then appends the central directory, which repeats every header
+//! along with its offset, and the end-of-central-directory record. Sizes and
+//! CRCs are known before a file is written, so no data descriptors are used.
+//! Zip64 is not supported: archives are limited to 4 GiB and 65535 files.
+
+use crate::artifacts::archive::{ArchiveEntry, ArchiveEntryKind, ArchiveError, ArchiveWriter};
+use byteorder::{LittleEndian, WriteBytesExt};
+use chrono::{Datelike, Timelike};
+use std::io::Write;
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x02014b50;
+
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+
+/// Version 2.0, the first with deflate and directories
+const VERSION_NEEDED: u16 = 20;
+
+/// Created on Unix (high byte 3), so external attributes hold the file mode
+const VERSION_MADE_BY: u16 = (3 << 8) | VERSION_NEEDED;
+
+/// General purpose flag marking file names as UTF-8
+const UTF8_NAMES: u16 = 0x0800;
+
+const DEFLATE: u16 = 8;
+
+/// Regular file type bits of a Unix mode
+const S_IFREG: u32 = 0o100000;
+
+/// Symbolic link type bits of a Unix mode; the entry content is the target
+const S_IFLNK: u32 = 0o120000;
+
+/// A file already written, to be listed in the central directory
+struct CentralEntry {
+    path: String,
+    mode: u32,
+    crc32: u32,
+    compressed_size: u32,
+    size: u32,
+    time: u16,
+    date: u16,
+    offset: u32,
+}
+
+pub struct ZipWriter<'w> {
+    output: &'w mut dyn Write,
+    /// Number of bytes written so far
+    offset: u64,
+    entries: Vec<CentralEntry>,
+}
+
+impl<'w> ZipWriter<'w> {
+    pub fn new(output: &'w mut dyn Write) -> Self {
+        Self {
+            output,
+            offset: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), ArchiveError> {
+        self.output.write_all(bytes)?;
+        self.offset += bytes.len() as u64;
+
+        Ok(())
+    }
+}
+
+impl ArchiveWriter for ZipWriter<'_> {
+    fn add_entry(&mut self, entry: &ArchiveEntry) -> Result<(), ArchiveError> {
+        let too_large = || ArchiveError::EntryTooLarge {
+            path: entry.path.clone(),
+            format: "zip",
+        };
+        if self.entries.len() >= u16::MAX as usize {
+            return Err(ArchiveError::TooManyEntries { format: "zip" });
+        }
+
+        let mut crc = flate2::Crc::new();
+        crc.update(entry.content);
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(entry.content)?;
+        let compressed = encoder.finish()?;
+
+        let (time, date) = dos_date_time(&entry.mtime);
+        let central = CentralEntry {
+            path: entry.path.clone(),
+            mode: match entry.kind {
+                ArchiveEntryKind::Symlink => S_IFLNK,
+                _ => S_IFREG,
+            } | entry.mode(),
+            crc32: crc.sum(),
+            compressed_size: u32::try_from(compressed.len()).map_err(|_| too_large())?,
+            size: u32::try_from(entry.content.len()).map_err(|_| too_large())?,
+            time,
+            date,
+            offset: u32::try_from(self.offset).map_err(|_| too_large())?,
+        };
+
+        let mut header = Vec::new();
+        header.write_u32::<LittleEndian>(LOCAL_HEADER_SIGNATURE)?;
+        header.write_u16::<LittleEndian>(VERSION_NEEDED)?;
+        write_shared_fields(&mut header, &central)?;
+        header.write_u16::<LittleEndian>(0)?; // extra field length
+        header.extend_from_slice(central.path.as_bytes());
+
+        self.write(&header)?;
+        self.write(&compressed)?;
+        self.entries.push(central);
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), ArchiveError> {
+        let too_large = || ArchiveError::EntryTooLarge {
+            path: String::from("central directory"),
+            format: "zip",
+        };
+
+        let directory_offset = u32::try_from(self.offset).map_err(|_| too_large())?;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            directory.write_u32::<LittleEndian>(CENTRAL_HEADER_SIGNATURE)?;
+            directory.write_u16::<LittleEndian>(VERSION_MADE_BY)?;
+            directory.write_u16::<LittleEndian>(VERSION_NEEDED)?;
+            write_shared_fields(&mut directory, entry)?;
+            directory.write_u16::<LittleEndian>(0)?; // extra field length
+            directory.write_u16::<LittleEndian>(0)?; // comment length
+            directory.write_u16::<LittleEndian>(0)?; // disk number
+            directory.write_u16::<LittleEndian>(0)?; // internal attributes
+            directory.write_u32::<LittleEndian>(entry.mode << 16)?;
+            directory.write_u32::<LittleEndian>(entry.offset)?;
+            directory.extend_from_slice(entry.path.as_bytes());
+        }
+        let directory_size = u32::try_from(directory.len()).map_err(|_| too_large())?;
+        self.write(&directory)?;
+
+        let count = self.entries.len() as u16;
+        let mut end = Vec::new();
+        end.write_u32::<LittleEndian>(END_OF_CENTRAL_DIRECTORY_SIGNATURE)?;
+        end.write_u16::<LittleEndian>(0)?; // this disk
+        end.write_u16::<LittleEndian>(0)?; // disk holding the directory
+        end.write_u16::<LittleEndian>(count)?;
+        end.write_u16::<LittleEndian>(count)?;
+        end.write_u32::<LittleEndian>(directory_size)?;
+        end.write_u32::<LittleEndian>(directory_offset)?;
+        end.write_u16::<LittleEndian>(0)?; // comment length
+        self.write(&end)?;
+        self.output.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Write the fields shared by local and central headers, from the flags up
+/// to the file name length
+fn write_shared_fields(header: &mut Vec<u8>, entry: &CentralEntry) -> std::io::Result<()> {
+    header.write_u16::<LittleEndian>(UTF8_NAMES)?;
+    header.write_u16::<LittleEndian>(DEFLATE)?;
+    header.write_u16::<LittleEndian>(entry.time)?;
+    header.write_u16::<LittleEndian>(entry.date)?;
+    header.write_u32::<LittleEndian>(entry.crc32)?;
+    header.write_u32::<LittleEndian>(entry.compressed_size)?;
+    header.write_u32::<LittleEndian>(entry.size)?;
+    header.write_u16::<LittleEndian>(entry.path.len() as u16)
+}
+
+/// Encode a timestamp as MS-DOS (time, date), in its own timezone
+///
+/// DOS dates start in 1980 and have a two-second resolution.
+fn dos_date_time(timestamp: &chrono::DateTime<chrono::FixedOffset>) -> (u16, u16) {
+    let local = timestamp.naive_local();
+    if local.year() < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+
+    let time = (local.hour() << 11) | (local.minute() << 5) | (local.second() / 2);
+    let date = (((local.year() - 1980) as u32).min(127) << 9) | (local.month() << 5) | local.day();
+
+    (time as u16, date as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byteorder::{ByteOrder, LittleEndian};
+    use pretty_assertions::assert_eq;
+    use std::io::Read;
+
+    fn entry<'c>(path: &str, content: &'c [u8], kind: ArchiveEntryKind) -> ArchiveEntry<'c> {
+        ArchiveEntry {
+            path: path.to_string(),
+            kind,
+            content,
+            mtime: chrono::DateTime::parse_from_rfc3339("2024-03-15T10:20:31+02:00").unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_archive_ends_with_central_directory() {
+        let mut output = Vec::new();
+        let mut writer = ZipWriter::new(&mut output);
+        writer
+            .add_entry(&entry(
+                "a.txt",
+                b"hello hello hello",
+                ArchiveEntryKind::File,
+            ))
+            .unwrap();
+        writer
+            .add_entry(&entry(
+                "bin/run",
+                b"#!/bin/sh",
+                ArchiveEntryKind::Executable,
+            ))
+            .unwrap();
+        writer
+            .add_entry(&entry("link", b"a.txt", ArchiveEntryKind::Symlink))
+            .unwrap();
+        writer.finish().unwrap();
+
+        let end = &output[output.len() - 22..];
+        assert_eq!(
+            LittleEndian::read_u32(&end[0..4]),
+            END_OF_CENTRAL_DIRECTORY_SIGNATURE
+        );
+        assert_eq!(LittleEndian::read_u16(&end[10..12]), 3);
+
+        let directory = LittleEndian::read_u32(&end[16..20]) as usize;
+        assert_eq!(
+            LittleEndian::read_u32(&output[directory..]),
+            CENTRAL_HEADER_SIGNATURE
+        );
+        // External attributes of the first file: a regular 0644 file
+        assert_eq!(
+            LittleEndian::read_u32(&output[directory + 38..]) >> 16,
+            0o100644
+        );
+        // ...and of the last one a symbolic link
+        let last = output[..output.len() - 22]
+            .windows(4)
+            .rposition(|window| LittleEndian::read_u32(window) == CENTRAL_HEADER_SIGNATURE)
+            .unwrap();
+        assert_eq!(LittleEndian::read_u32(&output[last + 38..]) >> 16, 0o120777);
+    }
+
+    #[test]
+    fn test_local_entry_holds_deflated_content() {
+        let content = b"hello hello hello";
+        let mut output = Vec::new();
+        let mut writer = ZipWriter::new(&mut output);
+        writer
+            .add_entry(&entry("a.txt", content, ArchiveEntryKind::File))
+            .unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(LittleEndian::read_u32(&output), LOCAL_HEADER_SIGNATURE);
+        let compressed_size = LittleEndian::read_u32(&output[18..22]) as usize;
+        let data = &output[30 + "a.txt".len()..][..compressed_size];
+
+        let mut inflated = Vec::new();
+        flate2::read::DeflateDecoder::new(data)
+            .read_to_end(&mut inflated)
+            .unwrap();
+        assert_eq!(inflated, content);
+    }
+
+    #[test]
+    fn test_dos_date_time_uses_the_timestamp_offset() {
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2024-03-15T10:20:31+02:00").unwrap();
+
+        let (time, date) = dos_date_time(&timestamp);
+
+        assert_eq!(time, (10 << 11) | (20 << 5) | 15);
+        assert_eq!(date, (44 << 9) | (3 << 5) | 15);
+    }
+}
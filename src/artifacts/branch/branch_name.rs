@@ -15,6 +15,18 @@ pub enum BranchNameError {
 }
 
 const REF_PREFIX: &str = "refs/heads/";
+const REMOTE_REF_PREFIX: &str = "refs/remotes/";
+const TAG_REF_PREFIX: &str = "refs/tags/";
+
+/// Kind of a ref, in the order `log --decorate` lists them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RefKind {
+    Head,
+    Branch,
+    Remote,
+    Tag,
+    Other,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, PartialOrd, Ord, new)]
 pub struct SymRefName(String);
@@ -36,11 +48,45 @@ impl SymRefName {
         self.to_branch_name().map(|b| b.as_ref().to_string())
     }
 
-    pub fn to_colored_name(&self, name: String) -> String {
-        let colored_name = if self.is_detached_head() {
-            name.bold().cyan()
+    pub fn kind(&self) -> RefKind {
+        if self.is_detached_head() {
+            RefKind::Head
+        } else if self.0.starts_with(REF_PREFIX) {
+            RefKind::Branch
+        } else if self.0.starts_with(REMOTE_REF_PREFIX) {
+            RefKind::Remote
+        } else if self.0.starts_with(TAG_REF_PREFIX) {
+            RefKind::Tag
         } else {
-            name.bold().green()
+            RefKind::Other
+        }
+    }
+
+    /// Name of the ref in `log --decorate` output
+    ///
+    /// Short names drop the `refs/heads/`, `refs/remotes/` or `refs/tags/`
+    /// prefix; tags are labelled `tag: ` in both forms.
+    pub fn to_decoration_name(&self, full: bool) -> String {
+        let name = match self.kind() {
+            _ if full => self.0.as_str(),
+            RefKind::Branch => self.0.trim_start_matches(REF_PREFIX),
+            RefKind::Remote => self.0.trim_start_matches(REMOTE_REF_PREFIX),
+            RefKind::Tag => self.0.trim_start_matches(TAG_REF_PREFIX),
+            RefKind::Head | RefKind::Other => self.0.as_str(),
+        };
+
+        match self.kind() {
+            RefKind::Tag => format!("tag: {name}"),
+            _ => name.to_string(),
+        }
+    }
+
+    pub fn to_colored_name(&self, name: String) -> String {
+        let colored_name = match self.kind() {
+            RefKind::Head => name.bold().cyan(),
+            RefKind::Remote => name.bold().red(),
+            RefKind::Tag => name.bold().yellow(),
+            RefKind::Branch | RefKind::Other => name.bold().green(),
         };
 
         format!("{colored_name}")
@@ -4,6 +4,7 @@
 //! - Branch name validation and parsing
 //! - Revision specification parsing (refs, OIDs, parent notation, etc.)
 //! - Symbolic reference resolution
+//! - Reflog entries
 //!
 //! ## Revision Syntax
 //!
@@ -13,9 +14,12 @@
 //! - First parent notation: `HEAD^`, `main^` (equivalent to `^1`)
 //! - Nth parent notation: `HEAD^2`, `main^3` (for merge commits)
 //! - Ancestor notation: `HEAD~3`, `main~5` (follows first parent)
+//! - Peeling: `v1.0^{commit}`, `HEAD^{tree}`, `v1.0^{}`
+//! - Reflog notation: `master@{1}`, `HEAD@{2}` (earlier values of a ref)
 //! - Object IDs: Full (40 chars) or abbreviated (4-40 chars)
 
 pub mod branch_name;
+pub mod reflog;
 pub mod revision;
 
 /// Regex pattern for invalid characters in branch names
@@ -28,6 +32,12 @@ pub const NTH_PARENT_REGEX: &str = r"^(.+)\^(\d+)$";
 /// Regex pattern for first parent notation (e.g., "HEAD^")
 pub const PARENT_REGEX: &str = r"^(.+)\^$";
 
+/// Regex pattern for peeling notation (e.g., "v1.0^{commit}", "v1.0^{}")
+pub const PEEL_REGEX: &str = r"^(.+)\^\{(\w*)\}$";
+
+/// Regex pattern for reflog notation (e.g., "master@{1}", or "@{1}" for the current branch)
+pub const REFLOG_REGEX: &str = r"^(.*)@\{(\d+)\}$";
+
 /// Regex pattern for ancestor notation (e.g., "HEAD~3")
 pub const ANCESTOR_REGEX: &str = r"^(.+)\~(\d+)$";
 
@@ -0,0 +1,98 @@
+//! Reflog entries
+//!
+//! Each line of `logs/<ref>` records one update of the ref:
+//!
+//! ```text
+//! <old oid> <new oid> <name> <<email>> <timestamp> <timezone>\t<message>
+//! ```
+//!
+//! The old OID is all zeros when the ref did not exist before the update.
+
+use crate::artifacts::objects::commit::{Author, AuthorParseError};
+use crate::artifacts::objects::object_id::{ObjectId, ObjectIdError};
+
+/// Old OID of an entry recording the creation of a ref
+pub const NULL_OID: &str = "0000000000000000000000000000000000000000";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReflogError {
+    #[error("missing new object id")]
+    MissingNewOid,
+    #[error(transparent)]
+    ObjectId(#[from] ObjectIdError),
+    #[error("malformed committer: {0}")]
+    InvalidCommitter(#[from] AuthorParseError),
+}
+
+/// One update of a ref
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflogEntry {
+    /// Previous value of the ref, `None` when the update created it
+    pub old_oid: Option<ObjectId>,
+    pub new_oid: ObjectId,
+    /// Who updated the ref, and when
+    pub committer: Author,
+    /// Why the ref was updated, e.g. `reset: moving to HEAD~1`
+    pub message: String,
+}
+
+impl TryFrom<&str> for ReflogEntry {
+    type Error = ReflogError;
+
+    fn try_from(line: &str) -> Result<Self, ReflogError> {
+        let (header, message) = line.split_once('\t').unwrap_or((line, ""));
+        let (old_oid, rest) = header.split_once(' ').ok_or(ReflogError::MissingNewOid)?;
+        let (new_oid, committer) = rest.split_once(' ').ok_or(ReflogError::MissingNewOid)?;
+
+        Ok(ReflogEntry {
+            old_oid: (old_oid != NULL_OID)
+                .then(|| ObjectId::try_parse(old_oid.to_string()))
+                .transpose()?,
+            new_oid: ObjectId::try_parse(new_oid.to_string())?,
+            committer: Author::try_from(committer)?,
+            message: message.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const OLD: &str = "1111111111111111111111111111111111111111";
+    const NEW: &str = "2222222222222222222222222222222222222222";
+
+    #[test]
+    fn test_parses_entry_with_message() {
+        let line =
+            format!("{OLD} {NEW} A U Thor <a@u.thor> 1700000000 +0100\treset: moving to HEAD~1");
+
+        let entry = ReflogEntry::try_from(line.as_str()).unwrap();
+
+        assert_eq!(entry.old_oid.unwrap().as_ref(), OLD);
+        assert_eq!(entry.new_oid.as_ref(), NEW);
+        assert_eq!(entry.committer.name(), "A U Thor");
+        assert_eq!(entry.committer.timestamp().timestamp(), 1_700_000_000);
+        assert_eq!(entry.message, "reset: moving to HEAD~1");
+    }
+
+    #[test]
+    fn test_null_old_oid_means_the_ref_was_created() {
+        let line = format!("{NULL_OID} {NEW} A U Thor <a@u.thor> 1700000000 +0000");
+
+        let entry = ReflogEntry::try_from(line.as_str()).unwrap();
+
+        assert_eq!(entry.old_oid, None);
+        assert_eq!(entry.message, "");
+    }
+
+    #[test]
+    fn test_rejects_truncated_entries() {
+        assert!(matches!(
+            ReflogEntry::try_from(OLD),
+            Err(ReflogError::MissingNewOid)
+        ));
+        assert!(ReflogEntry::try_from(format!("{OLD} {NEW} nobody").as_str()).is_err());
+    }
+}
@@ -1,21 +1,33 @@
+use crate::areas::database::DatabaseError;
 use crate::areas::repository::Repository;
 use crate::artifacts::branch::branch_name::BranchName;
-use crate::artifacts::branch::{ANCESTOR_REGEX, NTH_PARENT_REGEX, PARENT_REGEX, REF_ALIASES};
+use crate::artifacts::branch::{
+    ANCESTOR_REGEX, NTH_PARENT_REGEX, PARENT_REGEX, PEEL_REGEX, REF_ALIASES, REFLOG_REGEX,
+};
 use crate::artifacts::objects::OBJECT_ID_LENGTH;
 use crate::artifacts::objects::object_id::ObjectId;
 use crate::artifacts::objects::object_type::ObjectType;
 use anyhow::Context;
 
+/// Candidates listed when an abbreviated OID is ambiguous; the object
+/// database is not scanned past the first match beyond them
+const MAX_AMBIGUOUS_CANDIDATES: usize = 10;
+
 /// Represents a revision specification that can be used to identify commits.
 ///
 /// Supports multiple formats:
 /// - Branch/ref names: `main`, `feature/new-feature`, `HEAD`
+/// - Tag names: `v1.0` - annotated tags resolve to the commit they point to
 /// - Aliases: `@` (resolves to `HEAD`)
+/// - Pseudo-refs stored in the git directory: `ORIG_HEAD`, `MERGE_HEAD`
 /// - Full OIDs: 40-character hexadecimal strings (resolved as fallback if ref doesn't exist)
 /// - Abbreviated OIDs: 4-40 character hexadecimal strings (resolved as fallback if ref doesn't exist)
 /// - First parent notation: `<revision>^` (e.g., `main^`, `HEAD^`) - equivalent to `^1`
 /// - Nth parent notation: `<revision>^<n>` (e.g., `main^2`, `HEAD^3`) - for merge commits
 /// - Ancestor notation: `<revision>~<n>` (e.g., `main~3`, `HEAD~5`) - follows first parent
+/// - Peeling: `<revision>^{<type>}` (e.g., `v1.0^{commit}`, `HEAD^{tree}`) and `<revision>^{}`
+/// - Reflog notation: `<ref>@{<n>}` (e.g., `master@{1}`) - the value of a ref n updates ago;
+///   `@{<n>}` reads the reflog of the current branch
 ///
 /// # Parsing Strategy
 ///
@@ -31,6 +43,16 @@ use anyhow::Context;
 /// - `<rev>^3` - Third parent (for octopus merges)
 /// - `<rev>~<n>` - Equivalent to `<rev>^1^1...^1` (n times, follows first parent)
 ///
+/// # Peeling
+///
+/// - `<rev>^{}` - Dereference tags until a non-tag object
+/// - `<rev>^{commit}` - Dereference tags down to a commit
+/// - `<rev>^{tree}` - Dereference tags, then take the tree of a commit
+/// - `<rev>^{blob}`/`<rev>^{tag}` - Check that the (peeled) object has that type
+///
+/// Unlike the other forms, the revision being peeled may name any object,
+/// not only a commit.
+///
 /// # Examples
 ///
 /// ```ignore
@@ -62,36 +84,17 @@ pub enum Revision {
     Parent(Box<Revision>),
     /// The Nth parent of a revision (e.g., HEAD^2 for second parent in merge commit)
     NthParent(Box<Revision>, usize),
+    /// A revision peeled to an object type (e.g., v1.0^{commit}); `None` for `^{}`
+    Peel(Box<Revision>, Option<ObjectType>),
+    /// The value a ref had n updates ago, read from its reflog (e.g., master@{1});
+    /// `None` stands for the current branch (e.g., @{1})
+    Reflog(Option<BranchName>, usize),
 }
 
 impl Revision {
     pub fn resolve(&self, repository: &Repository) -> anyhow::Result<Option<ObjectId>> {
         match self {
-            Revision::Ref(branch_name) => {
-                let name_str = branch_name.as_ref();
-
-                // Try to resolve as a ref first
-                match repository.refs().read_ref(branch_name.clone()) {
-                    Ok(Some(oid)) => Ok(Some(oid)),
-                    Ok(None) => Ok(None),
-                    Err(_) => {
-                        // Ref doesn't exist - try OID if it looks like one
-                        if Self::looks_like_oid(name_str) {
-                            // Try to resolve as OID
-                            match Self::resolve_oid(name_str, repository) {
-                                Ok(oid) => Ok(Some(oid)),
-                                Err(oid_err) => {
-                                    // OID resolution also failed, return OID error (more informative)
-                                    Err(oid_err)
-                                }
-                            }
-                        } else {
-                            // Not an OID-like string, return branch not found error
-                            Err(anyhow::anyhow!("branch {} not found", name_str))
-                        }
-                    }
-                }
-            }
+            Revision::Ref(branch_name) => Self::resolve_ref(branch_name, repository, true),
             Revision::Parent(base_revision) => {
                 Self::resolve_commit_parent(base_revision.resolve(repository)?, repository, 1)
             }
@@ -106,6 +109,137 @@ impl Revision {
 
                 Ok(oid)
             }
+            Revision::Peel(base_revision, target) => {
+                let oid = match base_revision.as_ref() {
+                    Revision::Ref(branch_name) => {
+                        Self::resolve_ref(branch_name, repository, false)?
+                    }
+                    base_revision => base_revision.resolve(repository)?,
+                };
+
+                oid.map(|oid| Self::peel(oid, target.as_ref(), repository))
+                    .transpose()
+            }
+            Revision::Reflog(branch_name, n) => {
+                Self::resolve_reflog(branch_name, *n, repository).map(Some)
+            }
+        }
+    }
+
+    /// Resolve the revision to the object it names, whatever its type
+    ///
+    /// Unlike `resolve`, an object ID may name an object other than a commit,
+    /// so an annotated tag resolves to the tag object itself rather than to
+    /// the commit it points to.
+    pub fn resolve_object(&self, repository: &Repository) -> anyhow::Result<Option<ObjectId>> {
+        match self {
+            Revision::Ref(branch_name) => Self::resolve_ref(branch_name, repository, false),
+            revision => revision.resolve(repository),
+        }
+    }
+
+    /// Look up the value of a ref `n` updates ago in its reflog
+    ///
+    /// `@{0}` is the value written by the latest update. Without a ref, the
+    /// current branch is used, or HEAD when it is detached.
+    fn resolve_reflog(
+        branch_name: &Option<BranchName>,
+        n: usize,
+        repository: &Repository,
+    ) -> anyhow::Result<ObjectId> {
+        let sym_ref_name = match branch_name {
+            Some(branch_name) => repository
+                .refs()
+                .full_ref_name(branch_name.clone())
+                .map_err(|_| unknown_revision(branch_name.as_ref()))?,
+            None => repository.refs().current_ref(None)?,
+        };
+        let log_name = sym_ref_name
+            .to_short_name()
+            .unwrap_or_else(|_| sym_ref_name.as_ref_path().to_string());
+        let entries = repository.refs().read_reflog(&sym_ref_name)?;
+
+        entries
+            .iter()
+            .rev()
+            .nth(n)
+            .map(|entry| entry.new_oid.clone())
+            .ok_or_else(|| {
+                anyhow::anyhow!("log for '{}' only has {} entries", log_name, entries.len())
+            })
+    }
+
+    /// Resolve a ref name, falling back to an object ID when no such ref exists
+    ///
+    /// With `commits_only`, refs are peeled to the commit they point to (e.g.
+    /// through an annotated tag) and object IDs must name a commit.
+    fn resolve_ref(
+        branch_name: &BranchName,
+        repository: &Repository,
+        commits_only: bool,
+    ) -> anyhow::Result<Option<ObjectId>> {
+        let name_str = branch_name.as_ref();
+
+        // Try to resolve as a ref first
+        match repository.refs().read_ref(branch_name.clone()) {
+            Ok(Some(oid)) if commits_only => {
+                Self::peel(oid, Some(&ObjectType::Commit), repository).map(Some)
+            }
+            Ok(Some(oid)) => Ok(Some(oid)),
+            Ok(None) => Ok(None),
+            Err(_) => {
+                // Ref doesn't exist - try OID if it looks like one
+                if Self::looks_like_oid(name_str) {
+                    // OID resolution errors are more informative than an unknown revision
+                    Self::resolve_oid(name_str, repository, commits_only).map(Some)
+                } else {
+                    Err(unknown_revision(name_str))
+                }
+            }
+        }
+    }
+
+    /// Dereference `oid` until it is an object of type `target`
+    ///
+    /// Tags are followed to their target, and commits to their tree when a
+    /// tree is wanted. Without a target, only tags are followed.
+    fn peel(
+        mut oid: ObjectId,
+        target: Option<&ObjectType>,
+        repository: &Repository,
+    ) -> anyhow::Result<ObjectId> {
+        loop {
+            let object_type = repository
+                .database()
+                .get_object_type(&oid)
+                .with_context(|| format!("object {} not found", oid))?;
+            if target == Some(&object_type) {
+                return Ok(oid);
+            }
+
+            match (object_type, target) {
+                (ObjectType::Tag, _) => {
+                    let tag = repository
+                        .database()
+                        .parse_object_as_tag(&oid)?
+                        .ok_or_else(|| anyhow::anyhow!("object {} is not a tag", oid))?;
+                    oid = tag.object().clone();
+                }
+                (ObjectType::Commit, Some(ObjectType::Tree)) => {
+                    let commit = repository
+                        .database()
+                        .parse_object_as_commit(&oid)?
+                        .ok_or_else(|| anyhow::anyhow!("object {} is not a commit", oid))?;
+                    oid = commit.tree_oid().clone();
+                }
+                (_, None) => return Ok(oid),
+                (object_type, Some(target)) => anyhow::bail!(
+                    "object {} is a {}, which cannot be peeled to a {}",
+                    oid.to_short_oid(),
+                    object_type,
+                    target
+                ),
+            }
         }
     }
 
@@ -142,12 +276,28 @@ impl Revision {
         }
     }
 
-    fn resolve_oid(oid_str: &str, repository: &Repository) -> anyhow::Result<ObjectId> {
+    fn resolve_oid(
+        oid_str: &str,
+        repository: &Repository,
+        commits_only: bool,
+    ) -> anyhow::Result<ObjectId> {
         // Check if it's a full OID (40 hex characters)
         if oid_str.len() == OBJECT_ID_LENGTH && oid_str.chars().all(|c| c.is_ascii_hexdigit()) {
             let oid = ObjectId::try_parse(oid_str.to_string())?;
-            // Validate that it's a commit
-            Self::validate_oid_is_commit(&oid, repository)?;
+            if commits_only {
+                Self::validate_oid_is_commit(&oid, repository)?;
+            } else {
+                // Any type will do, even an unknown one; a corrupt object
+                // keeps its own error
+                match repository.database().read_raw_object_header(&oid) {
+                    Err(DatabaseError::ReadObject { source, .. })
+                        if source.kind() == std::io::ErrorKind::NotFound =>
+                    {
+                        anyhow::bail!("object {} not found", oid)
+                    }
+                    result => result?,
+                };
+            }
             return Ok(oid);
         }
 
@@ -156,52 +306,55 @@ impl Revision {
             anyhow::bail!("invalid object id: {}", oid_str);
         }
 
-        let matches = repository.database().find_objects_by_prefix(oid_str)?;
-
-        match matches.len() {
-            0 => anyhow::bail!(
-                "ambiguous argument '{}': unknown revision or path not in the working tree",
-                oid_str
-            ),
-            1 => {
-                let oid = &matches[0];
-                // Validate that it's a commit
+        let matches = repository
+            .database()
+            .find_objects_by_prefix(oid_str, MAX_AMBIGUOUS_CANDIDATES)?;
+
+        if !matches.is_ambiguous() {
+            let oid = matches
+                .oids
+                .first()
+                .ok_or_else(|| unknown_revision(oid_str))?;
+            if commits_only {
                 Self::validate_oid_is_commit(oid, repository)?;
-                Ok(oid.clone())
-            }
-            _ => {
-                // Multiple matches - show hint (only show commits as valid candidates)
-                let commit_matches: Vec<_> = matches
-                    .iter()
-                    .filter(|oid| {
-                        repository
-                            .database()
-                            .get_object_type(oid)
-                            .map(|t| t == ObjectType::Commit)
-                            .unwrap_or(false)
-                    })
-                    .collect();
-
-                if commit_matches.is_empty() {
-                    anyhow::bail!(
-                        "ambiguous argument '{}': unknown revision or path not in the working tree",
-                        oid_str
-                    );
-                } else if commit_matches.len() == 1 {
-                    return Ok(commit_matches[0].clone());
-                }
+            }
+            return Ok(oid.clone());
+        }
 
-                // Multiple commit matches - show hint
-                let mut error_msg = format!(
-                    "short SHA1 {} is ambiguous\nhint: The candidates are:",
-                    oid_str
-                );
-                for oid in &commit_matches {
-                    error_msg.push_str(&format!("\nhint:   {} commit", oid.to_short_oid()));
+        let mut candidates = Vec::new();
+        for oid in &matches.oids {
+            match repository.database().get_object_type(oid) {
+                Ok(object_type) => candidates.push((oid, object_type)),
+                Err(_) if commits_only => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        if commits_only {
+            // Only commits are valid candidates
+            candidates.retain(|(_, object_type)| *object_type == ObjectType::Commit);
+
+            // With every match collected, a single commit among them is the answer
+            if !matches.truncated {
+                match candidates.as_slice() {
+                    [] => return Err(unknown_revision(oid_str)),
+                    [(oid, _)] => return Ok((*oid).clone()),
+                    _ => {}
                 }
-                anyhow::bail!(error_msg)
             }
         }
+
+        let mut error_msg = format!(
+            "short SHA1 {} is ambiguous\nhint: The candidates are:",
+            oid_str
+        );
+        for (oid, object_type) in &candidates {
+            error_msg.push_str(&format!("\nhint:   {} {}", oid.to_short_oid(), object_type));
+        }
+        if matches.truncated {
+            error_msg.push_str("\nhint:   ...");
+        }
+        anyhow::bail!(error_msg)
     }
 
     fn validate_oid_is_commit(oid: &ObjectId, repository: &Repository) -> anyhow::Result<()> {
@@ -222,8 +375,23 @@ impl Revision {
     }
 
     pub fn try_parse(revision: &str) -> anyhow::Result<Revision> {
+        // Check for ^{<type>} first: its braces are not part of any other form
+        if let Some(caps) = regex::Regex::new(PEEL_REGEX)
+            .with_context(|| format!("invalid peel regex: {PEEL_REGEX}"))?
+            .captures(revision)
+        {
+            let target = match &caps[2] {
+                "" => None,
+                object_type => Some(ObjectType::try_from(object_type).with_context(|| {
+                    format!("invalid object type to peel to in revision: {revision}")
+                })?),
+            };
+            let base_revision = Self::try_parse(&caps[1])?;
+
+            Ok(Revision::Peel(Box::new(base_revision), target))
+        }
         // Check for ^<n> pattern first (before checking for ^)
-        if regex::Regex::new(NTH_PARENT_REGEX)
+        else if regex::Regex::new(NTH_PARENT_REGEX)
             .with_context(|| format!("invalid nth parent regex: {NTH_PARENT_REGEX}"))?
             .is_match(revision)
         {
@@ -274,6 +442,22 @@ impl Revision {
             let base_revision = Self::try_parse(base_rev)?;
 
             Ok(Revision::Ancestor(Box::new(base_revision), generations))
+        } else if let Some(caps) = regex::Regex::new(REFLOG_REGEX)
+            .with_context(|| format!("invalid reflog regex: {REFLOG_REGEX}"))?
+            .captures(revision)
+        {
+            let branch_name = match &caps[1] {
+                "" => None,
+                name => {
+                    let resolved_name = REF_ALIASES.get(name).copied().unwrap_or(name);
+                    Some(BranchName::try_parse(resolved_name.to_string())?)
+                }
+            };
+            let n: usize = caps[2]
+                .parse()
+                .with_context(|| format!("failed to parse reflog index in revision: {revision}"))?;
+
+            Ok(Revision::Reflog(branch_name, n))
         } else {
             let resolved_name = *REF_ALIASES.get(revision).unwrap_or(&revision);
             let branch_name = BranchName::try_parse(resolved_name.to_string())?;
@@ -288,6 +472,31 @@ impl Revision {
     }
 }
 
+impl std::fmt::Display for Revision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Revision::Ref(branch_name) => write!(f, "{}", branch_name.as_ref()),
+            Revision::Ancestor(base, generations) => write!(f, "{}~{}", base, generations),
+            Revision::Parent(base) => write!(f, "{}^", base),
+            Revision::NthParent(base, n) => write!(f, "{}^{}", base, n),
+            Revision::Peel(base, Some(target)) => write!(f, "{}^{{{}}}", base, target),
+            Revision::Peel(base, None) => write!(f, "{}^{{}}", base),
+            Revision::Reflog(Some(branch_name), n) => write!(f, "{}@{{{}}}", branch_name, n),
+            Revision::Reflog(None, n) => write!(f, "@{{{}}}", n),
+        }
+    }
+}
+
+/// A revision that names no object
+#[derive(Debug, thiserror::Error)]
+#[error("ambiguous argument '{0}': unknown revision or path not in the working tree")]
+pub struct UnknownRevision(String);
+
+/// The error reported for a revision that names no object
+pub fn unknown_revision(spec: &str) -> anyhow::Error {
+    UnknownRevision(spec.to_string()).into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -660,6 +869,80 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_peel_forms() {
+        for (spec, target) in [
+            ("v1.0^{commit}", Some(ObjectType::Commit)),
+            ("v1.0^{tree}", Some(ObjectType::Tree)),
+            ("v1.0^{blob}", Some(ObjectType::Blob)),
+            ("v1.0^{tag}", Some(ObjectType::Tag)),
+            ("v1.0^{}", None),
+        ] {
+            let result = Revision::try_parse(spec).unwrap();
+            assert_eq!(
+                result,
+                Revision::Peel(
+                    Box::new(Revision::Ref(
+                        BranchName::try_parse("v1.0".to_string()).unwrap()
+                    )),
+                    target
+                )
+            );
+            assert_eq!(result.to_string(), spec);
+        }
+    }
+
+    #[test]
+    fn test_parse_peel_combined_with_parent_notation() {
+        let head = || {
+            Box::new(Revision::Ref(
+                BranchName::try_parse("HEAD".to_string()).unwrap(),
+            ))
+        };
+
+        // The ancestor is peeled, not the other way around
+        assert_eq!(
+            Revision::try_parse("HEAD~2^{tree}").unwrap(),
+            Revision::Peel(
+                Box::new(Revision::Ancestor(head(), 2)),
+                Some(ObjectType::Tree)
+            )
+        );
+        // The parent is taken of the peeled commit
+        assert_eq!(
+            Revision::try_parse("HEAD^{commit}^").unwrap(),
+            Revision::Parent(Box::new(Revision::Peel(head(), Some(ObjectType::Commit))))
+        );
+    }
+
+    #[test]
+    fn test_parse_peel_to_unknown_type_fails() {
+        assert!(Revision::try_parse("HEAD^{object}").is_err());
+        assert!(Revision::try_parse("HEAD^{tree").is_err());
+    }
+
+    #[test]
+    fn test_parse_reflog_notation() {
+        let master = BranchName::try_parse("master".to_string()).unwrap();
+
+        let result = Revision::try_parse("master@{2}").unwrap();
+        assert_eq!(result, Revision::Reflog(Some(master.clone()), 2));
+        assert_eq!(result.to_string(), "master@{2}");
+
+        assert_eq!(
+            Revision::try_parse("master@{1}~3").unwrap(),
+            Revision::Ancestor(Box::new(Revision::Reflog(Some(master), 1)), 3)
+        );
+        assert_eq!(
+            Revision::try_parse("HEAD@{0}").unwrap(),
+            Revision::Reflog(Some(BranchName::try_parse("HEAD".to_string()).unwrap()), 0)
+        );
+        let result = Revision::try_parse("@{1}").unwrap();
+        assert_eq!(result, Revision::Reflog(None, 1));
+        assert_eq!(result.to_string(), "@{1}");
+        assert!(Revision::try_parse("master@{one}").is_err());
+    }
+
     // Property tests
 
     // Strategy for valid branch names (simplified)
@@ -16,6 +16,11 @@
 //! - Untracked overwrites: Checkout would overwrite untracked file
 //! - Untracked removals: Checkout would remove untracked directory
 //!
+//! A forced migration skips the checks on tracked files, whose local changes
+//! are overwritten, but still refuses to clobber untracked files. A merging
+//! migration (`checkout -m`) skips them only for the paths whose local
+//! changes the caller merges back afterwards.
+//!
 //! ## Safety
 //!
 //! All operations are planned before execution, allowing conflicts to be
@@ -73,6 +78,12 @@ pub struct Migration<'r> {
     rmdirs: BTreeSet<PathBuf>,
     /// Whether this is a merge operation (less strict conflict detection)
     is_merge: bool,
+    /// Whether local changes to tracked files are overwritten instead of
+    /// reported (`checkout -f`)
+    force: bool,
+    /// Tracked paths whose local changes the caller carries over itself
+    /// (`checkout -m`), so they are not reported either
+    merged_paths: BTreeSet<PathBuf>,
 }
 
 impl<'r> Migration<'r> {
@@ -88,6 +99,33 @@ impl<'r> Migration<'r> {
         Self::new_with_mode(repository, index, tree_diff, true)
     }
 
+    /// Migration overwriting local changes to tracked files; untracked files
+    /// in the way still abort it
+    pub fn new_forced(
+        repository: &'r Repository,
+        index: &'r mut Index,
+        tree_diff: TreeDiff<'r>,
+    ) -> Self {
+        Self {
+            force: true,
+            ..Self::new_with_mode(repository, index, tree_diff, false)
+        }
+    }
+
+    /// Migration overwriting the local changes of `merged_paths`, which the
+    /// caller merges into the target versions once it has run
+    pub fn new_merging(
+        repository: &'r Repository,
+        index: &'r mut Index,
+        tree_diff: TreeDiff<'r>,
+        merged_paths: BTreeSet<PathBuf>,
+    ) -> Self {
+        Self {
+            merged_paths,
+            ..Self::new_with_mode(repository, index, tree_diff, false)
+        }
+    }
+
     fn new_with_mode(
         repository: &'r Repository,
         index: &'r mut Index,
@@ -119,6 +157,8 @@ impl<'r> Migration<'r> {
             mkdirs: BTreeSet::new(),
             rmdirs: BTreeSet::new(),
             is_merge,
+            force: false,
+            merged_paths: BTreeSet::new(),
         }
     }
 
@@ -126,6 +166,15 @@ impl<'r> Migration<'r> {
         &self.actions
     }
 
+    /// Paths the migration adds, modifies or deletes
+    pub fn changed_paths(&self) -> BTreeSet<PathBuf> {
+        self.actions
+            .values()
+            .flatten()
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
     pub fn mkdirs(&self) -> &BTreeSet<PathBuf> {
         &self.mkdirs
     }
@@ -191,6 +240,10 @@ impl<'r> Migration<'r> {
 
     fn check_for_conflict(&mut self, path: &Path, change: &TreeChangeType) -> anyhow::Result<()> {
         let entry = self.index.entry_by_path(path);
+        let tracked = entry.is_some() || self.index.is_conflicted_path(path);
+        if (self.force || self.merged_paths.contains(path)) && tracked {
+            return Ok(());
+        }
 
         let (old_entry, new_entry) = match change {
             TreeChangeType::Added(new_entry) => (None, Some(new_entry)),
@@ -234,7 +287,9 @@ impl<'r> Migration<'r> {
                 }
             }
             Some(_) => {
-                if self.inspector.check_index_against_workspace(entry, stat)?
+                if self
+                    .inspector
+                    .check_index_against_workspace(entry, stat, self.index)?
                     != WorkspaceChangeType::None
                 {
                     self.conflicts
@@ -0,0 +1,239 @@
+//! Path attributes from `.gitattributes`
+//!
+//! Each non-comment line holds a pattern followed by attributes:
+//!
+//! - `attr` sets the attribute, `-attr` unsets it, `attr=value` gives it a value
+//! - `!attr` drops any earlier setting, leaving the attribute unspecified
+//! - the `binary` macro stands for `-diff -text`
+//!
+//! Patterns follow the `.gitignore` rules for anchoring and wildcards, but
+//! cannot be negated. For a given attribute, the last matching line that
+//! mentions it wins.
+
+use crate::artifacts::core::ignore::glob_to_regex;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AttributeError {
+    #[error("failed to read attributes file {path}")]
+    ReadAttributesFile {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid attribute pattern '{pattern}'")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// State of an attribute for a path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    /// `attr`
+    Set,
+    /// `-attr`
+    Unset,
+    /// `attr=value`
+    Value(String),
+    /// `!attr`
+    Unspecified,
+}
+
+/// A single line from an attributes file
+#[derive(Debug, Clone)]
+struct AttributeRule {
+    /// Directory the pattern is relative to (empty for the workspace root)
+    base: PathBuf,
+    regex: Regex,
+    /// Whether the pattern is matched against the full relative path
+    anchored: bool,
+    attributes: Vec<(String, AttributeValue)>,
+}
+
+impl AttributeRule {
+    fn parse(base: &Path, line: &str) -> Result<Option<Self>, AttributeError> {
+        let mut fields = line.split_whitespace();
+        let Some(pattern) = fields.next().filter(|pattern| !pattern.starts_with('#')) else {
+            return Ok(None);
+        };
+
+        let mut attributes = Vec::new();
+        for field in fields {
+            if field == "binary" {
+                attributes.push(("diff".to_string(), AttributeValue::Unset));
+                attributes.push(("text".to_string(), AttributeValue::Unset));
+            } else if let Some(name) = field.strip_prefix('-') {
+                attributes.push((name.to_string(), AttributeValue::Unset));
+            } else if let Some(name) = field.strip_prefix('!') {
+                attributes.push((name.to_string(), AttributeValue::Unspecified));
+            } else if let Some((name, value)) = field.split_once('=') {
+                attributes.push((name.to_string(), AttributeValue::Value(value.to_string())));
+            } else {
+                attributes.push((field.to_string(), AttributeValue::Set));
+            }
+        }
+
+        let anchored = pattern.contains('/');
+        let regex = Regex::new(&format!(
+            "^{}$",
+            glob_to_regex(pattern.trim_start_matches('/'), false)
+        ))
+        .map_err(|source| AttributeError::InvalidPattern {
+            pattern: pattern.to_string(),
+            source,
+        })?;
+
+        Ok(Some(Self {
+            base: base.to_path_buf(),
+            regex,
+            anchored,
+            attributes,
+        }))
+    }
+
+    /// Whether the rule applies to `path`, which is relative to the workspace root
+    fn matches(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.base) else {
+            return false;
+        };
+
+        if self.anchored {
+            self.regex.is_match(&relative.to_string_lossy())
+        } else {
+            relative
+                .file_name()
+                .is_some_and(|name| self.regex.is_match(&name.to_string_lossy()))
+        }
+    }
+}
+
+/// Attribute lookup over the rules of one or more attributes files
+#[derive(Debug, Clone, Default)]
+pub struct AttributeMatcher {
+    rules: Vec<AttributeRule>,
+}
+
+impl AttributeMatcher {
+    /// Create a matcher without any rules
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Add the rules of an attributes file, if it exists
+    ///
+    /// Files added later take precedence over earlier ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - Directory the patterns are relative to, relative to the workspace root
+    /// * `file_path` - Path of the attributes file on disk
+    pub fn add_file(&mut self, base: &Path, file_path: &Path) -> Result<(), AttributeError> {
+        if !file_path.is_file() {
+            return Ok(());
+        }
+
+        let content = std::fs::read_to_string(file_path).map_err(|source| {
+            AttributeError::ReadAttributesFile {
+                path: file_path.display().to_string(),
+                source,
+            }
+        })?;
+        self.add_patterns(base, &content)
+    }
+
+    /// Add the rules found in the content of an attributes file
+    pub fn add_patterns(&mut self, base: &Path, content: &str) -> Result<(), AttributeError> {
+        for line in content.lines() {
+            if let Some(rule) = AttributeRule::parse(base, line)? {
+                self.rules.push(rule);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// State of `attribute` for a path relative to the workspace root
+    ///
+    /// Returns `None` when no rule mentions the attribute.
+    pub fn get(&self, path: &Path, attribute: &str) -> Option<&AttributeValue> {
+        self.rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.matches(path))
+            .find_map(|rule| {
+                rule.attributes
+                    .iter()
+                    .rev()
+                    .find(|(name, _)| name == attribute)
+                    .map(|(_, value)| value)
+            })
+            .filter(|value| **value != AttributeValue::Unspecified)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn matcher(content: &str) -> AttributeMatcher {
+        let mut matcher = AttributeMatcher::empty();
+        matcher.add_patterns(Path::new(""), content).unwrap();
+        matcher
+    }
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let matcher = matcher("*.bin diff=hex\n# comment\nfirmware/*.bin -diff\n");
+
+        assert_eq!(
+            matcher.get(Path::new("a/data.bin"), "diff"),
+            Some(&AttributeValue::Value("hex".to_string()))
+        );
+        assert_eq!(
+            matcher.get(Path::new("firmware/boot.bin"), "diff"),
+            Some(&AttributeValue::Unset)
+        );
+        assert_eq!(matcher.get(Path::new("notes.txt"), "diff"), None);
+    }
+
+    #[test]
+    fn test_binary_macro_unsets_diff_and_text() {
+        let matcher = matcher("*.png binary\n");
+
+        assert_eq!(
+            matcher.get(Path::new("logo.png"), "diff"),
+            Some(&AttributeValue::Unset)
+        );
+        assert_eq!(
+            matcher.get(Path::new("logo.png"), "text"),
+            Some(&AttributeValue::Unset)
+        );
+    }
+
+    #[test]
+    fn test_unspecified_attribute_clears_earlier_rules() {
+        let matcher = matcher("*.txt diff=words\nplain.txt !diff\n");
+
+        assert_eq!(matcher.get(Path::new("plain.txt"), "diff"), None);
+    }
+
+    #[test]
+    fn test_patterns_of_nested_files_are_relative_to_their_directory() {
+        let mut matcher = AttributeMatcher::empty();
+        matcher
+            .add_patterns(Path::new("docs"), "/*.md diff=markdown\n")
+            .unwrap();
+
+        assert_eq!(
+            matcher.get(Path::new("docs/intro.md"), "diff"),
+            Some(&AttributeValue::Value("markdown".to_string()))
+        );
+        assert_eq!(matcher.get(Path::new("docs/api/intro.md"), "diff"), None);
+        assert_eq!(matcher.get(Path::new("intro.md"), "diff"), None);
+    }
+}
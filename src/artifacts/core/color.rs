@@ -0,0 +1,171 @@
+//! Deciding whether output is colored
+//!
+//! In order of precedence:
+//! - `--color[=<when>]` or `--no-color`
+//! - the config of the command: `color.diff` (diff, show, log, reflog),
+//!   `color.status` or `color.branch`
+//! - `color.ui`
+//! - `auto`
+//!
+//! `auto` colors only output going to a terminal, directly or through the pager.
+
+use crate::areas::config::{Config, ConfigError};
+use clap::ValueEnum;
+
+/// Config key deciding colors for every command without a more specific one
+pub const COLOR_UI_KEY: &str = "color.ui";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ColorError {
+    #[error("bad color config value '{value}' for '{key}'")]
+    InvalidValue { key: String, value: String },
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+/// When to color the output
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum ColorWhen {
+    /// Always, even when the output is not a terminal
+    Always,
+    /// Never
+    Never,
+    /// Only when writing to a terminal or the pager
+    #[default]
+    Auto,
+}
+
+impl ColorWhen {
+    /// Parse a `color.*` config value
+    ///
+    /// Besides `always`, `never` and `auto`, booleans are accepted: true
+    /// means `auto`, as in Git.
+    pub fn from_config(key: &str, value: &str) -> Result<Self, ColorError> {
+        match value.to_lowercase().as_str() {
+            "always" => Ok(ColorWhen::Always),
+            "never" | "false" | "no" | "off" | "0" => Ok(ColorWhen::Never),
+            "auto" | "true" | "yes" | "on" | "1" | "" => Ok(ColorWhen::Auto),
+            _ => Err(ColorError::InvalidValue {
+                key: key.to_string(),
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+/// Configured colors for a command, `None` if nothing is configured
+///
+/// `key` is the command's own config key, e.g. `color.diff`, which takes
+/// precedence over `color.ui`. Each key is looked up in `configs` in order,
+/// typically the repository config before the global one.
+pub fn configured_color(
+    configs: &[&Config],
+    key: Option<&str>,
+) -> Result<Option<ColorWhen>, ColorError> {
+    for key in key.into_iter().chain([COLOR_UI_KEY]) {
+        for config in configs {
+            if let Some(value) = config.get(key)? {
+                return ColorWhen::from_config(key, &value).map(Some);
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether output written to a terminal (or not) should be colored
+///
+/// The command-line `flag` wins over the `configured` value; with neither,
+/// colors are automatic.
+pub fn should_colorize(
+    to_terminal: bool,
+    configured: Option<ColorWhen>,
+    flag: Option<ColorWhen>,
+) -> bool {
+    match flag.or(configured).unwrap_or_default() {
+        ColorWhen::Always => true,
+        ColorWhen::Never => false,
+        ColorWhen::Auto => to_terminal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    fn config(dir: &TempDir, name: &str, entries: &[(&str, &str)]) -> Config {
+        let config = Config::new(dir.path().join(name).into_boxed_path());
+        for (key, value) in entries {
+            config.set(key, value).unwrap();
+        }
+
+        config
+    }
+
+    #[rstest]
+    #[case(Some(ColorWhen::Never), Some(ColorWhen::Always), true, false)]
+    #[case(Some(ColorWhen::Always), None, false, true)]
+    #[case(None, Some(ColorWhen::Always), false, true)]
+    #[case(None, Some(ColorWhen::Auto), false, false)]
+    #[case(None, None, true, true)]
+    #[case(None, None, false, false)]
+    fn test_should_colorize_precedence(
+        #[case] flag: Option<ColorWhen>,
+        #[case] configured: Option<ColorWhen>,
+        #[case] to_terminal: bool,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(should_colorize(to_terminal, configured, flag), expected);
+    }
+
+    #[test]
+    fn test_command_key_overrides_color_ui() {
+        let dir = TempDir::new().unwrap();
+        let repository = config(
+            &dir,
+            "config",
+            &[("color.ui", "always"), ("color.diff", "never")],
+        );
+        let configs = [&repository];
+
+        assert_eq!(
+            configured_color(&configs, Some("color.diff")).unwrap(),
+            Some(ColorWhen::Never)
+        );
+        assert_eq!(
+            configured_color(&configs, Some("color.status")).unwrap(),
+            Some(ColorWhen::Always)
+        );
+        assert_eq!(
+            configured_color(&configs, None).unwrap(),
+            Some(ColorWhen::Always)
+        );
+    }
+
+    #[test]
+    fn test_repository_config_overrides_global() {
+        let dir = TempDir::new().unwrap();
+        let repository = config(&dir, "config", &[("color.ui", "false")]);
+        let global = config(&dir, "gitconfig", &[("color.ui", "always")]);
+
+        assert_eq!(
+            configured_color(&[&repository, &global], Some("color.branch")).unwrap(),
+            Some(ColorWhen::Never)
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_values() {
+        assert!(matches!(
+            ColorWhen::from_config("color.ui", "sometimes"),
+            Err(ColorError::InvalidValue { .. })
+        ));
+        assert_eq!(
+            ColorWhen::from_config("color.ui", "TRUE").unwrap(),
+            ColorWhen::Auto
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Asking the user for a message in their editor
+//!
+//! The editor is taken from `$GIT_EDITOR`, then `$EDITOR`, and is only
+//! started when stdin is a terminal, so scripts never hang waiting for it.
+
+use is_terminal::IsTerminal;
+use std::path::Path;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EditorError {
+    #[error("failed to prepare the message file {path}")]
+    WriteMessage {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to read the message file {path}")]
+    ReadMessage {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to start the editor '{editor}'")]
+    Spawn {
+        editor: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("there was a problem with the editor '{editor}'")]
+    Failed { editor: String },
+}
+
+/// Let the user edit `template` in `path` and return the result
+///
+/// Lines starting with `#` are dropped and surrounding blank lines trimmed.
+/// Returns `None` without touching `path` when no editor is configured or
+/// stdin is not a terminal.
+pub fn edit_message(path: &Path, template: &str) -> Result<Option<String>, EditorError> {
+    let editor = std::env::var("GIT_EDITOR")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.is_empty());
+    let Some(editor) = editor.filter(|_| std::io::stdin().is_terminal()) else {
+        return Ok(None);
+    };
+
+    std::fs::write(path, template).map_err(|source| EditorError::WriteMessage {
+        path: path.display().to_string(),
+        source,
+    })?;
+
+    // Run through the shell so editors configured with arguments work
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", editor))
+        .arg(&editor)
+        .arg(path)
+        .status()
+        .map_err(|source| EditorError::Spawn {
+            editor: editor.clone(),
+            source,
+        })?;
+    if !status.success() {
+        return Err(EditorError::Failed { editor });
+    }
+
+    let message = std::fs::read_to_string(path).map_err(|source| EditorError::ReadMessage {
+        path: path.display().to_string(),
+        source,
+    })?;
+
+    Ok(Some(strip_comments(&message)))
+}
+
+/// Drop `#` comment lines and trim the remaining text
+fn strip_comments(message: &str) -> String {
+    message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_strip_comments_keeps_message_lines() {
+        let message = "\nFirst line\n# a comment\n\nBody\n#\n";
+
+        assert_eq!(strip_comments(message), "First line\n\nBody");
+    }
+
+    #[test]
+    fn test_strip_comments_of_template_only_is_empty() {
+        assert_eq!(strip_comments("\n#\n# Write a message\n#\n"), "");
+    }
+}
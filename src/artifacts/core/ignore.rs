@@ -0,0 +1,403 @@
+//! Ignore rules for untracked files
+//!
+//! Implements the subset of `.gitignore` semantics needed to tell ignored
+//! files apart from plain untracked ones:
+//!
+//! - Blank lines and `#` comments are skipped
+//! - `!pattern` re-includes paths excluded by an earlier rule
+//! - `pattern/` only matches directories
+//! - Patterns containing a `/` are anchored to the directory of their
+//!   `.gitignore`, other patterns match the basename at any depth
+//! - `*`, `?`, `[...]` and `**` behave like in Git's wildmatch
+//!
+//! Later rules take precedence over earlier ones, and a path inside an
+//! ignored directory is always ignored. A leading UTF-8 byte order mark and
+//! `\r\n` line endings, as left by Windows editors, are accepted.
+
+use regex::Regex;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Ignore file read from every directory of the workspace
+pub const IGNORE_FILE: &str = ".gitignore";
+/// Repository-wide ignore file, relative to the workspace root
+pub const EXCLUDE_FILE: &str = ".git/info/exclude";
+
+const UTF8_BOM: char = '\u{feff}';
+
+#[derive(Debug, thiserror::Error)]
+pub enum IgnoreError {
+    #[error("failed to read ignore file {path}")]
+    ReadIgnoreFile {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid ignore pattern '{pattern}'")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// A single pattern from an ignore file
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    /// Directory the pattern is relative to (empty for the workspace root)
+    base: PathBuf,
+    /// Compiled pattern
+    regex: Regex,
+    /// Whether the pattern is matched against the full relative path
+    anchored: bool,
+    /// Whether the pattern re-includes matching paths (`!pattern`)
+    negated: bool,
+    /// Whether the pattern only matches directories (`pattern/`)
+    dir_only: bool,
+}
+
+impl IgnoreRule {
+    fn parse(base: &Path, line: &str) -> Result<Option<Self>, IgnoreError> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let (negated, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        if pattern.is_empty() {
+            return Ok(None);
+        }
+
+        let anchored = pattern.contains('/');
+        let pattern = pattern.trim_start_matches('/');
+        let regex =
+            Regex::new(&format!("^{}$", glob_to_regex(pattern, false))).map_err(|source| {
+                IgnoreError::InvalidPattern {
+                    pattern: line.to_string(),
+                    source,
+                }
+            })?;
+
+        Ok(Some(Self {
+            base: base.to_path_buf(),
+            regex,
+            anchored,
+            negated,
+            dir_only,
+        }))
+    }
+
+    /// Whether the rule applies to `path`, which is relative to the workspace root
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        let Ok(relative) = path.strip_prefix(&self.base) else {
+            return false;
+        };
+
+        if self.anchored {
+            self.regex.is_match(&relative.to_string_lossy())
+        } else {
+            relative
+                .file_name()
+                .is_some_and(|name| self.regex.is_match(&name.to_string_lossy()))
+        }
+    }
+}
+
+/// Translate a wildmatch pattern (`*`, `?`, `[...]`, `**`) into a regex
+///
+/// `*` and `?` stop at `/`, leaving `**` to span directories, unless
+/// `match_slashes` is set as for pathspecs.
+pub(crate) fn glob_to_regex(pattern: &str, match_slashes: bool) -> String {
+    let any_char = if match_slashes { "." } else { "[^/]" };
+    let chars = pattern.chars().collect::<Vec<_>>();
+    let mut regex = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '*' if !match_slashes && chars.get(i + 1) == Some(&'*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    // `**/` matches zero or more leading directories
+                    regex.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    regex.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => regex.push_str(&format!("{any_char}*")),
+            '?' => regex.push_str(any_char),
+            '[' => match chars[i + 1..].iter().position(|&c| c == ']') {
+                Some(len) => {
+                    let class = chars[i + 1..i + 1 + len].iter().collect::<String>();
+                    let class = match class.strip_prefix('!') {
+                        Some(rest) => format!("^{}", rest),
+                        None => class,
+                    };
+                    regex.push_str(&format!("[{}]", class.replace('\\', "\\\\")));
+                    i += len + 2;
+                    continue;
+                }
+                None => regex.push_str("\\["),
+            },
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+
+    regex
+}
+
+/// Matcher deciding whether untracked paths are ignored
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreMatcher {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreMatcher {
+    /// Create a matcher without any rules
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Add the rules of an ignore file, if it exists
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - Directory the patterns are relative to, relative to the workspace root
+    /// * `file_path` - Path of the ignore file on disk
+    pub fn add_file(&mut self, base: &Path, file_path: &Path) -> Result<(), IgnoreError> {
+        if !file_path.is_file() {
+            return Ok(());
+        }
+
+        let content =
+            std::fs::read_to_string(file_path).map_err(|source| IgnoreError::ReadIgnoreFile {
+                path: file_path.display().to_string(),
+                source,
+            })?;
+        self.add_patterns(base, &content)
+    }
+
+    /// Add the rules found in the content of an ignore file
+    pub fn add_patterns(&mut self, base: &Path, content: &str) -> Result<(), IgnoreError> {
+        let content = content.strip_prefix(UTF8_BOM).unwrap_or(content);
+        for line in content.lines() {
+            if let Some(rule) = IgnoreRule::parse(base, line)? {
+                self.rules.push(rule);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check whether a path, relative to the workspace root, is ignored
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        // Nothing inside an excluded directory can be re-included
+        let parent_ignored = path
+            .ancestors()
+            .skip(1)
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .any(|parent| self.match_rules(parent, true) == Some(true));
+
+        parent_ignored || self.match_rules(path, is_dir).unwrap_or(false)
+    }
+
+    fn match_rules(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path, is_dir))
+            .map(|rule| !rule.negated)
+    }
+}
+
+/// The ignore rules of a workspace, read as they are needed
+///
+/// Starts out with `.git/info/exclude` and reads the `.gitignore` of a
+/// directory the first time a path below it is checked, outer directories
+/// first so deeper files take precedence.
+#[derive(Debug)]
+pub struct IgnoreFiles {
+    /// Workspace root the ignore files are read from
+    root: PathBuf,
+    matcher: RefCell<IgnoreMatcher>,
+    /// Directories whose `.gitignore` has been read
+    loaded: RefCell<HashSet<PathBuf>>,
+}
+
+impl IgnoreFiles {
+    /// Read the exclude file of the workspace at `root`
+    pub fn load(root: &Path) -> Result<Self, IgnoreError> {
+        let mut matcher = IgnoreMatcher::empty();
+        matcher.add_file(Path::new(""), &root.join(EXCLUDE_FILE))?;
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            matcher: RefCell::new(matcher),
+            loaded: RefCell::default(),
+        })
+    }
+
+    /// Check whether a path, relative to the workspace root, is ignored
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> Result<bool, IgnoreError> {
+        let mut dirs = path.ancestors().skip(1).collect::<Vec<_>>();
+        dirs.reverse();
+        for dir in dirs {
+            if self.loaded.borrow_mut().insert(dir.to_path_buf()) {
+                self.matcher
+                    .borrow_mut()
+                    .add_file(dir, &self.root.join(dir).join(IGNORE_FILE))?;
+            }
+        }
+
+        Ok(self.matcher.borrow().is_ignored(path, is_dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(content: &str) -> IgnoreMatcher {
+        let mut matcher = IgnoreMatcher::empty();
+        matcher.add_patterns(Path::new(""), content).unwrap();
+        matcher
+    }
+
+    #[test]
+    fn test_basename_pattern_matches_at_any_depth() {
+        let matcher = matcher("*.log\n");
+
+        assert!(matcher.is_ignored(Path::new("debug.log"), false));
+        assert!(matcher.is_ignored(Path::new("a/b/debug.log"), false));
+        assert!(!matcher.is_ignored(Path::new("debug.txt"), false));
+    }
+
+    #[test]
+    fn test_anchored_pattern_matches_from_base() {
+        let matcher = matcher("/build\ndocs/*.html\n");
+
+        assert!(matcher.is_ignored(Path::new("build"), true));
+        assert!(!matcher.is_ignored(Path::new("src/build"), true));
+        assert!(matcher.is_ignored(Path::new("docs/index.html"), false));
+        assert!(!matcher.is_ignored(Path::new("docs/api/index.html"), false));
+    }
+
+    #[test]
+    fn test_directory_pattern_only_matches_directories() {
+        let matcher = matcher("target/\n");
+
+        assert!(matcher.is_ignored(Path::new("target"), true));
+        assert!(!matcher.is_ignored(Path::new("target"), false));
+        assert!(matcher.is_ignored(Path::new("target/debug/bit"), false));
+    }
+
+    #[test]
+    fn test_negated_pattern_reincludes_file() {
+        let matcher = matcher("*.log\n!keep.log\n");
+
+        assert!(matcher.is_ignored(Path::new("debug.log"), false));
+        assert!(!matcher.is_ignored(Path::new("keep.log"), false));
+    }
+
+    #[test]
+    fn test_negated_pattern_cannot_reinclude_inside_ignored_directory() {
+        let matcher = matcher("logs/\n!logs/keep.log\n");
+
+        assert!(matcher.is_ignored(Path::new("logs/keep.log"), false));
+    }
+
+    #[test]
+    fn test_double_star_matches_nested_directories() {
+        let matcher = matcher("**/cache\nsrc/**/*.tmp\n");
+
+        assert!(matcher.is_ignored(Path::new("cache"), true));
+        assert!(matcher.is_ignored(Path::new("a/b/cache"), true));
+        assert!(matcher.is_ignored(Path::new("src/x.tmp"), false));
+        assert!(matcher.is_ignored(Path::new("src/a/b/x.tmp"), false));
+    }
+
+    #[test]
+    fn test_comments_and_blank_lines_are_skipped() {
+        let matcher = matcher("# comment\n\n   \n");
+
+        assert!(!matcher.is_ignored(Path::new("# comment"), false));
+        assert!(matcher.rules.is_empty());
+    }
+
+    #[test]
+    fn test_file_with_bom_and_crlf_line_endings() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join(".gitignore");
+        std::fs::write(
+            &path,
+            "\u{feff}*.log\r\n# comment\r\nbuild/\r\n!keep.log\r\n",
+        )
+        .unwrap();
+
+        let mut matcher = IgnoreMatcher::empty();
+        matcher.add_file(Path::new(""), &path).unwrap();
+
+        assert_eq!(matcher.rules.len(), 3);
+        assert!(matcher.is_ignored(Path::new("debug.log"), false));
+        assert!(matcher.is_ignored(Path::new("build"), true));
+        assert!(!matcher.is_ignored(Path::new("keep.log"), false));
+    }
+
+    #[test]
+    fn test_rules_from_nested_file_only_apply_below_it() {
+        let mut matcher = IgnoreMatcher::empty();
+        matcher.add_patterns(Path::new("sub"), "*.o\n").unwrap();
+
+        assert!(matcher.is_ignored(Path::new("sub/main.o"), false));
+        assert!(!matcher.is_ignored(Path::new("main.o"), false));
+    }
+
+    #[test]
+    fn test_ignore_files_read_each_directory_below_the_exclude_file() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join(".git/info")).unwrap();
+        std::fs::create_dir_all(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join(EXCLUDE_FILE), "*.tmp\n").unwrap();
+        std::fs::write(dir.path().join(IGNORE_FILE), "*.log\n").unwrap();
+        std::fs::write(
+            dir.path().join("sub").join(IGNORE_FILE),
+            "!keep.log\n!*.tmp\n",
+        )
+        .unwrap();
+
+        let ignores = IgnoreFiles::load(dir.path()).unwrap();
+
+        assert!(ignores.is_ignored(Path::new("debug.log"), false).unwrap());
+        assert!(ignores.is_ignored(Path::new("cache.tmp"), false).unwrap());
+        assert!(
+            !ignores
+                .is_ignored(Path::new("sub/keep.log"), false)
+                .unwrap()
+        );
+        assert!(
+            !ignores
+                .is_ignored(Path::new("sub/cache.tmp"), false)
+                .unwrap()
+        );
+        assert!(
+            ignores
+                .is_ignored(Path::new("sub/debug.log"), false)
+                .unwrap()
+        );
+    }
+}
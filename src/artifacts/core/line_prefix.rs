@@ -0,0 +1,88 @@
+//! Prefixing every line of command output
+//!
+//! `--line-prefix` makes `diff` and `show` output embeddable in other text,
+//! e.g. quoted in an email or indented under a heading.
+
+use std::io::{self, Write};
+
+/// Writer that prepends a prefix to every line written through it
+///
+/// A line is prefixed when its first byte is written, so no prefix is left
+/// dangling after the final newline.
+pub struct LinePrefixWriter {
+    inner: Box<dyn Write>,
+    prefix: String,
+    at_line_start: bool,
+}
+
+impl LinePrefixWriter {
+    pub fn new(inner: Box<dyn Write>, prefix: String) -> Self {
+        Self {
+            inner,
+            prefix,
+            at_line_start: true,
+        }
+    }
+}
+
+impl Write for LinePrefixWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for line in buf.split_inclusive(|&byte| byte == b'\n') {
+            if self.at_line_start {
+                self.inner.write_all(self.prefix.as_bytes())?;
+            }
+            self.inner.write_all(line)?;
+            self.at_line_start = line.ends_with(b"\n");
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Buffer still readable once the writer owning a handle to it is done
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_prefixes_every_line() {
+        let buffer = SharedBuffer::default();
+        let mut writer = LinePrefixWriter::new(Box::new(buffer.clone()), "> ".to_string());
+
+        write!(writer, "first\nsecond\n").unwrap();
+
+        assert_eq!(&*buffer.0.borrow(), b"> first\n> second\n");
+    }
+
+    #[test]
+    fn test_lines_split_across_writes_get_one_prefix() {
+        let buffer = SharedBuffer::default();
+        let mut writer = LinePrefixWriter::new(Box::new(buffer.clone()), "| ".to_string());
+
+        write!(writer, "par").unwrap();
+        write!(writer, "tial\n\nnext").unwrap();
+
+        assert_eq!(&*buffer.0.borrow(), b"| partial\n| \n| next");
+    }
+}
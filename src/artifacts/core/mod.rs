@@ -1,9 +1,27 @@
 //! Core utilities and shared types
 //!
-//! This module contains shared utilities used across the application.
+//! This module contains shared utilities used across the application, such as
+//! the pager writer and its settings.
+//!
+//! - `attributes`: `.gitattributes` lookup (e.g. the `diff` driver of a path)
+//! - `color`: Whether to color output (`--color`, `color.ui` and friends)
+//! - `editor`: Asking for messages in the user's editor
+//! - `ignore`: `.gitignore` rule matching for untracked files
+//! - `line_prefix`: Prefixing every output line (`--line-prefix`)
+//! - `pathspec`: Matching of path arguments (prefixes and globs)
+//! - `quote`: C-style quoting of unusual paths in output
+
+pub mod attributes;
+pub mod color;
+pub mod editor;
+pub mod ignore;
+pub mod line_prefix;
+pub mod pathspec;
+pub mod quote;
 
 use derive_new::new;
-use minus::Pager;
+use minus::error::MinusError;
+use minus::{LineNumbers, Pager};
 use std::io::{self, Write};
 
 /// Wrapper that implements `Write` for the minus pager
@@ -27,11 +45,69 @@ pub struct PagerWriter {
 }
 
 impl PagerWriter {
+    /// Create a writer over a new pager set up with `config`
+    pub fn with_config(config: &PagerConfig) -> Result<Self, MinusError> {
+        Ok(Self::new(config.build()?))
+    }
+
     pub fn pager(&self) -> &Pager {
         &self.pager
     }
 }
 
+/// Settings applied to the minus pager
+///
+/// Searching with `/` and `?` (incrementally highlighting matches while
+/// typing) comes with minus' `search` feature and needs no setup here.
+/// Following the output is off by default: the whole output is pushed before
+/// paging starts, so following opens the pager at its last page, which
+/// `core.pagerFollowOutput` asks for.
+///
+/// ## Usage
+///
+/// ```ignore
+/// let config = PagerConfig::default().line_numbers(true);
+/// let writer = PagerWriter::with_config(&config)?;
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PagerConfig {
+    line_numbers: bool,
+    follow_output: bool,
+}
+
+impl PagerConfig {
+    /// Show line numbers next to each line
+    pub fn line_numbers(mut self, enabled: bool) -> Self {
+        self.line_numbers = enabled;
+        self
+    }
+
+    /// Keep the view at the end of the output as it grows
+    pub fn follow_output(mut self, enabled: bool) -> Self {
+        self.follow_output = enabled;
+        self
+    }
+
+    /// Create a pager with these settings
+    pub fn build(&self) -> Result<Pager, MinusError> {
+        let pager = Pager::new();
+        self.apply(&pager)?;
+
+        Ok(pager)
+    }
+
+    fn apply(&self, pager: &Pager) -> Result<(), MinusError> {
+        pager.set_line_numbers(if self.line_numbers {
+            LineNumbers::Enabled
+        } else {
+            LineNumbers::Disabled
+        })?;
+        pager.follow_output(self.follow_output)?;
+
+        Ok(())
+    }
+}
+
 impl Write for PagerWriter {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         let s =
@@ -44,3 +120,39 @@ impl Write for PagerWriter {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pager_config_defaults_to_plain_paging() {
+        let config = PagerConfig::default();
+
+        assert!(!config.line_numbers);
+        assert!(!config.follow_output);
+    }
+
+    #[test]
+    fn test_pager_config_builder_sets_options() {
+        let config = PagerConfig::default()
+            .line_numbers(true)
+            .follow_output(true);
+
+        assert_eq!(
+            config,
+            PagerConfig {
+                line_numbers: true,
+                follow_output: true,
+            }
+        );
+        assert!(!config.line_numbers(false).line_numbers);
+    }
+
+    #[test]
+    fn test_pager_config_builds_configured_pager() {
+        let config = PagerConfig::default().line_numbers(true);
+
+        assert!(PagerWriter::with_config(&config).is_ok());
+    }
+}
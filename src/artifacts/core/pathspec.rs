@@ -0,0 +1,212 @@
+//! Pathspecs: the path arguments accepted by path-scoped commands
+//!
+//! Each item either names a path prefix (a file, or a directory whose whole
+//! subtree it matches; `.` matches everything) or, when it contains any of
+//! `*`, `?` or `[`, a glob. As in Git's default pathspec semantics, glob
+//! wildcards also match `/`, so `*.txt` selects text files at any depth.
+//!
+//! Items written with the exclude magic, `:(exclude)<pattern>` or its short
+//! forms `:!<pattern>` and `:^<pattern>`, drop the paths they match from the
+//! selection instead. When every item is an exclusion, the rest of the
+//! workspace is selected, as if `.` had been given too.
+
+use crate::artifacts::core::ignore::glob_to_regex;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PathspecError {
+    #[error("invalid pathspec '{pattern}'")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+    #[error("unsupported pathspec magic '{magic}' in '{pattern}'")]
+    UnsupportedMagic { magic: String, pattern: String },
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    /// Matches the path itself and everything below it
+    Prefix(PathBuf),
+    /// Matches full paths against a wildcard pattern
+    Glob(Regex),
+}
+
+/// A single pathspec item, as given on the command line
+#[derive(Debug, Clone)]
+pub struct PathspecItem {
+    original: PathBuf,
+    matcher: Matcher,
+}
+
+impl PathspecItem {
+    pub fn parse(path: &Path) -> Result<Self, PathspecError> {
+        let pattern = path.to_string_lossy();
+        let matcher = if pattern.contains(['*', '?', '[']) {
+            let regex =
+                Regex::new(&format!("^{}$", glob_to_regex(&pattern, true))).map_err(|source| {
+                    PathspecError::InvalidPattern {
+                        pattern: pattern.to_string(),
+                        source,
+                    }
+                })?;
+            Matcher::Glob(regex)
+        } else {
+            Matcher::Prefix(path.to_path_buf())
+        };
+
+        Ok(Self {
+            original: path.to_path_buf(),
+            matcher,
+        })
+    }
+
+    /// The item as it was given
+    pub fn original(&self) -> &Path {
+        &self.original
+    }
+
+    /// Whether `path` (relative to the workspace root) is selected by this item
+    pub fn matches(&self, path: &Path) -> bool {
+        match &self.matcher {
+            Matcher::Prefix(prefix) => prefix == Path::new(".") || path.starts_with(prefix),
+            Matcher::Glob(regex) => regex.is_match(&path.to_string_lossy()),
+        }
+    }
+}
+
+/// A list of pathspec items; a path is selected when any item matches it
+/// and no exclusion does
+#[derive(Debug, Clone)]
+pub struct Pathspec {
+    items: Vec<PathspecItem>,
+    excludes: Vec<PathspecItem>,
+}
+
+impl Pathspec {
+    pub fn parse(paths: &[PathBuf]) -> Result<Self, PathspecError> {
+        Self::parse_relative(paths, Path::new(""))
+    }
+
+    /// Parse items given relative to `prefix`, a directory below the
+    /// workspace root, so that they match root-relative paths
+    pub fn parse_relative(paths: &[PathBuf], prefix: &Path) -> Result<Self, PathspecError> {
+        let mut items = Vec::new();
+        let mut excludes = Vec::new();
+        for path in paths {
+            match Self::exclusion(path)? {
+                Some(pattern) => excludes.push(PathspecItem::parse(&prefix.join(pattern))?),
+                None => items.push(PathspecItem::parse(&prefix.join(path))?),
+            }
+        }
+
+        Ok(Self { items, excludes })
+    }
+
+    /// The pattern of an item using the exclude magic, if `path` is one
+    fn exclusion(path: &Path) -> Result<Option<&str>, PathspecError> {
+        let Some(pattern) = path.to_str() else {
+            return Ok(None);
+        };
+
+        if let Some(rest) = pattern.strip_prefix(":(") {
+            let Some((magic, rest)) = rest.split_once(')') else {
+                return Ok(None);
+            };
+            if magic != "exclude" {
+                return Err(PathspecError::UnsupportedMagic {
+                    magic: magic.to_string(),
+                    pattern: pattern.to_string(),
+                });
+            }
+            return Ok(Some(rest));
+        }
+
+        Ok(pattern
+            .strip_prefix(":!")
+            .or_else(|| pattern.strip_prefix(":^")))
+    }
+
+    /// The items selecting paths, exclusions aside
+    pub fn items(&self) -> &[PathspecItem] {
+        &self.items
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        (self.items.is_empty() || self.items.iter().any(|item| item.matches(path)))
+            && !self.excludes.iter().any(|item| item.matches(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(pattern: &str) -> PathspecItem {
+        PathspecItem::parse(Path::new(pattern)).unwrap()
+    }
+
+    #[test]
+    fn test_prefix_matches_directory_contents() {
+        let spec = item("a/b");
+
+        assert!(spec.matches(Path::new("a/b/3.txt")));
+        assert!(spec.matches(Path::new("a/b")));
+        assert!(!spec.matches(Path::new("a/bc.txt")));
+        assert!(!spec.matches(Path::new("a/2.txt")));
+    }
+
+    #[test]
+    fn test_dot_matches_everything() {
+        assert!(item(".").matches(Path::new("a/b/3.txt")));
+    }
+
+    #[test]
+    fn test_glob_wildcards_cross_directories() {
+        let spec = item("*.txt");
+
+        assert!(spec.matches(Path::new("1.txt")));
+        assert!(spec.matches(Path::new("a/b/3.txt")));
+        assert!(!spec.matches(Path::new("notes.md")));
+    }
+
+    #[test]
+    fn test_glob_character_classes() {
+        let spec = item("a/[!b]*.txt");
+
+        assert!(spec.matches(Path::new("a/2.txt")));
+        assert!(!spec.matches(Path::new("a/b/3.txt")));
+        assert!(item("?.txt").matches(Path::new("1.txt")));
+    }
+
+    #[test]
+    fn test_exclusions_drop_matching_paths() {
+        let spec =
+            Pathspec::parse(&[PathBuf::from("a"), PathBuf::from(":(exclude)*.lock")]).unwrap();
+
+        assert!(spec.matches(Path::new("a/2.txt")));
+        assert!(!spec.matches(Path::new("a/Cargo.lock")));
+        assert!(!spec.matches(Path::new("1.txt")));
+    }
+
+    #[test]
+    fn test_only_exclusions_select_everything_else() {
+        for exclusion in [":!a/b", ":^a/b"] {
+            let spec = Pathspec::parse(&[PathBuf::from(exclusion)]).unwrap();
+
+            assert!(spec.items().is_empty());
+            assert!(spec.matches(Path::new("a/2.txt")));
+            assert!(!spec.matches(Path::new("a/b/3.txt")));
+        }
+    }
+
+    #[test]
+    fn test_unsupported_magic_is_reported() {
+        assert!(matches!(
+            Pathspec::parse(&[PathBuf::from(":(icase)README")]),
+            Err(PathspecError::UnsupportedMagic { .. })
+        ));
+    }
+}
@@ -0,0 +1,164 @@
+//! C-style quoting of paths in command output
+//!
+//! Paths containing "unusual" bytes are printed the way Git prints them:
+//! wrapped in double quotes, with control characters, `"` and `\` escaped
+//! (`\t`, `\n`, `\"`, `\\`, ...) and every other such byte written as a
+//! three-digit octal escape. Non-ASCII bytes are only escaped while
+//! `core.quotePath` is enabled (the default).
+//!
+//! Quoted paths passed back as arguments are unquoted with [`unquote_path`].
+
+use std::path::Path;
+
+/// Which bytes besides control characters, `"` and `\` force quoting
+#[derive(Debug, Clone, Copy)]
+pub struct QuoteOptions {
+    /// Escape bytes above 0x7f (`core.quotePath`)
+    pub non_ascii: bool,
+    /// Quote paths containing spaces (short status format)
+    pub space: bool,
+}
+
+/// Render a path, quoting it if it contains bytes that need escaping
+pub fn quote_path(path: &Path, opts: QuoteOptions) -> String {
+    let bytes = path.as_os_str().as_encoded_bytes();
+    let needs_quoting = |byte: u8| match byte {
+        b' ' => opts.space,
+        0x80..=0xff => opts.non_ascii,
+        _ => byte < 0x20 || byte == 0x7f || byte == b'"' || byte == b'\\',
+    };
+
+    if !bytes.iter().any(|&byte| needs_quoting(byte)) {
+        return path.display().to_string();
+    }
+
+    let mut quoted = Vec::with_capacity(bytes.len() + 2);
+    quoted.push(b'"');
+    for &byte in bytes {
+        match byte {
+            0x07 => quoted.extend_from_slice(b"\\a"),
+            0x08 => quoted.extend_from_slice(b"\\b"),
+            b'\t' => quoted.extend_from_slice(b"\\t"),
+            b'\n' => quoted.extend_from_slice(b"\\n"),
+            0x0b => quoted.extend_from_slice(b"\\v"),
+            0x0c => quoted.extend_from_slice(b"\\f"),
+            b'\r' => quoted.extend_from_slice(b"\\r"),
+            b'"' => quoted.extend_from_slice(b"\\\""),
+            b'\\' => quoted.extend_from_slice(b"\\\\"),
+            b' ' => quoted.push(b' '),
+            byte if needs_quoting(byte) => {
+                quoted.extend_from_slice(format!("\\{:03o}", byte).as_bytes())
+            }
+            byte => quoted.push(byte),
+        }
+    }
+    quoted.push(b'"');
+
+    String::from_utf8_lossy(&quoted).into_owned()
+}
+
+/// Undo [`quote_path`] on a command-line argument
+///
+/// Arguments that are not a well-formed quoted path are returned unchanged.
+pub fn unquote_path(arg: &str) -> String {
+    try_unquote(arg).unwrap_or_else(|| arg.to_string())
+}
+
+fn try_unquote(arg: &str) -> Option<String> {
+    let inner = arg.strip_prefix('"')?.strip_suffix('"')?;
+    let mut bytes = inner.bytes();
+    let mut unquoted = Vec::with_capacity(inner.len());
+
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'\\' => unquoted.push(match bytes.next()? {
+                b'a' => 0x07,
+                b'b' => 0x08,
+                b't' => b'\t',
+                b'n' => b'\n',
+                b'v' => 0x0b,
+                b'f' => 0x0c,
+                b'r' => b'\r',
+                b'"' => b'"',
+                b'\\' => b'\\',
+                digit @ b'0'..=b'3' => {
+                    let mut value = digit - b'0';
+                    for _ in 0..2 {
+                        match bytes.next()? {
+                            digit @ b'0'..=b'7' => value = value * 8 + (digit - b'0'),
+                            _ => return None,
+                        }
+                    }
+                    value
+                }
+                _ => return None,
+            }),
+            b'"' => return None,
+            byte => unquoted.push(byte),
+        }
+    }
+
+    String::from_utf8(unquoted).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const DEFAULT: QuoteOptions = QuoteOptions {
+        non_ascii: true,
+        space: false,
+    };
+
+    #[test]
+    fn test_plain_path_is_not_quoted() {
+        assert_eq!(quote_path(Path::new("a/b c.txt"), DEFAULT), "a/b c.txt");
+    }
+
+    #[test]
+    fn test_control_characters_and_quotes_are_escaped() {
+        assert_eq!(
+            quote_path(Path::new("a b\tc\"d\\.txt"), DEFAULT),
+            r#""a b\tc\"d\\.txt""#
+        );
+    }
+
+    #[test]
+    fn test_space_is_quoted_when_requested() {
+        let opts = QuoteOptions {
+            space: true,
+            ..DEFAULT
+        };
+
+        assert_eq!(quote_path(Path::new("a b.txt"), opts), r#""a b.txt""#);
+    }
+
+    #[test]
+    fn test_non_ascii_follows_quote_path_option() {
+        let path = Path::new("caf\u{e9}.txt");
+        let opts = QuoteOptions {
+            non_ascii: false,
+            ..DEFAULT
+        };
+
+        assert_eq!(quote_path(path, DEFAULT), r#""caf\303\251.txt""#);
+        assert_eq!(quote_path(path, opts), "caf\u{e9}.txt");
+    }
+
+    #[test]
+    fn test_unquote_reverses_quoting() {
+        for name in ["a b\tc.txt", "caf\u{e9}.txt", "x\"y\\z\n"] {
+            let quoted = quote_path(Path::new(name), DEFAULT);
+
+            assert_eq!(unquote_path(&quoted), name);
+        }
+    }
+
+    #[test]
+    fn test_unquote_leaves_other_arguments_unchanged() {
+        assert_eq!(unquote_path("plain.txt"), "plain.txt");
+        assert_eq!(unquote_path(r#""bad\q""#), r#""bad\q""#);
+        assert_eq!(unquote_path("\"unterminated"), "\"unterminated");
+    }
+}
@@ -23,4 +23,9 @@ impl DatabaseEntry {
     pub fn is_tree(&self) -> bool {
         self.mode.is_tree()
     }
+
+    /// Whether the entry records a submodule commit rather than a blob
+    pub fn is_gitlink(&self) -> bool {
+        self.mode.is_gitlink()
+    }
 }
@@ -2,5 +2,7 @@
 //!
 //! This module contains types used when reading objects from the database.
 //! Database entries represent references to objects with their mode/type information.
+//! `reachability` finds the objects reachable from a set of roots.
 
 pub mod database_entry;
+pub mod reachability;
@@ -0,0 +1,80 @@
+//! Object reachability
+//!
+//! An object is reachable when it can be found by following references from
+//! a set of root objects: commits lead to their tree and parents, trees to
+//! their entries and tags to their target. Submodule commits (gitlinks) live
+//! in another repository and are not followed.
+
+use crate::areas::database::{Database, DatabaseError};
+use crate::artifacts::objects::object::ObjectBox;
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::objects::object_type::ObjectType;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Every object reachable from `roots`, with its type
+pub fn reachable_objects(
+    database: &Database,
+    roots: impl IntoIterator<Item = ObjectId>,
+) -> Result<BTreeMap<ObjectId, ObjectType>, DatabaseError> {
+    let mut reachable = BTreeMap::new();
+    let mut pending = roots.into_iter().collect::<Vec<_>>();
+
+    while let Some(oid) = pending.pop() {
+        if reachable.contains_key(&oid) {
+            continue;
+        }
+        let (object_type, references) = references(database, &oid)?;
+        reachable.insert(oid, object_type);
+        pending.extend(
+            references
+                .into_iter()
+                .filter(|reference| !reachable.contains_key(reference)),
+        );
+    }
+
+    Ok(reachable)
+}
+
+/// The objects of `objects` that no other object of `objects` refers to
+///
+/// These are the tips from which the rest of the set can be reached again.
+pub fn unreferenced_objects(
+    database: &Database,
+    objects: &BTreeSet<ObjectId>,
+) -> Result<BTreeSet<ObjectId>, DatabaseError> {
+    let mut referenced = HashSet::new();
+    for oid in objects {
+        let (_, references) = references(database, oid)?;
+        referenced.extend(references);
+    }
+
+    Ok(objects
+        .iter()
+        .filter(|oid| !referenced.contains(*oid))
+        .cloned()
+        .collect())
+}
+
+/// Type of the object `oid` and the objects it directly refers to
+pub fn references(
+    database: &Database,
+    oid: &ObjectId,
+) -> Result<(ObjectType, Vec<ObjectId>), DatabaseError> {
+    Ok(match database.parse_object(oid)? {
+        ObjectBox::Blob(_) => (ObjectType::Blob, Vec::new()),
+        ObjectBox::Tree(tree) => (
+            ObjectType::Tree,
+            tree.entries()
+                .filter(|(_, entry)| !entry.is_gitlink())
+                .map(|(_, entry)| entry.oid.clone())
+                .collect(),
+        ),
+        ObjectBox::Commit(commit) => (
+            ObjectType::Commit,
+            std::iter::once(commit.tree_oid().clone())
+                .chain(commit.parents().iter().cloned())
+                .collect(),
+        ),
+        ObjectBox::Tag(tag) => (ObjectType::Tag, vec![tag.object().clone()]),
+    })
+}
@@ -0,0 +1,432 @@
+//! Combined diff for merge commits (Git's `--cc` format)
+//!
+//! A combined diff compares a merge result against all of its parents at
+//! once. Every line of output carries one column per parent:
+//!
+//! - `+` in column `i`: the line is in the result but not in parent `i`
+//! - `-` in column `i`: the line is in parent `i` but not in the result
+//! - ` ` in column `i`: the line is unchanged relative to parent `i`
+//!
+//! Only hunks that differ from every parent are kept: a hunk taken verbatim
+//! from one side of the merge is not interesting when reviewing the merge,
+//! while a region rewritten during conflict resolution is.
+//!
+//! ## Format
+//!
+//! ```text
+//! @@@ -<p1_start>,<p1_lines> -<p2_start>,<p2_lines> +<start>,<lines> @@@
+//!   context
+//! - removed from the first parent
+//!  -removed from the second parent
+//! ++added relative to both parents
+//! ```
+
+use crate::artifacts::diff::diff_algorithm::{DiffAlgorithm, Edit, MyersDiff};
+use colored::Colorize;
+use std::fmt::Display;
+
+/// Number of unchanged lines to show around each combined hunk
+const HUNK_CONTEXT: usize = 3;
+
+/// State of a combined diff line relative to a single parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombinedMark {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+impl CombinedMark {
+    fn as_char(&self) -> char {
+        match self {
+            CombinedMark::Unchanged => ' ',
+            CombinedMark::Added => '+',
+            CombinedMark::Removed => '-',
+        }
+    }
+}
+
+/// A line of a combined diff, with one mark per parent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CombinedLine {
+    value: String,
+    marks: Vec<CombinedMark>,
+    /// Whether the line is part of the merge result (as opposed to a line
+    /// only found in some of the parents)
+    in_result: bool,
+}
+
+impl CombinedLine {
+    fn result(value: String, marks: Vec<CombinedMark>) -> Self {
+        Self {
+            value,
+            marks,
+            in_result: true,
+        }
+    }
+
+    fn removed(value: String, parents: usize) -> Self {
+        Self {
+            value,
+            marks: vec![CombinedMark::Unchanged; parents],
+            in_result: false,
+        }
+    }
+
+    pub fn marks(&self) -> &[CombinedMark] {
+        &self.marks
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.marks
+            .iter()
+            .any(|mark| *mark != CombinedMark::Unchanged)
+    }
+
+    /// Whether the line exists in the given parent
+    fn in_parent(&self, parent: usize) -> bool {
+        if self.in_result {
+            self.marks[parent] == CombinedMark::Unchanged
+        } else {
+            self.marks[parent] == CombinedMark::Removed
+        }
+    }
+
+    pub fn as_string(&self) -> String {
+        let prefix = self
+            .marks
+            .iter()
+            .map(CombinedMark::as_char)
+            .collect::<String>();
+        format!("{}{}", prefix, self.value)
+    }
+}
+
+impl Display for CombinedLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let painted_line = if !self.in_result {
+            self.as_string().color(colored::Color::Red)
+        } else if self.is_changed() {
+            self.as_string().color(colored::Color::Green)
+        } else {
+            self.as_string().normal()
+        };
+
+        write!(f, "{}", painted_line)
+    }
+}
+
+/// A contiguous region of a combined diff with context
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CombinedHunk {
+    /// Starting line number in each parent
+    parent_starts: Vec<usize>,
+    /// Starting line number in the merge result
+    result_start: usize,
+    lines: Vec<CombinedLine>,
+}
+
+impl CombinedHunk {
+    pub fn lines(&self) -> &[CombinedLine] {
+        &self.lines
+    }
+
+    pub fn parent_size(&self, parent: usize) -> usize {
+        self.lines
+            .iter()
+            .filter(|line| line.in_parent(parent))
+            .count()
+    }
+
+    pub fn result_size(&self) -> usize {
+        self.lines.iter().filter(|line| line.in_result).count()
+    }
+
+    /// Hunk header, e.g. `@@@ -1,3 -1,3 +1,3 @@@` for a two-parent merge
+    pub fn header(&self) -> String {
+        let marker = "@".repeat(self.parent_starts.len() + 1);
+        let parent_offsets = self
+            .parent_starts
+            .iter()
+            .enumerate()
+            .map(|(parent, start)| format!("-{},{}", start, self.parent_size(parent)))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        format!(
+            "{marker} {parent_offsets} +{},{} {marker}",
+            self.result_start,
+            self.result_size()
+        )
+    }
+}
+
+/// Combined diff of a merge result against all of its parents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CombinedDiff {
+    parents: usize,
+    lines: Vec<CombinedLine>,
+}
+
+impl CombinedDiff {
+    pub fn new(parents: &[Vec<String>], result: &[String]) -> Self {
+        // removed[i][j]: lines of parent i removed right before result line j
+        // (j == result.len() holds the lines removed at the end of the file)
+        let mut removed = vec![vec![Vec::new(); result.len() + 1]; parents.len()];
+        let mut added = vec![vec![false; result.len()]; parents.len()];
+
+        for (parent, parent_lines) in parents.iter().enumerate() {
+            let mut result_offset = 0;
+            for edit in MyersDiff::new(parent_lines, result).diff() {
+                match edit {
+                    Edit::Delete { line } => {
+                        removed[parent][result_offset].push(line.value().clone());
+                    }
+                    Edit::Insert { .. } => {
+                        added[parent][result_offset] = true;
+                        result_offset += 1;
+                    }
+                    Edit::Equal { .. } => result_offset += 1,
+                }
+            }
+        }
+
+        let mut lines = Vec::new();
+        // One more offset than result lines, for the lines removed at the end
+        for (offset, value) in result.iter().map(Some).chain([None]).enumerate() {
+            lines.extend(Self::coalesce_removed(parents.len(), &removed, offset));
+
+            if let Some(value) = value {
+                let marks = added
+                    .iter()
+                    .map(|added| {
+                        if added[offset] {
+                            CombinedMark::Added
+                        } else {
+                            CombinedMark::Unchanged
+                        }
+                    })
+                    .collect();
+                lines.push(CombinedLine::result(value.clone(), marks));
+            }
+        }
+
+        Self {
+            parents: parents.len(),
+            lines,
+        }
+    }
+
+    /// Merge the lines removed from each parent at the same position, so a
+    /// line removed from several parents is only shown once
+    fn coalesce_removed(
+        parents: usize,
+        removed: &[Vec<Vec<String>>],
+        offset: usize,
+    ) -> Vec<CombinedLine> {
+        let mut lines: Vec<CombinedLine> = Vec::new();
+
+        for (parent, parent_removed) in removed.iter().enumerate() {
+            let parent_removed = &parent_removed[offset];
+            let mut position = 0;
+            for (index, value) in parent_removed.iter().enumerate() {
+                let find = |lines: &[CombinedLine], value: &String| {
+                    lines.iter().position(|line| {
+                        line.value == *value && line.marks[parent] == CombinedMark::Unchanged
+                    })
+                };
+
+                match find(&lines[position..], value) {
+                    Some(found) => position += found,
+                    None => {
+                        // Keep the lines of this parent after the ones of the previous
+                        // parents, unless a later line still has to be matched
+                        let later_match = parent_removed[index + 1..]
+                            .iter()
+                            .any(|later| find(&lines[position..], later).is_some());
+                        if !later_match {
+                            position = lines.len();
+                        }
+                        lines.insert(position, CombinedLine::removed(value.clone(), parents));
+                    }
+                }
+                lines[position].marks[parent] = CombinedMark::Removed;
+                position += 1;
+            }
+        }
+
+        lines
+    }
+
+    pub fn lines(&self) -> &[CombinedLine] {
+        &self.lines
+    }
+
+    /// Group the changes into hunks, dropping those identical to one of the parents
+    pub fn hunks(&self) -> Vec<CombinedHunk> {
+        let changed = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.is_changed())
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        // Changes separated by less than twice the context share a hunk
+        let mut groups: Vec<(usize, usize)> = Vec::new();
+        for index in changed {
+            match groups.last_mut() {
+                Some((_, end)) if index - *end <= 2 * HUNK_CONTEXT + 1 => *end = index,
+                _ => groups.push((index, index)),
+            }
+        }
+
+        groups
+            .into_iter()
+            .filter(|&(start, end)| self.differs_from_all_parents(start, end))
+            .map(|(start, end)| self.build_hunk(start, end))
+            .collect()
+    }
+
+    fn differs_from_all_parents(&self, start: usize, end: usize) -> bool {
+        (0..self.parents).all(|parent| {
+            self.lines[start..=end]
+                .iter()
+                .any(|line| line.marks[parent] != CombinedMark::Unchanged)
+        })
+    }
+
+    fn build_hunk(&self, first_change: usize, last_change: usize) -> CombinedHunk {
+        let start = self.context_start(first_change);
+        let end = self.context_end(last_change);
+        let lines = self.lines[start..end].to_vec();
+
+        let lines_before = &self.lines[..start];
+        let parent_starts = (0..self.parents)
+            .map(|parent| {
+                let before = lines_before
+                    .iter()
+                    .filter(|line| line.in_parent(parent))
+                    .count();
+                let present = lines.iter().any(|line| line.in_parent(parent));
+                if present { before + 1 } else { before }
+            })
+            .collect();
+        let result_before = lines_before.iter().filter(|line| line.in_result).count();
+        let result_start = if lines.iter().any(|line| line.in_result) {
+            result_before + 1
+        } else {
+            result_before
+        };
+
+        CombinedHunk {
+            parent_starts,
+            result_start,
+            lines,
+        }
+    }
+
+    /// Index of the first line of context before a change
+    fn context_start(&self, first_change: usize) -> usize {
+        let mut start = first_change;
+        let mut context = 0;
+        while start > 0 && context < HUNK_CONTEXT {
+            start -= 1;
+            if !self.lines[start].is_changed() {
+                context += 1;
+            }
+        }
+
+        start
+    }
+
+    /// Index right after the last line of context following a change
+    fn context_end(&self, last_change: usize) -> usize {
+        let mut end = last_change + 1;
+        let mut context = 0;
+        while end < self.lines.len() && context < HUNK_CONTEXT {
+            if !self.lines[end].is_changed() {
+                context += 1;
+            }
+            end += 1;
+        }
+
+        end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn lines(content: &str) -> Vec<String> {
+        content.lines().map(|line| line.to_string()).collect()
+    }
+
+    fn render(hunks: &[CombinedHunk]) -> String {
+        hunks
+            .iter()
+            .flat_map(|hunk| {
+                std::iter::once(hunk.header()).chain(hunk.lines().iter().map(|l| l.as_string()))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_conflict_resolution_differs_from_both_parents() {
+        let ours = lines("base\nours change\nmore\n");
+        let theirs = lines("base\ntheirs change\nmore\n");
+        let result = lines("base\nresolved\nmore\n");
+
+        let diff = CombinedDiff::new(&[ours, theirs], &result);
+
+        assert_eq!(
+            render(&diff.hunks()),
+            "@@@ -1,3 -1,3 +1,3 @@@\n  base\n- ours change\n -theirs change\n++resolved\n  more"
+        );
+    }
+
+    #[test]
+    fn test_change_taken_from_one_parent_is_dropped() {
+        let ours = lines("one\ntwo\nthree\n");
+        let theirs = lines("one\nTWO\nthree\n");
+        let result = lines("one\nTWO\nthree\n");
+
+        let diff = CombinedDiff::new(&[ours, theirs], &result);
+
+        assert!(diff.hunks().is_empty());
+    }
+
+    #[test]
+    fn test_line_removed_from_both_parents_is_shown_once() {
+        let ours = lines("keep\ngone\nours\n");
+        let theirs = lines("keep\ngone\ntheirs\n");
+        let result = lines("keep\nmerged\n");
+
+        let diff = CombinedDiff::new(&[ours, theirs], &result);
+
+        assert_eq!(
+            render(&diff.hunks()),
+            "@@@ -1,3 -1,3 +1,2 @@@\n  keep\n--gone\n- ours\n -theirs\n++merged"
+        );
+    }
+
+    #[test]
+    fn test_distant_changes_form_separate_hunks() {
+        let ours = lines("a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\n");
+        let theirs = lines("a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nK\n");
+        let result = lines("a\nX\nc\nd\ne\nf\ng\nh\ni\nj\nY\n");
+
+        let hunks = CombinedDiff::new(&[ours, theirs], &result).hunks();
+
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].header(), "@@@ -1,5 -1,5 +1,5 @@@");
+        assert_eq!(hunks[1].header(), "@@@ -8,4 -8,4 +8,4 @@@");
+    }
+}
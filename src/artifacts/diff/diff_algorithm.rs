@@ -35,6 +35,16 @@ pub struct Line<T> {
     value: T,
 }
 
+impl<T> Line<T> {
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+}
+
 /// An edit operation in the diff
 ///
 /// Represents one of three possible operations:
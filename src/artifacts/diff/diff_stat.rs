@@ -0,0 +1,312 @@
+//! Diffstat (`--stat`) summaries
+//!
+//! Each changed file gets one line with its number of changed lines and a
+//! bar of `+` and `-`, followed by a total:
+//!
+//! ```text
+//!  file1.txt | 3 ++-
+//!  file2.txt | 1 +
+//!  2 files changed, 3 insertions(+), 1 deletion(-)
+//! ```
+//!
+//! Lines of binary files are not counted: they show `Bin <old> -> <new> bytes`
+//! instead. Unmerged paths show `Unmerged` and are left out of the total.
+
+use crate::artifacts::diff::diff_algorithm::{DiffAlgorithm, Edit, MyersDiff};
+use crate::artifacts::diff::diff_target::DiffTarget;
+use crate::artifacts::diff::textconv::is_binary;
+use colored::Colorize;
+use std::path::PathBuf;
+
+/// Widest `+`/`-` bar; larger changes are scaled down to fit
+const STAT_GRAPH_WIDTH: usize = 40;
+
+/// Shown in place of the line count of binary files
+const BINARY_MARKER: &str = "Bin";
+
+/// Shown in place of the line count of unmerged paths
+const UNMERGED_MARKER: &str = "Unmerged";
+
+/// Lines added and removed in one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStat {
+    pub path: PathBuf,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Byte sizes of the old and new versions when either is binary, in
+    /// which case no lines were counted
+    pub binary: Option<(usize, usize)>,
+    /// The path has unresolved merge conflicts, so nothing was compared
+    pub unmerged: bool,
+}
+
+impl FileStat {
+    /// Count the lines changed between two versions of a file
+    ///
+    /// Binary-ness is decided on the raw content, which need not be UTF-8.
+    pub fn between(path: PathBuf, a: &DiffTarget, b: &DiffTarget) -> Self {
+        if is_binary(&a.content) || is_binary(&b.content) {
+            return FileStat {
+                path,
+                insertions: 0,
+                deletions: 0,
+                binary: Some((a.content.len(), b.content.len())),
+                unmerged: false,
+            };
+        }
+
+        let edits = MyersDiff::new(&a.data, &b.data).diff();
+
+        FileStat {
+            path,
+            insertions: edits
+                .iter()
+                .filter(|edit| matches!(edit, Edit::Insert { .. }))
+                .count(),
+            deletions: edits
+                .iter()
+                .filter(|edit| matches!(edit, Edit::Delete { .. }))
+                .count(),
+            binary: None,
+            unmerged: false,
+        }
+    }
+
+    /// Line of a path with unresolved merge conflicts
+    pub fn unmerged(path: PathBuf) -> Self {
+        FileStat {
+            path,
+            insertions: 0,
+            deletions: 0,
+            binary: None,
+            unmerged: true,
+        }
+    }
+
+    fn changes(&self) -> usize {
+        self.insertions + self.deletions
+    }
+}
+
+/// Render the per-file lines and the summary line of a diffstat
+pub fn format_stat(stats: &[FileStat]) -> Vec<String> {
+    let name_width = stats
+        .iter()
+        .map(|stat| stat.path.display().to_string().len())
+        .max()
+        .unwrap_or_default();
+    let max_changes = stats
+        .iter()
+        .map(FileStat::changes)
+        .max()
+        .unwrap_or_default();
+    let mut count_width = max_changes.to_string().len();
+    if stats.iter().any(|stat| stat.binary.is_some()) {
+        count_width = count_width.max(BINARY_MARKER.len());
+    }
+
+    let mut lines = stats
+        .iter()
+        .map(|stat| {
+            if stat.unmerged {
+                return format!(
+                    " {:<name_width$} | {}",
+                    stat.path.display(),
+                    UNMERGED_MARKER
+                );
+            }
+            if let Some((old_size, new_size)) = stat.binary {
+                return format!(
+                    " {:<name_width$} | {:>count_width$} {} -> {} bytes",
+                    stat.path.display(),
+                    BINARY_MARKER,
+                    old_size,
+                    new_size
+                );
+            }
+
+            let (plus, minus) = scale_bar(stat, max_changes);
+            format!(
+                " {:<name_width$} | {:>count_width$} {}{}",
+                stat.path.display(),
+                stat.changes(),
+                "+".repeat(plus).green(),
+                "-".repeat(minus).red()
+            )
+            .trim_end()
+            .to_string()
+        })
+        .collect::<Vec<_>>();
+    lines.push(summary_line(stats));
+
+    lines
+}
+
+/// Bar lengths for a file, scaled so the largest change fits the graph
+fn scale_bar(stat: &FileStat, max_changes: usize) -> (usize, usize) {
+    if max_changes <= STAT_GRAPH_WIDTH {
+        return (stat.insertions, stat.deletions);
+    }
+
+    let scale = |lines: usize| match lines {
+        0 => 0,
+        lines => (lines * STAT_GRAPH_WIDTH / max_changes).max(1),
+    };
+    (scale(stat.insertions), scale(stat.deletions))
+}
+
+fn summary_line(stats: &[FileStat]) -> String {
+    let plural = |count: usize, one: &str, many: &str| {
+        format!("{} {}", count, if count == 1 { one } else { many })
+    };
+    let files = stats.iter().filter(|stat| !stat.unmerged).count();
+    let insertions = stats.iter().map(|stat| stat.insertions).sum::<usize>();
+    let deletions = stats.iter().map(|stat| stat.deletions).sum::<usize>();
+
+    let mut summary = format!(" {}", plural(files, "file changed", "files changed"));
+    if files == 0 {
+        return summary;
+    }
+    if insertions > 0 || deletions == 0 {
+        summary.push_str(&format!(
+            ", {}",
+            plural(insertions, "insertion(+)", "insertions(+)")
+        ));
+    }
+    if deletions > 0 || insertions == 0 {
+        summary.push_str(&format!(
+            ", {}",
+            plural(deletions, "deletion(-)", "deletions(-)")
+        ));
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::areas::database::Database;
+    use crate::artifacts::diff::diff_target::content_lines;
+    use bytes::Bytes;
+    use pretty_assertions::assert_eq;
+
+    fn target(content: &[u8]) -> DiffTarget<'static> {
+        let content = Bytes::copy_from_slice(content);
+        DiffTarget::new(
+            PathBuf::from("file"),
+            Database::blob_id(&content).unwrap(),
+            Some("100644"),
+            content.clone(),
+            content_lines(&content),
+        )
+    }
+
+    #[test]
+    fn test_counts_inserted_and_deleted_lines() {
+        let stat = FileStat::between(
+            PathBuf::from("file.txt"),
+            &target(b"one\ntwo\nthree"),
+            &target(b"one\n2\nthree\nfour"),
+        );
+
+        assert_eq!((stat.insertions, stat.deletions), (2, 1));
+    }
+
+    #[test]
+    fn test_format_stat_aligns_columns() {
+        colored::control::set_override(false);
+        let stats = vec![
+            FileStat {
+                path: PathBuf::from("a.txt"),
+                insertions: 10,
+                deletions: 2,
+                binary: None,
+                unmerged: false,
+            },
+            FileStat {
+                path: PathBuf::from("dir/b.txt"),
+                insertions: 1,
+                deletions: 0,
+                binary: None,
+                unmerged: false,
+            },
+        ];
+
+        assert_eq!(
+            format_stat(&stats),
+            vec![
+                " a.txt     | 12 ++++++++++--",
+                " dir/b.txt |  1 +",
+                " 2 files changed, 11 insertions(+), 2 deletions(-)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summary_omits_empty_side() {
+        colored::control::set_override(false);
+        let stats = vec![FileStat {
+            path: PathBuf::from("a.txt"),
+            insertions: 0,
+            deletions: 1,
+            binary: None,
+            unmerged: false,
+        }];
+
+        assert_eq!(format_stat(&stats)[1], " 1 file changed, 1 deletion(-)");
+    }
+
+    #[test]
+    fn test_binary_files_show_bin_instead_of_counts() {
+        colored::control::set_override(false);
+        let binary = FileStat::between(
+            PathBuf::from("image.png"),
+            &target(b"\x89PNG\0\xff"),
+            &target(b"\x89PNG\0\xfe\xff"),
+        );
+        let text = FileStat::between(PathBuf::from("a.txt"), &target(b"one"), &target(b"two"));
+
+        assert_eq!(
+            format_stat(&[binary, text]),
+            vec![
+                " image.png | Bin 6 -> 7 bytes",
+                " a.txt     |   2 +-",
+                " 2 files changed, 1 insertion(+), 1 deletion(-)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unmerged_paths_are_left_out_of_the_total() {
+        colored::control::set_override(false);
+        let unmerged = FileStat::unmerged(PathBuf::from("conflict.txt"));
+        let text = FileStat::between(PathBuf::from("a.txt"), &target(b"one"), &target(b"two"));
+
+        assert_eq!(
+            format_stat(&[unmerged.clone(), text]),
+            vec![
+                " conflict.txt | Unmerged",
+                " a.txt        | 2 +-",
+                " 1 file changed, 1 insertion(+), 1 deletion(-)",
+            ]
+        );
+        assert_eq!(
+            format_stat(&[unmerged]),
+            vec![" conflict.txt | Unmerged", " 0 files changed"]
+        );
+    }
+
+    #[test]
+    fn test_large_changes_are_scaled() {
+        let stat = FileStat {
+            path: PathBuf::from("a.txt"),
+            insertions: 100,
+            deletions: 1,
+            binary: None,
+            unmerged: false,
+        };
+
+        assert_eq!(scale_bar(&stat, 101), (39, 1));
+    }
+}
@@ -3,25 +3,71 @@ use crate::areas::index::Index;
 use crate::areas::workspace::Workspace;
 use crate::artifacts::database::database_entry::DatabaseEntry;
 use crate::artifacts::index::index_entry::MergeStage;
-use crate::artifacts::objects::object::Object;
 use crate::artifacts::objects::object_id::ObjectId;
 use crate::artifacts::status::status_info::{FileStatSet, HeadTree};
+use bytes::Bytes;
 use derive_new::new;
 use std::path::{Path, PathBuf};
 
 const NULL_OID_RAW: &str = "0000000000000000000000000000000000000000";
 const NULL_PATH: &str = "/dev/null";
+const DEFAULT_SRC_PREFIX: &str = "a/";
+const DEFAULT_DST_PREFIX: &str = "b/";
 
 pub type LineSet = Vec<String>;
 
+/// Prefixes prepended to the old and new paths in diff headers
+#[derive(Debug, Clone, PartialEq, Eq, new)]
+pub struct DiffPrefix {
+    pub src: String,
+    pub dst: String,
+}
+
+impl Default for DiffPrefix {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_SRC_PREFIX.to_string(),
+            DEFAULT_DST_PREFIX.to_string(),
+        )
+    }
+}
+
+impl DiffPrefix {
+    /// Paths are printed as-is (`--no-prefix`)
+    pub fn none() -> Self {
+        Self::new(String::new(), String::new())
+    }
+
+    pub fn src_path(&self, file: &Path) -> PathBuf {
+        PathBuf::from(format!("{}{}", self.src, file.display()))
+    }
+
+    pub fn dst_path(&self, file: &Path) -> PathBuf {
+        PathBuf::from(format!("{}{}", self.dst, file.display()))
+    }
+}
+
+/// One side of a file diff
+///
+/// `content` keeps the raw bytes, which need not be UTF-8, for binary
+/// detection and textconv; `data` holds its lines, decoded lossily.
 #[derive(Debug, Clone, new)]
 pub struct DiffTarget<'d> {
     pub(crate) file: PathBuf,
     pub(crate) oid: ObjectId,
     pub(crate) mode: Option<&'d str>,
+    pub(crate) content: Bytes,
     pub(crate) data: LineSet,
 }
 
+/// Lines of raw content, with invalid UTF-8 replaced
+pub fn content_lines(content: &[u8]) -> LineSet {
+    String::from_utf8_lossy(content)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
 impl<'d> DiffTarget<'d> {
     pub fn from_head(
         file: &Path,
@@ -33,15 +79,16 @@ impl<'d> DiffTarget<'d> {
             .map(|entry| {
                 let oid = &entry.oid;
                 let mode = entry.mode.as_str();
-                let blob = database.parse_object_as_blob(oid)?;
-                let blob =
-                    blob.ok_or_else(|| anyhow::anyhow!("File {} not tracked", file.display()))?;
+                let content = database.load_blob_bytes(oid)?;
+                let content = content
+                    .ok_or_else(|| anyhow::anyhow!("File {} not tracked", file.display()))?;
 
                 Ok(Self {
                     file: file.to_path_buf(),
                     oid: oid.clone(),
                     mode: Some(mode),
-                    data: blob.content().lines().map(|s| s.to_string()).collect(),
+                    data: content_lines(&content),
+                    content,
                 })
             })
             .unwrap_or_else(|| anyhow::bail!("File {} not tracked", file.display()))
@@ -57,15 +104,16 @@ impl<'d> DiffTarget<'d> {
             .map(|entry| {
                 let oid = &entry.oid;
                 let mode = entry.metadata.mode.as_str();
-                let blob = database.parse_object_as_blob(oid)?;
-                let blob =
-                    blob.ok_or_else(|| anyhow::anyhow!("File {} not tracked", file.display()))?;
+                let content = database.load_blob_bytes(oid)?;
+                let content = content
+                    .ok_or_else(|| anyhow::anyhow!("File {} not tracked", file.display()))?;
 
                 Ok(Self {
                     file: file.to_path_buf(),
                     oid: oid.clone(),
                     mode: Some(mode),
-                    data: blob.content().lines().map(|s| s.to_string()).collect(),
+                    data: content_lines(&content),
+                    content,
                 })
             })
             .unwrap_or_else(|| anyhow::bail!("File {} not tracked", file.display()))
@@ -82,14 +130,15 @@ impl<'d> DiffTarget<'d> {
         index.entry_by_path_and_stage(file, stage).map(|entry| {
             let oid = &entry.oid;
             let mode = entry.metadata.mode.as_str();
-            let blob = database.parse_object_as_blob(oid)?;
-            let blob =
-                blob.ok_or_else(|| anyhow::anyhow!("Blob not found for {}", file.display()))?;
+            let content = database.load_blob_bytes(oid)?;
+            let content =
+                content.ok_or_else(|| anyhow::anyhow!("Blob not found for {}", file.display()))?;
             Ok(Self {
                 file: file.to_path_buf(),
                 oid: oid.clone(),
                 mode: Some(mode),
-                data: blob.content().lines().map(|s| s.to_string()).collect(),
+                data: content_lines(&content),
+                content,
             })
         })
     }
@@ -99,8 +148,8 @@ impl<'d> DiffTarget<'d> {
         workspace: &Workspace,
         file_stats: &'d FileStatSet,
     ) -> anyhow::Result<Self> {
-        let blob = workspace.parse_blob(file)?;
-        let oid = blob.object_id()?;
+        let content = Bytes::from(workspace.read_file_bytes(file)?);
+        let oid = Database::blob_id(&content)?;
         let mode = file_stats
             .get(file)
             .ok_or_else(|| anyhow::anyhow!("File {} not tracked", file.display()))?
@@ -111,23 +160,40 @@ impl<'d> DiffTarget<'d> {
             file: file.to_path_buf(),
             oid,
             mode: Some(mode),
-            data: blob.content().lines().map(|s| s.to_string()).collect(),
+            data: content_lines(&content),
+            content,
         })
     }
 
+    /// Target for a tree entry
+    ///
+    /// A submodule's commit is not in this repository's database, so a gitlink
+    /// is shown as the single line `Subproject commit <oid>`, like git does.
     pub fn from_entry(
         file: &Path,
         entry: Option<&'d DatabaseEntry>,
         database: &'d Database,
     ) -> anyhow::Result<Self> {
         if let Some(entry) = entry
-            && let Some(blob) = database.parse_object_as_blob(&entry.oid)?
+            && entry.is_gitlink()
+        {
+            let content = Bytes::from(format!("Subproject commit {}\n", entry.oid));
+            Ok(Self {
+                file: file.to_path_buf(),
+                oid: entry.oid.clone(),
+                mode: Some(entry.mode.as_str()),
+                data: content_lines(&content),
+                content,
+            })
+        } else if let Some(entry) = entry
+            && let Some(content) = database.load_blob_bytes(&entry.oid)?
         {
             Ok(Self {
                 file: file.to_path_buf(),
                 oid: entry.oid.clone(),
                 mode: Some(entry.mode.as_str()),
-                data: blob.content().lines().map(|s| s.to_string()).collect(),
+                data: content_lines(&content),
+                content,
             })
         } else {
             Self::from_nothing(file)
@@ -139,6 +205,7 @@ impl<'d> DiffTarget<'d> {
             file: file.to_path_buf(),
             oid: ObjectId::try_parse(NULL_OID_RAW.to_string())?,
             mode: None,
+            content: Bytes::new(),
             data: Vec::new(),
         })
     }
@@ -0,0 +1,238 @@
+//! External diff programs
+//!
+//! When `GIT_EXTERNAL_DIFF` or `diff.external` names a program, each changed
+//! file is handed to it instead of being diffed internally. The program is
+//! run through the shell with seven arguments:
+//!
+//! ```text
+//! <path> <old-file> <old-hex> <old-mode> <new-file> <new-hex> <new-mode>
+//! ```
+//!
+//! `<old-file>` and `<new-file>` hold the content of each side, usually in
+//! temporary files. A side where the path does not exist is passed as
+//! `/dev/null` with `.` as its object ID and mode.
+
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Config key of the external diff program
+pub const DIFF_EXTERNAL_KEY: &str = "diff.external";
+/// Environment variable naming the external diff program, over the config
+pub const EXTERNAL_DIFF_ENV: &str = "GIT_EXTERNAL_DIFF";
+
+const NULL_PATH: &str = "/dev/null";
+const MISSING_FIELD: &str = ".";
+
+/// Temporary files may hold private content, so only the owner reads them
+const TEMP_FILE_MODE: u32 = 0o600;
+
+/// Distinguishes the temporary files of one process
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExternalDiffError {
+    #[error("failed to write temporary file {path} for the external diff: {error}")]
+    TempFile { path: String, error: std::io::Error },
+    #[error("failed to start the external diff command '{command}'")]
+    Spawn {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("the external diff command '{command}' failed")]
+    Failed { command: String },
+}
+
+/// Content of one side of an external diff
+pub enum ExternalDiffFile {
+    /// The path does not exist on this side
+    Missing,
+    /// An existing file, such as the path in the workspace
+    Existing(PathBuf),
+    /// A temporary copy of a blob, removed when dropped
+    Temporary(PathBuf),
+}
+
+impl ExternalDiffFile {
+    /// Write `content` to a new temporary file named after `path`
+    pub fn temporary(path: &Path, content: &[u8]) -> Result<Self, ExternalDiffError> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        // The program runs in the workspace, so a relative `TMPDIR` would
+        // point somewhere else by then
+        let temp_dir = std::env::temp_dir()
+            .canonicalize()
+            .unwrap_or_else(|_| PathBuf::from("/tmp"));
+        loop {
+            let temp_path = temp_dir.join(format!(
+                "bit-diff-{}-{}-{}",
+                std::process::id(),
+                TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed),
+                name
+            ));
+            let temp_error = |error| ExternalDiffError::TempFile {
+                path: temp_path.display().to_string(),
+                error,
+            };
+            // The name is predictable, so never write through a file or
+            // symlink that is already there
+            let mut file = match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .mode(TEMP_FILE_MODE)
+                .open(&temp_path)
+            {
+                Ok(file) => file,
+                Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(temp_error(error)),
+            };
+            // Dropping the half-written file on failure removes it
+            let temp_file = ExternalDiffFile::Temporary(temp_path.clone());
+            file.write_all(content).map_err(temp_error)?;
+
+            return Ok(temp_file);
+        }
+    }
+
+    fn path(&self) -> &Path {
+        match self {
+            ExternalDiffFile::Missing => Path::new(NULL_PATH),
+            ExternalDiffFile::Existing(path) | ExternalDiffFile::Temporary(path) => path,
+        }
+    }
+}
+
+impl Drop for ExternalDiffFile {
+    fn drop(&mut self) {
+        if let ExternalDiffFile::Temporary(path) = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// One side of an external diff: its content, object ID and mode
+pub struct ExternalDiffSide<'a> {
+    pub file: ExternalDiffFile,
+    pub oid: &'a str,
+    pub mode: Option<&'a str>,
+}
+
+impl ExternalDiffSide<'_> {
+    fn args(&self) -> [String; 3] {
+        match (&self.file, self.mode) {
+            (ExternalDiffFile::Missing, _) | (_, None) => [
+                NULL_PATH.to_string(),
+                MISSING_FIELD.to_string(),
+                MISSING_FIELD.to_string(),
+            ],
+            (file, Some(mode)) => [
+                file.path().display().to_string(),
+                self.oid.to_string(),
+                mode.to_string(),
+            ],
+        }
+    }
+}
+
+/// Run `command` through the shell on the two sides of `path` and return
+/// what it prints
+pub fn external_diff(
+    command: &str,
+    path: &Path,
+    old: &ExternalDiffSide,
+    new: &ExternalDiffSide,
+) -> Result<Vec<u8>, ExternalDiffError> {
+    // The command may carry its own arguments, so the seven are appended
+    // through "$@" rather than to the command string
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", command))
+        .arg(command)
+        .arg(path)
+        .args(old.args())
+        .args(new.args())
+        .output()
+        .map_err(|source| ExternalDiffError::Spawn {
+            command: command.to_string(),
+            source,
+        })?;
+
+    if !output.status.success() {
+        return Err(ExternalDiffError::Failed {
+            command: command.to_string(),
+        });
+    }
+
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_passes_seven_arguments() {
+        let old = ExternalDiffSide {
+            file: ExternalDiffFile::temporary(Path::new("a.txt"), b"one\n").unwrap(),
+            oid: "1111111111111111111111111111111111111111",
+            mode: Some("100644"),
+        };
+        let new = ExternalDiffSide {
+            file: ExternalDiffFile::Missing,
+            oid: "0000000000000000000000000000000000000000",
+            mode: None,
+        };
+        let old_path = old.file.path().display().to_string();
+
+        let output = external_diff("printf '%s|'", Path::new("a.txt"), &old, &new).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "a.txt|{}|{}|100644|/dev/null|.|.|",
+                old_path, "1111111111111111111111111111111111111111"
+            )
+        );
+    }
+
+    #[test]
+    fn test_temporary_files_are_removed_when_dropped() {
+        let file = ExternalDiffFile::temporary(Path::new("a.txt"), b"one\n").unwrap();
+        let path = file.path().to_path_buf();
+        assert_eq!(std::fs::read(&path).unwrap(), b"one\n");
+
+        drop(file);
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_temporary_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let file = ExternalDiffFile::temporary(Path::new("a.txt"), b"one\n").unwrap();
+        let mode = std::fs::metadata(file.path()).unwrap().permissions().mode();
+
+        assert_eq!(mode & 0o777, TEMP_FILE_MODE);
+    }
+
+    #[test]
+    fn test_failing_command_is_reported() {
+        let side = || ExternalDiffSide {
+            file: ExternalDiffFile::Missing,
+            oid: ".",
+            mode: None,
+        };
+
+        assert!(matches!(
+            external_diff("false", Path::new("a.txt"), &side(), &side()),
+            Err(ExternalDiffError::Failed { .. })
+        ));
+    }
+}
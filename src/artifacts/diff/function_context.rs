@@ -0,0 +1,176 @@
+//! Function context of hunk headers
+//!
+//! Like Git, hunk headers name the section a change belongs to:
+//!
+//! ```text
+//! @@ -10,7 +10,9 @@ fn main() {
+//! ```
+//!
+//! The context is the nearest line before the hunk, in the old version,
+//! that looks like the start of a function or section. By default that is a
+//! line starting with a letter, `_` or `$`. A path whose `diff` attribute
+//! names a driver with a `diff.<driver>.xfuncname` regex uses it instead:
+//! one pattern per line, where patterns starting with `!` reject the lines
+//! they match. The first capture group (or the whole match) is shown.
+
+use regex::Regex;
+
+/// Longest context shown, in bytes, as in Git
+const MAX_CONTEXT_LENGTH: usize = 80;
+
+#[derive(Debug, thiserror::Error)]
+pub enum FunctionContextError {
+    #[error("invalid regex '{pattern}' in '{key}'")]
+    InvalidPattern {
+        key: String,
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// Config key of the function-name regex of a diff driver
+pub fn xfuncname_key(driver: &str) -> String {
+    format!("diff.{}.xfuncname", driver)
+}
+
+/// How the lines starting a function or section are recognized
+#[derive(Debug, Default)]
+pub enum FunctionMatcher {
+    /// Lines starting with a letter, `_` or `$`
+    #[default]
+    Default,
+    /// The patterns of an `xfuncname`, with whether each one rejects lines
+    Patterns(Vec<(bool, Regex)>),
+}
+
+impl FunctionMatcher {
+    /// Compile the `xfuncname` value found under `key`
+    pub fn from_xfuncname(key: &str, value: &str) -> Result<Self, FunctionContextError> {
+        value
+            .lines()
+            .map(|pattern| {
+                let (negated, regex) = match pattern.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, pattern),
+                };
+                Regex::new(regex)
+                    .map(|regex| (negated, regex))
+                    .map_err(|source| FunctionContextError::InvalidPattern {
+                        key: key.to_string(),
+                        pattern: pattern.to_string(),
+                        source,
+                    })
+            })
+            .collect::<Result<_, _>>()
+            .map(FunctionMatcher::Patterns)
+    }
+
+    /// Context shown for `line`, if it starts a function or section
+    fn context<'l>(&self, line: &'l str) -> Option<&'l str> {
+        match self {
+            FunctionMatcher::Default => line
+                .starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '$')
+                .then_some(line),
+            FunctionMatcher::Patterns(patterns) => {
+                for (negated, regex) in patterns {
+                    if let Some(captures) = regex.captures(line) {
+                        if *negated {
+                            return None;
+                        }
+                        let matched = captures.get(1).or_else(|| captures.get(0))?;
+                        return Some(matched.as_str());
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Context of a hunk starting at line `start` (1-based) of `lines`
+pub fn function_context(
+    lines: &[String],
+    start: usize,
+    matcher: &FunctionMatcher,
+) -> Option<String> {
+    let before = start.saturating_sub(1).min(lines.len());
+
+    lines[..before]
+        .iter()
+        .rev()
+        .find_map(|line| matcher.context(line))
+        .map(|context| truncate(context).to_string())
+}
+
+/// Cut `context` to at most `MAX_CONTEXT_LENGTH` bytes, on a character
+/// boundary, without trailing whitespace
+fn truncate(context: &str) -> &str {
+    let mut end = context.len().min(MAX_CONTEXT_LENGTH);
+    while !context.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    context[..end].trim_end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn lines(content: &str) -> Vec<String> {
+        content.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_default_finds_nearest_unindented_line() {
+        let content = lines("use std::io;\n\nfn main() {\n    let a = 1;\n\n    let b = 2;\n}\n");
+
+        assert_eq!(
+            function_context(&content, 6, &FunctionMatcher::Default),
+            Some("fn main() {".to_string())
+        );
+        assert_eq!(
+            function_context(&content, 3, &FunctionMatcher::Default),
+            Some("use std::io;".to_string())
+        );
+        assert_eq!(
+            function_context(&content, 1, &FunctionMatcher::Default),
+            None
+        );
+    }
+
+    #[test]
+    fn test_xfuncname_shows_first_group_and_honours_negations() {
+        let matcher =
+            FunctionMatcher::from_xfuncname("diff.py.xfuncname", "!^def _\n^def ([a-z_]+)")
+                .unwrap();
+        let content = lines("def public(x):\n    pass\ndef _private(y):\n    return y\n");
+
+        assert_eq!(
+            function_context(&content, 4, &matcher),
+            Some("public".to_string())
+        );
+    }
+
+    #[test]
+    fn test_context_is_truncated() {
+        let content = vec![
+            format!("fn {}() {{", "a".repeat(100)),
+            "    body".to_string(),
+        ];
+
+        let context = function_context(&content, 3, &FunctionMatcher::Default).unwrap();
+
+        assert_eq!(context.len(), MAX_CONTEXT_LENGTH);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_reported() {
+        assert!(matches!(
+            FunctionMatcher::from_xfuncname("diff.x.xfuncname", "(unclosed"),
+            Err(FunctionContextError::InvalidPattern { .. })
+        ));
+    }
+}
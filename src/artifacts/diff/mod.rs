@@ -2,13 +2,29 @@
 //!
 //! This module implements various diffing algorithms:
 //!
+//! - `combined_diff`: Combined (`--cc`) diff of a merge against all its parents
 //! - `diff_algorithm`: Myers' diff for line-by-line comparison
+//! - `diff_stat`: Per-file line counts for `--stat` summaries
 //! - `diff_target`: Abstraction over diff sources (workspace, index, commits)
+//! - `external_diff`: Handing changed files to an external diff program
+//! - `function_context`: Naming the enclosing function in hunk headers
+//! - `moved_lines`: Detection of lines moved within a file for `--color-moved`
+//! - `patch`: Parsing and applying mail-formatted patches (`format-patch`, `am`)
+//! - `rename_detection`: Pairing deleted and added files into renames and copies
+//! - `textconv`: Converting binary content to text through an external command
 //! - `tree_diff`: Tree-level diffing for detecting file changes
 //!
 //! The diff implementation supports both tree-level (which files changed)
 //! and content-level (what changed within files) comparison.
 
+pub mod combined_diff;
 pub mod diff_algorithm;
+pub mod diff_stat;
 pub mod diff_target;
+pub mod external_diff;
+pub mod function_context;
+pub mod moved_lines;
+pub mod patch;
+pub mod rename_detection;
+pub mod textconv;
 pub mod tree_diff;
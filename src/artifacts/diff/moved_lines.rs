@@ -0,0 +1,300 @@
+//! Moved line detection for `--color-moved`
+//!
+//! Lines deleted in one place of a file and inserted verbatim elsewhere are
+//! usually code that was moved rather than rewritten. Deleted and inserted
+//! lines are indexed by their content; each changed line then either extends
+//! the block of the line before it, when the next line of one of the matched
+//! blocks has the same content, or starts a new block at every matching line
+//! of the opposite side.
+
+use crate::artifacts::diff::diff_algorithm::Edit;
+use clap::ValueEnum;
+use colored::Colorize;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Alphanumeric characters a block needs to count as moved in `blocks` and
+/// `zebra` modes, so that moved braces or blank lines are not highlighted
+const MIN_BLOCK_ALNUM: usize = 20;
+
+/// How moved lines are highlighted
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum ColorMoved {
+    #[value(name = "no", help = "Do not highlight moved lines")]
+    #[default]
+    No,
+    #[value(
+        name = "plain",
+        help = "Highlight every line deleted in one place and inserted in another"
+    )]
+    Plain,
+    #[value(
+        name = "blocks",
+        help = "Highlight blocks of moved lines with at least 20 alphanumeric characters"
+    )]
+    Blocks,
+    #[value(
+        name = "zebra",
+        alias = "default",
+        help = "Like blocks, alternating colors between adjacent blocks"
+    )]
+    Zebra,
+}
+
+/// Highlight of a single diff line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MoveHighlight {
+    /// Not moved: plain deletion, insertion or context
+    #[default]
+    None,
+    Moved,
+    /// Moved, in a block right after another moved block (`zebra` mode)
+    MovedAlternative,
+}
+
+impl MoveHighlight {
+    /// Render an edit with its move color, or its usual color if not moved
+    pub fn paint(&self, edit: &Edit<String>) -> String {
+        let line = edit.as_string();
+        let painted = match (edit, self) {
+            (_, MoveHighlight::None) => return edit.to_string(),
+            (Edit::Delete { .. }, MoveHighlight::Moved) => line.magenta(),
+            (Edit::Delete { .. }, MoveHighlight::MovedAlternative) => line.blue(),
+            (Edit::Insert { .. }, MoveHighlight::Moved) => line.cyan(),
+            (Edit::Insert { .. }, MoveHighlight::MovedAlternative) => line.yellow(),
+            (Edit::Equal { .. }, _) => return edit.to_string(),
+        };
+
+        painted.bold().to_string()
+    }
+}
+
+/// Find the moved lines among the edits of a file
+///
+/// Returns one highlight per edit.
+pub fn detect_moved_lines(edits: &[&Edit<String>], mode: ColorMoved) -> Vec<MoveHighlight> {
+    let mut highlights = vec![MoveHighlight::None; edits.len()];
+    if mode == ColorMoved::No {
+        return highlights;
+    }
+
+    let mut previous: Option<Range<usize>> = None;
+    for block in moved_blocks(edits) {
+        if mode != ColorMoved::Plain && alphanumeric_count(edits, &block) < MIN_BLOCK_ALNUM {
+            continue;
+        }
+
+        let adjacent = previous.as_ref().is_some_and(|previous| {
+            let inserted = |i: usize| side(edits[i]).map(|(inserted, _)| inserted);
+            previous.end == block.start && inserted(previous.start) == inserted(block.start)
+        });
+        let highlight = match previous.as_ref().map(|previous| highlights[previous.start]) {
+            Some(MoveHighlight::Moved) if adjacent && mode == ColorMoved::Zebra => {
+                MoveHighlight::MovedAlternative
+            }
+            _ => MoveHighlight::Moved,
+        };
+
+        highlights[block.clone()].fill(highlight);
+        previous = Some(block);
+    }
+
+    highlights
+}
+
+/// Side and content of a changed line: `Some((true, line))` for insertions,
+/// `Some((false, line))` for deletions and `None` for context
+fn side(edit: &Edit<String>) -> Option<(bool, &str)> {
+    match edit {
+        Edit::Delete { line } => Some((false, line.value())),
+        Edit::Insert { line } => Some((true, line.value())),
+        Edit::Equal { .. } => None,
+    }
+    .map(|(inserted, line)| (inserted, line.as_str()))
+}
+
+/// Ranges of consecutive edits, all deletions or all insertions, whose lines
+/// appear in the same order on the opposite side
+fn moved_blocks(edits: &[&Edit<String>]) -> Vec<Range<usize>> {
+    let mut lines: HashMap<(bool, &str), Vec<usize>> = HashMap::new();
+    for (i, edit) in edits.iter().enumerate() {
+        if let Some(key) = side(edit) {
+            lines.entry(key).or_default().push(i);
+        }
+    }
+
+    let mut blocks: Vec<Range<usize>> = Vec::new();
+    // Edits of the opposite side matching the last line of the current block
+    let mut candidates: Vec<usize> = Vec::new();
+    for (i, edit) in edits.iter().enumerate() {
+        let Some((inserted, line)) = side(edit) else {
+            candidates.clear();
+            continue;
+        };
+
+        let extends_block = blocks.last().is_some_and(|block| block.end == i)
+            && side(edits[i - 1]).is_some_and(|(previous, _)| previous == inserted);
+        if extends_block {
+            let next = candidates
+                .iter()
+                .map(|candidate| candidate + 1)
+                .filter(|&candidate| {
+                    edits
+                        .get(candidate)
+                        .and_then(|edit| side(edit))
+                        .is_some_and(|key| key == (!inserted, line))
+                })
+                .collect::<Vec<_>>();
+            if !next.is_empty() {
+                candidates = next;
+                if let Some(block) = blocks.last_mut() {
+                    block.end = i + 1;
+                }
+                continue;
+            }
+        }
+
+        match lines.get(&(!inserted, line)) {
+            Some(matches) => {
+                candidates = matches.clone();
+                blocks.push(i..i + 1);
+            }
+            None => candidates.clear(),
+        }
+    }
+
+    blocks
+}
+
+fn alphanumeric_count(edits: &[&Edit<String>], block: &Range<usize>) -> usize {
+    edits[block.clone()]
+        .iter()
+        .filter_map(|edit| side(edit))
+        .map(|(_, line)| line.chars().filter(|c| c.is_alphanumeric()).count())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::diff::diff_algorithm::Line;
+    use pretty_assertions::assert_eq;
+
+    fn delete(number: usize, value: &str) -> Edit<String> {
+        Edit::Delete {
+            line: Line::new(number, value.to_string()),
+        }
+    }
+
+    fn insert(number: usize, value: &str) -> Edit<String> {
+        Edit::Insert {
+            line: Line::new(number, value.to_string()),
+        }
+    }
+
+    fn equal(number: usize, value: &str) -> Edit<String> {
+        Edit::Equal {
+            line_a: Line::new(number, value.to_string()),
+            line_b: Line::new(number, value.to_string()),
+        }
+    }
+
+    fn detect(edits: &[Edit<String>], mode: ColorMoved) -> Vec<MoveHighlight> {
+        detect_moved_lines(&edits.iter().collect::<Vec<_>>(), mode)
+    }
+
+    #[test]
+    fn test_block_moved_below_context_is_highlighted_on_both_sides() {
+        let edits = [
+            delete(1, "fn moved_function() {"),
+            delete(2, "    do_something();"),
+            equal(3, "fn other() {}"),
+            insert(2, "fn moved_function() {"),
+            insert(3, "    do_something();"),
+        ];
+
+        let highlights = detect(&edits, ColorMoved::Blocks);
+
+        assert_eq!(
+            highlights,
+            vec![
+                MoveHighlight::Moved,
+                MoveHighlight::Moved,
+                MoveHighlight::None,
+                MoveHighlight::Moved,
+                MoveHighlight::Moved,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rewritten_lines_are_not_moved() {
+        let edits = [delete(1, "let answer = 41;"), insert(1, "let answer = 42;")];
+
+        assert_eq!(
+            detect(&edits, ColorMoved::Zebra),
+            vec![MoveHighlight::None, MoveHighlight::None]
+        );
+    }
+
+    #[test]
+    fn test_short_blocks_are_only_highlighted_in_plain_mode() {
+        let edits = [delete(1, "}"), equal(2, "x"), insert(2, "}")];
+
+        assert_eq!(
+            detect(&edits, ColorMoved::Blocks),
+            vec![MoveHighlight::None; 3]
+        );
+        assert_eq!(
+            detect(&edits, ColorMoved::Plain),
+            vec![
+                MoveHighlight::Moved,
+                MoveHighlight::None,
+                MoveHighlight::Moved
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zebra_alternates_between_adjacent_blocks() {
+        // Two blocks swapped places: inserted in the opposite order
+        let edits = [
+            delete(1, "first block of moved text"),
+            delete(2, "second block of moved text"),
+            equal(3, "anchor"),
+            insert(2, "second block of moved text"),
+            insert(3, "first block of moved text"),
+        ];
+
+        assert_eq!(
+            detect(&edits, ColorMoved::Zebra),
+            vec![
+                MoveHighlight::Moved,
+                MoveHighlight::MovedAlternative,
+                MoveHighlight::None,
+                MoveHighlight::Moved,
+                MoveHighlight::MovedAlternative,
+            ]
+        );
+        assert_eq!(
+            detect(&edits, ColorMoved::Blocks),
+            vec![
+                MoveHighlight::Moved,
+                MoveHighlight::Moved,
+                MoveHighlight::None,
+                MoveHighlight::Moved,
+                MoveHighlight::Moved,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_mode_highlights_nothing() {
+        let edits = [
+            delete(1, "a line that was moved away"),
+            insert(5, "a line that was moved away"),
+        ];
+
+        assert_eq!(detect(&edits, ColorMoved::No), vec![MoveHighlight::None; 2]);
+    }
+}
@@ -0,0 +1,615 @@
+//! Mail-formatted patches
+//!
+//! Parses the mailboxes written by `format-patch` back into commits (author,
+//! subject, body) and per-file unified diffs, and applies those diffs to file
+//! contents.
+//!
+//! ## Format
+//!
+//! ```text
+//! From <oid> Mon Sep 17 00:00:00 2001
+//! From: <name> <email>
+//! Date: <RFC 2822 date>
+//! Subject: [PATCH] <subject>
+//!
+//! <body>
+//! ---
+//! diff --git a/<path> b/<path>
+//! ...
+//! --
+//! <version>
+//! ```
+//!
+//! Plain diffs, as written by `diff`, are parsed the same way, without the
+//! mail headers. Paths in the diff are expected to carry a one-component
+//! prefix (`a/`, `b/`), like `git apply -p1`.
+
+use crate::artifacts::index::entry_mode::EntryMode;
+use crate::artifacts::objects::commit::Author;
+use std::path::PathBuf;
+
+const MBOX_FROM_REGEX: &str = r"^From [0-9a-f]{40} ";
+const SUBJECT_PREFIX_REGEX: &str = r"^\[PATCH[^\]]*\]\s*";
+const HUNK_HEADER_REGEX: &str = r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@";
+const NULL_PATH: &str = "/dev/null";
+
+#[derive(Debug, thiserror::Error)]
+pub enum PatchError {
+    #[error("no patches found")]
+    Empty,
+    #[error("missing {0} header")]
+    MissingHeader(&'static str),
+    #[error("invalid {header} header: {value}")]
+    InvalidHeader { header: &'static str, value: String },
+    #[error("malformed patch: {0}")]
+    Malformed(String),
+    #[error("{path}: already exists in working directory")]
+    AlreadyExists { path: String },
+    #[error("{path}: does not exist in working directory")]
+    DoesNotExist { path: String },
+    #[error("{path}: patch does not apply at {hunk}")]
+    HunkFailed { path: String, hunk: String },
+    #[error(transparent)]
+    Regex(#[from] regex::Error),
+}
+
+/// One commit of a mailbox
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub author: Author,
+    pub subject: String,
+    pub body: String,
+    pub files: Vec<FilePatch>,
+}
+
+/// How a file patch changes its file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilePatchKind {
+    Added,
+    Deleted,
+    Modified,
+}
+
+/// The changes a patch makes to a single file
+#[derive(Debug, Clone)]
+pub struct FilePatch {
+    pub path: PathBuf,
+    pub kind: FilePatchKind,
+    /// Mode of the file after the patch, when the patch records one
+    pub new_mode: Option<EntryMode>,
+    /// Abbreviated IDs of the blobs before and after the patch, from its
+    /// `index <old>..<new>` line
+    pub blob_ids: Option<(String, String)>,
+    pub hunks: Vec<PatchHunk>,
+}
+
+/// A `@@ -a,b +c,d @@` block of context, removed and added lines
+#[derive(Debug, Clone)]
+pub struct PatchHunk {
+    header: String,
+    old_start: usize,
+    /// Lines with their leading ` `, `-` or `+` marker
+    lines: Vec<(char, String)>,
+}
+
+impl Mail {
+    /// Split a mailbox into its mails
+    ///
+    /// Mails start at `From <oid> ` lines; a file without them is read as a
+    /// single mail.
+    pub fn parse_mailbox(content: &str) -> Result<Vec<Mail>, PatchError> {
+        let from_regex = regex::Regex::new(MBOX_FROM_REGEX)?;
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+        let mut mails = Vec::new();
+        let mut current = Vec::new();
+        for line in content.lines().map(|line| line.trim_end_matches('\r')) {
+            if from_regex.is_match(line) {
+                if !current.is_empty() {
+                    mails.push(Self::parse(&current)?);
+                }
+                current.clear();
+            } else {
+                current.push(line);
+            }
+        }
+        if current.iter().any(|line| !line.trim().is_empty()) {
+            mails.push(Self::parse(&current)?);
+        }
+
+        if mails.is_empty() {
+            return Err(PatchError::Empty);
+        }
+
+        Ok(mails)
+    }
+
+    /// Commit message: the subject, then the body as its own paragraph
+    pub fn message(&self) -> String {
+        if self.body.is_empty() {
+            self.subject.clone()
+        } else {
+            format!("{}\n\n{}", self.subject, self.body)
+        }
+    }
+
+    fn parse(lines: &[&str]) -> Result<Self, PatchError> {
+        let header_end = lines
+            .iter()
+            .position(|line| line.is_empty())
+            .unwrap_or(lines.len());
+        let headers = Self::parse_headers(&lines[..header_end]);
+        let header = |name: &'static str| {
+            headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+                .ok_or(PatchError::MissingHeader(name))
+        };
+
+        let author = Self::parse_author(header("From")?, header("Date")?)?;
+        let subject = regex::Regex::new(SUBJECT_PREFIX_REGEX)?
+            .replace(header("Subject")?, "")
+            .to_string();
+
+        // The body runs up to the `---` separator (or straight into the diff)
+        let rest = lines.get(header_end + 1..).unwrap_or_default();
+        let body_end = rest
+            .iter()
+            .position(|line| *line == "---" || line.starts_with("diff --git "))
+            .unwrap_or(rest.len());
+        let body = rest[..body_end].join("\n").trim().to_string();
+        let files = FilePatch::parse_all(&rest[body_end..])?;
+
+        Ok(Mail {
+            author,
+            subject,
+            body,
+            files,
+        })
+    }
+
+    /// Collect `Name: value` headers, joining folded continuation lines
+    fn parse_headers(lines: &[&str]) -> Vec<(String, String)> {
+        let mut headers: Vec<(String, String)> = Vec::new();
+
+        for line in lines {
+            if line.starts_with([' ', '\t']) {
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            } else if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_string(), value.trim().to_string()));
+            }
+        }
+
+        headers
+    }
+
+    fn parse_author(from: &str, date: &str) -> Result<Author, PatchError> {
+        let invalid_from = || PatchError::InvalidHeader {
+            header: "From",
+            value: from.to_string(),
+        };
+        let (name, email) = from.rsplit_once('<').ok_or_else(invalid_from)?;
+        let email = email.strip_suffix('>').ok_or_else(invalid_from)?;
+        let timestamp =
+            chrono::DateTime::parse_from_rfc2822(date).map_err(|_| PatchError::InvalidHeader {
+                header: "Date",
+                value: date.to_string(),
+            })?;
+
+        Ok(Author::new_with_timestamp(
+            name.trim().trim_matches('"').to_string(),
+            email.to_string(),
+            timestamp,
+        ))
+    }
+}
+
+impl FilePatch {
+    /// Parse the file sections of a plain diff
+    pub fn parse_patch(content: &str) -> Result<Vec<FilePatch>, PatchError> {
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+        let lines = content
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .collect::<Vec<_>>();
+
+        let files = Self::parse_all(&lines)?;
+        if files.is_empty() {
+            return Err(PatchError::Empty);
+        }
+
+        Ok(files)
+    }
+
+    /// Parse every `diff --git` section, ignoring anything around them
+    /// (diffstat, signature)
+    fn parse_all(lines: &[&str]) -> Result<Vec<FilePatch>, PatchError> {
+        let mut files = Vec::new();
+        let mut position = 0;
+
+        while position < lines.len() {
+            if lines[position].starts_with("diff --git ") {
+                let (file, consumed) = Self::parse(&lines[position..])?;
+                files.push(file);
+                position += consumed;
+            } else {
+                position += 1;
+            }
+        }
+
+        Ok(files)
+    }
+
+    /// Parse one file section, returning it with the number of lines used
+    fn parse(lines: &[&str]) -> Result<(FilePatch, usize), PatchError> {
+        let header_regex = regex::Regex::new(HUNK_HEADER_REGEX)?;
+        let git_line = lines[0].trim_start_matches("diff --git ");
+        let mut path = git_line
+            .rsplit_once(' ')
+            .map(|(_, new_path)| strip_path_prefix(new_path))
+            .ok_or_else(|| PatchError::Malformed(lines[0].to_string()))?;
+        let mut kind = FilePatchKind::Modified;
+        let mut new_mode = None;
+        let mut blob_ids = None;
+        let mut hunks = Vec::new();
+
+        let mut position = 1;
+        while position < lines.len() {
+            let line = lines[position];
+            if line.starts_with("diff --git ") {
+                break;
+            } else if let Some(mode) = line.strip_prefix("new file mode ") {
+                kind = FilePatchKind::Added;
+                new_mode = Some(parse_mode(mode)?);
+            } else if line.starts_with("deleted file mode ") {
+                kind = FilePatchKind::Deleted;
+            } else if let Some(mode) = line.strip_prefix("new mode ") {
+                new_mode = Some(parse_mode(mode)?);
+            } else if let Some(range) = line.strip_prefix("index ") {
+                let range = range.split_once(' ').map_or(range, |(range, _)| range);
+                blob_ids = range
+                    .split_once("..")
+                    .map(|(old, new)| (old.to_string(), new.to_string()));
+            } else if let Some(old_path) = line.strip_prefix("--- ") {
+                // Without mode lines, `/dev/null` alone tells creations and
+                // deletions apart
+                if old_path == NULL_PATH {
+                    kind = FilePatchKind::Added;
+                } else {
+                    path = strip_path_prefix(old_path);
+                }
+            } else if let Some(new_path) = line.strip_prefix("+++ ") {
+                if new_path == NULL_PATH {
+                    kind = FilePatchKind::Deleted;
+                } else {
+                    path = strip_path_prefix(new_path);
+                }
+            } else if let Some(captures) = header_regex.captures(line) {
+                let number = |i: usize| {
+                    captures
+                        .get(i)
+                        .map_or(Ok(1), |m| m.as_str().parse::<usize>())
+                        .map_err(|_| PatchError::Malformed(line.to_string()))
+                };
+                let (old_start, old_size, new_size) = (number(1)?, number(2)?, number(4)?);
+
+                let (hunk, consumed) = PatchHunk::parse(
+                    line,
+                    old_start,
+                    (old_size, new_size),
+                    &lines[position + 1..],
+                )?;
+                hunks.push(hunk);
+                position += consumed;
+            } else if line == "-- " {
+                // Mail signature
+                break;
+            }
+            position += 1;
+        }
+
+        Ok((
+            FilePatch {
+                path,
+                kind,
+                new_mode,
+                blob_ids,
+                hunks,
+            },
+            position,
+        ))
+    }
+
+    /// Apply the patch to a file's current content
+    ///
+    /// Returns the new content, or `None` when the patch deletes the file.
+    /// Hunks are first tried at their recorded line and otherwise at the
+    /// closest place their context matches. The diff does not record missing
+    /// final newlines, so a modified file keeps its own and new files get one.
+    pub fn apply(&self, original: Option<&str>) -> Result<Option<String>, PatchError> {
+        let path = self.path.display().to_string();
+        let original = match (self.kind, original) {
+            (FilePatchKind::Added, Some(_)) => return Err(PatchError::AlreadyExists { path }),
+            (FilePatchKind::Added, None) => "",
+            (_, None) => return Err(PatchError::DoesNotExist { path }),
+            (_, Some(original)) => original,
+        };
+
+        let mut lines = original.lines().map(str::to_string).collect::<Vec<_>>();
+        let mut shift = 0isize;
+        for hunk in &self.hunks {
+            let old = hunk.old_lines();
+            let new = hunk.new_lines();
+            let expected = if old.is_empty() {
+                hunk.old_start
+            } else {
+                hunk.old_start.saturating_sub(1)
+            };
+            let expected = (expected as isize + shift).max(0) as usize;
+
+            let start =
+                find_lines(&lines, &old, expected).ok_or_else(|| PatchError::HunkFailed {
+                    path: path.clone(),
+                    hunk: hunk.header.clone(),
+                })?;
+            lines.splice(
+                start..start + old.len(),
+                new.iter().map(|line| line.to_string()),
+            );
+            shift += new.len() as isize - old.len() as isize;
+        }
+
+        if self.kind == FilePatchKind::Deleted {
+            return if lines.is_empty() {
+                Ok(None)
+            } else {
+                Err(PatchError::HunkFailed {
+                    path,
+                    hunk: "end of file".to_string(),
+                })
+            };
+        }
+
+        let mut content = lines.join("\n");
+        if !content.is_empty() && (original.is_empty() || original.ends_with('\n')) {
+            content.push('\n');
+        }
+
+        Ok(Some(content))
+    }
+}
+
+impl PatchHunk {
+    /// Read hunk lines until both sides reach the sizes in the header
+    fn parse(
+        header: &str,
+        old_start: usize,
+        (old_size, new_size): (usize, usize),
+        lines: &[&str],
+    ) -> Result<(PatchHunk, usize), PatchError> {
+        let mut hunk_lines = Vec::new();
+        let (mut old_seen, mut new_seen) = (0, 0);
+        let mut consumed = 0;
+
+        for line in lines {
+            if old_seen >= old_size && new_seen >= new_size {
+                break;
+            }
+            consumed += 1;
+
+            // Mailers may strip the trailing space of empty context lines
+            let (marker, text) = match line.chars().next() {
+                Some(marker @ (' ' | '-' | '+')) => (marker, &line[1..]),
+                None => (' ', ""),
+                Some('\\') => continue,
+                Some(_) => return Err(PatchError::Malformed(line.to_string())),
+            };
+            match marker {
+                '-' => old_seen += 1,
+                '+' => new_seen += 1,
+                _ => {
+                    old_seen += 1;
+                    new_seen += 1;
+                }
+            }
+            hunk_lines.push((marker, text.to_string()));
+        }
+
+        if old_seen != old_size || new_seen != new_size {
+            return Err(PatchError::Malformed(format!("truncated hunk {}", header)));
+        }
+
+        Ok((
+            PatchHunk {
+                header: header.to_string(),
+                old_start,
+                lines: hunk_lines,
+            },
+            consumed,
+        ))
+    }
+
+    fn old_lines(&self) -> Vec<&str> {
+        self.side_lines('+')
+    }
+
+    fn new_lines(&self) -> Vec<&str> {
+        self.side_lines('-')
+    }
+
+    /// Lines of one side of the hunk: every line without the other side's marker
+    fn side_lines(&self, excluded: char) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter(|(marker, _)| *marker != excluded)
+            .map(|(_, line)| line.as_str())
+            .collect()
+    }
+}
+
+/// Find where `needle` occurs in `lines`, preferring the spot closest to
+/// `expected`
+fn find_lines(lines: &[String], needle: &[&str], expected: usize) -> Option<usize> {
+    if needle.len() > lines.len() {
+        return None;
+    }
+    let last_start = lines.len() - needle.len();
+    let matches_at = |start: usize| {
+        lines[start..start + needle.len()]
+            .iter()
+            .zip(needle)
+            .all(|(line, expected)| line == expected)
+    };
+
+    let expected = expected.min(last_start);
+    (0..=last_start)
+        .flat_map(|distance| {
+            [
+                expected.checked_sub(distance),
+                expected.checked_add(distance),
+            ]
+        })
+        .flatten()
+        .filter(|start| *start <= last_start)
+        .find(|start| matches_at(*start))
+}
+
+/// Drop the `a/` or `b/` prefix of a diff path
+fn strip_path_prefix(path: &str) -> PathBuf {
+    PathBuf::from(path.split_once('/').map_or(path, |(_, rest)| rest))
+}
+
+fn parse_mode(mode: &str) -> Result<EntryMode, PatchError> {
+    EntryMode::from_octal_str(mode.trim()).map_err(|_| PatchError::InvalidHeader {
+        header: "mode",
+        value: mode.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const MAILBOX: &str = "From 1234567890123456789012345678901234567890 Mon Sep 17 00:00:00 2001
+From: Jane Doe <jane@example.com>
+Date: Sun, 1 Jan 2023 12:00:00 +0000
+Subject: [PATCH] Update notes
+
+Explain the change.
+---
+diff --git a/notes.txt b/notes.txt
+index 1111111..2222222 100644
+--- a/notes.txt
++++ b/notes.txt
+@@ -1,3 +1,3 @@
+ first
+-second
++second, revised
+ third
+diff --git a/new.txt b/new.txt
+new file mode 100755
+index 0000000..3333333
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1,1 @@
++new
+-- 
+bit 0.1.0
+
+";
+
+    #[test]
+    fn test_parse_mailbox() {
+        let mails = Mail::parse_mailbox(MAILBOX).unwrap();
+
+        assert_eq!(mails.len(), 1);
+        let mail = &mails[0];
+        assert_eq!(mail.author.display_name(), "Jane Doe <jane@example.com>");
+        assert_eq!(mail.author.timestamp().timestamp(), 1672574400);
+        assert_eq!(mail.message(), "Update notes\n\nExplain the change.");
+        assert_eq!(mail.files.len(), 2);
+        assert_eq!(mail.files[0].path, PathBuf::from("notes.txt"));
+        assert_eq!(mail.files[0].kind, FilePatchKind::Modified);
+        assert_eq!(mail.files[1].path, PathBuf::from("new.txt"));
+        assert_eq!(mail.files[1].kind, FilePatchKind::Added);
+        assert_eq!(
+            mail.files[1].new_mode,
+            Some(EntryMode::from_octal_str("100755").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_index_line_records_blob_ids() {
+        let mails = Mail::parse_mailbox(MAILBOX).unwrap();
+
+        assert_eq!(
+            mails[0].files[0].blob_ids,
+            Some(("1111111".to_string(), "2222222".to_string()))
+        );
+        assert_eq!(
+            mails[0].files[1].blob_ids,
+            Some(("0000000".to_string(), "3333333".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_apply_hunks_with_shifted_context() {
+        let mails = Mail::parse_mailbox(MAILBOX).unwrap();
+        let notes = &mails[0].files[0];
+
+        let patched = notes.apply(Some("zeroth\nfirst\nsecond\nthird\n")).unwrap();
+        assert_eq!(
+            patched.as_deref(),
+            Some("zeroth\nfirst\nsecond, revised\nthird\n")
+        );
+
+        let new = &mails[0].files[1];
+        assert_eq!(new.apply(None).unwrap().as_deref(), Some("new\n"));
+    }
+
+    #[test]
+    fn test_apply_fails_when_context_is_missing() {
+        let mails = Mail::parse_mailbox(MAILBOX).unwrap();
+
+        assert!(matches!(
+            mails[0].files[0].apply(Some("first\nother\nthird\n")),
+            Err(PatchError::HunkFailed { .. })
+        ));
+        assert!(matches!(
+            mails[0].files[1].apply(Some("new\n")),
+            Err(PatchError::AlreadyExists { .. })
+        ));
+    }
+
+    #[test]
+    fn test_dev_null_marks_deletions_without_mode_lines() {
+        let files = FilePatch::parse_patch(
+            "diff --git a/old.txt b/old.txt
+--- a/old.txt
++++ /dev/null
+@@ -1,2 +0,0 @@
+-one
+-two
+",
+        )
+        .unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, PathBuf::from("old.txt"));
+        assert_eq!(files[0].kind, FilePatchKind::Deleted);
+        assert_eq!(files[0].apply(Some("one\ntwo\n")).unwrap(), None);
+        assert!(matches!(
+            files[0].apply(Some("one\ntwo\nthree\n")),
+            Err(PatchError::HunkFailed { .. })
+        ));
+        assert!(matches!(
+            FilePatch::parse_patch("no diff here\n"),
+            Err(PatchError::Empty)
+        ));
+    }
+}
@@ -0,0 +1,421 @@
+//! Rename and copy detection
+//!
+//! A deleted file and an added file with similar enough content are reported
+//! as a rename (`R`). With copy detection, an added file similar to the old
+//! version of a modified file is reported as a copy (`C`).
+//!
+//! Similarity is the share of lines Myers' diff keeps unchanged between the
+//! two versions, relative to the longer one, so pairs whose line counts are
+//! too far apart are not compared at all. Identical blobs always pair up;
+//! comparing contents is skipped when there are more candidates than
+//! `diff.renameLimit` allows, like git does.
+
+use crate::artifacts::database::database_entry::DatabaseEntry;
+use crate::artifacts::diff::diff_algorithm::{DiffAlgorithm, Edit, MyersDiff};
+use crate::artifacts::diff::tree_diff::{ChangeSet, DiffFilter, TreeChangeType};
+use crate::artifacts::objects::object_id::ObjectId;
+use std::path::{Path, PathBuf};
+
+/// Config key enabling rename detection (`true`, `false` or `copies`)
+pub const RENAMES_KEY: &str = "diff.renames";
+/// Config key bounding the number of files compared for inexact renames
+pub const RENAME_LIMIT_KEY: &str = "diff.renameLimit";
+/// Minimum similarity (percent) when `-M`/`-C` is given without a value
+pub const DEFAULT_SIMILARITY: u8 = 50;
+/// Number of candidate files above which inexact detection is skipped
+pub const DEFAULT_RENAME_LIMIT: usize = 1000;
+
+/// Whether a pair moved a file or duplicated it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameKind {
+    Rename,
+    Copy,
+}
+
+impl RenameKind {
+    pub fn status_char(&self) -> char {
+        match self {
+            RenameKind::Rename => 'R',
+            RenameKind::Copy => 'C',
+        }
+    }
+
+    pub fn matches_filter(&self, filter: DiffFilter) -> bool {
+        match self {
+            RenameKind::Rename => filter.contains(DiffFilter::RENAMED),
+            RenameKind::Copy => filter.contains(DiffFilter::COPIED),
+        }
+    }
+}
+
+/// How renames (and copies) are looked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenameOptions {
+    /// Minimum similarity (percent) for a pair to be reported
+    pub threshold: u8,
+    /// Also look for copies of modified files
+    pub copies: bool,
+    /// Candidate count above which only identical blobs are paired
+    pub limit: usize,
+}
+
+/// A file reported under a new path
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenamePair {
+    pub kind: RenameKind,
+    pub old_path: PathBuf,
+    pub new_path: PathBuf,
+    pub old_entry: DatabaseEntry,
+    pub new_entry: DatabaseEntry,
+    /// Similarity of the two versions (percent)
+    pub similarity: u8,
+}
+
+/// Outcome of rename detection over a changeset
+#[derive(Debug, Clone, Default)]
+pub struct RenameDetection {
+    /// Detected pairs, ordered by new path
+    pub pairs: Vec<RenamePair>,
+    /// The `diff.renameLimit` that would have allowed inexact detection,
+    /// when it was skipped
+    pub skipped_limit: Option<usize>,
+}
+
+impl RenameDetection {
+    /// Whether `path` is the old side of a rename, and so not deleted
+    pub fn is_renamed_from(&self, path: &Path) -> bool {
+        self.pairs
+            .iter()
+            .any(|pair| pair.kind == RenameKind::Rename && pair.old_path == path)
+    }
+
+    /// The pair reporting `path` as its new side, if any
+    pub fn pair_to(&self, path: &Path) -> Option<&RenamePair> {
+        self.pairs.iter().find(|pair| pair.new_path == path)
+    }
+}
+
+struct Candidate<'c> {
+    path: &'c PathBuf,
+    entry: &'c DatabaseEntry,
+    kind: RenameKind,
+}
+
+/// Pair up the added files of `changes` with deleted (or modified) ones
+///
+/// `load` reads the lines of a blob; it is only called for inexact
+/// detection.
+pub fn detect_renames(
+    changes: &ChangeSet,
+    options: RenameOptions,
+    load: impl Fn(&ObjectId) -> anyhow::Result<Vec<String>>,
+) -> anyhow::Result<RenameDetection> {
+    let mut sources = Vec::new();
+    let mut destinations = Vec::new();
+    for (path, change) in changes {
+        match change {
+            TreeChangeType::Added(entry) if !entry.is_gitlink() => destinations.push((path, entry)),
+            TreeChangeType::Deleted(entry) if !entry.is_gitlink() => sources.push(Candidate {
+                path,
+                entry,
+                kind: RenameKind::Rename,
+            }),
+            TreeChangeType::Modified { old, .. } if options.copies && !old.is_gitlink() => sources
+                .push(Candidate {
+                    path,
+                    entry: old,
+                    kind: RenameKind::Copy,
+                }),
+            _ => {}
+        }
+    }
+
+    let mut detection = RenameDetection::default();
+    let mut used_sources = vec![false; sources.len()];
+    let mut paired = vec![false; destinations.len()];
+
+    // Identical blobs first: no content needs to be read
+    for (dst_index, (path, entry)) in destinations.iter().enumerate() {
+        let source = sources
+            .iter()
+            .enumerate()
+            .filter(|(src_index, source)| {
+                source.entry.oid == entry.oid && !used_sources[*src_index]
+            })
+            .min_by_key(|(_, source)| source.kind == RenameKind::Copy);
+        if let Some((src_index, source)) = source {
+            used_sources[src_index] = source.kind == RenameKind::Rename;
+            paired[dst_index] = true;
+            detection.pairs.push(pair(source, path, entry, 100));
+        }
+    }
+
+    let remaining_destinations = paired.iter().filter(|paired| !**paired).count();
+    let remaining_sources = used_sources.iter().filter(|used| !**used).count();
+    let needed = remaining_destinations.max(remaining_sources);
+    if remaining_destinations * remaining_sources > options.limit.saturating_mul(options.limit) {
+        detection.skipped_limit = Some(needed);
+    } else if remaining_destinations > 0 && remaining_sources > 0 {
+        // Each source is read once, however many destinations it is compared to
+        let source_lines = sources
+            .iter()
+            .zip(&used_sources)
+            .map(|(source, used)| (!used).then(|| load(&source.entry.oid)).transpose())
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut scores = Vec::new();
+        for (dst_index, (_, dst_entry)) in destinations.iter().enumerate() {
+            if paired[dst_index] {
+                continue;
+            }
+            let dst_lines = load(&dst_entry.oid)?;
+            for (src_index, src_lines) in source_lines.iter().enumerate() {
+                let Some(src_lines) = src_lines else {
+                    continue;
+                };
+                if max_similarity(src_lines.len(), dst_lines.len()) < options.threshold {
+                    continue;
+                }
+                let score = similarity(src_lines, &dst_lines);
+                if score >= options.threshold {
+                    scores.push((score, dst_index, src_index));
+                }
+            }
+        }
+
+        // Best matches win; ties go to the earliest paths
+        scores.sort_by(|a, b| b.0.cmp(&a.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+        for (score, dst_index, src_index) in scores {
+            if paired[dst_index] || used_sources[src_index] {
+                continue;
+            }
+            let source = &sources[src_index];
+            let (path, entry) = destinations[dst_index];
+            used_sources[src_index] = source.kind == RenameKind::Rename;
+            paired[dst_index] = true;
+            detection.pairs.push(pair(source, path, entry, score));
+        }
+    }
+
+    detection.pairs.sort_by(|a, b| a.new_path.cmp(&b.new_path));
+
+    Ok(detection)
+}
+
+fn pair(source: &Candidate, path: &Path, entry: &DatabaseEntry, similarity: u8) -> RenamePair {
+    RenamePair {
+        kind: source.kind,
+        old_path: source.path.clone(),
+        new_path: path.to_path_buf(),
+        old_entry: source.entry.clone(),
+        new_entry: entry.clone(),
+        similarity,
+    }
+}
+
+/// Highest similarity two files of these line counts can reach: at most the
+/// shorter one is left unchanged
+fn max_similarity(a_len: usize, b_len: usize) -> u8 {
+    let longest = a_len.max(b_len);
+    if longest == 0 {
+        return 100;
+    }
+
+    (a_len.min(b_len) * 100 / longest) as u8
+}
+
+/// Share of lines (percent) left unchanged between two versions of a file
+pub fn similarity(a: &[String], b: &[String]) -> u8 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 100;
+    }
+
+    let unchanged = MyersDiff::new(a, b)
+        .diff()
+        .iter()
+        .filter(|edit| matches!(edit, Edit::Equal { .. }))
+        .count();
+
+    (unchanged * 100 / longest) as u8
+}
+
+/// Parse the value of `-M<n>`/`-C<n>` like git does
+///
+/// Digits are read as a fraction (`9` and `90` are both 90%) unless they end
+/// with `%` (`90%`); an empty value selects the default similarity.
+pub fn parse_similarity(value: &str) -> Result<u8, String> {
+    if value.is_empty() {
+        return Ok(DEFAULT_SIMILARITY);
+    }
+
+    let (digits, percent) = match value.strip_suffix('%') {
+        Some(digits) => (digits, true),
+        None => (value, false),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("invalid similarity '{}'", value));
+    }
+
+    let number = digits.parse::<u64>().map_err(|e| e.to_string())?;
+    let scale = if percent {
+        100
+    } else {
+        10u64.saturating_pow(digits.len() as u32)
+    };
+
+    Ok((number.saturating_mul(100) / scale).min(100) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::index::entry_mode::{EntryMode, FileMode};
+    use pretty_assertions::assert_eq;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    fn entry(oid: &str) -> DatabaseEntry {
+        DatabaseEntry::new(
+            ObjectId::try_parse(format!("{:0<40}", oid)).unwrap(),
+            EntryMode::File(FileMode::Regular),
+        )
+    }
+
+    fn lines(content: &str) -> Vec<String> {
+        content.lines().map(str::to_string).collect()
+    }
+
+    fn options(threshold: u8) -> RenameOptions {
+        RenameOptions {
+            threshold,
+            copies: false,
+            limit: DEFAULT_RENAME_LIMIT,
+        }
+    }
+
+    /// `old.txt` (a1) deleted and `new.txt` (b2) added, 3 of 5 lines shared
+    fn near_rename() -> (ChangeSet, HashMap<ObjectId, Vec<String>>) {
+        let changes = ChangeSet::from([
+            (PathBuf::from("new.txt"), TreeChangeType::Added(entry("b2"))),
+            (
+                PathBuf::from("old.txt"),
+                TreeChangeType::Deleted(entry("a1")),
+            ),
+        ]);
+        let blobs = HashMap::from([
+            (entry("a1").oid, lines("1\n2\n3\n4\n5")),
+            (entry("b2").oid, lines("1\n2\n3\nfour\nfive")),
+        ]);
+
+        (changes, blobs)
+    }
+
+    #[test]
+    fn test_threshold_gates_inexact_renames() {
+        let (changes, blobs) = near_rename();
+        let load = |oid: &ObjectId| Ok(blobs[oid].clone());
+
+        let lenient = detect_renames(&changes, options(40), load).unwrap();
+        assert_eq!(lenient.pairs.len(), 1);
+        assert_eq!(lenient.pairs[0].old_path, PathBuf::from("old.txt"));
+        assert_eq!(lenient.pairs[0].similarity, 60);
+
+        let strict = detect_renames(&changes, options(90), load).unwrap();
+        assert!(strict.pairs.is_empty());
+    }
+
+    #[test]
+    fn test_sources_are_loaded_once_and_unreachable_sizes_skipped() {
+        let changes = ChangeSet::from([
+            (
+                PathBuf::from("long.txt"),
+                TreeChangeType::Added(entry("e5")),
+            ),
+            (PathBuf::from("new.txt"), TreeChangeType::Added(entry("b2"))),
+            (
+                PathBuf::from("old.txt"),
+                TreeChangeType::Deleted(entry("a1")),
+            ),
+        ]);
+        let (_, mut blobs) = near_rename();
+        blobs.insert(entry("e5").oid, lines(&"1\n".repeat(20)));
+        let loads = RefCell::new(HashMap::<ObjectId, usize>::new());
+        let load = |oid: &ObjectId| {
+            *loads.borrow_mut().entry(oid.clone()).or_default() += 1;
+            Ok(blobs[oid].clone())
+        };
+
+        let detection = detect_renames(&changes, options(40), load).unwrap();
+
+        assert_eq!(detection.pairs.len(), 1);
+        assert_eq!(detection.pairs[0].new_path, PathBuf::from("new.txt"));
+        assert_eq!(loads.borrow()[&entry("a1").oid], 1);
+        assert_eq!(max_similarity(5, 20), 25);
+    }
+
+    #[test]
+    fn test_identical_blobs_pair_without_loading() {
+        let changes = ChangeSet::from([
+            (PathBuf::from("a.txt"), TreeChangeType::Deleted(entry("c3"))),
+            (PathBuf::from("b.txt"), TreeChangeType::Added(entry("c3"))),
+        ]);
+
+        let detection = detect_renames(&changes, options(100), |_| unreachable!()).unwrap();
+
+        assert_eq!(detection.pairs[0].similarity, 100);
+        assert!(detection.is_renamed_from(Path::new("a.txt")));
+    }
+
+    #[test]
+    fn test_rename_limit_skips_inexact_detection() {
+        let (changes, blobs) = near_rename();
+        let mut limited = options(40);
+        limited.limit = 0;
+
+        let detection = detect_renames(&changes, limited, |oid| Ok(blobs[oid].clone())).unwrap();
+
+        assert!(detection.pairs.is_empty());
+        assert_eq!(detection.skipped_limit, Some(1));
+    }
+
+    #[test]
+    fn test_copies_come_from_modified_files() {
+        let changes = ChangeSet::from([
+            (
+                PathBuf::from("copy.txt"),
+                TreeChangeType::Added(entry("a1")),
+            ),
+            (
+                PathBuf::from("original.txt"),
+                TreeChangeType::Modified {
+                    old: entry("a1"),
+                    new: entry("d4"),
+                },
+            ),
+        ]);
+        let mut copies = options(50);
+
+        assert!(
+            detect_renames(&changes, copies, |_| unreachable!())
+                .unwrap()
+                .pairs
+                .is_empty()
+        );
+
+        copies.copies = true;
+        let detection = detect_renames(&changes, copies, |_| unreachable!()).unwrap();
+        assert_eq!(detection.pairs[0].kind, RenameKind::Copy);
+        assert!(!detection.is_renamed_from(Path::new("original.txt")));
+    }
+
+    #[test]
+    fn test_parse_similarity_like_git() {
+        assert_eq!(parse_similarity(""), Ok(50));
+        assert_eq!(parse_similarity("90"), Ok(90));
+        assert_eq!(parse_similarity("9"), Ok(90));
+        assert_eq!(parse_similarity("40%"), Ok(40));
+        assert_eq!(parse_similarity("100%"), Ok(100));
+        assert!(parse_similarity("abc").is_err());
+    }
+}
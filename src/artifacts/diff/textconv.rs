@@ -0,0 +1,119 @@
+//! Text conversion of binary files for diffing
+//!
+//! A path whose `diff` attribute names a driver with a
+//! `diff.<driver>.textconv` command is diffed through that command: the
+//! content of each side is fed to it on stdin and its stdout is compared
+//! instead. Other content with a NUL byte is treated as binary and only
+//! reported as differing.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// How many leading bytes are looked at to tell binary content, like git
+const BINARY_CHECK_LENGTH: usize = 8000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TextconvError {
+    #[error("failed to start the textconv command '{command}'")]
+    Spawn {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("the textconv command '{command}' failed")]
+    Failed { command: String },
+}
+
+/// Config key of the textconv command of a diff driver
+pub fn textconv_key(driver: &str) -> String {
+    format!("diff.{}.textconv", driver)
+}
+
+/// Whether raw content looks binary: a NUL byte within its first bytes
+pub fn is_binary(content: &[u8]) -> bool {
+    content
+        .iter()
+        .take(BINARY_CHECK_LENGTH)
+        .any(|&byte| byte == 0)
+}
+
+/// Run `command` through the shell with the raw `content` on stdin and return
+/// the lines it prints
+pub fn textconv(command: &str, content: &[u8]) -> Result<Vec<String>, TextconvError> {
+    let spawn_error = |source| TextconvError::Spawn {
+        command: command.to_string(),
+        source,
+    };
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+
+    // Feed stdin from another thread so a command writing before it has read
+    // everything cannot block on a full pipe
+    let input = content.to_vec();
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+    let output = child.wait_with_output().map_err(spawn_error)?;
+    // A command that exits without reading all its input closes the pipe early
+    let _ = writer.join();
+    if !output.status.success() {
+        return Err(TextconvError::Failed {
+            command: command.to_string(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn lines(content: &str) -> Vec<String> {
+        content.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_nul_bytes_mark_content_as_binary() {
+        assert!(is_binary(b"PK\0\x03\nrest"));
+        assert!(!is_binary(b"plain\ntext\n"));
+        assert!(!is_binary(b"\xff\xfe not utf-8\n"));
+    }
+
+    #[test]
+    fn test_textconv_pipes_content_through_command() {
+        let converted = textconv("tr 'a-z' 'A-Z'", b"one\ntwo\n").unwrap();
+
+        assert_eq!(converted, lines("ONE\nTWO\n"));
+    }
+
+    #[test]
+    fn test_textconv_receives_content_that_is_not_utf8() {
+        let converted = textconv("od -An -tx1", b"\xff\xfe\x00a\n").unwrap();
+
+        assert_eq!(
+            converted
+                .iter()
+                .flat_map(|line| line.split_whitespace())
+                .collect::<Vec<_>>(),
+            vec!["ff", "fe", "00", "61", "0a"]
+        );
+    }
+
+    #[test]
+    fn test_failing_command_is_reported() {
+        assert!(matches!(
+            textconv("exit 3", b"one\n"),
+            Err(TextconvError::Failed { .. })
+        ));
+    }
+}
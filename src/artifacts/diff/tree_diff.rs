@@ -9,13 +9,15 @@
 //! 1. Loads two tree objects (old and new)
 //! 2. Recursively compares their entries
 //! 3. Detects changes by comparing object IDs
-//! 4. Supports filtering by change type (A/D/M/R)
+//! 4. Supports filtering by change type (A/D/M/R/C)
 //! 5. Can filter by specific file paths
 //!
 //! ## Performance
 //!
 //! Uses BTreeMap for sorted traversal and efficient comparison.
-//! Only loads and expands subtrees when necessary (lazy evaluation).
+//! Only loads and expands subtrees when necessary (lazy evaluation): entries
+//! with the same OID on both sides are skipped, so identical subtrees are
+//! never loaded.
 
 use crate::areas::database::Database;
 use crate::artifacts::database::database_entry::DatabaseEntry;
@@ -24,6 +26,7 @@ use crate::artifacts::objects::object::ObjectBox;
 use crate::artifacts::objects::object_id::ObjectId;
 use crate::artifacts::objects::tree::Tree;
 use bitflags::bitflags;
+use std::cell::Cell;
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
@@ -39,8 +42,10 @@ bitflags! {
         const DELETED = 0b0010;
         /// Show modified files
         const MODIFIED = 0b0100;
-        /// Show renamed files (not yet implemented)
+        /// Show renamed files (with rename detection)
         const RENAMED = 0b1000;
+        /// Show copied files (with copy detection)
+        const COPIED = 0b10000;
     }
 }
 
@@ -54,6 +59,7 @@ impl DiffFilter {
                 'D' => filter |= Self::DELETED,
                 'M' => filter |= Self::MODIFIED,
                 'R' => filter |= Self::RENAMED,
+                'C' => filter |= Self::COPIED,
                 _ => return None,
             }
         }
@@ -148,6 +154,8 @@ pub struct TreeDiff<'r> {
     database: &'r Database,
     /// Detected changes between trees
     change_set: ChangeSet,
+    /// Number of tree objects loaded so far
+    loaded_trees: Cell<usize>,
 }
 
 impl<'r> TreeDiff<'r> {
@@ -155,6 +163,7 @@ impl<'r> TreeDiff<'r> {
         TreeDiff {
             database,
             change_set: BTreeMap::new(),
+            loaded_trees: Cell::new(0),
         }
     }
 
@@ -164,6 +173,7 @@ impl<'r> TreeDiff<'r> {
         TreeDiff {
             database,
             change_set,
+            loaded_trees: Cell::new(0),
         }
     }
 
@@ -171,6 +181,13 @@ impl<'r> TreeDiff<'r> {
         &self.change_set
     }
 
+    /// Number of tree objects loaded from the database so far
+    ///
+    /// Subtrees with the same OID on both sides are never loaded.
+    pub fn loaded_trees(&self) -> usize {
+        self.loaded_trees.get()
+    }
+
     pub fn get_entries(&self, path: &Path) -> (Option<&DatabaseEntry>, Option<&DatabaseEntry>) {
         if let Some(change) = self.change_set.get(path) {
             (change.old_entry(), change.new_entry())
@@ -185,6 +202,10 @@ impl<'r> TreeDiff<'r> {
         new: Option<&ObjectId>,
         path_filter: &PathFilter,
     ) -> anyhow::Result<()> {
+        // The empty tree has no entries, so it is equivalent to a missing side
+        let old = old.filter(|oid| !oid.is_empty_tree());
+        let new = new.filter(|oid| !oid.is_empty_tree());
+
         if old == new {
             return Ok(());
         }
@@ -212,7 +233,10 @@ impl<'r> TreeDiff<'r> {
         let object = self.database.parse_object(oid)?;
 
         match object {
-            ObjectBox::Tree(tree) => Ok(*tree),
+            ObjectBox::Tree(tree) => {
+                self.loaded_trees.set(self.loaded_trees.get() + 1);
+                Ok(*tree)
+            }
             ObjectBox::Commit(commit) => {
                 let tree_oid = commit.tree_oid();
                 self.inflate_oid_to_tree(tree_oid)
@@ -299,3 +323,92 @@ impl<'r> TreeDiff<'r> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::index::entry_mode::{EntryMode, FileMode};
+    use crate::artifacts::index::index_entry::{EntryMetadata, IndexEntry};
+    use crate::artifacts::objects::blob::Blob;
+    use crate::artifacts::objects::object::Object;
+    use pretty_assertions::assert_eq;
+
+    const DEPTH: usize = 5;
+
+    /// Store a tree nesting `DEPTH` directories, with a file and an unchanged
+    /// sibling directory at every level; the deepest file holds `content`
+    fn store_deep_tree(database: &Database, content: &str) -> ObjectId {
+        let mut index_entries = Vec::new();
+        let mut dir = PathBuf::new();
+
+        for level in 0..=DEPTH {
+            let file_content = if level == DEPTH {
+                content.to_string()
+            } else {
+                format!("level {}", level)
+            };
+            let files = [
+                (dir.join("file.txt"), file_content),
+                (
+                    dir.join(format!("side{}", level)).join("file.txt"),
+                    "side".to_string(),
+                ),
+            ];
+            for (path, file_content) in files {
+                let blob = Blob::new(file_content, Default::default());
+                database.store(blob.clone()).unwrap();
+
+                index_entries.push(IndexEntry::new(
+                    path,
+                    blob.object_id().unwrap(),
+                    EntryMetadata {
+                        mode: EntryMode::File(FileMode::Regular),
+                        ..Default::default()
+                    },
+                ));
+            }
+            dir = dir.join(format!("dir{}", level));
+        }
+
+        let tree = Tree::build(index_entries.iter()).unwrap();
+        tree.traverse(&|tree: &Tree| Ok(database.store(tree.clone())?))
+            .unwrap();
+
+        tree.object_id().unwrap()
+    }
+
+    #[test]
+    fn test_unchanged_subtrees_are_not_loaded() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let database = Database::new(dir.path().into());
+        let old = store_deep_tree(&database, "before");
+        let new = store_deep_tree(&database, "after");
+
+        let mut diff = TreeDiff::new(&database);
+        diff.compare_oids(Some(&old), Some(&new), &PathFilter::empty())
+            .unwrap();
+
+        let changed = (0..DEPTH)
+            .map(|level| format!("dir{}", level))
+            .collect::<PathBuf>()
+            .join("file.txt");
+        assert_eq!(diff.changes().keys().collect::<Vec<_>>(), vec![&changed]);
+        // Only the directories along the changed path, on both sides; none of
+        // the DEPTH + 1 identical side directories
+        assert_eq!(diff.loaded_trees(), 2 * (DEPTH + 1));
+    }
+
+    #[test]
+    fn test_identical_trees_load_nothing() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let database = Database::new(dir.path().into());
+        let tree = store_deep_tree(&database, "same");
+
+        let mut diff = TreeDiff::new(&database);
+        diff.compare_oids(Some(&tree), Some(&tree), &PathFilter::empty())
+            .unwrap();
+
+        assert!(diff.changes().is_empty());
+        assert_eq!(diff.loaded_trees(), 0);
+    }
+}
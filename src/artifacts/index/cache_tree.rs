@@ -0,0 +1,268 @@
+//! TREE index extension (cache tree)
+//!
+//! Records, for every directory of the index, the OID of the tree it was last
+//! written as, so that committing only rebuilds the trees along the paths of
+//! changed entries. Changing an entry invalidates the cached OIDs of all the
+//! directories containing it; the others stay valid.
+//!
+//! ## Format
+//!
+//! Directories are written depth-first, each as:
+//!
+//! ```text
+//! <name>\0<entry count> <subtree count>\n<20-byte OID>
+//! ```
+//!
+//! The root has an empty name. An invalidated directory has an entry count of
+//! `-1` and no OID, but still lists its subtrees.
+
+use crate::artifacts::objects::object_id::{ObjectId, ObjectIdError};
+use std::collections::BTreeMap;
+use std::io::{BufRead, Cursor};
+use std::path::Path;
+
+/// Signature of the extension in the index file
+pub const TREE_SIGNATURE: &[u8; 4] = b"TREE";
+
+#[derive(Debug, thiserror::Error)]
+pub enum CacheTreeError {
+    #[error("malformed TREE extension at byte {0}")]
+    Malformed(u64),
+    #[error(transparent)]
+    ObjectId(#[from] ObjectIdError),
+}
+
+/// Cached tree of one directory of the index
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheTree {
+    /// OID of the directory's tree, `None` until written or once invalidated
+    oid: Option<ObjectId>,
+    /// Number of index entries below the directory, meaningful only with an OID
+    entry_count: usize,
+    subtrees: BTreeMap<String, CacheTree>,
+}
+
+impl CacheTree {
+    /// Valid cache of a directory just written as the tree `oid`
+    pub fn new(oid: ObjectId, entry_count: usize, subtrees: BTreeMap<String, CacheTree>) -> Self {
+        Self {
+            oid: Some(oid),
+            entry_count,
+            subtrees,
+        }
+    }
+
+    /// Whether there is nothing worth persisting
+    pub fn is_empty(&self) -> bool {
+        self.oid.is_none() && self.subtrees.is_empty()
+    }
+
+    /// Cached tree OID, `None` if the directory changed since it was written
+    pub fn oid(&self) -> Option<&ObjectId> {
+        self.oid.as_ref()
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.entry_count
+    }
+
+    /// Cache of the direct subdirectory `name`
+    pub fn subtree(&self, name: &str) -> Option<&CacheTree> {
+        self.subtrees.get(name)
+    }
+
+    /// Cache of the directory `dir`, relative to this one, if still valid
+    pub fn valid_subtree(&self, dir: &Path) -> Option<&CacheTree> {
+        let mut node = self;
+        for component in dir.iter() {
+            node = node.subtrees.get(component.to_str()?)?;
+        }
+
+        node.oid.is_some().then_some(node)
+    }
+
+    /// Forget the OIDs of every directory containing `path`
+    pub fn invalidate(&mut self, path: &Path) {
+        self.oid = None;
+        self.entry_count = 0;
+
+        let mut node = self;
+        for component in path.parent().into_iter().flat_map(Path::iter) {
+            let Some(subtree) = component
+                .to_str()
+                .and_then(|name| node.subtrees.get_mut(name))
+            else {
+                return;
+            };
+            subtree.oid = None;
+            subtree.entry_count = 0;
+            node = subtree;
+        }
+    }
+
+    /// Encode the extension data, without its signature and size
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write_node("", &mut bytes);
+
+        bytes
+    }
+
+    fn write_node(&self, name: &str, bytes: &mut Vec<u8>) {
+        let entry_count = match self.oid {
+            Some(_) => self.entry_count.to_string(),
+            None => String::from("-1"),
+        };
+
+        bytes.extend_from_slice(name.as_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(format!("{} {}\n", entry_count, self.subtrees.len()).as_bytes());
+        if let Some(oid) = &self.oid {
+            // Writing to a Vec cannot fail
+            let _ = oid.write_h40_to(bytes);
+        }
+
+        // Git orders subtrees by name length first
+        let mut subtrees = self.subtrees.iter().collect::<Vec<_>>();
+        subtrees.sort_by_key(|(name, _)| (name.len(), name.as_str()));
+        for (name, subtree) in subtrees {
+            subtree.write_node(name, bytes);
+        }
+    }
+
+    /// Decode the extension data, without its signature and size
+    pub fn parse(data: &[u8]) -> Result<Self, CacheTreeError> {
+        let mut reader = Cursor::new(data);
+        let (_, root) = Self::read_node(&mut reader)?;
+
+        if reader.position() != data.len() as u64 {
+            return Err(CacheTreeError::Malformed(reader.position()));
+        }
+
+        Ok(root)
+    }
+
+    fn read_node(reader: &mut Cursor<&[u8]>) -> Result<(String, Self), CacheTreeError> {
+        let name = read_field(reader, 0)?;
+        let position = reader.position();
+        let counts = read_field(reader, b'\n')?;
+
+        let (entry_count, subtree_count) = counts
+            .split_once(' ')
+            .and_then(|(entries, subtrees)| {
+                Some((
+                    entries.parse::<i64>().ok()?,
+                    subtrees.parse::<usize>().ok()?,
+                ))
+            })
+            .ok_or(CacheTreeError::Malformed(position))?;
+
+        let oid = match entry_count {
+            count if count >= 0 => Some(ObjectId::read_h40_from(reader)?),
+            _ => None,
+        };
+
+        let mut subtrees = BTreeMap::new();
+        for _ in 0..subtree_count {
+            let (subtree_name, subtree) = Self::read_node(reader)?;
+            subtrees.insert(subtree_name, subtree);
+        }
+
+        Ok((
+            name,
+            Self {
+                oid,
+                entry_count: entry_count.max(0) as usize,
+                subtrees,
+            },
+        ))
+    }
+}
+
+/// Read up to `delimiter`, which is consumed but not returned
+fn read_field(reader: &mut Cursor<&[u8]>, delimiter: u8) -> Result<String, CacheTreeError> {
+    let start = reader.position();
+    let mut field = Vec::new();
+    reader
+        .read_until(delimiter, &mut field)
+        .map_err(|_| CacheTreeError::Malformed(start))?;
+
+    if field.pop() != Some(delimiter) {
+        return Err(CacheTreeError::Malformed(start));
+    }
+
+    String::from_utf8(field).map_err(|_| CacheTreeError::Malformed(start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn oid(digit: char) -> ObjectId {
+        ObjectId::try_parse(digit.to_string().repeat(40)).unwrap()
+    }
+
+    /// Root with `a`, `a/b` and `c`, all valid
+    fn cache_tree() -> CacheTree {
+        let b = CacheTree::new(oid('3'), 1, BTreeMap::new());
+        let a = CacheTree::new(oid('2'), 2, BTreeMap::from([("b".to_string(), b)]));
+        let c = CacheTree::new(oid('4'), 1, BTreeMap::new());
+
+        CacheTree::new(
+            oid('1'),
+            4,
+            BTreeMap::from([("a".to_string(), a), ("c".to_string(), c)]),
+        )
+    }
+
+    #[test]
+    fn test_invalidate_clears_only_containing_directories() {
+        let mut cache_tree = cache_tree();
+
+        cache_tree.invalidate(Path::new("a/file.txt"));
+
+        assert_eq!(cache_tree.oid(), None);
+        assert_eq!(cache_tree.valid_subtree(Path::new("a")), None);
+        assert_eq!(
+            cache_tree.valid_subtree(Path::new("a/b")).unwrap().oid(),
+            Some(&oid('3'))
+        );
+        assert_eq!(
+            cache_tree.valid_subtree(Path::new("c")).unwrap().oid(),
+            Some(&oid('4'))
+        );
+    }
+
+    #[test]
+    fn test_round_trips_valid_and_invalidated_directories() {
+        let mut cache_tree = cache_tree();
+        cache_tree.invalidate(Path::new("a/b/file.txt"));
+
+        let parsed = CacheTree::parse(&cache_tree.serialize()).unwrap();
+
+        assert_eq!(parsed, cache_tree);
+    }
+
+    #[test]
+    fn test_serializes_in_git_format() {
+        let mut cache_tree = CacheTree::new(oid('1'), 1, BTreeMap::new());
+        cache_tree.invalidate(Path::new("file.txt"));
+
+        assert_eq!(cache_tree.serialize(), b"\0-1 0\n");
+    }
+
+    #[test]
+    fn test_rejects_truncated_data() {
+        let bytes = cache_tree().serialize();
+
+        assert!(matches!(
+            CacheTree::parse(&bytes[..bytes.len() - 1]),
+            Err(CacheTreeError::ObjectId(_))
+        ));
+        assert!(matches!(
+            CacheTree::parse(b"\x001 0"),
+            Err(CacheTreeError::Malformed(_))
+        ));
+    }
+}
@@ -21,6 +21,8 @@ pub enum ChecksumError {
 pub struct Checksum<'f> {
     file: FileGuard<&'f mut std::fs::File>,
     digest: Sha1,
+    /// Number of bytes read or written so far
+    offset: usize,
 }
 
 impl<'f> Checksum<'f> {
@@ -28,9 +30,14 @@ impl<'f> Checksum<'f> {
         Checksum {
             file,
             digest: Sha1::new(),
+            offset: 0,
         }
     }
 
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+
     pub(crate) fn read(&mut self, size: usize) -> Result<Bytes, ChecksumError> {
         let mut buffer = vec![0; size];
         self.file
@@ -39,12 +46,14 @@ impl<'f> Checksum<'f> {
             .map_err(|_| ChecksumError::UnexpectedEof)?;
 
         self.digest.update(&buffer);
+        self.offset += size;
         Ok(Bytes::from(buffer))
     }
 
     pub(crate) fn write(&mut self, data: &[u8]) -> Result<(), ChecksumError> {
         self.file.deref_mut().write_all(data)?;
         self.digest.update(data);
+        self.offset += data.len();
         Ok(())
     }
 
@@ -20,6 +20,10 @@ pub enum EntryMode {
     File(FileMode),
     #[default]
     Directory,
+    /// A symbolic link: the blob holds the path it points to
+    Symlink,
+    /// A submodule: the entry records a commit of another repository
+    Gitlink,
 }
 
 impl EntryMode {
@@ -27,7 +31,9 @@ impl EntryMode {
         match self {
             EntryMode::File(FileMode::Regular) => "100644",
             EntryMode::File(FileMode::Executable) => "100755",
+            EntryMode::Symlink => "120000",
             EntryMode::Directory => "40000",
+            EntryMode::Gitlink => "160000",
         }
     }
 
@@ -35,7 +41,9 @@ impl EntryMode {
         match self {
             EntryMode::File(FileMode::Regular) => 0o100644,
             EntryMode::File(FileMode::Executable) => 0o100755,
+            EntryMode::Symlink => 0o120000,
             EntryMode::Directory => 0o40000,
+            EntryMode::Gitlink => 0o160000,
         }
     }
 
@@ -43,7 +51,9 @@ impl EntryMode {
         match s {
             "100644" => Ok(EntryMode::File(FileMode::Regular)),
             "100755" => Ok(EntryMode::File(FileMode::Executable)),
+            "120000" => Ok(EntryMode::Symlink),
             "40000" => Ok(EntryMode::Directory),
+            "160000" => Ok(EntryMode::Gitlink),
             _ => Err(EntryModeError::InvalidModeString(s.to_string())),
         }
     }
@@ -51,6 +61,14 @@ impl EntryMode {
     pub fn is_tree(&self) -> bool {
         matches!(self, EntryMode::Directory)
     }
+
+    pub fn is_gitlink(&self) -> bool {
+        matches!(self, EntryMode::Gitlink)
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        matches!(self, EntryMode::Symlink)
+    }
 }
 
 impl TryFrom<u32> for EntryMode {
@@ -60,7 +78,9 @@ impl TryFrom<u32> for EntryMode {
         match mode {
             0o100644 => Ok(EntryMode::File(FileMode::Regular)),
             0o100755 => Ok(EntryMode::File(FileMode::Executable)),
+            0o120000 => Ok(EntryMode::Symlink),
             0o40000 => Ok(EntryMode::Directory),
+            0o160000 => Ok(EntryMode::Gitlink),
             _ => Err(EntryModeError::InvalidModeValue(mode)),
         }
     }
@@ -71,7 +91,9 @@ impl From<EntryMode> for u32 {
         match mode {
             EntryMode::File(FileMode::Regular) => 0o100644,
             EntryMode::File(FileMode::Executable) => 0o100755,
+            EntryMode::Symlink => 0o120000,
             EntryMode::Directory => 0o40000,
+            EntryMode::Gitlink => 0o160000,
         }
     }
 }
@@ -110,7 +132,9 @@ impl TryFrom<&str> for EntryMode {
         match value {
             "100644" => Ok(EntryMode::File(FileMode::Regular)),
             "100755" => Ok(EntryMode::File(FileMode::Executable)),
+            "120000" => Ok(EntryMode::Symlink),
             "40000" => Ok(EntryMode::Directory),
+            "160000" => Ok(EntryMode::Gitlink),
             _ => Err(EntryModeError::InvalidModeString(value.to_string())),
         }
     }
@@ -12,6 +12,7 @@
 //! which enable fast change detection without reading file content.
 
 use crate::artifacts::index::entry_mode::{EntryMode, EntryModeError, FileMode};
+use crate::artifacts::index::prefix_compression::compress_name;
 use crate::artifacts::objects::object::{Packable, Unpackable};
 use crate::artifacts::objects::object_id::{ObjectId, ObjectIdError};
 use byteorder::{ByteOrder, WriteBytesExt};
@@ -35,6 +36,10 @@ pub enum IndexEntryError {
     InvalidFileName,
     #[error("invalid entry name for serialization")]
     InvalidEntryName,
+    #[error("compressed entry name drops {strip} bytes from the {length}-byte name before it")]
+    InvalidNameCompression { strip: usize, length: usize },
+    #[error("invalid path '{path}': {reason}")]
+    InvalidPath { path: String, reason: &'static str },
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
@@ -73,8 +78,44 @@ impl From<u8> for MergeStage {
     }
 }
 
-/// Maximum path length supported in index entries
-const MAX_PATH_SIZE: usize = 4095;
+/// Per-entry bits stored alongside the merge stage in the flags fields
+///
+/// `assume_unchanged` lives in the base flags word (bit 15); `skip_worktree`
+/// needs the extended flags word introduced by index version 3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EntryFlags {
+    /// Trust the index and never stat the workspace file
+    pub assume_unchanged: bool,
+    /// Treat the workspace file as matching the index, even if missing
+    pub skip_worktree: bool,
+}
+
+impl EntryFlags {
+    /// Whether the entry needs the extended flags word (index version 3)
+    pub fn is_extended(&self) -> bool {
+        self.skip_worktree
+    }
+}
+
+/// Assume-valid bit of the base flags word
+const ASSUME_VALID_FLAG: u16 = 0x8000;
+
+/// Bit of the base flags word announcing an extended flags word
+const EXTENDED_FLAG: u16 = 0x4000;
+
+/// Skip-worktree bit of the extended flags word
+const SKIP_WORKTREE_FLAG: u16 = 0x4000;
+
+/// Offset of the entry name when there is no extended flags word
+pub const NAME_OFFSET: usize = 62;
+
+/// Offset of the entry name after the extended flags word
+const EXTENDED_NAME_OFFSET: usize = 64;
+
+/// Largest path length the 12-bit name-length field can hold
+///
+/// Longer names store this value and are read up to their NUL terminator.
+const MAX_PATH_SIZE: usize = 0xFFF;
 
 /// Block size for entry alignment (8 bytes)
 pub const ENTRY_BLOCK: usize = 8;
@@ -97,6 +138,8 @@ pub struct IndexEntry {
     pub metadata: EntryMetadata,
     /// Merge stage (clean, base, ours, or theirs)
     pub stage: MergeStage,
+    /// Assume-unchanged and skip-worktree bits
+    pub flags: EntryFlags,
 }
 
 impl IndexEntry {
@@ -107,6 +150,7 @@ impl IndexEntry {
             oid,
             metadata,
             stage: MergeStage::Clean,
+            flags: EntryFlags::default(),
         }
     }
 
@@ -120,6 +164,7 @@ impl IndexEntry {
                 ..Default::default()
             },
             stage,
+            flags: EntryFlags::default(),
         }
     }
 
@@ -142,6 +187,72 @@ impl IndexEntry {
         dirs[1..].to_vec()
     }
 
+    /// Check that `path` can be stored as an index entry name
+    ///
+    /// Names are NUL-terminated relative paths inside the work tree, so NUL
+    /// bytes, absolute paths and `.`/`..` segments are rejected.
+    pub fn validate_path(path: &Path) -> Result<(), IndexEntryError> {
+        let invalid = |reason| IndexEntryError::InvalidPath {
+            path: path.display().to_string(),
+            reason,
+        };
+
+        if path.as_os_str().is_empty() {
+            return Err(invalid("empty path"));
+        }
+        if path.as_os_str().as_encoded_bytes().contains(&0) {
+            return Err(invalid("contains a NUL byte"));
+        }
+        if !path
+            .components()
+            .all(|component| matches!(component, std::path::Component::Normal(_)))
+        {
+            return Err(invalid("not a relative path inside the work tree"));
+        }
+
+        Ok(())
+    }
+
+    /// Offset of the name in a serialized entry, given its first bytes
+    ///
+    /// Needs at least the fixed-size part of the entry (62 bytes).
+    pub fn name_offset(entry_bytes: &[u8]) -> usize {
+        let flags_raw = byteorder::NetworkEndian::read_u16(&entry_bytes[60..62]);
+
+        if flags_raw & EXTENDED_FLAG != 0 {
+            EXTENDED_NAME_OFFSET
+        } else {
+            NAME_OFFSET
+        }
+    }
+
+    /// Serialize the entry as in index version 4, without padding and with
+    /// its name compressed against the name of the entry written before it
+    pub fn serialize_compressed(&self, previous_name: &Path) -> anyhow::Result<Bytes> {
+        let entry_bytes = self.serialize()?;
+        let name_offset = Self::name_offset(&entry_bytes);
+        let previous_name = previous_name
+            .to_str()
+            .ok_or(IndexEntryError::InvalidEntryName)?;
+        let name = self
+            .name
+            .to_str()
+            .ok_or(IndexEntryError::InvalidEntryName)?;
+
+        Ok(Bytes::from(
+            [
+                &entry_bytes[..name_offset],
+                &compress_name(previous_name.as_bytes(), name.as_bytes()),
+            ]
+            .concat(),
+        ))
+    }
+
+    /// Whether status should trust the index instead of checking the workspace file
+    pub fn skips_workspace_check(&self) -> bool {
+        self.flags.assume_unchanged || self.flags.skip_worktree
+    }
+
     pub fn stat_match(&self, other: &EntryMetadata) -> bool {
         (self.metadata.size == 0 || self.metadata.size == other.size)
             && self.metadata.mode == other.mode
@@ -236,10 +347,27 @@ impl Packable for IndexEntry {
         entry_bytes.write_u32::<byteorder::NetworkEndian>(self.metadata.gid)?;
         entry_bytes.write_u32::<byteorder::NetworkEndian>(self.metadata.size as u32)?;
         self.oid.write_h40_to(&mut entry_bytes)?;
-        // Pack stage (bits 12-13) and path length (bits 0-11) into the 16-bit flags field
+        // Pack assume-valid (bit 15), extended (bit 14), stage (bits 12-13) and
+        // path length (bits 0-11) into the 16-bit flags field
         let path_length = min(entry_name.len(), MAX_PATH_SIZE);
-        let packed_flags: u16 = ((self.stage as u8 as u16) << 12) | (path_length as u16 & 0x0FFF);
+        let mut packed_flags: u16 =
+            ((self.stage as u8 as u16) << 12) | (path_length as u16 & 0x0FFF);
+        if self.flags.assume_unchanged {
+            packed_flags |= ASSUME_VALID_FLAG;
+        }
+        if self.flags.is_extended() {
+            packed_flags |= EXTENDED_FLAG;
+        }
         entry_bytes.write_u16::<byteorder::NetworkEndian>(packed_flags)?;
+
+        if self.flags.is_extended() {
+            let extended_flags = if self.flags.skip_worktree {
+                SKIP_WORKTREE_FLAG
+            } else {
+                0
+            };
+            entry_bytes.write_u16::<byteorder::NetworkEndian>(extended_flags)?;
+        }
         entry_bytes.write_all(entry_name.as_bytes())?;
 
         // Ensure the entry bytes are padded to ENTRY_BLOCK size with null bytes
@@ -281,12 +409,23 @@ impl Unpackable for IndexEntry {
         let flags_raw = byteorder::NetworkEndian::read_u16(&bytes[60..62]);
         let stage = MergeStage::from(((flags_raw >> 12) & 0x3) as u8);
 
+        let name_offset = Self::name_offset(&bytes);
+        let extended_flags = if name_offset == EXTENDED_NAME_OFFSET {
+            byteorder::NetworkEndian::read_u16(&bytes[62..64])
+        } else {
+            0
+        };
+        let flags = EntryFlags {
+            assume_unchanged: flags_raw & ASSUME_VALID_FLAG != 0,
+            skip_worktree: extended_flags & SKIP_WORKTREE_FLAG != 0,
+        };
+
         // Extract the entry name, which is null-terminated
-        let name_end = bytes[62..]
+        let name_end = bytes[name_offset..]
             .iter()
             .position(|&b| b == 0)
             .ok_or(IndexEntryError::MissingNullTerminator)?;
-        let name_bytes = &bytes[62..62 + name_end];
+        let name_bytes = &bytes[name_offset..name_offset + name_end];
         let name = PathBuf::from(
             std::str::from_utf8(name_bytes).map_err(|_| IndexEntryError::InvalidUtf8)?,
         );
@@ -295,6 +434,7 @@ impl Unpackable for IndexEntry {
             name,
             oid,
             stage,
+            flags,
             metadata: EntryMetadata {
                 ctime,
                 ctime_nsec,
@@ -318,6 +458,8 @@ impl TryFrom<(&Path, Metadata)> for EntryMetadata {
     fn try_from((file_path, metadata): (&Path, Metadata)) -> Result<Self, IndexEntryError> {
         let mode = if metadata.is_dir() {
             EntryMode::Directory
+        } else if metadata.file_type().is_symlink() {
+            EntryMode::Symlink
         } else {
             match file_path.is_executable() {
                 true => EntryMode::File(FileMode::Executable),
@@ -384,4 +526,69 @@ mod tests {
         let basename = entry.basename().unwrap();
         pretty_assertions::assert_eq!(basename, "c");
     }
+
+    fn round_trip(entry: &IndexEntry) -> IndexEntry {
+        let bytes = entry.serialize().unwrap();
+        assert_eq!(bytes.len() % ENTRY_BLOCK, 0);
+
+        IndexEntry::deserialize(std::io::Cursor::new(bytes)).unwrap()
+    }
+
+    #[rstest]
+    fn test_entry_round_trips_assume_unchanged_flag(oid: ObjectId, entry_metadata: EntryMetadata) {
+        let mut entry = IndexEntry::new(PathBuf::from("a/b/c.txt"), oid, entry_metadata);
+        entry.flags.assume_unchanged = true;
+
+        let bytes = entry.serialize().unwrap();
+        assert_eq!(IndexEntry::name_offset(&bytes), 62);
+
+        let parsed = round_trip(&entry);
+        pretty_assertions::assert_eq!(parsed.flags, entry.flags);
+        pretty_assertions::assert_eq!(parsed.name, entry.name);
+    }
+
+    #[rstest]
+    fn test_entry_round_trips_skip_worktree_flag(oid: ObjectId, entry_metadata: EntryMetadata) {
+        let mut entry = IndexEntry::new(PathBuf::from("a/b/c.txt"), oid, entry_metadata);
+        entry.flags = EntryFlags {
+            assume_unchanged: true,
+            skip_worktree: true,
+        };
+
+        let bytes = entry.serialize().unwrap();
+        assert_eq!(IndexEntry::name_offset(&bytes), 64);
+
+        let parsed = round_trip(&entry);
+        pretty_assertions::assert_eq!(parsed.flags, entry.flags);
+        pretty_assertions::assert_eq!(parsed.name, entry.name);
+        pretty_assertions::assert_eq!(parsed.stage, MergeStage::Clean);
+    }
+
+    #[rstest]
+    fn test_entry_round_trips_path_longer_than_name_length_field(
+        oid: ObjectId,
+        entry_metadata: EntryMetadata,
+    ) {
+        let name = PathBuf::from(format!("{}/file.txt", "d".repeat(MAX_PATH_SIZE + 100)));
+        let entry = IndexEntry::new(name, oid, entry_metadata);
+
+        let bytes = entry.serialize().unwrap();
+        let length_field = byteorder::NetworkEndian::read_u16(&bytes[60..62]) & 0x0FFF;
+        pretty_assertions::assert_eq!(length_field as usize, MAX_PATH_SIZE);
+
+        let parsed = round_trip(&entry);
+        pretty_assertions::assert_eq!(parsed.name, entry.name);
+    }
+
+    #[rstest]
+    #[case("a/b.txt", true)]
+    #[case("", false)]
+    #[case("a\0b.txt", false)]
+    #[case("/etc/passwd", false)]
+    #[case("../outside.txt", false)]
+    #[case("a/../../outside.txt", false)]
+    #[case("./a.txt", false)]
+    fn test_validate_path(#[case] path: &str, #[case] is_valid: bool) {
+        pretty_assertions::assert_eq!(IndexEntry::validate_path(Path::new(path)).is_ok(), is_valid);
+    }
 }
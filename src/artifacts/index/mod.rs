@@ -3,26 +3,35 @@
 //! The index (also called staging area or cache) stores information about the working tree.
 //! It tracks which files should be included in the next commit.
 //!
-//! ## File Format (Version 2)
+//! ## File Format (Versions 2 to 4)
 //!
 //! ```text
 //! Header (12 bytes):
 //!   - Signature: "DIRC" (4 bytes)
-//!   - Version: 2 (4 bytes)
+//!   - Version: 2, or 3 when an entry has extended flags, or 4 (4 bytes)
 //!   - Entry count (4 bytes)
 //!
 //! Entries (variable length):
-//!   - Each entry padded to 8-byte alignment
+//!   - Each entry padded to 8-byte alignment, except in version 4
 //!   - Contains metadata and path
+//!   - Version 3 and 4 entries may carry an extra 16-bit extended flags word
+//!   - Version 4 paths are prefix-compressed (see `prefix_compression`)
+//!
+//! Extensions (optional):
+//!   - 4-byte signature, 4-byte size, then the data
+//!   - `TREE` caches the tree OIDs of directories (see `cache_tree`)
 //!
 //! Checksum (20 bytes):
 //!   - SHA-1 hash of all preceding bytes
 //! ```
 
+pub mod cache_tree;
 pub mod checksum;
 pub mod entry_mode;
 pub mod index_entry;
 pub mod index_header;
+pub mod prefix_compression;
+pub mod verify;
 
 /// Size of SHA-1 checksum in bytes
 pub const CHECKSUM_SIZE: usize = 20; // SHA1 produces a 20-byte hash
@@ -30,8 +39,17 @@ pub const CHECKSUM_SIZE: usize = 20; // SHA1 produces a 20-byte hash
 /// Size of index header in bytes
 pub const HEADER_SIZE: usize = 12; // 4 bytes for marker, 4 for version, 4 for entries_count
 
+/// Size of an extension header (4-byte signature, 4-byte size)
+pub const EXTENSION_HEADER_SIZE: usize = 8;
+
 /// Magic signature identifying index files
 pub const SIGNATURE: &str = "DIRC"; // Signature for the index file
 
 /// Index file format version
 pub const VERSION: u32 = 2; // Version of the index file format
+
+/// Index file format version supporting extended entry flags (skip-worktree)
+pub const EXTENDED_VERSION: u32 = 3;
+
+/// Index file format version with prefix-compressed entry paths
+pub const PREFIX_COMPRESSED_VERSION: u32 = 4;
@@ -0,0 +1,109 @@
+//! Path prefix compression of index version 4
+//!
+//! Version 4 entries are not padded and store their name relative to the
+//! name of the entry before them: a variable-length integer gives the number
+//! of bytes to drop from the end of the previous name, and the NUL-terminated
+//! bytes that follow are appended to what remains. The first entry is
+//! compressed against an empty name.
+//!
+//! The integer is encoded like the base offsets of pack deltas: seven bits
+//! per byte, most significant first, with the high bit set on every byte but
+//! the last and one added to the value for each continuation byte.
+
+/// Encode the number of bytes to drop from the previous name
+pub fn encode_varint(value: usize) -> Vec<u8> {
+    let mut bytes = vec![(value & 0x7f) as u8];
+    let mut value = value >> 7;
+    while value != 0 {
+        value -= 1;
+        bytes.push(0x80 | (value & 0x7f) as u8);
+        value >>= 7;
+    }
+    bytes.reverse();
+
+    bytes
+}
+
+/// Decode the integer at the start of `bytes`
+///
+/// Returns the value and the number of bytes it took, or `None` when
+/// `bytes` ends before the last byte of the integer.
+pub fn decode_varint(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut iter = bytes.iter();
+    let mut byte = *iter.next()?;
+    let mut value = (byte & 0x7f) as usize;
+    let mut length = 1;
+    while byte & 0x80 != 0 {
+        byte = *iter.next()?;
+        value = ((value + 1) << 7) | (byte & 0x7f) as usize;
+        length += 1;
+    }
+
+    Some((value, length))
+}
+
+/// Compressed form of `name`, NUL terminator included
+pub fn compress_name(previous: &[u8], name: &[u8]) -> Vec<u8> {
+    let common = previous
+        .iter()
+        .zip(name)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut bytes = encode_varint(previous.len() - common);
+    bytes.extend_from_slice(&name[common..]);
+    bytes.push(0);
+
+    bytes
+}
+
+/// Name made of `previous` without its last `strip` bytes, then `suffix`
+///
+/// Returns `None` when `previous` is shorter than `strip`.
+pub fn expand_name(previous: &[u8], strip: usize, suffix: &[u8]) -> Option<Vec<u8>> {
+    let kept = previous.len().checked_sub(strip)?;
+
+    Some([&previous[..kept], suffix].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, &[0x00])]
+    #[case(127, &[0x7f])]
+    #[case(128, &[0x80, 0x00])]
+    #[case(300, &[0x81, 0x2c])]
+    #[case(16511, &[0xff, 0x7f])]
+    #[case(16512, &[0x80, 0x80, 0x00])]
+    fn test_varint_matches_git_encoding(#[case] value: usize, #[case] encoded: &[u8]) {
+        assert_eq!(encode_varint(value), encoded);
+        assert_eq!(decode_varint(encoded), Some((value, encoded.len())));
+    }
+
+    #[test]
+    fn test_truncated_varint_is_rejected() {
+        assert_eq!(decode_varint(&[0x81]), None);
+        assert_eq!(decode_varint(&[]), None);
+    }
+
+    #[test]
+    fn test_names_round_trip_against_previous_name() {
+        let names: [&[u8]; 4] = [b"a/b/c.txt", b"a/b/d.txt", b"a/e.txt", b"b.txt"];
+
+        let mut previous: &[u8] = b"";
+        for name in names {
+            let compressed = compress_name(previous, name);
+            let (strip, length) = decode_varint(&compressed).unwrap();
+            let suffix = &compressed[length..compressed.len() - 1];
+
+            assert_eq!(expand_name(previous, strip, suffix).unwrap(), name);
+            previous = name;
+        }
+
+        assert_eq!(compress_name(b"a/b/c.txt", b"a/b/d.txt"), b"\x05d.txt\0");
+    }
+}
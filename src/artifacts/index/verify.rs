@@ -0,0 +1,363 @@
+//! Structural validation of a raw index file
+//!
+//! Unlike [`Index::rehydrate`](crate::areas::index::Index::rehydrate), which
+//! stops at the first entry it cannot decode, this walks the file byte by
+//! byte and reports the first inconsistency together with its offset:
+//!
+//! - the `DIRC` signature and a supported version
+//! - one entry per the header's count, each ending in 1-8 NUL bytes that pad
+//!   it to a multiple of 8 bytes, or in version 4 a single NUL after a name
+//!   that expands from the name before it
+//! - entries in ascending (path, stage) order without duplicates
+//! - extensions that fit between the last entry and the checksum
+//! - a trailing SHA-1 of everything before it
+
+use crate::artifacts::index::index_entry::{ENTRY_BLOCK, ENTRY_MIN_SIZE, IndexEntry, NAME_OFFSET};
+use crate::artifacts::index::prefix_compression::{decode_varint, expand_name};
+use crate::artifacts::index::{
+    CHECKSUM_SIZE, EXTENDED_VERSION, EXTENSION_HEADER_SIZE, HEADER_SIZE, PREFIX_COMPRESSED_VERSION,
+    SIGNATURE, VERSION,
+};
+use byteorder::{ByteOrder, NetworkEndian};
+use sha1::{Digest, Sha1};
+use std::path::{Path, PathBuf};
+
+/// Name-length value stored for paths too long for the 12-bit field
+const NAME_LENGTH_MASK: u16 = 0x0FFF;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum IndexCorruption {
+    #[error("index file is truncated at offset {offset}")]
+    Truncated { offset: usize },
+    #[error("bad index signature at offset 0: expected {SIGNATURE}")]
+    InvalidSignature,
+    #[error("unsupported index version {version} at offset 4")]
+    UnsupportedVersion { version: u32 },
+    #[error("entry '{path}' at offset {offset} is not NUL-terminated and padded to 8 bytes")]
+    InvalidPadding { path: String, offset: usize },
+    #[error("entry name at offset {offset} is not valid UTF-8")]
+    InvalidName { offset: usize },
+    #[error("entry '{path}' at offset {offset} is out of order (after '{previous}')")]
+    Unsorted {
+        path: String,
+        previous: String,
+        offset: usize,
+    },
+    #[error("extension at offset {offset} runs past the index checksum")]
+    ExtensionOverrun { offset: usize },
+    #[error("index checksum mismatch at offset {offset}")]
+    ChecksumMismatch { offset: usize },
+}
+
+/// Check the structure of the raw bytes of an index file
+///
+/// # Returns
+///
+/// The number of entries in the index
+pub fn verify_index(bytes: &[u8]) -> Result<u32, IndexCorruption> {
+    if bytes.len() < HEADER_SIZE {
+        return Err(IndexCorruption::Truncated {
+            offset: bytes.len(),
+        });
+    }
+    if &bytes[0..4] != SIGNATURE.as_bytes() {
+        return Err(IndexCorruption::InvalidSignature);
+    }
+    let version = NetworkEndian::read_u32(&bytes[4..8]);
+    if ![VERSION, EXTENDED_VERSION, PREFIX_COMPRESSED_VERSION].contains(&version) {
+        return Err(IndexCorruption::UnsupportedVersion { version });
+    }
+    let entries_count = NetworkEndian::read_u32(&bytes[8..12]);
+
+    // Everything but the checksum; entries and extensions must fit in it
+    let content_end = bytes
+        .len()
+        .checked_sub(CHECKSUM_SIZE)
+        .filter(|end| *end >= HEADER_SIZE)
+        .ok_or(IndexCorruption::Truncated {
+            offset: bytes.len(),
+        })?;
+    let content = &bytes[..content_end];
+
+    let mut offset = HEADER_SIZE;
+    let mut previous: Option<(PathBuf, u8)> = None;
+    for _ in 0..entries_count {
+        let (path, stage, size) = if version == PREFIX_COMPRESSED_VERSION {
+            let previous_path = previous.as_ref().map_or(Path::new(""), |(path, _)| path);
+            verify_compressed_entry(content, offset, previous_path)?
+        } else {
+            verify_entry(content, offset)?
+        };
+
+        // Git orders names bytewise, so `a-b` sorts before `a/b`
+        if let Some((previous_path, previous_stage)) = &previous
+            && (
+                previous_path.as_os_str().as_encoded_bytes(),
+                *previous_stage,
+            ) >= (path.as_os_str().as_encoded_bytes(), stage)
+        {
+            return Err(IndexCorruption::Unsorted {
+                path: path.display().to_string(),
+                previous: previous_path.display().to_string(),
+                offset,
+            });
+        }
+        previous = Some((path, stage));
+        offset += size;
+    }
+
+    while offset < content_end {
+        let size = content
+            .get(offset + 4..offset + EXTENSION_HEADER_SIZE)
+            .map(NetworkEndian::read_u32)
+            .ok_or(IndexCorruption::ExtensionOverrun { offset })?;
+        let end = offset + EXTENSION_HEADER_SIZE + size as usize;
+        if end > content_end {
+            return Err(IndexCorruption::ExtensionOverrun { offset });
+        }
+        offset = end;
+    }
+
+    if Sha1::digest(content).as_slice() != &bytes[content_end..] {
+        return Err(IndexCorruption::ChecksumMismatch {
+            offset: content_end,
+        });
+    }
+
+    Ok(entries_count)
+}
+
+/// Check the entry starting at `offset`
+///
+/// Returns the entry's path, its merge stage and its size on disk.
+fn verify_entry(content: &[u8], offset: usize) -> Result<(PathBuf, u8, usize), IndexCorruption> {
+    let fixed = content
+        .get(offset..offset + ENTRY_MIN_SIZE)
+        .ok_or(IndexCorruption::Truncated {
+            offset: content.len(),
+        })?;
+    let flags = NetworkEndian::read_u16(&fixed[60..62]);
+    let stage = ((flags >> 12) & 0x3) as u8;
+    let name_start = offset + IndexEntry::name_offset(fixed);
+
+    // Names longer than the length field can hold are read up to their NUL
+    let name_length = match (flags & NAME_LENGTH_MASK) as usize {
+        length if length < NAME_LENGTH_MASK as usize => length,
+        _ => content[name_start..]
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or(IndexCorruption::Truncated {
+                offset: content.len(),
+            })?,
+    };
+    let name_end = name_start + name_length;
+    let entry_end = offset + (name_end - offset + ENTRY_BLOCK) / ENTRY_BLOCK * ENTRY_BLOCK;
+    if entry_end > content.len() {
+        return Err(IndexCorruption::Truncated {
+            offset: content.len(),
+        });
+    }
+
+    let name = std::str::from_utf8(&content[name_start..name_end])
+        .map_err(|_| IndexCorruption::InvalidName { offset })?;
+    if content[name_end..entry_end].iter().any(|&byte| byte != 0) {
+        return Err(IndexCorruption::InvalidPadding {
+            path: name.to_string(),
+            offset,
+        });
+    }
+
+    Ok((PathBuf::from(name), stage, entry_end - offset))
+}
+
+/// Check the version 4 entry starting at `offset`, whose name is compressed
+/// against `previous`
+///
+/// Returns the entry's path, its merge stage and its size on disk.
+fn verify_compressed_entry(
+    content: &[u8],
+    offset: usize,
+    previous: &Path,
+) -> Result<(PathBuf, u8, usize), IndexCorruption> {
+    let truncated = || IndexCorruption::Truncated {
+        offset: content.len(),
+    };
+    let fixed = content
+        .get(offset..offset + NAME_OFFSET)
+        .ok_or_else(truncated)?;
+    let flags = NetworkEndian::read_u16(&fixed[60..62]);
+    let stage = ((flags >> 12) & 0x3) as u8;
+    let name_start = offset + IndexEntry::name_offset(fixed);
+
+    let (strip, length) = content
+        .get(name_start..)
+        .and_then(decode_varint)
+        .ok_or_else(truncated)?;
+    let suffix_start = name_start + length;
+    let suffix_length = content[suffix_start..]
+        .iter()
+        .position(|&byte| byte == 0)
+        .ok_or_else(truncated)?;
+    let suffix = &content[suffix_start..suffix_start + suffix_length];
+
+    let name = expand_name(previous.as_os_str().as_encoded_bytes(), strip, suffix)
+        .ok_or(IndexCorruption::InvalidName { offset })?;
+    let name = String::from_utf8(name).map_err(|_| IndexCorruption::InvalidName { offset })?;
+
+    Ok((
+        PathBuf::from(name),
+        stage,
+        suffix_start + suffix_length + 1 - offset,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::index::index_entry::EntryMetadata;
+    use crate::artifacts::objects::object::Packable;
+    use crate::artifacts::objects::object_id::ObjectId;
+    use pretty_assertions::assert_eq;
+
+    fn entry(name: &str) -> Vec<u8> {
+        let oid =
+            ObjectId::try_parse(String::from("0123456789abcdef0123456789abcdef01234567")).unwrap();
+        IndexEntry::new(name.into(), oid, EntryMetadata::default())
+            .serialize()
+            .unwrap()
+            .to_vec()
+    }
+
+    fn index(entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = SIGNATURE.as_bytes().to_vec();
+        bytes.extend_from_slice(&VERSION.to_be_bytes());
+        bytes.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        for entry in entries {
+            bytes.extend_from_slice(entry);
+        }
+        seal(bytes)
+    }
+
+    /// Append the checksum of `bytes`
+    fn seal(mut bytes: Vec<u8>) -> Vec<u8> {
+        let checksum = Sha1::digest(&bytes);
+        bytes.extend_from_slice(checksum.as_slice());
+        bytes
+    }
+
+    #[test]
+    fn test_valid_index_reports_entry_count() {
+        let bytes = index(&[entry("a.txt"), entry("b/c.txt")]);
+
+        assert_eq!(verify_index(&bytes), Ok(2));
+    }
+
+    #[test]
+    fn test_prefix_compressed_entries_are_expanded() {
+        let oid =
+            ObjectId::try_parse(String::from("0123456789abcdef0123456789abcdef01234567")).unwrap();
+        let mut bytes = SIGNATURE.as_bytes().to_vec();
+        bytes.extend_from_slice(&PREFIX_COMPRESSED_VERSION.to_be_bytes());
+        bytes.extend_from_slice(&3u32.to_be_bytes());
+        let mut previous = PathBuf::new();
+        for name in ["a/b.txt", "a/c.txt", "d.txt"] {
+            let entry = IndexEntry::new(name.into(), oid.clone(), EntryMetadata::default());
+            bytes.extend_from_slice(&entry.serialize_compressed(&previous).unwrap());
+            previous = PathBuf::from(name);
+        }
+
+        assert_eq!(verify_index(&seal(bytes.clone())), Ok(3));
+
+        // Dropping more bytes than the first name has cannot expand
+        bytes[HEADER_SIZE + NAME_OFFSET] = 1;
+        assert_eq!(
+            verify_index(&seal(bytes)),
+            Err(IndexCorruption::InvalidName {
+                offset: HEADER_SIZE
+            })
+        );
+    }
+
+    #[test]
+    fn test_unsorted_entries_are_reported_with_their_offset() {
+        let first = entry("b.txt");
+        let offset = HEADER_SIZE + first.len();
+        let bytes = index(&[first, entry("a.txt")]);
+
+        assert_eq!(
+            verify_index(&bytes),
+            Err(IndexCorruption::Unsorted {
+                path: "a.txt".to_string(),
+                previous: "b.txt".to_string(),
+                offset,
+            })
+        );
+    }
+
+    #[test]
+    fn test_entries_are_ordered_bytewise() {
+        let bytes = index(&[entry("a-b"), entry("a/b"), entry("a0")]);
+
+        assert_eq!(verify_index(&bytes), Ok(3));
+    }
+
+    #[test]
+    fn test_non_zero_padding_is_reported() {
+        let mut padded = entry("a.txt");
+        *padded.last_mut().unwrap() = b'x';
+        let bytes = index(&[padded]);
+
+        assert_eq!(
+            verify_index(&bytes),
+            Err(IndexCorruption::InvalidPadding {
+                path: "a.txt".to_string(),
+                offset: HEADER_SIZE,
+            })
+        );
+    }
+
+    #[test]
+    fn test_entry_count_beyond_the_entries_is_truncation() {
+        let mut bytes = index(&[entry("a.txt")]);
+        bytes[11] = 2;
+
+        assert!(matches!(
+            verify_index(&bytes),
+            Err(IndexCorruption::Truncated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_checksum_mismatch_is_reported_at_the_checksum() {
+        let mut bytes = index(&[entry("a.txt")]);
+        let checksum_offset = bytes.len() - CHECKSUM_SIZE;
+        bytes[checksum_offset] ^= 0xFF;
+
+        assert_eq!(
+            verify_index(&bytes),
+            Err(IndexCorruption::ChecksumMismatch {
+                offset: checksum_offset
+            })
+        );
+    }
+
+    #[test]
+    fn test_extensions_are_skipped() {
+        let mut bytes = index(&[entry("a.txt")]);
+        bytes.truncate(bytes.len() - CHECKSUM_SIZE);
+        bytes.extend_from_slice(b"TREE");
+        bytes.extend_from_slice(&3u32.to_be_bytes());
+        bytes.extend_from_slice(b"abc");
+        let bytes = seal(bytes);
+
+        assert_eq!(verify_index(&bytes), Ok(1));
+    }
+
+    #[test]
+    fn test_bad_signature_is_reported() {
+        let mut bytes = index(&[]);
+        bytes[0] = b'X';
+
+        assert_eq!(verify_index(&bytes), Err(IndexCorruption::InvalidSignature));
+    }
+}
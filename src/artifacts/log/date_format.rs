@@ -0,0 +1,103 @@
+//! Date rendering for `--date`
+//!
+//! - `relative`: time elapsed until now, e.g. `3 hours ago`
+//! - `iso`: `2024-01-01 12:00:00 +0000`, in the timezone of the timestamp
+//! - `unix`: seconds since the epoch
+
+use chrono::{DateTime, FixedOffset};
+use clap::ValueEnum;
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+
+/// How timestamps are rendered
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum DateFormat {
+    #[value(
+        name = "relative",
+        help = "Time elapsed until now, e.g. \"2 hours ago\""
+    )]
+    Relative,
+    #[value(name = "iso", help = "ISO 8601-like: 2024-01-01 12:00:00 +0000")]
+    Iso,
+    #[value(name = "unix", help = "Seconds since the epoch")]
+    Unix,
+}
+
+impl DateFormat {
+    /// Render `timestamp`, with `now` as the reference point of relative dates
+    pub fn format(&self, timestamp: &DateTime<FixedOffset>, now: &DateTime<FixedOffset>) -> String {
+        match self {
+            DateFormat::Relative => relative_date(now.timestamp() - timestamp.timestamp()),
+            DateFormat::Iso => timestamp.format("%Y-%m-%d %H:%M:%S %z").to_string(),
+            DateFormat::Unix => timestamp.timestamp().to_string(),
+        }
+    }
+}
+
+/// Describe an age in seconds the way Git does, rounding to the nearest unit
+fn relative_date(age: i64) -> String {
+    if age < 0 {
+        return String::from("in the future");
+    }
+
+    let (count, unit) = if age < 90 {
+        (age, "second")
+    } else if age < 90 * MINUTE {
+        ((age + MINUTE / 2) / MINUTE, "minute")
+    } else if age < 36 * HOUR {
+        ((age + HOUR / 2) / HOUR, "hour")
+    } else if age < 14 * DAY {
+        ((age + DAY / 2) / DAY, "day")
+    } else if age < 70 * DAY {
+        ((age / DAY + 3) / 7, "week")
+    } else if age < 365 * DAY {
+        ((age / DAY + 15) / 30, "month")
+    } else {
+        ((age / DAY + 183) / 365, "year")
+    };
+
+    format!(
+        "{} {}{} ago",
+        count,
+        unit,
+        if count == 1 { "" } else { "s" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn timestamp(rfc3339: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap()
+    }
+
+    #[test]
+    fn test_iso_and_unix_keep_the_timestamp_offset() {
+        let date = timestamp("2024-03-15T10:20:30+02:00");
+
+        assert_eq!(
+            DateFormat::Iso.format(&date, &date),
+            "2024-03-15 10:20:30 +0200"
+        );
+        assert_eq!(DateFormat::Unix.format(&date, &date), "1710490830");
+    }
+
+    #[test]
+    fn test_relative_rounds_to_the_nearest_unit() {
+        let now = timestamp("2024-03-15T12:00:00+00:00");
+        let relative = |date: &str| DateFormat::Relative.format(&timestamp(date), &now);
+
+        assert_eq!(relative("2024-03-15T11:59:59+00:00"), "1 second ago");
+        assert_eq!(relative("2024-03-15T11:55:00+00:00"), "5 minutes ago");
+        assert_eq!(relative("2024-03-15T09:00:00+01:00"), "4 hours ago");
+        assert_eq!(relative("2024-03-12T12:00:00+00:00"), "3 days ago");
+        assert_eq!(relative("2024-02-15T12:00:00+00:00"), "4 weeks ago");
+        assert_eq!(relative("2023-12-15T12:00:00+00:00"), "3 months ago");
+        assert_eq!(relative("2022-03-15T12:00:00+00:00"), "2 years ago");
+        assert_eq!(relative("2024-03-15T12:00:01+00:00"), "in the future");
+    }
+}
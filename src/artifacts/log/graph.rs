@@ -0,0 +1,529 @@
+//! ASCII commit graph for `log --graph`
+//!
+//! The graph tracks one lane per line of history that is still waiting for
+//! its next commit to be shown. Every commit occupies the lane that expects
+//! it; its first parent inherits the lane and any further parents open new
+//! lanes to its right.
+//!
+//! ```text
+//! *   Merge branch 'feature'
+//! |\
+//! | * Feature commit
+//! * | Main commit
+//! |/
+//! * Base commit
+//! ```
+//!
+//! Octopus merges (three or more parents) draw a `-.` fan to the right of
+//! their `*`. Lanes to the right of such a merge first step aside on
+//! standalone lines, so that the new lanes of the parents fit in between:
+//!
+//! ```text
+//! *-.   Octopus merge
+//! |\ \
+//! | | * Third parent
+//! ```
+//!
+//! Lines are rendered lazily through [`GraphWriter`], which prefixes every
+//! line of commit output with the rails of the active lanes. This keeps
+//! multi-line messages and patches aligned with the graph.
+//!
+//! When color is enabled each lane is drawn in its own color, assigned in
+//! rotation when the lane is opened.
+
+use crate::artifacts::objects::object_id::ObjectId;
+use colored::{Color, Colorize};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::io::Write;
+use std::rc::Rc;
+
+/// Colors assigned to lanes, in rotation
+const LANE_COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+/// A line of history waiting for its next commit
+#[derive(Debug, Clone)]
+struct Lane {
+    oid: ObjectId,
+    color: Color,
+}
+
+/// A single character of the graph
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    symbol: char,
+    color: Option<Color>,
+}
+
+impl Cell {
+    fn blank() -> Self {
+        Cell {
+            symbol: ' ',
+            color: None,
+        }
+    }
+}
+
+/// A row of graph cells
+#[derive(Debug, Clone, Default)]
+struct GraphLine {
+    cells: Vec<Cell>,
+}
+
+impl GraphLine {
+    fn with_width(width: usize) -> Self {
+        GraphLine {
+            cells: vec![Cell::blank(); width],
+        }
+    }
+
+    fn put(&mut self, position: usize, symbol: char, color: Option<Color>) {
+        if position >= self.cells.len() {
+            self.cells.resize(position + 1, Cell::blank());
+        }
+        self.cells[position] = Cell { symbol, color };
+    }
+
+    fn render(&self, trim: bool) -> String {
+        let len = if trim {
+            self.cells
+                .iter()
+                .rposition(|cell| cell.symbol != ' ')
+                .map_or(0, |last| last + 1)
+        } else {
+            self.cells.len()
+        };
+
+        self.cells[..len]
+            .iter()
+            .map(|cell| match cell.color {
+                Some(color) => cell.symbol.to_string().color(color).to_string(),
+                None => cell.symbol.to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Lane state of the commit graph
+#[derive(Debug, Default)]
+pub struct LogGraph {
+    /// Active lanes; `None` marks a lane closed by a root commit
+    lanes: Vec<Option<Lane>>,
+    /// Commits that will be shown (parents outside this set get no lane)
+    visible: HashSet<ObjectId>,
+    /// Index into `LANE_COLORS` for the next opened lane
+    next_color: usize,
+    /// Standalone lines to print before the commit line (pending expansion,
+    /// lane collapsing and stepping aside)
+    before_commit: Vec<GraphLine>,
+    /// Prefix of the commit line, consumed by its first output line
+    commit_prefix: Option<GraphLine>,
+    /// Standalone lines to print after the commit line (merge expansion)
+    after_commit: Vec<GraphLine>,
+    /// Prefix of every following line of the commit
+    rails: GraphLine,
+}
+
+impl LogGraph {
+    pub fn new(visible: HashSet<ObjectId>) -> Self {
+        LogGraph {
+            visible,
+            ..Default::default()
+        }
+    }
+
+    /// Advance the graph to the next commit to be shown
+    ///
+    /// # Arguments
+    ///
+    /// * `oid` - ID of the commit about to be shown
+    /// * `parents` - The commit's parents, in order
+    pub fn update(&mut self, oid: &ObjectId, parents: &[ObjectId]) {
+        let parents = parents
+            .iter()
+            .filter(|parent| self.visible.contains(*parent))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // The previous commit's merge expansion still precedes this one
+        self.before_commit = std::mem::take(&mut self.after_commit);
+        self.collapse();
+
+        let column = match self.find_lane(oid) {
+            Some(column) => column,
+            None => {
+                let lane = self.open_lane(oid.clone());
+                self.lanes.push(Some(lane));
+                self.lanes.len() - 1
+            }
+        };
+        let color = self.lanes[column]
+            .as_ref()
+            .map_or(LANE_COLORS[0], |lane| lane.color);
+
+        let mut children = Vec::with_capacity(parents.len());
+        if let Some(first_parent) = parents.first() {
+            children.push(Some(Lane {
+                oid: first_parent.clone(),
+                color,
+            }));
+        }
+        for parent in parents.iter().skip(1) {
+            children.push(Some(self.open_lane(parent.clone())));
+        }
+        let extra_parents = children.len().saturating_sub(1);
+
+        let mut lanes = self.lanes[..column].to_vec();
+        if children.is_empty() {
+            lanes.push(None);
+        }
+        lanes.extend(children);
+        lanes.extend_from_slice(&self.lanes[column + 1..]);
+
+        let width = 2 * self.lanes.len().max(lanes.len());
+
+        // Lanes right of an octopus merge make room for its fan beforehand
+        let fan = 2 * extra_parents.saturating_sub(1);
+        if self.lanes[column + 1..].iter().any(Option::is_some) {
+            for step in 0..fan / 2 {
+                let mut line = GraphLine::with_width(width);
+                for (i, lane) in self.lanes.iter().enumerate() {
+                    let Some(lane) = lane else { continue };
+                    if i <= column {
+                        line.put(2 * i, '|', Some(lane.color));
+                    } else {
+                        line.put(2 * (i + step), '\\', Some(lane.color));
+                    }
+                }
+                self.before_commit.push(line);
+            }
+        }
+
+        let mut commit_line = GraphLine::with_width(width);
+        for (i, lane) in self.lanes.iter().enumerate() {
+            let Some(lane) = lane else { continue };
+            let position = if i > column { 2 * i + fan } else { 2 * i };
+            commit_line.put(position, '|', Some(lane.color));
+        }
+        commit_line.put(2 * column, '*', None);
+        for offset in 1..=fan {
+            let symbol = if offset == fan { '.' } else { '-' };
+            let lane_color = lanes[column + offset / 2 + 1]
+                .as_ref()
+                .map(|lane| lane.color);
+            commit_line.put(2 * column + offset, symbol, lane_color);
+        }
+        self.commit_prefix = Some(commit_line);
+
+        if extra_parents > 0 {
+            let mut expansion = GraphLine::with_width(width);
+            for (i, lane) in self.lanes.iter().enumerate() {
+                let Some(lane) = lane else { continue };
+                if i < column {
+                    expansion.put(2 * i, '|', Some(lane.color));
+                } else if i > column {
+                    expansion.put(2 * i + fan + 1, '\\', Some(lane.color));
+                }
+            }
+            expansion.put(2 * column, '|', Some(color));
+            for k in 1..=extra_parents {
+                let lane_color = lanes[column + k].as_ref().map(|lane| lane.color);
+                expansion.put(2 * column + 2 * k - 1, '\\', lane_color);
+            }
+            self.after_commit.push(expansion);
+        }
+
+        self.rails = self.rails_of(&lanes, 2 * lanes.len());
+        self.lanes = lanes;
+    }
+
+    /// Take the standalone graph lines and prefix for the next output line
+    ///
+    /// The first line after an update is the commit line; standalone lines
+    /// that must precede it are returned alongside its prefix.
+    pub fn next_line(&mut self, blank: bool) -> (Vec<String>, String) {
+        let (lines, prefix) = match self.commit_prefix.take() {
+            Some(prefix) => (std::mem::take(&mut self.before_commit), prefix),
+            None => (std::mem::take(&mut self.after_commit), self.rails.clone()),
+        };
+
+        (
+            lines.iter().map(|line| line.render(true)).collect(),
+            prefix.render(blank),
+        )
+    }
+
+    /// Merge lanes waiting for the same commit and drop closed lanes
+    ///
+    /// Lanes that move left are drawn with `/` on a standalone line.
+    fn collapse(&mut self) {
+        let mut compacted: Vec<Lane> = Vec::with_capacity(self.lanes.len());
+        let mut targets = Vec::with_capacity(self.lanes.len());
+
+        for lane in &self.lanes {
+            let target = lane.as_ref().map(|lane| {
+                match compacted.iter().position(|kept| kept.oid == lane.oid) {
+                    Some(target) => target,
+                    None => {
+                        compacted.push(lane.clone());
+                        compacted.len() - 1
+                    }
+                }
+            });
+            targets.push(target);
+        }
+
+        let shifted = targets
+            .iter()
+            .enumerate()
+            .any(|(i, target)| target.is_some_and(|target| target < i));
+
+        if shifted {
+            let mut line = GraphLine::with_width(2 * self.lanes.len());
+            for (i, (lane, target)) in self.lanes.iter().zip(&targets).enumerate() {
+                let (Some(lane), Some(target)) = (lane, target) else {
+                    continue;
+                };
+                if *target == i {
+                    line.put(2 * i, '|', Some(lane.color));
+                } else {
+                    line.put(2 * i - 1, '/', Some(lane.color));
+                }
+            }
+            self.before_commit.push(line);
+        }
+
+        self.lanes = compacted.into_iter().map(Some).collect();
+    }
+
+    fn find_lane(&self, oid: &ObjectId) -> Option<usize> {
+        self.lanes
+            .iter()
+            .position(|lane| lane.as_ref().is_some_and(|lane| lane.oid == *oid))
+    }
+
+    fn open_lane(&mut self, oid: ObjectId) -> Lane {
+        let color = LANE_COLORS[self.next_color % LANE_COLORS.len()];
+        self.next_color += 1;
+
+        Lane { oid, color }
+    }
+
+    fn rails_of(&self, lanes: &[Option<Lane>], width: usize) -> GraphLine {
+        let mut line = GraphLine::with_width(width);
+        for (i, lane) in lanes.iter().enumerate() {
+            if let Some(lane) = lane {
+                line.put(2 * i, '|', Some(lane.color));
+            }
+        }
+        line
+    }
+}
+
+/// Writer that prefixes every output line with the commit graph
+pub struct GraphWriter {
+    inner: Box<dyn Write>,
+    graph: Rc<RefCell<LogGraph>>,
+    /// Bytes of the current, not yet terminated line
+    line: Vec<u8>,
+    /// Whether the current line was already prefixed by a flush
+    prefixed: bool,
+}
+
+impl GraphWriter {
+    pub fn new(inner: Box<dyn Write>, graph: Rc<RefCell<LogGraph>>) -> Self {
+        GraphWriter {
+            inner,
+            graph,
+            line: Vec::new(),
+            prefixed: false,
+        }
+    }
+
+    fn emit_line(&mut self) -> std::io::Result<()> {
+        if !self.prefixed {
+            let blank = self.line.iter().all(|byte| byte.is_ascii_whitespace());
+            let (lines, prefix) = self.graph.borrow_mut().next_line(blank);
+
+            for line in lines {
+                writeln!(self.inner, "{}", line)?;
+            }
+            self.inner.write_all(prefix.as_bytes())?;
+        }
+
+        self.inner.write_all(&self.line)?;
+        self.prefixed = !self.line.ends_with(b"\n");
+        self.line.clear();
+
+        Ok(())
+    }
+}
+
+impl Write for GraphWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for chunk in buf.split_inclusive(|byte| *byte == b'\n') {
+            self.line.extend_from_slice(chunk);
+            if chunk.ends_with(b"\n") {
+                self.emit_line()?;
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.line.is_empty() {
+            self.emit_line()?;
+        }
+
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(byte: char) -> ObjectId {
+        ObjectId::try_parse(byte.to_string().repeat(40)).unwrap()
+    }
+
+    fn render(graph: &mut LogGraph, commits: &[(ObjectId, Vec<ObjectId>)]) -> Vec<String> {
+        let mut output = Vec::new();
+        for (commit, parents) in commits {
+            graph.update(commit, parents);
+            let (lines, prefix) = graph.next_line(false);
+            output.extend(lines);
+            output.push(format!("{}{}", prefix, &commit.as_ref()[..1]));
+            let (lines, _) = graph.next_line(false);
+            output.extend(lines);
+        }
+        output
+    }
+
+    #[test]
+    fn test_linear_history_uses_single_lane() {
+        colored::control::set_override(false);
+        let (a, b) = (oid('a'), oid('b'));
+        let mut graph = LogGraph::new(HashSet::from([a.clone(), b.clone()]));
+
+        let output = render(&mut graph, &[(b.clone(), vec![a.clone()]), (a, vec![])]);
+
+        assert_eq!(output, vec!["* b", "* a"]);
+    }
+
+    #[test]
+    fn test_merge_expands_and_collapses_lanes() {
+        colored::control::set_override(false);
+        let (base, main, side, merge) = (oid('a'), oid('b'), oid('c'), oid('d'));
+        let mut graph = LogGraph::new(HashSet::from([
+            base.clone(),
+            main.clone(),
+            side.clone(),
+            merge.clone(),
+        ]));
+
+        let output = render(
+            &mut graph,
+            &[
+                (merge, vec![main.clone(), side.clone()]),
+                (side, vec![base.clone()]),
+                (main, vec![base.clone()]),
+                (base, vec![]),
+            ],
+        );
+
+        assert_eq!(output, vec!["*   d", "|\\", "| * c", "* | b", "|/", "* a"]);
+    }
+
+    #[test]
+    fn test_octopus_merge_fans_out_to_every_parent() {
+        colored::control::set_override(false);
+        let (base, first, second, third, merge) =
+            (oid('a'), oid('b'), oid('c'), oid('d'), oid('e'));
+        let mut graph = LogGraph::new(HashSet::from([
+            base.clone(),
+            first.clone(),
+            second.clone(),
+            third.clone(),
+            merge.clone(),
+        ]));
+
+        let output = render(
+            &mut graph,
+            &[
+                (merge, vec![first.clone(), second.clone(), third.clone()]),
+                (third, vec![base.clone()]),
+                (second, vec![base.clone()]),
+                (first, vec![base.clone()]),
+                (base, vec![]),
+            ],
+        );
+
+        assert_eq!(
+            output,
+            vec![
+                "*-.   e", "|\\ \\", "| | * d", "| * | c", "| |/", "* | b", "|/", "* a"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lanes_right_of_octopus_merge_step_aside() {
+        colored::control::set_override(false);
+        let (base, first, second, third, octopus, side, top) = (
+            oid('a'),
+            oid('b'),
+            oid('c'),
+            oid('d'),
+            oid('e'),
+            oid('f'),
+            oid('0'),
+        );
+        let mut graph = LogGraph::new(HashSet::from([
+            base.clone(),
+            first.clone(),
+            second.clone(),
+            third.clone(),
+            octopus.clone(),
+            side.clone(),
+            top.clone(),
+        ]));
+
+        let output = render(
+            &mut graph,
+            &[
+                (top, vec![octopus.clone(), side.clone()]),
+                (octopus, vec![first, second, third]),
+            ],
+        );
+
+        assert_eq!(
+            output,
+            vec!["*   0", "|\\", "| \\", "*-. |   e", "|\\ \\ \\"]
+        );
+    }
+
+    #[test]
+    fn test_lanes_get_distinct_colors() {
+        let (base, main, side, merge) = (oid('a'), oid('b'), oid('c'), oid('d'));
+        let mut graph = LogGraph::new(HashSet::from([base.clone(), main.clone(), side.clone()]));
+
+        graph.update(&merge, &[main, side]);
+
+        let colors = graph
+            .lanes
+            .iter()
+            .flatten()
+            .map(|lane| lane.color)
+            .collect::<Vec<_>>();
+        assert_eq!(colors, vec![Color::Red, Color::Green]);
+    }
+}
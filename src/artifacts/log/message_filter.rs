@@ -0,0 +1,187 @@
+//! Commit message filtering for git log
+//!
+//! Implements `--grep`, `--invert-grep`, `--all-match`, `--author` and `-i`:
+//! a commit is kept when its full message (subject and body) matches any of
+//! the `--grep` regular expressions, or all of them with `--all-match`, or
+//! when it does not if the filter is inverted. `--author` patterns are
+//! matched against `Name <email>`; any of them must match on top of the
+//! message patterns.
+
+use crate::artifacts::objects::commit::Commit;
+use regex::{Regex, RegexBuilder};
+
+/// Message filter for log traversal
+#[derive(Debug, Clone)]
+pub struct MessageFilter {
+    patterns: Vec<Regex>,
+    authors: Vec<Regex>,
+    invert: bool,
+    all_match: bool,
+}
+
+impl MessageFilter {
+    /// Build a filter from regular expressions
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Regular expressions searched for in the commit message
+    /// * `invert` - Keep the commits whose message does NOT match
+    /// * `ignore_case` - Match letters regardless of case
+    pub fn new<P: AsRef<str>>(
+        patterns: &[P],
+        invert: bool,
+        ignore_case: bool,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            patterns: compile(patterns, ignore_case)?,
+            authors: Vec::new(),
+            invert,
+            all_match: false,
+        })
+    }
+
+    /// Also require the author to match one of `authors`
+    pub fn with_authors<P: AsRef<str>>(
+        mut self,
+        authors: &[P],
+        ignore_case: bool,
+    ) -> anyhow::Result<Self> {
+        self.authors = compile(authors, ignore_case)?;
+        Ok(self)
+    }
+
+    /// Require every message pattern to match instead of any of them
+    pub fn with_all_match(mut self, all_match: bool) -> Self {
+        self.all_match = all_match;
+        self
+    }
+
+    /// Check whether a commit should be shown
+    pub fn matches(&self, commit: &Commit) -> bool {
+        self.message_matches(commit.message()) && self.author_matches(commit)
+    }
+
+    fn message_matches(&self, message: &str) -> bool {
+        if self.patterns.is_empty() {
+            return true;
+        }
+
+        let matched = if self.all_match {
+            self.patterns
+                .iter()
+                .all(|pattern| pattern.is_match(message))
+        } else {
+            self.patterns
+                .iter()
+                .any(|pattern| pattern.is_match(message))
+        };
+
+        matched != self.invert
+    }
+
+    fn author_matches(&self, commit: &Commit) -> bool {
+        let author = commit.author().display_name();
+
+        self.authors.is_empty() || self.authors.iter().any(|pattern| pattern.is_match(&author))
+    }
+}
+
+fn compile<P: AsRef<str>>(patterns: &[P], ignore_case: bool) -> anyhow::Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Ok(RegexBuilder::new(pattern.as_ref())
+                .case_insensitive(ignore_case)
+                .multi_line(true)
+                .build()?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::objects::commit::Author;
+    use crate::artifacts::objects::object_id::ObjectId;
+
+    fn commit_with_message(message: &str) -> Commit {
+        Commit::new(
+            vec![],
+            ObjectId::empty_tree(),
+            Author::new("fake_user".to_string(), "fake_email@email.com".to_string()),
+            message.to_string(),
+        )
+    }
+
+    #[test]
+    fn message_filter_matches_subject_and_body() {
+        let filter = MessageFilter::new(&["fix"], false, false).unwrap();
+
+        assert!(filter.matches(&commit_with_message("fix parser")));
+        assert!(filter.matches(&commit_with_message("Parser\n\nThis is a fix")));
+        assert!(!filter.matches(&commit_with_message("Add parser")));
+    }
+
+    #[test]
+    fn message_filter_anchors_match_each_line() {
+        let filter = MessageFilter::new(&["^Refs:"], false, false).unwrap();
+
+        assert!(filter.matches(&commit_with_message("Add parser\n\nRefs: #12")));
+    }
+
+    #[test]
+    fn message_filter_ignores_case_when_requested() {
+        let filter = MessageFilter::new(&["fix"], false, true).unwrap();
+
+        assert!(filter.matches(&commit_with_message("FIX parser")));
+        assert!(
+            !MessageFilter::new(&["fix"], false, false)
+                .unwrap()
+                .matches(&commit_with_message("FIX parser"))
+        );
+    }
+
+    #[test]
+    fn message_filter_inverted_keeps_non_matching_commits() {
+        let filter = MessageFilter::new(&["fix"], true, false).unwrap();
+
+        assert!(!filter.matches(&commit_with_message("fix parser")));
+        assert!(filter.matches(&commit_with_message("Add parser")));
+    }
+
+    #[test]
+    fn message_filter_rejects_invalid_pattern() {
+        assert!(MessageFilter::new(&["(unclosed"], false, false).is_err());
+    }
+
+    #[test]
+    fn message_filter_matches_any_pattern_unless_all_match() {
+        let filter = MessageFilter::new(&["parser", "fix"], false, false).unwrap();
+
+        assert!(filter.matches(&commit_with_message("Add parser")));
+        assert!(filter.matches(&commit_with_message("fix parser")));
+        assert!(!filter.matches(&commit_with_message("Add lexer")));
+
+        let filter = filter.with_all_match(true);
+
+        assert!(!filter.matches(&commit_with_message("Add parser")));
+        assert!(filter.matches(&commit_with_message("fix parser")));
+    }
+
+    #[test]
+    fn message_filter_requires_author_match() {
+        let filter = MessageFilter::new(&["parser"], false, false)
+            .unwrap()
+            .with_authors(&["someone_else"], false)
+            .unwrap();
+
+        assert!(!filter.matches(&commit_with_message("fix parser")));
+
+        let filter = MessageFilter::new::<&str>(&[], false, false)
+            .unwrap()
+            .with_authors(&["FAKE_USER"], true)
+            .unwrap();
+
+        assert!(filter.matches(&commit_with_message("fix parser")));
+    }
+}
@@ -4,6 +4,11 @@
 //!
 //! - `rev_list`: Revision list traversal with range expressions
 //! - `path_filter`: Efficient path filtering using trie data structure
+//! - `graph`: ASCII commit graph rendering for `--graph`
+//! - `message_filter`: Commit message matching for `--grep`
+//! - `pretty_format`: Placeholder templates for `--format`
+//! - `date_format`: Timestamp rendering for `--date`
+//! - `simplify`: History simplification for `--simplify-by-decoration`
 //!
 //! ## Algorithm
 //!
@@ -12,7 +17,13 @@
 //! - Range expressions (commit1..commit2)
 //! - Excluded revisions (^commit)
 //! - Path filtering (show only commits affecting specific files)
+//! - Message filtering (show only commits whose message matches a pattern)
 //! - Proper handling of merge commits and complex histories
 
+pub mod date_format;
+pub mod graph;
+pub mod message_filter;
 pub mod path_filter;
+pub mod pretty_format;
 pub mod rev_list;
+pub mod simplify;
@@ -0,0 +1,230 @@
+//! Custom `--format`/`--pretty` templates for git log
+//!
+//! A template is plain text with `%`-placeholders expanded per commit
+//! (`%H`, `%h`, `%s`, `%an`, ...). Placeholders this module does not know are
+//! printed as written, like Git does.
+
+use crate::CommitDisplayFormat;
+use crate::artifacts::objects::commit::{Author, Commit};
+use crate::artifacts::objects::object::Object;
+use crate::artifacts::objects::object_id::ObjectId;
+use clap::ValueEnum;
+
+/// Value of `--format`/`--pretty`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrettyFormat {
+    /// One of the named layouts (`medium`, `oneline`, `raw`)
+    Builtin(CommitDisplayFormat),
+    /// A placeholder template
+    Template(Template),
+}
+
+/// Placeholder template of `--format`
+///
+/// `format:` templates are separated by newlines; `tformat:` ones (and bare
+/// templates containing a `%`) are terminated by one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pub text: String,
+    pub terminated: bool,
+}
+
+/// Parse a `--format` value: a layout name, `format:<t>`, `tformat:<t>`, or
+/// a bare template containing `%`
+pub fn parse_pretty_format(value: &str) -> Result<PrettyFormat, String> {
+    if let Some(template) = value.strip_prefix("format:") {
+        return Ok(PrettyFormat::Template(Template {
+            text: template.to_string(),
+            terminated: false,
+        }));
+    }
+    if let Some(template) = value.strip_prefix("tformat:") {
+        return Ok(PrettyFormat::Template(Template {
+            text: template.to_string(),
+            terminated: true,
+        }));
+    }
+    if value.contains('%') {
+        return Ok(PrettyFormat::Template(Template {
+            text: value.to_string(),
+            terminated: true,
+        }));
+    }
+
+    CommitDisplayFormat::from_str(value, false)
+        .map(PrettyFormat::Builtin)
+        .map_err(|_| format!("invalid --pretty format: {}", value))
+}
+
+/// Expand the placeholders of `template` for `commit`
+///
+/// `abbrev` shortens the object IDs printed by `%h`, `%t` and `%p`.
+pub fn render_template(
+    template: &str,
+    commit: &Commit,
+    abbrev: impl Fn(&ObjectId) -> anyhow::Result<String>,
+) -> anyhow::Result<String> {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(position) = rest.find('%') {
+        output.push_str(&rest[..position]);
+        rest = &rest[position + 1..];
+
+        match expand_placeholder(rest, commit, &abbrev)? {
+            Some((expansion, length)) => {
+                output.push_str(&expansion);
+                rest = &rest[length..];
+            }
+            None => output.push('%'),
+        }
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+/// Expansion of the placeholder at the start of `spec` (after the `%`) and
+/// the number of bytes it spans
+fn expand_placeholder(
+    spec: &str,
+    commit: &Commit,
+    abbrev: &impl Fn(&ObjectId) -> anyhow::Result<String>,
+) -> anyhow::Result<Option<(String, usize)>> {
+    let mut chars = spec.chars();
+    let expansion = match chars.next() {
+        Some('%') => "%".to_string(),
+        Some('n') => "\n".to_string(),
+        Some('H') => commit.object_id()?.to_string(),
+        Some('h') => abbrev(&commit.object_id()?)?,
+        Some('T') => commit.tree_oid().to_string(),
+        Some('t') => abbrev(commit.tree_oid())?,
+        Some('P') => commit
+            .parents()
+            .iter()
+            .map(ObjectId::to_string)
+            .collect::<Vec<_>>()
+            .join(" "),
+        Some('p') => commit
+            .parents()
+            .iter()
+            .map(abbrev)
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .join(" "),
+        Some('s') => commit.short_message(),
+        Some('b') => terminated(commit_body(commit.message())),
+        Some('B') => terminated(commit.message()),
+        Some('a') => match chars
+            .next()
+            .and_then(|field| person_field(commit.author(), field))
+        {
+            Some(value) => return Ok(Some((value, 2))),
+            None => return Ok(None),
+        },
+        Some('c') => match chars
+            .next()
+            .and_then(|field| person_field(commit.committer(), field))
+        {
+            Some(value) => return Ok(Some((value, 2))),
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    Ok(Some((expansion, 1)))
+}
+
+/// `%an`/`%ae`/`%ad`/`%at` (and the committer forms) for one person
+fn person_field(person: &Author, field: char) -> Option<String> {
+    match field {
+        'n' => Some(person.name().to_string()),
+        'e' => Some(person.email().to_string()),
+        'd' => Some(person.readable_timestamp()),
+        't' => Some(person.timestamp().timestamp().to_string()),
+        _ => None,
+    }
+}
+
+/// The message without its subject line and the blank lines after it
+fn commit_body(message: &str) -> &str {
+    message
+        .split_once('\n')
+        .map(|(_, body)| body.trim_start_matches('\n'))
+        .unwrap_or("")
+}
+
+/// Message text ending with the newline that parsing the commit dropped,
+/// like git prints `%b` and `%B`
+fn terminated(text: &str) -> String {
+    match text {
+        "" => String::new(),
+        text => format!("{}\n", text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::objects::object::Unpackable;
+    use pretty_assertions::assert_eq;
+
+    const TREE_OID: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+    const PARENT_OID: &str = "8e5b1b6a4bb1d6e2c9a1e0b3f6f4a2b5c7d9e1f3";
+
+    fn commit() -> Commit {
+        let content = format!(
+            "tree {TREE_OID}\nparent {PARENT_OID}\n\
+            author A. U. Thor <author@example.com> 1700000000 +0200\n\
+            committer C. O. Mitter <committer@example.com> 1700000100 -0500\n\
+            \n\
+            Add feature\n\nWith a body\n"
+        );
+
+        Commit::deserialize(std::io::Cursor::new(content)).unwrap()
+    }
+
+    fn short(oid: &ObjectId) -> anyhow::Result<String> {
+        Ok(oid.as_ref()[..5].to_string())
+    }
+
+    #[test]
+    fn test_parse_pretty_format_variants() {
+        assert_eq!(
+            parse_pretty_format("oneline"),
+            Ok(PrettyFormat::Builtin(CommitDisplayFormat::OneLine))
+        );
+        assert_eq!(
+            parse_pretty_format("format:%h"),
+            Ok(PrettyFormat::Template(Template {
+                text: "%h".to_string(),
+                terminated: false
+            }))
+        );
+        assert_eq!(
+            parse_pretty_format("%h %s"),
+            Ok(PrettyFormat::Template(Template {
+                text: "%h %s".to_string(),
+                terminated: true
+            }))
+        );
+        assert!(parse_pretty_format("fancy").is_err());
+    }
+
+    #[test]
+    fn test_render_template_expands_placeholders() {
+        let rendered = render_template("%t %p|%s|%an <%ae>|%cn %ct|%b", &commit(), short).unwrap();
+
+        assert_eq!(
+            rendered,
+            "4b825 8e5b1|Add feature|A. U. Thor <author@example.com>|C. O. Mitter 1700000100|\
+            With a body\n"
+        );
+    }
+
+    #[test]
+    fn test_render_template_keeps_unknown_placeholders() {
+        let rendered = render_template("100%% %x %a? %", &commit(), short).unwrap();
+
+        assert_eq!(rendered, "100% %x %a? %");
+    }
+}
@@ -7,6 +7,9 @@
 //! - Range expressions (commit1..commit2)
 //! - Excluded revisions (^commit)
 //! - Path filtering (show only commits affecting specific files)
+//! - Message filtering (show only commits whose message matches a pattern)
+//! - First-parent traversal (only follow the first parent of merges)
+//! - Boundary commits (the excluded parents of listed commits, yielded last)
 //! - Handling of merge commits and complex histories
 //!
 //! ## Algorithm
@@ -25,8 +28,9 @@
 
 use crate::areas::refs::HEAD_REF_NAME;
 use crate::areas::repository::Repository;
-use crate::artifacts::branch::revision::Revision;
+use crate::artifacts::branch::revision::{Revision, unknown_revision};
 use crate::artifacts::diff::tree_diff::TreeDiff;
+use crate::artifacts::log::message_filter::MessageFilter;
 use crate::artifacts::log::path_filter::PathFilter;
 use crate::artifacts::objects::commit::Commit;
 use crate::artifacts::objects::object::Object;
@@ -53,6 +57,8 @@ enum LogTraversalCommitFlag {
     Uninteresting,
     /// The commit has the same tree as its parent (used for filtering).
     TreeSame,
+    /// The commit is an uninteresting parent of a yielded commit.
+    Boundary,
 }
 
 /// Wrapper for ObjectId in the priority queue that orders by commit timestamp.
@@ -107,6 +113,7 @@ pub type CommitsDiffs<'r> = HashMap<(Option<ObjectId>, Option<ObjectId>), TreeDi
 /// - `commits_output_list`: Final ordered list of commits to display
 /// - `path_filter`: Optional filter to show only commits affecting specific paths
 /// - `commits_diffs`: Cached tree diffs for path filtering
+/// - `message_filter`: Optional filter to show only commits whose message matches
 pub struct RevList<'r> {
     repository: &'r Repository,
     /// Cache of loaded commit objects
@@ -125,6 +132,22 @@ pub struct RevList<'r> {
     commits_diffs: CommitsDiffs<'r>,
     /// Trie-based path filter
     path_filter: PathFilter,
+    /// Commit message filter
+    message_filter: Option<MessageFilter>,
+    /// Only walk the first parent of merge commits
+    first_parent: bool,
+    /// Whether to yield boundary commits after the listed ones
+    boundary: bool,
+    /// Boundary commits found so far, in the order they were reached
+    boundary_commits: VecDeque<ObjectId>,
+    /// Number of matching commits to leave out before yielding any
+    skip: usize,
+    /// Number of commits to yield at most
+    max_count: Option<usize>,
+    /// Matching commits left out so far
+    skipped: usize,
+    /// Commits yielded so far, boundary commits aside
+    yielded: usize,
 }
 
 impl<'r> RevList<'r> {
@@ -132,6 +155,7 @@ impl<'r> RevList<'r> {
         repository: &'r Repository,
         mut target_revisions: Vec<LogRevisionTargets>,
         target_files: Option<Vec<PathBuf>>,
+        first_parent: bool,
     ) -> anyhow::Result<Self> {
         let mut rev_list = Self {
             repository,
@@ -143,19 +167,22 @@ impl<'r> RevList<'r> {
             interesting_files: Vec::new(),
             commits_diffs: HashMap::new(),
             path_filter: PathFilter::empty(),
+            message_filter: None,
+            first_parent,
+            boundary: false,
+            boundary_commits: VecDeque::new(),
+            skip: 0,
+            max_count: None,
+            skipped: 0,
+            yielded: 0,
         };
 
+        // Like git, paths after `--` need not exist in the workspace: a path
+        // that was never committed just matches no commit
         let interesting_files = if let Some(files) = target_files {
-            let mut interesting_files = vec![];
-            for file in files {
-                let _file_stat = rev_list.repository.workspace().stat_file(file.as_ref())?;
-
-                interesting_files.push(file);
-            }
-
             rev_list.is_limited = true;
 
-            interesting_files
+            files
         } else {
             vec![]
         };
@@ -187,6 +214,36 @@ impl<'r> RevList<'r> {
         Ok(rev_list)
     }
 
+    /// Only yield the commits accepted by the given message filter
+    ///
+    /// Filtered-out commits are still walked, so their ancestors are reached.
+    pub fn with_message_filter(mut self, message_filter: Option<MessageFilter>) -> Self {
+        self.message_filter = message_filter;
+        self
+    }
+
+    /// Also yield the excluded commits whose children are listed, once all
+    /// listed commits were yielded
+    pub fn with_boundary(mut self, boundary: bool) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Leave out the first `skip` commits that would be yielded
+    pub fn with_skip(mut self, skip: usize) -> Self {
+        self.skip = skip;
+        self
+    }
+
+    /// Stop the traversal once `max_count` commits were yielded
+    ///
+    /// Commits left out by the path and message filters, or by
+    /// [`with_skip`](Self::with_skip), do not count.
+    pub fn with_max_count(mut self, max_count: Option<usize>) -> Self {
+        self.max_count = max_count;
+        self
+    }
+
     pub fn into_iter(self) -> RevListIntoIter<'r> {
         RevListIntoIter { rev_list: self }
     }
@@ -288,7 +345,7 @@ impl<'r> RevList<'r> {
     ) -> anyhow::Result<()> {
         let commit_oid = revision
             .resolve(self.repository)?
-            .ok_or_else(|| anyhow::anyhow!("Failed to resolve revision"))?;
+            .ok_or_else(|| unknown_revision(&revision.to_string()))?;
 
         self.load_commit(&commit_oid)?;
         self.enqueue_commit(&commit_oid)?;
@@ -416,6 +473,8 @@ impl<'r> RevList<'r> {
     ) -> anyhow::Result<Vec<Option<&'a ObjectId>>> {
         let parents = if commit.parents().is_empty() {
             vec![None]
+        } else if self.first_parent {
+            vec![commit.parent()]
         } else {
             commit.parents().iter().map(Some).collect()
         };
@@ -468,16 +527,45 @@ pub struct RevListIntoIter<'r> {
     rev_list: RevList<'r>,
 }
 
+impl RevListIntoIter<'_> {
+    /// Whether `oid` was yielded as a boundary commit
+    pub fn is_boundary(&self, oid: &ObjectId) -> bool {
+        self.rev_list
+            .commits_flags
+            .get(oid)
+            .is_some_and(|flags| flags.contains(&LogTraversalCommitFlag::Boundary))
+    }
+
+    /// Remember the uninteresting parents of a yielded commit
+    fn mark_boundary_parents(&mut self, commit: &Commit) {
+        let parents = if self.rev_list.first_parent {
+            &commit.parents()[..commit.parents().len().min(1)]
+        } else {
+            commit.parents()
+        };
+
+        for parent in parents {
+            if let Some(flags) = self.rev_list.commits_flags.get_mut(parent)
+                && flags.contains(&LogTraversalCommitFlag::Uninteresting)
+                && flags.insert(LogTraversalCommitFlag::Boundary)
+            {
+                self.rev_list.boundary_commits.push_back(parent.clone());
+            }
+        }
+    }
+}
+
 impl Iterator for RevListIntoIter<'_> {
     type Item = Commit;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.rev_list.commits_pqueue.pop().and_then(|entry| {
-            let commit = if let Some(commit) = self.rev_list.commits_cache.get(&entry.oid) {
-                commit.clone()
-            } else {
-                return None;
-            };
+        let rev_list = &self.rev_list;
+        let exhausted = rev_list
+            .max_count
+            .is_some_and(|max_count| rev_list.yielded >= max_count);
+
+        while !exhausted && let Some(entry) = self.rev_list.commits_pqueue.pop() {
+            let commit = self.rev_list.commits_cache.get(&entry.oid)?.clone();
 
             // Avoid adding parents again if the rev_list is limited,
             // as they were already traversed during limiting
@@ -488,7 +576,26 @@ impl Iterator for RevListIntoIter<'_> {
                 return None;
             }
 
-            Some(commit)
-        })
+            if self
+                .rev_list
+                .message_filter
+                .as_ref()
+                .is_none_or(|filter| filter.matches(&commit))
+            {
+                if self.rev_list.skipped < self.rev_list.skip {
+                    self.rev_list.skipped += 1;
+                    continue;
+                }
+                self.rev_list.yielded += 1;
+                if self.rev_list.boundary {
+                    self.mark_boundary_parents(&commit);
+                }
+                return Some(commit);
+            }
+        }
+
+        // Boundary commits come last, once every listed commit is known
+        let oid = self.rev_list.boundary_commits.pop_front()?;
+        self.rev_list.commits_cache.get(&oid).cloned()
     }
 }
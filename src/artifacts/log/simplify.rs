@@ -0,0 +1,144 @@
+//! History simplification for `--simplify-by-decoration`
+//!
+//! Only the commits some ref points at are shown, plus the merges joining
+//! lines of history that lead to different decorated commits; the linear
+//! runs of undecorated commits between them are collapsed.
+//!
+//! A merge is kept when two of its parents reach sets of kept ancestors of
+//! which neither contains the other, i.e. when dropping it would lose the
+//! fact that two decorated lines of history meet there.
+
+use crate::artifacts::objects::object_id::ObjectId;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Select the commits shown by `--simplify-by-decoration`
+///
+/// `commits` maps every commit of the walk to its parents; parents outside
+/// of it are ignored. `decorated` holds the commits pointed at by refs.
+pub fn simplify_by_decoration(
+    commits: &HashMap<ObjectId, Vec<ObjectId>>,
+    decorated: &HashSet<ObjectId>,
+) -> HashSet<ObjectId> {
+    let mut kept = HashSet::new();
+    // Nearest kept ancestors of each undecided or dropped commit
+    let mut frontiers: HashMap<&ObjectId, BTreeSet<&ObjectId>> = HashMap::new();
+
+    for start in commits.keys() {
+        // Parents are decided before their children, without recursion
+        let mut stack = vec![(start, false)];
+        while let Some((oid, parents_done)) = stack.pop() {
+            if frontiers.contains_key(oid) {
+                continue;
+            }
+            let parents = commits[oid]
+                .iter()
+                .filter(|parent| commits.contains_key(*parent))
+                .collect::<Vec<_>>();
+
+            if !parents_done {
+                stack.push((oid, true));
+                stack.extend(
+                    parents
+                        .iter()
+                        .filter(|parent| !frontiers.contains_key(**parent))
+                        .map(|parent| (*parent, false)),
+                );
+                continue;
+            }
+
+            let parent_frontiers = parents
+                .iter()
+                .map(|parent| match kept.contains(*parent) {
+                    true => BTreeSet::from([*parent]),
+                    false => frontiers[*parent].clone(),
+                })
+                .collect::<Vec<_>>();
+
+            if decorated.contains(oid) || joins_decorated_lines(&parent_frontiers) {
+                kept.insert(oid.clone());
+            }
+            frontiers.insert(oid, parent_frontiers.into_iter().flatten().collect());
+        }
+    }
+
+    kept
+}
+
+/// Whether two parents lead to kept ancestors neither of them fully covers
+fn joins_decorated_lines(parent_frontiers: &[BTreeSet<&ObjectId>]) -> bool {
+    parent_frontiers.iter().enumerate().any(|(i, left)| {
+        parent_frontiers[i + 1..]
+            .iter()
+            .any(|right| !left.is_subset(right) && !right.is_subset(left))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn oid(digit: char) -> ObjectId {
+        ObjectId::try_parse(digit.to_string().repeat(40)).unwrap()
+    }
+
+    fn history(edges: &[(char, &[char])]) -> HashMap<ObjectId, Vec<ObjectId>> {
+        edges
+            .iter()
+            .map(|(commit, parents)| (oid(*commit), parents.iter().map(|p| oid(*p)).collect()))
+            .collect()
+    }
+
+    fn oids(digits: &[char]) -> HashSet<ObjectId> {
+        digits.iter().map(|digit| oid(*digit)).collect()
+    }
+
+    #[test]
+    fn test_collapses_undecorated_linear_runs() {
+        // 1 <- 2 <- 3 <- 4 <- 5, tagged at 2 and 5
+        let commits = history(&[
+            ('1', &[]),
+            ('2', &['1']),
+            ('3', &['2']),
+            ('4', &['3']),
+            ('5', &['4']),
+        ]);
+
+        let kept = simplify_by_decoration(&commits, &oids(&['2', '5']));
+
+        assert_eq!(kept, oids(&['2', '5']));
+    }
+
+    #[test]
+    fn test_keeps_merges_joining_decorated_lines() {
+        // 1 <- 2 (tagged) <- 4 (merge) <- 5 (branch)
+        //  \<- 3 (tagged) <-/
+        let commits = history(&[
+            ('1', &[]),
+            ('2', &['1']),
+            ('3', &['1']),
+            ('4', &['2', '3']),
+            ('5', &['4']),
+        ]);
+
+        let kept = simplify_by_decoration(&commits, &oids(&['2', '3', '5']));
+
+        assert_eq!(kept, oids(&['2', '3', '4', '5']));
+    }
+
+    #[test]
+    fn test_drops_merges_of_undecorated_side_lines() {
+        // The side line of the merge 4 leads back to the tagged 1 only
+        let commits = history(&[
+            ('1', &[]),
+            ('2', &['1']),
+            ('3', &['1']),
+            ('4', &['2', '3']),
+            ('5', &['4']),
+        ]);
+
+        let kept = simplify_by_decoration(&commits, &oids(&['1', '5']));
+
+        assert_eq!(kept, oids(&['1', '5']));
+    }
+}
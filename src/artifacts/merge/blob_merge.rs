@@ -0,0 +1,115 @@
+//! Conflict markers for content conflicts
+//!
+//! Both versions of a conflicted file are written in full between markers.
+//! The `diff3` style (`merge.conflictStyle=diff3`) also shows the merge base
+//! version, between `|||||||` and `=======`, to help resolve the conflict.
+
+/// Config key selecting the conflict marker layout
+pub const CONFLICT_STYLE_KEY: &str = "merge.conflictStyle";
+
+/// Layout of the conflict markers written to the workspace
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictStyle {
+    /// `<<<<<<<` ours `=======` theirs `>>>>>>>`
+    #[default]
+    Merge,
+    /// Like `Merge`, with the base version after `|||||||`
+    Diff3,
+}
+
+impl ConflictStyle {
+    pub fn try_parse(value: &str) -> Option<Self> {
+        match value {
+            "merge" => Some(ConflictStyle::Merge),
+            "diff3" => Some(ConflictStyle::Diff3),
+            _ => None,
+        }
+    }
+}
+
+/// One version of a conflicted file, with the label shown on its marker
+#[derive(Debug, Clone, Copy)]
+pub struct MergeSide<'a> {
+    pub label: &'a str,
+    pub content: &'a str,
+}
+
+/// Render the conflicted file for the given versions
+///
+/// Without a base (add/add conflicts) both styles use the two-way layout.
+pub fn merge_blobs(
+    base: Option<MergeSide>,
+    ours: MergeSide,
+    theirs: MergeSide,
+    style: ConflictStyle,
+) -> String {
+    let mut merged = format!("<<<<<<< {}\n{}", ours.label, ours.content);
+    if style == ConflictStyle::Diff3
+        && let Some(base) = base
+    {
+        merged.push_str(&format!("||||||| {}\n{}", base.label, base.content));
+    }
+    merged.push_str(&format!(
+        "=======\n{}>>>>>>> {}\n",
+        theirs.content, theirs.label
+    ));
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const BASE: MergeSide = MergeSide {
+        label: "1a2b3c4",
+        content: "shared\n",
+    };
+    const OURS: MergeSide = MergeSide {
+        label: "HEAD",
+        content: "ours change\n",
+    };
+    const THEIRS: MergeSide = MergeSide {
+        label: "feature",
+        content: "theirs change\n",
+    };
+
+    #[test]
+    fn test_merge_style_markers() {
+        assert_eq!(
+            merge_blobs(Some(BASE), OURS, THEIRS, ConflictStyle::Merge),
+            "<<<<<<< HEAD\nours change\n=======\ntheirs change\n>>>>>>> feature\n"
+        );
+    }
+
+    #[test]
+    fn test_diff3_style_markers_include_base() {
+        assert_eq!(
+            merge_blobs(Some(BASE), OURS, THEIRS, ConflictStyle::Diff3),
+            "<<<<<<< HEAD\nours change\n||||||| 1a2b3c4\nshared\n\
+            =======\ntheirs change\n>>>>>>> feature\n"
+        );
+    }
+
+    #[test]
+    fn test_diff3_style_without_base_is_two_way() {
+        assert_eq!(
+            merge_blobs(None, OURS, THEIRS, ConflictStyle::Diff3),
+            merge_blobs(None, OURS, THEIRS, ConflictStyle::Merge)
+        );
+    }
+
+    #[test]
+    fn test_parse_conflict_style() {
+        assert_eq!(
+            ConflictStyle::try_parse("diff3"),
+            Some(ConflictStyle::Diff3)
+        );
+        assert_eq!(
+            ConflictStyle::try_parse("merge"),
+            Some(ConflictStyle::Merge)
+        );
+        assert_eq!(ConflictStyle::try_parse("zdiff3"), None);
+    }
+}
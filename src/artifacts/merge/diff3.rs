@@ -0,0 +1,309 @@
+//! Line-based three-way merge
+//!
+//! Both versions are diffed against their common base. Runs of lines that
+//! neither side changed split the files into chunks; a chunk changed on one
+//! side only takes that side, while a chunk changed differently on both
+//! sides becomes a conflict, written with the markers of [`merge_blobs`].
+//!
+//! [`merge_blobs`]: crate::artifacts::merge::blob_merge::merge_blobs
+
+use crate::artifacts::diff::diff_algorithm::{DiffAlgorithm, Edit, MyersDiff};
+use crate::artifacts::merge::blob_merge::{ConflictStyle, MergeSide, merge_blobs};
+use std::collections::HashMap;
+
+/// Outcome of a three-way merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedContent {
+    /// The merged file, with conflict markers around unresolved chunks
+    pub content: String,
+    /// Whether every chunk merged without conflict
+    pub clean: bool,
+}
+
+/// A run of lines in the merged output
+enum Chunk<'a> {
+    Clean(&'a [&'a str]),
+    Conflict {
+        base: &'a [&'a str],
+        ours: &'a [&'a str],
+        theirs: &'a [&'a str],
+    },
+}
+
+/// Merge the changes of `ours` and `theirs` relative to `base`
+pub fn merge_lines(
+    base: MergeSide,
+    ours: MergeSide,
+    theirs: MergeSide,
+    style: ConflictStyle,
+) -> MergedContent {
+    let base_lines = base.content.split_inclusive('\n').collect::<Vec<_>>();
+    let our_lines = ours.content.split_inclusive('\n').collect::<Vec<_>>();
+    let their_lines = theirs.content.split_inclusive('\n').collect::<Vec<_>>();
+
+    let chunks = Diff3::new(&base_lines, &our_lines, &their_lines).chunks();
+
+    let mut content = String::new();
+    let mut clean = true;
+    for chunk in chunks {
+        match chunk {
+            Chunk::Clean(lines) => content.push_str(&lines.concat()),
+            Chunk::Conflict {
+                base: base_chunk,
+                ours: our_chunk,
+                theirs: their_chunk,
+            } => {
+                clean = false;
+                let (base_chunk, our_chunk, their_chunk) = (
+                    terminated(base_chunk),
+                    terminated(our_chunk),
+                    terminated(their_chunk),
+                );
+                content.push_str(&merge_blobs(
+                    Some(MergeSide {
+                        label: base.label,
+                        content: &base_chunk,
+                    }),
+                    MergeSide {
+                        label: ours.label,
+                        content: &our_chunk,
+                    },
+                    MergeSide {
+                        label: theirs.label,
+                        content: &their_chunk,
+                    },
+                    style,
+                ));
+            }
+        }
+    }
+
+    MergedContent { content, clean }
+}
+
+/// The lines of a conflicting chunk, ending with a newline so the next
+/// marker starts on its own line
+fn terminated(lines: &[&str]) -> String {
+    let mut text = lines.concat();
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Chunking state of a three-way merge
+///
+/// Line positions count the lines already consumed from each version, so
+/// the next unconsumed line of the base is `base[line_base]`.
+struct Diff3<'a> {
+    base: &'a [&'a str],
+    ours: &'a [&'a str],
+    theirs: &'a [&'a str],
+    /// Base line number (1-based) to the number of the same line in ours
+    match_ours: HashMap<usize, usize>,
+    /// Base line number (1-based) to the number of the same line in theirs
+    match_theirs: HashMap<usize, usize>,
+    line_base: usize,
+    line_ours: usize,
+    line_theirs: usize,
+}
+
+impl<'a> Diff3<'a> {
+    fn new(base: &'a [&'a str], ours: &'a [&'a str], theirs: &'a [&'a str]) -> Self {
+        Self {
+            base,
+            ours,
+            theirs,
+            match_ours: Self::match_set(base, ours),
+            match_theirs: Self::match_set(base, theirs),
+            line_base: 0,
+            line_ours: 0,
+            line_theirs: 0,
+        }
+    }
+
+    fn match_set(base: &[&str], other: &[&str]) -> HashMap<usize, usize> {
+        MyersDiff::new(base, other)
+            .diff()
+            .into_iter()
+            .filter_map(|edit| match edit {
+                Edit::Equal { line_a, line_b } => Some((line_a.number(), line_b.number())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn chunks(mut self) -> Vec<Chunk<'a>> {
+        let mut chunks = Vec::new();
+
+        loop {
+            match self.find_next_mismatch() {
+                // The next lines already differ: skip to where all three
+                // versions agree again
+                Some(1) => match self.find_next_match() {
+                    (base, Some(ours), Some(theirs)) => {
+                        chunks.push(self.emit_chunk(base, ours, theirs));
+                    }
+                    _ => {
+                        chunks.push(self.emit_final_chunk());
+                        return chunks;
+                    }
+                },
+                Some(offset) => chunks.push(self.emit_chunk(
+                    self.line_base + offset,
+                    self.line_ours + offset,
+                    self.line_theirs + offset,
+                )),
+                None => {
+                    chunks.push(self.emit_final_chunk());
+                    return chunks;
+                }
+            }
+        }
+    }
+
+    /// Offset of the first line, from the current positions, that is not
+    /// the same in all three versions
+    fn find_next_mismatch(&self) -> Option<usize> {
+        let mut offset = 1;
+        while self.in_bounds(offset)
+            && self.matches(&self.match_ours, self.line_ours, offset)
+            && self.matches(&self.match_theirs, self.line_theirs, offset)
+        {
+            offset += 1;
+        }
+
+        self.in_bounds(offset).then_some(offset)
+    }
+
+    fn in_bounds(&self, offset: usize) -> bool {
+        self.line_base + offset <= self.base.len()
+            || self.line_ours + offset <= self.ours.len()
+            || self.line_theirs + offset <= self.theirs.len()
+    }
+
+    fn matches(&self, matches: &HashMap<usize, usize>, line: usize, offset: usize) -> bool {
+        matches.get(&(self.line_base + offset)) == Some(&(line + offset))
+    }
+
+    /// Next base line number kept by both sides, with its numbers in each
+    fn find_next_match(&self) -> (usize, Option<usize>, Option<usize>) {
+        let mut base = self.line_base + 1;
+        while base <= self.base.len()
+            && !(self.match_ours.contains_key(&base) && self.match_theirs.contains_key(&base))
+        {
+            base += 1;
+        }
+
+        (
+            base,
+            self.match_ours.get(&base).copied(),
+            self.match_theirs.get(&base).copied(),
+        )
+    }
+
+    /// Chunk of the lines before the given line numbers
+    fn emit_chunk(&mut self, base: usize, ours: usize, theirs: usize) -> Chunk<'a> {
+        let chunk = Self::chunk(
+            &self.base[self.line_base..base - 1],
+            &self.ours[self.line_ours..ours - 1],
+            &self.theirs[self.line_theirs..theirs - 1],
+        );
+        self.line_base = base - 1;
+        self.line_ours = ours - 1;
+        self.line_theirs = theirs - 1;
+
+        chunk
+    }
+
+    fn emit_final_chunk(&self) -> Chunk<'a> {
+        Self::chunk(
+            &self.base[self.line_base..],
+            &self.ours[self.line_ours..],
+            &self.theirs[self.line_theirs..],
+        )
+    }
+
+    fn chunk(base: &'a [&'a str], ours: &'a [&'a str], theirs: &'a [&'a str]) -> Chunk<'a> {
+        if ours == base || ours == theirs {
+            Chunk::Clean(theirs)
+        } else if theirs == base {
+            Chunk::Clean(ours)
+        } else {
+            Chunk::Conflict { base, ours, theirs }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn merge(base: &str, ours: &str, theirs: &str) -> MergedContent {
+        merge_lines(
+            MergeSide {
+                label: "base",
+                content: base,
+            },
+            MergeSide {
+                label: "ours",
+                content: ours,
+            },
+            MergeSide {
+                label: "theirs",
+                content: theirs,
+            },
+            ConflictStyle::Merge,
+        )
+    }
+
+    #[test]
+    fn test_changes_to_separate_lines_merge_cleanly() {
+        let merged = merge(
+            "one\ntwo\nthree\nfour\nfive\n",
+            "ONE\ntwo\nthree\nfour\nfive\n",
+            "one\ntwo\nthree\nfour\nFIVE\n",
+        );
+
+        assert_eq!(
+            merged,
+            MergedContent {
+                content: "ONE\ntwo\nthree\nfour\nFIVE\n".to_string(),
+                clean: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_changes_to_the_same_line_conflict() {
+        let merged = merge("one\ntwo\nthree\n", "one\nTWO\nthree\n", "one\n2\nthree\n");
+
+        assert_eq!(
+            merged,
+            MergedContent {
+                content: "one\n<<<<<<< ours\nTWO\n=======\n2\n>>>>>>> theirs\nthree\n".to_string(),
+                clean: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_identical_changes_merge_cleanly() {
+        let merged = merge("one\n", "one\ntwo\n", "one\ntwo\n");
+
+        assert_eq!(merged.content, "one\ntwo\n");
+        assert!(merged.clean);
+    }
+
+    #[test]
+    fn test_conflict_without_trailing_newline_keeps_markers_on_their_own_lines() {
+        let merged = merge("one", "uno", "eins");
+
+        assert_eq!(
+            merged.content,
+            "<<<<<<< ours\nuno\n=======\neins\n>>>>>>> theirs\n"
+        );
+    }
+}
@@ -1,6 +1,5 @@
 use crate::areas::database::CommitCache;
 use crate::areas::repository::Repository;
-use crate::artifacts::branch::revision::Revision;
 use crate::artifacts::merge::bca_finder::BCAFinder;
 use crate::artifacts::objects::object_id::ObjectId;
 
@@ -52,9 +51,7 @@ impl<'r> MergeInputs<'r> {
     }
 
     fn resolve(repository: &'r Repository, ref_name: &'r str) -> anyhow::Result<ObjectId> {
-        Revision::try_parse(ref_name)?
-            .resolve(repository)?
-            .ok_or_else(|| anyhow::anyhow!("Failed to resolve reference: {}", ref_name))
+        repository.resolve_revision(ref_name)
     }
 
     fn find_best_common_ancestor<'m>(
@@ -1,3 +1,5 @@
 pub mod bca_finder;
+pub mod blob_merge;
+pub mod diff3;
 pub mod inputs;
 pub mod resolution;
@@ -1,14 +1,21 @@
 use crate::areas::index::Index;
 use crate::areas::repository::Repository;
 use crate::artifacts::checkout::migration::Migration;
+use crate::artifacts::database::database_entry::DatabaseEntry;
 use crate::artifacts::diff::tree_diff::{ChangeSet, TreeChangeType, TreeDiff};
 use crate::artifacts::index::entry_mode::EntryMode;
 use crate::artifacts::index::index_entry::{IndexEntry, MergeStage};
 use crate::artifacts::log::path_filter::PathFilter;
+use crate::artifacts::merge::blob_merge::{
+    CONFLICT_STYLE_KEY, ConflictStyle, MergeSide, merge_blobs,
+};
+use crate::artifacts::merge::diff3::{MergedContent, merge_lines};
 use crate::artifacts::merge::inputs::MergeInputs;
+use crate::artifacts::objects::blob::Blob;
+use crate::artifacts::objects::object::Object;
 use crate::artifacts::objects::object_id::ObjectId;
 use std::collections::{BTreeMap, BTreeSet};
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 pub struct MergeResolution<'r> {
     repository: &'r Repository,
@@ -223,7 +230,8 @@ impl<'r> MergeResolution<'r> {
     /// | Modified(A)             | Modified(A)              | **Clean** — both converged to the same blob               | No-op; both sides already agree                                            |
     /// | Deleted                 | Deleted                  | **Clean** — both deleted                                  | Migration applies the delete; index entry removed                          |
     /// | Added(A)                | Added(B)                 | **Conflict** `Content` — different blobs, no common base  | Conflict markers written to workspace; stages 2 (ours) + 3 (theirs)       |
-    /// | Modified(A)             | Modified(B)              | **Conflict** `Content` — diverged from a shared base      | Conflict markers written to workspace; stages 1 (base) + 2 + 3            |
+    /// | Modified(A)             | Modified(B), other lines | **Clean** — line merge of both sides against the base     | Merged blob stored; Migration writes it; index promoted to stage 0         |
+    /// | Modified(A)             | Modified(B), same lines  | **Conflict** `Content` — diverged from a shared base      | Markers around conflicting hunks; stages 1 (base) + 2 + 3                 |
     /// | Modified/Added          | Deleted                  | **Conflict** `ModifyDelete` — we kept it, they removed it | Our version left in workspace as-is; stages 1 (base) + 2 (ours)           |
     /// | Deleted                 | Modified/Added           | **Conflict** `DeleteModify` — we removed it, they kept it | Their blob written to workspace; stages 1 (base) + 3 (theirs)             |
     /// | Added/Modified(A)       | Modified/Added(B)        | **Conflict** `Content` — remaining cross-combinations     | Conflict markers written to workspace; stages 1 (base, if any) + 2 + 3    |
@@ -315,10 +323,50 @@ impl<'r> MergeResolution<'r> {
                     MergeTriviality::Conflict
                 }
 
-                // CONFLICT: Both modified to different content
-                // Or remaining cross-combinations (e.g. Added vs Modified)
-                (SideChange::Modified(l_oid, l_mode), SideChange::Modified(r_oid, _))
-                | (
+                // Both modified to different content → merge their lines;
+                // CONFLICT only if both sides changed the same lines
+                (SideChange::Modified(l_oid, l_mode), SideChange::Modified(r_oid, _)) => {
+                    let base_oid = base_oid();
+                    let merged = match &base_oid {
+                        Some(base_oid) => Some(self.merge_blob_lines(
+                            base_oid,
+                            l_oid,
+                            r_oid,
+                            right_name,
+                            ConflictStyle::default(),
+                        )?),
+                        None => None,
+                    };
+
+                    match merged {
+                        Some(merged) if merged.clean => {
+                            let merged_oid = self.store_blob(merged.content)?;
+                            clean_diff.insert(
+                                path.clone(),
+                                TreeChangeType::Modified {
+                                    old: DatabaseEntry::new(l_oid.clone(), *l_mode),
+                                    new: DatabaseEntry::new(merged_oid, *l_mode),
+                                },
+                            );
+                            MergeTriviality::Trivial
+                        }
+                        _ => {
+                            conflicts.push(Conflict {
+                                path: path.clone(),
+                                base_oid,
+                                ours_oid: Some(l_oid.clone()),
+                                theirs_oid: Some(r_oid.clone()),
+                                mode: *l_mode,
+                                kind: ConflictKind::Content,
+                                rename: None,
+                            });
+                            MergeTriviality::Conflict
+                        }
+                    }
+                }
+
+                // CONFLICT: remaining cross-combinations (e.g. Added vs Modified)
+                (
                     SideChange::Added(l_oid, l_mode) | SideChange::Modified(l_oid, l_mode),
                     SideChange::Modified(r_oid, _) | SideChange::Added(r_oid, _),
                 ) => {
@@ -584,12 +632,12 @@ impl<'r> MergeResolution<'r> {
         conflicts: &[Conflict],
         right_name: &str,
     ) -> anyhow::Result<()> {
+        let style = conflict_style(self.repository)?;
+
         for conflict in conflicts {
             match conflict.kind {
                 ConflictKind::Content => {
-                    let ours = self.load_blob_content(conflict.ours_oid.as_ref().unwrap())?;
-                    let theirs = self.load_blob_content(conflict.theirs_oid.as_ref().unwrap())?;
-                    self.write_conflict_markers(&conflict.path, &ours, &theirs, right_name)?;
+                    self.write_conflict_markers(conflict, right_name, style)?;
                 }
                 ConflictKind::DeleteModify => {
                     let content = self.load_blob_content(conflict.theirs_oid.as_ref().unwrap())?;
@@ -605,23 +653,83 @@ impl<'r> MergeResolution<'r> {
         Ok(())
     }
 
+    /// Write a conflicted file: with a common base only the conflicting hunks
+    /// get markers, otherwise the whole file does
     fn write_conflict_markers(
         &self,
-        path: &Path,
-        ours_content: &str,
-        theirs_content: &str,
+        conflict: &Conflict,
         right_name: &str,
+        style: ConflictStyle,
     ) -> anyhow::Result<()> {
-        let content = format!(
-            "<<<<<<< HEAD\n{}=======\n{}>>>>>>> {}\n",
-            ours_content, theirs_content, right_name
-        );
+        let ours_oid = conflict.ours_oid.as_ref().unwrap();
+        let theirs_oid = conflict.theirs_oid.as_ref().unwrap();
+
+        let content = match &conflict.base_oid {
+            Some(base_oid) => {
+                self.merge_blob_lines(base_oid, ours_oid, theirs_oid, right_name, style)?
+                    .content
+            }
+            None => {
+                let ours = self.load_blob_content(ours_oid)?;
+                let theirs = self.load_blob_content(theirs_oid)?;
+                merge_blobs(
+                    None,
+                    MergeSide {
+                        label: "HEAD",
+                        content: &ours,
+                    },
+                    MergeSide {
+                        label: right_name,
+                        content: &theirs,
+                    },
+                    style,
+                )
+            }
+        };
         self.repository
             .workspace()
-            .write_file(path, content.as_bytes())?;
+            .write_file(&conflict.path, content.as_bytes())?;
         Ok(())
     }
 
+    /// Three-way merge of the lines of two blobs diverged from `base_oid`
+    fn merge_blob_lines(
+        &self,
+        base_oid: &ObjectId,
+        ours_oid: &ObjectId,
+        theirs_oid: &ObjectId,
+        right_name: &str,
+        style: ConflictStyle,
+    ) -> anyhow::Result<MergedContent> {
+        let base = self.load_blob_content(base_oid)?;
+        let ours = self.load_blob_content(ours_oid)?;
+        let theirs = self.load_blob_content(theirs_oid)?;
+
+        Ok(merge_lines(
+            MergeSide {
+                label: &base_oid.to_short_oid(),
+                content: &base,
+            },
+            MergeSide {
+                label: "HEAD",
+                content: &ours,
+            },
+            MergeSide {
+                label: right_name,
+                content: &theirs,
+            },
+            style,
+        ))
+    }
+
+    fn store_blob(&self, content: String) -> anyhow::Result<ObjectId> {
+        let blob = Blob::new(content, Default::default());
+        let oid = blob.object_id()?;
+        self.repository.database().store(blob)?;
+
+        Ok(oid)
+    }
+
     fn load_blob_content(&self, oid: &ObjectId) -> anyhow::Result<String> {
         self.repository
             .database()
@@ -630,3 +738,12 @@ impl<'r> MergeResolution<'r> {
             .ok_or_else(|| anyhow::anyhow!("Expected blob at {}", oid))
     }
 }
+
+/// Marker layout from `merge.conflictStyle` (default `merge`)
+pub fn conflict_style(repository: &Repository) -> anyhow::Result<ConflictStyle> {
+    match repository.config().get(CONFLICT_STYLE_KEY)? {
+        None => Ok(ConflictStyle::default()),
+        Some(value) => ConflictStyle::try_parse(&value)
+            .ok_or_else(|| anyhow::anyhow!("unknown {}: {}", CONFLICT_STYLE_KEY, value)),
+    }
+}
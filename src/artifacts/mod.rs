@@ -2,17 +2,20 @@
 //!
 //! This module contains the core Git types and algorithms:
 //!
+//! - `archive`: Tar and zip writers for exporting trees
 //! - `branch`: Branch names and revision parsing
 //! - `checkout`: Checkout operations and conflict detection
-//! - `core`: Shared utilities (pager wrapper, etc.)
+//! - `core`: Shared utilities (pager wrapper, ignore rules, etc.)
 //! - `database`: Database entry types
 //! - `diff`: Tree diffing algorithms (Myers' diff)
 //! - `index`: Index/staging area data structures
 //! - `log`: Commit history traversal and filtering
 //! - `objects`: Git object types (blob, tree, commit)
+//! - `pack`: Packfile parsing and delta resolution
 //! - `status`: Working tree status inspection
 //! - `merge`: Merge algorithms and conflict resolution
 
+pub mod archive;
 pub mod branch;
 pub mod checkout;
 pub mod core;
@@ -22,4 +25,5 @@ pub mod index;
 pub mod log;
 pub mod merge;
 pub mod objects;
+pub mod pack;
 pub mod status;
@@ -74,4 +74,8 @@ impl Object for Blob {
     fn display(&self) -> String {
         self.content.to_string()
     }
+
+    fn size(&self) -> anyhow::Result<usize> {
+        Ok(self.content.len())
+    }
 }
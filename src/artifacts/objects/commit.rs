@@ -16,15 +16,21 @@
 //! parent <parent-sha>
 //! author <name> <email> <timestamp> <timezone>
 //! committer <name> <email> <timestamp> <timezone>
+//! gpgsig -----BEGIN PGP SIGNATURE-----
+//!  <signature line>
+//!  -----END PGP SIGNATURE-----
 //!
 //! <commit message>
 //! ```
+//!
+//! Headers after the committer line, such as `gpgsig`, `encoding` or
+//! `mergetag`, are kept as they are but not interpreted; multi-line values
+//! continue on lines starting with a space. Signatures are never verified.
 
 use crate::artifacts::objects::object::Unpackable;
 use crate::artifacts::objects::object::{Object, Packable};
 use crate::artifacts::objects::object_id::ObjectId;
 use crate::artifacts::objects::object_type::ObjectType;
-use anyhow::Context;
 use bytes::Bytes;
 use std::io::{BufRead, Write};
 
@@ -44,6 +50,39 @@ pub enum AuthorParseError {
     EnvVarMissing(String),
 }
 
+/// Structural problems found while parsing a commit object
+#[derive(Debug, thiserror::Error)]
+pub enum CommitError {
+    #[error("invalid commit object: missing tree line")]
+    MissingTree,
+    #[error("invalid commit object: malformed tree line '{0}'")]
+    InvalidTree(String),
+    #[error("invalid commit object: malformed parent line '{0}'")]
+    InvalidParent(String),
+    #[error("invalid commit object: missing author line")]
+    MissingAuthor,
+    #[error("invalid commit object: malformed author line: {0}")]
+    InvalidAuthor(AuthorParseError),
+    #[error("invalid commit object: missing committer line")]
+    MissingCommitter,
+    #[error("invalid commit object: malformed committer line: {0}")]
+    InvalidCommitter(AuthorParseError),
+    #[error("invalid commit object: content is not valid UTF-8")]
+    InvalidEncoding,
+    #[error("commit {commit} points to {object_type} {oid} as its tree")]
+    TreeNotATree {
+        commit: String,
+        oid: String,
+        object_type: ObjectType,
+    },
+    #[error("commit {commit} has {object_type} {oid} as a parent")]
+    ParentNotACommit {
+        commit: String,
+        oid: String,
+        object_type: ObjectType,
+    },
+}
+
 /// Author or committer information
 ///
 /// Contains name, email, and timestamp with timezone information.
@@ -88,6 +127,16 @@ impl Author {
         }
     }
 
+    /// Get the name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the email address
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
     /// Format author name and email for display
     ///
     /// # Returns
@@ -179,13 +228,7 @@ impl TryFrom<&str> for Author {
         let name = name_email_part[..email_start].trim().to_string();
         let email = name_email_part[email_start + 1..email_end].to_string();
 
-        let datetime = chrono::DateTime::from_timestamp(timestamp, 0)
-            .ok_or(AuthorParseError::InvalidTimestamp)?;
-        let datetime = chrono::DateTime::parse_from_str(
-            &format!("{} {}", datetime.format("%Y-%m-%d %H:%M:%S"), timezone),
-            "%Y-%m-%d %H:%M:%S %z",
-        )
-        .map_err(|_| AuthorParseError::InvalidTimezone(timezone.to_string()))?;
+        let datetime = author_timestamp(timestamp, timezone)?;
 
         Ok(Author {
             name,
@@ -195,6 +238,26 @@ impl TryFrom<&str> for Author {
     }
 }
 
+/// The moment `timestamp` (seconds since the epoch) shown in `timezone`
+///
+/// The timezone only tells how the moment was displayed to the author: it
+/// never shifts the moment itself.
+fn author_timestamp(
+    timestamp: i64,
+    timezone: &str,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, AuthorParseError> {
+    let offset = *chrono::DateTime::parse_from_str(
+        &format!("1970-01-01 00:00:00 {}", timezone),
+        "%Y-%m-%d %H:%M:%S %z",
+    )
+    .map_err(|_| AuthorParseError::InvalidTimezone(timezone.to_string()))?
+    .offset();
+
+    Ok(chrono::DateTime::from_timestamp(timestamp, 0)
+        .ok_or(AuthorParseError::InvalidTimestamp)?
+        .with_timezone(&offset))
+}
+
 /// Slim representation of a commit
 ///
 /// Contains only essential information for lightweight operations like merge base finding.
@@ -242,6 +305,8 @@ pub struct Commit {
     author: Author,
     /// Committer who recorded the commit
     committer: Author,
+    /// Headers after the committer line, in order, e.g. `gpgsig`
+    extra_headers: Vec<(String, String)>,
     /// Commit message
     message: String,
 }
@@ -266,10 +331,17 @@ impl Commit {
             tree_oid,
             author: author.clone(),
             committer: author,
+            extra_headers: Vec::new(),
             message,
         }
     }
 
+    /// Record a committer other than the author (e.g. when applying a patch)
+    pub fn with_committer(mut self, committer: Author) -> Self {
+        self.committer = committer;
+        self
+    }
+
     /// Get the first line of the commit message
     ///
     /// Useful for short-form display (e.g., `git log --oneline`)
@@ -303,6 +375,16 @@ impl Commit {
         &self.author
     }
 
+    pub fn committer(&self) -> &Author {
+        &self.committer
+    }
+
+    /// Headers after the committer line as (name, value) pairs, with
+    /// multi-line values joined by newlines
+    pub fn extra_headers(&self) -> &[(String, String)] {
+        &self.extra_headers
+    }
+
     pub fn timestamp(&self) -> chrono::DateTime<chrono::FixedOffset> {
         self.author.timestamp()
     }
@@ -310,18 +392,7 @@ impl Commit {
 
 impl Packable for Commit {
     fn serialize(&self) -> anyhow::Result<Bytes> {
-        let mut object_content = vec![];
-
-        object_content.push(format!("tree {}", self.tree_oid.as_ref()));
-        for parent in &self.parents {
-            object_content.push(format!("parent {}", parent.as_ref()));
-        }
-        object_content.push(format!("author {}", self.author.display()));
-        object_content.push(format!("committer {}", self.committer.display()));
-        object_content.push(String::new());
-        object_content.push(self.message.to_string());
-
-        let object_content = object_content.join("\n");
+        let object_content = self.display();
 
         let mut content_bytes = Vec::new();
         content_bytes.write_all(object_content.as_bytes())?;
@@ -335,60 +406,76 @@ impl Packable for Commit {
     }
 }
 
-impl Unpackable for Commit {
-    fn deserialize(reader: impl BufRead) -> anyhow::Result<Self> {
-        let content = reader
-            .bytes()
-            .collect::<Result<Vec<u8>, std::io::Error>>()?;
-
-        let content = String::from_utf8(content)?;
+impl Commit {
+    /// Parse the body of a commit object (everything after the header)
+    ///
+    /// Every header line is checked: the tree and parent lines must hold
+    /// well-formed object IDs and both the author and committer lines must
+    /// carry a parseable timestamp and timezone. Headers between the
+    /// committer and the message (e.g. `gpgsig`, `encoding`) are kept byte
+    /// for byte.
+    pub fn parse(content: &str) -> Result<Self, CommitError> {
         let mut lines = content.lines();
 
         let tree_line = lines
             .next()
-            .context("Invalid commit object: missing tree line")?;
-        let tree_oid = tree_line
-            .strip_prefix("tree ")
-            .context("Invalid commit object: invalid tree line")?
-            .to_string();
-        let tree_oid = ObjectId::try_parse(tree_oid)?;
+            .filter(|line| line.starts_with("tree "))
+            .ok_or(CommitError::MissingTree)?;
+        let tree_oid = Self::parse_oid_line(tree_line, "tree ")
+            .ok_or_else(|| CommitError::InvalidTree(tree_line.to_string()))?;
 
         // Parse all parent lines (there can be 0, 1, or multiple parents)
         let mut parents = Vec::new();
-        let mut next_line = lines
-            .next()
-            .context("Invalid commit object: missing author line")?;
+        let mut next_line = lines.next().ok_or(CommitError::MissingAuthor)?;
 
         while next_line.starts_with("parent ") {
-            let parent_oid = next_line
-                .strip_prefix("parent ")
-                .context("Invalid commit object: invalid parent line")?;
-            parents.push(ObjectId::try_parse(parent_oid.to_string())?);
-
-            next_line = lines
-                .next()
-                .context("Invalid commit object: missing author line")?;
+            let parent_oid = Self::parse_oid_line(next_line, "parent ")
+                .ok_or_else(|| CommitError::InvalidParent(next_line.to_string()))?;
+            parents.push(parent_oid);
+
+            next_line = lines.next().ok_or(CommitError::MissingAuthor)?;
         }
 
         // At this point, next_line should be the author line
         let author = next_line
             .strip_prefix("author ")
-            .context("Invalid commit object: invalid author line")?;
-        let author = Author::try_from(author)?;
+            .ok_or(CommitError::MissingAuthor)?;
+        let author = Author::try_from(author).map_err(CommitError::InvalidAuthor)?;
 
-        let committer_line = lines
+        let committer = lines
             .next()
-            .context("Invalid commit object: missing committer line")?;
-        let committer = committer_line
-            .strip_prefix("committer ")
-            .context("Invalid commit object: invalid committer line")?;
-        let _committer = Author::try_from(committer)?;
+            .and_then(|line| line.strip_prefix("committer "))
+            .ok_or(CommitError::MissingCommitter)?;
+        let committer = Author::try_from(committer).map_err(CommitError::InvalidCommitter)?;
 
-        // skip the empty line
-        lines.next();
+        // extra headers run up to (and including) the empty line
+        let extra_headers = parse_extra_headers(lines.by_ref().take_while(|line| !line.is_empty()));
 
         let message = lines.collect::<Vec<&str>>().join("\n");
-        Ok(Self::new(parents, tree_oid, author, message))
+        Ok(Commit {
+            parents,
+            tree_oid,
+            author,
+            committer,
+            extra_headers,
+            message,
+        })
+    }
+
+    fn parse_oid_line(line: &str, prefix: &str) -> Option<ObjectId> {
+        let oid = line.strip_prefix(prefix)?;
+        ObjectId::try_parse(oid.to_string()).ok()
+    }
+}
+
+impl Unpackable for Commit {
+    fn deserialize(reader: impl BufRead) -> anyhow::Result<Self> {
+        let content = reader
+            .bytes()
+            .collect::<Result<Vec<u8>, std::io::Error>>()?;
+        let content = String::from_utf8(content).map_err(|_| CommitError::InvalidEncoding)?;
+
+        Ok(Self::parse(&content)?)
     }
 }
 
@@ -406,9 +493,224 @@ impl Object for Commit {
         }
         lines.push(format!("author {}", self.author.display()));
         lines.push(format!("committer {}", self.committer.display()));
+        for (name, value) in &self.extra_headers {
+            lines.push(format_extra_header(name, value));
+        }
         lines.push(String::new());
         lines.push(self.message.to_string());
 
         lines.join("\n")
     }
 }
+
+/// Collect `name value` header lines, appending continuation lines (which
+/// start with a space) to the value of the header before them
+pub(crate) fn parse_extra_headers<'a>(
+    lines: impl Iterator<Item = &'a str>,
+) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+
+    for line in lines {
+        match (line.strip_prefix(' '), headers.last_mut()) {
+            (Some(continuation), Some((_, value))) => {
+                value.push('\n');
+                value.push_str(continuation);
+            }
+            _ => {
+                let (name, value) = line.split_once(' ').unwrap_or((line, ""));
+                headers.push((name.to_string(), value.to_string()));
+            }
+        }
+    }
+
+    headers
+}
+
+/// Header line(s) of `name`, continuing each line of a multi-line value on
+/// a line of its own starting with a space
+pub(crate) fn format_extra_header(name: &str, value: &str) -> String {
+    format!("{} {}", name, value.replace('\n', "\n "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TREE_OID: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+    const PARENT_OID: &str = "8e5b1b6a4bb1d6e2c9a1e0b3f6f4a2b5c7d9e1f3";
+
+    #[test]
+    fn test_author_timestamp_keeps_the_moment_in_any_timezone() {
+        for (timezone, shown) in [
+            ("+0000", "2023-11-14T22:13:20+00:00"),
+            ("+0200", "2023-11-15T00:13:20+02:00"),
+            ("-0530", "2023-11-14T16:43:20-05:30"),
+        ] {
+            let line = format!("A <a@example.com> 1700000000 {}", timezone);
+            let author = Author::try_from(line.as_str()).unwrap();
+
+            assert_eq!(author.timestamp().timestamp(), 1700000000);
+            assert_eq!(author.timestamp().to_rfc3339(), shown);
+            assert_eq!(author.display(), line);
+        }
+        assert!(matches!(
+            author_timestamp(0, "EST"),
+            Err(AuthorParseError::InvalidTimezone(_))
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_valid_commit() {
+        let content = format!(
+            "tree {TREE_OID}\nparent {PARENT_OID}\n\
+            author A. U. Thor <author@example.com> 1700000000 +0200\n\
+            committer C. O. Mitter <committer@example.com> 1700000100 -0500\n\
+            \n\
+            Add feature\n\nWith a body"
+        );
+
+        let commit = Commit::deserialize(std::io::Cursor::new(content)).unwrap();
+
+        assert_eq!(commit.tree_oid().as_ref(), TREE_OID);
+        assert_eq!(commit.parents().len(), 1);
+        assert_eq!(commit.parents()[0].as_ref(), PARENT_OID);
+        assert_eq!(
+            commit.author().display(),
+            "A. U. Thor <author@example.com> 1700000000 +0200"
+        );
+        assert_eq!(
+            commit.committer.display(),
+            "C. O. Mitter <committer@example.com> 1700000100 -0500"
+        );
+        assert_eq!(commit.message(), "Add feature\n\nWith a body");
+    }
+
+    #[test]
+    fn test_parse_commit_without_tree_line_fails() {
+        let content = format!(
+            "parent {PARENT_OID}\n\
+            author A. U. Thor <author@example.com> 1700000000 +0200\n\
+            committer A. U. Thor <author@example.com> 1700000000 +0200\n\
+            \n\
+            No tree"
+        );
+
+        let result = Commit::parse(&content);
+
+        assert!(matches!(result, Err(CommitError::MissingTree)));
+    }
+
+    #[test]
+    fn test_parse_commit_with_malformed_tree_line_fails() {
+        let content = "tree not-an-oid\n\
+            author A. U. Thor <author@example.com> 1700000000 +0200\n\
+            committer A. U. Thor <author@example.com> 1700000000 +0200\n\
+            \n\
+            Bad tree";
+
+        let result = Commit::parse(content);
+
+        assert!(matches!(result, Err(CommitError::InvalidTree(_))));
+    }
+
+    #[test]
+    fn test_parse_commit_with_malformed_parent_line_fails() {
+        let content = format!(
+            "tree {TREE_OID}\nparent abc\n\
+            author A. U. Thor <author@example.com> 1700000000 +0200\n\
+            committer A. U. Thor <author@example.com> 1700000000 +0200\n\
+            \n\
+            Bad parent"
+        );
+
+        let result = Commit::parse(&content);
+
+        assert!(matches!(result, Err(CommitError::InvalidParent(_))));
+    }
+
+    #[test]
+    fn test_parse_commit_with_unparseable_date_fails() {
+        let content = format!(
+            "tree {TREE_OID}\n\
+            author A. U. Thor <author@example.com> yesterday +0200\n\
+            committer A. U. Thor <author@example.com> 1700000000 +0200\n\
+            \n\
+            Bad date"
+        );
+
+        let result = Commit::parse(&content);
+
+        assert!(matches!(
+            result,
+            Err(CommitError::InvalidAuthor(
+                AuthorParseError::InvalidTimestamp
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_parse_commit_with_unparseable_committer_timezone_fails() {
+        let content = format!(
+            "tree {TREE_OID}\n\
+            author A. U. Thor <author@example.com> 1700000000 +0200\n\
+            committer A. U. Thor <author@example.com> 1700000000 CEST\n\
+            \n\
+            Bad timezone"
+        );
+
+        let result = Commit::parse(&content);
+
+        assert!(matches!(
+            result,
+            Err(CommitError::InvalidCommitter(
+                AuthorParseError::InvalidTimezone(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_parse_commit_skips_extra_headers() {
+        let content = format!(
+            "tree {TREE_OID}\n\
+            author A. U. Thor <author@example.com> 1700000000 +0200\n\
+            committer A. U. Thor <author@example.com> 1700000000 +0200\n\
+            encoding ISO-8859-1\n\
+            \n\
+            Extra headers"
+        );
+
+        let commit = Commit::parse(&content).unwrap();
+
+        assert_eq!(commit.message(), "Extra headers");
+    }
+
+    #[test]
+    fn test_signed_commit_round_trips_byte_for_byte() {
+        let content = format!(
+            "tree {TREE_OID}\nparent {PARENT_OID}\n\
+            author A. U. Thor <author@example.com> 1700000000 +0200\n\
+            committer A. U. Thor <author@example.com> 1700000000 +0200\n\
+            gpgsig -----BEGIN PGP SIGNATURE-----\n \n \
+            iQEzBAABCAAdFiEEexample0signature0lines0only0AAoJEFake\n \
+            =AbCd\n \
+            -----END PGP SIGNATURE-----\n\
+            \n\
+            Signed commit"
+        );
+
+        let commit = Commit::parse(&content).unwrap();
+        let serialized = commit.serialize().unwrap();
+
+        assert_eq!(
+            commit.extra_headers()[0].1,
+            "-----BEGIN PGP SIGNATURE-----\n\n\
+            iQEzBAABCAAdFiEEexample0signature0lines0only0AAoJEFake\n=AbCd\n\
+            -----END PGP SIGNATURE-----"
+        );
+        assert_eq!(commit.message(), "Signed commit");
+        assert_eq!(
+            serialized.as_ref(),
+            format!("commit {}\0{}", content.len(), content).as_bytes()
+        );
+    }
+}
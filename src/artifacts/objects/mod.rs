@@ -15,7 +15,20 @@ pub mod commit;
 pub mod object;
 pub mod object_id;
 pub mod object_type;
+pub mod tag;
 pub mod tree;
 
 /// Length of a SHA-1 hash in hexadecimal format
 pub const OBJECT_ID_LENGTH: usize = 40;
+
+/// Default number of characters in an abbreviated object ID
+pub const SHORT_OID_LENGTH: usize = 7;
+
+/// Fewest characters an abbreviated object ID may have
+pub const MIN_ABBREV_LENGTH: usize = 4;
+
+/// Object ID of the tree with no entries (`tree 0\0`)
+///
+/// Git treats this tree as always present, even when it was never written
+/// to the object database.
+pub const EMPTY_TREE_OID: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
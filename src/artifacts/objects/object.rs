@@ -17,6 +17,7 @@ use crate::artifacts::objects::blob::Blob;
 use crate::artifacts::objects::commit::{AuthorParseError, Commit};
 use crate::artifacts::objects::object_id::{ObjectId, ObjectIdError};
 use crate::artifacts::objects::object_type::{ObjectType, ObjectTypeError};
+use crate::artifacts::objects::tag::Tag;
 use crate::artifacts::objects::tree::Tree;
 use anyhow::Result;
 use bytes::Bytes;
@@ -59,7 +60,7 @@ pub trait Unpackable {
 
 /// Core Git object trait
 ///
-/// Implemented by all Git object types (Blob, Tree, Commit).
+/// Implemented by all Git object types (Blob, Tree, Commit, Tag).
 /// Provides common operations like ID computation and display.
 pub trait Object: Packable {
     /// Get the object's type
@@ -81,6 +82,17 @@ pub trait Object: Packable {
         Ok(ObjectId::try_parse(format!("{oid:x}"))?)
     }
 
+    /// Size of the object content in bytes (excluding the header)
+    fn size(&self) -> Result<usize> {
+        let content = self.serialize()?;
+        let header_len = content
+            .iter()
+            .position(|&byte| byte == b'\0')
+            .map_or(0, |nul| nul + 1);
+
+        Ok(content.len() - header_len)
+    }
+
     /// Get the file system path where this object would be stored
     fn object_path(&self) -> Result<PathBuf> {
         Ok(self.object_id()?.to_path())
@@ -95,4 +107,5 @@ pub enum ObjectBox<'o> {
     Blob(Box<Blob>),
     Tree(Box<Tree<'o>>),
     Commit(Box<Commit>),
+    Tag(Box<Tag>),
 }
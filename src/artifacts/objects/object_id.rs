@@ -12,7 +12,7 @@
 //!
 //! Objects are stored in `.git/objects/<first-2-chars>/<remaining-38-chars>`
 
-use crate::artifacts::objects::OBJECT_ID_LENGTH;
+use crate::artifacts::objects::{EMPTY_TREE_OID, OBJECT_ID_LENGTH, SHORT_OID_LENGTH};
 use std::io;
 use std::path::PathBuf;
 
@@ -56,6 +56,16 @@ impl ObjectId {
         Ok(Self(id.to_string()))
     }
 
+    /// The well-known object ID of the empty tree
+    pub fn empty_tree() -> Self {
+        Self(EMPTY_TREE_OID.to_string())
+    }
+
+    /// Check whether this is the object ID of the empty tree
+    pub fn is_empty_tree(&self) -> bool {
+        self.0 == EMPTY_TREE_OID
+    }
+
     /// Write the object ID in binary format (20 bytes)
     ///
     /// Converts the 40-char hex string to 20 bytes and writes to the given writer.
@@ -113,7 +123,7 @@ impl ObjectId {
     ///
     /// First 7 characters of the hash (standard Git abbreviation)
     pub fn to_short_oid(&self) -> String {
-        self.0.split_at(7).0.to_string()
+        self.0.split_at(SHORT_OID_LENGTH).0.to_string()
     }
 }
 
@@ -8,6 +8,10 @@ pub enum ObjectTypeError {
     Io(#[from] std::io::Error),
     #[error("invalid UTF-8 in object type header")]
     InvalidUtf8(#[from] std::string::FromUtf8Error),
+    #[error("invalid object size in header: {0}")]
+    InvalidSize(String),
+    #[error("object header is truncated")]
+    TruncatedHeader,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -15,6 +19,7 @@ pub enum ObjectType {
     Blob,
     Tree,
     Commit,
+    Tag,
 }
 
 impl ObjectType {
@@ -23,22 +28,49 @@ impl ObjectType {
             ObjectType::Blob => "blob",
             ObjectType::Tree => "tree",
             ObjectType::Commit => "commit",
+            ObjectType::Tag => "tag",
         }
     }
 
     pub fn parse_object_type(
         data_reader: &mut impl BufRead,
     ) -> Result<ObjectType, ObjectTypeError> {
+        let (object_type, _) = Self::parse_header(data_reader)?;
+
+        Ok(object_type)
+    }
+
+    /// Read a `<type> <size>\0` object header, leaving the reader at the content
+    pub fn parse_header(
+        data_reader: &mut impl BufRead,
+    ) -> Result<(ObjectType, usize), ObjectTypeError> {
+        let (object_type, size) = Self::parse_raw_header(data_reader)?;
+
+        Ok((ObjectType::try_from(object_type.as_str())?, size))
+    }
+
+    /// Read an object header without requiring the type to be a known one
+    pub fn parse_raw_header(
+        data_reader: &mut impl BufRead,
+    ) -> Result<(String, usize), ObjectTypeError> {
         let mut object_type = Vec::new();
         data_reader.read_until(b' ', &mut object_type)?;
-
+        if object_type.pop() != Some(b' ') {
+            return Err(ObjectTypeError::TruncatedHeader);
+        }
         let object_type = String::from_utf8(object_type)?;
-        let object_type = object_type.trim();
 
         let mut size = Vec::new();
         data_reader.read_until(b'\0', &mut size)?;
+        if size.pop() != Some(b'\0') {
+            return Err(ObjectTypeError::TruncatedHeader);
+        }
+        let size = String::from_utf8(size)?;
+        let size = size
+            .parse::<usize>()
+            .map_err(|_| ObjectTypeError::InvalidSize(size.to_string()))?;
 
-        ObjectType::try_from(object_type)
+        Ok((object_type, size))
     }
 }
 
@@ -50,6 +82,7 @@ impl TryFrom<&str> for ObjectType {
             "blob" => Ok(ObjectType::Blob),
             "tree" => Ok(ObjectType::Tree),
             "commit" => Ok(ObjectType::Commit),
+            "tag" => Ok(ObjectType::Tag),
             _ => Err(ObjectTypeError::Invalid(value.to_string())),
         }
     }
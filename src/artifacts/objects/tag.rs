@@ -0,0 +1,259 @@
+//! Git tag object
+//!
+//! Annotated tags are objects of their own: they name another object
+//! (usually a commit) and record who created the tag, when, and why.
+//! Lightweight tags are plain refs and have no object.
+//!
+//! ## Format
+//!
+//! On disk:
+//! ```text
+//! tag <size>\0
+//! object <target-sha>
+//! type <target-type>
+//! tag <name>
+//! tagger <name> <email> <timestamp> <timezone>
+//!
+//! <tag message>
+//! ```
+//!
+//! Signed tags carry their signature at the end of the message. Headers
+//! after the tagger line are kept as they are, like those of commits.
+
+use crate::artifacts::objects::commit::{
+    Author, AuthorParseError, format_extra_header, parse_extra_headers,
+};
+use crate::artifacts::objects::object::{Object, Packable, Unpackable};
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::objects::object_type::ObjectType;
+use bytes::Bytes;
+use std::io::{BufRead, Write};
+
+/// Structural problems found while parsing a tag object
+#[derive(Debug, thiserror::Error)]
+pub enum TagError {
+    #[error("invalid tag object: missing {0} line")]
+    MissingHeader(&'static str),
+    #[error("invalid tag object: malformed object line '{0}'")]
+    InvalidObject(String),
+    #[error("invalid tag object: unknown target type '{0}'")]
+    InvalidType(String),
+    #[error("invalid tag object: malformed tagger line: {0}")]
+    InvalidTagger(AuthorParseError),
+    #[error("invalid tag object: content is not valid UTF-8")]
+    InvalidEncoding,
+}
+
+/// Git annotated tag object
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Tag {
+    /// The tagged object
+    object: ObjectId,
+    /// Type of the tagged object
+    object_type: ObjectType,
+    /// Tag name (without `refs/tags/`)
+    name: String,
+    /// Who created the tag
+    tagger: Author,
+    /// Headers after the tagger line, in order
+    extra_headers: Vec<(String, String)>,
+    /// Tag message
+    message: String,
+}
+
+impl Tag {
+    pub fn new(
+        object: ObjectId,
+        object_type: ObjectType,
+        name: String,
+        tagger: Author,
+        message: String,
+    ) -> Self {
+        Tag {
+            object,
+            object_type,
+            name,
+            tagger,
+            extra_headers: Vec::new(),
+            message,
+        }
+    }
+
+    pub fn object(&self) -> &ObjectId {
+        &self.object
+    }
+
+    pub fn target_type(&self) -> &ObjectType {
+        &self.object_type
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn tagger(&self) -> &Author {
+        &self.tagger
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Parse the body of a tag object (everything after the header)
+    ///
+    /// The message is kept byte for byte, so a parsed tag serializes back to
+    /// the same object, and so are unknown headers after the tagger line.
+    pub fn parse(content: &str) -> Result<Self, TagError> {
+        let (headers, message) = content.split_once("\n\n").unwrap_or((content, ""));
+        let mut lines = headers.lines();
+
+        let object = lines
+            .next()
+            .and_then(|line| line.strip_prefix("object "))
+            .ok_or(TagError::MissingHeader("object"))?;
+        let object = ObjectId::try_parse(object.to_string())
+            .map_err(|_| TagError::InvalidObject(object.to_string()))?;
+
+        let object_type = lines
+            .next()
+            .and_then(|line| line.strip_prefix("type "))
+            .ok_or(TagError::MissingHeader("type"))?;
+        let object_type = ObjectType::try_from(object_type)
+            .map_err(|_| TagError::InvalidType(object_type.to_string()))?;
+
+        let name = lines
+            .next()
+            .and_then(|line| line.strip_prefix("tag "))
+            .ok_or(TagError::MissingHeader("tag"))?
+            .to_string();
+
+        let tagger = lines
+            .next()
+            .and_then(|line| line.strip_prefix("tagger "))
+            .ok_or(TagError::MissingHeader("tagger"))?;
+        let tagger = Author::try_from(tagger).map_err(TagError::InvalidTagger)?;
+
+        Ok(Tag {
+            object,
+            object_type,
+            name,
+            tagger,
+            extra_headers: parse_extra_headers(lines),
+            message: message.to_string(),
+        })
+    }
+}
+
+impl Packable for Tag {
+    fn serialize(&self) -> anyhow::Result<Bytes> {
+        let content = self.display();
+
+        let mut tag_bytes = Vec::new();
+        let header = format!("{} {}\0", self.object_type().as_str(), content.len());
+        tag_bytes.write_all(header.as_bytes())?;
+        tag_bytes.write_all(content.as_bytes())?;
+
+        Ok(Bytes::from(tag_bytes))
+    }
+}
+
+impl Unpackable for Tag {
+    fn deserialize(reader: impl BufRead) -> anyhow::Result<Self> {
+        let content = reader
+            .bytes()
+            .collect::<Result<Vec<u8>, std::io::Error>>()?;
+        let content = String::from_utf8(content).map_err(|_| TagError::InvalidEncoding)?;
+
+        Ok(Self::parse(&content)?)
+    }
+}
+
+impl Object for Tag {
+    fn object_type(&self) -> ObjectType {
+        ObjectType::Tag
+    }
+
+    fn display(&self) -> String {
+        let mut lines = vec![
+            format!("object {}", self.object.as_ref()),
+            format!("type {}", self.object_type),
+            format!("tag {}", self.name),
+            format!("tagger {}", self.tagger.display()),
+        ];
+        for (name, value) in &self.extra_headers {
+            lines.push(format_extra_header(name, value));
+        }
+        lines.push(String::new());
+        lines.push(self.message.to_string());
+
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const COMMIT_OID: &str = "8e5b1b6a4bb1d6e2c9a1e0b3f6f4a2b5c7d9e1f3";
+
+    #[test]
+    fn test_parse_valid_tag() {
+        let content = format!(
+            "object {COMMIT_OID}\n\
+            type commit\n\
+            tag v1.0\n\
+            tagger A. U. Thor <author@example.com> 1700000000 +0200\n\
+            \n\
+            Release 1.0\n"
+        );
+
+        let tag = Tag::parse(&content).unwrap();
+
+        assert_eq!(tag.object().as_ref(), COMMIT_OID);
+        assert_eq!(tag.target_type(), &ObjectType::Commit);
+        assert_eq!(tag.name(), "v1.0");
+        assert_eq!(
+            tag.tagger().display(),
+            "A. U. Thor <author@example.com> 1700000000 +0200"
+        );
+        assert_eq!(tag.message(), "Release 1.0\n");
+    }
+
+    #[test]
+    fn test_parse_tag_without_tagger_fails() {
+        let content = format!("object {COMMIT_OID}\ntype commit\ntag v1.0\n\nNo tagger\n");
+
+        let result = Tag::parse(&content);
+
+        assert!(matches!(result, Err(TagError::MissingHeader("tagger"))));
+    }
+
+    #[test]
+    fn test_serialize_matches_git_format() {
+        let tagger = Author::try_from("A. U. Thor <author@example.com> 1700000000 +0000").unwrap();
+        let tag = Tag::new(
+            ObjectId::try_parse(COMMIT_OID.to_string()).unwrap(),
+            ObjectType::Commit,
+            "v1.0".to_string(),
+            tagger,
+            "Release 1.0\n".to_string(),
+        );
+
+        let expected_body = format!(
+            "object {COMMIT_OID}\n\
+            type commit\n\
+            tag v1.0\n\
+            tagger A. U. Thor <author@example.com> 1700000000 +0000\n\
+            \n\
+            Release 1.0\n"
+        );
+        let serialized = tag.serialize().unwrap();
+
+        assert_eq!(Tag::parse(&expected_body).unwrap(), tag);
+        assert_eq!(
+            serialized.as_ref(),
+            format!("tag {}\0{}", expected_body.len(), expected_body).as_bytes()
+        );
+    }
+}
@@ -14,21 +14,32 @@
 //! - Index entries (staging area)
 //! - Existing tree objects (for reading)
 //!
-//! The tree structure is lazily evaluated for efficiency.
+//! The tree structure is lazily evaluated for efficiency: when building from
+//! the index, directories still valid in its cache tree become lazy entries
+//! holding their cached OID, so neither they nor anything below them is
+//! serialized or stored again.
+//!
+//! ## Tree Walking
+//!
+//! `TreeWalker` lists the entries of a stored tree depth-first, loading
+//! subtrees only when it descends into them and dropping them once they are
+//! exhausted, so at most one tree per level is held in memory.
 
+use crate::areas::database::Database;
 use crate::artifacts::database::database_entry::DatabaseEntry;
+use crate::artifacts::index::cache_tree::CacheTree;
 use crate::artifacts::index::entry_mode::EntryMode;
-use crate::artifacts::index::index_entry::IndexEntry;
+use crate::artifacts::index::index_entry::{EntryMetadata, IndexEntry};
 use crate::artifacts::objects::object::Unpackable;
 use crate::artifacts::objects::object::{Object, Packable};
 use crate::artifacts::objects::object_id::ObjectId;
 use crate::artifacts::objects::object_type::ObjectType;
 use anyhow::Context;
 use bytes::Bytes;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, btree_map};
 use std::io::{BufRead, Write};
 use std::marker::PhantomData;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Internal tree entry representation
 ///
@@ -115,6 +126,84 @@ impl<'tree> Tree<'tree> {
         Ok(root)
     }
 
+    /// Build a tree from index entries, reusing the directories still valid
+    /// in `cache_tree`
+    ///
+    /// Entries below a reused directory are skipped: the directory becomes a
+    /// lazy entry with its cached OID. The resulting tree is the same as the
+    /// one `build` would produce.
+    pub fn build_with_cache(
+        entries: impl Iterator<Item = &'tree IndexEntry> + 'tree,
+        cache_tree: &CacheTree,
+    ) -> anyhow::Result<Self> {
+        let mut root = Self::default();
+        // Entries below a directory are contiguous, so it is only added once
+        let mut reused_dir: Option<&Path> = None;
+
+        for entry in entries {
+            if reused_dir.is_some_and(|dir| entry.name.starts_with(dir)) {
+                continue;
+            }
+
+            let parents = entry.parent_dirs();
+            let cached = parents.iter().enumerate().find_map(|(depth, dir)| {
+                let oid = cache_tree.valid_subtree(dir)?.oid()?;
+                Some((depth, *dir, oid))
+            });
+            match cached {
+                Some((depth, dir, oid)) => {
+                    let directory = IndexEntry::new(
+                        dir.to_path_buf(),
+                        oid.clone(),
+                        EntryMetadata {
+                            mode: EntryMode::Directory,
+                            ..Default::default()
+                        },
+                    );
+                    let name = format!("{}/", directory.basename()?);
+                    root.insert_entry(
+                        parents[..depth].to_vec(),
+                        name,
+                        TreeEntry::LazyDirectory(directory),
+                    )?;
+                    reused_dir = Some(dir);
+                }
+                None => root.add_entry(parents, entry)?,
+            }
+        }
+
+        Ok(root)
+    }
+
+    /// Cache tree recording the OID of this tree and of every directory below
+    /// it, with reused directories keeping their entry in `previous`
+    pub fn cache_tree(&self, previous: &CacheTree) -> anyhow::Result<CacheTree> {
+        let mut entry_count = 0;
+        let mut subtrees = BTreeMap::new();
+
+        for (name, tree_entry) in &self.writeable_entries {
+            let name = name.trim_end_matches('/');
+            let subtree = match tree_entry {
+                TreeEntry::File(_) => {
+                    entry_count += 1;
+                    continue;
+                }
+                TreeEntry::Directory(tree) => {
+                    tree.cache_tree(previous.subtree(name).unwrap_or(&CacheTree::default()))?
+                }
+                TreeEntry::LazyDirectory(_) => previous
+                    .subtree(name)
+                    .cloned()
+                    .with_context(|| format!("no cached tree for directory '{}'", name))?,
+            };
+
+            entry_count += subtree.entry_count();
+            subtrees.insert(name.to_string(), subtree);
+        }
+
+        Ok(CacheTree::new(self.object_id()?, entry_count, subtrees))
+    }
+
     /// Traverse the tree depth-first, calling a function on each node
     ///
     /// Visits children before parents (post-order traversal), which is
@@ -142,11 +231,19 @@ impl<'tree> Tree<'tree> {
     ///
     /// Creates intermediate directory entries as needed.
     fn add_entry(&mut self, parents: Vec<&Path>, entry: &IndexEntry) -> anyhow::Result<()> {
+        let name = entry.basename()?.to_string();
+        self.insert_entry(parents, name, TreeEntry::File(entry.clone()))
+    }
+
+    /// Insert `tree_entry` as `name` in the directory below `parents`
+    fn insert_entry(
+        &mut self,
+        parents: Vec<&Path>,
+        name: String,
+        tree_entry: TreeEntry<'tree>,
+    ) -> anyhow::Result<()> {
         if parents.is_empty() {
-            self.writeable_entries.insert(
-                entry.basename()?.to_string(),
-                TreeEntry::File(entry.clone()),
-            );
+            self.writeable_entries.insert(name, tree_entry);
         } else {
             let parent = parents[0]
                 .file_name()
@@ -167,7 +264,7 @@ impl<'tree> Tree<'tree> {
                     }
                 }
             };
-            tree.add_entry(parents[1..].to_vec(), entry)?;
+            tree.insert_entry(parents[1..].to_vec(), name, tree_entry)?;
         }
 
         Ok(())
@@ -182,6 +279,115 @@ impl<'tree> Tree<'tree> {
     }
 }
 
+/// Entry yielded by a [`TreeWalker`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeWalkEntry {
+    /// Path of the entry relative to the root tree
+    pub path: PathBuf,
+    /// Object ID and mode of the entry
+    pub entry: DatabaseEntry,
+}
+
+impl TreeWalkEntry {
+    /// Type of the referenced object; submodules reference a commit
+    pub fn object_type(&self) -> ObjectType {
+        if self.entry.is_tree() {
+            ObjectType::Tree
+        } else if self.entry.is_gitlink() {
+            ObjectType::Commit
+        } else {
+            ObjectType::Blob
+        }
+    }
+}
+
+/// Lazy depth-first walker over a stored tree
+///
+/// Keeps a stack with the remaining entries of each tree on the current
+/// path. When recursive, subtrees are descended into instead of being
+/// yielded, matching `git ls-tree -r`.
+pub struct TreeWalker<'d> {
+    database: &'d Database,
+    recursive: bool,
+    /// Remaining entries of each open tree, together with its path
+    stack: Vec<(PathBuf, btree_map::IntoIter<String, DatabaseEntry>)>,
+    /// Number of tree objects loaded so far
+    loaded_trees: usize,
+    /// Maximum number of trees held at the same time
+    peak_open_trees: usize,
+}
+
+impl<'d> TreeWalker<'d> {
+    /// Start walking the tree identified by `oid`
+    ///
+    /// # Arguments
+    ///
+    /// * `database` - Database to load trees from
+    /// * `oid` - Object ID of the root tree
+    /// * `recursive` - Whether to descend into subtrees
+    pub fn new(database: &'d Database, oid: &ObjectId, recursive: bool) -> anyhow::Result<Self> {
+        let mut walker = Self {
+            database,
+            recursive,
+            stack: Vec::new(),
+            loaded_trees: 0,
+            peak_open_trees: 0,
+        };
+        walker.open_tree(oid, PathBuf::new())?;
+
+        Ok(walker)
+    }
+
+    /// Number of tree objects loaded from the database so far
+    pub fn loaded_trees(&self) -> usize {
+        self.loaded_trees
+    }
+
+    /// Maximum number of trees that were held in memory at the same time
+    pub fn peak_open_trees(&self) -> usize {
+        self.peak_open_trees
+    }
+
+    fn open_tree(&mut self, oid: &ObjectId, path: PathBuf) -> anyhow::Result<()> {
+        let tree = self
+            .database
+            .parse_object_as_tree(oid)?
+            .ok_or_else(|| anyhow::anyhow!("{} is not a tree object", oid))?;
+
+        self.stack.push((path, tree.readable_entries.into_iter()));
+        self.loaded_trees += 1;
+        self.peak_open_trees = self.peak_open_trees.max(self.stack.len());
+
+        Ok(())
+    }
+}
+
+impl Iterator for TreeWalker<'_> {
+    type Item = anyhow::Result<TreeWalkEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (prefix, entries) = self.stack.last_mut()?;
+
+            let Some((name, entry)) = entries.next() else {
+                // Drop the exhausted tree before moving back up
+                self.stack.pop();
+                continue;
+            };
+            let path = prefix.join(name);
+
+            if self.recursive && entry.is_tree() {
+                if let Err(err) = self.open_tree(&entry.oid, path) {
+                    return Some(Err(err));
+                }
+                continue;
+            }
+
+            return Some(Ok(TreeWalkEntry { path, entry }));
+        }
+    }
+}
+
 impl Packable for Tree<'_> {
     fn serialize(&self) -> anyhow::Result<Bytes> {
         let content_bytes: Bytes = self
@@ -286,3 +492,232 @@ impl Object for Tree<'_> {
             .join("\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::index::entry_mode::FileMode;
+    use crate::artifacts::objects::EMPTY_TREE_OID;
+    use crate::artifacts::objects::blob::Blob;
+
+    /// Store a tree with `depth` nested directories, each holding `width` files
+    fn store_nested_tree(
+        database: &Database,
+        depth: usize,
+        width: usize,
+    ) -> (ObjectId, Vec<TreeWalkEntry>) {
+        let mut index_entries = Vec::new();
+        let mut dir = PathBuf::new();
+
+        for level in 0..=depth {
+            for file in 0..width {
+                let blob = Blob::new(format!("{} {}", level, file), Default::default());
+                database.store(blob.clone()).unwrap();
+
+                index_entries.push(IndexEntry::new(
+                    dir.join(format!("file{}.txt", file)),
+                    blob.object_id().unwrap(),
+                    EntryMetadata {
+                        mode: EntryMode::File(FileMode::Regular),
+                        ..Default::default()
+                    },
+                ));
+            }
+            dir = dir.join(format!("dir{}", level));
+        }
+
+        let tree = Tree::build(index_entries.iter()).unwrap();
+        tree.traverse(&|tree: &Tree| Ok(database.store(tree.clone())?))
+            .unwrap();
+
+        let mut expected = index_entries
+            .iter()
+            .map(|entry| TreeWalkEntry {
+                path: entry.name.clone(),
+                entry: DatabaseEntry::new(entry.oid.clone(), entry.metadata.mode),
+            })
+            .collect::<Vec<_>>();
+        expected.sort_by(|a, b| a.path.cmp(&b.path));
+
+        (tree.object_id().unwrap(), expected)
+    }
+
+    /// Index entries for `top/sub/file.txt` paths, plus a root-level file
+    fn wide_index_entries(top_dirs: usize, sub_dirs: usize, files: usize) -> Vec<IndexEntry> {
+        let mut paths = vec![PathBuf::from("README")];
+        for top in 0..top_dirs {
+            for sub in 0..sub_dirs {
+                for file in 0..files {
+                    paths.push(PathBuf::from(format!("dir{top}/sub{sub}/file{file}.txt")));
+                }
+            }
+        }
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let blob = Blob::new(path.display().to_string(), Default::default());
+                IndexEntry::new(
+                    path,
+                    blob.object_id().unwrap(),
+                    EntryMetadata {
+                        mode: EntryMode::File(FileMode::Regular),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Number of trees a store of `tree` would serialize and write
+    fn rebuilt_trees(tree: &Tree) -> usize {
+        let count = std::cell::Cell::new(0);
+        tree.traverse(&|_: &Tree| {
+            count.set(count.get() + 1);
+            Ok(())
+        })
+        .unwrap();
+
+        count.get()
+    }
+
+    #[test]
+    fn test_build_with_cache_only_rebuilds_trees_along_changed_path() {
+        let mut entries = wide_index_entries(4, 3, 5);
+        let mut cache_tree = Tree::build(entries.iter())
+            .unwrap()
+            .cache_tree(&CacheTree::default())
+            .unwrap();
+        assert_eq!(cache_tree.entry_count(), entries.len());
+
+        let changed = entries
+            .iter_mut()
+            .find(|entry| entry.name == Path::new("dir2/sub1/file3.txt"))
+            .unwrap();
+        changed.oid = Blob::new("changed".to_string(), Default::default())
+            .object_id()
+            .unwrap();
+        cache_tree.invalidate(&changed.name);
+
+        let tree = Tree::build_with_cache(entries.iter(), &cache_tree).unwrap();
+        let full_tree = Tree::build(entries.iter()).unwrap();
+
+        // Only the root, dir2 and dir2/sub1 are rebuilt
+        assert_eq!(rebuilt_trees(&tree), 3);
+        assert_eq!(rebuilt_trees(&full_tree), 1 + 4 + 4 * 3);
+        assert_eq!(tree.object_id().unwrap(), full_tree.object_id().unwrap());
+        assert_eq!(
+            tree.cache_tree(&cache_tree).unwrap(),
+            full_tree.cache_tree(&CacheTree::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_with_cache_reuses_directories_when_only_root_files_change() {
+        let mut entries = wide_index_entries(3, 2, 2);
+        let mut cache_tree = Tree::build(entries.iter())
+            .unwrap()
+            .cache_tree(&CacheTree::default())
+            .unwrap();
+
+        entries[0].oid = Blob::new("new readme".to_string(), Default::default())
+            .object_id()
+            .unwrap();
+        cache_tree.invalidate(&entries[0].name);
+
+        let tree = Tree::build_with_cache(entries.iter(), &cache_tree).unwrap();
+
+        assert_eq!(rebuilt_trees(&tree), 1);
+        assert_eq!(
+            tree.object_id().unwrap(),
+            Tree::build(entries.iter()).unwrap().object_id().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_from_no_entries_yields_empty_tree_oid() {
+        let tree = Tree::build(std::iter::empty()).unwrap();
+
+        assert_eq!(tree.object_id().unwrap().as_ref(), EMPTY_TREE_OID);
+        assert!(tree.object_id().unwrap().is_empty_tree());
+    }
+
+    #[test]
+    fn test_deserialize_empty_content_yields_no_entries() {
+        let tree = Tree::deserialize(std::io::Cursor::new(Vec::new())).unwrap();
+
+        assert_eq!(tree.entries().count(), 0);
+    }
+
+    #[test]
+    fn test_walk_recursive_yields_flattened_blob_entries() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let database = Database::new(dir.path().into());
+        let (tree_oid, expected) = store_nested_tree(&database, 4, 3);
+
+        let mut entries = TreeWalker::new(&database, &tree_oid, true)
+            .unwrap()
+            .collect::<anyhow::Result<Vec<_>>>()
+            .unwrap();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        assert_eq!(entries, expected);
+        assert!(
+            entries
+                .iter()
+                .all(|entry| entry.object_type() == ObjectType::Blob)
+        );
+    }
+
+    #[test]
+    fn test_walk_non_recursive_yields_top_level_entries() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let database = Database::new(dir.path().into());
+        let (tree_oid, _) = store_nested_tree(&database, 2, 2);
+
+        let mut walker = TreeWalker::new(&database, &tree_oid, false).unwrap();
+        let paths = walker
+            .by_ref()
+            .map(|entry| entry.unwrap().path)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("dir0"),
+                PathBuf::from("file0.txt"),
+                PathBuf::from("file1.txt")
+            ]
+        );
+        assert_eq!(walker.loaded_trees(), 1);
+    }
+
+    #[test]
+    fn test_walk_holds_one_tree_per_level() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let database = Database::new(dir.path().into());
+        let (narrow_oid, _) = store_nested_tree(&database, 5, 1);
+        let (wide_oid, _) = store_nested_tree(&database, 5, 50);
+
+        for tree_oid in [narrow_oid, wide_oid] {
+            let mut walker = TreeWalker::new(&database, &tree_oid, true).unwrap();
+            for entry in walker.by_ref() {
+                entry.unwrap();
+            }
+
+            // Every tree is loaded exactly once, but only the current path is kept
+            assert_eq!(walker.loaded_trees(), 6);
+            assert_eq!(walker.peak_open_trees(), 6);
+        }
+    }
+
+    #[test]
+    fn test_walk_rejects_non_tree_root() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let database = Database::new(dir.path().into());
+        let blob = Blob::new("content".to_string(), Default::default());
+        database.store(blob.clone()).unwrap();
+
+        assert!(TreeWalker::new(&database, &blob.object_id().unwrap(), true).is_err());
+    }
+}
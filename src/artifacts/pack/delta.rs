@@ -0,0 +1,170 @@
+//! Git delta decoding
+//!
+//! A delta rebuilds a target object from a base object using two kinds of
+//! instructions:
+//! - Copy (`1xxxxxxx`): copy a byte range of the base into the target
+//! - Insert (`0xxxxxxx`): append the next `n` literal bytes of the delta
+//!
+//! The instructions are preceded by the base and target sizes, encoded as
+//! little-endian base-128 varints.
+
+use crate::artifacts::pack::reader::PackError;
+
+/// Size used by a copy instruction that encodes a size of zero
+const DEFAULT_COPY_SIZE: usize = 0x10000;
+
+/// Apply a delta to its base object, producing the target object content
+///
+/// # Arguments
+///
+/// * `base` - Content of the base object
+/// * `delta` - Decompressed delta data
+///
+/// # Returns
+///
+/// The reconstructed target content, or an error if the delta is malformed
+/// or does not match the base size
+pub fn apply_delta(base: &[u8], delta: &[u8]) -> Result<Vec<u8>, PackError> {
+    let mut cursor = 0;
+
+    let base_size = read_size(delta, &mut cursor)?;
+    if base_size != base.len() {
+        return Err(PackError::InvalidDelta(format!(
+            "base size mismatch: expected {}, got {}",
+            base_size,
+            base.len()
+        )));
+    }
+    let target_size = read_size(delta, &mut cursor)?;
+
+    let mut target = Vec::with_capacity(target_size);
+
+    while cursor < delta.len() {
+        let instruction = delta[cursor];
+        cursor += 1;
+
+        if instruction & 0x80 != 0 {
+            let offset = read_copy_operand(delta, &mut cursor, instruction, 0, 4)?;
+            let size = match read_copy_operand(delta, &mut cursor, instruction >> 4, 0, 3)? {
+                0 => DEFAULT_COPY_SIZE,
+                size => size,
+            };
+
+            let chunk = base.get(offset..offset + size).ok_or_else(|| {
+                PackError::InvalidDelta(format!(
+                    "copy of {} bytes at offset {} is out of bounds",
+                    size, offset
+                ))
+            })?;
+            target.extend_from_slice(chunk);
+        } else if instruction != 0 {
+            let size = instruction as usize;
+            let chunk = delta.get(cursor..cursor + size).ok_or_else(|| {
+                PackError::InvalidDelta("insert runs past the end of the delta".to_string())
+            })?;
+            target.extend_from_slice(chunk);
+            cursor += size;
+        } else {
+            return Err(PackError::InvalidDelta(
+                "reserved instruction 0x00".to_string(),
+            ));
+        }
+    }
+
+    if target.len() != target_size {
+        return Err(PackError::InvalidDelta(format!(
+            "target size mismatch: expected {}, got {}",
+            target_size,
+            target.len()
+        )));
+    }
+
+    Ok(target)
+}
+
+/// Read a little-endian base-128 varint (used for the delta header sizes)
+fn read_size(data: &[u8], cursor: &mut usize) -> Result<usize, PackError> {
+    let mut size = 0;
+    let mut shift = 0;
+
+    loop {
+        let byte = *data
+            .get(*cursor)
+            .ok_or_else(|| PackError::InvalidDelta("truncated size header".to_string()))?;
+        *cursor += 1;
+
+        size |= ((byte & 0x7f) as usize) << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok(size);
+        }
+    }
+}
+
+/// Read the sparse little-endian operand of a copy instruction
+///
+/// Each of the low `count` bits of `flags` says whether the corresponding
+/// byte of the operand is present in the delta stream.
+fn read_copy_operand(
+    data: &[u8],
+    cursor: &mut usize,
+    flags: u8,
+    start: u8,
+    count: u8,
+) -> Result<usize, PackError> {
+    let mut value = 0;
+
+    for i in start..start + count {
+        if flags & (1 << i) != 0 {
+            let byte = *data
+                .get(*cursor)
+                .ok_or_else(|| PackError::InvalidDelta("truncated copy operand".to_string()))?;
+            *cursor += 1;
+            value |= (byte as usize) << (8 * (i - start));
+        }
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_delta_with_copy_and_insert() {
+        let base = b"hello world";
+        // base size 11, target size 11, copy 6 bytes from offset 0, insert "there"
+        let delta = [11, 11, 0x90, 6, 5, b't', b'h', b'e', b'r', b'e'];
+
+        let target = apply_delta(base, &delta).unwrap();
+
+        assert_eq!(target, b"hello there");
+    }
+
+    #[test]
+    fn test_apply_delta_with_copy_offset() {
+        let base = b"hello world";
+        // copy 5 bytes from offset 6
+        let delta = [11, 5, 0x91, 6, 5];
+
+        let target = apply_delta(base, &delta).unwrap();
+
+        assert_eq!(target, b"world");
+    }
+
+    #[test]
+    fn test_apply_delta_rejects_base_size_mismatch() {
+        let delta = [3, 1, 1, b'x'];
+
+        assert!(apply_delta(b"hello", &delta).is_err());
+    }
+
+    #[test]
+    fn test_apply_delta_rejects_out_of_bounds_copy() {
+        let delta = [5, 10, 0x90, 10];
+
+        assert!(apply_delta(b"hello", &delta).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+//! Pack index (`.idx`, version 2)
+//!
+//! Maps the object IDs stored in a packfile to the offsets of their entries,
+//! so single objects can be read without scanning the whole pack.
+//!
+//! ## Format
+//!
+//! ```text
+//! \377tOc <version: u32 = 2>
+//! <fanout: 256 x u32>       (number of objects whose first byte is <= i)
+//! <object IDs: N x 20 bytes, sorted>
+//! <CRC-32 of each packed entry: N x u32>
+//! <offsets: N x u32>        (MSB set: index into the 64-bit offset table)
+//! <64-bit offsets: M x u64>
+//! <pack checksum: 20 bytes>
+//! <index checksum: 20 bytes>
+//! ```
+
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::pack::reader::PackError;
+use byteorder::{BigEndian, ByteOrder};
+use sha1::{Digest, Sha1};
+
+/// Magic bytes at the start of every version 2 pack index
+pub const PACK_INDEX_SIGNATURE: &[u8; 4] = b"\xfftOc";
+
+const PACK_INDEX_VERSION: u32 = 2;
+const FANOUT_SIZE: usize = 256 * 4;
+const HEADER_SIZE: usize = 8;
+const LARGE_OFFSET_FLAG: u32 = 0x8000_0000;
+
+/// Location of one object in its pack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackIndexEntry {
+    pub oid: ObjectId,
+    /// CRC-32 of the raw (compressed) entry in the pack
+    pub crc32: u32,
+    /// Offset of the entry header from the start of the pack
+    pub offset: u64,
+}
+
+/// In-memory pack index, sorted by object ID
+#[derive(Debug, Clone)]
+pub struct PackIndex {
+    entries: Vec<PackIndexEntry>,
+    /// Number of entries whose first byte is <= i
+    fanout: [u32; 256],
+    pack_checksum: Vec<u8>,
+}
+
+impl PackIndex {
+    pub fn new(mut entries: Vec<PackIndexEntry>, pack_checksum: Vec<u8>) -> Self {
+        entries.sort_by(|a, b| a.oid.cmp(&b.oid));
+
+        let mut fanout = [0u32; 256];
+        for entry in &entries {
+            fanout[Self::first_byte(&entry.oid) as usize] += 1;
+        }
+        for i in 1..fanout.len() {
+            fanout[i] += fanout[i - 1];
+        }
+
+        PackIndex {
+            entries,
+            fanout,
+            pack_checksum,
+        }
+    }
+
+    pub fn parse(data: &[u8]) -> Result<Self, PackError> {
+        let invalid = |reason: &str| PackError::InvalidIndex(reason.to_string());
+
+        if data.len() < HEADER_SIZE + FANOUT_SIZE + 40 {
+            return Err(invalid("file too short"));
+        }
+        if &data[..4] != PACK_INDEX_SIGNATURE {
+            return Err(invalid("bad signature"));
+        }
+        let version = BigEndian::read_u32(&data[4..8]);
+        if version != PACK_INDEX_VERSION {
+            return Err(invalid(&format!("unsupported version {}", version)));
+        }
+
+        let (content, checksum) = data.split_at(data.len() - 20);
+        if Sha1::digest(content).as_slice() != checksum {
+            return Err(invalid("checksum mismatch"));
+        }
+
+        let mut fanout = [0u32; 256];
+        BigEndian::read_u32_into(&data[HEADER_SIZE..HEADER_SIZE + FANOUT_SIZE], &mut fanout);
+        if fanout.windows(2).any(|pair| pair[0] > pair[1]) {
+            return Err(invalid("fanout table not sorted"));
+        }
+
+        let count = fanout[255] as usize;
+        let oids_start = HEADER_SIZE + FANOUT_SIZE;
+        let crcs_start = oids_start + count * 20;
+        let offsets_start = crcs_start + count * 4;
+        let large_offsets_start = offsets_start + count * 4;
+        if data.len() < large_offsets_start + 40 {
+            return Err(invalid("truncated object table"));
+        }
+
+        let mut entries = Vec::with_capacity(count);
+        for i in 0..count {
+            let mut oid_bytes = &data[oids_start + i * 20..oids_start + (i + 1) * 20];
+            let oid = ObjectId::read_h40_from(&mut oid_bytes)?;
+            let crc32 = BigEndian::read_u32(&data[crcs_start + i * 4..]);
+            let offset = BigEndian::read_u32(&data[offsets_start + i * 4..]);
+
+            let offset = if offset & LARGE_OFFSET_FLAG == 0 {
+                offset as u64
+            } else {
+                let position = large_offsets_start + (offset & !LARGE_OFFSET_FLAG) as usize * 8;
+                if position + 8 > data.len() - 40 {
+                    return Err(invalid("large offset out of range"));
+                }
+                BigEndian::read_u64(&data[position..])
+            };
+
+            entries.push(PackIndexEntry { oid, crc32, offset });
+        }
+
+        let pack_checksum = data[data.len() - 40..data.len() - 20].to_vec();
+
+        Ok(PackIndex {
+            entries,
+            fanout,
+            pack_checksum,
+        })
+    }
+
+    pub fn serialize(&self) -> Result<Vec<u8>, PackError> {
+        let mut data = Vec::new();
+        data.extend_from_slice(PACK_INDEX_SIGNATURE);
+        data.extend_from_slice(&PACK_INDEX_VERSION.to_be_bytes());
+
+        for total in self.fanout {
+            data.extend_from_slice(&total.to_be_bytes());
+        }
+
+        for entry in &self.entries {
+            entry.oid.write_h40_to(&mut data)?;
+        }
+        for entry in &self.entries {
+            data.extend_from_slice(&entry.crc32.to_be_bytes());
+        }
+
+        let mut large_offsets = Vec::new();
+        for entry in &self.entries {
+            let offset = match u32::try_from(entry.offset) {
+                Ok(offset) if offset & LARGE_OFFSET_FLAG == 0 => offset,
+                _ => {
+                    large_offsets.push(entry.offset);
+                    LARGE_OFFSET_FLAG | (large_offsets.len() - 1) as u32
+                }
+            };
+            data.extend_from_slice(&offset.to_be_bytes());
+        }
+        for offset in large_offsets {
+            data.extend_from_slice(&offset.to_be_bytes());
+        }
+
+        data.extend_from_slice(&self.pack_checksum);
+        let checksum = Sha1::digest(&data);
+        data.extend_from_slice(checksum.as_slice());
+
+        Ok(data)
+    }
+
+    /// Offset of an object's entry in the pack, if the pack holds it
+    pub fn offset_of(&self, oid: &ObjectId) -> Option<u64> {
+        self.entries
+            .binary_search_by(|entry| entry.oid.cmp(oid))
+            .ok()
+            .map(|position| self.entries[position].offset)
+    }
+
+    /// Entries whose object ID starts with the hexadecimal `prefix`
+    ///
+    /// The fanout table narrows the search to the entries sharing the first
+    /// byte, and a binary search finds the range within them.
+    pub fn prefix_matches(&self, prefix: &str) -> &[PackIndexEntry] {
+        let candidates = match u8::from_str_radix(prefix.get(..2).unwrap_or_default(), 16) {
+            Ok(byte) => &self.entries[self.fanout_range(byte)],
+            Err(_) => &self.entries[..],
+        };
+
+        let start = candidates.partition_point(|entry| entry.oid.as_ref() < prefix);
+        let end = candidates.partition_point(|entry| {
+            entry.oid.as_ref() < prefix || entry.oid.as_ref().starts_with(prefix)
+        });
+
+        &candidates[start..end]
+    }
+
+    /// The entries sorted right before and after `oid`, plus `oid` itself
+    /// when the pack holds it
+    ///
+    /// Only entries with the same first byte are considered: these are the
+    /// only ones that can share a longer prefix with `oid` than any other in
+    /// the pack.
+    pub fn neighbours(&self, oid: &ObjectId) -> &[PackIndexEntry] {
+        let candidates = &self.entries[self.fanout_range(Self::first_byte(oid))];
+        let position = candidates.partition_point(|entry| entry.oid < *oid);
+        let end = match candidates.get(position) {
+            Some(entry) if entry.oid == *oid => position + 2,
+            _ => position + 1,
+        };
+
+        &candidates[position.saturating_sub(1)..end.min(candidates.len())]
+    }
+
+    pub fn entries(&self) -> &[PackIndexEntry] {
+        &self.entries
+    }
+
+    pub fn pack_checksum(&self) -> &[u8] {
+        &self.pack_checksum
+    }
+
+    /// Positions of the entries whose first byte is `byte`
+    fn fanout_range(&self, byte: u8) -> std::ops::Range<usize> {
+        let start = match byte {
+            0 => 0,
+            byte => self.fanout[byte as usize - 1] as usize,
+        };
+
+        start..self.fanout[byte as usize] as usize
+    }
+
+    fn first_byte(oid: &ObjectId) -> u8 {
+        u8::from_str_radix(&oid.as_ref()[..2], 16).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn oid(prefix: &str) -> ObjectId {
+        ObjectId::try_parse(format!("{:0<40}", prefix)).unwrap()
+    }
+
+    #[test]
+    fn test_serialize_and_parse_round_trip() {
+        let index = PackIndex::new(
+            vec![
+                PackIndexEntry {
+                    oid: oid("ff"),
+                    crc32: 3,
+                    offset: 1 << 33,
+                },
+                PackIndexEntry {
+                    oid: oid("0a"),
+                    crc32: 1,
+                    offset: 12,
+                },
+                PackIndexEntry {
+                    oid: oid("0b"),
+                    crc32: 2,
+                    offset: 40,
+                },
+            ],
+            vec![7; 20],
+        );
+
+        let parsed = PackIndex::parse(&index.serialize().unwrap()).unwrap();
+
+        assert_eq!(parsed.entries(), index.entries());
+        assert_eq!(parsed.pack_checksum(), &[7; 20]);
+        assert_eq!(parsed.offset_of(&oid("0b")), Some(40));
+        assert_eq!(parsed.offset_of(&oid("ff")), Some(1 << 33));
+        assert_eq!(parsed.offset_of(&oid("0c")), None);
+    }
+
+    #[test]
+    fn test_prefix_matches_are_a_range_of_entries() {
+        let index = PackIndex::new(
+            ["0a1", "0a2", "0b", "1c"]
+                .into_iter()
+                .map(|prefix| PackIndexEntry {
+                    oid: oid(prefix),
+                    crc32: 0,
+                    offset: 12,
+                })
+                .collect(),
+            vec![0; 20],
+        );
+        let matches = |prefix: &str| {
+            index
+                .prefix_matches(prefix)
+                .iter()
+                .map(|entry| entry.oid.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(matches("0a"), vec![oid("0a1"), oid("0a2")]);
+        assert_eq!(matches("0"), vec![oid("0a1"), oid("0a2"), oid("0b")]);
+        assert_eq!(matches("0a2"), vec![oid("0a2")]);
+        assert!(matches("0c").is_empty());
+    }
+
+    #[test]
+    fn test_neighbours_surround_the_object() {
+        let index = PackIndex::new(
+            ["0a1", "0a2", "0b", "1c"]
+                .into_iter()
+                .map(|prefix| PackIndexEntry {
+                    oid: oid(prefix),
+                    crc32: 0,
+                    offset: 12,
+                })
+                .collect(),
+            vec![0; 20],
+        );
+        let neighbours = |of: &str| {
+            index
+                .neighbours(&oid(of))
+                .iter()
+                .map(|entry| entry.oid.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(neighbours("0a2"), vec![oid("0a1"), oid("0a2")]);
+        assert_eq!(neighbours("0a3"), vec![oid("0a2")]);
+        assert_eq!(neighbours("1c"), vec![oid("1c")]);
+    }
+
+    #[test]
+    fn test_parse_rejects_corrupt_index() {
+        let index = PackIndex::new(vec![], vec![0; 20]);
+        let mut data = index.serialize().unwrap();
+        data[HEADER_SIZE] ^= 0xff;
+
+        assert!(matches!(
+            PackIndex::parse(&data),
+            Err(PackError::InvalidIndex(_))
+        ));
+    }
+}
@@ -0,0 +1,33 @@
+//! Packfile reading and writing
+//!
+//! Packfiles bundle many objects into a single file, storing most of them as
+//! deltas against other objects. This module decodes packs so their objects
+//! can be imported into the loose object database or read in place through
+//! their index, and writes the packs produced by `gc`.
+//!
+//! - `reader`: Pack header/entry parsing and delta chain resolution
+//! - `delta`: Application of Git's copy/insert delta instructions
+//! - `index`: Pack index (`.idx`) parsing and serialization
+//! - `writer`: Packfile creation from full objects
+//!
+//! ## Format
+//!
+//! ```text
+//! PACK <version: u32> <object count: u32>
+//! <entry>*            (type + size varint, optional delta base, zlib data)
+//! <20-byte SHA-1 of everything above>
+//! ```
+
+pub mod delta;
+pub mod index;
+pub mod reader;
+pub mod writer;
+
+/// Magic bytes at the start of every packfile
+pub const PACK_SIGNATURE: &[u8; 4] = b"PACK";
+
+/// Size of the pack header (signature, version, object count)
+pub const PACK_HEADER_SIZE: usize = 12;
+
+/// Size of the trailing SHA-1 checksum
+pub const PACK_CHECKSUM_SIZE: usize = 20;
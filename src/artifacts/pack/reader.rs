@@ -0,0 +1,438 @@
+//! Packfile reader
+//!
+//! Parses a packfile held in memory into its entries and resolves delta
+//! chains into full objects.
+//!
+//! ## Entry Header
+//!
+//! Every entry starts with a varint whose first byte holds the object type
+//! in bits 4-6 and the low 4 bits of the (uncompressed) size:
+//!
+//! ```text
+//! 1ttt ssss  1sss ssss  ...  0sss ssss
+//! ```
+//!
+//! Delta entries are followed by their base: a negative offset for
+//! `OFS_DELTA` or the 20-byte base object ID for `REF_DELTA`.
+
+use crate::artifacts::objects::object_id::{ObjectId, ObjectIdError};
+use crate::artifacts::objects::object_type::ObjectType;
+use crate::artifacts::pack::delta::apply_delta;
+use crate::artifacts::pack::{PACK_CHECKSUM_SIZE, PACK_HEADER_SIZE, PACK_SIGNATURE};
+use byteorder::{BigEndian, ByteOrder};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PackError {
+    #[error("invalid pack signature")]
+    InvalidSignature,
+    #[error("unsupported pack version: {0}")]
+    UnsupportedVersion(u32),
+    #[error("pack checksum mismatch: packfile is corrupt")]
+    ChecksumMismatch,
+    #[error("unexpected end of pack data at offset {0}")]
+    UnexpectedEof(usize),
+    #[error("invalid object type {0} in pack")]
+    InvalidObjectType(u8),
+    #[error("invalid delta: {0}")]
+    InvalidDelta(String),
+    #[error("delta base {0} not found")]
+    MissingBase(String),
+    #[error("failed to decompress pack entry at offset {0}")]
+    Decompress(usize),
+    #[error("failed to compress pack entry")]
+    Compress(#[source] std::io::Error),
+    #[error("failed to write pack data")]
+    Write(#[source] std::io::Error),
+    #[error("pack object count does not match its header")]
+    ObjectCount,
+    #[error("invalid pack index: {0}")]
+    InvalidIndex(String),
+    #[error(transparent)]
+    ObjectId(#[from] ObjectIdError),
+}
+
+/// Supported pack versions
+const PACK_VERSIONS: [u32; 2] = [2, 3];
+
+/// Pack entry type codes
+pub(super) const OBJ_COMMIT: u8 = 1;
+pub(super) const OBJ_TREE: u8 = 2;
+pub(super) const OBJ_BLOB: u8 = 3;
+pub(super) const OBJ_TAG: u8 = 4;
+const OBJ_OFS_DELTA: u8 = 6;
+const OBJ_REF_DELTA: u8 = 7;
+
+/// How an entry's content is stored in the pack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackEntryKind {
+    /// A full object of the given type
+    Base(ObjectType),
+    /// A delta against the entry at the given pack offset
+    OfsDelta(usize),
+    /// A delta against the object with the given ID
+    RefDelta(ObjectId),
+}
+
+/// A single, decompressed entry of a packfile
+#[derive(Debug, Clone)]
+pub struct PackEntry {
+    /// Offset of the entry header from the start of the pack
+    pub offset: usize,
+    /// Storage kind (full object or delta)
+    pub kind: PackEntryKind,
+    /// Decompressed data (object content or delta instructions)
+    pub data: Vec<u8>,
+}
+
+/// A fully resolved object extracted from a pack
+#[derive(Debug, Clone)]
+pub struct PackedObject {
+    pub object_type: ObjectType,
+    pub content: Vec<u8>,
+}
+
+/// Reader over an in-memory packfile, or a part of one
+#[derive(Debug)]
+pub struct PackReader<'p> {
+    data: &'p [u8],
+    /// Offset in the pack of the first byte of `data`
+    start: usize,
+}
+
+impl<'p> PackReader<'p> {
+    pub fn new(data: &'p [u8]) -> Self {
+        PackReader { data, start: 0 }
+    }
+
+    /// Reader over the bytes of a pack from offset `start` on, e.g. a single
+    /// entry read through the pack index
+    pub fn with_start(data: &'p [u8], start: usize) -> Self {
+        PackReader { data, start }
+    }
+
+    /// Parse the header, verify the trailing checksum and decode every entry
+    pub fn read_entries(&self) -> Result<Vec<PackEntry>, PackError> {
+        let object_count = self.read_header()?;
+        self.verify_checksum()?;
+
+        let mut cursor = PACK_HEADER_SIZE;
+        let mut entries = Vec::with_capacity(object_count as usize);
+
+        for _ in 0..object_count {
+            let entry = self.read_entry(&mut cursor)?;
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    /// Decode all entries and resolve deltas into full objects
+    ///
+    /// # Arguments
+    ///
+    /// * `load_external` - Lookup for `REF_DELTA` bases that are not part of
+    ///   the pack itself (e.g. thin packs completed by the object database)
+    ///
+    /// # Returns
+    ///
+    /// The resolved objects, in pack order
+    pub fn read_objects<F>(&self, load_external: F) -> Result<Vec<PackedObject>, PackError>
+    where
+        F: Fn(&ObjectId) -> Option<PackedObject>,
+    {
+        let entries = self.read_entries()?;
+
+        let mut by_offset: HashMap<usize, PackedObject> = HashMap::new();
+        let mut by_oid: HashMap<ObjectId, usize> = HashMap::new();
+        let mut pending: Vec<&PackEntry> = entries.iter().collect();
+
+        // REF_DELTA bases may appear later in the pack, so keep resolving
+        // until a full pass makes no progress
+        while !pending.is_empty() {
+            let mut unresolved = Vec::new();
+
+            for entry in pending.iter().copied() {
+                let object = match &entry.kind {
+                    PackEntryKind::Base(object_type) => Some(PackedObject {
+                        object_type: object_type.clone(),
+                        content: entry.data.clone(),
+                    }),
+                    PackEntryKind::OfsDelta(base_offset) => by_offset
+                        .get(base_offset)
+                        .map(|base| Self::patch(base, &entry.data))
+                        .transpose()?,
+                    PackEntryKind::RefDelta(base_oid) => by_oid
+                        .get(base_oid)
+                        .and_then(|offset| by_offset.get(offset))
+                        .map(|base| Self::patch(base, &entry.data))
+                        .transpose()?,
+                };
+
+                match object {
+                    Some(object) => {
+                        by_oid.insert(Self::hash_object(&object)?, entry.offset);
+                        by_offset.insert(entry.offset, object);
+                    }
+                    None => unresolved.push(entry),
+                }
+            }
+
+            if unresolved.len() == pending.len() {
+                // No progress: the next base must come from outside the pack
+                let entry = unresolved.remove(0);
+                let object = match &entry.kind {
+                    PackEntryKind::RefDelta(base_oid) => {
+                        let base = load_external(base_oid)
+                            .ok_or_else(|| PackError::MissingBase(base_oid.to_string()))?;
+                        Self::patch(&base, &entry.data)?
+                    }
+                    _ => {
+                        return Err(PackError::MissingBase(format!(
+                            "at offset {}",
+                            entry.offset
+                        )));
+                    }
+                };
+
+                by_oid.insert(Self::hash_object(&object)?, entry.offset);
+                by_offset.insert(entry.offset, object);
+            }
+
+            pending = unresolved;
+        }
+
+        Ok(entries
+            .iter()
+            .filter_map(|entry| by_offset.remove(&entry.offset))
+            .collect())
+    }
+
+    /// Decode the single entry starting at the pack offset `offset`
+    ///
+    /// Used for lookups through a pack index, so the pack checksum is not
+    /// verified and deltas are left for the caller to resolve.
+    pub fn read_entry_at(&self, offset: usize) -> Result<PackEntry, PackError> {
+        let mut cursor = offset
+            .checked_sub(self.start)
+            .ok_or(PackError::UnexpectedEof(offset))?;
+
+        self.read_entry(&mut cursor)
+    }
+
+    fn read_header(&self) -> Result<u32, PackError> {
+        if self.data.len() < PACK_HEADER_SIZE + PACK_CHECKSUM_SIZE {
+            return Err(PackError::UnexpectedEof(self.data.len()));
+        }
+
+        if &self.data[..4] != PACK_SIGNATURE {
+            return Err(PackError::InvalidSignature);
+        }
+
+        let version = BigEndian::read_u32(&self.data[4..8]);
+        if !PACK_VERSIONS.contains(&version) {
+            return Err(PackError::UnsupportedVersion(version));
+        }
+
+        Ok(BigEndian::read_u32(&self.data[8..12]))
+    }
+
+    fn verify_checksum(&self) -> Result<(), PackError> {
+        let (content, checksum) = self.data.split_at(self.data.len() - PACK_CHECKSUM_SIZE);
+
+        if Sha1::digest(content).as_slice() != checksum {
+            return Err(PackError::ChecksumMismatch);
+        }
+
+        Ok(())
+    }
+
+    fn read_entry(&self, cursor: &mut usize) -> Result<PackEntry, PackError> {
+        let offset = self.start + *cursor;
+
+        let mut byte = self.read_byte(cursor)?;
+        let type_code = (byte >> 4) & 0x07;
+        let mut size = (byte & 0x0f) as usize;
+        let mut shift = 4;
+
+        while byte & 0x80 != 0 {
+            byte = self.read_byte(cursor)?;
+            size |= ((byte & 0x7f) as usize) << shift;
+            shift += 7;
+        }
+
+        let kind = match type_code {
+            OBJ_COMMIT => PackEntryKind::Base(ObjectType::Commit),
+            OBJ_TREE => PackEntryKind::Base(ObjectType::Tree),
+            OBJ_BLOB => PackEntryKind::Base(ObjectType::Blob),
+            OBJ_TAG => PackEntryKind::Base(ObjectType::Tag),
+            OBJ_OFS_DELTA => {
+                let relative_offset = self.read_base_offset(cursor)?;
+                let base_offset = offset.checked_sub(relative_offset).ok_or_else(|| {
+                    PackError::InvalidDelta(format!("base offset out of range at {}", offset))
+                })?;
+                PackEntryKind::OfsDelta(base_offset)
+            }
+            OBJ_REF_DELTA => {
+                let oid_bytes = self
+                    .data
+                    .get(*cursor..*cursor + 20)
+                    .ok_or(PackError::UnexpectedEof(*cursor))?;
+                *cursor += 20;
+                PackEntryKind::RefDelta(ObjectId::read_h40_from(&mut &oid_bytes[..])?)
+            }
+            other => return Err(PackError::InvalidObjectType(other)),
+        };
+
+        let data = self.inflate(cursor, size)?;
+
+        Ok(PackEntry { offset, kind, data })
+    }
+
+    /// Read the offset encoding used by `OFS_DELTA` entries
+    ///
+    /// Each continuation adds one before shifting, so that every offset has
+    /// exactly one encoding.
+    fn read_base_offset(&self, cursor: &mut usize) -> Result<usize, PackError> {
+        let mut byte = self.read_byte(cursor)?;
+        let mut offset = (byte & 0x7f) as usize;
+
+        while byte & 0x80 != 0 {
+            byte = self.read_byte(cursor)?;
+            offset = ((offset + 1) << 7) | (byte & 0x7f) as usize;
+        }
+
+        Ok(offset)
+    }
+
+    /// Decompress a zlib stream starting at the cursor, advancing past it
+    fn inflate(&self, cursor: &mut usize, size: usize) -> Result<Vec<u8>, PackError> {
+        let start = *cursor;
+        let mut decompressor = flate2::Decompress::new(true);
+        let mut output = Vec::with_capacity(size);
+
+        loop {
+            let input = &self.data[start + decompressor.total_in() as usize..];
+            let (total_in, total_out) = (decompressor.total_in(), decompressor.total_out());
+            let status = decompressor
+                .decompress_vec(input, &mut output, flate2::FlushDecompress::Finish)
+                .map_err(|_| PackError::Decompress(start))?;
+
+            match status {
+                flate2::Status::StreamEnd => break,
+                _ if output.len() == output.capacity() => output.reserve(size.max(64)),
+                _ if input.is_empty() => return Err(PackError::UnexpectedEof(start)),
+                // Neither input consumed nor output produced: retrying would spin
+                _ if decompressor.total_in() == total_in
+                    && decompressor.total_out() == total_out =>
+                {
+                    return Err(PackError::Decompress(start));
+                }
+                _ => {}
+            }
+        }
+
+        if output.len() != size {
+            return Err(PackError::Decompress(start));
+        }
+
+        *cursor = start + decompressor.total_in() as usize;
+        Ok(output)
+    }
+
+    fn read_byte(&self, cursor: &mut usize) -> Result<u8, PackError> {
+        let byte = *self
+            .data
+            .get(*cursor)
+            .ok_or(PackError::UnexpectedEof(*cursor))?;
+        *cursor += 1;
+        Ok(byte)
+    }
+
+    /// Apply the delta instructions of an entry to its base object
+    pub fn patch(base: &PackedObject, delta: &[u8]) -> Result<PackedObject, PackError> {
+        Ok(PackedObject {
+            object_type: base.object_type.clone(),
+            content: apply_delta(&base.content, delta)?,
+        })
+    }
+
+    fn hash_object(object: &PackedObject) -> Result<ObjectId, PackError> {
+        let mut hasher = Sha1::new();
+        hasher.update(format!("{} {}\0", object.object_type, object.content.len()));
+        hasher.update(&object.content);
+
+        Ok(ObjectId::try_parse(format!("{:x}", hasher.finalize()))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn build_pack(entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut pack = Vec::new();
+        pack.extend_from_slice(PACK_SIGNATURE);
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        pack.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+        for entry in entries {
+            pack.extend_from_slice(entry);
+        }
+        let checksum = Sha1::digest(&pack);
+        pack.extend_from_slice(checksum.as_slice());
+        pack
+    }
+
+    #[test]
+    fn test_read_objects_resolves_ofs_delta() {
+        let base_content = b"hello world";
+        let mut base_entry = vec![(OBJ_BLOB << 4) | base_content.len() as u8];
+        base_entry.extend(compress(base_content));
+
+        // copy "hello " then insert "there"
+        let delta = [11, 11, 0x90, 6, 5, b't', b'h', b'e', b'r', b'e'];
+        let mut delta_entry = vec![(OBJ_OFS_DELTA << 4) | delta.len() as u8];
+        delta_entry.push(base_entry.len() as u8);
+        delta_entry.extend(compress(&delta));
+
+        let pack = build_pack(&[base_entry, delta_entry]);
+        let objects = PackReader::new(&pack).read_objects(|_| None).unwrap();
+
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].content, b"hello world");
+        assert_eq!(objects[1].object_type, ObjectType::Blob);
+        assert_eq!(objects[1].content, b"hello there");
+    }
+
+    #[test]
+    fn test_read_entries_rejects_corrupt_checksum() {
+        let mut pack = build_pack(&[]);
+        let last = pack.len() - 1;
+        pack[last] ^= 0xff;
+
+        assert!(matches!(
+            PackReader::new(&pack).read_entries(),
+            Err(PackError::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_read_entries_rejects_invalid_signature() {
+        let mut pack = build_pack(&[]);
+        pack[0] = b'X';
+
+        assert!(matches!(
+            PackReader::new(&pack).read_entries(),
+            Err(PackError::InvalidSignature)
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+//! Packfile writer
+//!
+//! Streams full objects into a version 2 packfile along with its index.
+//! Objects are stored whole (no deltas): packing trades a little space for a
+//! single file per repack instead of one file per object.
+
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::objects::object_type::ObjectType;
+use crate::artifacts::pack::PACK_SIGNATURE;
+use crate::artifacts::pack::index::{PackIndex, PackIndexEntry};
+use crate::artifacts::pack::reader::{
+    OBJ_BLOB, OBJ_COMMIT, OBJ_TAG, OBJ_TREE, PackError, PackedObject,
+};
+use sha1::{Digest, Sha1};
+use std::io::Write;
+
+const PACK_VERSION: u32 = 2;
+
+/// Streams objects into a version 2 packfile, building its index on the way
+///
+/// Only one object is held in memory at a time: each entry is compressed and
+/// written out as soon as it is added, and the trailing checksum is computed
+/// over everything written so far.
+pub struct PackWriter<W: Write> {
+    out: W,
+    hasher: Sha1,
+    offset: u64,
+    remaining: u32,
+    entries: Vec<PackIndexEntry>,
+}
+
+impl<W: Write> PackWriter<W> {
+    /// Start a pack holding exactly `count` objects
+    pub fn new(out: W, count: u32) -> Result<Self, PackError> {
+        let mut writer = Self {
+            out,
+            hasher: Sha1::new(),
+            offset: 0,
+            remaining: count,
+            entries: Vec::with_capacity(count as usize),
+        };
+
+        let mut header = Vec::with_capacity(12);
+        header.extend_from_slice(PACK_SIGNATURE);
+        header.extend_from_slice(&PACK_VERSION.to_be_bytes());
+        header.extend_from_slice(&count.to_be_bytes());
+        writer.write_bytes(&header)?;
+
+        Ok(writer)
+    }
+
+    /// Append an object to the pack
+    pub fn write_object(&mut self, oid: ObjectId, object: &PackedObject) -> Result<(), PackError> {
+        if self.remaining == 0 {
+            return Err(PackError::ObjectCount);
+        }
+
+        let mut entry = Vec::new();
+        write_entry(&mut entry, object)?;
+
+        let mut crc = flate2::Crc::new();
+        crc.update(&entry);
+        self.entries.push(PackIndexEntry {
+            oid,
+            crc32: crc.sum(),
+            offset: self.offset,
+        });
+        self.write_bytes(&entry)?;
+        self.remaining -= 1;
+
+        Ok(())
+    }
+
+    /// Write the trailing checksum and return the index of the pack
+    pub fn finish(mut self) -> Result<PackIndex, PackError> {
+        if self.remaining != 0 {
+            return Err(PackError::ObjectCount);
+        }
+
+        let checksum = self.hasher.clone().finalize().to_vec();
+        self.out.write_all(&checksum).map_err(PackError::Write)?;
+        self.out.flush().map_err(PackError::Write)?;
+
+        Ok(PackIndex::new(self.entries, checksum))
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), PackError> {
+        self.out.write_all(bytes).map_err(PackError::Write)?;
+        self.hasher.update(bytes);
+        self.offset += bytes.len() as u64;
+
+        Ok(())
+    }
+}
+
+/// Append the entry header (type and size varint) and the compressed content
+fn write_entry(pack: &mut Vec<u8>, object: &PackedObject) -> Result<(), PackError> {
+    let type_code = match object.object_type {
+        ObjectType::Commit => OBJ_COMMIT,
+        ObjectType::Tree => OBJ_TREE,
+        ObjectType::Blob => OBJ_BLOB,
+        ObjectType::Tag => OBJ_TAG,
+    };
+
+    let mut size = object.content.len();
+    let mut byte = (type_code << 4) | (size & 0x0f) as u8;
+    size >>= 4;
+    while size > 0 {
+        pack.push(byte | 0x80);
+        byte = (size & 0x7f) as u8;
+        size >>= 7;
+    }
+    pack.push(byte);
+
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder
+        .write_all(&object.content)
+        .and_then(|_| encoder.finish())
+        .map(|compressed| pack.extend_from_slice(&compressed))
+        .map_err(PackError::Compress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::pack::reader::{PackEntryKind, PackReader};
+    use pretty_assertions::assert_eq;
+
+    fn blob(content: &[u8]) -> (ObjectId, PackedObject) {
+        let mut hasher = Sha1::new();
+        hasher.update(format!("blob {}\0", content.len()));
+        hasher.update(content);
+        let oid = ObjectId::try_parse(format!("{:x}", hasher.finalize())).unwrap();
+
+        (
+            oid,
+            PackedObject {
+                object_type: ObjectType::Blob,
+                content: content.to_vec(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_written_pack_reads_back() {
+        let objects = vec![blob(b"small"), blob(&[b'x'; 300])];
+
+        let mut pack = Vec::new();
+        let mut writer = PackWriter::new(&mut pack, objects.len() as u32).unwrap();
+        for (oid, object) in &objects {
+            writer.write_object(oid.clone(), object).unwrap();
+        }
+        let index = writer.finish().unwrap();
+        let reader = PackReader::new(&pack);
+        let read = reader.read_objects(|_| None).unwrap();
+
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[1].content, objects[1].1.content);
+        for (oid, object) in &objects {
+            let offset = index.offset_of(oid).unwrap();
+            let entry = reader.read_entry_at(offset as usize).unwrap();
+            assert_eq!(entry.kind, PackEntryKind::Base(ObjectType::Blob));
+            assert_eq!(entry.data, object.content);
+        }
+    }
+
+    #[test]
+    fn test_pack_must_hold_the_announced_object_count() {
+        let (oid, object) = blob(b"small");
+
+        let writer = PackWriter::new(Vec::new(), 2).unwrap();
+        assert!(matches!(writer.finish(), Err(PackError::ObjectCount)));
+
+        let mut writer = PackWriter::new(Vec::new(), 0).unwrap();
+        assert!(matches!(
+            writer.write_object(oid, &object),
+            Err(PackError::ObjectCount)
+        ));
+    }
+}
@@ -92,6 +92,7 @@ pub enum IndexChangeType {
     Added,
     Modified,
     Deleted,
+    Renamed,
 }
 
 impl From<&IndexChangeType> for &str {
@@ -101,6 +102,7 @@ impl From<&IndexChangeType> for &str {
             IndexChangeType::Added => "A",
             IndexChangeType::Modified => "M",
             IndexChangeType::Deleted => "D",
+            IndexChangeType::Renamed => "R",
         }
     }
 }
@@ -125,6 +127,7 @@ impl From<&FileChangeType> for &str {
                 IndexChangeType::Added => "new file:   ",
                 IndexChangeType::Modified => "modified:   ",
                 IndexChangeType::Deleted => "deleted:   ",
+                IndexChangeType::Renamed => "renamed:    ",
             },
         }
     }
@@ -144,6 +147,7 @@ impl std::fmt::Display for FileChangeType {
                 IndexChangeType::Added => "new file:   ".green(),
                 IndexChangeType::Modified => "modified:   ".green(),
                 IndexChangeType::Deleted => "deleted:    ".green(),
+                IndexChangeType::Renamed => "renamed:    ".green(),
             },
         };
         write!(f, "{:>width$}{}", "", colored_str, width = LABEL_WIDTH)
@@ -8,16 +8,19 @@
 //!
 //! 1. First check file metadata (size, mtime) for quick detection
 //! 2. If metadata changed, compute content hash for accurate detection
-//! 3. Handle special cases (untracked files, deletions, etc.)
+//! 3. If metadata matches but cannot be trusted, because the entry is racy
+//!    (modified no earlier than the index was written) or its size was
+//!    zeroed on write, compute content hash as well
+//! 4. Handle special cases (untracked files, deletions, etc.)
 //!
 //! This mimics Git's approach of using stat() for performance while
 //! falling back to full content comparison when necessary.
 
+use crate::areas::database::Database;
 use crate::areas::index::Index;
 use crate::areas::repository::Repository;
 use crate::artifacts::database::database_entry::DatabaseEntry;
 use crate::artifacts::index::index_entry::{EntryMetadata, IndexEntry};
-use crate::artifacts::objects::object::Object;
 use crate::artifacts::status::file_change::{IndexChangeType, WorkspaceChangeType};
 use derive_new::new;
 use std::path::Path;
@@ -33,13 +36,14 @@ pub struct Inspector<'r> {
 
 impl<'r> Inspector<'r> {
     pub fn is_indirectly_tracked(&self, path: &Path, index: &Index) -> anyhow::Result<bool> {
-        if path.is_file() {
+        let workspace = self.repository.workspace();
+        if workspace.is_file(path) {
             return Ok(index.is_directly_tracked(path));
         }
 
-        let paths = self.repository.workspace().list_dir(Some(path))?;
-        let files = paths.iter().filter(|p| p.is_file());
-        let dirs = paths.iter().filter(|p| p.is_dir());
+        let paths = workspace.list_dir(Some(path))?;
+        let files = paths.iter().filter(|p| workspace.is_file(p));
+        let dirs = paths.iter().filter(|p| workspace.is_dir(p));
 
         let mut paths = files.chain(dirs);
 
@@ -52,8 +56,11 @@ impl<'r> Inspector<'r> {
     }
 
     fn is_content_changed(&self, index_entry: &IndexEntry) -> anyhow::Result<bool> {
-        let blob = self.repository.workspace().parse_blob(&index_entry.name)?;
-        let oid = blob.object_id()?;
+        let content = self
+            .repository
+            .workspace()
+            .read_file_bytes(&index_entry.name)?;
+        let oid = Database::blob_id(&content)?;
 
         Ok(oid != index_entry.oid)
     }
@@ -62,14 +69,25 @@ impl<'r> Inspector<'r> {
         &self,
         entry: Option<&IndexEntry>,
         stat: Option<&EntryMetadata>,
+        index: &Index,
     ) -> anyhow::Result<WorkspaceChangeType> {
         match (entry, stat) {
             (None, _) => Ok(WorkspaceChangeType::Untracked),
+            // Assume-unchanged and skip-worktree entries are never compared to the workspace
+            (Some(entry), _) if entry.skips_workspace_check() => Ok(WorkspaceChangeType::None),
             (Some(_), None) => Ok(WorkspaceChangeType::Deleted),
+            // A size zeroed on write proves nothing, only the content tells
+            (Some(entry), Some(stat)) if Self::is_smudged(entry, stat) => {
+                if self.is_content_changed(entry)? {
+                    Ok(WorkspaceChangeType::Modified)
+                } else {
+                    Ok(WorkspaceChangeType::None)
+                }
+            }
             (Some(entry), Some(stat)) if !entry.stat_match(stat) => {
                 Ok(WorkspaceChangeType::Modified)
             }
-            (Some(entry), Some(stat)) if entry.stat_match(stat) && entry.times_match(stat) => {
+            (Some(entry), Some(stat)) if entry.times_match(stat) && !index.is_racy(entry) => {
                 Ok(WorkspaceChangeType::None)
             }
             (Some(entry), Some(_)) if self.is_content_changed(entry)? => {
@@ -79,6 +97,11 @@ impl<'r> Inspector<'r> {
         }
     }
 
+    /// Whether the entry size was zeroed on write although the file is not empty
+    fn is_smudged(entry: &IndexEntry, stat: &EntryMetadata) -> bool {
+        entry.metadata.size == 0 && stat.size != 0
+    }
+
     pub fn check_index_against_head_tree(
         &self,
         index_entry: Option<&IndexEntry>,
@@ -7,8 +7,12 @@
 //!
 //! - `file_change`: Enum types for categorizing changes
 //! - `inspector`: Core logic for detecting changes
+//! - `porcelain`: Machine-readable `--porcelain` formats
 //! - `status_info`: Status information aggregation and display
+//! - `status_report`: Changes grouped as staged, unstaged, untracked and conflicted
 
 pub mod file_change;
 pub mod inspector;
+pub mod porcelain;
 pub mod status_info;
+pub mod status_report;
@@ -0,0 +1,202 @@
+//! Machine-readable status formats
+//!
+//! - `v1`: `XY <path>`, `XY <old> -> <new>` for renames, `?? <path>`
+//! - `v2`: a `# branch.*` header block, then one line per entry:
+//!
+//! ```text
+//! 1 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <path>
+//! 2 <XY> <sub> <mH> <mI> <mW> <hH> <hI> R<score> <path>\t<origPath>
+//! u <XY> <sub> <m1> <m2> <m3> <mW> <h1> <h2> <h3> <path>
+//! ? <path>
+//! ```
+//!
+//! `m*` are octal modes and `h*` object IDs in HEAD (`H`), the index (`I`,
+//! or stages `1` to `3`) and the workspace (`W`). Sides where the path does
+//! not exist get an all-zero mode and object ID; unchanged sides of `XY`
+//! are shown as `.`.
+
+use crate::artifacts::database::database_entry::DatabaseEntry;
+use crate::artifacts::index::entry_mode::EntryMode;
+use crate::artifacts::status::file_change::FileChange;
+use clap::ValueEnum;
+
+/// Submodule state field; submodules are never inspected
+const NOT_A_SUBMODULE: &str = "N...";
+const NULL_MODE: &str = "000000";
+const NULL_OID: &str = "0000000000000000000000000000000000000000";
+
+/// Version of the `--porcelain` format
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum PorcelainVersion {
+    /// Two-letter status codes followed by the path
+    #[default]
+    V1,
+    /// Modes and object IDs of each side, after a branch header
+    V2,
+}
+
+/// `XY` code of a change, with `.` for unchanged sides
+pub fn change_code(change: &FileChange) -> String {
+    let index: &str = (&change.index_change).into();
+    let workspace: &str = (&change.workspace_change).into();
+
+    format!("{}{}", index, workspace).replace(' ', ".")
+}
+
+/// Line of an entry changed in the index or the workspace
+pub fn ordinary_line(
+    code: &str,
+    head: Option<&DatabaseEntry>,
+    index: Option<&DatabaseEntry>,
+    workspace_mode: Option<&EntryMode>,
+    path: &str,
+) -> String {
+    format!(
+        "1 {} {} {} {} {} {} {} {}",
+        code,
+        NOT_A_SUBMODULE,
+        mode(head),
+        mode(index),
+        workspace_mode.map_or(NULL_MODE, EntryMode::as_str),
+        oid(head),
+        oid(index),
+        path
+    )
+}
+
+/// Line of a staged rename from `old_path`, whose content is `score`
+/// percent similar
+pub fn renamed_line(
+    code: &str,
+    head: Option<&DatabaseEntry>,
+    index: Option<&DatabaseEntry>,
+    workspace_mode: Option<&EntryMode>,
+    score: u8,
+    path: &str,
+    old_path: &str,
+) -> String {
+    format!(
+        "2 {} {} {} {} {} {} {} R{} {}\t{}",
+        code,
+        NOT_A_SUBMODULE,
+        mode(head),
+        mode(index),
+        workspace_mode.map_or(NULL_MODE, EntryMode::as_str),
+        oid(head),
+        oid(index),
+        score,
+        path,
+        old_path
+    )
+}
+
+/// Line of an unmerged entry, given its base, ours and theirs stages
+pub fn unmerged_line(
+    code: &str,
+    stages: [Option<&DatabaseEntry>; 3],
+    workspace_mode: Option<&EntryMode>,
+    path: &str,
+) -> String {
+    let [base, ours, theirs] = stages;
+
+    format!(
+        "u {} {} {} {} {} {} {} {} {} {}",
+        code,
+        NOT_A_SUBMODULE,
+        mode(base),
+        mode(ours),
+        mode(theirs),
+        workspace_mode.map_or(NULL_MODE, EntryMode::as_str),
+        oid(base),
+        oid(ours),
+        oid(theirs),
+        path
+    )
+}
+
+fn mode(entry: Option<&DatabaseEntry>) -> &str {
+    entry.map_or(NULL_MODE, |entry| entry.mode.as_str())
+}
+
+fn oid(entry: Option<&DatabaseEntry>) -> &str {
+    entry.map_or(NULL_OID, |entry| entry.oid.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::index::entry_mode::FileMode;
+    use crate::artifacts::objects::object_id::ObjectId;
+    use crate::artifacts::status::file_change::{IndexChangeType, WorkspaceChangeType};
+    use pretty_assertions::assert_eq;
+
+    fn entry(digit: char) -> DatabaseEntry {
+        DatabaseEntry::new(
+            ObjectId::try_parse(digit.to_string().repeat(40)).unwrap(),
+            EntryMode::File(FileMode::Regular),
+        )
+    }
+
+    #[test]
+    fn test_change_code_marks_unchanged_sides_with_dots() {
+        let staged = FileChange {
+            index_change: IndexChangeType::Added,
+            workspace_change: WorkspaceChangeType::None,
+        };
+        let unstaged = FileChange {
+            index_change: IndexChangeType::None,
+            workspace_change: WorkspaceChangeType::Deleted,
+        };
+
+        assert_eq!(change_code(&staged), "A.");
+        assert_eq!(change_code(&unstaged), ".D");
+    }
+
+    #[test]
+    fn test_ordinary_line_zeroes_missing_sides() {
+        let added = entry('1');
+
+        assert_eq!(
+            ordinary_line("A.", None, Some(&added), Some(&added.mode), "new.txt"),
+            format!(
+                "1 A. N... 000000 100644 100644 {} {} new.txt",
+                NULL_OID,
+                "1".repeat(40)
+            )
+        );
+    }
+
+    #[test]
+    fn test_renamed_line_tab_separates_original_path() {
+        let (head, index) = (entry('1'), entry('2'));
+
+        assert_eq!(
+            renamed_line("R.", Some(&head), Some(&index), None, 90, "b.txt", "a.txt"),
+            format!(
+                "2 R. N... 100644 100644 000000 {} {} R90 b.txt\ta.txt",
+                "1".repeat(40),
+                "2".repeat(40)
+            )
+        );
+    }
+
+    #[test]
+    fn test_unmerged_line_lists_every_stage() {
+        let (ours, theirs) = (entry('2'), entry('3'));
+
+        assert_eq!(
+            unmerged_line(
+                "AA",
+                [None, Some(&ours), Some(&theirs)],
+                Some(&ours.mode),
+                "f.txt"
+            ),
+            format!(
+                "u AA N... 000000 100644 100644 100644 {} {} {} f.txt",
+                NULL_OID,
+                "2".repeat(40),
+                "3".repeat(40)
+            )
+        );
+    }
+}
@@ -1,6 +1,11 @@
 use crate::areas::index::Index;
 use crate::areas::repository::Repository;
+use crate::areas::workspace::WalkEntry;
+use crate::artifacts::core::ignore::IgnoreFiles;
+use crate::artifacts::core::pathspec::Pathspec;
 use crate::artifacts::database::database_entry::DatabaseEntry;
+use crate::artifacts::diff::rename_detection::{RenameOptions, detect_renames};
+use crate::artifacts::diff::tree_diff::TreeChangeType;
 use crate::artifacts::index::index_entry::{EntryMetadata, IndexEntry, MergeStage};
 use crate::artifacts::status::file_change::{
     FileChange, FileChangeType, IndexChangeType, WorkspaceChangeType,
@@ -16,29 +21,70 @@ pub type ChangeSet = BTreeMap<PathBuf, FileChangeType>;
 pub type FileSet = BTreeSet<PathBuf>;
 pub type HeadTree = BTreeMap<PathBuf, DatabaseEntry>;
 pub type ConflictSet = BTreeMap<PathBuf, BTreeSet<MergeStage>>;
+pub type IndexEntrySet = BTreeMap<(PathBuf, MergeStage), DatabaseEntry>;
 
 #[derive(Debug, Clone)]
 pub struct StatusInfo {
     pub(crate) file_stats: FileStatSet,
     pub(crate) untracked_files: FileSet,
+    /// Untracked paths matched by ignore rules; directories end with `/`.
+    pub(crate) ignored_files: FileSet,
     pub(crate) changed_files: BTreeMap<PathBuf, FileChange>,
     pub(crate) untracked_changeset: ChangeSet,
     pub(crate) workspace_changeset: ChangeSet,
     pub(crate) index_changeset: ChangeSet,
     pub(crate) head_tree: HeadTree,
+    /// Index entries, by stage, of the changed and conflicted paths.
+    pub(crate) index_entries: IndexEntrySet,
     /// Paths with unresolved merge conflicts mapped to the set of stages present.
     pub(crate) conflicts: ConflictSet,
+    /// Staged renames: the new path mapped to the path it was renamed from.
+    pub(crate) renames: BTreeMap<PathBuf, PathBuf>,
+    /// Similarity (percent) of each staged rename, keyed by the new path.
+    pub(crate) rename_scores: BTreeMap<PathBuf, u8>,
+}
+
+impl StatusInfo {
+    /// Drop the changes to paths `pathspec` does not select
+    ///
+    /// Renames are kept or dropped along with their new path.
+    pub fn retain_paths(&mut self, pathspec: &Pathspec) {
+        self.untracked_files.retain(|path| pathspec.matches(path));
+        self.ignored_files.retain(|path| pathspec.matches(path));
+        self.changed_files.retain(|path, _| pathspec.matches(path));
+        for changeset in [
+            &mut self.untracked_changeset,
+            &mut self.workspace_changeset,
+            &mut self.index_changeset,
+        ] {
+            changeset.retain(|path, _| pathspec.matches(path));
+        }
+        self.conflicts.retain(|path, _| pathspec.matches(path));
+        self.index_entries
+            .retain(|(path, _), _| pathspec.matches(path));
+        self.renames.retain(|path, _| pathspec.matches(path));
+        self.rename_scores.retain(|path, _| pathspec.matches(path));
+    }
 }
 
 #[derive(new)]
 pub struct Status<'r> {
     repository: &'r Repository,
+    #[new(default)]
+    renames: Option<RenameOptions>,
 }
 
 impl<'r> Status<'r> {
+    /// Pair staged deletions with staged additions of similar content
+    pub fn with_renames(mut self, renames: Option<RenameOptions>) -> Self {
+        self.renames = renames;
+        self
+    }
+
     pub async fn initialize(&self, index: &mut Index) -> anyhow::Result<StatusInfo> {
         let mut file_stats = BTreeMap::<PathBuf, EntryMetadata>::new();
         let mut untracked_files = BTreeSet::<PathBuf>::new();
+        let mut ignored_files = BTreeSet::<PathBuf>::new();
 
         let inspector = Inspector::new(self.repository);
 
@@ -54,17 +100,34 @@ impl<'r> Status<'r> {
         }
 
         self.scan_workspace(
-            None,
             &mut untracked_files,
+            &mut ignored_files,
             &mut file_stats,
             index,
-            &inspector,
-        )
-        .await?;
+        )?;
         let head_tree = self.load_head_tree().await?;
         let mut changed_files =
             self.check_index_entries(&file_stats, &head_tree, index, &inspector)?;
         self.collect_deleted_head_files(&head_tree, index, &mut changed_files);
+        let (renames, rename_scores) = match self.renames {
+            Some(options) => {
+                self.detect_index_renames(&head_tree, index, options, &mut changed_files)?
+            }
+            None => (BTreeMap::new(), BTreeMap::new()),
+        };
+
+        let index_entries = index
+            .entries()
+            .filter(|entry| {
+                changed_files.contains_key(&entry.name) || conflicts.contains_key(&entry.name)
+            })
+            .map(|entry| {
+                (
+                    (entry.name.clone(), entry.stage),
+                    DatabaseEntry::new(entry.oid.clone(), entry.metadata.mode),
+                )
+            })
+            .collect();
 
         let untracked_changeset = untracked_files
             .iter()
@@ -99,64 +162,168 @@ impl<'r> Status<'r> {
         Ok(StatusInfo {
             file_stats,
             untracked_files,
+            ignored_files,
             changed_files,
             untracked_changeset,
             workspace_changeset,
             index_changeset,
             head_tree,
+            index_entries,
             conflicts,
+            renames,
+            rename_scores,
         })
     }
 
-    async fn scan_workspace(
+    /// Stat the tracked files and sort the other paths into untracked and
+    /// ignored ones, walking the workspace once
+    ///
+    /// Directories holding no tracked file are not walked through: each is
+    /// reported as a whole by `scan_untracked_dir`.
+    fn scan_workspace(
         &self,
-        prefix_path: Option<&Path>,
         untracked_files: &mut BTreeSet<PathBuf>,
+        ignored_files: &mut BTreeSet<PathBuf>,
         file_stats: &mut BTreeMap<PathBuf, EntryMetadata>,
         index: &Index,
-        inspector: &Inspector<'_>,
     ) -> anyhow::Result<()> {
-        let files = self.repository.workspace().list_dir(prefix_path)?;
-
-        for path in files.iter() {
-            if index.is_directly_tracked(path) {
-                if path.is_dir() {
-                    Box::pin(self.scan_workspace(
-                        Some(path),
-                        untracked_files,
-                        file_stats,
-                        index,
-                        inspector,
-                    ))
-                    .await?;
-                } else {
-                    let stat = self.repository.workspace().stat_file(path)?;
-                    file_stats.insert(path.clone(), stat);
+        let workspace = self.repository.workspace();
+        let ignores = IgnoreFiles::load(self.repository.path())?;
+
+        let untracked_dir = |dir: &Path| self.tracked_path(dir, index).is_none();
+        for entry in workspace.walk_pruning(None, Some(&ignores), untracked_dir)? {
+            match entry? {
+                WalkEntry::File(path, stat) => match self.tracked_file(&path, index) {
+                    Some(path) => {
+                        file_stats.insert(path, stat);
+                    }
+                    None => {
+                        untracked_files.insert(self.untracked_unit(&path, false, index));
+                    }
+                },
+                WalkEntry::Dir(path) if untracked_dir(&path) => {
+                    self.scan_untracked_dir(&path, &ignores, untracked_files, ignored_files)?;
                 }
-            } else if index.is_conflicted_path(path) {
-                // Conflicted files have no stage-0 entry, but their workspace content (which
-                // contains conflict markers) must be stat'd so that `diff --ours/--theirs/--base`
-                // can read the file for comparison.
-                if !path.is_dir() {
-                    let stat = self.repository.workspace().stat_file(path)?;
-                    file_stats.insert(path.clone(), stat);
+                WalkEntry::Dir(_) => {}
+                // Ignore rules never apply to tracked files
+                WalkEntry::Ignored {
+                    path,
+                    is_dir: false,
+                } => match self.tracked_file(&path, index) {
+                    Some(path) => {
+                        let stat = workspace.stat_file(&path)?;
+                        file_stats.insert(path, stat);
+                    }
+                    None => {
+                        ignored_files.insert(self.untracked_unit(&path, false, index));
+                    }
+                },
+                // Everything untracked below an ignored directory is ignored,
+                // but its tracked files are still looked at
+                WalkEntry::Ignored { path, is_dir: true }
+                    if self.tracked_path(&path, index).is_some() =>
+                {
+                    for entry in workspace.walk(Some(workspace.path().join(&path)), None)? {
+                        if let WalkEntry::File(path, stat) = entry? {
+                            match self.tracked_file(&path, index) {
+                                Some(path) => {
+                                    file_stats.insert(path, stat);
+                                }
+                                None => {
+                                    ignored_files.insert(self.untracked_unit(&path, false, index));
+                                }
+                            }
+                        }
+                    }
+                }
+                WalkEntry::Ignored { path, is_dir: true } => {
+                    ignored_files.insert(self.untracked_unit(&path, true, index));
                 }
-            } else if !inspector.is_indirectly_tracked(path, index)? {
-                // add the file separator if it's a directory
-                let path = if path.is_dir() {
-                    let mut p = path.clone();
-                    p.push("");
-                    p
-                } else {
-                    path.clone()
-                };
-                untracked_files.insert(path);
             }
         }
 
+        // A directory holding a file no ignore rule matches is untracked
+        ignored_files.retain(|path| !untracked_files.contains(path));
+
         Ok(())
     }
 
+    /// Report an untracked directory as `dir/`: untracked when it holds a
+    /// file no ignore rule matches, which ends the scan, ignored when it holds
+    /// nothing but ignored files, and not at all when it holds no file
+    fn scan_untracked_dir(
+        &self,
+        path: &Path,
+        ignores: &IgnoreFiles,
+        untracked_files: &mut BTreeSet<PathBuf>,
+        ignored_files: &mut BTreeSet<PathBuf>,
+    ) -> anyhow::Result<()> {
+        let workspace = self.repository.workspace();
+        let mut holds_ignored = false;
+
+        for entry in workspace.walk(Some(workspace.path().join(path)), Some(ignores))? {
+            match entry? {
+                WalkEntry::File(..) => {
+                    untracked_files.insert(path.join(""));
+                    return Ok(());
+                }
+                WalkEntry::Ignored { .. } => holds_ignored = true,
+                WalkEntry::Dir(_) => {}
+            }
+        }
+        if holds_ignored {
+            ignored_files.insert(path.join(""));
+        }
+
+        Ok(())
+    }
+
+    /// How the index names a workspace file, if it is tracked or conflicted
+    ///
+    /// Conflicted files have no stage-0 entry, but their workspace content (which
+    /// contains conflict markers) must be stat'd so that `diff --ours/--theirs/--base`
+    /// can read the file for comparison.
+    fn tracked_file(&self, path: &Path, index: &Index) -> Option<PathBuf> {
+        self.tracked_path(path, index)
+            .or_else(|| index.is_conflicted_path(path).then(|| path.to_path_buf()))
+    }
+
+    /// The path status reports for an untracked file or directory
+    ///
+    /// That is the topmost directory above it holding no tracked file, with
+    /// the file separator added, or the path itself when every directory
+    /// above it is tracked.
+    fn untracked_unit(&self, path: &Path, is_dir: bool, index: &Index) -> PathBuf {
+        let mut dirs = path
+            .ancestors()
+            .skip(usize::from(!is_dir))
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .collect::<Vec<_>>();
+        dirs.reverse();
+
+        match dirs
+            .into_iter()
+            .find(|dir| self.tracked_path(dir, index).is_none())
+        {
+            Some(dir) => dir.join(""),
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// How the index spells a workspace path, if the path is tracked
+    ///
+    /// With `core.ignorecase`, a file whose case differs from its index entry
+    /// is still that entry rather than an untracked file next to a deleted one.
+    fn tracked_path(&self, path: &Path, index: &Index) -> Option<PathBuf> {
+        if index.is_directly_tracked(path) {
+            Some(path.to_path_buf())
+        } else if self.repository.workspace().ignore_case() {
+            index.find_path_ignoring_case(path)
+        } else {
+            None
+        }
+    }
+
     async fn load_head_tree(&self) -> anyhow::Result<BTreeMap<PathBuf, DatabaseEntry>> {
         let mut head_tree = BTreeMap::<PathBuf, DatabaseEntry>::new();
 
@@ -168,7 +335,7 @@ impl<'r> Status<'r> {
 
             if let Some(commit) = commit {
                 self.repository
-                    .parse_tree(commit.tree_oid(), None, &mut head_tree, false)
+                    .parse_tree(commit.tree_oid(), &mut head_tree)
                     .await?;
             }
         }
@@ -218,11 +385,13 @@ impl<'r> Status<'r> {
         changed_files: &mut BTreeMap<PathBuf, FileChange>,
     ) -> anyhow::Result<()> {
         let stat = file_stats.get(&index_entry.name);
-        let status = inspector.check_index_against_workspace(Some(index_entry), stat)?;
+        let status = inspector.check_index_against_workspace(Some(index_entry), stat, index)?;
 
         if status != WorkspaceChangeType::None {
             self.record_workspace_change(index_entry.name.clone(), status, changed_files);
-        } else if let Some(stat) = stat {
+        } else if let Some(stat) = stat
+            && !index_entry.skips_workspace_check()
+        {
             index.update_entry_stat(index_entry, stat.clone());
         }
 
@@ -282,4 +451,54 @@ impl<'r> Status<'r> {
             }
         });
     }
+
+    /// Turn staged delete+add pairs into renames
+    ///
+    /// The added path is recorded as renamed and the deleted path is dropped
+    /// from `changed_files`. Returns the old path and the similarity of each
+    /// rename, keyed by the new path.
+    fn detect_index_renames(
+        &self,
+        head_tree: &BTreeMap<PathBuf, DatabaseEntry>,
+        index: &Index,
+        options: RenameOptions,
+        changed_files: &mut BTreeMap<PathBuf, FileChange>,
+    ) -> anyhow::Result<(BTreeMap<PathBuf, PathBuf>, BTreeMap<PathBuf, u8>)> {
+        let mut changes = BTreeMap::new();
+        for (path, change) in changed_files.iter() {
+            let tree_change = match change.index_change {
+                IndexChangeType::Added => index.entry_by_path(path).map(|entry| {
+                    TreeChangeType::Added(DatabaseEntry::new(
+                        entry.oid.clone(),
+                        entry.metadata.mode,
+                    ))
+                }),
+                IndexChangeType::Deleted => {
+                    head_tree.get(path).cloned().map(TreeChangeType::Deleted)
+                }
+                _ => None,
+            };
+            if let Some(tree_change) = tree_change {
+                changes.insert(path.clone(), tree_change);
+            }
+        }
+
+        let detection = detect_renames(&changes, options, |oid| {
+            self.repository.load_blob_lines(oid)
+        })?;
+
+        let mut renames = BTreeMap::new();
+        let mut scores = BTreeMap::new();
+        for pair in detection.pairs {
+            changed_files.remove(&pair.old_path);
+            changed_files
+                .entry(pair.new_path.clone())
+                .or_default()
+                .index_change = IndexChangeType::Renamed;
+            scores.insert(pair.new_path.clone(), pair.similarity);
+            renames.insert(pair.new_path, pair.old_path);
+        }
+
+        Ok((renames, scores))
+    }
 }
@@ -0,0 +1,120 @@
+//! Structured status results
+//!
+//! A `StatusReport` sorts the changes found by a status scan by where they
+//! live: staged (HEAD vs. index), unstaged (index vs. workspace), untracked,
+//! ignored and conflicted. It is what `Repository::status` returns, and every status
+//! format is rendered from it: along with the changes, it keeps the HEAD,
+//! index and workspace entries of the paths it reports.
+
+use crate::artifacts::database::database_entry::DatabaseEntry;
+use crate::artifacts::index::entry_mode::EntryMode;
+use crate::artifacts::index::index_entry::MergeStage;
+use crate::artifacts::status::file_change::{
+    ConflictType, FileChange, FileChangeType, IndexChangeType, WorkspaceChangeType,
+};
+use crate::artifacts::status::status_info::{IndexEntrySet, StatusInfo};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Categorized result of a status scan
+#[derive(Debug, Clone)]
+pub struct StatusReport {
+    /// Changes staged in the index, relative to HEAD
+    pub staged: BTreeMap<PathBuf, IndexChangeType>,
+    /// Changes in the workspace not staged in the index
+    pub unstaged: BTreeMap<PathBuf, WorkspaceChangeType>,
+    /// Files the index does not track; untracked directories end with `/`
+    pub untracked: BTreeSet<PathBuf>,
+    /// Untracked paths matched by `.gitignore` rules, kept apart from
+    /// `untracked`; ignored directories end with `/`
+    pub ignored: BTreeSet<PathBuf>,
+    /// Paths with unresolved merge conflicts
+    pub conflicted: BTreeMap<PathBuf, ConflictType>,
+    /// Staged renames: the new path mapped to the path it was renamed from
+    pub renames: BTreeMap<PathBuf, PathBuf>,
+    /// Similarity (percent) of each staged rename, keyed by the new path
+    pub rename_scores: BTreeMap<PathBuf, u8>,
+    /// HEAD entries of the changed and conflicted paths, and of the paths
+    /// they were renamed from
+    pub head_entries: BTreeMap<PathBuf, DatabaseEntry>,
+    /// Index entries of the changed and conflicted paths, by stage
+    pub index_entries: IndexEntrySet,
+    /// Workspace modes of the changed and conflicted paths still on disk
+    pub workspace_modes: BTreeMap<PathBuf, EntryMode>,
+}
+
+impl StatusReport {
+    /// Staged and unstaged changes of every tracked path, side by side
+    pub fn changes(&self) -> BTreeMap<&PathBuf, FileChange> {
+        let mut changes = BTreeMap::<&PathBuf, FileChange>::new();
+        for (path, change) in &self.staged {
+            changes.entry(path).or_default().index_change = change.clone();
+        }
+        for (path, change) in &self.unstaged {
+            changes.entry(path).or_default().workspace_change = change.clone();
+        }
+
+        changes
+    }
+
+    /// The entry of `path` at `stage` in the index, if any
+    pub fn index_entry(&self, path: &Path, stage: MergeStage) -> Option<&DatabaseEntry> {
+        self.index_entries.get(&(path.to_path_buf(), stage))
+    }
+}
+
+impl From<StatusInfo> for StatusReport {
+    fn from(info: StatusInfo) -> Self {
+        let staged = info
+            .index_changeset
+            .iter()
+            .filter_map(|(path, change)| match change {
+                FileChangeType::Index(change) => Some((path.clone(), change.clone())),
+                FileChangeType::Workspace(_) => None,
+            })
+            .collect();
+        let unstaged = info
+            .workspace_changeset
+            .iter()
+            .filter_map(|(path, change)| match change {
+                FileChangeType::Workspace(change) => Some((path.clone(), change.clone())),
+                FileChangeType::Index(_) => None,
+            })
+            .collect();
+        let conflicted = info
+            .conflicts
+            .iter()
+            .map(|(path, stages)| (path.clone(), ConflictType::from_stages(stages)))
+            .collect();
+
+        let reported = |path: &PathBuf| {
+            info.changed_files.contains_key(path) || info.conflicts.contains_key(path)
+        };
+        let renamed_from = info.renames.values().collect::<BTreeSet<_>>();
+        let head_entries = info
+            .head_tree
+            .iter()
+            .filter(|(path, _)| reported(path) || renamed_from.contains(path))
+            .map(|(path, entry)| (path.clone(), entry.clone()))
+            .collect();
+        let workspace_modes = info
+            .file_stats
+            .iter()
+            .filter(|(path, _)| reported(path))
+            .map(|(path, stat)| (path.clone(), stat.mode))
+            .collect();
+
+        Self {
+            staged,
+            unstaged,
+            untracked: info.untracked_files,
+            ignored: info.ignored_files,
+            conflicted,
+            renames: info.renames,
+            rename_scores: info.rename_scores,
+            head_entries,
+            index_entries: info.index_entries,
+            workspace_modes,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::areas::repository::Repository;
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::objects::object_type::ObjectType;
+use crate::artifacts::objects::tree::TreeWalker;
+
+/// What `bit cat-file` prints about an object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatFileMode {
+    /// The object type (`-t`)
+    Type,
+    /// The content size in bytes (`-s`)
+    Size,
+    /// The content, with trees listed one entry per line (`-p`)
+    Pretty,
+}
+
+impl Repository {
+    /// Print the type or size of an object, read from its header only, or
+    /// its content
+    ///
+    /// With `allow_unknown_type`, the type is printed as written in the
+    /// header even if it is none of the known object types.
+    pub fn cat_file(
+        &self,
+        object: &str,
+        mode: CatFileMode,
+        allow_unknown_type: bool,
+    ) -> anyhow::Result<()> {
+        let oid = self.resolve_object(object)?;
+        let read_header = || -> anyhow::Result<(String, usize)> {
+            if allow_unknown_type {
+                Ok(self.database().read_raw_object_header(&oid)?)
+            } else {
+                let (object_type, size) = self.database().read_object_header(&oid)?;
+                Ok((object_type.to_string(), size))
+            }
+        };
+
+        match mode {
+            CatFileMode::Type => writeln!(self.writer(), "{}", read_header()?.0)?,
+            CatFileMode::Size => writeln!(self.writer(), "{}", read_header()?.1)?,
+            CatFileMode::Pretty => self.pretty_print_object(&oid)?,
+        }
+
+        Ok(())
+    }
+
+    /// Print a tree as `<mode> <type> <oid>\t<name>` lines, like `git
+    /// cat-file -p`, and any other object as stored
+    fn pretty_print_object(&self, oid: &ObjectId) -> anyhow::Result<()> {
+        let (object_type, content) = self.database().load_raw(oid)?;
+        if object_type != ObjectType::Tree {
+            self.writer().write_all(&content)?;
+            return Ok(());
+        }
+
+        for entry in TreeWalker::new(self.database(), oid, false)? {
+            let entry = entry?;
+            writeln!(
+                self.writer(),
+                "{:06o} {} {}\t{}",
+                entry.entry.mode.as_u32(),
+                entry.object_type(),
+                entry.entry.oid,
+                entry.path.display()
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use crate::areas::repository::Repository;
+use crate::artifacts::database::reachability::{reachable_objects, unreferenced_objects};
+use crate::artifacts::objects::object_id::ObjectId;
+use std::collections::BTreeSet;
+
+impl Repository {
+    /// Report the objects no ref leads to
+    ///
+    /// Refs, HEAD, the index entries and, unless `no_reflogs`, the reflog
+    /// entries make objects reachable, as they are what pruning keeps.
+    ///
+    /// - `unreachable`: print `unreachable <type> <oid>` for every object not
+    ///   reachable from these roots
+    /// - `dangling`: print `dangling <type> <oid>` for the unreachable objects
+    ///   that no other unreachable object refers to, e.g. a commit amended
+    ///   away (with `no_reflogs`), or an orphaned blob. These are the
+    ///   starting points to recover lost work from.
+    pub async fn fsck(
+        &self,
+        unreachable: bool,
+        dangling: bool,
+        no_reflogs: bool,
+    ) -> anyhow::Result<()> {
+        let ref_roots = self.refs().reverse_refs()?;
+        let reflog_roots = if no_reflogs {
+            Vec::new()
+        } else {
+            ref_roots
+                .values()
+                .flatten()
+                .map(|sym_ref| self.refs().read_reflog(sym_ref))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .flat_map(|entry| entry.old_oid.into_iter().chain([entry.new_oid]))
+                .collect::<Vec<_>>()
+        };
+        let roots = ref_roots
+            .into_keys()
+            .chain(self.index_objects().await?)
+            .chain(reflog_roots)
+            .collect::<Vec<_>>();
+        let reachable = reachable_objects(self.database(), roots)?;
+
+        let lost = self
+            .database()
+            .loose_objects()?
+            .into_iter()
+            .chain(self.database().packed_objects()?)
+            .filter(|oid| !reachable.contains_key(oid))
+            .collect::<BTreeSet<_>>();
+
+        if unreachable {
+            for oid in &lost {
+                let object_type = self.database().get_object_type(oid)?;
+                writeln!(self.writer(), "unreachable {} {}", object_type, oid)?;
+            }
+        }
+
+        if dangling {
+            for oid in unreferenced_objects(self.database(), &lost)? {
+                let object_type = self.database().get_object_type(&oid)?;
+                writeln!(self.writer(), "dangling {} {}", object_type, oid)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Objects staged in the index, submodule commits aside
+    async fn index_objects(&self) -> anyhow::Result<Vec<ObjectId>> {
+        let index = self.index();
+        let mut index = index.lock().await;
+        index.rehydrate()?;
+
+        Ok(index
+            .entries()
+            .filter(|entry| !entry.metadata.mode.is_gitlink())
+            .map(|entry| entry.oid.clone())
+            .collect())
+    }
+}
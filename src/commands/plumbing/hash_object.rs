@@ -1,25 +1,191 @@
 use crate::areas::repository::Repository;
+use crate::areas::workspace::WalkEntry;
+use crate::artifacts::core::ignore::IgnoreFiles;
 use crate::artifacts::objects::blob::Blob;
 use crate::artifacts::objects::object::Object;
+use crate::artifacts::objects::object_id::ObjectId;
+use std::path::Path;
+
+/// Size above which files are hashed and stored by streaming them
+const BIG_FILE_THRESHOLD_KEY: &str = "core.bigFileThreshold";
+/// Default `core.bigFileThreshold` (512 MiB, as in Git)
+const DEFAULT_BIG_FILE_THRESHOLD: i64 = 512 * 1024 * 1024;
 
 impl Repository {
-    pub fn hash_object(&mut self, object_path: &str, write: bool) -> anyhow::Result<()> {
+    /// Print the blob ID of a file
+    ///
+    /// A directory has every file below it hashed instead, printing one
+    /// `<oid>\t<path>` line per file, sorted by its path relative to the
+    /// directory. With `respect_ignore`, files excluded by `.gitignore` or
+    /// `.git/info/exclude` are skipped.
+    pub fn hash_object(
+        &mut self,
+        object_path: &str,
+        write: bool,
+        respect_ignore: bool,
+    ) -> anyhow::Result<()> {
+        if Path::new(object_path).is_dir() {
+            return self.hash_directory(Path::new(object_path), write, respect_ignore);
+        }
+
+        let object_id = self.hash_file(Path::new(object_path), write)?;
+
+        write!(self.writer(), "{}", object_id.as_ref())?;
+
+        Ok(())
+    }
+
+    fn hash_directory(
+        &mut self,
+        dir_path: &Path,
+        write: bool,
+        respect_ignore: bool,
+    ) -> anyhow::Result<()> {
+        let dir = std::fs::canonicalize(dir_path)?;
+        let prefix = dir
+            .strip_prefix(self.workspace().path())
+            .map_err(|_| anyhow::anyhow!("'{}' is outside the repository", dir_path.display()))?
+            .to_path_buf();
+
+        let ignores = if respect_ignore {
+            Some(IgnoreFiles::load(self.path())?)
+        } else {
+            None
+        };
+
+        let mut files = self
+            .workspace()
+            .walk(Some(dir.clone()), ignores.as_ref())?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter_map(WalkEntry::into_file)
+            .map(|(path, _)| {
+                let relative = path.strip_prefix(&prefix).unwrap_or(&path);
+                (relative.to_string_lossy().into_owned(), path.clone())
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+
+        for (relative, path) in files {
+            let object_id = self.hash_file(&path, write)?;
+            writeln!(self.writer(), "{}\t{}", object_id, relative)?;
+        }
+
+        Ok(())
+    }
+
+    /// Hash a workspace file as a blob without printing anything
+    ///
+    /// # Arguments
+    ///
+    /// * `object_path` - Path of the file, relative to the workspace root
+    /// * `write` - Whether to also store the blob in the object database
+    ///
+    /// Files larger than `core.bigFileThreshold` are streamed in chunks
+    /// instead of being loaded into memory. Symlinks hash the path they
+    /// point to.
+    pub fn hash_file(&self, object_path: &Path, write: bool) -> anyhow::Result<ObjectId> {
+        let size = self.workspace().stat_file(object_path)?.size;
+        if size as i64 > self.big_file_threshold()? {
+            let file = self.workspace().open_file(object_path)?;
+            return Ok(self.database().hash_blob_stream(file, size, write)?);
+        }
+
         // read the object file
-        let object_data = self.workspace().read_file(object_path.as_ref())?;
+        let object_data = self.workspace().read_file(object_path)?;
         let object = Blob::new(object_data, Default::default());
 
         // hash
         let object_id = object.object_id()?;
 
-        write!(self.writer(), "{}", object_id.as_ref())?;
-
         // write (if write is true) as a compressed object file
-        if !write {
-            return Ok(());
+        if write {
+            self.database().store(object)?;
         }
 
-        self.database().store(object)?;
+        Ok(object_id)
+    }
+
+    fn big_file_threshold(&self) -> anyhow::Result<i64> {
+        Ok(self
+            .config()
+            .get_int(BIG_FILE_THRESHOLD_KEY)?
+            .unwrap_or(DEFAULT_BIG_FILE_THRESHOLD))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
 
-        Ok(())
+    fn repository_with_file(content: &str) -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("hello.txt"), content).unwrap();
+        let repository =
+            Repository::new(dir.path().to_path_buf(), Box::new(std::io::sink())).unwrap();
+
+        (dir, repository)
+    }
+
+    #[test]
+    fn test_hash_file_returns_known_blob_oid() {
+        let (_dir, repository) = repository_with_file("hello\n");
+
+        let object_id = repository.hash_file(Path::new("hello.txt"), false).unwrap();
+
+        assert_eq!(
+            object_id.as_ref(),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+        assert!(!repository.database().objects_path().exists());
+    }
+
+    #[test]
+    fn test_hash_file_with_write_stores_blob() {
+        let (_dir, repository) = repository_with_file("hello\n");
+
+        let object_id = repository.hash_file(Path::new("hello.txt"), true).unwrap();
+
+        let blob = repository
+            .database()
+            .parse_object_as_blob(&object_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(blob.content(), "hello\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hash_file_of_symlink_hashes_its_target_path() {
+        let (dir, repository) = repository_with_file("hello\n");
+        std::os::unix::fs::symlink("hello.txt", dir.path().join("link")).unwrap();
+
+        let object_id = repository.hash_file(Path::new("link"), false).unwrap();
+
+        let target = Blob::new("hello.txt".to_string(), Default::default());
+        assert_eq!(object_id, target.object_id().unwrap());
+    }
+
+    #[test]
+    fn test_hash_file_above_big_file_threshold_matches_in_memory_hash() {
+        let content = "big file line\n".repeat(1000);
+        let (dir, repository) = repository_with_file(&content);
+        let expected = repository.hash_file(Path::new("hello.txt"), false).unwrap();
+
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        repository
+            .config()
+            .set(BIG_FILE_THRESHOLD_KEY, "1k")
+            .unwrap();
+        let object_id = repository.hash_file(Path::new("hello.txt"), true).unwrap();
+
+        assert_eq!(object_id, expected);
+        let blob = repository
+            .database()
+            .parse_object_as_blob(&object_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(blob.content(), content);
     }
 }
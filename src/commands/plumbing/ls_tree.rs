@@ -1,12 +1,17 @@
 use crate::areas::repository::Repository;
 use crate::artifacts::database::database_entry::DatabaseEntry;
 use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::objects::tree::{TreeWalkEntry, TreeWalker};
 use std::collections::BTreeMap;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 impl Repository {
-    // TODO: add support for the recursive flag
-    pub async fn ls_tree(&mut self, object_sha: &str, _recursive: bool) -> anyhow::Result<()> {
+    pub async fn ls_tree(
+        &mut self,
+        object_sha: &str,
+        recursive: bool,
+        long: bool,
+    ) -> anyhow::Result<()> {
         let oid = if object_sha == "HEAD" {
             self.refs()
                 .read_head()?
@@ -15,53 +20,52 @@ impl Repository {
             ObjectId::try_parse(object_sha.to_string())?
         };
 
-        match self.database().parse_object_as_commit(&oid)? {
-            Some(commit) => {
-                self.parse_tree(commit.tree_oid(), None, &mut Default::default(), true)
-                    .await?;
-            }
-            None => {
-                self.parse_tree(&oid, None, &mut Default::default(), true)
-                    .await?;
-            }
+        let tree_oid = match self.database().parse_object_as_commit(&oid)? {
+            Some(commit) => commit.tree_oid().clone(),
+            None => oid,
+        };
+
+        for entry in TreeWalker::new(self.database(), &tree_oid, recursive)? {
+            self.print_tree_entry(&entry?, long)?;
         }
 
         Ok(())
     }
 
+    /// Collect every blob reachable from a tree, keyed by its path
     pub(crate) async fn parse_tree(
         &self,
         oid: &ObjectId,
-        prefix: Option<&Path>,
         tree_data: &mut BTreeMap<PathBuf, DatabaseEntry>,
-        should_print: bool,
     ) -> anyhow::Result<()> {
-        if let Some(tree) = self.database().parse_object_as_tree(oid)? {
-            for (name, entry) in tree.into_entries() {
-                let path = if let Some(prefix) = prefix {
-                    prefix.join(name)
-                } else {
-                    Path::new(&name).to_path_buf()
-                };
-
-                if entry.is_tree() {
-                    Box::pin(self.parse_tree(&entry.oid, Some(&path), tree_data, should_print))
-                        .await?;
-                } else {
-                    if should_print {
-                        writeln!(
-                            self.writer(),
-                            "{:o} {} {}",
-                            entry.mode.as_u32(),
-                            entry.oid.as_ref(),
-                            path.display()
-                        )?;
-                    }
-                    tree_data.insert(path, entry);
-                }
-            }
+        for entry in TreeWalker::new(self.database(), oid, true)? {
+            let entry = entry?;
+            tree_data.insert(entry.path, entry.entry);
         }
 
         Ok(())
     }
+
+    /// Print one entry as `<mode> <type> <oid>\t<path>`; `long` adds the
+    /// blob size (`-` for trees and gitlinks) right-aligned after the oid,
+    /// read from the object header only
+    fn print_tree_entry(&self, entry: &TreeWalkEntry, long: bool) -> anyhow::Result<()> {
+        let size = match long {
+            false => String::new(),
+            true if entry.entry.is_tree() || entry.entry.is_gitlink() => format!(" {:>7}", "-"),
+            true => format!(" {:>7}", self.database().object_size(&entry.entry.oid)?),
+        };
+
+        writeln!(
+            self.writer(),
+            "{:06o} {} {}{}\t{}",
+            entry.entry.mode.as_u32(),
+            entry.object_type(),
+            entry.entry.oid,
+            size,
+            entry.path.display()
+        )?;
+
+        Ok(())
+    }
 }
@@ -6,9 +6,23 @@
 //!
 //! ## Commands
 //!
+//! - `cat-file`: Show the type or size of an object
+//! - `fsck`: Report unreachable and dangling objects
 //! - `hash-object`: Compute object ID and optionally store in database
 //! - `ls-tree`: List contents of a tree object
+//! - `rev-parse`: Resolve revisions and report repository locations
+//! - `unpack-objects`: Import the objects of a packfile as loose objects
+//! - `update-index`: Set the assume-unchanged/skip-worktree bits of index entries
+//! - `verify-commit`: Check that a commit object is well-formed
+//! - `verify-index`: Check the structure of the index file
 
+pub mod cat_file;
+pub mod fsck;
 pub mod hash_object;
 pub mod ls_tree;
+pub mod rev_parse;
+pub mod unpack_objects;
+pub mod update_index;
+pub mod verify_commit;
+pub mod verify_index;
 mod write_commit;
@@ -0,0 +1,187 @@
+use crate::areas::repository::Repository;
+use crate::artifacts::branch::revision::{Revision, unknown_revision};
+use crate::artifacts::objects::object_id::ObjectId;
+use std::path::Path;
+
+/// Repository queries answered by `bit rev-parse`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RevParseOptions {
+    /// Print the path of the `.git` directory
+    pub git_dir: bool,
+    /// Print the root of the working tree
+    pub show_toplevel: bool,
+    /// Print whether the current directory is inside the working tree
+    pub is_inside_work_tree: bool,
+}
+
+impl Repository {
+    /// Answer the requested queries, then print the OID of every revision
+    ///
+    /// `cwd` is the directory the command was run from, which may be any
+    /// directory below the repository root. An annotated tag prints the OID
+    /// of the tag object itself, as in Git; `^{}` peels it to the commit.
+    pub fn rev_parse(
+        &self,
+        opts: RevParseOptions,
+        revisions: &[String],
+        cwd: &Path,
+    ) -> anyhow::Result<()> {
+        if opts.git_dir {
+            writeln!(self.writer(), "{}", self.git_dir().display())?;
+        }
+        if opts.show_toplevel {
+            writeln!(self.writer(), "{}", self.path().display())?;
+        }
+        if opts.is_inside_work_tree {
+            let inside = !cwd.canonicalize()?.starts_with(self.git_dir());
+            writeln!(self.writer(), "{}", inside)?;
+        }
+
+        for revision in revisions {
+            let oid = self.resolve_object(revision)?;
+            writeln!(self.writer(), "{}", oid)?;
+        }
+
+        Ok(())
+    }
+
+    /// Parse and resolve a revision (`main`, `HEAD~2`, `1a2b3c4^2`, ...)
+    ///
+    /// Names that don't parse or don't point at anything are reported as an
+    /// unknown revision; abbreviated OIDs matching several commits and OIDs of
+    /// other object types keep their specific errors.
+    pub fn resolve_revision(&self, spec: &str) -> anyhow::Result<ObjectId> {
+        Revision::try_parse(spec)
+            .map_err(|_| unknown_revision(spec))?
+            .resolve(self)?
+            .ok_or_else(|| unknown_revision(spec))
+    }
+
+    /// Parse and resolve a revision to the object it names, which may be of
+    /// any type (e.g. an annotated tag rather than the commit it tags)
+    pub fn resolve_object(&self, spec: &str) -> anyhow::Result<ObjectId> {
+        Revision::try_parse(spec)
+            .map_err(|_| unknown_revision(spec))?
+            .resolve_object(self)?
+            .ok_or_else(|| unknown_revision(spec))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::index::entry_mode::FileMode;
+    use crate::artifacts::objects::blob::Blob;
+    use crate::artifacts::objects::commit::{Author, Commit};
+    use crate::artifacts::objects::object::Object;
+    use crate::artifacts::objects::tree::Tree;
+    use assert_fs::TempDir;
+    use std::collections::HashMap;
+
+    fn repository() -> (TempDir, Repository) {
+        let dir = TempDir::new().unwrap();
+        let repository =
+            Repository::new(dir.path().to_path_buf(), Box::new(std::io::sink())).unwrap();
+
+        (dir, repository)
+    }
+
+    fn commit(message: &str) -> Commit {
+        let tree = Tree::build(std::iter::empty()).unwrap();
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2023-01-01T12:00:00+00:00").unwrap();
+        let author = Author::new_with_timestamp("bit".into(), "bit@example.com".into(), timestamp);
+
+        Commit::new(
+            vec![],
+            tree.object_id().unwrap(),
+            author,
+            message.to_string(),
+        )
+    }
+
+    fn error_of(repository: &Repository, spec: &str) -> String {
+        repository.resolve_revision(spec).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_resolve_full_commit_oid() {
+        let (_dir, repository) = repository();
+        let commit = commit("first");
+        let oid = commit.object_id().unwrap();
+        repository.database().store(commit).unwrap();
+
+        assert_eq!(repository.resolve_revision(oid.as_ref()).unwrap(), oid);
+    }
+
+    #[test]
+    fn test_unknown_ref_is_unknown_revision() {
+        let (_dir, repository) = repository();
+
+        assert_eq!(
+            error_of(&repository, "missing"),
+            "ambiguous argument 'missing': unknown revision or path not in the working tree"
+        );
+        assert_eq!(
+            error_of(&repository, "not a ref"),
+            "ambiguous argument 'not a ref': unknown revision or path not in the working tree"
+        );
+        assert_eq!(
+            error_of(&repository, "dead"),
+            "ambiguous argument 'dead': unknown revision or path not in the working tree"
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_abbreviation_lists_candidates() {
+        let (_dir, repository) = repository();
+
+        // Find two commits whose IDs share their first four hex digits
+        let mut seen = HashMap::new();
+        let (first, second) = (0..)
+            .find_map(|i| {
+                let commit = commit(&format!("commit {}", i));
+                let prefix = commit.object_id().unwrap().as_ref()[..4].to_string();
+                seen.insert(prefix, commit.clone())
+                    .map(|other| (other, commit))
+            })
+            .unwrap();
+        let prefix = first.object_id().unwrap().as_ref()[..4].to_string();
+        repository.database().store(first).unwrap();
+        repository.database().store(second).unwrap();
+
+        let error = error_of(&repository, &prefix);
+        assert!(error.starts_with(&format!("short SHA1 {} is ambiguous", prefix)));
+        assert_eq!(error.matches("hint:   ").count(), 2);
+    }
+
+    #[test]
+    fn test_non_commit_target_is_rejected() {
+        let (_dir, repository) = repository();
+        let blob = Blob::new("content".into(), FileMode::Regular);
+        let oid = blob.object_id().unwrap();
+        repository.database().store(blob).unwrap();
+
+        assert_eq!(
+            error_of(&repository, oid.as_ref()),
+            format!("object {} is a blob, not a commit", oid.to_short_oid())
+        );
+    }
+
+    #[test]
+    fn test_resolve_object_names_objects_of_any_type() {
+        let (_dir, repository) = repository();
+        let blob = Blob::new("content".into(), FileMode::Regular);
+        let oid = blob.object_id().unwrap();
+        repository.database().store(blob).unwrap();
+
+        assert_eq!(repository.resolve_object(oid.as_ref()).unwrap(), oid);
+        assert_eq!(repository.resolve_object(&oid.as_ref()[..7]).unwrap(), oid);
+        assert_eq!(
+            repository
+                .resolve_object("0123456789012345678901234567890123456789")
+                .unwrap_err()
+                .to_string(),
+            "object 0123456789012345678901234567890123456789 not found"
+        );
+    }
+}
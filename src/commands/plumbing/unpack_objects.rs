@@ -0,0 +1,39 @@
+use crate::areas::repository::Repository;
+use crate::artifacts::pack::reader::{PackReader, PackedObject};
+use std::io::Read;
+use std::path::Path;
+
+impl Repository {
+    /// Import every object of a packfile into the loose object database
+    ///
+    /// Reads the pack from `pack_path`, or from stdin when no path is given.
+    pub fn unpack_objects(&mut self, pack_path: Option<&Path>) -> anyhow::Result<()> {
+        let pack = match pack_path {
+            Some(path) => std::fs::read(path)?,
+            None => {
+                let mut pack = Vec::new();
+                std::io::stdin().read_to_end(&mut pack)?;
+                pack
+            }
+        };
+
+        let objects = PackReader::new(&pack).read_objects(|oid| {
+            self.database()
+                .load_raw(oid)
+                .ok()
+                .map(|(object_type, content)| PackedObject {
+                    object_type,
+                    content: content.to_vec(),
+                })
+        })?;
+
+        for object in &objects {
+            self.database()
+                .store_raw(&object.object_type, &object.content)?;
+        }
+
+        writeln!(self.writer(), "Unpacked {} objects", objects.len())?;
+
+        Ok(())
+    }
+}
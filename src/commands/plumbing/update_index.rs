@@ -0,0 +1,47 @@
+use crate::areas::repository::Repository;
+use std::path::PathBuf;
+
+/// Flag change requested through `bit update-index`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexFlagUpdate {
+    /// Set (`true`) or clear (`false`) the assume-unchanged bit
+    AssumeUnchanged(bool),
+    /// Set (`true`) or clear (`false`) the skip-worktree bit
+    SkipWorktree(bool),
+}
+
+impl Repository {
+    /// Set or clear the assume-unchanged/skip-worktree bits of tracked paths
+    ///
+    /// Fails without touching the index if any path is not tracked.
+    pub async fn update_index(
+        &mut self,
+        paths: &[PathBuf],
+        updates: &[IndexFlagUpdate],
+    ) -> anyhow::Result<()> {
+        let index = self.index();
+        let mut index = index.lock().await;
+
+        index.rehydrate()?;
+
+        for path in paths {
+            let mut flags = index
+                .entry_by_path(path)
+                .ok_or_else(|| anyhow::anyhow!("Unable to mark file {}", path.display()))?
+                .flags;
+
+            for update in updates {
+                match *update {
+                    IndexFlagUpdate::AssumeUnchanged(value) => flags.assume_unchanged = value,
+                    IndexFlagUpdate::SkipWorktree(value) => flags.skip_worktree = value,
+                }
+            }
+
+            index.update_entry_flags(path, flags)?;
+        }
+
+        self.write_index(&mut index)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+use crate::areas::repository::Repository;
+
+impl Repository {
+    /// Check that a commit object is well-formed
+    ///
+    /// Fails with a descriptive error if the commit has malformed header lines
+    /// or references objects of the wrong type; prints nothing on success.
+    pub fn verify_commit(&mut self, revision: &str) -> anyhow::Result<()> {
+        let oid = self.resolve_revision(revision)?;
+
+        self.database().verify_commit(&oid)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use crate::areas::repository::Repository;
+
+impl Repository {
+    /// Check that the index file is well-formed
+    ///
+    /// Validates the header, the padding and order of the entries and the
+    /// trailing checksum. Fails with the first inconsistency and its byte
+    /// offset; prints nothing on success.
+    pub async fn verify_index(&self) -> anyhow::Result<()> {
+        let index = self.index();
+        let index = index.lock().await;
+
+        index.verify()?;
+
+        Ok(())
+    }
+}
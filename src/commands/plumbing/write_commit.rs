@@ -9,19 +9,40 @@ impl Repository {
         &mut self,
         parents: Vec<ObjectId>,
         message: String,
+    ) -> anyhow::Result<Commit> {
+        let author = Author::load_from_env()?;
+        self.write_commit_with_author(parents, message, author)
+            .await
+    }
+
+    /// Commit the index on behalf of `author`, with the committer taken from
+    /// the environment
+    pub async fn write_commit_with_author(
+        &mut self,
+        parents: Vec<ObjectId>,
+        message: String,
+        author: Author,
     ) -> anyhow::Result<Commit> {
         let tree_id = self.write_tree().await?;
 
-        let author = Author::load_from_env()?;
-        let commit = Commit::new(parents, tree_id, author, message);
+        let committer = Author::load_from_env()?;
+        let commit = Commit::new(parents, tree_id, author, message).with_committer(committer);
         let commit_id = commit.object_id()?;
 
         self.database().store(commit.clone())?;
-        self.refs().update_head(commit_id)?;
+        let kind = match commit.parents().len() {
+            0 => "commit (initial)",
+            1 => "commit",
+            _ => "commit (merge)",
+        };
+        let reflog_message = format!("{}: {}", kind, commit.short_message());
+        self.refs().update_head(commit_id, &reflog_message)?;
 
         Ok(commit)
     }
 
+    /// Store the trees of the index, rebuilding only the directories whose
+    /// cached tree was invalidated since the last write
     async fn write_tree(&mut self) -> anyhow::Result<ObjectId> {
         let index = self.index();
         let mut index = index.lock().await;
@@ -29,11 +50,21 @@ impl Repository {
         // Load the index file from the disk
         index.rehydrate()?;
 
-        let tree = Tree::build(index.entries())?;
-        let tree_id = tree.object_id()?;
-        let store_tree =
-            &|tree: &Tree| -> anyhow::Result<()> { Ok(self.database().store(tree.clone())?) };
-        tree.traverse(store_tree)?;
+        if let Some(tree_id) = index.cache_tree().oid() {
+            return Ok(tree_id.clone());
+        }
+
+        let (tree_id, cache_tree) = {
+            let tree = Tree::build_with_cache(index.entries(), index.cache_tree())?;
+            let store_tree =
+                &|tree: &Tree| -> anyhow::Result<()> { Ok(self.database().store(tree.clone())?) };
+            tree.traverse(store_tree)?;
+
+            (tree.object_id()?, tree.cache_tree(index.cache_tree())?)
+        };
+
+        index.set_cache_tree(cache_tree);
+        self.write_index(&mut index)?;
 
         Ok(tree_id)
     }
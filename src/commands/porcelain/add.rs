@@ -1,24 +1,52 @@
 use crate::areas::repository::Repository;
+use crate::areas::workspace::WalkEntry;
+use crate::artifacts::core::ignore::IgnoreFiles;
+use crate::artifacts::core::quote::unquote_path;
 use crate::artifacts::index::index_entry::IndexEntry;
-use crate::artifacts::objects::blob::Blob;
-use crate::artifacts::objects::object::Object;
 use std::collections::HashSet;
 use std::path::PathBuf;
 
 impl Repository {
-    pub async fn add(&mut self, paths: &[String]) -> anyhow::Result<()> {
+    /// Stage the files under `paths`
+    ///
+    /// Untracked files matched by the ignore rules are skipped unless `force`
+    /// is set; naming a path whose files are all ignored is an error, as in
+    /// Git. Files that are already tracked are always updated.
+    pub async fn add(&mut self, paths: &[String], force: bool) -> anyhow::Result<()> {
         let index = self.index();
         let mut index = index.lock().await;
 
         // Load the index file from the disk
         index.rehydrate()?;
 
-        // Iterate over each provided file path and expand it if it's a directory
+        let ignores = if force {
+            None
+        } else {
+            Some(IgnoreFiles::load(self.path())?)
+        };
+
+        // Accept paths quoted the way status prints them
         let paths = paths
             .iter()
-            .map(|path| (path, self.workspace().list_files(Some(PathBuf::from(path)))))
+            .map(|path| unquote_path(path))
             .collect::<Vec<_>>();
 
+        // Iterate over each provided file path and expand it if it's a directory,
+        // stat'ing every file found along the way
+        let paths = paths
+            .iter()
+            .map(|path| -> anyhow::Result<_> {
+                let entries = match self
+                    .workspace()
+                    .walk(Some(PathBuf::from(path)), ignores.as_ref())
+                {
+                    Ok(entries) => Ok(entries.collect::<Result<Vec<_>, _>>()?),
+                    Err(error) => Err(error),
+                };
+                Ok((path, entries))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
         // Collect all the invalid paths to remove them from the index in case they were tracked before
         let invalid_paths = paths
             .iter()
@@ -33,10 +61,41 @@ impl Repository {
         }
 
         if !invalid_paths.is_empty() {
-            index.write_updates()?;
+            self.write_index(&mut index)?;
             anyhow::bail!("The following paths are not valid: {:?}", invalid_paths);
         }
 
+        // Keep the files the ignore rules left in, plus the tracked files they
+        // matched, remembering the named paths that were left with nothing to add
+        let mut ignored_paths = Vec::new();
+        let mut kept_paths = Vec::with_capacity(paths.len());
+        for (path, entries) in paths {
+            let files = entries.map(|entries| {
+                let mut ignored = false;
+                let mut files = Vec::with_capacity(entries.len());
+                for entry in entries {
+                    match entry {
+                        WalkEntry::File(file, stat) => files.push((file, stat)),
+                        WalkEntry::Dir(_) => {}
+                        WalkEntry::Ignored { path, .. } => {
+                            ignored = true;
+                            for file in index.entries_under_path(&path) {
+                                if let Ok(stat) = self.workspace().stat_file(&file) {
+                                    files.push((file, stat));
+                                }
+                            }
+                        }
+                    }
+                }
+                if ignored && files.is_empty() {
+                    ignored_paths.push(path.clone());
+                }
+                files
+            });
+            kept_paths.push((path, files));
+        }
+        let paths = kept_paths;
+
         // Collect all the valid paths to add them to the index
         let valid_paths = paths
             .iter()
@@ -50,17 +109,13 @@ impl Repository {
             .collect::<Vec<_>>();
 
         // Collect workspace files into a set for deletion check later
-        let workspace_files: HashSet<PathBuf> = valid_paths.iter().map(|p| (*p).clone()).collect();
-
-        for path in &valid_paths {
-            let data = self.workspace().read_file(path)?;
-            let stat = self.workspace().stat_file(path)?;
+        let workspace_files: HashSet<PathBuf> =
+            valid_paths.iter().map(|(path, _)| path.clone()).collect();
 
-            let blob = Blob::new(data, stat.clone().mode.try_into()?);
-            let blob_id = blob.object_id()?;
+        for (path, stat) in &valid_paths {
+            let blob_id = self.hash_file(path, true)?;
 
-            self.database().store(blob)?;
-            index.add(IndexEntry::new(path.to_path_buf(), blob_id, stat))?;
+            index.add(IndexEntry::new(path.to_path_buf(), blob_id, stat.clone()))?;
         }
 
         // Handle deletions: Check if tracked files in the index no longer exist in the workspace
@@ -81,7 +136,15 @@ impl Repository {
             }
         }
 
-        index.write_updates()?;
+        self.write_index(&mut index)?;
+
+        if !ignored_paths.is_empty() {
+            anyhow::bail!(
+                "The following paths are ignored by one of your .gitignore files:\n{}\n\
+                hint: Use -f if you really want to add them.",
+                ignored_paths.join("\n")
+            );
+        }
 
         Ok(())
     }
@@ -0,0 +1,106 @@
+use crate::areas::repository::Repository;
+use crate::artifacts::database::database_entry::DatabaseEntry;
+use crate::artifacts::diff::patch::Mail;
+use crate::artifacts::index::index_entry::MergeStage;
+use crate::commands::porcelain::apply::ApplyOptions;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+impl Repository {
+    /// Apply the mails of a mailbox (as written by `format-patch`) as commits
+    ///
+    /// Each mail's diff is applied to the workspace and the index, then
+    /// committed with the mail's author, date and message. Applying stops at
+    /// the first patch that does not apply, leaving the earlier commits in
+    /// place.
+    ///
+    /// Like Git, nothing is applied while the index differs from HEAD, since
+    /// each commit records the whole index.
+    pub async fn am(&mut self, patch_path: &Path) -> anyhow::Result<()> {
+        let content = std::fs::read_to_string(patch_path)
+            .map_err(|e| anyhow::anyhow!("could not read {}: {}", patch_path.display(), e))?;
+        let mails = Mail::parse_mailbox(&content)?;
+
+        let dirty = self.paths_staged_against_head().await?;
+        if !dirty.is_empty() {
+            anyhow::bail!(
+                "Dirty index: cannot apply patches (dirty: {})",
+                dirty
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+        }
+
+        for (number, mail) in mails.iter().enumerate() {
+            writeln!(self.writer(), "Applying: {}", mail.subject)?;
+
+            if let Err(error) = self.apply_mail(mail).await {
+                anyhow::bail!(
+                    "patch failed at {:04} {}\n{}",
+                    number + 1,
+                    mail.subject,
+                    error
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn apply_mail(&mut self, mail: &Mail) -> anyhow::Result<()> {
+        self.apply_file_patches(
+            &mail.files,
+            ApplyOptions {
+                index: true,
+                three_way: false,
+            },
+        )
+        .await?;
+
+        let parents = self.refs().read_head()?.into_iter().collect();
+        self.write_commit_with_author(parents, mail.message(), mail.author.clone())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Paths whose index entries (any stage) differ from the tree of HEAD
+    async fn paths_staged_against_head(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let mut head_tree = BTreeMap::new();
+        if let Some(head) = self.refs().read_head()?
+            && let Some(commit) = self.database().parse_object_as_commit(&head)?
+        {
+            self.parse_tree(commit.tree_oid(), &mut head_tree).await?;
+        }
+
+        let index = self.index();
+        let mut index = index.lock().await;
+        index.rehydrate()?;
+
+        let mut index_tree = BTreeMap::new();
+        let mut dirty = Vec::new();
+        for entry in index.entries() {
+            if entry.stage != MergeStage::Clean {
+                dirty.push(entry.name.clone());
+                continue;
+            }
+            index_tree.insert(
+                entry.name.clone(),
+                DatabaseEntry::new(entry.oid.clone(), entry.metadata.mode),
+            );
+        }
+        dirty.extend(
+            head_tree
+                .keys()
+                .chain(index_tree.keys())
+                .filter(|path| head_tree.get(*path) != index_tree.get(*path))
+                .cloned(),
+        );
+        dirty.sort();
+        dirty.dedup();
+
+        Ok(dirty)
+    }
+}
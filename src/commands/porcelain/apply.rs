@@ -0,0 +1,239 @@
+use crate::areas::index::Index;
+use crate::areas::repository::Repository;
+use crate::artifacts::diff::patch::{FilePatch, PatchError};
+use crate::artifacts::index::entry_mode::{EntryMode, FileMode};
+use crate::artifacts::index::index_entry::{IndexEntry, MergeStage};
+use crate::artifacts::merge::blob_merge::MergeSide;
+use crate::artifacts::merge::diff3::merge_lines;
+use crate::artifacts::merge::resolution::conflict_style;
+use crate::artifacts::objects::blob::Blob;
+use crate::artifacts::objects::object::Object;
+use crate::artifacts::objects::object_id::ObjectId;
+use std::path::Path;
+
+/// How `bit apply` applies a patch
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApplyOptions {
+    /// Apply the patch to the index as well as the working tree
+    pub index: bool,
+    /// When hunks don't apply, merge the patch into the file starting from
+    /// the blob it was made against; implies `index`
+    pub three_way: bool,
+}
+
+/// A file with its patch applied
+enum Patched {
+    /// The new content, or `None` when the patch deletes the file
+    Applied(Option<String>),
+    /// The result of the three-way fallback, with the blobs of the base, the
+    /// current file and the patched base when it did not merge cleanly
+    Merged {
+        content: String,
+        conflict: Option<[ObjectId; 3]>,
+    },
+}
+
+impl Repository {
+    /// Apply a diff to the working tree, and to the index with `index`
+    ///
+    /// Creations write the file with the mode the patch declares; deletions
+    /// only remove a file whose content is exactly what the patch removes.
+    /// Nothing is changed unless every file of the patch applies, or merges
+    /// with `three_way`.
+    pub async fn apply(&mut self, patch_path: &Path, opts: ApplyOptions) -> anyhow::Result<()> {
+        let content = std::fs::read_to_string(patch_path)
+            .map_err(|e| anyhow::anyhow!("could not read {}: {}", patch_path.display(), e))?;
+        let files = FilePatch::parse_patch(&content)?;
+
+        self.apply_file_patches(&files, opts).await
+    }
+
+    /// Apply the patches of several files at once
+    ///
+    /// Every file is patched in memory first so that a failing hunk changes
+    /// nothing, then the results are written to the workspace and, with
+    /// `index` or `three_way`, staged. Files merged with conflicts are left
+    /// with conflict markers and their three stages in the index.
+    pub(crate) async fn apply_file_patches(
+        &mut self,
+        files: &[FilePatch],
+        opts: ApplyOptions,
+    ) -> anyhow::Result<()> {
+        let patched = files
+            .iter()
+            .map(|file| {
+                let original = if self.workspace().is_file(&file.path) {
+                    Some(self.workspace().read_file(&file.path)?)
+                } else {
+                    None
+                };
+                let patched = match file.apply(original.as_deref()) {
+                    Err(error @ PatchError::HunkFailed { .. }) if opts.three_way => {
+                        let merged = match original.as_deref() {
+                            Some(original) => self.three_way_fallback(file, original)?,
+                            None => None,
+                        };
+                        merged.ok_or(error)?
+                    }
+                    result => Patched::Applied(result?),
+                };
+                Ok((file, patched))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let update_index = opts.index || opts.three_way;
+        let index = self.index();
+        let mut index = index.lock().await;
+        if update_index {
+            index.rehydrate()?;
+        }
+
+        let mut conflicted = Vec::new();
+        for (file, patched) in patched {
+            match patched {
+                Patched::Applied(Some(content)) => {
+                    self.write_patched_file(file, &content)?;
+                    if update_index {
+                        self.stage_patched_file(&mut index, &file.path)?;
+                    }
+                }
+                Patched::Applied(None) => {
+                    self.workspace().remove_file(&file.path)?;
+                    if update_index {
+                        index.remove(file.path.clone())?;
+                    }
+                }
+                Patched::Merged {
+                    content,
+                    conflict: None,
+                } => {
+                    self.write_patched_file(file, &content)?;
+                    self.stage_patched_file(&mut index, &file.path)?;
+                    writeln!(
+                        self.writer(),
+                        "Applied patch to '{}' cleanly.",
+                        file.path.display()
+                    )?;
+                }
+                Patched::Merged {
+                    content,
+                    conflict: Some(oids),
+                } => {
+                    self.write_patched_file(file, &content)?;
+                    let mode = self.workspace().stat_file(&file.path)?.mode;
+                    let entries = [MergeStage::Base, MergeStage::Ours, MergeStage::Theirs]
+                        .into_iter()
+                        .zip(oids)
+                        .map(|(stage, oid)| {
+                            IndexEntry::for_conflict(file.path.clone(), oid, mode, stage)
+                        })
+                        .collect();
+                    index.add_conflict_entries(entries)?;
+                    writeln!(
+                        self.writer(),
+                        "Applied patch to '{}' with conflicts.",
+                        file.path.display()
+                    )?;
+                    writeln!(self.writer(), "U {}", file.path.display())?;
+                    conflicted.push(file.path.display().to_string());
+                }
+            }
+        }
+
+        if update_index {
+            self.write_index(&mut index)?;
+        }
+
+        if !conflicted.is_empty() {
+            anyhow::bail!("patch applied with conflicts in: {}", conflicted.join(", "));
+        }
+
+        Ok(())
+    }
+
+    /// Merge the patch into `original`, using the blob the patch was made
+    /// against as the common base
+    ///
+    /// Returns `None` when the patch records no such blob or the database
+    /// lacks it, or when the patch does not apply to it either, so that the
+    /// failure of the plain apply stands.
+    fn three_way_fallback(
+        &self,
+        file: &FilePatch,
+        original: &str,
+    ) -> anyhow::Result<Option<Patched>> {
+        let Some((base_id, _)) = &file.blob_ids else {
+            return Ok(None);
+        };
+        let Ok(base_oid) = self.resolve_object(base_id) else {
+            return Ok(None);
+        };
+        let Some(base) = self.database().parse_object_as_blob(&base_oid)? else {
+            return Ok(None);
+        };
+        let Ok(Some(patched)) = file.apply(Some(base.content())) else {
+            return Ok(None);
+        };
+
+        let merged = merge_lines(
+            MergeSide {
+                label: "base",
+                content: base.content(),
+            },
+            MergeSide {
+                label: "ours",
+                content: original,
+            },
+            MergeSide {
+                label: "theirs",
+                content: &patched,
+            },
+            conflict_style(self)?,
+        );
+        if merged.clean {
+            return Ok(Some(Patched::Merged {
+                content: merged.content,
+                conflict: None,
+            }));
+        }
+
+        let ours_oid = self.store_blob(original)?;
+        let theirs_oid = self.store_blob(&patched)?;
+
+        Ok(Some(Patched::Merged {
+            content: merged.content,
+            conflict: Some([base_oid, ours_oid, theirs_oid]),
+        }))
+    }
+
+    fn store_blob(&self, content: &str) -> anyhow::Result<ObjectId> {
+        let blob = Blob::new(content.to_string(), Default::default());
+        let oid = blob.object_id()?;
+        self.database().store(blob)?;
+
+        Ok(oid)
+    }
+
+    fn stage_patched_file(&self, index: &mut Index, path: &Path) -> anyhow::Result<()> {
+        let stat = self.workspace().stat_file(path)?;
+        let blob_id = self.hash_file(path, true)?;
+        index.add(IndexEntry::new(path.to_path_buf(), blob_id, stat))?;
+
+        Ok(())
+    }
+
+    /// Write a patched file, keeping its mode unless the patch sets one
+    fn write_patched_file(&self, file: &FilePatch, content: &str) -> anyhow::Result<()> {
+        let mode = match file.new_mode {
+            Some(mode) => mode,
+            None if self.workspace().is_file(&file.path) => {
+                self.workspace().stat_file(&file.path)?.mode
+            }
+            None => EntryMode::File(FileMode::Regular),
+        };
+        self.workspace()
+            .write_entry_file(&file.path, content.as_bytes(), &mode)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use crate::areas::repository::Repository;
+use crate::artifacts::archive::{ArchiveEntry, ArchiveEntryKind, ArchiveFormat, ArchiveWriter};
+use crate::artifacts::index::entry_mode::{EntryMode, FileMode};
+use crate::artifacts::objects::object::ObjectBox;
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::objects::tree::TreeWalker;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+/// How `bit archive` packages a tree
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveOptions {
+    /// Archive format; inferred from `output` (or tar) when unset
+    pub format: Option<ArchiveFormat>,
+    /// String prepended to every path, e.g. `project/`
+    pub prefix: Option<String>,
+    /// File to write the archive to instead of stdout
+    pub output: Option<PathBuf>,
+}
+
+impl Repository {
+    /// Write the snapshot of a commit or tree as an archive
+    ///
+    /// Files get the committer date of the commit as their modification time,
+    /// or the current time when a bare tree is archived. Submodule entries are
+    /// skipped, as their content lives in another repository.
+    pub fn archive(&self, revision: &str, opts: ArchiveOptions) -> anyhow::Result<()> {
+        let format = opts
+            .format
+            .or_else(|| {
+                opts.output
+                    .as_ref()
+                    .and_then(|output| ArchiveFormat::from_file_name(&output.to_string_lossy()))
+            })
+            .unwrap_or_default();
+
+        // Peel tags so that annotated tags archive the commit they point to
+        let oid = self.resolve_revision(&format!("{}^{{}}", revision))?;
+        let (tree_oid, mtime) = match self.database().parse_object(&oid)? {
+            ObjectBox::Commit(commit) => {
+                (commit.tree_oid().clone(), commit.committer().timestamp())
+            }
+            ObjectBox::Tree(_) => (oid, chrono::Local::now().fixed_offset()),
+            _ => anyhow::bail!("not a tree object: {}", revision),
+        };
+
+        match &opts.output {
+            Some(output) => {
+                let mut file = BufWriter::new(std::fs::File::create(output)?);
+                let mut writer = format.writer(&mut file);
+                self.write_archive(writer.as_mut(), &tree_oid, &opts, mtime)
+            }
+            None => {
+                let mut stdout = self.writer();
+                let mut writer = format.writer(&mut **stdout);
+                self.write_archive(writer.as_mut(), &tree_oid, &opts, mtime)
+            }
+        }
+    }
+
+    fn write_archive(
+        &self,
+        writer: &mut dyn ArchiveWriter,
+        tree_oid: &ObjectId,
+        opts: &ArchiveOptions,
+        mtime: chrono::DateTime<chrono::FixedOffset>,
+    ) -> anyhow::Result<()> {
+        let prefix = opts.prefix.as_deref().unwrap_or_default();
+
+        for entry in TreeWalker::new(self.database(), tree_oid, true)? {
+            let entry = entry?;
+            let kind = match entry.entry.mode {
+                EntryMode::File(FileMode::Regular) => ArchiveEntryKind::File,
+                EntryMode::File(FileMode::Executable) => ArchiveEntryKind::Executable,
+                EntryMode::Symlink => ArchiveEntryKind::Symlink,
+                EntryMode::Directory | EntryMode::Gitlink => continue,
+            };
+
+            let (_, content) = self.database().load_raw(&entry.entry.oid)?;
+            let path = entry
+                .path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            writer.add_entry(&ArchiveEntry {
+                path: format!("{}{}", prefix, path),
+                kind,
+                content: &content,
+                mtime,
+            })?;
+        }
+
+        writer.finish()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use crate::BisectAction;
+use crate::CommitDecoration;
+use crate::areas::refs::HEAD_REF_NAME;
+use crate::areas::repository::Repository;
+use crate::artifacts::branch::revision::Revision;
+use crate::artifacts::log::rev_list::RevList;
+use crate::artifacts::objects::commit::Commit;
+use crate::artifacts::objects::object::Object;
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::commands::porcelain::checkout::CheckoutMode;
+use crate::commands::porcelain::log::LogRevisionTargets;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+impl Repository {
+    pub async fn bisect(&mut self, bisect_action: &BisectAction) -> anyhow::Result<()> {
+        match bisect_action {
+            BisectAction::Start => self.bisect_start()?,
+            BisectAction::Bad { revision } => {
+                self.ensure_bisecting()?;
+
+                let oid = self.resolve_bisect_revision(revision.as_deref())?;
+                self.refs().write_bisect_bad(&oid)?;
+
+                self.bisect_next().await?;
+            }
+            BisectAction::Good { revisions } => {
+                self.ensure_bisecting()?;
+
+                if revisions.is_empty() {
+                    let oid = self.resolve_bisect_revision(None)?;
+                    self.refs().add_bisect_good(&oid)?;
+                }
+                for revision in revisions {
+                    let oid = self.resolve_bisect_revision(Some(revision))?;
+                    self.refs().add_bisect_good(&oid)?;
+                }
+
+                self.bisect_next().await?;
+            }
+            BisectAction::Reset => self.bisect_reset().await?,
+        }
+
+        Ok(())
+    }
+
+    /// Start a session, remembering where HEAD was so `reset` can return there
+    fn bisect_start(&self) -> anyhow::Result<()> {
+        if self.refs().read_bisect_start()?.is_some() {
+            anyhow::bail!("bisect already in progress; use `bit bisect reset` to abort it");
+        }
+
+        let current_ref = self.refs().current_ref(None)?;
+        let head_oid = self
+            .refs()
+            .read_head()?
+            .ok_or_else(|| anyhow::anyhow!("no current HEAD to bisect from"))?;
+        let original_head = if current_ref.is_detached_head() {
+            head_oid.as_ref().to_string()
+        } else {
+            current_ref.to_short_name()?
+        };
+
+        self.refs().write_bisect_start(&original_head)?;
+        writeln!(
+            self.writer(),
+            "status: waiting for both good and bad commits"
+        )?;
+
+        Ok(())
+    }
+
+    /// Check out the original HEAD and forget the session
+    async fn bisect_reset(&mut self) -> anyhow::Result<()> {
+        let Some(original_head) = self.refs().read_bisect_start()? else {
+            writeln!(self.writer(), "We are not bisecting.")?;
+            return Ok(());
+        };
+
+        self.checkout(&original_head, CheckoutMode::Safe).await?;
+        self.refs().clear_bisect_state()?;
+
+        Ok(())
+    }
+
+    /// Report what is missing, or check out the next commit to test
+    async fn bisect_next(&mut self) -> anyhow::Result<()> {
+        let bad = self.refs().read_bisect_bad()?;
+        let good = self.refs().read_bisect_good()?;
+
+        let bad = match (bad, good.is_empty()) {
+            (None, true) => {
+                writeln!(
+                    self.writer(),
+                    "status: waiting for both good and bad commits"
+                )?;
+                return Ok(());
+            }
+            (None, false) => {
+                writeln!(
+                    self.writer(),
+                    "status: waiting for bad commit, {} good commit(s) known",
+                    good.len()
+                )?;
+                return Ok(());
+            }
+            (Some(_), true) => {
+                writeln!(
+                    self.writer(),
+                    "status: waiting for good commit(s), bad commit known"
+                )?;
+                return Ok(());
+            }
+            (Some(bad), false) => bad,
+        };
+
+        let candidates = self.bisect_candidates(&bad, &good)?;
+        let (midpoint, reaches) = match candidates.as_slice() {
+            [] => anyhow::bail!("the bad commit {} is an ancestor of a good commit", bad),
+            [first_bad] => {
+                writeln!(self.writer(), "{} is the first bad commit", bad)?;
+                self.show_commit_medium(first_bad, "", None, &[], CommitDecoration::None)?;
+                return Ok(());
+            }
+            candidates => Self::bisect_midpoint(candidates)?,
+        };
+
+        let remaining = candidates.len() - reaches - 1;
+        let steps = Self::estimate_bisect_steps(candidates.len());
+        writeln!(
+            self.writer(),
+            "Bisecting: {} revision{} left to test after this (roughly {} step{})",
+            remaining,
+            if remaining == 1 { "" } else { "s" },
+            steps,
+            if steps == 1 { "" } else { "s" }
+        )?;
+
+        let midpoint = &candidates[midpoint];
+        let midpoint_oid = midpoint.object_id()?;
+        self.checkout(midpoint_oid.as_ref(), CheckoutMode::Safe)
+            .await?;
+        writeln!(
+            self.writer(),
+            "[{}] {}",
+            midpoint_oid,
+            midpoint.short_message()
+        )?;
+
+        Ok(())
+    }
+
+    /// Commits reachable from the bad commit but not from any good one,
+    /// newest first (the bad commit itself included)
+    fn bisect_candidates(&self, bad: &ObjectId, good: &[ObjectId]) -> anyhow::Result<Vec<Commit>> {
+        let bad = Revision::try_parse(bad.as_ref())?;
+        let mut targets = vec![LogRevisionTargets::IncludedRevision(bad)];
+        for oid in good {
+            let good = Revision::try_parse(oid.as_ref())?;
+            targets.push(LogRevisionTargets::ExcludedRevision(good));
+        }
+
+        Ok(RevList::new(self, targets, None, false)?
+            .into_iter()
+            .collect())
+    }
+
+    /// Pick the candidate splitting the others in the most even halves
+    ///
+    /// For every candidate, counts the candidates reachable from it (itself
+    /// included): if it turns out bad, only those remain. Returns the index of
+    /// the best candidate and its reach count; ties go to the smaller reach,
+    /// i.e. the older commit on a linear history.
+    fn bisect_midpoint(candidates: &[Commit]) -> anyhow::Result<(usize, usize)> {
+        let positions = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, commit)| Ok((commit.object_id()?, index)))
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+
+        let reaches = (0..candidates.len()).map(|start| {
+            let mut visited = HashSet::from([start]);
+            let mut stack = vec![start];
+            while let Some(index) = stack.pop() {
+                for parent in candidates[index].parents() {
+                    if let Some(&parent_index) = positions.get(parent)
+                        && visited.insert(parent_index)
+                    {
+                        stack.push(parent_index);
+                    }
+                }
+            }
+
+            visited.len()
+        });
+
+        reaches
+            .enumerate()
+            .max_by_key(|&(_, reach)| (reach.min(candidates.len() - reach), Reverse(reach)))
+            .ok_or_else(|| anyhow::anyhow!("no commits left to bisect"))
+    }
+
+    /// Roughly how many more steps a session over `all` candidates needs
+    fn estimate_bisect_steps(all: usize) -> usize {
+        if all < 3 {
+            return 0;
+        }
+
+        let n = all.ilog2() as usize;
+        let e = 1 << n;
+        let x = all - e;
+
+        if e < 3 * x { n } else { n - 1 }
+    }
+
+    fn ensure_bisecting(&self) -> anyhow::Result<()> {
+        if self.refs().read_bisect_start()?.is_none() {
+            anyhow::bail!("not bisecting; start with `bit bisect start`");
+        }
+
+        Ok(())
+    }
+
+    fn resolve_bisect_revision(&self, revision: Option<&str>) -> anyhow::Result<ObjectId> {
+        let revision = revision.unwrap_or(HEAD_REF_NAME);
+
+        self.resolve_revision(revision)
+    }
+}
@@ -1,8 +1,18 @@
 use crate::BranchAction;
+use crate::areas::config::ConfigError;
+use crate::areas::refs::HEAD_REF_NAME;
 use crate::areas::repository::Repository;
-use crate::artifacts::branch::branch_name::BranchName;
-use crate::artifacts::branch::revision::Revision;
+use crate::artifacts::branch::branch_name::{BranchName, SymRefName};
+use crate::artifacts::core::editor::edit_message;
 use colored::Colorize;
+use regex::Regex;
+use std::sync::LazyLock;
+
+const BRANCH_DESCRIPTION: &str = "BRANCH_DESCRIPTION";
+
+/// `%%` or a `%(atom)` placeholder of a `branch list --format` string
+static FORMAT_PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"%%|%\(([^)]*)\)").expect("valid placeholder regex"));
 
 impl Repository {
     pub fn branch(&mut self, branch_action: &BranchAction) -> anyhow::Result<()> {
@@ -14,13 +24,18 @@ impl Repository {
                 let branch_name = BranchName::try_parse(branch_name.clone())?;
 
                 let source_oid = if let Some(source_refname) = source_refname {
-                    Revision::try_parse(source_refname.as_str())?.resolve(self)?
+                    Some(self.resolve_revision(source_refname)?)
                 } else {
                     self.refs().read_head()?
                 }
                 .ok_or_else(|| anyhow::anyhow!("no current HEAD to branch from"))?;
+                let start_point = source_refname.as_deref().unwrap_or(HEAD_REF_NAME);
 
-                self.refs().create_branch(branch_name, source_oid)?;
+                self.refs().create_branch(
+                    branch_name,
+                    source_oid,
+                    &format!("branch: Created from {}", start_point),
+                )?;
             }
             BranchAction::Delete {
                 branch_names,
@@ -46,7 +61,24 @@ impl Repository {
                     );
                 }
             }
-            BranchAction::List { verbose } => {
+            BranchAction::EditDescription {
+                branch_name,
+                message,
+            } => self.edit_branch_description(branch_name.as_deref(), message.as_deref())?,
+            BranchAction::List {
+                format: Some(format),
+                ..
+            } => {
+                let current_ref = self.refs().current_ref(None)?;
+                let mut branches = self.refs().list_branches()?;
+                branches.sort();
+
+                for branch in branches {
+                    let line = self.format_branch(format, &branch, branch == current_ref)?;
+                    writeln!(self.writer(), "{}", line)?;
+                }
+            }
+            BranchAction::List { verbose, .. } => {
                 let current_ref = self.refs().current_ref(None)?;
                 let mut branches = self.refs().list_branches()?;
                 branches.sort();
@@ -105,4 +137,103 @@ impl Repository {
 
         Ok(())
     }
+
+    /// Set, replace or (when empty) remove `branch.<name>.description`
+    ///
+    /// Without a message the current description is opened in the editor.
+    fn edit_branch_description(
+        &self,
+        branch_name: Option<&str>,
+        message: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let branch_name = match branch_name {
+            Some(name) => BranchName::try_parse(name.to_string())?,
+            None => {
+                let current_ref = self.refs().current_ref(None)?;
+                if current_ref.is_detached_head() {
+                    anyhow::bail!("cannot give a description to a detached HEAD");
+                }
+                current_ref.to_branch_name()?
+            }
+        };
+        if !self.refs().branch_exists(&branch_name) {
+            anyhow::bail!("no branch named '{}'", branch_name.as_ref());
+        }
+
+        let key = format!("branch.{}.description", branch_name.as_ref());
+        let description = match message {
+            Some(message) => message.trim().to_string(),
+            None => {
+                let current = self.config().get(&key)?.unwrap_or_default();
+                let template = format!(
+                    "{}\n# Please edit the description for the branch\n#   {}\n\
+                    # Lines starting with '#' will be stripped.\n",
+                    current,
+                    branch_name.as_ref()
+                );
+                edit_message(&self.git_dir().join(BRANCH_DESCRIPTION), &template)?
+                    .ok_or_else(|| anyhow::anyhow!("no description given; use -m <description>"))?
+            }
+        };
+
+        if description.is_empty() {
+            match self.config().unset(&key) {
+                Err(ConfigError::KeyNotFound(_)) => {}
+                result => result?,
+            }
+        } else {
+            self.config().set(&key, &description)?;
+        }
+
+        Ok(())
+    }
+
+    /// Expand the `%(atom)` placeholders of a `branch list --format` string
+    ///
+    /// `%%` stands for a literal `%`; unknown atoms are an error.
+    fn format_branch(
+        &self,
+        format: &str,
+        branch: &SymRefName,
+        is_current: bool,
+    ) -> anyhow::Result<String> {
+        let short_name = branch.to_short_name()?;
+        let oid = self.refs().read_oid(branch)?.ok_or_else(|| {
+            anyhow::anyhow!("branch {} has no associated commit", branch.as_ref())
+        })?;
+
+        let mut output = String::new();
+        let mut last = 0;
+        for captures in FORMAT_PLACEHOLDER.captures_iter(format) {
+            let whole = captures.get(0).unwrap();
+            output.push_str(&format[last..whole.start()]);
+            last = whole.end();
+
+            let Some(atom) = captures.get(1) else {
+                output.push('%');
+                continue;
+            };
+            let value = match atom.as_str() {
+                "refname" => branch.as_ref().to_string(),
+                "refname:short" => short_name.clone(),
+                "objectname" => oid.as_ref().to_string(),
+                "objectname:short" => oid.to_short_oid(),
+                "HEAD" => if is_current { "*" } else { " " }.to_string(),
+                "subject" => self
+                    .database()
+                    .parse_object_as_commit(&oid)?
+                    .map(|commit| commit.short_message())
+                    .unwrap_or_default(),
+                "description" => self
+                    .config()
+                    .get(&format!("branch.{}.description", short_name))?
+                    .unwrap_or_default(),
+                atom => anyhow::bail!("unknown field name: {}", atom),
+            };
+            output.push_str(&value);
+        }
+        output.push_str(&format[last..]);
+
+        Ok(output)
+    }
 }
@@ -1,9 +1,23 @@
+use crate::areas::index::Index;
+use crate::areas::refs::RefsError;
 use crate::areas::repository::Repository;
-use crate::artifacts::branch::branch_name::SymRefName;
-use crate::artifacts::branch::revision::Revision;
+use crate::areas::workspace::DirCache;
+use crate::artifacts::branch::branch_name::{BranchName, SymRefName};
 use crate::artifacts::checkout::migration::Migration;
+use crate::artifacts::core::pathspec::Pathspec;
+use crate::artifacts::core::quote::unquote_path;
+use crate::artifacts::database::database_entry::DatabaseEntry;
+use crate::artifacts::diff::tree_diff::{TreeChangeType, TreeDiff};
+use crate::artifacts::index::index_entry::{EntryMetadata, IndexEntry, MergeStage};
 use crate::artifacts::log::path_filter::PathFilter;
+use crate::artifacts::merge::blob_merge::MergeSide;
+use crate::artifacts::merge::diff3::merge_lines;
+use crate::artifacts::merge::resolution::conflict_style;
+use crate::artifacts::objects::blob::Blob;
+use crate::artifacts::objects::object::Object;
 use crate::artifacts::objects::object_id::ObjectId;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 
 const DETACHMENT_NOTICE: &str = r#"
 You are in 'detached HEAD' state. You can look around, make experimental
@@ -16,18 +30,54 @@ do so (now or later) by using the branch command. Example:
     bit branch <new-branch-name>
 "#;
 
+/// What `bit checkout` does with local changes the switch would overwrite
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CheckoutMode {
+    /// Abort the checkout
+    #[default]
+    Safe,
+    /// Discard them, as with `reset --hard`
+    Force,
+    /// Merge them into the target versions of the files (`-m`)
+    Merge,
+}
+
+/// A locally changed file that `checkout -m` carries over to the target
+struct LocalChange {
+    path: PathBuf,
+    /// Version of the current commit
+    old: DatabaseEntry,
+    /// Version of the target commit
+    new: DatabaseEntry,
+    /// Workspace content
+    content: String,
+}
+
 impl Repository {
-    pub async fn checkout(&mut self, target: &str) -> anyhow::Result<()> {
+    /// Switch HEAD to `target`, updating the index and the workspace
+    ///
+    /// With `CheckoutMode::Force`, local changes to tracked files are
+    /// discarded instead of aborting the checkout; untracked files that would
+    /// be overwritten still abort it. With `CheckoutMode::Merge`, files that
+    /// differ from the current commit and that the target changes get a
+    /// three-way merge of the current commit, target and workspace versions;
+    /// conflicting merges leave markers in the file and the three versions
+    /// in the index.
+    pub async fn checkout(&mut self, target: &str, mode: CheckoutMode) -> anyhow::Result<()> {
         let current_ref = self.refs().current_ref(None)?;
         let current_oid = self
             .refs()
             .read_oid(&current_ref)?
             .ok_or_else(|| anyhow::anyhow!("no current HEAD to checkout from"))?;
 
-        let target_revision = Revision::try_parse(target)?;
-        let target_oid = target_revision
-            .resolve(self)?
-            .ok_or_else(|| anyhow::anyhow!("target revision could not be resolved"))?;
+        // A name that resolves to nothing may still match a remote-tracking branch
+        let (target_oid, tracked_remote) = match self.resolve_revision(target) {
+            Ok(oid) => (oid, None),
+            Err(err) => match self.find_tracking_candidate(target)? {
+                Some((remote, oid)) => (oid, Some(remote)),
+                None => return Err(err),
+            },
+        };
 
         let index = self.index();
         let mut index = index.lock().await;
@@ -40,17 +90,383 @@ impl Repository {
             &PathFilter::empty(),
         )?;
 
-        let mut migration = Migration::new(self, &mut index, tree_diff);
+        let local_changes = match mode {
+            CheckoutMode::Merge => self.local_changes_to_merge(&index, &tree_diff)?,
+            CheckoutMode::Safe | CheckoutMode::Force => Vec::new(),
+        };
+
+        let mut migration = match mode {
+            CheckoutMode::Safe => Migration::new(self, &mut index, tree_diff),
+            CheckoutMode::Force => Migration::new_forced(self, &mut index, tree_diff),
+            CheckoutMode::Merge => {
+                let merged_paths = local_changes
+                    .iter()
+                    .map(|change| change.path.clone())
+                    .collect::<BTreeSet<_>>();
+                Migration::new_merging(self, &mut index, tree_diff, merged_paths)
+            }
+        };
         migration.apply_changes()?;
+        let migrated = migration.changed_paths();
 
-        index.write_updates()?;
-        self.refs()
-            .set_head(target, target_oid.clone().as_ref().into())?;
+        if !local_changes.is_empty() {
+            let current_name = current_ref
+                .to_short_name()
+                .unwrap_or_else(|_| current_oid.to_short_oid());
+            self.merge_local_changes(&mut index, local_changes, &current_name, target)?;
+        }
+
+        if mode == CheckoutMode::Force {
+            // Files left alone by the migration may still carry local changes
+            let target_commit = self
+                .database()
+                .parse_object_as_commit(&target_oid)?
+                .ok_or_else(|| anyhow::anyhow!("{} is not a commit", target_oid))?;
+            let target_entries = self.flatten_tree(target_commit.tree_oid()).await?;
+            self.reset_workspace(&mut index, &target_entries, &migrated)?;
+        }
+
+        self.write_index(&mut index)?;
+        if let Some(remote) = &tracked_remote {
+            self.create_tracking_branch(target, remote, &target_oid)?;
+        }
+        let previous = match current_ref.to_short_name() {
+            Ok(name) if !current_ref.is_detached_head() => name,
+            _ => current_oid.to_string(),
+        };
+        self.refs().switch_head(
+            target,
+            target_oid.clone().as_ref().into(),
+            &format!("checkout: moving from {} to {}", previous, target),
+        )?;
         let new_ref = self.refs().current_ref(None)?;
 
         self.print_previous_head(&current_ref, &current_oid, &target_oid)?;
-        self.print_detachment_notice(&current_ref, &new_ref, target)?;
-        self.print_new_head(&current_ref, &new_ref, &target_oid, target)?;
+        if tracked_remote.is_some() {
+            eprintln!("Switched to a new branch '{}'", target);
+        } else {
+            self.print_detachment_notice(&current_ref, &new_ref, target)?;
+            self.print_new_head(&current_ref, &new_ref, &target_oid, target)?;
+        }
+
+        Ok(())
+    }
+
+    /// Files the checkout changes whose workspace content differs from the
+    /// current commit, for `checkout -m` to merge
+    fn local_changes_to_merge(
+        &self,
+        index: &Index,
+        tree_diff: &TreeDiff,
+    ) -> anyhow::Result<Vec<LocalChange>> {
+        let mut local_changes = Vec::new();
+        for (path, change) in tree_diff.changes() {
+            let TreeChangeType::Modified { old, new } = change else {
+                continue;
+            };
+            if index.entry_by_path(path).is_none() || !self.workspace().is_file(path) {
+                continue;
+            }
+
+            if self.hash_file(path, false)? != old.oid {
+                local_changes.push(LocalChange {
+                    path: path.clone(),
+                    old: old.clone(),
+                    new: new.clone(),
+                    content: self.workspace().read_file(path)?,
+                });
+            }
+        }
+
+        Ok(local_changes)
+    }
+
+    /// Merge the local changes into the files the migration just checked out
+    ///
+    /// Clean merges stay unstaged changes on top of the target version.
+    /// Conflicted ones get the current commit, target and workspace
+    /// versions as stages 1, 2 and 3, like a conflicted `bit merge`.
+    fn merge_local_changes(
+        &self,
+        index: &mut Index,
+        local_changes: Vec<LocalChange>,
+        current_name: &str,
+        target: &str,
+    ) -> anyhow::Result<()> {
+        let style = conflict_style(self)?;
+
+        for change in local_changes {
+            let base = self.blob_content(&change.old.oid)?;
+            let ours = self.blob_content(&change.new.oid)?;
+            let merged = merge_lines(
+                MergeSide {
+                    label: current_name,
+                    content: &base,
+                },
+                MergeSide {
+                    label: target,
+                    content: &ours,
+                },
+                MergeSide {
+                    label: "local",
+                    content: &change.content,
+                },
+                style,
+            );
+            if merged.clean {
+                self.workspace()
+                    .write_file(&change.path, merged.content.as_bytes())?;
+                // Zeroed stat data makes status re-hash the rewritten file
+                index.add(IndexEntry::new(
+                    change.path.clone(),
+                    change.new.oid,
+                    EntryMetadata {
+                        mode: change.new.mode,
+                        ..Default::default()
+                    },
+                ))?;
+            } else {
+                let local_blob = Blob::new(change.content, Default::default());
+                let local_oid = local_blob.object_id()?;
+                self.database().store(local_blob)?;
+                self.workspace()
+                    .write_file(&change.path, merged.content.as_bytes())?;
+                index.add_conflict_entries(vec![
+                    IndexEntry::for_conflict(
+                        change.path.clone(),
+                        change.old.oid,
+                        change.old.mode,
+                        MergeStage::Base,
+                    ),
+                    IndexEntry::for_conflict(
+                        change.path.clone(),
+                        change.new.oid,
+                        change.new.mode,
+                        MergeStage::Ours,
+                    ),
+                    IndexEntry::for_conflict(
+                        change.path.clone(),
+                        local_oid,
+                        change.new.mode,
+                        MergeStage::Theirs,
+                    ),
+                ])?;
+                writeln!(
+                    self.writer(),
+                    "CONFLICT (content): Merge conflict in {}",
+                    change.path.display()
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn blob_content(&self, oid: &ObjectId) -> anyhow::Result<String> {
+        let blob = self
+            .database()
+            .parse_object_as_blob(oid)?
+            .ok_or_else(|| anyhow::anyhow!("{} is not a blob", oid))?;
+
+        Ok(blob.content().to_string())
+    }
+
+    /// The remote whose `refs/remotes/<remote>/<target>` a checkout of
+    /// `target` should start a local branch from, along with its tip
+    ///
+    /// Only considered when `target` is a valid branch name without a local
+    /// branch; several matching remotes make the name ambiguous.
+    fn find_tracking_candidate(&self, target: &str) -> anyhow::Result<Option<(String, ObjectId)>> {
+        let Ok(branch_name) = BranchName::try_parse(target.to_string()) else {
+            return Ok(None);
+        };
+        if self.refs().branch_exists(&branch_name) {
+            return Ok(None);
+        }
+
+        let mut candidates = self.refs().find_remote_branches(&branch_name)?;
+        match candidates.len() {
+            0 => Ok(None),
+            1 => Ok(candidates.pop()),
+            count => anyhow::bail!(
+                "'{}' matched multiple ({}) remote tracking branches",
+                target,
+                count
+            ),
+        }
+    }
+
+    /// Create `refs/heads/<name>` at `oid` and record it as tracking
+    /// `<remote>/<name>` in `branch.<name>.remote` and `branch.<name>.merge`
+    fn create_tracking_branch(
+        &self,
+        name: &str,
+        remote: &str,
+        oid: &ObjectId,
+    ) -> anyhow::Result<()> {
+        let branch_name = BranchName::try_parse(name.to_string())?;
+        self.refs().create_branch(
+            branch_name,
+            oid.clone(),
+            &format!("branch: Created from refs/remotes/{}/{}", remote, name),
+        )?;
+        self.config()
+            .set(&format!("branch.{}.remote", name), remote)?;
+        self.config().set(
+            &format!("branch.{}.merge", name),
+            &format!("refs/heads/{}", name),
+        )?;
+
+        eprintln!(
+            "Branch '{}' set up to track remote branch '{}' from '{}'.",
+            name, name, remote
+        );
+
+        Ok(())
+    }
+
+    /// Restore paths in the workspace from the index, or from a revision
+    ///
+    /// Files missing from the workspace are recreated along with their parent
+    /// directories. Restoring from a revision also stages the restored content.
+    /// `paths` are pathspecs: directory prefixes select their whole subtree and
+    /// globs (`'*.txt'`) are matched against full paths.
+    pub async fn checkout_paths(
+        &mut self,
+        source: Option<&str>,
+        paths: &[PathBuf],
+    ) -> anyhow::Result<()> {
+        let index = self.index();
+        let mut index = index.lock().await;
+
+        index.rehydrate()?;
+
+        let source_entries: BTreeMap<PathBuf, DatabaseEntry> = match source {
+            Some(source) => {
+                let source_oid = self.resolve_revision(source)?;
+                let source_commit = self
+                    .database()
+                    .parse_object_as_commit(&source_oid)?
+                    .ok_or_else(|| anyhow::anyhow!("{} is not a commit", source_oid))?;
+
+                self.flatten_tree(source_commit.tree_oid()).await?
+            }
+            None => index
+                .entries()
+                .filter(|entry| entry.stage == MergeStage::Clean)
+                .map(|entry| {
+                    let source_entry = DatabaseEntry::new(entry.oid.clone(), entry.metadata.mode);
+                    (entry.name.clone(), source_entry)
+                })
+                .collect(),
+        };
+
+        self.restore_entries(&mut index, &source_entries, paths)?;
+        self.write_index(&mut index)?;
+
+        Ok(())
+    }
+
+    /// Resolve conflicted paths by taking one side of the merge
+    ///
+    /// The stage-2 (`--ours`) or stage-3 (`--theirs`) version of every
+    /// matching unmerged path is written to the workspace and staged, which
+    /// replaces the path's conflict entries with a single clean one.
+    pub async fn checkout_stage(
+        &mut self,
+        stage: MergeStage,
+        paths: &[PathBuf],
+    ) -> anyhow::Result<()> {
+        let index = self.index();
+        let mut index = index.lock().await;
+
+        index.rehydrate()?;
+
+        let source_entries: BTreeMap<PathBuf, DatabaseEntry> = index
+            .entries()
+            .filter(|entry| entry.stage == stage)
+            .map(|entry| {
+                let source_entry = DatabaseEntry::new(entry.oid.clone(), entry.metadata.mode);
+                (entry.name.clone(), source_entry)
+            })
+            .collect();
+
+        self.restore_entries(&mut index, &source_entries, paths)?;
+        self.write_index(&mut index)?;
+
+        Ok(())
+    }
+
+    /// Write the entries matching `paths` to the workspace and stage them
+    fn restore_entries(
+        &self,
+        index: &mut Index,
+        source_entries: &BTreeMap<PathBuf, DatabaseEntry>,
+        paths: &[PathBuf],
+    ) -> anyhow::Result<()> {
+        let paths = paths
+            .iter()
+            .map(|path| match path.to_str() {
+                Some(path) => PathBuf::from(unquote_path(path)),
+                None => path.clone(),
+            })
+            .collect::<Vec<_>>();
+        let pathspec = Pathspec::parse(&paths)?;
+
+        // Every item must match something before any file is touched
+        if let Some(item) = pathspec
+            .items()
+            .iter()
+            .find(|item| !source_entries.keys().any(|path| item.matches(path)))
+        {
+            anyhow::bail!(
+                "pathspec '{}' did not match any file(s) known to bit",
+                item.original().display()
+            );
+        }
+
+        let matching_entries = source_entries
+            .iter()
+            .filter(|(entry_path, _)| pathspec.matches(entry_path));
+        let mut dirs = DirCache::default();
+        for (entry_path, entry) in matching_entries {
+            let blob = self
+                .database()
+                .parse_object_as_blob(&entry.oid)?
+                .ok_or_else(|| anyhow::anyhow!("{} is not a blob", entry.oid))?;
+
+            self.workspace().write_entry_file_cached(
+                &mut dirs,
+                entry_path,
+                blob.content().as_bytes(),
+                &entry.mode,
+            )?;
+
+            let stat = self.workspace().stat_file(entry_path)?;
+            index.add(IndexEntry::new(entry_path.clone(), entry.oid.clone(), stat))?;
+        }
+
+        Ok(())
+    }
+
+    /// Start a new branch with no history
+    ///
+    /// HEAD is pointed at the (still unborn) branch while the index and the
+    /// workspace are left untouched, so the next commit becomes a root commit
+    /// which creates the branch ref.
+    pub fn checkout_orphan(&mut self, name: &str) -> anyhow::Result<()> {
+        let branch_name = BranchName::try_parse(name.to_string())?;
+
+        self.refs()
+            .set_head_to_unborn_branch(&branch_name)
+            .map_err(|err| match err {
+                RefsError::BranchAlreadyExists(_) => {
+                    anyhow::anyhow!("a branch named '{}' already exists", name)
+                }
+                err => err.into(),
+            })?;
+
+        eprintln!("Switched to a new branch '{}'", name);
 
         Ok(())
     }
@@ -0,0 +1,111 @@
+use crate::areas::index::Index;
+use crate::areas::repository::Repository;
+use crate::areas::workspace::WalkEntry;
+use crate::artifacts::core::ignore::IgnoreFiles;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Which untracked paths `bit clean` removes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CleanOptions {
+    /// Actually delete the files
+    pub force: bool,
+    /// Only list what would be deleted
+    pub dry_run: bool,
+    /// Also remove untracked directories
+    pub directories: bool,
+    /// Also remove ignored files
+    pub ignored: bool,
+}
+
+impl Repository {
+    pub async fn clean(&mut self, opts: CleanOptions) -> anyhow::Result<()> {
+        if !opts.force && !opts.dry_run {
+            anyhow::bail!("refusing to clean without -f (use -n to see what would be removed)");
+        }
+
+        let index = self.index();
+        let mut index = index.lock().await;
+
+        index.rehydrate()?;
+
+        for path in self.clean_candidates(&index, opts)? {
+            if opts.dry_run {
+                writeln!(self.writer(), "Would remove {}", path.display())?;
+            } else {
+                self.workspace().remove_path(&path)?;
+                writeln!(self.writer(), "Removing {}", path.display())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Collect the untracked paths to remove, walking the workspace once
+    ///
+    /// An untracked directory holding nothing to keep is removed as a whole,
+    /// listed with the file separator added; otherwise its entries are looked
+    /// at one by one.
+    fn clean_candidates(&self, index: &Index, opts: CleanOptions) -> anyhow::Result<Vec<PathBuf>> {
+        let ignores = if opts.ignored {
+            None
+        } else {
+            Some(IgnoreFiles::load(self.path())?)
+        };
+
+        let mut kept = HashSet::new();
+        let mut untracked = Vec::new();
+        for entry in self.workspace().walk(None, ignores.as_ref())? {
+            let (path, is_dir) = match entry? {
+                WalkEntry::File(path, _) => (path, false),
+                WalkEntry::Dir(path) => (path, true),
+                WalkEntry::Ignored { path, .. } => {
+                    kept.insert(path);
+                    continue;
+                }
+            };
+
+            if index.is_directly_tracked(&path) || index.is_conflicted_path(&path) {
+                kept.insert(path);
+                continue;
+            }
+            // Untracked directories are only touched with -d, and nested
+            // repositories are never removed
+            if is_dir && (!opts.directories || self.path().join(&path).join(".git").exists()) {
+                kept.insert(path.clone());
+            }
+            untracked.push((path, is_dir));
+        }
+
+        // Directories above a path to keep cannot be removed as a whole
+        let partly_kept = kept
+            .iter()
+            .flat_map(|path| path.ancestors().skip(1))
+            .collect::<HashSet<_>>();
+
+        // Parents sort before their entries, so a directory is settled before
+        // anything inside it
+        untracked.sort();
+        let mut settled_dirs = HashSet::<&Path>::new();
+        let mut candidates = Vec::new();
+        for (path, is_dir) in &untracked {
+            if path
+                .ancestors()
+                .skip(1)
+                .any(|dir| settled_dirs.contains(dir))
+            {
+                continue;
+            }
+            if !is_dir {
+                candidates.push(path.clone());
+            } else if kept.contains(path) {
+                settled_dirs.insert(path);
+            } else if !partly_kept.contains(path.as_path()) {
+                settled_dirs.insert(path);
+                candidates.push(path.join(""));
+            }
+        }
+
+        Ok(candidates)
+    }
+}
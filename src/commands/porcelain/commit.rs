@@ -1,12 +1,37 @@
 use crate::areas::repository::Repository;
+use crate::artifacts::objects::commit::Author;
 use crate::artifacts::objects::object::Object;
 use std::io::Write;
 
 impl Repository {
-    pub async fn commit(&mut self, message: Option<&str>) -> anyhow::Result<()> {
-        let message = match message {
-            Some(m) => m.trim().to_string(),
-            None => self.refs().read_merge_msg()?.ok_or_else(|| {
+    /// Commit the index on top of HEAD, or with `amend`, in place of HEAD
+    ///
+    /// An amended commit keeps the parents and author (with its timestamp)
+    /// of the commit it replaces, and its message unless a new one is given.
+    /// The replaced commit is left in the database, only unreferenced.
+    pub async fn commit(&mut self, message: Option<&str>, amend: bool) -> anyhow::Result<()> {
+        let merge_head = self.refs().read_merge_head()?;
+        let amended = if amend {
+            if merge_head.is_some() {
+                anyhow::bail!("you are in the middle of a merge -- cannot amend");
+            }
+            let head_oid = self
+                .refs()
+                .read_head()?
+                .ok_or_else(|| anyhow::anyhow!("you have nothing to amend"))?;
+            let head_commit = self
+                .database()
+                .parse_object_as_commit(&head_oid)?
+                .ok_or_else(|| anyhow::anyhow!("HEAD {} is not a commit", head_oid))?;
+            Some(head_commit)
+        } else {
+            None
+        };
+
+        let message = match (message, &amended) {
+            (Some(m), _) => m.trim().to_string(),
+            (None, Some(amended)) => amended.message().to_string(),
+            (None, None) => self.refs().read_merge_msg()?.ok_or_else(|| {
                 anyhow::anyhow!(
                     "no commit message provided (use -m or resolve a merge in progress)"
                 )
@@ -20,23 +45,30 @@ impl Repository {
 
             if index.has_conflicts() {
                 anyhow::bail!(
-                    "Cannot commit: unresolved merge conflicts. Resolve conflicts and stage the files first."
+                    "committing is not possible because you have unmerged files; \
+                     fix conflicts and run `bit add` first"
                 );
             }
         }
 
-        let head_parent = self.refs().read_head()?;
-        let merge_head = self.refs().read_merge_head()?;
+        let (parents, author) = match amended {
+            Some(amended) => (amended.parents().to_vec(), amended.author().clone()),
+            None => {
+                let head_parent = self.refs().read_head()?;
+                let parents = head_parent.into_iter().chain(merge_head).collect();
+                (parents, Author::load_from_env()?)
+            }
+        };
 
-        let is_root = if head_parent.is_none() {
+        let is_root = if parents.is_empty() {
             "(root-commit) "
         } else {
             ""
         };
 
-        let parents: Vec<_> = head_parent.into_iter().chain(merge_head).collect();
-
-        let commit = self.write_commit(parents, message).await?;
+        let commit = self
+            .write_commit_with_author(parents, message, author)
+            .await?;
         let commit_id = commit.object_id()?;
 
         // Clear merge state after a successful merge commit
@@ -51,6 +83,12 @@ impl Repository {
             commit.short_message()
         )?;
 
+        // Like git, tidy up the object database once it has grown enough.
+        // The commit already succeeded, so a failed gc is only reported.
+        if let Err(err) = self.gc(true) {
+            eprintln!("warning: auto gc failed: {}", err);
+        }
+
         Ok(())
     }
 }
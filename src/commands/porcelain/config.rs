@@ -0,0 +1,60 @@
+use crate::areas::config::ConfigError;
+use crate::areas::repository::Repository;
+
+/// Operation requested by `bit config`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigAction {
+    /// Print the last value of a key
+    Get { key: String },
+    /// Print every value of a key
+    GetAll { key: String },
+    /// Set a key to a single value
+    Set { key: String, value: String },
+    /// Append a value to a (possibly multi-valued) key
+    Add { key: String, value: String },
+    /// Remove the single value of a key
+    Unset { key: String },
+    /// Remove every value of a key
+    UnsetAll { key: String },
+}
+
+impl Repository {
+    pub fn configure(&self, action: &ConfigAction) -> anyhow::Result<()> {
+        match action {
+            ConfigAction::Get { key } => {
+                let value = self
+                    .config()
+                    .get(key)?
+                    .ok_or_else(|| ConfigError::KeyNotFound(key.clone()))?;
+                writeln!(self.writer(), "{}", value)?;
+            }
+            ConfigAction::GetAll { key } => {
+                let values = self.config().get_all(key)?;
+                if values.is_empty() {
+                    return Err(ConfigError::KeyNotFound(key.clone()).into());
+                }
+                for value in values {
+                    writeln!(self.writer(), "{}", value)?;
+                }
+            }
+            ConfigAction::Set { key, value } => match self.config().set(key, value) {
+                Err(ConfigError::MultipleValues(key)) => anyhow::bail!(
+                    "key {} has multiple values, use --unset-all before setting it",
+                    key
+                ),
+                result => result?,
+            },
+            ConfigAction::Add { key, value } => self.config().add(key, value)?,
+            ConfigAction::Unset { key } => match self.config().unset(key) {
+                Err(ConfigError::MultipleValues(key)) => anyhow::bail!(
+                    "key {} has multiple values, use --unset-all to remove them all",
+                    key
+                ),
+                result => result?,
+            },
+            ConfigAction::UnsetAll { key } => self.config().unset_all(key)?,
+        }
+
+        Ok(())
+    }
+}
@@ -1,63 +1,122 @@
+use crate::areas::database::DatabaseError;
 use crate::areas::index::Index;
 use crate::areas::repository::Repository;
 use crate::areas::workspace::Workspace;
-use crate::artifacts::branch::revision::Revision;
+use crate::artifacts::core::attributes::{AttributeMatcher, AttributeValue};
+use crate::artifacts::core::pathspec::Pathspec;
+use crate::artifacts::core::quote::quote_path;
+use crate::artifacts::database::database_entry::DatabaseEntry;
 use crate::artifacts::diff::diff_algorithm::{DiffAlgorithm, Hunk, MyersDiff};
-use crate::artifacts::diff::diff_target::DiffTarget;
-use crate::artifacts::diff::tree_diff::DiffFilter;
+use crate::artifacts::diff::diff_stat::{FileStat, format_stat};
+use crate::artifacts::diff::diff_target::{DiffTarget, content_lines};
+use crate::artifacts::diff::external_diff::{
+    DIFF_EXTERNAL_KEY, EXTERNAL_DIFF_ENV, ExternalDiffFile, ExternalDiffSide, external_diff,
+};
+use crate::artifacts::diff::function_context::{FunctionMatcher, function_context, xfuncname_key};
+use crate::artifacts::diff::moved_lines::{MoveHighlight, detect_moved_lines};
+use crate::artifacts::diff::rename_detection::{
+    DEFAULT_RENAME_LIMIT, DEFAULT_SIMILARITY, RENAME_LIMIT_KEY, RENAMES_KEY, RenameDetection,
+    RenameKind, RenameOptions, RenamePair, detect_renames,
+};
+use crate::artifacts::diff::textconv::{is_binary, textconv, textconv_key};
+use crate::artifacts::diff::tree_diff::{ChangeSet, DiffFilter};
 use crate::artifacts::index::index_entry::MergeStage;
 use crate::artifacts::log::path_filter::PathFilter;
 use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::objects::object_type::ObjectType;
 use crate::artifacts::status::file_change::{FileChangeType, IndexChangeType, WorkspaceChangeType};
-use crate::artifacts::status::status_info::{FileStatSet, StatusInfo};
+use crate::artifacts::status::status_info::{FileStatSet, Status, StatusInfo};
 use colored::Colorize;
 use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 
+const ATTRIBUTES_FILE: &str = ".gitattributes";
+const INFO_ATTRIBUTES_FILE: &str = ".git/info/attributes";
+
+/// How `bit diff` shows each changed file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiffFormat {
+    /// A patch with headers and hunks
+    #[default]
+    Patch,
+    /// One `<status>\t<path>` line per file (`--name-status`)
+    NameStatus,
+    /// Changed line counts with a `+`/`-` bar, then a summary (`--stat`)
+    Stat,
+}
+
 impl Repository {
+    /// Show changes between the workspace, the index and commits
+    ///
+    /// `find_renames` (`-M<n>` or `-C<n>`) asks for rename or copy detection
+    /// with the given similarity between two commits, or for staged renames
+    /// with `cached`, overriding `diff.renames`. Only the paths selected by
+    /// `paths`, relative to `cwd`, are shown, which may exclude some with
+    /// `:(exclude)<pattern>` or `:!<pattern>`.
+    #[allow(clippy::too_many_arguments)]
     pub async fn diff(
         &mut self,
         cached: bool,
-        name_status: bool,
+        format: DiffFormat,
         diff_filter: Option<&str>,
         old_revision: Option<&str>,
         new_revision: Option<&str>,
         conflict_stage: Option<MergeStage>,
+        find_renames: Option<(RenameKind, u8)>,
+        paths: &[PathBuf],
+        cwd: &Path,
     ) -> anyhow::Result<()> {
+        let prefix = cwd
+            .canonicalize()?
+            .strip_prefix(self.path())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let pathspec = Pathspec::parse_relative(paths, &prefix)?;
+
+        // parse raw diff filter to DiffFilter
+        let diff_filter = if let Some(filter) = diff_filter {
+            DiffFilter::try_parse(filter)
+        } else {
+            None
+        };
+
         // If both commits are provided, compare them
         if let (Some(old_revision), Some(new_revision)) = (old_revision, new_revision) {
             // parse revisions to commit OIDs
-            let commit_a = Revision::try_parse(old_revision)?
-                .resolve(self)?
-                .ok_or_else(|| {
-                    anyhow::anyhow!("Old revision could not be resolved: {}", old_revision)
-                })?;
-            let commit_b = Revision::try_parse(new_revision)?
-                .resolve(self)?
-                .ok_or_else(|| {
-                    anyhow::anyhow!("New revision could not be resolved: {}", new_revision)
-                })?;
-
-            // parse raw diff filter to DiffFilter
-            let diff_filter = if let Some(filter) = diff_filter {
-                DiffFilter::try_parse(filter)
-            } else {
-                None
-            };
+            let commit_a = self.resolve_revision(old_revision)?;
+            let commit_b = self.resolve_revision(new_revision)?;
 
-            return self.diff_commits(commit_a, commit_b, name_status, diff_filter);
+            let renames = self.rename_options(find_renames)?;
+            return self.diff_commits(commit_a, commit_b, format, diff_filter, renames, &pathspec);
         }
 
         let index = self.index();
         let mut index = index.lock().await;
 
         index.rehydrate()?;
-        let status_info = self.status().initialize(&mut index).await?;
+        // Only staged changes can be renames: the workspace side has no
+        // additions, untracked files aside
+        let renames = if cached {
+            self.rename_options(find_renames)?
+        } else {
+            None
+        };
+        let mut status_info = Status::new(self)
+            .with_renames(renames)
+            .initialize(&mut index)
+            .await?;
+        status_info.retain_paths(&pathspec);
 
         if !cached {
-            self.diff_index_workspace(&status_info, &index, self.workspace(), conflict_stage)?;
+            self.diff_index_workspace(
+                &status_info,
+                &index,
+                self.workspace(),
+                conflict_stage,
+                format,
+            )?;
         } else {
-            self.diff_head_index(&status_info, &index)?;
+            self.diff_head_index(&status_info, &index, format, diff_filter)?;
         }
 
         Ok(())
@@ -67,15 +126,50 @@ impl Repository {
         &self,
         commit_a: ObjectId,
         commit_b: ObjectId,
-        name_status: bool,
+        format: DiffFormat,
         diff_filter: Option<DiffFilter>,
+        renames: Option<RenameOptions>,
+        pathspec: &Pathspec,
     ) -> anyhow::Result<()> {
         let tree_diff =
             self.database()
                 .tree_diff(Some(&commit_a), Some(&commit_b), &PathFilter::empty())?;
-        let changeset = tree_diff.changes();
+        let changeset = tree_diff
+            .changes()
+            .iter()
+            .filter(|(path, _)| pathspec.matches(path))
+            .map(|(path, change_type)| (path.clone(), change_type.clone()))
+            .collect::<ChangeSet>();
+        let quote_options = self.quote_options(false)?;
+
+        let detection = match renames {
+            Some(options) => detect_renames(&changeset, options, |oid| self.load_blob_lines(oid))?,
+            None => RenameDetection::default(),
+        };
+        if let Some(limit) = detection.skipped_limit {
+            eprintln!("warning: inexact rename detection was skipped due to too many files.");
+            eprintln!(
+                "warning: you may want to set your {} variable to at least {} and retry \
+                the command.",
+                RENAME_LIMIT_KEY, limit
+            );
+        }
+
+        let mut stats = Vec::new();
+        for (path, change_type) in &changeset {
+            if let Some(pair) = detection.pair_to(path) {
+                if diff_filter.is_none_or(|filter| pair.kind.matches_filter(filter)) {
+                    match format {
+                        DiffFormat::Stat => stats.push(self.rename_stat(pair)?),
+                        _ => self.print_rename(pair, format)?,
+                    }
+                }
+                continue;
+            }
+            if detection.is_renamed_from(path) {
+                continue;
+            }
 
-        for (path, change_type) in changeset {
             // Apply diff filter if specified
             if let Some(filter) = diff_filter
                 && !change_type.matches_filter(filter)
@@ -83,67 +177,196 @@ impl Repository {
                 continue;
             }
 
-            if name_status {
-                // Print in name-status format: <status>\t<path>
-                writeln!(
-                    self.writer(),
-                    "{}\t{}",
-                    change_type.status_char(),
-                    path.display()
-                )?;
-            } else {
-                let (old_entry, new_entry) = tree_diff.get_entries(path);
-                self.print_diff(
-                    &mut DiffTarget::from_entry(path, old_entry, self.database())?,
-                    &mut DiffTarget::from_entry(path, new_entry, self.database())?,
-                )?;
+            let (old_entry, new_entry) = tree_diff.get_entries(path);
+            match format {
+                DiffFormat::NameStatus => {
+                    // Print in name-status format: <status>\t<path>
+                    writeln!(
+                        self.writer(),
+                        "{}\t{}",
+                        change_type.status_char(),
+                        quote_path(path, quote_options)
+                    )?;
+                }
+                DiffFormat::Stat => stats.push(FileStat::between(
+                    path.clone(),
+                    &DiffTarget::from_entry(path, old_entry, self.database())?,
+                    &DiffTarget::from_entry(path, new_entry, self.database())?,
+                )),
+                DiffFormat::Patch => self.print_entry_diff(path, old_entry, new_entry)?,
             }
         }
 
+        self.print_stat(&stats)
+    }
+
+    /// Print a diffstat, unless no file changed
+    fn print_stat(&self, stats: &[FileStat]) -> anyhow::Result<()> {
+        if stats.is_empty() {
+            return Ok(());
+        }
+
+        for line in format_stat(stats) {
+            writeln!(self.writer(), "{}", line)?;
+        }
+
         Ok(())
     }
 
+    /// Rename detection requested on the command line, or by `diff.renames`
+    ///
+    /// Detection stays off unless asked for; `diff.renames=copies` also looks
+    /// for copies. A `diff.renameLimit` of 0 lifts the limit.
+    fn rename_options(
+        &self,
+        find_renames: Option<(RenameKind, u8)>,
+    ) -> anyhow::Result<Option<RenameOptions>> {
+        let (copies, threshold) = match find_renames {
+            Some((kind, threshold)) => (kind == RenameKind::Copy, threshold),
+            None => match self.config().get(RENAMES_KEY)?.as_deref() {
+                Some("copies" | "copy") => (true, DEFAULT_SIMILARITY),
+                _ if self.config().get_bool(RENAMES_KEY)? == Some(true) => {
+                    (false, DEFAULT_SIMILARITY)
+                }
+                _ => return Ok(None),
+            },
+        };
+
+        Ok(Some(RenameOptions {
+            threshold,
+            copies,
+            limit: self.rename_limit()?,
+        }))
+    }
+
+    /// Most files `diff.renameLimit` allows inexact rename detection to
+    /// compare; zero or less means no limit
+    pub(crate) fn rename_limit(&self) -> anyhow::Result<usize> {
+        Ok(match self.config().get_int(RENAME_LIMIT_KEY)? {
+            Some(limit) if limit <= 0 => usize::MAX,
+            Some(limit) => limit as usize,
+            None => DEFAULT_RENAME_LIMIT,
+        })
+    }
+
+    pub(crate) fn load_blob_lines(&self, oid: &ObjectId) -> anyhow::Result<Vec<String>> {
+        let content = self
+            .database()
+            .load_blob_bytes(oid)?
+            .ok_or_else(|| anyhow::anyhow!("{} is not a blob", oid))?;
+
+        Ok(content_lines(&content))
+    }
+
+    /// Print a renamed or copied file as a `R<score>` status line with both
+    /// paths, or as a patch with `similarity index` and `rename from`/`to`
+    /// headers
+    fn print_rename(&self, pair: &RenamePair, format: DiffFormat) -> anyhow::Result<()> {
+        if format == DiffFormat::NameStatus {
+            let quote_options = self.quote_options(false)?;
+            writeln!(
+                self.writer(),
+                "{}{:03}\t{}\t{}",
+                pair.kind.status_char(),
+                pair.similarity,
+                quote_path(&pair.old_path, quote_options),
+                quote_path(&pair.new_path, quote_options)
+            )?;
+            return Ok(());
+        }
+
+        let mut a = DiffTarget::from_entry(&pair.old_path, Some(&pair.old_entry), self.database())?;
+        let mut b = DiffTarget::from_entry(&pair.new_path, Some(&pair.new_entry), self.database())?;
+        let attributes = self.path_attributes(&b.file)?;
+        let binary = self.convert_for_diff(&mut a, &mut b, &attributes)?;
+        let functions = self.function_matcher(&b.file, &attributes)?;
+        a.file = self.diff_prefix().src_path(&a.file);
+        b.file = self.diff_prefix().dst_path(&b.file);
+
+        let verb = match pair.kind {
+            RenameKind::Rename => "rename",
+            RenameKind::Copy => "copy",
+        };
+        for header in [
+            format!("diff --git {} {}", a.file.display(), b.file.display()),
+            format!("similarity index {}%", pair.similarity),
+            format!("{} from {}", verb, pair.old_path.display()),
+            format!("{} to {}", verb, pair.new_path.display()),
+        ] {
+            writeln!(self.writer(), "{}", header.bold())?;
+        }
+        self.print_diff_mode(&a, &b)?;
+        self.print_diff_content(&a, &b, binary, &functions)?;
+
+        Ok(())
+    }
+
+    /// Show the unstaged changes; with `--stat` or `--name-status`,
+    /// conflicted paths are left out
     fn diff_index_workspace(
         &self,
         status_info: &StatusInfo,
         index: &Index,
         workspace: &Workspace,
         conflict_stage: Option<MergeStage>,
+        format: DiffFormat,
     ) -> anyhow::Result<()> {
         let mut paths: BTreeSet<PathBuf> = status_info.conflicts.keys().cloned().collect();
         for file in status_info.workspace_changeset.keys() {
             paths.insert(file.clone());
         }
 
+        let mut stats = Vec::new();
         for file in &paths {
             if index.is_conflicted_path(file) {
-                self.print_conflict_diff(
-                    file,
-                    conflict_stage,
-                    index,
-                    workspace,
-                    &status_info.file_stats,
-                )?;
-            } else {
-                match status_info.workspace_changeset.get(file) {
-                    Some(FileChangeType::Workspace(WorkspaceChangeType::Modified)) => {
-                        self.print_diff(
-                            &mut DiffTarget::from_index(file, index, self.database())?,
-                            &mut DiffTarget::from_file(file, workspace, &status_info.file_stats)?,
-                        )?;
-                    }
-                    Some(FileChangeType::Workspace(WorkspaceChangeType::Deleted)) => {
-                        self.print_diff(
-                            &mut DiffTarget::from_index(file, index, self.database())?,
-                            &mut DiffTarget::from_nothing(file)?,
+                match format {
+                    DiffFormat::NameStatus => {
+                        writeln!(
+                            self.writer(),
+                            "U\t{}",
+                            quote_path(file, self.quote_options(false)?)
                         )?;
                     }
-                    _ => {}
+                    DiffFormat::Stat => stats.push(FileStat::unmerged(file.clone())),
+                    DiffFormat::Patch => self.print_conflict_diff(
+                        file,
+                        conflict_stage,
+                        index,
+                        workspace,
+                        &status_info.file_stats,
+                    )?,
                 }
+                continue;
+            }
+
+            let (status_char, mut a, mut b) = match status_info.workspace_changeset.get(file) {
+                Some(FileChangeType::Workspace(WorkspaceChangeType::Modified)) => (
+                    'M',
+                    DiffTarget::from_index(file, index, self.database())?,
+                    DiffTarget::from_file(file, workspace, &status_info.file_stats)?,
+                ),
+                Some(FileChangeType::Workspace(WorkspaceChangeType::Deleted)) => (
+                    'D',
+                    DiffTarget::from_index(file, index, self.database())?,
+                    DiffTarget::from_nothing(file)?,
+                ),
+                _ => continue,
+            };
+            match format {
+                DiffFormat::NameStatus => {
+                    writeln!(
+                        self.writer(),
+                        "{}\t{}",
+                        status_char,
+                        quote_path(file, self.quote_options(false)?)
+                    )?;
+                }
+                DiffFormat::Stat => stats.push(FileStat::between(file.clone(), &a, &b)),
+                DiffFormat::Patch => self.print_diff(&mut a, &mut b)?,
             }
         }
 
-        Ok(())
+        self.print_stat(&stats)
     }
 
     fn print_conflict_diff(
@@ -172,38 +395,154 @@ impl Repository {
         Ok(())
     }
 
-    fn diff_head_index(&self, status_info: &StatusInfo, index: &Index) -> anyhow::Result<()> {
-        status_info
-            .index_changeset
-            .iter()
-            .filter_map(|(file, change)| match *change {
-                FileChangeType::Index(IndexChangeType::Added) => {
-                    Some((file, IndexChangeType::Added))
-                }
-                FileChangeType::Index(IndexChangeType::Modified) => {
-                    Some((file, IndexChangeType::Modified))
-                }
-                FileChangeType::Index(IndexChangeType::Deleted) => {
-                    Some((file, IndexChangeType::Deleted))
+    /// Show the staged changes in the given format
+    ///
+    /// Staged renames found by the status scan are shown as such, with the
+    /// old path taken from HEAD.
+    fn diff_head_index(
+        &self,
+        status_info: &StatusInfo,
+        index: &Index,
+        format: DiffFormat,
+        diff_filter: Option<DiffFilter>,
+    ) -> anyhow::Result<()> {
+        let quote_options = self.quote_options(false)?;
+
+        let mut stats = Vec::new();
+        for (file, change) in &status_info.index_changeset {
+            let FileChangeType::Index(change) = change else {
+                continue;
+            };
+            let (status_char, filter_flag) = match change {
+                IndexChangeType::Added => ('A', DiffFilter::ADDED),
+                IndexChangeType::Modified => ('M', DiffFilter::MODIFIED),
+                IndexChangeType::Deleted => ('D', DiffFilter::DELETED),
+                IndexChangeType::Renamed => ('R', DiffFilter::RENAMED),
+                IndexChangeType::None => continue,
+            };
+            if diff_filter.is_some_and(|filter| !filter.contains(filter_flag)) {
+                continue;
+            }
+
+            if *change == IndexChangeType::Renamed {
+                let pair = self.staged_rename(file, status_info, index)?;
+                match format {
+                    DiffFormat::Stat => stats.push(self.rename_stat(&pair)?),
+                    _ => self.print_rename(&pair, format)?,
                 }
-                _ => None,
-            })
-            .map(|(file, change)| match change {
-                IndexChangeType::Added => self.print_diff(
-                    &mut DiffTarget::from_nothing(file)?,
-                    &mut DiffTarget::from_index(file, index, self.database())?,
-                ),
-                IndexChangeType::Modified => self.print_diff(
-                    &mut DiffTarget::from_head(file, &status_info.head_tree, self.database())?,
-                    &mut DiffTarget::from_index(file, index, self.database())?,
-                ),
-                IndexChangeType::Deleted => self.print_diff(
-                    &mut DiffTarget::from_head(file, &status_info.head_tree, self.database())?,
-                    &mut DiffTarget::from_nothing(file)?,
-                ),
-                _ => unreachable!(),
-            })
-            .collect::<anyhow::Result<Vec<_>>>()?;
+                continue;
+            }
+            if format == DiffFormat::NameStatus {
+                writeln!(
+                    self.writer(),
+                    "{}\t{}",
+                    status_char,
+                    quote_path(file, quote_options)
+                )?;
+                continue;
+            }
+
+            let mut a = match change {
+                IndexChangeType::Added => DiffTarget::from_nothing(file)?,
+                _ => DiffTarget::from_head(file, &status_info.head_tree, self.database())?,
+            };
+            let mut b = match change {
+                IndexChangeType::Deleted => DiffTarget::from_nothing(file)?,
+                _ => DiffTarget::from_index(file, index, self.database())?,
+            };
+            match format {
+                DiffFormat::Stat => stats.push(FileStat::between(file.clone(), &a, &b)),
+                _ => self.print_diff(&mut a, &mut b)?,
+            }
+        }
+
+        self.print_stat(&stats)
+    }
+
+    /// Diffstat of a renamed or copied file, labelled `<old> => <new>`
+    fn rename_stat(&self, pair: &RenamePair) -> anyhow::Result<FileStat> {
+        let a = DiffTarget::from_entry(&pair.old_path, Some(&pair.old_entry), self.database())?;
+        let b = DiffTarget::from_entry(&pair.new_path, Some(&pair.new_entry), self.database())?;
+        let label = format!("{} => {}", pair.old_path.display(), pair.new_path.display());
+
+        Ok(FileStat::between(PathBuf::from(label), &a, &b))
+    }
+
+    /// The staged rename to `file`, from its old version in HEAD
+    fn staged_rename(
+        &self,
+        file: &Path,
+        status_info: &StatusInfo,
+        index: &Index,
+    ) -> anyhow::Result<RenamePair> {
+        let old_path = status_info
+            .renames
+            .get(file)
+            .ok_or_else(|| anyhow::anyhow!("no staged rename to {}", file.display()))?;
+        let old_entry = status_info
+            .head_tree
+            .get(old_path)
+            .ok_or_else(|| anyhow::anyhow!("{} is not in HEAD", old_path.display()))?;
+        let new_entry = index
+            .entry_by_path(file)
+            .ok_or_else(|| anyhow::anyhow!("{} is not in the index", file.display()))?;
+
+        Ok(RenamePair {
+            kind: RenameKind::Rename,
+            old_path: old_path.clone(),
+            new_path: file.to_path_buf(),
+            old_entry: old_entry.clone(),
+            new_entry: DatabaseEntry::new(new_entry.oid.clone(), new_entry.metadata.mode),
+            similarity: status_info.rename_scores.get(file).copied().unwrap_or(100),
+        })
+    }
+
+    /// Print the diff between two versions of a tree entry
+    ///
+    /// Submodule (gitlink) changes are summarized as
+    /// `Submodule <path> <old>..<new>` since their commits live in another
+    /// repository.
+    pub fn print_entry_diff(
+        &self,
+        path: &Path,
+        old_entry: Option<&DatabaseEntry>,
+        new_entry: Option<&DatabaseEntry>,
+    ) -> anyhow::Result<()> {
+        let is_gitlink = |entry: Option<&DatabaseEntry>| entry.is_some_and(|e| e.is_gitlink());
+        if is_gitlink(old_entry) || is_gitlink(new_entry) {
+            return self.print_submodule_summary(path, old_entry, new_entry);
+        }
+
+        self.print_diff(
+            &mut DiffTarget::from_entry(path, old_entry, self.database())?,
+            &mut DiffTarget::from_entry(path, new_entry, self.database())?,
+        )
+    }
+
+    fn print_submodule_summary(
+        &self,
+        path: &Path,
+        old_entry: Option<&DatabaseEntry>,
+        new_entry: Option<&DatabaseEntry>,
+    ) -> anyhow::Result<()> {
+        let short_oid = |entry: Option<&DatabaseEntry>| match entry {
+            Some(entry) if entry.is_gitlink() => entry.oid.to_short_oid(),
+            _ => "0".repeat(7),
+        };
+        let note = match (old_entry, new_entry) {
+            (None, _) => " (new submodule)",
+            (_, None) => " (submodule deleted)",
+            _ => "",
+        };
+
+        writeln!(
+            self.writer(),
+            "Submodule {} {}..{}{}",
+            path.display(),
+            short_oid(old_entry),
+            short_oid(new_entry),
+            note
+        )?;
 
         Ok(())
     }
@@ -212,9 +551,15 @@ impl Repository {
         if a.oid == b.oid && a.mode == b.mode {
             return Ok(());
         }
+        if let Some(command) = self.external_diff_command()? {
+            return self.print_external_diff(&command, a, b);
+        }
 
-        a.file = Path::new("a").join(&a.file);
-        b.file = Path::new("b").join(&b.file);
+        let attributes = self.path_attributes(&b.file)?;
+        let binary = self.convert_for_diff(a, b, &attributes)?;
+        let functions = self.function_matcher(&b.file, &attributes)?;
+        a.file = self.diff_prefix().src_path(&a.file);
+        b.file = self.diff_prefix().dst_path(&b.file);
 
         writeln!(
             self.writer(),
@@ -222,11 +567,140 @@ impl Repository {
             format!("diff --git {} {}", a.file.display(), b.file.display()).bold()
         )?;
         self.print_diff_mode(a, b)?;
-        self.print_diff_content(a, b)?;
+        self.print_diff_content(a, b, binary, &functions)?;
 
         Ok(())
     }
 
+    /// External diff program, when enabled: `GIT_EXTERNAL_DIFF`, then
+    /// `diff.external`
+    fn external_diff_command(&self) -> anyhow::Result<Option<String>> {
+        if !self.ext_diff() {
+            return Ok(None);
+        }
+        if let Ok(command) = std::env::var(EXTERNAL_DIFF_ENV)
+            && !command.is_empty()
+        {
+            return Ok(Some(command));
+        }
+
+        Ok(self.config().get(DIFF_EXTERNAL_KEY)?)
+    }
+
+    /// Hand both sides of a changed file to the external diff program and
+    /// print its output in place of the internal diff
+    fn print_external_diff(
+        &self,
+        command: &str,
+        a: &DiffTarget,
+        b: &DiffTarget,
+    ) -> anyhow::Result<()> {
+        let (a_oid, b_oid) = (a.oid.to_string(), b.oid.to_string());
+        let old = ExternalDiffSide {
+            file: self.external_diff_file(a)?,
+            oid: &a_oid,
+            mode: a.mode,
+        };
+        let new = ExternalDiffSide {
+            file: self.external_diff_file(b)?,
+            oid: &b_oid,
+            mode: b.mode,
+        };
+
+        let output = external_diff(command, &b.file, &old, &new)?;
+        self.writer().write_all(&output)?;
+
+        Ok(())
+    }
+
+    /// Content of one side for the external diff program
+    ///
+    /// Blobs are copied to temporary files; content only found in the
+    /// workspace has not been stored yet and is passed in place.
+    fn external_diff_file(&self, target: &DiffTarget) -> anyhow::Result<ExternalDiffFile> {
+        if target.mode.is_none() {
+            return Ok(ExternalDiffFile::Missing);
+        }
+
+        match self.database().load_raw(&target.oid) {
+            Ok((ObjectType::Blob, content)) => {
+                Ok(ExternalDiffFile::temporary(&target.file, &content)?)
+            }
+            Ok(_) => Ok(ExternalDiffFile::Existing(self.path().join(&target.file))),
+            Err(DatabaseError::ReadObject { source, .. })
+                if source.kind() == std::io::ErrorKind::NotFound =>
+            {
+                Ok(ExternalDiffFile::Existing(self.path().join(&target.file)))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Prepare both sides of a diff according to the path's `diff` attribute
+    ///
+    /// A driver with a `diff.<driver>.textconv` command replaces the content
+    /// of each side by the command's output (when textconv is enabled).
+    /// Returns whether the content is to be reported as binary: `-diff`
+    /// forces it, `diff` rules it out, and otherwise a NUL byte decides.
+    fn convert_for_diff(
+        &self,
+        a: &mut DiffTarget,
+        b: &mut DiffTarget,
+        attributes: &AttributeMatcher,
+    ) -> anyhow::Result<bool> {
+        match attributes.get(&b.file, "diff") {
+            Some(AttributeValue::Unset) => return Ok(true),
+            Some(AttributeValue::Set) => return Ok(false),
+            Some(AttributeValue::Value(driver)) if self.textconv() => {
+                if let Some(command) = self.config().get(&textconv_key(driver))? {
+                    for (has_file, content, data) in [
+                        (a.mode.is_some(), &a.content, &mut a.data),
+                        (b.mode.is_some(), &b.content, &mut b.data),
+                    ] {
+                        if has_file {
+                            *data = textconv(&command, content)?;
+                        }
+                    }
+                    return Ok(false);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(is_binary(&a.content) || is_binary(&b.content))
+    }
+
+    /// How hunk headers of `path` find their function context: through the
+    /// `diff.<driver>.xfuncname` of its diff driver, if any
+    fn function_matcher(
+        &self,
+        path: &Path,
+        attributes: &AttributeMatcher,
+    ) -> anyhow::Result<FunctionMatcher> {
+        if let Some(AttributeValue::Value(driver)) = attributes.get(path, "diff") {
+            let key = xfuncname_key(driver);
+            if let Some(xfuncname) = self.config().get(&key)? {
+                return Ok(FunctionMatcher::from_xfuncname(&key, &xfuncname)?);
+            }
+        }
+
+        Ok(FunctionMatcher::default())
+    }
+
+    /// Attribute rules for `path`: the `.gitattributes` files from the
+    /// workspace root down to its directory, then `.git/info/attributes`
+    fn path_attributes(&self, path: &Path) -> anyhow::Result<AttributeMatcher> {
+        let mut matcher = AttributeMatcher::empty();
+        let mut dirs = path.ancestors().skip(1).collect::<Vec<_>>();
+        dirs.reverse();
+        for dir in dirs {
+            matcher.add_file(dir, &self.path().join(dir).join(ATTRIBUTES_FILE))?;
+        }
+        matcher.add_file(Path::new(""), &self.path().join(INFO_ATTRIBUTES_FILE))?;
+
+        Ok(matcher)
+    }
+
     fn print_diff_mode(&self, a: &DiffTarget, b: &DiffTarget) -> anyhow::Result<()> {
         if a.mode.is_none() {
             writeln!(
@@ -256,7 +730,13 @@ impl Repository {
         Ok(())
     }
 
-    fn print_diff_content(&self, a: &DiffTarget, b: &DiffTarget) -> anyhow::Result<()> {
+    fn print_diff_content(
+        &self,
+        a: &DiffTarget,
+        b: &DiffTarget,
+        binary: bool,
+        functions: &FunctionMatcher,
+    ) -> anyhow::Result<()> {
         if a.oid == b.oid {
             return Ok(());
         }
@@ -267,6 +747,15 @@ impl Repository {
         }
 
         writeln!(self.writer(), "{}", oid_range.to_string().bold())?;
+        if binary {
+            writeln!(
+                self.writer(),
+                "Binary files {} and {} differ",
+                a.diff_path().display(),
+                b.diff_path().display()
+            )?;
+            return Ok(());
+        }
         writeln!(
             self.writer(),
             "{}",
@@ -279,25 +768,41 @@ impl Repository {
         )?;
 
         let hunks = MyersDiff::new(&a.data, &b.data).flatten_diff();
-        for hunk in hunks {
-            self.print_diff_hunk(&hunk)?;
+        let edits = hunks.iter().flat_map(Hunk::edits).collect::<Vec<_>>();
+        let mut highlights = detect_moved_lines(&edits, self.color_moved()).into_iter();
+        for hunk in hunks.iter() {
+            let hunk_highlights = highlights
+                .by_ref()
+                .take(hunk.edits().len())
+                .collect::<Vec<_>>();
+            let context = function_context(&a.data, hunk.a_start(), functions);
+            self.print_diff_hunk(hunk, &hunk_highlights, context.as_deref())?;
         }
 
         Ok(())
     }
 
-    fn print_diff_hunk(&self, hunk: &Hunk<String>) -> anyhow::Result<()> {
+    fn print_diff_hunk(
+        &self,
+        hunk: &Hunk<String>,
+        highlights: &[MoveHighlight],
+        context: Option<&str>,
+    ) -> anyhow::Result<()> {
         let a_offset = format!("{},{}", hunk.a_start(), hunk.a_size());
         let b_offset = format!("{},{}", hunk.b_start(), hunk.b_size());
 
-        writeln!(
+        write!(
             self.writer(),
             "{}",
             format!("@@ -{a_offset} +{b_offset} @@").cyan()
         )?;
+        match context {
+            Some(context) => writeln!(self.writer(), " {}", context)?,
+            None => writeln!(self.writer())?,
+        }
 
-        for edit in hunk.edits() {
-            writeln!(self.writer(), "{}", edit)?;
+        for (edit, highlight) in hunk.edits().iter().zip(highlights) {
+            writeln!(self.writer(), "{}", highlight.paint(edit))?;
         }
 
         Ok(())
@@ -0,0 +1,50 @@
+use crate::areas::refs::HEAD_REF_NAME;
+use crate::areas::repository::Repository;
+
+/// Fixed date of the `From <oid>` line, which git uses to mark mbox patches
+const MBOX_FROM_DATE: &str = "Mon Sep 17 00:00:00 2001";
+
+impl Repository {
+    /// Print a commit as an email patch, like `git format-patch --stdout -1`
+    ///
+    /// The message subject becomes the `Subject: [PATCH]` header and the rest
+    /// of the message the mail body, followed by the commit's diff.
+    pub fn format_patch(&self, revision: Option<&str>) -> anyhow::Result<()> {
+        let revision = revision.unwrap_or(HEAD_REF_NAME);
+        let oid = self.resolve_revision(revision)?;
+        let commit = self
+            .database()
+            .parse_object_as_commit(&oid)?
+            .ok_or_else(|| anyhow::anyhow!("{} is not a commit", oid))?;
+        if commit.is_merge_commit() {
+            anyhow::bail!("cannot format a patch for merge commit {}", oid);
+        }
+
+        let author = commit.author();
+        let subject = commit.short_message();
+        let body = commit
+            .message()
+            .split_once('\n')
+            .map(|(_, body)| body.trim())
+            .unwrap_or_default();
+
+        writeln!(self.writer(), "From {} {}", oid, MBOX_FROM_DATE)?;
+        writeln!(self.writer(), "From: {}", author.display_name())?;
+        writeln!(self.writer(), "Date: {}", author.timestamp().to_rfc2822())?;
+        writeln!(self.writer(), "Subject: [PATCH] {}", subject)?;
+        writeln!(self.writer())?;
+        if !body.is_empty() {
+            writeln!(self.writer(), "{}", body)?;
+        }
+        writeln!(self.writer(), "---")?;
+
+        self.print_commit_diff(&commit, None, None)?;
+
+        writeln!(self.writer(), "-- ")?;
+        writeln!(self.writer(), "bit {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(self.writer())?;
+        self.writer().flush()?;
+
+        Ok(())
+    }
+}
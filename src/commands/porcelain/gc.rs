@@ -0,0 +1,66 @@
+use crate::areas::database::Database;
+use crate::areas::repository::Repository;
+
+/// Loose object count above which `gc --auto` repacks (`gc.auto`)
+const DEFAULT_GC_AUTO: i64 = 6700;
+/// Pack count above which `gc --auto` consolidates the packs (`gc.autoPackLimit`)
+const DEFAULT_AUTO_PACK_LIMIT: i64 = 50;
+
+impl Repository {
+    /// Pack the loose objects and consolidate the packs into one
+    ///
+    /// Packs marked with a `.keep` file are left alone. With `auto`, nothing
+    /// happens unless there are (by estimate) more loose objects than
+    /// `gc.auto`, which only packs the loose objects, or more packs than
+    /// `gc.autoPackLimit`, which also consolidates the packs. A `gc.auto` of 0
+    /// disables automatic packing altogether.
+    pub fn gc(&self, auto: bool) -> anyhow::Result<()> {
+        let consolidate = if auto {
+            let Some(consolidate) = self.auto_gc_mode()? else {
+                return Ok(());
+            };
+            eprintln!("Auto packing the repository for optimum performance.");
+            consolidate
+        } else {
+            true
+        };
+
+        if let Some((count, name)) = self.database().repack(consolidate)? {
+            writeln!(self.writer(), "Packed {} objects into {}.pack", count, name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether `gc --auto` has work to do, and if so whether it consolidates
+    /// the packs as well
+    fn auto_gc_mode(&self) -> anyhow::Result<Option<bool>> {
+        let gc_auto = self.config().get_int("gc.auto")?.unwrap_or(DEFAULT_GC_AUTO);
+        if gc_auto <= 0 {
+            return Ok(None);
+        }
+
+        let pack_limit = self
+            .config()
+            .get_int("gc.autoPackLimit")?
+            .unwrap_or(DEFAULT_AUTO_PACK_LIMIT);
+        let packs = self
+            .database()
+            .pack_indexes()?
+            .iter()
+            .filter(|index| !Database::is_kept_pack(index))
+            .count();
+        if pack_limit > 0 && packs as i64 > pack_limit {
+            return Ok(Some(true));
+        }
+
+        // Objects spread evenly over the 256 fan-out directories, so one of
+        // them stands in for the rest
+        let sample_limit = (gc_auto + 255) / 256;
+        if self.database().sampled_loose_objects()? as i64 > sample_limit {
+            return Ok(Some(false));
+        }
+
+        Ok(None)
+    }
+}
@@ -1,11 +1,20 @@
-use crate::areas::repository::Repository;
+use crate::areas::repository::{IGNORE_CASE_KEY, Repository};
+use crate::artifacts::branch::branch_name::BranchName;
 use anyhow::Context;
 use std::fs;
 
 const DEFAULT_BRANCH: &str = "master";
+const DEFAULT_BRANCH_KEY: &str = "init.defaultBranch";
+const CASE_PROBE_FILE: &str = "CoNfIg.CaSe";
 
 impl Repository {
-    pub async fn init(&mut self) -> anyhow::Result<()> {
+    /// Create the repository layout, or fill in what an existing one lacks
+    ///
+    /// Re-running `init` keeps the existing config, refs and a valid HEAD, and
+    /// reports the repository as reinitialized.
+    pub async fn init(&mut self, initial_branch: Option<&str>) -> anyhow::Result<()> {
+        let reinitialized = self.git_dir().exists();
+
         fs::create_dir_all(self.database().objects_path())
             .context("Failed to create .git/objects directory")?;
 
@@ -15,17 +24,33 @@ impl Repository {
         fs::create_dir_all(self.refs().heads_path())
             .context("Failed to create .git/refs/heads directory")?;
 
-        self.refs()
-            .set_head(
-                DEFAULT_BRANCH,
-                format!("ref: refs/heads/{}", DEFAULT_BRANCH),
-            )
-            .context("Failed to create initial HEAD reference")?;
-
-        // make sure the DEFAULT_BRANCH file exists
-        let head_ref_path = self.refs().heads_path().join(DEFAULT_BRANCH);
-        if !head_ref_path.exists() {
-            fs::write(&head_ref_path, b"").context("Failed to create default branch file")?;
+        // Keep a valid HEAD when re-initializing, but repair a missing or empty one
+        if self.refs().verify_head().is_ok() {
+            if let Some(initial_branch) = initial_branch {
+                eprintln!(
+                    "warning: re-init: ignored --initial-branch={}",
+                    initial_branch
+                );
+            }
+        } else {
+            let initial_branch = self.initial_branch(initial_branch)?;
+
+            self.refs()
+                .set_head(
+                    initial_branch.as_ref(),
+                    format!("ref: refs/heads/{}", initial_branch),
+                )
+                .context("Failed to create initial HEAD reference")?;
+
+            // make sure the initial branch file exists
+            let head_ref_path = self.refs().heads_path().join(initial_branch.as_ref());
+            if !head_ref_path.exists() {
+                fs::write(&head_ref_path, b"").context("Failed to create initial branch file")?;
+            }
+        }
+
+        if self.config().get(IGNORE_CASE_KEY)?.is_none() && self.is_case_insensitive_fs()? {
+            self.config().set(IGNORE_CASE_KEY, "true")?;
         }
 
         let index = self.index();
@@ -35,12 +60,49 @@ impl Repository {
             fs::write(index.path(), b"").context("Failed to create .git/index file")?;
         }
 
-        writeln!(
-            self.writer(),
-            "Initialized empty Git repository in {}",
-            self.path().display()
-        )?;
+        if reinitialized {
+            writeln!(
+                self.writer(),
+                "Reinitialized existing Git repository in {}",
+                self.path().display()
+            )?;
+        } else {
+            writeln!(
+                self.writer(),
+                "Initialized empty Git repository in {}",
+                self.path().display()
+            )?;
+        }
 
         Ok(())
     }
+
+    /// Whether the filesystem holding the repository ignores case
+    ///
+    /// Writes a probe file in the git directory and checks whether it can be
+    /// found under a differently cased name.
+    fn is_case_insensitive_fs(&self) -> anyhow::Result<bool> {
+        let probe = self.git_dir().join(CASE_PROBE_FILE);
+        fs::write(&probe, b"").context("Failed to probe filesystem case sensitivity")?;
+        let insensitive = self.git_dir().join(CASE_PROBE_FILE.to_lowercase()).exists();
+        fs::remove_file(&probe)?;
+
+        Ok(insensitive)
+    }
+
+    /// Pick the branch HEAD points at in a new repository
+    ///
+    /// An explicit name wins over `init.defaultBranch`, which is looked up in
+    /// the repository config first and the global config next.
+    fn initial_branch(&self, initial_branch: Option<&str>) -> anyhow::Result<BranchName> {
+        let name = match initial_branch {
+            Some(name) => name.to_string(),
+            None => self
+                .config()
+                .get_or_global(DEFAULT_BRANCH_KEY)?
+                .unwrap_or_else(|| DEFAULT_BRANCH.to_string()),
+        };
+
+        Ok(BranchName::try_parse(name)?)
+    }
 }
@@ -1,16 +1,28 @@
 use crate::areas::refs::HEAD_REF_NAME;
 use crate::areas::repository::Repository;
 use crate::artifacts::branch::branch_name::SymRefName;
-use crate::artifacts::branch::revision::Revision;
+use crate::artifacts::branch::revision::{Revision, UnknownRevision};
+use crate::artifacts::core::quote::quote_path;
+use crate::artifacts::database::database_entry::DatabaseEntry;
+use crate::artifacts::diff::diff_stat::{FileStat, format_stat};
 use crate::artifacts::diff::diff_target::DiffTarget;
-use crate::artifacts::diff::tree_diff::TreeDiff;
+use crate::artifacts::diff::tree_diff::{DiffFilter, TreeChangeType, TreeDiff};
+use crate::artifacts::log::graph::{GraphWriter, LogGraph};
+use crate::artifacts::log::message_filter::MessageFilter;
 use crate::artifacts::log::path_filter::PathFilter;
+use crate::artifacts::log::pretty_format::{Template, render_template};
 use crate::artifacts::log::rev_list::{CommitsDiffs, RevList};
-use crate::artifacts::objects::commit::Commit;
+use crate::artifacts::log::simplify::simplify_by_decoration;
+use crate::artifacts::objects::commit::{Commit, format_extra_header};
 use crate::artifacts::objects::object::Object;
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::objects::object_type::ObjectType;
 use crate::{CommitDecoration, CommitDisplayFormat};
 use colored::Colorize;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::PathBuf;
+use std::rc::Rc;
 
 const RANGE_REGEX: &str = r"^(?P<excluded>.*)\.\.(?P<included>.*)$";
 const EXCLUDED_REGEX: &str = r"^\^(?P<excluded>.+)$";
@@ -84,9 +96,61 @@ pub struct LogOptions {
     pub target_files: Option<Vec<PathBuf>>,
     pub oneline: bool,
     pub abbrev_commit: bool,
+    /// Least number of characters in abbreviated object IDs; they grow
+    /// until unique
+    pub abbrev: usize,
     pub format: CommitDisplayFormat,
+    /// Placeholder template of `--format`, used instead of `format`
+    pub template: Option<Template>,
     pub decorate: CommitDecoration,
     pub patch: bool,
+    /// Print a diffstat of each commit (before its patch, if any)
+    pub stat: bool,
+    /// Print the raw `:mode mode oid oid status\tpath` lines of each commit
+    pub raw: bool,
+    /// Only show commits (and changes) of the selected kinds
+    pub diff_filter: Option<DiffFilter>,
+    pub graph: bool,
+    /// Message patterns of `--grep`, any of which must match
+    pub grep: Vec<String>,
+    pub invert_grep: bool,
+    /// Require every `--grep` pattern to match instead of any of them
+    pub all_match: bool,
+    /// Patterns of `--author`, any of which must match `Name <email>`
+    pub author: Vec<String>,
+    pub ignore_case: bool,
+    /// Also start from every ref (branches, tags, remotes) and HEAD
+    pub all: bool,
+    /// Print the number of selected commits instead of the commits
+    pub count: bool,
+    /// Show at most this many commits
+    pub max_count: Option<usize>,
+    /// Leave out this many commits before showing any
+    pub skip: Option<usize>,
+    /// Only follow the first parent of merges, and diff merges against it
+    pub first_parent: bool,
+    /// Only show commits pointed at by refs, and the merges joining them
+    pub simplify_by_decoration: bool,
+    /// Print the parents' IDs after each commit ID
+    pub parents: bool,
+    /// Also show the excluded parents of the listed commits, marked with `- `
+    pub boundary: bool,
+}
+
+impl LogOptions {
+    /// Whether the changes of `commit` are left out of the diff output
+    ///
+    /// Merges have no single diff, unless `--first-parent` picks the first
+    /// parent to diff against.
+    fn skips_diff_of(&self, commit: &Commit) -> bool {
+        commit.is_merge_commit() && !self.first_parent
+    }
+
+    /// Parents printed after the ID of `commit` with `--parents`: all of
+    /// them, as Git does even with `--first-parent`
+    fn shown_parents<'c>(&self, commit: &'c Commit) -> &'c [ObjectId] {
+        if self.parents { commit.parents() } else { &[] }
+    }
 }
 
 impl Repository {
@@ -94,119 +158,448 @@ impl Repository {
         self.set_reverse_refs(self.refs().reverse_refs()?);
         self.set_current_ref(self.refs().current_ref(None)?);
 
-        let target_revisions =
-            opts.target_revisions
-                .clone()
-                .unwrap_or(vec![LogRevisionTargets::IncludedRevision(
-                    Revision::try_parse(HEAD_REF_NAME)?,
-                )]);
-        let rev_list = RevList::new(self, target_revisions, opts.target_files.clone());
-
-        match rev_list {
-            Ok(rev_list) => {
-                let commits_diffs = if opts.target_files.is_some() {
-                    Some(rev_list.commit_diffs().clone())
-                } else {
-                    None
-                };
-                for commit in rev_list.into_iter() {
-                    // Display the commit in medium format
-                    self.show_commit(&commit, commits_diffs.as_ref(), opts)?;
-                    writeln!(self.writer())?;
-                }
+        let mut target_revisions = opts.target_revisions.clone().unwrap_or_default();
+        if opts.all {
+            target_revisions.extend(self.all_ref_targets()?);
+        }
+        if target_revisions.is_empty() {
+            // The current branch has no history until its first commit
+            if self.refs().read_head()?.is_none() {
+                return self.show_no_commits(opts);
             }
-            Err(_) => {
-                // No commits to show
-                writeln!(self.writer(), "No commits to show.")?;
+            target_revisions.push(LogRevisionTargets::IncludedRevision(Revision::try_parse(
+                HEAD_REF_NAME,
+            )?));
+        }
+        let message_filter = if opts.grep.is_empty() && opts.author.is_empty() {
+            None
+        } else {
+            Some(
+                MessageFilter::new(&opts.grep, opts.invert_grep, opts.ignore_case)?
+                    .with_authors(&opts.author, opts.ignore_case)?
+                    .with_all_match(opts.all_match),
+            )
+        };
+        // Commits dropped after the traversal must not count towards the
+        // limits, which are then applied to what is left
+        let limit_late = opts.diff_filter.is_some() || opts.simplify_by_decoration;
+        let rev_list = match RevList::new(
+            self,
+            target_revisions,
+            opts.target_files.clone(),
+            opts.first_parent,
+        ) {
+            Ok(rev_list) => rev_list,
+            // A revision naming nothing has no history, like an unborn branch
+            Err(error) if error.is::<UnknownRevision>() => {
+                return self.show_no_commits(opts);
             }
+            Err(error) => return Err(error),
         }
+        .with_message_filter(message_filter)
+        .with_boundary(opts.boundary)
+        .with_skip(if limit_late {
+            0
+        } else {
+            opts.skip.unwrap_or(0)
+        })
+        .with_max_count(if limit_late { None } else { opts.max_count });
+
+        let commits_diffs = if opts.target_files.is_some() {
+            Some(rev_list.commit_diffs().clone())
+        } else {
+            None
+        };
+        let mut rev_list = rev_list.into_iter();
+        // Commits stream straight from the walk, unless a later stage needs
+        // the whole selection first
+        let buffered = if limit_late || opts.count || opts.graph {
+            let mut commits = rev_list.by_ref().collect::<Vec<_>>();
+            if let Some(filter) = opts.diff_filter {
+                commits =
+                    self.filter_commits_by_changes(commits, commits_diffs.as_ref(), filter, opts)?;
+            }
+            if opts.simplify_by_decoration {
+                commits = self.simplify_commits_by_decoration(commits)?;
+            }
+            if limit_late {
+                commits = commits
+                    .into_iter()
+                    .skip(opts.skip.unwrap_or(0))
+                    .take(opts.max_count.unwrap_or(usize::MAX))
+                    .collect();
+            }
+            Some(commits)
+        } else {
+            None
+        };
+        if let Some(commits) = &buffered
+            && opts.count
+        {
+            writeln!(self.writer(), "{}", commits.len())?;
+            self.writer().flush()?;
+            return Ok(());
+        }
+        let graph = match &buffered {
+            Some(commits) if opts.graph => Some(self.install_graph(commits)?),
+            _ => None,
+        };
+        let oneline = opts.oneline || opts.format == CommitDisplayFormat::OneLine;
+
+        let mut buffered = buffered.map(Vec::into_iter);
+        for position in 0.. {
+            let next = match buffered.as_mut() {
+                Some(commits) => commits.next(),
+                None => rev_list.next(),
+            };
+            let Some(commit) = next else {
+                break;
+            };
+
+            if let Some(graph) = &graph {
+                graph
+                    .borrow_mut()
+                    .update(&commit.object_id()?, commit.parents());
+            }
+
+            // `format:` templates are separated, not terminated, by newlines
+            if position > 0
+                && let Some(template) = &opts.template
+                && !template.terminated
+            {
+                writeln!(self.writer())?;
+            }
+
+            // Like Git, boundary commits get a `- ` before their ID
+            let mark = if rev_list.is_boundary(&commit.object_id()?) {
+                "- "
+            } else {
+                ""
+            };
+            self.show_commit(&commit, mark, commits_diffs.as_ref(), opts)?;
+
+            // Like Git, oneline commits are not set apart by blank lines
+            if opts.template.is_none() && !oneline {
+                writeln!(self.writer())?;
+            }
+        }
+        self.writer().flush()?;
 
         Ok(())
     }
 
+    fn show_no_commits(&self, opts: &LogOptions) -> anyhow::Result<()> {
+        if opts.count {
+            writeln!(self.writer(), "0")?;
+        } else {
+            writeln!(self.writer(), "No commits to show.")?;
+        }
+
+        Ok(())
+    }
+
+    /// Start points for `--all`: the commits of every ref
+    fn all_ref_targets(&self) -> anyhow::Result<Vec<LogRevisionTargets>> {
+        self.ref_commits()?
+            .into_iter()
+            .map(|oid| {
+                Ok(LogRevisionTargets::IncludedRevision(Revision::try_parse(
+                    oid.as_ref(),
+                )?))
+            })
+            .collect()
+    }
+
+    /// The commits of every ref, with annotated tags peeled and refs to
+    /// non-commits skipped
+    fn ref_commits(&self) -> anyhow::Result<BTreeSet<ObjectId>> {
+        let mut oids = BTreeSet::new();
+
+        for mut oid in self.refs().reverse_refs()?.into_keys() {
+            while let Some(tag) = self.database().parse_object_as_tag(&oid)? {
+                oid = tag.object().clone();
+            }
+            if self.database().get_object_type(&oid)? == ObjectType::Commit {
+                oids.insert(oid);
+            }
+        }
+
+        Ok(oids)
+    }
+
+    /// Keep the decorated commits and the merges joining them, in order
+    fn simplify_commits_by_decoration(&self, commits: Vec<Commit>) -> anyhow::Result<Vec<Commit>> {
+        let decorated = self.ref_commits()?.into_iter().collect::<HashSet<_>>();
+        let history = commits
+            .iter()
+            .map(|commit| Ok((commit.object_id()?, commit.parents().to_vec())))
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+        let kept = simplify_by_decoration(&history, &decorated);
+
+        commits
+            .into_iter()
+            .filter_map(|commit| match commit.object_id() {
+                Ok(oid) if kept.contains(&oid) => Some(Ok(commit)),
+                Ok(_) => None,
+                Err(error) => Some(Err(error)),
+            })
+            .collect()
+    }
+
+    /// Route the output through a graph writer tracking the given commits
+    fn install_graph(&self, commits: &[Commit]) -> anyhow::Result<Rc<RefCell<LogGraph>>> {
+        let visible = commits
+            .iter()
+            .map(|commit| commit.object_id())
+            .collect::<Result<_, _>>()?;
+        let graph = Rc::new(RefCell::new(LogGraph::new(visible)));
+
+        let mut writer = self.writer();
+        let inner = std::mem::replace(&mut *writer, Box::new(std::io::sink()));
+        *writer = Box::new(GraphWriter::new(inner, graph.clone()));
+
+        Ok(graph)
+    }
+
     // TODO: define a RepositoryWriter trait to abstract over the writer using trait objects
     pub fn show_commit(
         &self,
         commit: &Commit,
+        mark: &str,
         commits_diffs: Option<&CommitsDiffs>,
         opts: &LogOptions,
     ) -> anyhow::Result<()> {
-        if opts.oneline {
-            self.show_commit_oneline(commit, true, CommitDecoration::Short)?;
+        let abbrev = opts.abbrev_commit.then_some(opts.abbrev);
+        let parents = opts.shown_parents(commit);
+        if let Some(template) = &opts.template {
+            self.show_commit_template(commit, template, opts.abbrev)?;
+        } else if opts.oneline {
+            self.show_commit_oneline(
+                commit,
+                mark,
+                Some(opts.abbrev),
+                parents,
+                CommitDecoration::Short,
+            )?;
         } else {
             match opts.format {
                 CommitDisplayFormat::Medium => {
-                    self.show_commit_medium(commit, opts.abbrev_commit, opts.decorate)?;
+                    self.show_commit_medium(commit, mark, abbrev, parents, opts.decorate)?;
                 }
                 CommitDisplayFormat::OneLine => {
-                    self.show_commit_oneline(commit, opts.abbrev_commit, opts.decorate)?;
+                    self.show_commit_oneline(commit, mark, abbrev, parents, opts.decorate)?;
+                }
+                CommitDisplayFormat::Raw => {
+                    self.show_commit_raw(commit, mark, abbrev, parents, opts.decorate)?;
                 }
             }
         }
 
-        self.show_commit_patch(commit, commits_diffs, opts.patch)?;
+        self.show_commit_raw_diff(commit, commits_diffs, opts)?;
+        self.show_commit_stat(commit, commits_diffs, opts)?;
+        self.show_commit_patch(commit, commits_diffs, opts)?;
 
         Ok(())
     }
 
+    /// Keep the commits with at least one change selected by `filter`
+    ///
+    /// Merges are dropped, unless `--first-parent` gives them a diff.
+    fn filter_commits_by_changes(
+        &self,
+        commits: Vec<Commit>,
+        commits_diffs: Option<&CommitsDiffs>,
+        filter: DiffFilter,
+        opts: &LogOptions,
+    ) -> anyhow::Result<Vec<Commit>> {
+        let mut kept = Vec::new();
+
+        for commit in commits {
+            if opts.skips_diff_of(&commit) {
+                continue;
+            }
+
+            let tree_diff = self.commit_tree_diff(&commit, commits_diffs)?;
+            if tree_diff
+                .changes()
+                .values()
+                .any(|change| change.matches_filter(filter))
+            {
+                kept.push(commit);
+            }
+        }
+
+        Ok(kept)
+    }
+
     fn show_commit_patch(
         &self,
         commit: &Commit,
         commits_diffs: Option<&CommitsDiffs>,
-        patch: bool,
+        opts: &LogOptions,
     ) -> anyhow::Result<()> {
-        if !patch || commit.is_merge_commit() {
+        if !opts.patch || opts.skips_diff_of(commit) {
+            return Ok(());
+        }
+
+        self.print_commit_diff(commit, commits_diffs, opts.diff_filter)?;
+
+        Ok(())
+    }
+
+    /// Print the diffstat of a commit against its first parent
+    ///
+    /// Followed by a patch, the stat is set apart from the message by `---`
+    /// and from the patch by a blank line, like `git log --patch-with-stat`.
+    fn show_commit_stat(
+        &self,
+        commit: &Commit,
+        commits_diffs: Option<&CommitsDiffs>,
+        opts: &LogOptions,
+    ) -> anyhow::Result<()> {
+        if !opts.stat || opts.skips_diff_of(commit) {
+            return Ok(());
+        }
+
+        let tree_diff = self.commit_tree_diff(commit, commits_diffs)?;
+        let mut stats = Vec::new();
+        for (path, change) in tree_diff.changes() {
+            if opts.diff_filter.is_some_and(|f| !change.matches_filter(f)) {
+                continue;
+            }
+
+            let (old_entry, new_entry) = tree_diff.get_entries(path);
+            let old = DiffTarget::from_entry(path, old_entry, self.database())?;
+            let new = DiffTarget::from_entry(path, new_entry, self.database())?;
+            stats.push(FileStat::between(path.clone(), &old, &new));
+        }
+        if stats.is_empty() {
             return Ok(());
         }
 
-        self.print_commit_diff(commit, commits_diffs)?;
+        writeln!(self.writer(), "{}", if opts.patch { "---" } else { "" })?;
+        for line in format_stat(&stats) {
+            writeln!(self.writer(), "{}", line)?;
+        }
+        if opts.patch {
+            writeln!(self.writer())?;
+        }
 
         Ok(())
     }
 
-    fn print_commit_diff(
+    /// Print one `:old-mode new-mode old-oid new-oid status\tpath` line per
+    /// change against the first parent, like `git log --raw`
+    fn show_commit_raw_diff(
         &self,
         commit: &Commit,
         commits_diffs: Option<&CommitsDiffs>,
+        opts: &LogOptions,
     ) -> anyhow::Result<()> {
+        if !opts.raw || opts.skips_diff_of(commit) {
+            return Ok(());
+        }
+
+        let tree_diff = self.commit_tree_diff(commit, commits_diffs)?;
+        let changes = tree_diff
+            .changes()
+            .iter()
+            .filter(|(_, change)| opts.diff_filter.is_none_or(|f| change.matches_filter(f)))
+            .collect::<Vec<_>>();
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let quote_options = self.quote_options(false)?;
+        if !(opts.oneline || opts.format == CommitDisplayFormat::OneLine) {
+            writeln!(self.writer())?;
+        }
+        for (path, change) in changes {
+            writeln!(
+                self.writer(),
+                "{}\t{}",
+                raw_change_line(change),
+                quote_path(path, quote_options)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// The diff between a commit and its first parent
+    ///
+    /// Taken from the revision list cache when available, so that it is
+    /// restricted to the paths being logged.
+    fn commit_tree_diff(
+        &self,
+        commit: &Commit,
+        commits_diffs: Option<&CommitsDiffs>,
+    ) -> anyhow::Result<TreeDiff<'_>> {
         let parent_oid = commit.parent();
         let commit_oid = commit.object_id()?;
 
-        // Get the tree diff between the parent and the commit from the revision list cache if available
         let tree_diff = if let Some(commits_diffs) = commits_diffs {
-            commits_diffs
+            let changes = commits_diffs
                 .get(&(parent_oid.cloned(), Some(commit_oid)))
-                .cloned()
-                .unwrap_or(TreeDiff::new(self.database()))
+                .map(|tree_diff| tree_diff.changes().clone())
+                .unwrap_or_default();
+            TreeDiff::from_changeset(self.database(), changes)
         } else {
             self.database()
                 .tree_diff(parent_oid, Some(&commit_oid), &PathFilter::empty())?
         };
+
+        Ok(tree_diff)
+    }
+
+    pub(crate) fn print_commit_diff(
+        &self,
+        commit: &Commit,
+        commits_diffs: Option<&CommitsDiffs>,
+        diff_filter: Option<DiffFilter>,
+    ) -> anyhow::Result<()> {
+        let tree_diff = self.commit_tree_diff(commit, commits_diffs)?;
         let changeset = tree_diff.changes();
 
-        for path in changeset.keys() {
+        for (path, change) in changeset {
+            if let Some(filter) = diff_filter
+                && !change.matches_filter(filter)
+            {
+                continue;
+            }
+
             let (old_entry, new_entry) = tree_diff.get_entries(path);
-            self.print_diff(
-                &mut DiffTarget::from_entry(path, old_entry, self.database())?,
-                &mut DiffTarget::from_entry(path, new_entry, self.database())?,
-            )?;
+            self.print_entry_diff(path, old_entry, new_entry)?;
         }
 
         Ok(())
     }
 
-    fn show_commit_medium(
+    pub(crate) fn show_commit_medium(
         &self,
         commit: &Commit,
-        abbrev_commit: bool,
+        mark: &str,
+        abbrev: Option<usize>,
+        parents: &[ObjectId],
         decoration: CommitDecoration,
     ) -> anyhow::Result<()> {
         writeln!(
             self.writer(),
-            "commit {}{}",
-            self.abbrev_commit_id(commit, abbrev_commit)?.yellow(),
+            "commit {}{}{}",
+            mark,
+            self.commit_id_with_parents(commit, abbrev, parents)?
+                .yellow(),
             self.commit_decoration(commit, decoration)?
         )?;
+        if commit.is_merge_commit() {
+            let parents = commit
+                .parents()
+                .iter()
+                .map(|parent| parent.to_short_oid())
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(self.writer(), "Merge: {}", parents)?;
+        }
         writeln!(self.writer(), "Author: {}", commit.author().display_name())?;
         writeln!(
             self.writer(),
@@ -221,16 +614,54 @@ impl Repository {
         Ok(())
     }
 
+    /// Print the commit headers as stored, followed by the indented message
+    fn show_commit_raw(
+        &self,
+        commit: &Commit,
+        mark: &str,
+        abbrev: Option<usize>,
+        parents: &[ObjectId],
+        decoration: CommitDecoration,
+    ) -> anyhow::Result<()> {
+        writeln!(
+            self.writer(),
+            "commit {}{}{}",
+            mark,
+            self.commit_id_with_parents(commit, abbrev, parents)?
+                .yellow(),
+            self.commit_decoration(commit, decoration)?
+        )?;
+        writeln!(self.writer(), "tree {}", commit.tree_oid())?;
+        for parent in commit.parents() {
+            writeln!(self.writer(), "parent {}", parent)?;
+        }
+        writeln!(self.writer(), "author {}", commit.author().display())?;
+        writeln!(self.writer(), "committer {}", commit.committer().display())?;
+        for (name, value) in commit.extra_headers() {
+            writeln!(self.writer(), "{}", format_extra_header(name, value))?;
+        }
+        writeln!(self.writer())?;
+        for message_line in commit.message().lines() {
+            writeln!(self.writer(), "    {}", message_line)?;
+        }
+
+        Ok(())
+    }
+
     fn show_commit_oneline(
         &self,
         commit: &Commit,
-        abbrev_commit: bool,
+        mark: &str,
+        abbrev: Option<usize>,
+        parents: &[ObjectId],
         decoration: CommitDecoration,
     ) -> anyhow::Result<()> {
         writeln!(
             self.writer(),
-            "{}{} {}",
-            self.abbrev_commit_id(commit, abbrev_commit)?.yellow(),
+            "{}{}{} {}",
+            mark,
+            self.commit_id_with_parents(commit, abbrev, parents)?
+                .yellow(),
             self.commit_decoration(commit, decoration)?,
             commit.short_message()
         )?;
@@ -238,6 +669,24 @@ impl Repository {
         Ok(())
     }
 
+    /// Print the commit through a `--format` template
+    fn show_commit_template(
+        &self,
+        commit: &Commit,
+        template: &Template,
+        abbrev: usize,
+    ) -> anyhow::Result<()> {
+        let rendered = render_template(&template.text, commit, |oid| {
+            Ok(self.database().unique_abbreviation(oid, abbrev)?)
+        })?;
+        write!(self.writer(), "{}", rendered)?;
+        if template.terminated {
+            writeln!(self.writer())?;
+        }
+
+        Ok(())
+    }
+
     fn commit_decoration(
         &self,
         commit: &Commit,
@@ -248,56 +697,103 @@ impl Repository {
         }
 
         let commit_oid = commit.object_id()?;
-        if let Some(ref_names) = self.reverse_refs().get(&commit_oid) {
-            let (head, refs): (Vec<_>, Vec<_>) = ref_names.iter().partition(|ref_name| {
-                ref_name.is_detached_head() && !self.current_ref().is_detached_head()
-            });
-            let head = head.into_iter().cloned().collect::<Vec<_>>();
-            let refs = refs.into_iter().cloned().collect::<Vec<_>>();
-
-            let names = refs
-                .into_iter()
-                .map(|ref_name| {
-                    self.ref_decoration_name(head.first().cloned(), ref_name, decoration)
-                })
-                .collect::<anyhow::Result<Vec<_>>>()?
-                .join(", ");
-
-            Ok(format!(" ({})", names))
-        } else {
-            Ok(String::new())
-        }
+        let Some(ref_names) = self.reverse_refs().get(&commit_oid).cloned() else {
+            return Ok(String::new());
+        };
+
+        // An attached HEAD is shown as `HEAD -> <branch>` on its branch;
+        // a detached one is listed on its own
+        let current_ref = self.current_ref().clone();
+        let attached = !current_ref.is_detached_head();
+        let mut refs = ref_names
+            .into_iter()
+            .filter(|ref_name| !(attached && ref_name.is_detached_head()))
+            .collect::<Vec<_>>();
+        // HEAD (or its branch) first, then branches, remotes and tags
+        refs.sort_by(|a, b| {
+            (*a != current_ref, a.kind(), a.as_ref()).cmp(&(
+                *b != current_ref,
+                b.kind(),
+                b.as_ref(),
+            ))
+        });
+
+        let names = refs
+            .iter()
+            .map(|ref_name| {
+                self.ref_decoration_name(attached && *ref_name == current_ref, ref_name, decoration)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Ok(format!(" ({})", names))
     }
 
     fn ref_decoration_name(
         &self,
-        head: Option<SymRefName>,
-        ref_name: SymRefName,
+        is_head: bool,
+        ref_name: &SymRefName,
         decoration: CommitDecoration,
-    ) -> anyhow::Result<String> {
+    ) -> String {
         let name = match decoration {
-            CommitDecoration::Short => ref_name.to_short_name()?,
-            CommitDecoration::Full => ref_name.as_ref().to_string(),
+            CommitDecoration::Short => ref_name.to_decoration_name(false),
+            CommitDecoration::Full => ref_name.to_decoration_name(true),
             CommitDecoration::None => unreachable!(),
         };
         let name = ref_name.to_colored_name(name);
 
-        if let Some(head) = head
-            && ref_name == *self.current_ref()
-        {
-            return Ok(head
-                .to_colored_name(format!("{} -> {name}", head.as_ref()))
-                .to_string());
+        if is_head {
+            let head = SymRefName::new(HEAD_REF_NAME.to_string());
+            return head.to_colored_name(format!("{HEAD_REF_NAME} -> {name}"));
         }
 
-        Ok(name)
+        name
     }
 
-    fn abbrev_commit_id(&self, commit: &Commit, abbrev_commit: bool) -> anyhow::Result<String> {
-        if abbrev_commit {
-            Ok(commit.object_id()?.to_short_oid().as_str().to_string())
-        } else {
-            Ok(commit.object_id()?.as_ref().to_string())
+    /// The commit ID followed by the IDs of `parents`, all abbreviated alike
+    fn commit_id_with_parents(
+        &self,
+        commit: &Commit,
+        abbrev: Option<usize>,
+        parents: &[ObjectId],
+    ) -> anyhow::Result<String> {
+        let mut ids = vec![self.abbrev_oid(&commit.object_id()?, abbrev)?];
+        for parent in parents {
+            ids.push(self.abbrev_oid(parent, abbrev)?);
         }
+
+        Ok(ids.join(" "))
     }
+
+    /// The object ID, or its shortest unique prefix of at least `abbrev`
+    /// characters
+    fn abbrev_oid(&self, oid: &ObjectId, abbrev: Option<usize>) -> anyhow::Result<String> {
+        match abbrev {
+            Some(length) => Ok(self.database().unique_abbreviation(oid, length)?),
+            None => Ok(oid.as_ref().to_string()),
+        }
+    }
+}
+
+/// The `:old-mode new-mode old-oid new-oid status` part of a raw diff line
+///
+/// Missing sides use an all-zero mode and object id.
+fn raw_change_line(change: &TreeChangeType) -> String {
+    let mode = |entry: Option<&DatabaseEntry>| {
+        entry.map_or("000000".to_string(), |entry| {
+            format!("{:06o}", entry.mode.as_u32())
+        })
+    };
+    let oid = |entry: Option<&DatabaseEntry>| {
+        entry.map_or("0".repeat(7), |entry| entry.oid.to_short_oid())
+    };
+
+    format!(
+        ":{} {} {} {} {}",
+        mode(change.old_entry()),
+        mode(change.new_entry()),
+        oid(change.old_entry()),
+        oid(change.new_entry()),
+        change.status_char()
+    )
 }
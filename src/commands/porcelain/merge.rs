@@ -8,15 +8,20 @@ use crate::artifacts::objects::object::Object;
 
 // TODO: pattern match the merge type (null, fast-forward, normal) and handle each case separately
 impl Repository {
-    pub async fn merge(&mut self, target: &str, message: &str) -> anyhow::Result<()> {
+    /// Merge `target` into HEAD
+    ///
+    /// A target HEAD already contains is a no-op, and one descending from
+    /// HEAD is fast-forwarded to, unless `no_ff` asks for a merge commit
+    /// anyway.
+    pub async fn merge(&mut self, target: &str, message: &str, no_ff: bool) -> anyhow::Result<()> {
         let merge_inputs = MergeInputs::new(self, HEAD_REF_NAME, target)?;
 
         if self.is_null_merge(&merge_inputs) {
-            self.handle_null_merge(&merge_inputs)?;
+            self.handle_null_merge()?;
             return Ok(());
         }
 
-        if self.is_fast_forward_merge(&merge_inputs) {
+        if !no_ff && self.is_fast_forward_merge(&merge_inputs) {
             self.handle_fast_forward_merge(&merge_inputs).await?;
             return Ok(());
         }
@@ -87,17 +92,15 @@ impl Repository {
         Ok(())
     }
 
+    /// The target is an ancestor of HEAD when it is its own merge base with HEAD
     fn is_null_merge(&self, merge_inputs: &MergeInputs) -> bool {
         merge_inputs.base_oid() == merge_inputs.right_oid()
     }
 
-    fn handle_null_merge(&self, merge_inputs: &MergeInputs) -> anyhow::Result<()> {
-        writeln!(
-            self.writer(),
-            "Already up to date: {} is an ancestor of {}",
-            merge_inputs.right_oid().to_short_oid(),
-            merge_inputs.left_oid().to_short_oid()
-        )?;
+    /// The target is already reachable from HEAD: leave HEAD, the index and the
+    /// workspace alone
+    fn handle_null_merge(&self) -> anyhow::Result<()> {
+        writeln!(self.writer(), "Already up to date.")?;
         Ok(())
     }
 
@@ -133,8 +136,11 @@ impl Repository {
         let mut migration = Migration::new_for_merge(self, &mut index, tree_diff);
         migration.apply_changes()?;
 
-        index.write_updates()?;
-        self.refs().update_head(merge_inputs.right_oid().clone())?;
+        self.write_index(&mut index)?;
+        self.refs().update_head(
+            merge_inputs.right_oid().clone(),
+            &format!("merge {}: Fast-forward", merge_inputs.right_name()),
+        )?;
 
         Ok(())
     }
@@ -154,7 +160,7 @@ impl Repository {
         merge_resolution.execute(&mut index, right_name)?;
 
         let conflicted = index.conflicted_paths();
-        index.write_updates()?;
+        self.write_index(&mut index)?;
 
         Ok(conflicted)
     }
@@ -8,19 +8,47 @@
 //!
 //! - `init`: Initialize a new repository
 //! - `add`: Stage files for commit
+//! - `am`: Apply mailbox patches as commits
+//! - `apply`: Apply a diff to the working tree and optionally the index
+//! - `archive`: Export a tree as a tar or zip archive
 //! - `commit`: Create a new commit
 //! - `status`: Show working tree status
 //! - `diff`: Show changes between commits/trees
+//! - `format-patch`: Export a commit as an email patch
+//! - `gc`: Pack loose objects, optionally only past configured thresholds
 //! - `log`: Show commit history
+//! - `mv`: Rename a tracked file
+//! - `bisect`: Binary search for the commit that introduced a bug
 //! - `branch`: Create, list, or delete branches
 //! - `checkout`: Switch branches or restore files
+//! - `clean`: Remove untracked files from the working tree
+//! - `config`: Get and set repository options
+//! - `reflog`: List the recorded updates of a ref
+//! - `reset`: Move HEAD and rewind the index and workspace
+//! - `rm`: Remove files from the index and the working tree
+//! - `show`: Show a commit and its patch
+//! - `tag`: Create, list, or delete tags
 
 pub mod add;
+pub mod am;
+pub mod apply;
+pub mod archive;
+pub mod bisect;
 pub mod branch;
 pub mod checkout;
+pub mod clean;
 pub mod commit;
+pub mod config;
 pub mod diff;
+pub mod format_patch;
+pub mod gc;
 pub mod init;
 pub mod log;
 mod merge;
+pub mod mv;
+pub mod reflog;
+pub mod reset;
+pub mod rm;
+pub mod show;
 pub mod status;
+pub mod tag;
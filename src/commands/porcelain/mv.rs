@@ -0,0 +1,80 @@
+use crate::areas::repository::Repository;
+use crate::artifacts::objects::object::Object;
+use std::path::Path;
+
+impl Repository {
+    /// Rename a tracked file in the workspace and the index
+    ///
+    /// A destination naming a directory receives the file under its own
+    /// name. Like `git mv`, the index entry moves with its staged blob, so
+    /// the file keeps its status under the new name. A
+    /// destination that already exists is only overwritten with `force`.
+    pub async fn mv(
+        &mut self,
+        source: &Path,
+        destination: &Path,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let index = self.index();
+        let mut index = index.lock().await;
+
+        index.rehydrate()?;
+
+        let context = format!(
+            "source={}, destination={}",
+            source.display(),
+            destination.display()
+        );
+        let Some(mut entry) = index.entry_by_path(source).cloned() else {
+            if index.is_conflicted_path(source) {
+                anyhow::bail!("conflicted, {}", context);
+            }
+            if index.is_directly_tracked(source) {
+                anyhow::bail!("moving directories is not supported, {}", context);
+            }
+            anyhow::bail!("not under version control, {}", context);
+        };
+        if !self.workspace().is_file(source) {
+            anyhow::bail!("bad source, {}", context);
+        }
+
+        let destination = match source.file_name() {
+            Some(name) if self.workspace().is_dir(destination) => destination.join(name),
+            _ => destination.to_path_buf(),
+        };
+        if self.workspace().is_dir(&destination) {
+            anyhow::bail!("destination is a directory, {}", context);
+        }
+        if !force
+            && (self.workspace().is_file(&destination)
+                || index.entry_by_path(&destination).is_some())
+        {
+            anyhow::bail!("destination exists, {}", context);
+        }
+        if let Some(parent) = destination.parent()
+            && !parent.as_os_str().is_empty()
+            && !self.workspace().is_dir(parent)
+        {
+            anyhow::bail!("destination directory does not exist, {}", context);
+        }
+
+        self.workspace().rename_file(source, &destination)?;
+        index.remove(source.to_path_buf())?;
+
+        // Renaming changes the ctime, so like Git refresh the stat data of
+        // files still matching the staged blob. Modified files, and racy
+        // entries whose stat data proves nothing, keep the old one.
+        let stat = self.workspace().stat_file(&destination)?;
+        if !index.is_racy(&entry)
+            && entry.stat_match(&stat)
+            && self.workspace().parse_blob(&destination)?.object_id()? == entry.oid
+        {
+            entry.metadata = stat;
+        }
+        entry.name = destination;
+        index.add(entry)?;
+        self.write_index(&mut index)?;
+
+        Ok(())
+    }
+}
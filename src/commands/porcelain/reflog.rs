@@ -0,0 +1,45 @@
+use crate::areas::refs::HEAD_REF_NAME;
+use crate::areas::repository::Repository;
+use crate::artifacts::branch::branch_name::BranchName;
+use crate::artifacts::branch::revision::unknown_revision;
+use crate::artifacts::log::date_format::DateFormat;
+use colored::Colorize;
+
+impl Repository {
+    /// List the updates recorded in the reflog of a ref, newest first
+    ///
+    /// Each line shows the value the ref took and the `<ref>@{n}` selector
+    /// naming it, with the date of the update in place of `n` when a date
+    /// format is given.
+    pub fn reflog_show(
+        &self,
+        reference: Option<&str>,
+        date: Option<DateFormat>,
+    ) -> anyhow::Result<()> {
+        let name = reference.unwrap_or(HEAD_REF_NAME);
+        let sym_ref_name = self
+            .refs()
+            .full_ref_name(BranchName::try_parse(name.to_string())?)
+            .map_err(|_| unknown_revision(name))?;
+        let now = chrono::Local::now().fixed_offset();
+
+        let entries = self.refs().read_reflog(&sym_ref_name)?;
+        for (n, entry) in entries.iter().rev().enumerate() {
+            let selector = match date {
+                Some(format) => format.format(&entry.committer.timestamp(), &now),
+                None => n.to_string(),
+            };
+
+            writeln!(
+                self.writer(),
+                "{} {}@{{{}}}: {}",
+                entry.new_oid.to_short_oid().yellow(),
+                name,
+                selector,
+                entry.message
+            )?;
+        }
+
+        Ok(())
+    }
+}
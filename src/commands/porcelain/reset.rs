@@ -0,0 +1,235 @@
+use crate::areas::index::Index;
+use crate::areas::refs::HEAD_REF_NAME;
+use crate::areas::repository::Repository;
+use crate::areas::workspace::DirCache;
+use crate::artifacts::checkout::migration::Migration;
+use crate::artifacts::database::database_entry::DatabaseEntry;
+use crate::artifacts::index::index_entry::{EntryMetadata, IndexEntry, MergeStage};
+use crate::artifacts::log::path_filter::PathFilter;
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::status::file_change::WorkspaceChangeType;
+use crate::artifacts::status::inspector::Inspector;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// Which areas `bit reset` rewinds besides HEAD
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResetMode {
+    /// Only move HEAD
+    Soft,
+    /// Move HEAD and reset the index
+    #[default]
+    Mixed,
+    /// Move HEAD and reset the index and the workspace, discarding local changes
+    /// to tracked files; like `checkout --force`, it aborts rather than
+    /// overwrite untracked files
+    Hard,
+    /// Move HEAD and update the files that differ between the two commits,
+    /// aborting if any of them has local changes
+    Keep,
+}
+
+impl Repository {
+    pub async fn reset(&mut self, target: Option<&str>, mode: ResetMode) -> anyhow::Result<()> {
+        let head_oid = self
+            .refs()
+            .read_head()?
+            .ok_or_else(|| anyhow::anyhow!("no current HEAD to reset from"))?;
+
+        let target_oid = self.resolve_revision(target.unwrap_or(HEAD_REF_NAME))?;
+        let target_commit = self
+            .database()
+            .parse_object_as_commit(&target_oid)?
+            .ok_or_else(|| anyhow::anyhow!("{} is not a commit", target_oid))?;
+
+        let index = self.index();
+        let mut index = index.lock().await;
+
+        index.rehydrate()?;
+
+        // The merge state would survive the reset and be committed with the
+        // wrong parents
+        if mode == ResetMode::Soft
+            && (self.refs().read_merge_head()?.is_some() || index.has_conflicts())
+        {
+            anyhow::bail!("cannot do a soft reset in the middle of a merge");
+        }
+
+        match mode {
+            ResetMode::Soft => {}
+            ResetMode::Mixed => {
+                let target_entries = self.flatten_tree(target_commit.tree_oid()).await?;
+                Self::reset_index(&mut index, &target_entries)?;
+            }
+            ResetMode::Hard => {
+                // Same checks as `checkout --force`: local changes to tracked
+                // files are discarded, but untracked files in the way abort
+                let tree_diff = self.database().tree_diff(
+                    Some(&head_oid),
+                    Some(&target_oid),
+                    &PathFilter::empty(),
+                )?;
+                let mut migration = Migration::new_forced(self, &mut index, tree_diff);
+                migration.apply_changes()?;
+                let migrated = migration.changed_paths();
+
+                let target_entries = self.flatten_tree(target_commit.tree_oid()).await?;
+                self.reset_workspace(&mut index, &target_entries, &migrated)?;
+            }
+            ResetMode::Keep => {
+                // Same safety rules as checkout: only files that differ between
+                // the two commits are touched, and only if they are unmodified
+                let tree_diff = self.database().tree_diff(
+                    Some(&head_oid),
+                    Some(&target_oid),
+                    &PathFilter::empty(),
+                )?;
+
+                let mut migration = Migration::new(self, &mut index, tree_diff);
+                migration.apply_changes()?;
+            }
+        }
+
+        if mode != ResetMode::Soft {
+            self.write_index(&mut index)?;
+            self.refs().clear_merge_head()?;
+            self.refs().clear_merge_msg()?;
+        }
+
+        self.refs().write_orig_head(&head_oid)?;
+        let reflog_message = format!("reset: moving to {}", target.unwrap_or(HEAD_REF_NAME));
+        self.refs()
+            .update_head(target_oid.clone(), &reflog_message)?;
+
+        if matches!(mode, ResetMode::Hard | ResetMode::Keep) {
+            writeln!(
+                self.writer(),
+                "HEAD is now at {} {}",
+                target_oid.to_short_oid(),
+                target_commit.short_message()
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn flatten_tree(
+        &self,
+        tree_oid: &ObjectId,
+    ) -> anyhow::Result<BTreeMap<PathBuf, DatabaseEntry>> {
+        let mut entries = BTreeMap::new();
+        self.parse_tree(tree_oid, &mut entries).await?;
+
+        Ok(entries)
+    }
+
+    /// Make the index match the target tree, leaving the workspace untouched
+    ///
+    /// Entries that already match the target keep their cached stat data;
+    /// the others get zeroed timestamps so status re-hashes the file.
+    fn reset_index(
+        index: &mut Index,
+        target_entries: &BTreeMap<PathBuf, DatabaseEntry>,
+    ) -> anyhow::Result<()> {
+        for path in Self::tracked_paths(index) {
+            if !target_entries.contains_key(&path) {
+                index.remove(path)?;
+            }
+        }
+
+        for (path, entry) in target_entries {
+            let unchanged = index
+                .entry_by_path_and_stage(path, MergeStage::Clean)
+                .is_some_and(|current| {
+                    current.oid == entry.oid && current.metadata.mode == entry.mode
+                });
+
+            if !unchanged {
+                index.add(IndexEntry::new(
+                    path.clone(),
+                    entry.oid.clone(),
+                    EntryMetadata {
+                        mode: entry.mode,
+                        ..Default::default()
+                    },
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Make both the workspace and the index match the target tree
+    ///
+    /// `migrated` holds the paths a migration to the target has just written,
+    /// which are left as they are. Of the others, only the entries whose index
+    /// entry differs from the target, or whose file differs from its index
+    /// entry, are written. Submodules only get
+    /// their index entry, as their content lives in another repository.
+    pub(crate) fn reset_workspace(
+        &self,
+        index: &mut Index,
+        target_entries: &BTreeMap<PathBuf, DatabaseEntry>,
+        migrated: &BTreeSet<PathBuf>,
+    ) -> anyhow::Result<()> {
+        for path in Self::tracked_paths(index) {
+            if !target_entries.contains_key(&path) {
+                self.workspace().remove_file(&path)?;
+                index.remove(path)?;
+            }
+        }
+
+        let inspector = Inspector::new(self);
+        let mut dirs = DirCache::default();
+        for (path, entry) in target_entries {
+            if migrated.contains(path) {
+                continue;
+            }
+            let current = index
+                .entry_by_path_and_stage(path, MergeStage::Clean)
+                .filter(|current| current.oid == entry.oid && current.metadata.mode == entry.mode);
+
+            if entry.mode.is_gitlink() {
+                if current.is_none() {
+                    index.add(IndexEntry::new(
+                        path.clone(),
+                        entry.oid.clone(),
+                        EntryMetadata {
+                            mode: entry.mode,
+                            ..Default::default()
+                        },
+                    ))?;
+                }
+                continue;
+            }
+            if let Some(current) = current {
+                let stat = self.workspace().stat_file(path).ok();
+                if stat.as_ref().is_some_and(|stat| !stat.mode.is_tree())
+                    && inspector.check_index_against_workspace(
+                        Some(current),
+                        stat.as_ref(),
+                        index,
+                    )? == WorkspaceChangeType::None
+                {
+                    continue;
+                }
+            }
+
+            let content = self
+                .database()
+                .load_blob_bytes(&entry.oid)?
+                .ok_or_else(|| anyhow::anyhow!("{} is not a blob", entry.oid))?;
+            self.workspace()
+                .write_entry_file_cached(&mut dirs, path, &content, &entry.mode)?;
+
+            let stat = self.workspace().stat_file(path)?;
+            index.add(IndexEntry::new(path.clone(), entry.oid.clone(), stat))?;
+        }
+
+        Ok(())
+    }
+
+    fn tracked_paths(index: &Index) -> BTreeSet<PathBuf> {
+        index.entries().map(|entry| entry.name.clone()).collect()
+    }
+}
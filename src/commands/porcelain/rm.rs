@@ -0,0 +1,150 @@
+use crate::areas::index::Index;
+use crate::areas::repository::Repository;
+use crate::artifacts::core::quote::unquote_path;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// How `bit rm` removes the matched paths
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RmOptions {
+    /// Allow removing the files under a directory
+    pub recursive: bool,
+    /// Skip the checks protecting staged and local changes
+    pub force: bool,
+    /// Only remove the paths from the index, keeping the files
+    pub cached: bool,
+}
+
+impl Repository {
+    pub async fn rm(&mut self, paths: &[String], opts: RmOptions) -> anyhow::Result<()> {
+        let index = self.index();
+        let mut index = index.lock().await;
+
+        index.rehydrate()?;
+
+        // Resolve every pathspec before touching anything, so a bad one
+        // leaves the index and workspace as they were
+        let mut removals = BTreeSet::new();
+        for path in paths {
+            let pathspec = PathBuf::from(unquote_path(path));
+            let entries = index.entries_under_path(&pathspec);
+
+            if entries.is_empty() {
+                anyhow::bail!("pathspec '{}' did not match any files", path);
+            }
+            if !opts.recursive && entries.iter().any(|entry| entry != &pathspec) {
+                anyhow::bail!("not removing '{}' recursively without -r", path);
+            }
+
+            removals.extend(entries);
+        }
+
+        if !opts.force {
+            self.check_removals(&index, &removals, opts.cached).await?;
+        }
+
+        for path in &removals {
+            writeln!(self.writer(), "rm '{}'", path.display())?;
+            index.remove(path.clone())?;
+            if !opts.cached {
+                self.workspace().remove_file(path)?;
+            }
+        }
+
+        self.write_index(&mut index)?;
+
+        Ok(())
+    }
+
+    /// Refuse to drop changes that exist nowhere but in the index or the
+    /// workspace, as Git does without `-f`
+    ///
+    /// A path whose staged content matches neither HEAD nor the file is always
+    /// protected. Unless only the index entry goes away (`--cached`), staged
+    /// changes and local modifications are protected too.
+    async fn check_removals(
+        &self,
+        index: &Index,
+        removals: &BTreeSet<PathBuf>,
+        cached: bool,
+    ) -> anyhow::Result<()> {
+        let head_entries = match self.refs().read_head()? {
+            Some(head_oid) => {
+                let head_commit = self
+                    .database()
+                    .parse_object_as_commit(&head_oid)?
+                    .ok_or_else(|| anyhow::anyhow!("HEAD {} is not a commit", head_oid))?;
+                self.flatten_tree(head_commit.tree_oid()).await?
+            }
+            None => BTreeMap::new(),
+        };
+
+        let mut both_changed = Vec::new();
+        let mut staged = Vec::new();
+        let mut modified = Vec::new();
+        for path in removals {
+            // Conflicted paths have no stage-0 entry and are removed as is
+            let Some(entry) = index.entry_by_path(path) else {
+                continue;
+            };
+
+            let staged_change = !head_entries
+                .get(path)
+                .is_some_and(|head| head.oid == entry.oid && head.mode == entry.metadata.mode);
+            let local_change = self.workspace().is_file(path)
+                && (self.workspace().stat_file(path)?.mode != entry.metadata.mode
+                    || self.hash_file(path, false)? != entry.oid);
+
+            if staged_change && local_change {
+                both_changed.push(path);
+            } else if staged_change && !cached {
+                staged.push(path);
+            } else if local_change && !cached {
+                modified.push(path);
+            }
+        }
+
+        let mut errors = Vec::new();
+        for (paths, problem, hint) in [
+            (
+                both_changed,
+                "staged content different from both the file and the HEAD",
+                "(use -f to force removal)",
+            ),
+            (
+                staged,
+                "changes staged in the index",
+                "(use --cached to keep the file, or -f to force removal)",
+            ),
+            (
+                modified,
+                "local modifications",
+                "(use --cached to keep the file, or -f to force removal)",
+            ),
+        ] {
+            if paths.is_empty() {
+                continue;
+            }
+
+            let (subject, verb) = if paths.len() == 1 {
+                ("file", "has")
+            } else {
+                ("files", "have")
+            };
+            let listing = paths
+                .iter()
+                .map(|path| format!("    {}\n", path.display()))
+                .collect::<String>();
+            errors.push(format!(
+                "the following {} {} {}:\n{}{}",
+                subject, verb, problem, listing, hint
+            ));
+        }
+
+        if !errors.is_empty() {
+            anyhow::bail!(errors.join("\n"));
+        }
+
+        Ok(())
+    }
+}
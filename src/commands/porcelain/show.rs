@@ -0,0 +1,169 @@
+use crate::CommitDecoration;
+use crate::areas::refs::HEAD_REF_NAME;
+use crate::areas::repository::Repository;
+use crate::artifacts::diff::combined_diff::CombinedDiff;
+use crate::artifacts::diff::diff_target::DiffTarget;
+use crate::artifacts::log::path_filter::PathFilter;
+use crate::artifacts::objects::commit::Commit;
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::objects::tag::Tag;
+use colored::Colorize;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+impl Repository {
+    /// Show a commit followed by its patch
+    ///
+    /// Merge commits are shown with a combined diff against all their parents,
+    /// which only keeps the regions that differ from every parent (e.g. conflict
+    /// resolutions). Annotated tags are shown first, with their tagger and
+    /// message, followed by the commit they point to.
+    pub fn show(&self, revision: Option<&str>) -> anyhow::Result<()> {
+        let revision = revision.unwrap_or(HEAD_REF_NAME);
+        let mut oid = self.resolve_object(revision)?;
+        while let Some(tag) = self.database().parse_object_as_tag(&oid)? {
+            self.show_tag(&tag)?;
+            oid = tag.object().clone();
+        }
+
+        let commit = self
+            .database()
+            .parse_object_as_commit(&oid)?
+            .ok_or_else(|| anyhow::anyhow!("{} is not a commit", oid))?;
+
+        self.show_commit_medium(&commit, "", None, &[], CommitDecoration::None)?;
+        writeln!(self.writer())?;
+
+        if commit.is_merge_commit() {
+            self.print_combined_diff(&commit, &oid)?;
+        } else {
+            self.print_commit_diff(&commit, None, None)?;
+        }
+        self.writer().flush()?;
+
+        Ok(())
+    }
+
+    fn show_tag(&self, tag: &Tag) -> anyhow::Result<()> {
+        writeln!(self.writer(), "{}", format!("tag {}", tag.name()).yellow())?;
+        writeln!(self.writer(), "Tagger: {}", tag.tagger().display_name())?;
+        writeln!(
+            self.writer(),
+            "Date:   {}",
+            tag.tagger().readable_timestamp()
+        )?;
+        writeln!(self.writer())?;
+        write!(self.writer(), "{}", tag.message())?;
+        if !tag.message().ends_with('\n') {
+            writeln!(self.writer())?;
+        }
+        writeln!(self.writer())?;
+
+        Ok(())
+    }
+
+    fn print_combined_diff(&self, commit: &Commit, oid: &ObjectId) -> anyhow::Result<()> {
+        let tree_diffs = commit
+            .parents()
+            .iter()
+            .map(|parent| {
+                self.database()
+                    .tree_diff(Some(parent), Some(oid), &PathFilter::empty())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Only paths that differ from every parent can have interesting hunks
+        let paths = tree_diffs
+            .iter()
+            .map(|tree_diff| tree_diff.changes().keys().cloned().collect::<BTreeSet<_>>())
+            .reduce(|common, changed| common.intersection(&changed).cloned().collect())
+            .unwrap_or_default();
+
+        for path in paths {
+            let parents = tree_diffs
+                .iter()
+                .map(|tree_diff| {
+                    DiffTarget::from_entry(&path, tree_diff.get_entries(&path).0, self.database())
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let result =
+                DiffTarget::from_entry(&path, tree_diffs[0].get_entries(&path).1, self.database())?;
+
+            self.print_combined_diff_content(&path, &parents, &result)?;
+        }
+
+        Ok(())
+    }
+
+    fn print_combined_diff_content(
+        &self,
+        path: &Path,
+        parents: &[DiffTarget],
+        result: &DiffTarget,
+    ) -> anyhow::Result<()> {
+        let parent_data = parents
+            .iter()
+            .map(|parent| parent.data.clone())
+            .collect::<Vec<_>>();
+        let hunks = CombinedDiff::new(&parent_data, &result.data).hunks();
+        if hunks.is_empty() {
+            return Ok(());
+        }
+
+        let a_path = self.diff_prefix().src_path(path);
+        let b_path = if result.mode.is_some() {
+            self.diff_prefix().dst_path(path)
+        } else {
+            result.diff_path()
+        };
+
+        writeln!(
+            self.writer(),
+            "{}",
+            format!("diff --cc {}", path.display()).bold()
+        )?;
+        if result.mode.is_none() {
+            writeln!(
+                self.writer(),
+                "{}",
+                format!("deleted file mode {}", parents[0].pretty_mode()).bold()
+            )?;
+        } else if parents.iter().all(|parent| parent.mode.is_none()) {
+            writeln!(
+                self.writer(),
+                "{}",
+                format!("new file mode {}", result.pretty_mode()).bold()
+            )?;
+        }
+
+        let parent_oids = parents
+            .iter()
+            .map(|parent| parent.oid.to_short_oid().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+            self.writer(),
+            "{}",
+            format!("index {}..{}", parent_oids, result.oid.to_short_oid()).bold()
+        )?;
+        writeln!(
+            self.writer(),
+            "{}",
+            format!("--- {}", a_path.display()).bold()
+        )?;
+        writeln!(
+            self.writer(),
+            "{}",
+            format!("+++ {}", b_path.display()).bold()
+        )?;
+
+        for hunk in hunks {
+            writeln!(self.writer(), "{}", hunk.header().cyan())?;
+            for line in hunk.lines() {
+                writeln!(self.writer(), "{}", line)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,31 @@
 use crate::areas::repository::Repository;
-use crate::artifacts::status::file_change::{ConflictType, FileChangeType};
-use crate::artifacts::status::status_info::{ConflictSet, StatusInfo};
+use crate::artifacts::core::pathspec::Pathspec;
+use crate::artifacts::core::quote::quote_path;
+use crate::artifacts::diff::rename_detection::{DEFAULT_SIMILARITY, RENAMES_KEY, RenameOptions};
+use crate::artifacts::index::index_entry::MergeStage;
+use crate::artifacts::status::file_change::{ConflictType, FileChangeType, WorkspaceChangeType};
+use crate::artifacts::status::porcelain::{
+    PorcelainVersion, change_code, ordinary_line, renamed_line, unmerged_line,
+};
+use crate::artifacts::status::status_info::Status;
+use crate::artifacts::status::status_report::StatusReport;
 use colored::*;
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+
+/// Whether status pairs staged deletions and additions into renames
+/// (falls back to `diff.renames`)
+pub const STATUS_RENAMES_KEY: &str = "status.renames";
+
+/// What `Repository::status` scans
+#[derive(Debug, Clone, Default)]
+pub struct StatusOptions {
+    /// Force staged rename detection on or off; without it, `status.renames`
+    /// (then `diff.renames`) decides, and detection is off when neither is set
+    pub renames: Option<bool>,
+    /// Only report the paths this selects (all paths without one)
+    pub pathspec: Option<Pathspec>,
+}
 
 // Terminology:
 // - untracked files: files that are not tracked by the index
@@ -13,59 +35,260 @@ use std::path::PathBuf;
 // - index modified files: files that are in the index and in the HEAD commit but have different content or mode
 // - index deleted files: files that are in the HEAD commit but not in the index
 impl Repository {
-    pub async fn display_status(&mut self, porcelain: bool) -> anyhow::Result<()> {
+    /// Compare the workspace, the index and HEAD, and sort the changes into
+    /// staged, unstaged, untracked and conflicted paths
+    ///
+    /// Refreshed stat data of unchanged files is written back to the index.
+    pub async fn status(&self, opts: &StatusOptions) -> anyhow::Result<StatusReport> {
         let index = self.index();
         let mut index = index.lock().await;
 
         index.rehydrate()?;
-        let status_info = self.status().initialize(&mut index).await?;
-        index.write_updates()?;
-
-        if porcelain {
-            // Merge regular changes and conflict entries into a single sorted map.
-            let mut porcelain_lines: BTreeMap<PathBuf, String> = BTreeMap::new();
-            for (file, change) in &status_info.changed_files {
-                porcelain_lines.insert(file.clone(), String::from(change));
-            }
-            for (file, stages) in &status_info.conflicts {
-                let ct = ConflictType::from_stages(stages);
-                porcelain_lines.insert(file.clone(), ct.porcelain_code().to_string());
-            }
-            for (file, code) in &porcelain_lines {
-                writeln!(self.writer(), "{} {}", code, file.display())?;
-            }
-            for file in &status_info.untracked_files {
-                writeln!(self.writer(), "?? {}", file.display())?;
-            }
+        let rename_options = self.status_rename_options(opts.renames)?;
+        let mut status_info = Status::new(self)
+            .with_renames(rename_options)
+            .initialize(&mut index)
+            .await?;
+        self.write_index(&mut index)?;
+        if let Some(pathspec) = &opts.pathspec {
+            status_info.retain_paths(pathspec);
+        }
+
+        Ok(StatusReport::from(status_info))
+    }
+
+    /// Print the status of the working tree
+    ///
+    /// Paths are computed relative to the repository root. The long format
+    /// shows them relative to `cwd` instead, as Git does; `--porcelain`
+    /// output, in either version, keeps them root-relative.
+    ///
+    /// `renames` forces staged rename detection on or off; without it,
+    /// `status.renames` (then `diff.renames`) decides, and detection is off
+    /// when neither is set.
+    ///
+    /// `ignored` also lists the untracked paths `.gitignore` rules match,
+    /// which are otherwise left out.
+    ///
+    /// `paths` limits the output to the paths they select, relative to `cwd`;
+    /// exclusions (`:(exclude)<pattern>`, `:!<pattern>`) hide matching paths.
+    pub async fn display_status(
+        &mut self,
+        porcelain: Option<PorcelainVersion>,
+        renames: Option<bool>,
+        ignored: bool,
+        paths: &[PathBuf],
+        cwd: &Path,
+    ) -> anyhow::Result<()> {
+        let prefix = cwd
+            .canonicalize()?
+            .strip_prefix(self.path())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let mut report = self
+            .status(&StatusOptions {
+                renames,
+                pathspec: Some(Pathspec::parse_relative(paths, &prefix)?),
+            })
+            .await?;
+        if !ignored {
+            report.ignored.clear();
+        }
+
+        match porcelain {
+            Some(PorcelainVersion::V1) => self.print_porcelain_v1(&report)?,
+            Some(PorcelainVersion::V2) => self.print_porcelain_v2(&report)?,
+            None => self.print_long_status(&report, &prefix)?,
+        }
+
+        Ok(())
+    }
+
+    /// Print `--porcelain` (v1) output: `XY <path>` per change, then
+    /// untracked files
+    fn print_porcelain_v1(&self, report: &StatusReport) -> anyhow::Result<()> {
+        // Merge regular changes and conflict entries into a single sorted map.
+        let mut porcelain_lines: BTreeMap<PathBuf, String> = BTreeMap::new();
+        for (file, change) in report.changes() {
+            porcelain_lines.insert(file.clone(), String::from(&change));
+        }
+        for (file, conflict) in &report.conflicted {
+            porcelain_lines.insert(file.clone(), conflict.porcelain_code().to_string());
+        }
+        let quote_options = self.quote_options(true)?;
+        for (file, code) in &porcelain_lines {
+            let path = match report.renames.get(file) {
+                Some(old_path) => format!(
+                    "{} -> {}",
+                    quote_path(old_path, quote_options),
+                    quote_path(file, quote_options)
+                ),
+                None => quote_path(file, quote_options),
+            };
+            writeln!(self.writer(), "{} {}", code, path)?;
+        }
+        for file in &report.untracked {
+            writeln!(self.writer(), "?? {}", quote_path(file, quote_options))?;
+        }
+        for file in &report.ignored {
+            writeln!(self.writer(), "!! {}", quote_path(file, quote_options))?;
+        }
+
+        Ok(())
+    }
+
+    /// Print `--porcelain=v2` output: the `# branch.*` header, one line per
+    /// changed, renamed or unmerged entry in path order, then untracked files
+    ///
+    /// The modes and object IDs all come from the entries kept in the report.
+    fn print_porcelain_v2(&self, report: &StatusReport) -> anyhow::Result<()> {
+        let head = self.refs().read_head()?;
+        let current_ref = self.refs().current_ref(None)?;
+        let branch = if current_ref.is_detached_head() {
+            String::from("(detached)")
         } else {
-            if !status_info.conflicts.is_empty() {
-                self.print_conflicts(&status_info.conflicts)?;
-            }
-            self.print_changes("Changes to be committed", &status_info.index_changeset)?;
-            self.print_changes(
-                "Changes not staged for commit",
-                &status_info.workspace_changeset,
-            )?;
-            self.print_changes("Untracked files", &status_info.untracked_changeset)?;
+            current_ref.to_short_name()?
+        };
+        writeln!(
+            self.writer(),
+            "# branch.oid {}",
+            head.as_ref().map_or("(initial)", |oid| oid.as_ref())
+        )?;
+        writeln!(self.writer(), "# branch.head {}", branch)?;
 
-            if status_info.conflicts.is_empty() {
-                self.print_commit_status(&status_info)?;
-            }
+        let quote_options = self.quote_options(true)?;
+
+        let mut lines: BTreeMap<&PathBuf, String> = BTreeMap::new();
+        for (file, change) in report.changes() {
+            let code = change_code(&change);
+            let path = quote_path(file, quote_options);
+            let head_entry = report.head_entries.get(file);
+            let index_entry = report.index_entry(file, MergeStage::Clean);
+            let workspace_mode = report.workspace_modes.get(file);
+
+            let line = match report.renames.get(file) {
+                Some(old_path) => renamed_line(
+                    &code,
+                    report.head_entries.get(old_path),
+                    index_entry,
+                    workspace_mode,
+                    report.rename_scores.get(file).copied().unwrap_or(100),
+                    &path,
+                    &quote_path(old_path, quote_options),
+                ),
+                None => ordinary_line(&code, head_entry, index_entry, workspace_mode, &path),
+            };
+            lines.insert(file, line);
+        }
+        for (file, conflict) in &report.conflicted {
+            let code = conflict.porcelain_code();
+            let stages = [MergeStage::Base, MergeStage::Ours, MergeStage::Theirs]
+                .map(|stage| report.index_entry(file, stage));
+            let line = unmerged_line(
+                code,
+                stages,
+                report.workspace_modes.get(file),
+                &quote_path(file, quote_options),
+            );
+            lines.insert(file, line);
+        }
+
+        for line in lines.values() {
+            writeln!(self.writer(), "{}", line)?;
+        }
+        for file in &report.untracked {
+            writeln!(self.writer(), "? {}", quote_path(file, quote_options))?;
+        }
+        for file in &report.ignored {
+            writeln!(self.writer(), "! {}", quote_path(file, quote_options))?;
+        }
+
+        Ok(())
+    }
+
+    /// Print the human-readable status, with paths relative to `prefix`
+    fn print_long_status(&self, report: &StatusReport, prefix: &Path) -> anyhow::Result<()> {
+        if !report.conflicted.is_empty() {
+            self.print_conflicts(&report.conflicted, prefix)?;
+        }
+        let index_changeset = report
+            .staged
+            .iter()
+            .map(|(file, change)| (file.clone(), FileChangeType::Index(change.clone())))
+            .collect();
+        let workspace_changeset = report
+            .unstaged
+            .iter()
+            .map(|(file, change)| (file.clone(), FileChangeType::Workspace(change.clone())))
+            .collect();
+        let untracked_changeset = report
+            .untracked
+            .iter()
+            .map(|file| {
+                (
+                    file.clone(),
+                    FileChangeType::Workspace(WorkspaceChangeType::Untracked),
+                )
+            })
+            .collect();
+
+        let no_renames = BTreeMap::new();
+        self.print_changes(
+            "Changes to be committed",
+            &index_changeset,
+            &report.renames,
+            prefix,
+        )?;
+        self.print_changes(
+            "Changes not staged for commit",
+            &workspace_changeset,
+            &no_renames,
+            prefix,
+        )?;
+        self.print_changes("Untracked files", &untracked_changeset, &no_renames, prefix)?;
+        let ignored_changeset = report
+            .ignored
+            .iter()
+            .map(|file| {
+                (
+                    file.clone(),
+                    FileChangeType::Workspace(WorkspaceChangeType::Untracked),
+                )
+            })
+            .collect();
+        self.print_changes("Ignored files", &ignored_changeset, &no_renames, prefix)?;
+
+        if report.conflicted.is_empty() {
+            self.print_commit_status(report)?;
         }
 
         Ok(())
     }
 
-    fn print_conflicts(&self, conflicts: &ConflictSet) -> anyhow::Result<()> {
-        writeln!(self.writer(), "{}:\n", "Unmerged paths".bold())?;
-        writeln!(self.writer(), "  (fix conflicts and run 'bit commit')\n")?;
-        for (file, stages) in conflicts {
-            let ct = ConflictType::from_stages(stages);
+    /// Print the unmerged-paths summary and section, with git's hints
+    ///
+    /// Each path is labelled by the stages it has in the index (`both
+    /// modified`, `deleted by them`, ...).
+    fn print_conflicts(
+        &self,
+        conflicts: &BTreeMap<PathBuf, ConflictType>,
+        prefix: &Path,
+    ) -> anyhow::Result<()> {
+        writeln!(self.writer(), "{}", "You have unmerged paths.".yellow())?;
+        writeln!(self.writer(), "  (fix conflicts and run \"bit commit\")\n")?;
+        writeln!(self.writer(), "{}:", "Unmerged paths".bold())?;
+        writeln!(
+            self.writer(),
+            "  (use \"bit add <file>...\" to mark resolution)"
+        )?;
+        let quote_options = self.quote_options(false)?;
+        for (file, conflict) in conflicts {
             writeln!(
                 self.writer(),
                 "        {}{}",
-                ct.long_label().red(),
-                file.display().to_string().red()
+                conflict.long_label().red(),
+                quote_path(&relative_path(file, prefix), quote_options).red()
             )?;
         }
         writeln!(self.writer())?;
@@ -76,16 +299,23 @@ impl Repository {
         &self,
         message: &str,
         changeset: &BTreeMap<PathBuf, FileChangeType>,
+        renames: &BTreeMap<PathBuf, PathBuf>,
+        prefix: &Path,
     ) -> anyhow::Result<()> {
         if !changeset.is_empty() {
             writeln!(self.writer(), "{}:\n", message.bold())?;
+            let quote_options = self.quote_options(false)?;
             for (file, change) in changeset {
-                writeln!(
-                    self.writer(),
-                    "{}{}",
-                    change,
-                    file.display().to_string().cyan()
-                )?;
+                let path = quote_path(&relative_path(file, prefix), quote_options);
+                let path = match renames.get(file) {
+                    Some(old_path) => format!(
+                        "{} -> {}",
+                        quote_path(&relative_path(old_path, prefix), quote_options),
+                        path
+                    ),
+                    None => path,
+                };
+                writeln!(self.writer(), "{}{}", change, path.cyan())?;
             }
             writeln!(self.writer())?;
         }
@@ -93,17 +323,49 @@ impl Repository {
         Ok(())
     }
 
-    fn print_commit_status(&self, status_info: &StatusInfo) -> anyhow::Result<()> {
-        if !status_info.index_changeset.is_empty() {
+    /// Rename detection for staged changes: the flag, then `status.renames`,
+    /// then `diff.renames`
+    fn status_rename_options(
+        &self,
+        renames: Option<bool>,
+    ) -> anyhow::Result<Option<RenameOptions>> {
+        let enabled = match renames {
+            Some(enabled) => enabled,
+            None => match self.config_renames(STATUS_RENAMES_KEY)? {
+                Some(enabled) => enabled,
+                None => self.config_renames(RENAMES_KEY)?.unwrap_or(false),
+            },
+        };
+        if !enabled {
+            return Ok(None);
+        }
+
+        Ok(Some(RenameOptions {
+            threshold: DEFAULT_SIMILARITY,
+            copies: false,
+            limit: self.rename_limit()?,
+        }))
+    }
+
+    /// Read a renames setting, where `copies` also turns detection on
+    fn config_renames(&self, key: &str) -> anyhow::Result<Option<bool>> {
+        match self.config().get(key)?.as_deref() {
+            Some("copies" | "copy") => Ok(Some(true)),
+            _ => Ok(self.config().get_bool(key)?),
+        }
+    }
+
+    fn print_commit_status(&self, report: &StatusReport) -> anyhow::Result<()> {
+        if !report.staged.is_empty() {
             return Ok(());
         }
 
-        if !status_info.workspace_changeset.is_empty() {
+        if !report.unstaged.is_empty() {
             writeln!(self.writer(), "{}", "no changes added to commit".yellow())?;
             return Ok(());
         }
 
-        if !status_info.untracked_changeset.is_empty() {
+        if !report.untracked.is_empty() {
             writeln!(
                 self.writer(),
                 "{}",
@@ -120,3 +382,142 @@ impl Repository {
         Ok(())
     }
 }
+
+/// Rewrite a root-relative `path` relative to the `base` directory
+///
+/// Untracked directories keep their trailing separator; `base` itself is
+/// shown as `./`.
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let common = path
+        .components()
+        .zip(base.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative = base
+        .components()
+        .skip(common)
+        .map(|_| Component::ParentDir)
+        .collect::<PathBuf>();
+    relative.extend(path.components().skip(common));
+
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    if path.as_os_str().as_encoded_bytes().ends_with(b"/") {
+        relative.push("");
+    }
+
+    relative
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifacts::index::entry_mode::{EntryMode, FileMode};
+    use crate::artifacts::status::file_change::IndexChangeType;
+    use assert_fs::TempDir;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeSet;
+
+    #[tokio::test]
+    async fn test_status_reports_changes_by_category() {
+        let dir = TempDir::new().unwrap();
+        let mut repository =
+            Repository::new(dir.path().to_path_buf(), Box::new(std::io::sink())).unwrap();
+        repository.init(None).await.unwrap();
+
+        std::fs::write(dir.path().join("staged.txt"), "one\n").unwrap();
+        std::fs::write(dir.path().join("edited.txt"), "two\n").unwrap();
+        repository
+            .add(
+                &["staged.txt", "edited.txt"]
+                    .map(|file| dir.path().join(file).to_string_lossy().into_owned()),
+                false,
+            )
+            .await
+            .unwrap();
+        std::fs::write(dir.path().join("edited.txt"), "changed\n").unwrap();
+        std::fs::write(dir.path().join("new.txt"), "three\n").unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(dir.path().join("debug.log"), "four\n").unwrap();
+
+        let report = repository.status(&StatusOptions::default()).await.unwrap();
+
+        assert_eq!(
+            report.staged,
+            BTreeMap::from([
+                (PathBuf::from("edited.txt"), IndexChangeType::Added),
+                (PathBuf::from("staged.txt"), IndexChangeType::Added),
+            ])
+        );
+        assert_eq!(
+            report.unstaged,
+            BTreeMap::from([(PathBuf::from("edited.txt"), WorkspaceChangeType::Modified)])
+        );
+        assert_eq!(
+            report.untracked,
+            BTreeSet::from([PathBuf::from(".gitignore"), PathBuf::from("new.txt")])
+        );
+        assert_eq!(report.ignored, BTreeSet::from([PathBuf::from("debug.log")]));
+        assert!(report.conflicted.is_empty());
+        assert!(report.renames.is_empty());
+
+        // Entries of the reported paths come along, with no HEAD yet
+        assert!(report.head_entries.is_empty());
+        let staged = report
+            .index_entry(Path::new("staged.txt"), MergeStage::Clean)
+            .unwrap();
+        assert_eq!(
+            staged.oid.as_ref(),
+            "5626abf0f72e58d7a153368ba57db4c673c0e171"
+        );
+        assert_eq!(staged.mode, EntryMode::File(FileMode::Regular));
+        assert_eq!(
+            report.workspace_modes.get(Path::new("edited.txt")),
+            Some(&EntryMode::File(FileMode::Regular))
+        );
+        assert!(!report.workspace_modes.contains_key(Path::new("new.txt")));
+    }
+
+    #[test]
+    fn test_relative_path_from_root_is_unchanged() {
+        assert_eq!(
+            relative_path(Path::new("a/b.txt"), Path::new("")),
+            PathBuf::from("a/b.txt")
+        );
+    }
+
+    #[test]
+    fn test_relative_path_climbs_out_of_subdirectory() {
+        let base = Path::new("a/b");
+
+        assert_eq!(
+            relative_path(Path::new("a/b/3.txt"), base),
+            PathBuf::from("3.txt")
+        );
+        assert_eq!(
+            relative_path(Path::new("a/2.txt"), base),
+            PathBuf::from("../2.txt")
+        );
+        assert_eq!(
+            relative_path(Path::new("1.txt"), base),
+            PathBuf::from("../../1.txt")
+        );
+        assert_eq!(
+            relative_path(Path::new("c/4.txt"), base),
+            PathBuf::from("../../c/4.txt")
+        );
+    }
+
+    #[test]
+    fn test_relative_path_keeps_directory_separator() {
+        let base = Path::new("a");
+
+        assert_eq!(relative_path(Path::new("a/"), base), PathBuf::from("./"));
+        assert_eq!(
+            relative_path(Path::new("new/"), base),
+            PathBuf::from("../new/")
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use crate::areas::refs::{HEAD_REF_NAME, RefsError};
+use crate::areas::repository::Repository;
+use crate::artifacts::branch::branch_name::BranchName;
+use crate::artifacts::core::editor::edit_message;
+use crate::artifacts::objects::commit::Author;
+use crate::artifacts::objects::object::Object;
+use crate::artifacts::objects::object_id::ObjectId;
+use crate::artifacts::objects::tag::Tag;
+
+const TAG_EDITMSG: &str = "TAG_EDITMSG";
+
+/// Operation requested by `bit tag`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagAction {
+    /// Print the names of all tags
+    List,
+    /// Create (or, with `force`, move) a tag
+    Create {
+        name: String,
+        revision: Option<String>,
+        /// Create an annotated tag object instead of a plain ref
+        annotate: bool,
+        message: Option<String>,
+        force: bool,
+    },
+    /// Delete tags
+    Delete { names: Vec<String> },
+}
+
+impl Repository {
+    pub fn tag(&self, action: &TagAction) -> anyhow::Result<()> {
+        match action {
+            TagAction::List => {
+                for name in self.refs().list_tags()? {
+                    writeln!(self.writer(), "{}", name)?;
+                }
+            }
+            TagAction::Create {
+                name,
+                revision,
+                annotate,
+                message,
+                force,
+            } => self.create_tag(
+                name,
+                revision.as_deref(),
+                *annotate,
+                message.as_deref(),
+                *force,
+            )?,
+            TagAction::Delete { names } => {
+                for name in names {
+                    let tag_name = BranchName::try_parse(name.clone())?;
+                    let oid = self.refs().delete_tag(&tag_name)?;
+
+                    writeln!(
+                        self.writer(),
+                        "Deleted tag '{}' (was {})",
+                        name,
+                        oid.to_short_oid()
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Point `name` at a revision, either directly (lightweight tag) or
+    /// through a new tag object (annotated tag, implied by a message)
+    fn create_tag(
+        &self,
+        name: &str,
+        revision: Option<&str>,
+        annotate: bool,
+        message: Option<&str>,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let tag_name = BranchName::try_parse(name.to_string())?;
+        let revision = revision.unwrap_or(HEAD_REF_NAME);
+        let target = self.resolve_revision(revision)?;
+
+        // Fail before prompting for a message that would be thrown away
+        if !force && self.refs().read_tag(&tag_name)?.is_some() {
+            return Err(RefsError::TagAlreadyExists(name.to_string()).into());
+        }
+
+        let tag_oid = if annotate || message.is_some() {
+            let message = match message {
+                Some(message) => message.to_string(),
+                None => self.edit_tag_message(name)?,
+            };
+            self.write_tag_object(name, target, message)?
+        } else {
+            target
+        };
+
+        let previous = self.refs().write_tag(&tag_name, &tag_oid, force)?;
+        if let Some(previous) = previous
+            && previous != tag_oid
+        {
+            writeln!(
+                self.writer(),
+                "Updated tag '{}' (was {})",
+                name,
+                previous.to_short_oid()
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_tag_object(
+        &self,
+        name: &str,
+        target: ObjectId,
+        message: String,
+    ) -> anyhow::Result<ObjectId> {
+        let target_type = self.database().get_object_type(&target)?;
+        let tagger = Author::load_from_env()?;
+        let message = format!("{}\n", message.trim_end());
+
+        let tag = Tag::new(target, target_type, name.to_string(), tagger, message);
+        let tag_oid = tag.object_id()?;
+        self.database().store(tag)?;
+
+        Ok(tag_oid)
+    }
+
+    /// Ask for an annotated tag message in the editor
+    ///
+    /// Only done when stdin is a terminal; scripts must pass `-m`. An empty
+    /// message aborts the tag.
+    fn edit_tag_message(&self, name: &str) -> anyhow::Result<String> {
+        let template = format!(
+            "\n#\n# Write a message for tag:\n#   {}\n\
+            # Lines starting with '#' will be ignored.\n",
+            name
+        );
+        let message = edit_message(&self.git_dir().join(TAG_EDITMSG), &template)?
+            .ok_or_else(|| anyhow::anyhow!("no tag message given; use -m <message>"))?;
+        if message.is_empty() {
+            anyhow::bail!("no tag message given, aborting");
+        }
+
+        Ok(message)
+    }
+}
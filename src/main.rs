@@ -17,17 +17,42 @@
 
 #![allow(dead_code)]
 
-use crate::artifacts::core::PagerWriter;
+use crate::areas::config::Config;
+use crate::artifacts::archive::ArchiveFormat;
+use crate::artifacts::core::color::{ColorWhen, configured_color, should_colorize};
+use crate::artifacts::core::line_prefix::LinePrefixWriter;
+use crate::artifacts::core::{PagerConfig, PagerWriter};
+use crate::artifacts::diff::diff_target::DiffPrefix;
+use crate::artifacts::diff::moved_lines::ColorMoved;
+use crate::artifacts::diff::rename_detection::{RenameKind, parse_similarity};
+use crate::artifacts::diff::tree_diff::DiffFilter;
 use crate::artifacts::index::index_entry::MergeStage;
+use crate::artifacts::log::date_format::DateFormat;
+use crate::artifacts::log::pretty_format::{PrettyFormat, parse_pretty_format};
+use crate::artifacts::objects::SHORT_OID_LENGTH;
+use crate::artifacts::status::porcelain::PorcelainVersion;
+use crate::commands::plumbing::cat_file::CatFileMode;
+use crate::commands::plumbing::rev_parse::RevParseOptions;
+use crate::commands::plumbing::update_index::IndexFlagUpdate;
+use crate::commands::porcelain::apply::ApplyOptions;
+use crate::commands::porcelain::archive::ArchiveOptions;
+use crate::commands::porcelain::checkout::CheckoutMode;
+use crate::commands::porcelain::clean::CleanOptions;
+use crate::commands::porcelain::config::ConfigAction;
+use crate::commands::porcelain::diff::DiffFormat;
 use crate::commands::porcelain::log::parse_log_target;
 use crate::commands::porcelain::log::{LogOptions, LogRevisionTargets};
+use crate::commands::porcelain::reset::ResetMode;
+use crate::commands::porcelain::rm::RmOptions;
+use crate::commands::porcelain::tag::TagAction;
 use anyhow::Result;
 use areas::repository::Repository;
 use clap::{Parser, Subcommand, ValueEnum};
 use colored::control;
 use is_terminal::IsTerminal;
-use minus::{Pager, page_all};
-use std::path::PathBuf;
+use minus::page_all;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 
 // TODO: improve error handling and messages using thiserror
 // TODO: improve test harness using snapbox
@@ -36,6 +61,11 @@ mod areas;
 mod artifacts;
 mod commands;
 
+/// Config key enabling line numbers in the pager
+const PAGER_LINE_NUMBERS_KEY: &str = "core.pagerLineNumbers";
+/// Config key making the pager follow the output, opening it at its end
+const PAGER_FOLLOW_OUTPUT_KEY: &str = "core.pagerFollowOutput";
+
 /// Main CLI structure parsed by clap
 ///
 /// This is the top-level command-line interface for the bit version control system.
@@ -62,6 +92,23 @@ OPTIONS:
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    #[arg(
+        long,
+        global = true,
+        value_name = "WHEN",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "always",
+        help = "Whether to color the output (defaults to color.ui, or auto: only on a terminal)"
+    )]
+    color: Option<ColorWhen>,
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "color",
+        help = "Never color the output, same as --color=never"
+    )]
+    no_color: bool,
 }
 
 /// All available commands in the bit CLI
@@ -78,12 +125,20 @@ enum Commands {
     Init {
         #[arg(index = 1, help = "The path to the repository")]
         path: Option<String>,
+        #[arg(
+            short = 'b',
+            long = "initial-branch",
+            value_name = "BRANCH_NAME",
+            help = "The name of the initial branch (overrides init.defaultBranch)"
+        )]
+        initial_branch: Option<String>,
     },
     #[command(
         name = "hash-object",
         about = "Hash an object and optionally write it to the object database",
         long_about = "This command hashes an object file and can write it to the object database. \
-        It requires the path to the file to be specified."
+        It requires the path to the file to be specified. Given a directory, it hashes every \
+        file below it and prints one '<oid> TAB <path>' line per file, sorted by path."
     )]
     HashObject {
         #[arg(
@@ -93,6 +148,11 @@ enum Commands {
             help = "Write the object to the object database"
         )]
         write: bool,
+        #[arg(
+            long,
+            help = "Skip files ignored by .gitignore when hashing a directory"
+        )]
+        respect_ignore: bool,
         #[arg(index = 1)]
         file: String,
     },
@@ -105,9 +165,146 @@ enum Commands {
     LsTree {
         #[arg(short = 'r', long, help = "Recursively list the tree")]
         recursive: bool,
+        #[arg(short = 'l', long, help = "Show the size of blob entries")]
+        long: bool,
         #[arg(index = 1, help = "The tree SHA to list")]
         sha: String,
     },
+    #[command(
+        name = "rev-parse",
+        about = "Resolve revisions and report repository locations",
+        long_about = "This command prints the object ID of each given revision. \
+        It can also print the git directory, the working tree root, and whether the current \
+        directory is inside the working tree; it works from any subdirectory of the repository."
+    )]
+    RevParse {
+        #[arg(long, help = "Print the path of the .git directory")]
+        git_dir: bool,
+        #[arg(long, help = "Print the absolute path of the working tree root")]
+        show_toplevel: bool,
+        #[arg(
+            long,
+            help = "Print whether the current directory is inside the working tree"
+        )]
+        is_inside_work_tree: bool,
+        #[arg(help = "The revisions to resolve")]
+        revisions: Vec<String>,
+    },
+    #[command(
+        name = "unpack-objects",
+        about = "Unpack objects from a packed archive",
+        long_about = "This command reads a packfile, resolves all deltas, \
+        and writes every object it contains into the object database as a loose object."
+    )]
+    UnpackObjects {
+        #[arg(index = 1, help = "The packfile to read (defaults to stdin)")]
+        pack: Option<PathBuf>,
+    },
+    #[command(
+        name = "update-index",
+        about = "Set flags on index entries",
+        long_about = "This command sets or clears the assume-unchanged and skip-worktree bits \
+        of the index entries for the given paths, which makes status trust the index for them."
+    )]
+    UpdateIndex {
+        #[arg(
+            long,
+            conflicts_with = "no_assume_unchanged",
+            help = "Stop checking the workspace files for changes"
+        )]
+        assume_unchanged: bool,
+        #[arg(long, help = "Check the workspace files for changes again")]
+        no_assume_unchanged: bool,
+        #[arg(
+            long,
+            conflicts_with = "no_skip_worktree",
+            help = "Treat the workspace files as matching the index"
+        )]
+        skip_worktree: bool,
+        #[arg(long, help = "Compare the workspace files with the index again")]
+        no_skip_worktree: bool,
+        #[arg(required = true, help = "The tracked paths to update")]
+        paths: Vec<PathBuf>,
+    },
+    #[command(
+        name = "fsck",
+        about = "Report objects that no ref leads to",
+        long_about = "This command walks the objects reachable from refs, HEAD, the index and \
+        reflogs. It reports dangling objects, which only reflogs or nothing at all lead to and \
+        no other such object refers to, and with --unreachable every object that pruning \
+        would remove."
+    )]
+    Fsck {
+        #[arg(
+            long,
+            help = "Report every object not reachable from refs, the index or reflogs"
+        )]
+        unreachable: bool,
+        #[arg(
+            long,
+            overrides_with = "no_dangling",
+            help = "Report objects no ref leads to and no other object refers to (the default)"
+        )]
+        dangling: bool,
+        #[arg(
+            long,
+            overrides_with = "dangling",
+            help = "Do not report dangling objects"
+        )]
+        no_dangling: bool,
+        #[arg(
+            long,
+            help = "Do not consider commits referenced only by reflog entries reachable"
+        )]
+        no_reflogs: bool,
+    },
+    #[command(
+        name = "verify-commit",
+        about = "Check the structure of a commit object",
+        long_about = "This command parses the given commit and checks that its tree and parent lines \
+        point to objects of the right type and that its author and committer lines are well-formed."
+    )]
+    VerifyCommit {
+        #[arg(index = 1, help = "The commit to verify")]
+        revision: String,
+    },
+    #[command(
+        name = "verify-index",
+        about = "Check that the index is well-formed",
+        long_about = "This command checks the signature, version and entry count of the index, \
+        that every entry is padded to 8 bytes and that the entries are sorted by path, \
+        and the trailing checksum. The first inconsistency is reported with its byte offset."
+    )]
+    VerifyIndex,
+    #[command(
+        name = "cat-file",
+        about = "Show the type, size or content of a repository object",
+        long_about = "This command reads the header of an object and prints its type (-t) \
+        or the size of its content (-s), or pretty-prints the object (-p): blobs, commits \
+        and tags as stored, trees one entry per line. Corrupt or truncated object files are \
+        reported as such."
+    )]
+    CatFile {
+        #[arg(
+            short = 'p',
+            conflicts_with_all = ["type_only", "size", "allow_unknown_type"],
+            help = "Pretty-print the object content"
+        )]
+        pretty: bool,
+        #[arg(
+            short = 't',
+            conflicts_with = "size",
+            required_unless_present_any = ["size", "pretty"],
+            help = "Print the object type"
+        )]
+        type_only: bool,
+        #[arg(short = 's', help = "Print the object size")]
+        size: bool,
+        #[arg(long, help = "Allow -t and -s on objects of an unknown type")]
+        allow_unknown_type: bool,
+        #[arg(index = 1, help = "The object (full or abbreviated ID, or a revision)")]
+        object: String,
+    },
     #[command(
         name = "add",
         about = "Add files or directories to the index",
@@ -117,15 +314,61 @@ enum Commands {
     Add {
         #[arg(index = 1, help = "The files or directories to add to the index")]
         paths: Vec<String>,
+        #[arg(short, long, help = "Also add files ignored by .gitignore rules")]
+        force: bool,
+    },
+    #[command(
+        name = "rm",
+        about = "Remove files from the index and the working tree",
+        long_about = "This command removes the specified files from the index and the working \
+        tree. Directories are only removed with -r. Files whose staged or local changes would \
+        be lost are kept unless -f is given; --cached only removes them from the index."
+    )]
+    Rm {
+        #[arg(
+            index = 1,
+            required = true,
+            help = "The files or directories to remove"
+        )]
+        paths: Vec<String>,
+        #[arg(short = 'r', help = "Remove directories recursively")]
+        recursive: bool,
+        #[arg(
+            short,
+            long,
+            help = "Remove files even if they have staged or local changes"
+        )]
+        force: bool,
+        #[arg(long, help = "Only remove the paths from the index, keeping the files")]
+        cached: bool,
+    },
+    #[command(
+        name = "mv",
+        about = "Rename a tracked file",
+        long_about = "This command moves a tracked file in the working tree and renames its index \
+        entry, keeping the staged content. A destination that is a directory receives the file \
+        under its own name; an existing destination is only overwritten with -f."
+    )]
+    Mv {
+        #[arg(index = 1, help = "The tracked file to move")]
+        source: PathBuf,
+        #[arg(index = 2, help = "The new path, or a directory to move the file into")]
+        destination: PathBuf,
+        #[arg(short, long, help = "Overwrite an existing destination")]
+        force: bool,
     },
     #[command(
         name = "commit",
         about = "Create a new commit with the specified message",
-        long_about = "This command creates a new commit in the repository with the specified commit message."
+        long_about = "This command creates a new commit in the repository with the specified \
+        commit message. With --amend, the commit replaces HEAD instead, keeping its parents, \
+        author and, unless a new one is given, message."
     )]
     Commit {
         #[arg(short, long, help = "The commit message")]
         message: Option<String>,
+        #[arg(long, help = "Replace the tip commit instead of adding a new one")]
+        amend: bool,
     },
     #[command(
         name = "status",
@@ -137,9 +380,34 @@ enum Commands {
         #[arg(
             short,
             long,
-            help = "Give the output in a stable, machine-readable format"
+            value_name = "VERSION",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "v1",
+            help = "Give the output in a stable, machine-readable format (v1 or v2)"
+        )]
+        porcelain: Option<PorcelainVersion>,
+        #[arg(
+            long,
+            overrides_with = "no_renames",
+            help = "Show staged files with similar content as renames"
+        )]
+        renames: bool,
+        #[arg(
+            long,
+            overrides_with = "renames",
+            help = "Show staged renames as a deletion and an addition"
+        )]
+        no_renames: bool,
+        #[arg(
+            long,
+            help = "Also list the untracked files matched by .gitignore rules"
         )]
-        porcelain: bool,
+        ignored: bool,
+        #[arg(
+            help = "Only show these paths; :(exclude)<pattern> or :!<pattern> hides matching paths"
+        )]
+        paths: Vec<PathBuf>,
     },
     #[command(
         name = "diff",
@@ -155,6 +423,12 @@ enum Commands {
         cached: bool,
         #[arg(long, help = "Show only the names and status of changed files")]
         name_status: bool,
+        #[arg(
+            long,
+            conflicts_with = "name_status",
+            help = "Show the number of changed lines of each file and a summary instead of a patch"
+        )]
+        stat: bool,
         #[arg(
             long,
             help = "Filter the diff output by file status (e.g., A for added, D for deleted, M for modified)"
@@ -178,10 +452,105 @@ enum Commands {
             help = "Compare workspace to stage 3 (theirs/incoming)"
         )]
         theirs: bool,
-        #[arg(index = 1, help = "The first commit SHA to compare (optional)")]
+        #[arg(
+            long,
+            value_name = "PREFIX",
+            help = "Show the given source prefix instead of \"a/\""
+        )]
+        src_prefix: Option<String>,
+        #[arg(
+            long,
+            value_name = "PREFIX",
+            help = "Show the given destination prefix instead of \"b/\""
+        )]
+        dst_prefix: Option<String>,
+        #[arg(
+            long,
+            conflicts_with_all = ["src_prefix", "dst_prefix"],
+            help = "Do not show any source or destination prefix"
+        )]
+        no_prefix: bool,
+        #[arg(
+            short = 'M',
+            long = "find-renames",
+            value_name = "N",
+            num_args = 0..=1,
+            default_missing_value = "",
+            value_parser = parse_similarity,
+            help = "Report a deleted and an added file at least N% similar (default 50) as a \
+            rename, between two commits or with --cached"
+        )]
+        find_renames: Option<u8>,
+        #[arg(
+            short = 'C',
+            long = "find-copies",
+            value_name = "N",
+            num_args = 0..=1,
+            default_missing_value = "",
+            value_parser = parse_similarity,
+            conflicts_with = "find_renames",
+            help = "Like -M, also reporting added files similar to a modified file as copies"
+        )]
+        find_copies: Option<u8>,
+        #[arg(
+            long,
+            overrides_with = "no_textconv",
+            help = "Diff files through their diff.<driver>.textconv command (the default)"
+        )]
+        textconv: bool,
+        #[arg(
+            long,
+            overrides_with = "textconv",
+            help = "Diff files without running their textconv command"
+        )]
+        no_textconv: bool,
+        #[arg(
+            long,
+            overrides_with = "no_ext_diff",
+            help = "Hand changed files to the diff.external program (the default)"
+        )]
+        ext_diff: bool,
+        #[arg(
+            long,
+            overrides_with = "ext_diff",
+            help = "Diff files internally even when diff.external is set"
+        )]
+        no_ext_diff: bool,
+        #[arg(
+            long,
+            value_name = "MODE",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "zebra",
+            help = "Color lines moved within a file differently from added and removed lines"
+        )]
+        color_moved: Option<ColorMoved>,
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Write the diff to a file instead of the pager or stdout"
+        )]
+        output: Option<PathBuf>,
+        #[arg(
+            long,
+            value_name = "PREFIX",
+            help = "Prepend the given string to every line of output"
+        )]
+        line_prefix: Option<String>,
+        #[arg(help = "The first commit SHA to compare (optional)")]
         old_revision: Option<String>,
-        #[arg(index = 2, help = "The second commit SHA to compare (optional)")]
+        #[arg(help = "The second commit SHA to compare (optional)")]
         new_revision: Option<String>,
+        #[arg(
+            last = true,
+            help = "Only diff these paths; :(exclude)<pattern> or :!<pattern> drops matching paths"
+        )]
+        paths: Vec<PathBuf>,
+        #[arg(
+            long,
+            help = "Show line numbers when paging output (also enabled by core.pagerLineNumbers)"
+        )]
+        line_numbers: bool,
     },
     #[command(
         name = "branch",
@@ -192,6 +561,17 @@ enum Commands {
         #[command(subcommand)]
         action: BranchAction,
     },
+    #[command(
+        name = "bisect",
+        about = "Use binary search to find the commit that introduced a bug",
+        long_about = "This command records a bisection session under .git/BISECT_* files. \
+        After a bad and at least one good commit are marked, it checks out the commit halfway \
+        between them until the first bad commit is found."
+    )]
+    Bisect {
+        #[command(subcommand)]
+        action: BisectAction,
+    },
     #[command(
         name = "checkout",
         about = "Switch branches or restore working tree files",
@@ -199,8 +579,51 @@ enum Commands {
         updating the working directory and the index to match the state of that revision."
     )]
     Checkout {
-        #[arg(index = 1, help = "The target revision to checkout")]
-        target_revision: String,
+        #[arg(
+            required_unless_present_any = ["orphan", "paths"],
+            help = "The target revision to checkout"
+        )]
+        target_revision: Option<String>,
+        #[arg(
+            long,
+            value_name = "NEW_BRANCH",
+            conflicts_with_all = ["target_revision", "paths"],
+            help = "Create a new branch with no history and switch to it"
+        )]
+        orphan: Option<String>,
+        #[arg(
+            long,
+            requires = "paths",
+            conflicts_with_all = ["target_revision", "theirs"],
+            help = "Resolve conflicted paths with our version (stage 2)"
+        )]
+        ours: bool,
+        #[arg(
+            long,
+            requires = "paths",
+            conflicts_with = "target_revision",
+            help = "Resolve conflicted paths with their version (stage 3)"
+        )]
+        theirs: bool,
+        #[arg(
+            short,
+            long,
+            conflicts_with_all = ["ours", "theirs"],
+            help = "Discard local changes to tracked files instead of aborting"
+        )]
+        force: bool,
+        #[arg(
+            short,
+            long,
+            conflicts_with_all = ["force", "ours", "theirs", "paths"],
+            help = "Merge local changes into the files the switch updates instead of aborting"
+        )]
+        merge: bool,
+        #[arg(
+            last = true,
+            help = "Paths to restore from the index, or from the target revision if given"
+        )]
+        paths: Vec<PathBuf>,
     },
     #[command(
         name = "log",
@@ -223,8 +646,19 @@ enum Commands {
         oneline: bool,
         #[arg(long, help = "Show abbreviated commit hashes")]
         abbrev_commit: bool,
-        #[arg(long, help = "Pretty format for log output")]
-        format: Option<CommitDisplayFormat>,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Abbreviate object names to at least N hex digits, more if ambiguous"
+        )]
+        abbrev: Option<usize>,
+        #[arg(
+            long,
+            alias = "pretty",
+            value_parser = parse_pretty_format,
+            help = "Pretty format for log output (medium, oneline, raw, or format:<template>)"
+        )]
+        format: Option<PrettyFormat>,
         #[arg(
             long,
             help = "Whether to decorate commit messages with refs (branches, tags, etc.)"
@@ -232,6 +666,371 @@ enum Commands {
         decorate: Option<CommitDecoration>,
         #[arg(short, long, help = "Show the full diff of each commit")]
         patch: bool,
+        #[arg(
+            short = 's',
+            long,
+            help = "Suppress all diff output, even when other flags ask for it"
+        )]
+        no_patch: bool,
+        #[arg(
+            long,
+            help = "Show a diffstat followed by the full diff of each commit"
+        )]
+        patch_with_stat: bool,
+        #[arg(
+            long,
+            help = "Show the raw change lines (:mode mode oid oid status path) of each commit"
+        )]
+        raw: bool,
+        #[arg(
+            long,
+            help = "Only show commits and changes of the given kinds: \
+            (A)dded, (D)eleted, (M)odified"
+        )]
+        diff_filter: Option<String>,
+        #[arg(long, help = "Draw a text-based graph of the commit history")]
+        graph: bool,
+        #[arg(
+            long,
+            help = "Show only commits whose message matches the given regular expression \
+            (repeat to match any of several)"
+        )]
+        grep: Vec<String>,
+        #[arg(
+            long,
+            requires = "grep",
+            help = "Show only commits whose message does not match the --grep patterns"
+        )]
+        invert_grep: bool,
+        #[arg(
+            long,
+            requires = "grep",
+            help = "Show only commits whose message matches all the --grep patterns"
+        )]
+        all_match: bool,
+        #[arg(
+            long,
+            help = "Show only commits whose author (Name <email>) matches the given regular \
+            expression (repeat to match any of several)"
+        )]
+        author: Vec<String>,
+        #[arg(
+            short = 'i',
+            long = "regexp-ignore-case",
+            help = "Match the --grep and --author patterns regardless of case"
+        )]
+        ignore_case: bool,
+        #[arg(
+            long,
+            help = "Show the history of all refs (branches, tags, remotes) and HEAD"
+        )]
+        all: bool,
+        #[arg(
+            long,
+            help = "Print the number of commits that would be shown and exit"
+        )]
+        count: bool,
+        #[arg(
+            short = 'n',
+            long,
+            value_name = "N",
+            help = "Show at most N commits, counted after path and message filtering"
+        )]
+        max_count: Option<usize>,
+        #[arg(
+            long,
+            value_name = "N",
+            help = "Skip the first N commits before showing any"
+        )]
+        skip: Option<usize>,
+        #[arg(
+            long,
+            help = "Follow only the first parent of merge commits, and show their changes \
+            against it"
+        )]
+        first_parent: bool,
+        #[arg(
+            long,
+            help = "Show only commits pointed at by a branch or tag, and the merges joining them"
+        )]
+        simplify_by_decoration: bool,
+        #[arg(
+            long,
+            help = "Also print the IDs of each commit's parents after its own"
+        )]
+        parents: bool,
+        #[arg(
+            long,
+            help = "Also show the excluded commits bordering the range, marked with '-'"
+        )]
+        boundary: bool,
+        #[arg(long, help = "Hand changed files to the diff.external program")]
+        ext_diff: bool,
+        #[arg(
+            long,
+            value_name = "MODE",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "zebra",
+            help = "Color lines moved within a file differently from added and removed lines"
+        )]
+        color_moved: Option<ColorMoved>,
+        #[arg(
+            long,
+            help = "Show line numbers when paging output (also enabled by core.pagerLineNumbers)"
+        )]
+        line_numbers: bool,
+    },
+    #[command(
+        name = "show",
+        about = "Show a commit and its changes",
+        long_about = "This command shows the log message and the diff of a commit. \
+        Merge commits are shown with a combined diff against all their parents. \
+        An annotated tag is shown with its tagger and message, then the commit it tags."
+    )]
+    Show {
+        #[arg(index = 1, help = "The commit to show (defaults to HEAD)")]
+        revision: Option<String>,
+        #[arg(
+            long,
+            overrides_with = "no_textconv",
+            help = "Diff files through their diff.<driver>.textconv command (the default)"
+        )]
+        textconv: bool,
+        #[arg(
+            long,
+            overrides_with = "textconv",
+            help = "Diff files without running their textconv command"
+        )]
+        no_textconv: bool,
+        #[arg(long, help = "Hand changed files to the diff.external program")]
+        ext_diff: bool,
+        #[arg(
+            long,
+            value_name = "MODE",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "zebra",
+            help = "Color lines moved within a file differently from added and removed lines"
+        )]
+        color_moved: Option<ColorMoved>,
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Write the diff to a file instead of the pager or stdout"
+        )]
+        output: Option<PathBuf>,
+        #[arg(
+            long,
+            value_name = "PREFIX",
+            help = "Prepend the given string to every line of output"
+        )]
+        line_prefix: Option<String>,
+    },
+    #[command(
+        name = "format-patch",
+        about = "Export a commit as an email patch",
+        long_about = "This command prints a commit as a mail-formatted patch (From, Date and \
+        Subject: [PATCH] headers, the message body and the diff) suitable for \
+        email-based workflows. Redirect the output to a file to save the patch."
+    )]
+    FormatPatch {
+        #[arg(index = 1, help = "The commit to export (defaults to HEAD)")]
+        revision: Option<String>,
+    },
+    #[command(
+        name = "am",
+        about = "Apply patches from a mailbox",
+        long_about = "This command applies the patches of a mailbox written by format-patch to \
+        the working tree and the index, and commits each one with its original author, date \
+        and message. It stops at the first patch that does not apply."
+    )]
+    Am {
+        #[arg(index = 1, help = "The mailbox (patch file) to apply")]
+        patch: PathBuf,
+    },
+    #[command(
+        name = "apply",
+        about = "Apply a patch to files and optionally to the index",
+        long_about = "This command applies a diff, as written by diff, to the working tree. \
+        Files created by the patch get the mode it declares, and files it deletes are only \
+        removed when their content matches. Nothing is changed unless the whole patch applies."
+    )]
+    Apply {
+        #[arg(index = 1, help = "The patch file to apply")]
+        patch: PathBuf,
+        #[arg(long, help = "Apply the patch to both the working tree and the index")]
+        index: bool,
+        #[arg(
+            short = '3',
+            long = "3way",
+            help = "Fall back to a three-way merge with the blobs named in the patch when hunks \
+            don't apply (implies --index)"
+        )]
+        three_way: bool,
+    },
+    #[command(
+        name = "archive",
+        about = "Create an archive of the files of a commit or tree",
+        long_about = "This command writes the snapshot of a commit or tree as a tar (default) \
+        or zip archive, to stdout or to the file given with --output. Files keep their \
+        executable bit and get the commit date as their modification time."
+    )]
+    Archive {
+        #[arg(index = 1, help = "The commit or tree to archive")]
+        revision: String,
+        #[arg(
+            long,
+            value_enum,
+            help = "The archive format (inferred from --output, otherwise tar)"
+        )]
+        format: Option<ArchiveFormat>,
+        #[arg(
+            long,
+            help = "Prepend <prefix> to every path (add a trailing '/' for a directory)"
+        )]
+        prefix: Option<String>,
+        #[arg(short, long, help = "Write the archive to <file> instead of stdout")]
+        output: Option<PathBuf>,
+    },
+    #[command(
+        name = "tag",
+        about = "Create, list, or delete tags",
+        long_about = "This command creates lightweight tags (plain refs under refs/tags) or, \
+        with -a or -m, annotated tag objects recording the tagger and a message. \
+        Without a tag name it lists the existing tags."
+    )]
+    Tag {
+        #[arg(index = 1, help = "The tag to create")]
+        name: Option<String>,
+        #[arg(
+            index = 2,
+            requires = "name",
+            help = "The object to tag (defaults to HEAD)"
+        )]
+        revision: Option<String>,
+        #[arg(
+            short,
+            long,
+            requires = "name",
+            help = "Create an annotated tag, asking for a message in the editor without -m"
+        )]
+        annotate: bool,
+        #[arg(short, long, requires = "name", help = "The tag message (implies -a)")]
+        message: Option<String>,
+        #[arg(short, long, requires = "name", help = "Replace an existing tag")]
+        force: bool,
+        #[arg(
+            short,
+            long,
+            num_args = 1..,
+            value_name = "TAG",
+            conflicts_with_all = ["name", "annotate", "message", "force", "list"],
+            help = "Delete the given tags"
+        )]
+        delete: Vec<String>,
+        #[arg(short, long, conflicts_with = "name", help = "List tags")]
+        list: bool,
+    },
+    #[command(
+        name = "reflog",
+        about = "Show the recorded updates of a ref",
+        long_about = "This command lists the entries of a ref's reflog (.git/logs/<ref>), newest \
+        first. Each entry can be named as <ref>@{n}, the value the ref had n updates ago. \
+        Without a subcommand, the reflog of HEAD is shown."
+    )]
+    Reflog {
+        #[command(subcommand)]
+        action: Option<ReflogAction>,
+    },
+    #[command(
+        name = "reset",
+        about = "Reset current HEAD to the specified state",
+        long_about = "This command moves the current branch to the target revision and, \
+        depending on the mode, resets the index and the working tree to match it. \
+        The previous HEAD is saved as ORIG_HEAD and the move is logged in the reflog."
+    )]
+    Reset {
+        #[arg(index = 1, help = "The revision to reset to (defaults to HEAD)")]
+        target_revision: Option<String>,
+        #[arg(long, group = "mode", help = "Only move HEAD")]
+        soft: bool,
+        #[arg(long, group = "mode", help = "Move HEAD and reset the index (default)")]
+        mixed: bool,
+        #[arg(
+            long,
+            group = "mode",
+            help = "Move HEAD and reset the index and working tree, discarding local changes"
+        )]
+        hard: bool,
+        #[arg(
+            long,
+            group = "mode",
+            help = "Move HEAD and update files that differ between the commits, \
+            aborting if any of them has local changes"
+        )]
+        keep: bool,
+    },
+    #[command(
+        name = "clean",
+        about = "Remove untracked files from the working tree",
+        long_about = "This command removes files that are not tracked by the index. \
+        It refuses to delete anything unless -f is given; ignored files are kept unless -x is given."
+    )]
+    Clean {
+        #[arg(short, long, help = "Actually remove the untracked files")]
+        force: bool,
+        #[arg(short = 'n', long, help = "Only show what would be removed")]
+        dry_run: bool,
+        #[arg(short = 'd', help = "Also remove untracked directories")]
+        directories: bool,
+        #[arg(short = 'x', help = "Also remove ignored files")]
+        ignored: bool,
+    },
+    #[command(
+        name = "gc",
+        about = "Pack the object database",
+        long_about = "This command packs every loose object, and the contents of existing packs, \
+        into a single packfile. Packs with a .keep file are left alone. With --auto it only \
+        packs the loose objects past the gc.auto loose object count (default 6700), and only \
+        consolidates the packs past the gc.autoPackLimit pack count (default 50)."
+    )]
+    Gc {
+        #[arg(long, help = "Only pack when the configured thresholds are exceeded")]
+        auto: bool,
+    },
+    #[command(
+        name = "config",
+        about = "Get and set repository options",
+        long_about = "This command reads and writes variables of the repository config file. \
+        Keys may hold several values: --add appends one, --get-all lists them all."
+    )]
+    Config {
+        #[arg(index = 1, help = "The config key, as section[.subsection].name")]
+        key: String,
+        #[arg(index = 2, help = "The value to set (omit to read the key)")]
+        value: Option<String>,
+        #[arg(
+            long,
+            requires = "value",
+            conflicts_with_all = ["get_all", "unset", "unset_all"],
+            help = "Add a new value to the key without altering existing ones"
+        )]
+        add: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["value", "unset", "unset_all"],
+            help = "Print every value of the key"
+        )]
+        get_all: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["value", "unset_all"],
+            help = "Remove the key, failing if it has multiple values"
+        )]
+        unset: bool,
+        #[arg(long, conflicts_with = "value", help = "Remove every value of the key")]
+        unset_all: bool,
     },
     #[command(
         name = "merge",
@@ -248,6 +1047,12 @@ enum Commands {
             help = "Resume an in-progress merge after resolving conflicts"
         )]
         continue_merge: bool,
+        #[arg(
+            long,
+            conflicts_with = "continue_merge",
+            help = "Create a merge commit even when the merge could fast-forward"
+        )]
+        no_ff: bool,
     },
 }
 
@@ -261,6 +1066,8 @@ pub enum CommitDisplayFormat {
     Medium,
     #[value(name = "oneline", help = "One line format")]
     OneLine,
+    #[value(name = "raw", help = "Commit headers and message as stored")]
+    Raw,
 }
 
 /// Decoration options for commit output
@@ -298,9 +1105,72 @@ enum BranchAction {
     List {
         #[arg(short = 'v', long, help = "Show more information")]
         verbose: bool,
+        #[arg(
+            long,
+            conflicts_with = "verbose",
+            help = "Print each branch with a format using %(refname[:short]), \
+            %(objectname[:short]), %(subject), %(HEAD) and %(description)"
+        )]
+        format: Option<String>,
+    },
+    #[command(
+        name = "edit-description",
+        visible_alias = "set-description",
+        about = "Set the description of a branch (branch.<name>.description)"
+    )]
+    EditDescription {
+        #[arg(
+            index = 1,
+            help = "The branch to describe (defaults to the current branch)"
+        )]
+        branch_name: Option<String>,
+        #[arg(
+            short,
+            long,
+            help = "The description; without it, the editor is opened. Empty removes it"
+        )]
+        message: Option<String>,
+    },
+}
+
+/// Reflog subcommands
+#[derive(Subcommand)]
+enum ReflogAction {
+    #[command(name = "show", about = "List the reflog entries of a ref")]
+    Show {
+        #[arg(index = 1, help = "The ref whose reflog to show (defaults to HEAD)")]
+        reference: Option<String>,
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            help = "Show the date of each entry instead of its index"
+        )]
+        date: Option<DateFormat>,
     },
 }
 
+/// Bisect session subcommands
+#[derive(Subcommand)]
+enum BisectAction {
+    #[command(name = "start", about = "Start a bisection session")]
+    Start,
+    #[command(name = "bad", about = "Mark a commit as bad")]
+    Bad {
+        #[arg(index = 1, help = "The bad revision (defaults to HEAD)")]
+        revision: Option<String>,
+    },
+    #[command(name = "good", about = "Mark one or more commits as good")]
+    Good {
+        #[arg(index = 1, help = "The good revision(s) (defaults to HEAD)")]
+        revisions: Vec<String>,
+    },
+    #[command(
+        name = "reset",
+        about = "End the session and return to the original HEAD"
+    )]
+    Reset,
+}
+
 /// Application entry point
 ///
 /// Initializes the async runtime and handles top-level errors.
@@ -328,32 +1198,51 @@ async fn main() {
 /// - Output is redirected to a file or pipe
 /// - NO_PAGER environment variable is set
 ///
+/// The pager supports searching, and shows line numbers with `--line-numbers`
+/// (diff and log) or `core.pagerLineNumbers`.
+///
 /// # Color Handling
 ///
-/// Colors are enabled when using a pager (since final output goes to terminal),
-/// and disabled when piping to avoid ANSI escape codes in redirected output.
+/// `--color`/`--no-color`, then the command's `color.*` config and `color.ui`
+/// decide whether to color; by default, output is colored only on a terminal
+/// (including through the pager), so redirected output has no ANSI escape codes.
 async fn run() -> Result<()> {
     // Decide whether to use a pager or stdout directly FIRST, before parsing CLI
-    // This ensures color settings are applied before any colored output is generated
     let use_pager = std::io::stdout().is_terminal() && std::env::var("NO_PAGER").is_err();
 
-    // Force colored output when using pager (since the final output goes to terminal via pager)
-    // We need to set this BEFORE parsing CLI and BEFORE any colored output is generated
-    if use_pager {
-        control::set_override(true);
-    } else if !std::io::stdout().is_terminal() {
-        // Disable colors when piping to non-terminal
-        control::set_override(false);
-    }
-
     let cli = Cli::parse();
-    let pager = Pager::new();
+    // Decided before any colored output is generated
+    let color = if cli.no_color {
+        Some(ColorWhen::Never)
+    } else {
+        cli.color
+    };
+    let config_repository = config_repository()?;
+    // `config` has to keep working to fix a bad color setting
+    let configured = match &cli.command {
+        Commands::Config { .. } => None,
+        command => color_config(config_repository.as_ref(), color_config_key(command))?,
+    };
+    control::set_override(should_colorize(
+        std::io::stdout().is_terminal(),
+        configured,
+        color,
+    ));
+    let pager_config = if use_pager {
+        pager_config(config_repository.as_ref(), line_numbers(&cli.command))?
+    } else {
+        PagerConfig::default()
+    };
 
     let stdout_writer = Box::new(std::io::stdout());
-    let pager_writer = Box::new(PagerWriter::new(pager.clone()));
+    let pager_writer = Box::new(PagerWriter::with_config(&pager_config)?);
+    let pager = pager_writer.pager().clone();
 
     match &cli.command {
-        Commands::Init { path } => {
+        Commands::Init {
+            path,
+            initial_branch,
+        } => {
             let mut repository = match path {
                 Some(path) => Repository::new(PathBuf::from(path), stdout_writer)?,
                 None => {
@@ -362,56 +1251,209 @@ async fn run() -> Result<()> {
                 }
             };
 
-            repository.init().await?
+            repository.init(initial_branch.as_deref()).await?
+        }
+        Commands::HashObject {
+            write,
+            respect_ignore,
+            file,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            repository.hash_object(file, *write, *respect_ignore)?
+        }
+        Commands::LsTree {
+            recursive,
+            long,
+            sha,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            repository.ls_tree(sha, *recursive, *long).await?
+        }
+        Commands::RevParse {
+            git_dir,
+            show_toplevel,
+            is_inside_work_tree,
+            revisions,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let repository = Repository::discover(pwd.clone(), stdout_writer)?;
+
+            repository.rev_parse(
+                RevParseOptions {
+                    git_dir: *git_dir,
+                    show_toplevel: *show_toplevel,
+                    is_inside_work_tree: *is_inside_work_tree,
+                },
+                revisions,
+                &pwd,
+            )?
+        }
+        Commands::UnpackObjects { pack } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            repository.unpack_objects(pack.as_deref())?
+        }
+        Commands::UpdateIndex {
+            assume_unchanged,
+            no_assume_unchanged,
+            skip_worktree,
+            no_skip_worktree,
+            paths,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            let mut updates = Vec::new();
+            if *assume_unchanged || *no_assume_unchanged {
+                updates.push(IndexFlagUpdate::AssumeUnchanged(*assume_unchanged));
+            }
+            if *skip_worktree || *no_skip_worktree {
+                updates.push(IndexFlagUpdate::SkipWorktree(*skip_worktree));
+            }
+
+            repository.update_index(paths, &updates).await?
+        }
+        Commands::Fsck {
+            unreachable,
+            dangling: _,
+            no_dangling,
+            no_reflogs,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let repository = Repository::open(pwd, stdout_writer)?;
+
+            repository
+                .fsck(*unreachable, !*no_dangling, *no_reflogs)
+                .await?
         }
-        Commands::HashObject { write, file } => {
+        Commands::VerifyCommit { revision } => {
             let pwd = std::env::current_dir()?;
-            let mut repository = Repository::new(pwd, stdout_writer)?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
 
-            repository.hash_object(file, *write)?
+            repository.verify_commit(revision)?
         }
-        Commands::LsTree { recursive, sha } => {
+        Commands::VerifyIndex => {
             let pwd = std::env::current_dir()?;
-            let mut repository = Repository::new(pwd, stdout_writer)?;
+            let repository = Repository::open(pwd, stdout_writer)?;
 
-            repository.ls_tree(sha, *recursive).await?
+            repository.verify_index().await?
+        }
+        Commands::CatFile {
+            pretty,
+            type_only,
+            size: _,
+            allow_unknown_type,
+            object,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let repository = Repository::open(pwd, stdout_writer)?;
+
+            let mode = if *pretty {
+                CatFileMode::Pretty
+            } else if *type_only {
+                CatFileMode::Type
+            } else {
+                CatFileMode::Size
+            };
+            repository.cat_file(object, mode, *allow_unknown_type)?
+        }
+        Commands::Add { paths, force } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            repository.add(paths, *force).await?
+        }
+        Commands::Rm {
+            paths,
+            recursive,
+            force,
+            cached,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            repository
+                .rm(
+                    paths,
+                    RmOptions {
+                        recursive: *recursive,
+                        force: *force,
+                        cached: *cached,
+                    },
+                )
+                .await?
         }
-        Commands::Add { paths } => {
+        Commands::Mv {
+            source,
+            destination,
+            force,
+        } => {
             let pwd = std::env::current_dir()?;
-            let mut repository = Repository::new(pwd, stdout_writer)?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
 
-            repository.add(paths).await?
+            repository.mv(source, destination, *force).await?
         }
-        Commands::Commit { message } => {
+        Commands::Commit { message, amend } => {
             let pwd = std::env::current_dir()?;
-            let mut repository = Repository::new(pwd, stdout_writer)?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
 
-            repository.commit(message.as_deref()).await?
+            repository.commit(message.as_deref(), *amend).await?
         }
-        Commands::Status { porcelain } => {
+        Commands::Status {
+            porcelain,
+            renames,
+            no_renames,
+            ignored,
+            paths,
+        } => {
             let pwd = std::env::current_dir()?;
-            let mut repository = Repository::new(pwd, stdout_writer)?;
+            let mut repository = Repository::discover(pwd.clone(), stdout_writer)?;
+            let renames = match (*renames, *no_renames) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
 
-            repository.display_status(*porcelain).await?
+            repository
+                .display_status(*porcelain, renames, *ignored, paths, &pwd)
+                .await?
         }
         Commands::Diff {
             cached,
             name_status,
+            stat,
             diff_filter,
             base,
             ours,
             theirs,
+            src_prefix,
+            dst_prefix,
+            no_prefix,
+            find_renames,
+            find_copies,
+            textconv: _,
+            no_textconv,
+            ext_diff: _,
+            no_ext_diff,
+            color_moved,
+            output,
+            line_prefix,
             old_revision,
             new_revision,
+            paths,
+            line_numbers: _,
         } => {
+            let paging = use_pager && output.is_none();
+            let writer: Box<dyn Write> = if paging { pager_writer } else { stdout_writer };
             let pwd = std::env::current_dir()?;
-            let mut repository = Repository::new(
-                pwd,
-                if use_pager {
-                    pager_writer
-                } else {
-                    stdout_writer
-                },
+            let mut repository = Repository::discover(
+                pwd.clone(),
+                diff_writer(writer, output.as_deref(), line_prefix.clone(), color)?,
             )?;
 
             let conflict_stage = match (base, ours, theirs) {
@@ -421,24 +1463,48 @@ async fn run() -> Result<()> {
                 _ => None,
             };
 
+            let default_prefix = DiffPrefix::default();
+            repository.set_diff_prefix(if *no_prefix {
+                DiffPrefix::none()
+            } else {
+                DiffPrefix::new(
+                    src_prefix.clone().unwrap_or(default_prefix.src),
+                    dst_prefix.clone().unwrap_or(default_prefix.dst),
+                )
+            });
+
+            repository.set_textconv(!*no_textconv);
+            repository.set_ext_diff(!*no_ext_diff);
+            repository.set_color_moved(color_moved.unwrap_or_default());
+
             repository
                 .diff(
                     *cached,
-                    *name_status,
+                    match (*name_status, *stat) {
+                        (true, _) => DiffFormat::NameStatus,
+                        (_, true) => DiffFormat::Stat,
+                        _ => DiffFormat::Patch,
+                    },
                     diff_filter.as_deref(),
                     old_revision.as_deref(),
                     new_revision.as_deref(),
                     conflict_stage,
+                    find_copies
+                        .map(|threshold| (RenameKind::Copy, threshold))
+                        .or(find_renames.map(|threshold| (RenameKind::Rename, threshold))),
+                    paths,
+                    &pwd,
                 )
                 .await?;
+            repository.writer().flush()?;
 
-            if use_pager {
+            if paging {
                 page_all(pager)?;
             }
         }
         Commands::Branch { action } => {
             let pwd = std::env::current_dir()?;
-            let mut repository = Repository::new(
+            let mut repository = Repository::open(
                 pwd,
                 if use_pager {
                     pager_writer
@@ -453,23 +1519,94 @@ async fn run() -> Result<()> {
                 page_all(pager)?;
             }
         }
-        Commands::Checkout { target_revision } => {
+        Commands::Bisect { action } => {
             let pwd = std::env::current_dir()?;
-            let mut repository = Repository::new(pwd, stdout_writer)?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
 
-            repository.checkout(target_revision.as_str()).await?
+            repository.bisect(action).await?
+        }
+        Commands::Checkout {
+            target_revision,
+            orphan,
+            ours,
+            theirs,
+            force,
+            merge,
+            paths,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            match (orphan, target_revision) {
+                (Some(orphan), _) => repository.checkout_orphan(orphan)?,
+                (None, _) if *ours => repository.checkout_stage(MergeStage::Ours, paths).await?,
+                (None, _) if *theirs => {
+                    repository.checkout_stage(MergeStage::Theirs, paths).await?
+                }
+                (None, target_revision) if !paths.is_empty() => {
+                    repository
+                        .checkout_paths(target_revision.as_deref(), paths)
+                        .await?
+                }
+                (None, Some(target_revision)) => {
+                    let mode = if *force {
+                        CheckoutMode::Force
+                    } else if *merge {
+                        CheckoutMode::Merge
+                    } else {
+                        CheckoutMode::Safe
+                    };
+                    repository.checkout(target_revision.as_str(), mode).await?
+                }
+                (None, None) => anyhow::bail!("target revision required"),
+            }
         }
         Commands::Log {
             target_revisions,
             target_files,
             oneline,
             abbrev_commit,
+            abbrev,
             format,
             decorate,
             patch,
+            no_patch,
+            patch_with_stat,
+            raw,
+            diff_filter,
+            graph,
+            grep,
+            invert_grep,
+            all_match,
+            author,
+            ignore_case,
+            all,
+            count,
+            max_count,
+            skip,
+            first_parent,
+            simplify_by_decoration,
+            parents,
+            boundary,
+            ext_diff,
+            color_moved,
+            line_numbers: _,
         } => {
+            let diff_filter = diff_filter
+                .as_deref()
+                .map(|filter| {
+                    DiffFilter::try_parse(filter)
+                        .ok_or_else(|| anyhow::anyhow!("invalid --diff-filter value: {}", filter))
+                })
+                .transpose()?;
+            let (format, template) = match format.clone() {
+                Some(PrettyFormat::Builtin(format)) => (format, None),
+                Some(PrettyFormat::Template(template)) => (Default::default(), Some(template)),
+                None => (Default::default(), None),
+            };
+
             let pwd = std::env::current_dir()?;
-            let repository = Repository::new(
+            let repository = Repository::open(
                 pwd,
                 if use_pager {
                     pager_writer
@@ -477,28 +1614,240 @@ async fn run() -> Result<()> {
                     stdout_writer
                 },
             )?;
+            repository.set_ext_diff(*ext_diff);
+            repository.set_color_moved(color_moved.unwrap_or_default());
 
             repository.log(&LogOptions {
                 target_revisions: target_revisions.clone(),
                 target_files: target_files.clone(),
                 oneline: *oneline,
                 abbrev_commit: *abbrev_commit,
-                format: (*format).unwrap_or_default(),
+                abbrev: abbrev.unwrap_or(SHORT_OID_LENGTH),
+                format,
+                template,
                 decorate: (*decorate).unwrap_or_default(),
-                patch: *patch,
+                patch: (*patch || *patch_with_stat) && !*no_patch,
+                stat: *patch_with_stat && !*no_patch,
+                raw: *raw && !*no_patch,
+                diff_filter,
+                graph: *graph,
+                grep: grep.clone(),
+                invert_grep: *invert_grep,
+                all_match: *all_match,
+                author: author.clone(),
+                ignore_case: *ignore_case,
+                all: *all,
+                count: *count,
+                max_count: *max_count,
+                skip: *skip,
+                first_parent: *first_parent,
+                simplify_by_decoration: *simplify_by_decoration,
+                parents: *parents,
+                boundary: *boundary,
             })?;
 
             if use_pager {
                 page_all(pager)?;
             }
         }
+        Commands::Show {
+            revision,
+            textconv: _,
+            no_textconv,
+            ext_diff,
+            color_moved,
+            output,
+            line_prefix,
+        } => {
+            let paging = use_pager && output.is_none();
+            let writer: Box<dyn Write> = if paging { pager_writer } else { stdout_writer };
+            let pwd = std::env::current_dir()?;
+            let repository = Repository::open(
+                pwd,
+                diff_writer(writer, output.as_deref(), line_prefix.clone(), color)?,
+            )?;
+            repository.set_textconv(!*no_textconv);
+            repository.set_ext_diff(*ext_diff);
+            repository.set_color_moved(color_moved.unwrap_or_default());
+
+            repository.show(revision.as_deref())?;
+            repository.writer().flush()?;
+
+            if paging {
+                page_all(pager)?;
+            }
+        }
+        Commands::FormatPatch { revision } => {
+            let pwd = std::env::current_dir()?;
+            let repository = Repository::open(pwd, stdout_writer)?;
+
+            repository.format_patch(revision.as_deref())?
+        }
+        Commands::Am { patch } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            repository.am(patch).await?
+        }
+        Commands::Apply {
+            patch,
+            index,
+            three_way,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            repository
+                .apply(
+                    patch,
+                    ApplyOptions {
+                        index: *index,
+                        three_way: *three_way,
+                    },
+                )
+                .await?
+        }
+        Commands::Archive {
+            revision,
+            format,
+            prefix,
+            output,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let repository = Repository::open(pwd, stdout_writer)?;
+
+            repository.archive(
+                revision,
+                ArchiveOptions {
+                    format: *format,
+                    prefix: prefix.clone(),
+                    output: output.clone(),
+                },
+            )?
+        }
+        Commands::Tag {
+            name,
+            revision,
+            annotate,
+            message,
+            force,
+            delete,
+            list: _,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let repository = Repository::open(pwd, stdout_writer)?;
+
+            let action = match name.clone() {
+                Some(name) => TagAction::Create {
+                    name,
+                    revision: revision.clone(),
+                    annotate: *annotate,
+                    message: message.clone(),
+                    force: *force,
+                },
+                None if !delete.is_empty() => TagAction::Delete {
+                    names: delete.clone(),
+                },
+                None => TagAction::List,
+            };
+
+            repository.tag(&action)?
+        }
+        Commands::Reflog { action } => {
+            let pwd = std::env::current_dir()?;
+            let repository = Repository::open(
+                pwd,
+                if use_pager {
+                    pager_writer
+                } else {
+                    stdout_writer
+                },
+            )?;
+
+            let (reference, date) = match action {
+                Some(ReflogAction::Show { reference, date }) => (reference.as_deref(), *date),
+                None => (None, None),
+            };
+            repository.reflog_show(reference, date)?;
+
+            if use_pager {
+                page_all(pager)?;
+            }
+        }
+        Commands::Reset {
+            target_revision,
+            soft,
+            mixed: _,
+            hard,
+            keep,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            let mode = match (*soft, *hard, *keep) {
+                (true, _, _) => ResetMode::Soft,
+                (_, true, _) => ResetMode::Hard,
+                (_, _, true) => ResetMode::Keep,
+                _ => ResetMode::Mixed,
+            };
+
+            repository.reset(target_revision.as_deref(), mode).await?
+        }
+        Commands::Gc { auto } => {
+            let pwd = std::env::current_dir()?;
+            let repository = Repository::open(pwd, stdout_writer)?;
+
+            repository.gc(*auto)?
+        }
+        Commands::Clean {
+            force,
+            dry_run,
+            directories,
+            ignored,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
+
+            repository
+                .clean(CleanOptions {
+                    force: *force,
+                    dry_run: *dry_run,
+                    directories: *directories,
+                    ignored: *ignored,
+                })
+                .await?
+        }
+        Commands::Config {
+            key,
+            value,
+            add,
+            get_all,
+            unset,
+            unset_all,
+        } => {
+            let pwd = std::env::current_dir()?;
+            let repository = Repository::open(pwd, stdout_writer)?;
+
+            let key = key.clone();
+            let action = match value.clone() {
+                Some(value) if *add => ConfigAction::Add { key, value },
+                Some(value) => ConfigAction::Set { key, value },
+                None if *get_all => ConfigAction::GetAll { key },
+                None if *unset => ConfigAction::Unset { key },
+                None if *unset_all => ConfigAction::UnsetAll { key },
+                None => ConfigAction::Get { key },
+            };
+
+            repository.configure(&action)?
+        }
         Commands::Merge {
             target_revision,
             message,
             continue_merge,
+            no_ff,
         } => {
             let pwd = std::env::current_dir()?;
-            let mut repository = Repository::new(pwd, stdout_writer)?;
+            let mut repository = Repository::open(pwd, stdout_writer)?;
 
             if *continue_merge {
                 repository.merge_continue().await?
@@ -509,10 +1858,101 @@ async fn run() -> Result<()> {
                 let msg = message
                     .as_deref()
                     .ok_or_else(|| anyhow::anyhow!("--message required for merge"))?;
-                repository.merge(target, msg).await?
+                repository.merge(target, msg, *no_ff).await?
             }
         }
     }
 
     Ok(())
 }
+
+/// Output of `diff` and `show`
+///
+/// `--output` redirects the diff to a file, uncolored unless `--color=always`
+/// was given, and `--line-prefix` prepends a string to every line.
+fn diff_writer(
+    writer: Box<dyn Write>,
+    output: Option<&Path>,
+    line_prefix: Option<String>,
+    color: Option<ColorWhen>,
+) -> Result<Box<dyn Write>> {
+    let writer: Box<dyn Write> = match output {
+        Some(output) => {
+            if color != Some(ColorWhen::Always) {
+                control::set_override(false);
+            }
+            Box::new(BufWriter::new(std::fs::File::create(output)?))
+        }
+        None => writer,
+    };
+
+    Ok(match line_prefix {
+        Some(prefix) => Box::new(LinePrefixWriter::new(writer, prefix)),
+        None => writer,
+    })
+}
+
+/// Config key deciding the colors of a command, besides `color.ui`
+fn color_config_key(command: &Commands) -> Option<&'static str> {
+    match command {
+        Commands::Diff { .. }
+        | Commands::Show { .. }
+        | Commands::Log { .. }
+        | Commands::Reflog { .. } => Some("color.diff"),
+        Commands::Status { .. } => Some("color.status"),
+        Commands::Branch { .. } => Some("color.branch"),
+        _ => None,
+    }
+}
+
+/// The repository around the current directory, whose config applies before
+/// the global one
+///
+/// Outside a repository, or in a broken one, only the global config applies.
+fn config_repository() -> Result<Option<Repository>> {
+    let pwd = std::env::current_dir()?;
+
+    Ok(Repository::discover(pwd, Box::new(std::io::sink())).ok())
+}
+
+/// The repository config, if any, followed by the global one
+fn configs<'c>(repository: Option<&'c Repository>, global: Option<&'c Config>) -> Vec<&'c Config> {
+    repository
+        .map(Repository::config)
+        .into_iter()
+        .chain(global)
+        .collect()
+}
+
+/// Configured colors, from the repository config or else the global one
+fn color_config(repository: Option<&Repository>, key: Option<&str>) -> Result<Option<ColorWhen>> {
+    let global = Config::global();
+
+    Ok(configured_color(
+        &configs(repository, global.as_ref()),
+        key,
+    )?)
+}
+
+/// Whether `--line-numbers` was given to a command that takes it
+fn line_numbers(command: &Commands) -> bool {
+    match command {
+        Commands::Diff { line_numbers, .. } | Commands::Log { line_numbers, .. } => *line_numbers,
+        _ => false,
+    }
+}
+
+/// Pager settings for this invocation
+///
+/// Line numbers are shown with `--line-numbers`, or when `core.pagerLineNumbers`
+/// is set in the repository config. `core.pagerFollowOutput` keeps the view at
+/// the end of the output.
+fn pager_config(repository: Option<&Repository>, line_numbers: bool) -> Result<PagerConfig> {
+    let Some(config) = repository.map(Repository::config) else {
+        return Ok(PagerConfig::default().line_numbers(line_numbers));
+    };
+
+    Ok(PagerConfig::default()
+        .line_numbers(line_numbers || config.get_bool(PAGER_LINE_NUMBERS_KEY)?.unwrap_or(false))
+        .follow_output(config.get_bool(PAGER_FOLLOW_OUTPUT_KEY)?.unwrap_or(false)))
+}
@@ -0,0 +1,34 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn add_file_above_big_file_threshold(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    run_bit_command(dir, &["config", "core.bigFileThreshold", "1k"])
+        .assert()
+        .success();
+
+    // 64 KiB of every byte value, so the file is not valid UTF-8 either
+    let content = (0..=255u8).cycle().take(64 * 1024).collect::<Vec<_>>();
+    std::fs::write(dir.join("big.bin"), &content)?;
+
+    run_bit_command(dir, &["add", "big.bin"]).assert().success();
+
+    let expected = run_git_command(dir, &["hash-object", "big.bin"]).output()?;
+    let staged = run_git_command(dir, &["rev-parse", ":big.bin"]).output()?;
+    assert_eq!(
+        String::from_utf8(staged.stdout)?,
+        String::from_utf8(expected.stdout.clone())?
+    );
+
+    let oid = String::from_utf8(expected.stdout)?.trim_end().to_string();
+    let stored = run_git_command(dir, &["cat-file", "blob", &oid]).output()?;
+    assert!(stored.status.success());
+    assert_eq!(stored.stdout, content);
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::common;
+use crate::common::command::{run_bit_command, run_git_command};
+use assert_fs::fixture::{FileWriteStr, PathChild, PathCreateDir};
+use predicates::prelude::predicate;
+
+#[test]
+fn adding_a_path_outside_the_work_tree_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    common::redirect_temp_dir();
+    let dir = assert_fs::TempDir::new()?;
+    let repository = dir.child("repository");
+    repository.create_dir_all()?;
+    run_bit_command(repository.path(), &["init"])
+        .assert()
+        .success();
+
+    dir.child("outside.txt").write_str("outside")?;
+    repository.child("inside.txt").write_str("inside")?;
+
+    run_bit_command(repository.path(), &["add", "../outside.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("../outside.txt"));
+
+    // Paths that only pass through the parent directory are fine
+    run_bit_command(repository.path(), &["add", "../repository/inside.txt"])
+        .assert()
+        .success();
+
+    run_git_command(repository.path(), &["ls-files"])
+        .assert()
+        .success()
+        .stdout("inside.txt\n");
+
+    Ok(())
+}
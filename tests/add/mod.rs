@@ -1,12 +1,15 @@
+mod add_file_above_big_file_threshold;
 mod add_files_from_nested_directories_to_index_successfully;
 mod add_multiple_files_to_index_incrementally_successfully;
 mod add_multiple_files_to_index_successfully;
 mod add_single_file_to_index_successfully;
 mod adding_a_non_existent_file_is_ignored;
+mod adding_a_path_outside_the_work_tree_is_rejected;
 mod adding_an_unreadable_file_is_ignored;
 mod concurrent_add_operations_maintain_index_consistency;
 mod removing_deleted_files_from_index_successfully;
 mod replace_directory_having_nested_children_with_file_successfully;
 mod replace_directory_having_only_direct_children_with_file_successfully;
 mod replace_file_with_directory_successfully;
+mod skip_ignored_files_unless_forced;
 mod stress_test_concurrent_add_operations;
@@ -0,0 +1,57 @@
+use crate::common;
+use crate::common::command::{run_bit_command, run_git_command};
+use assert_fs::fixture::{FileWriteStr, PathChild};
+use predicates::prelude::predicate;
+
+#[test]
+fn skip_ignored_files_unless_forced() -> Result<(), Box<dyn std::error::Error>> {
+    common::redirect_temp_dir();
+    let dir = assert_fs::TempDir::new()?;
+    run_bit_command(dir.path(), &["init"]).assert().success();
+
+    dir.child(".gitignore").write_str("*.log\nbuild/\n")?;
+    dir.child("a/.gitignore").write_str("!keep.log\n")?;
+    dir.child("a/keep.log").write_str("keep")?;
+    dir.child("debug.log").write_str("debug")?;
+    dir.child("build/out.txt").write_str("out")?;
+    dir.child("notes.txt").write_str("notes")?;
+
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+
+    run_git_command(dir.path(), &["ls-files"])
+        .assert()
+        .success()
+        .stdout(".gitignore\na/.gitignore\na/keep.log\nnotes.txt\n");
+
+    // Naming an ignored path is refused with a hint
+    run_bit_command(dir.path(), &["add", "debug.log"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The following paths are ignored by one of your .gitignore files:\ndebug.log",
+        ))
+        .stderr(predicate::str::contains("hint: Use -f"));
+
+    run_bit_command(dir.path(), &["add", "-f", "debug.log"])
+        .assert()
+        .success();
+
+    // Once tracked, an ignored file is updated like any other
+    dir.child("debug.log").write_str("more debug")?;
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+
+    run_git_command(dir.path(), &["ls-files"])
+        .assert()
+        .success()
+        .stdout(".gitignore\na/.gitignore\na/keep.log\ndebug.log\nnotes.txt\n");
+    run_git_command(dir.path(), &["diff", "--name-only"])
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, delete_path, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn git_output(dir: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = run_git_command(dir, args).assert().success();
+    Ok(String::from_utf8(output.get_output().stdout.clone())?)
+}
+
+#[rstest]
+fn apply_formatted_patch_round_trip(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(
+        dir.join("notes.txt"),
+        "first\nthird\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add notes").assert().success();
+
+    // A commit adding, modifying and deleting files
+    write_file(FileSpec::new(
+        dir.join("notes.txt"),
+        "first\nsecond\nthird\n".to_string(),
+    ));
+    write_file(FileSpec::new(dir.join("a/new.txt"), "new\n".to_string()));
+    delete_path(&dir.join("a").join("2.txt"));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(
+        dir,
+        "Extend notes\n\nAlso adds a/new.txt and drops a/2.txt.",
+    )
+    .assert()
+    .success();
+    let original_tree = git_output(dir, &["rev-parse", "HEAD^{tree}"])?;
+    let original_author = git_output(dir, &["log", "-1", "--format=%an <%ae> %ad%n%B"])?;
+
+    let output = run_bit_command(dir, &["format-patch"]).assert().success();
+    let patch_path = dir.join(".git").join("extend-notes.patch");
+    std::fs::write(&patch_path, &output.get_output().stdout)?;
+
+    run_bit_command(dir, &["reset", "--hard", "HEAD^"])
+        .assert()
+        .success();
+
+    // Commit as someone else: the patch author must be kept
+    run_bit_command(dir, &["am", patch_path.to_str().unwrap()])
+        .envs(vec![
+            ("GIT_AUTHOR_NAME", "applier"),
+            ("GIT_AUTHOR_EMAIL", "applier@email.com"),
+        ])
+        .assert()
+        .success()
+        .stdout("Applying: Extend notes\n");
+
+    assert_eq!(
+        git_output(dir, &["rev-parse", "HEAD^{tree}"])?,
+        original_tree
+    );
+    assert_eq!(
+        git_output(dir, &["log", "-1", "--format=%an <%ae> %ad%n%B"])?,
+        original_author
+    );
+    assert_eq!(
+        git_output(dir, &["log", "-1", "--format=%cn"])?,
+        "applier\n"
+    );
+    assert_eq!(git_output(dir, &["status", "--porcelain"])?, "");
+
+    // Applying it again fails: a/new.txt already exists
+    run_bit_command(dir, &["am", patch_path.to_str().unwrap()])
+        .envs(vec![
+            ("GIT_AUTHOR_NAME", "applier"),
+            ("GIT_AUTHOR_EMAIL", "applier@email.com"),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "patch failed at 0001 Extend notes",
+        ));
+
+    Ok(())
+}
@@ -0,0 +1,2 @@
+mod apply_formatted_patch_round_trip;
+mod refuse_to_apply_over_a_dirty_index;
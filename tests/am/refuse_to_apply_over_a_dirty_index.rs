@@ -0,0 +1,55 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn refuse_to_apply_over_a_dirty_index(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(dir.join("notes.txt"), "first\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add notes").assert().success();
+
+    let output = run_bit_command(dir, &["format-patch"]).assert().success();
+    let patch_path = dir.join(".git").join("add-notes.patch");
+    std::fs::write(&patch_path, &output.get_output().stdout)?;
+    run_bit_command(dir, &["reset", "--hard", "HEAD^"])
+        .assert()
+        .success();
+    let head = run_git_command(dir, &["rev-parse", "HEAD"])
+        .assert()
+        .success();
+    let head = String::from_utf8(head.get_output().stdout.clone())?;
+
+    // An unrelated staged change must not end up in the patch's commit
+    write_file(FileSpec::new(
+        dir.join("staged.txt"),
+        "staged\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "staged.txt"])
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["am", patch_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Dirty index: cannot apply patches (dirty: staged.txt)",
+        ));
+
+    let unchanged_head = run_git_command(dir, &["rev-parse", "HEAD"])
+        .assert()
+        .success();
+    assert_eq!(
+        String::from_utf8(unchanged_head.get_output().stdout.clone())?,
+        head
+    );
+    assert!(!dir.join("notes.txt").exists());
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, delete_path, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Save the diff between two commits as a patch outside the working tree
+fn save_diff(dir: &Path, from: &str, to: &str, name: &str) -> PathBuf {
+    let output = run_bit_command(dir, &["diff", from, to]).assert().success();
+    let patch_path = dir.join(".git").join(name);
+    std::fs::write(&patch_path, &output.get_output().stdout).unwrap();
+
+    patch_path
+}
+
+#[rstest]
+fn apply_patches_creating_and_deleting_files(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let base = get_head_commit_sha(dir)?;
+
+    write_file(FileSpec::new(
+        dir.join("tools").join("run.sh"),
+        "#!/bin/sh\necho run\n".to_string(),
+    ));
+    std::fs::set_permissions(
+        dir.join("tools").join("run.sh"),
+        std::fs::Permissions::from_mode(0o755),
+    )?;
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add script").assert().success();
+    let added = get_head_commit_sha(dir)?;
+
+    delete_path(&dir.join("1.txt"));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Remove 1.txt").assert().success();
+    let removed = get_head_commit_sha(dir)?;
+
+    let creation = save_diff(dir, &base, &added, "creation.patch");
+    let deletion = save_diff(dir, &added, &removed, "deletion.patch");
+    assert!(std::fs::read_to_string(&creation)?.contains("--- /dev/null\n"));
+    assert!(std::fs::read_to_string(&deletion)?.contains("+++ /dev/null\n"));
+
+    // Working tree only: the file is created with its declared mode, then removed
+    run_bit_command(dir, &["reset", "--hard", &base])
+        .assert()
+        .success();
+    run_bit_command(dir, &["apply", creation.to_str().unwrap()])
+        .assert()
+        .success();
+    let script = dir.join("tools").join("run.sh");
+    assert_eq!(std::fs::read_to_string(&script)?, "#!/bin/sh\necho run\n");
+    assert_eq!(
+        std::fs::metadata(&script)?.permissions().mode() & 0o777,
+        0o755
+    );
+    run_bit_command(dir, &["apply", deletion.to_str().unwrap()])
+        .assert()
+        .success();
+    assert!(!dir.join("1.txt").exists());
+
+    // Deleting a file that no longer exists fails
+    run_bit_command(dir, &["apply", deletion.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not exist"));
+
+    // With --index, both changes are staged. The script created above is
+    // untracked, so the reset keeps it and it must be removed by hand.
+    run_bit_command(dir, &["reset", "--hard", &base])
+        .assert()
+        .success();
+    delete_path(&script);
+    run_bit_command(dir, &["apply", "--index", creation.to_str().unwrap()])
+        .assert()
+        .success();
+    run_bit_command(dir, &["apply", "--index", deletion.to_str().unwrap()])
+        .assert()
+        .success();
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("D  1.txt\nA  tools/run.sh\n");
+
+    // A deletion is refused when the file's content differs from the patch
+    run_bit_command(dir, &["reset", "--hard", &base])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("1.txt"), "changed\n".to_string()));
+    run_bit_command(dir, &["apply", deletion.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("patch does not apply"));
+    assert_eq!(std::fs::read_to_string(dir.join("1.txt"))?, "changed\n");
+
+    Ok(())
+}
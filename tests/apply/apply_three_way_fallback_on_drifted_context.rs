@@ -0,0 +1,85 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn apply_three_way_fallback_on_drifted_context(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let notes = dir.join("notes.txt");
+
+    write_file(FileSpec::new(
+        notes.clone(),
+        "a\nb\nc\nd\ne\nf\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add notes").assert().success();
+    let base = get_head_commit_sha(dir)?;
+
+    write_file(FileSpec::new(
+        notes.clone(),
+        "a\nB\nc\nd\ne\nf\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Capitalize b").assert().success();
+    let patched = get_head_commit_sha(dir)?;
+
+    let output = run_bit_command(dir, &["diff", &base, &patched])
+        .assert()
+        .success();
+    let patch = dir.join(".git").join("capitalize.patch");
+    std::fs::write(&patch, &output.get_output().stdout)?;
+    let patch = patch.to_str().unwrap();
+
+    // The context of the hunk has drifted: only a three-way merge applies it
+    run_bit_command(dir, &["reset", "--hard", &base])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        notes.clone(),
+        "a\nb\nc\nD\ne\nf\n".to_string(),
+    ));
+    run_bit_command(dir, &["apply", patch])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("patch does not apply"));
+    run_bit_command(dir, &["apply", "--3way", patch])
+        .assert()
+        .success()
+        .stdout("Applied patch to 'notes.txt' cleanly.\n");
+    assert_eq!(std::fs::read_to_string(&notes)?, "a\nB\nc\nD\ne\nf\n");
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("M  notes.txt\n");
+
+    // The same line changed locally: the merge leaves conflict markers
+    run_bit_command(dir, &["reset", "--hard", &base])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        notes.clone(),
+        "a\nbeta\nc\nd\ne\nf\n".to_string(),
+    ));
+    run_bit_command(dir, &["apply", "-3", patch])
+        .assert()
+        .failure()
+        .stdout("Applied patch to 'notes.txt' with conflicts.\nU notes.txt\n")
+        .stderr(predicate::str::contains("patch applied with conflicts"));
+    assert_eq!(
+        std::fs::read_to_string(&notes)?,
+        "a\n<<<<<<< ours\nbeta\n=======\nB\n>>>>>>> theirs\nc\nd\ne\nf\n"
+    );
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("UU notes.txt\n");
+
+    Ok(())
+}
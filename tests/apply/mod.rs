@@ -0,0 +1,2 @@
+mod apply_patches_creating_and_deleting_files;
+mod apply_three_way_fallback_on_drifted_context;
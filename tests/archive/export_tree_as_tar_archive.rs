@@ -0,0 +1,107 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, make_file_executable, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::os::unix::fs::PermissionsExt;
+use std::process::Command;
+
+#[rstest]
+fn archive_exports_head_as_tar_with_prefix_and_exec_bit(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let script = dir.join("run.sh");
+    write_file(FileSpec::new(script.clone(), "echo run\n".to_string()));
+    make_file_executable(&script);
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add script").assert().success();
+
+    let archive = dir.join("out.tar");
+    run_bit_command(
+        dir,
+        &[
+            "archive",
+            "--format=tar",
+            "--prefix=project/",
+            "-o",
+            archive.to_str().unwrap(),
+            "HEAD",
+        ],
+    )
+    .assert()
+    .success()
+    .stdout("");
+
+    let extracted = TempDir::new()?;
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive)
+        .current_dir(extracted.path())
+        .status()?;
+    assert!(status.success());
+
+    let root = extracted.path().join("project");
+    assert_eq!(std::fs::read_to_string(root.join("1.txt"))?, "one");
+    assert_eq!(std::fs::read_to_string(root.join("a/b/3.txt"))?, "three");
+    assert_eq!(std::fs::read_to_string(root.join("run.sh"))?, "echo run\n");
+
+    let script_mode = std::fs::metadata(root.join("run.sh"))?.permissions().mode();
+    let file_mode = std::fs::metadata(root.join("1.txt"))?.permissions().mode();
+    assert_eq!(script_mode & 0o111, 0o111);
+    assert_eq!(file_mode & 0o111, 0);
+
+    Ok(())
+}
+
+#[rstest]
+fn archive_writes_zip_to_stdout(init_repository_dir: TempDir) {
+    let output = run_bit_command(
+        init_repository_dir.path(),
+        &["archive", "--format=zip", "HEAD"],
+    )
+    .assert()
+    .success();
+
+    let stdout = &output.get_output().stdout;
+    assert_eq!(&stdout[..4], b"PK\x03\x04");
+    // The end-of-central-directory record closes the archive
+    assert_eq!(&stdout[stdout.len() - 22..][..4], b"PK\x05\x06");
+}
+
+#[rstest]
+fn archive_fails_on_unknown_revision(init_repository_dir: TempDir) {
+    run_bit_command(init_repository_dir.path(), &["archive", "missing"])
+        .assert()
+        .failure();
+}
+
+#[rstest]
+fn archive_keeps_symlinks_as_links(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    std::os::unix::fs::symlink("1.txt", dir.join("link"))?;
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add link").assert().success();
+
+    let archive = dir.join("out.tar");
+    run_bit_command(dir, &["archive", "-o", archive.to_str().unwrap(), "HEAD"])
+        .assert()
+        .success();
+
+    let extracted = TempDir::new()?;
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive)
+        .current_dir(extracted.path())
+        .status()?;
+    assert!(status.success());
+
+    let link = extracted.path().join("link");
+    assert!(std::fs::symlink_metadata(&link)?.file_type().is_symlink());
+    assert_eq!(std::fs::read_link(&link)?, std::path::Path::new("1.txt"));
+    assert_eq!(std::fs::read_to_string(&link)?, "one");
+
+    Ok(())
+}
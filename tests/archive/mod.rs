@@ -0,0 +1 @@
+mod export_tree_as_tar_archive;
@@ -0,0 +1,91 @@
+use crate::common::command::{bit_commit, get_head_commit_sha, repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+use std::path::Path;
+
+fn commit_linear_history(
+    dir: &Path,
+    count: usize,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    run_bit_command(dir, &["init"]).assert().success();
+
+    let mut shas = Vec::new();
+    for i in 1..=count {
+        write_file(FileSpec::new(dir.join("file.txt"), format!("{}\n", i)));
+        run_bit_command(dir, &["add", "."]).assert().success();
+        bit_commit(dir, &format!("c{}", i)).assert().success();
+        shas.push(get_head_commit_sha(dir)?);
+    }
+
+    Ok(shas)
+}
+
+#[rstest]
+fn bisect_checks_out_midpoint_of_linear_history(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    let shas = commit_linear_history(dir, 10)?;
+
+    run_bit_command(dir, &["bisect", "start"])
+        .assert()
+        .success()
+        .stdout("status: waiting for both good and bad commits\n");
+    run_bit_command(dir, &["bisect", "bad"])
+        .assert()
+        .success()
+        .stdout("status: waiting for good commit(s), bad commit known\n");
+
+    // c2..c10 are suspects: c5 splits them into 4 and 5 commits
+    run_bit_command(dir, &["bisect", "good", &shas[0]])
+        .assert()
+        .success()
+        .stdout(format!(
+            "Bisecting: 4 revisions left to test after this (roughly 2 steps)\n[{}] c5\n",
+            shas[4]
+        ));
+    assert_eq!(
+        std::fs::read_to_string(dir.join(".git").join("HEAD"))?.trim(),
+        shas[4]
+    );
+    assert_eq!(std::fs::read_to_string(dir.join("file.txt"))?, "5\n");
+
+    // c6..c10 are left: c7 splits them into 2 and 3 commits
+    run_bit_command(dir, &["bisect", "good"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "Bisecting: 2 revisions left to test after this (roughly 1 step)\n[{}] c7\n",
+            shas[6]
+        ));
+    assert_eq!(get_head_commit_sha(dir)?, shas[6]);
+
+    assert_eq!(
+        std::fs::read_to_string(dir.join(".git").join("BISECT_START"))?,
+        "master"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join(".git").join("BISECT_BAD"))?,
+        shas[9]
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join(".git").join("BISECT_GOOD"))?,
+        format!("{}\n{}\n", shas[0], shas[4])
+    );
+
+    // Reset restores the original branch and forgets the session
+    run_bit_command(dir, &["bisect", "reset"])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(dir.join(".git").join("HEAD"))?.trim(),
+        "ref: refs/heads/master"
+    );
+    assert_eq!(std::fs::read_to_string(dir.join("file.txt"))?, "10\n");
+    assert!(!dir.join(".git").join("BISECT_START").exists());
+    assert!(!dir.join(".git").join("BISECT_BAD").exists());
+    assert!(!dir.join(".git").join("BISECT_GOOD").exists());
+
+    Ok(())
+}
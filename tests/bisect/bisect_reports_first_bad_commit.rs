@@ -0,0 +1,46 @@
+use crate::common::command::{bit_commit, get_head_commit_sha, repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+#[rstest]
+fn bisect_reports_first_bad_commit(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    let mut shas = Vec::new();
+    for i in 1..=4 {
+        write_file(FileSpec::new(dir.join("file.txt"), format!("{}\n", i)));
+        run_bit_command(dir, &["add", "."]).assert().success();
+        bit_commit(dir, &format!("c{}", i)).assert().success();
+        shas.push(get_head_commit_sha(dir)?);
+    }
+
+    run_bit_command(dir, &["bisect", "start"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["bisect", "bad", &shas[3]])
+        .assert()
+        .success();
+    run_bit_command(dir, &["bisect", "good", &shas[1]])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "Bisecting: 0 revisions left to test after this (roughly 0 steps)\n",
+        ));
+    assert_eq!(get_head_commit_sha(dir)?, shas[2]);
+
+    run_bit_command(dir, &["bisect", "bad"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!(
+            "{} is the first bad commit\ncommit {}\n",
+            shas[2], shas[2]
+        )))
+        .stdout(predicate::str::contains("    c3\n"));
+
+    Ok(())
+}
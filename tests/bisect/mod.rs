@@ -0,0 +1,2 @@
+mod bisect_checks_out_midpoint_of_linear_history;
+mod bisect_reports_first_bad_commit;
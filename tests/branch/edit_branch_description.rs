@@ -0,0 +1,142 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn set_and_read_back_branch_description(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    run_bit_command(repository_dir.path(), &["branch", "create", "feature"])
+        .assert()
+        .success();
+    run_bit_command(
+        repository_dir.path(),
+        &[
+            "branch",
+            "edit-description",
+            "feature",
+            "-m",
+            "Work on the new feature",
+        ],
+    )
+    .assert()
+    .success();
+
+    let output = run_bit_command(
+        repository_dir.path(),
+        &["config", "branch.feature.description"],
+    )
+    .assert()
+    .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    assert_eq!(stdout, "Work on the new feature\n");
+
+    // An empty description removes the key
+    run_bit_command(
+        repository_dir.path(),
+        &["branch", "edit-description", "feature", "-m", ""],
+    )
+    .assert()
+    .success();
+    run_bit_command(
+        repository_dir.path(),
+        &["config", "branch.feature.description"],
+    )
+    .assert()
+    .failure();
+
+    Ok(())
+}
+
+#[rstest]
+fn describe_current_branch_by_default(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    run_bit_command(
+        repository_dir.path(),
+        &["branch", "edit-description", "-m", "The main line"],
+    )
+    .assert()
+    .success();
+
+    let output = run_bit_command(
+        repository_dir.path(),
+        &["config", "branch.master.description"],
+    )
+    .assert()
+    .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    assert_eq!(stdout, "The main line\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn describe_missing_branch_fails(init_repository_dir: TempDir) {
+    let repository_dir = init_repository_dir;
+
+    run_bit_command(
+        repository_dir.path(),
+        &["branch", "edit-description", "nope", "-m", "Nothing"],
+    )
+    .assert()
+    .failure()
+    .stderr(predicates::str::contains("no branch named 'nope'"));
+}
+
+#[rstest]
+fn list_branches_with_description_format(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    run_bit_command(repository_dir.path(), &["branch", "create", "feature"])
+        .assert()
+        .success();
+    run_bit_command(
+        repository_dir.path(),
+        &[
+            "branch",
+            "edit-description",
+            "feature",
+            "-m",
+            "Feature work",
+        ],
+    )
+    .assert()
+    .success();
+
+    let output = run_bit_command(
+        repository_dir.path(),
+        &[
+            "branch",
+            "list",
+            "--format",
+            "%(HEAD) %(refname:short): %(description)",
+        ],
+    )
+    .assert()
+    .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    assert_eq!(stdout, "  feature: Feature work\n* master: \n");
+
+    Ok(())
+}
+
+#[rstest]
+fn list_branches_with_unknown_format_atom_fails(init_repository_dir: TempDir) {
+    let repository_dir = init_repository_dir;
+
+    run_bit_command(
+        repository_dir.path(),
+        &["branch", "list", "--format", "%(bogus)"],
+    )
+    .assert()
+    .failure()
+    .stderr(predicates::str::contains("unknown field name: bogus"));
+}
@@ -11,4 +11,5 @@ mod create_branch_with_various_valid_names;
 mod create_branch_without_commits;
 mod create_duplicate_branch;
 mod delete_branch;
+mod edit_branch_description;
 mod list_branches;
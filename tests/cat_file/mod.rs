@@ -0,0 +1,3 @@
+mod pretty_print_objects;
+mod report_corrupt_objects;
+mod show_object_type_and_size;
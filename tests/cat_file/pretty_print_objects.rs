@@ -0,0 +1,56 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+fn git_stdout(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = run_git_command(dir, args).assert().success();
+    String::from_utf8(output.get_output().stdout.clone()).unwrap()
+}
+
+fn bit_stdout(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = run_bit_command(dir, args).assert().success();
+    String::from_utf8(output.get_output().stdout.clone()).unwrap()
+}
+
+#[rstest]
+fn pretty_print_objects_like_git(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    write_file(FileSpec::new(
+        dir.join("nested/inner.txt"),
+        "inner\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add nested file").assert().success();
+
+    let head = get_head_commit_sha(dir)?;
+    let tree = git_stdout(dir, &["rev-parse", "HEAD^{tree}"]);
+    let blob = git_stdout(dir, &["rev-parse", "HEAD:nested/inner.txt"]);
+
+    for object in [head.as_str(), &head[..7], tree.trim(), blob.trim()] {
+        assert_eq!(
+            bit_stdout(dir, &["cat-file", "-p", object]),
+            git_stdout(dir, &["cat-file", "-p", object])
+        );
+    }
+
+    let tree_listing = bit_stdout(dir, &["cat-file", "-p", tree.trim()]);
+    assert!(tree_listing.contains(" tree "));
+    assert!(tree_listing.contains(" blob "));
+
+    Ok(())
+}
+
+#[rstest]
+fn pretty_print_conflicts_with_type(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    run_bit_command(dir, &["cat-file", "-p", "-t", "HEAD"])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,52 @@
+use crate::common::command::{get_head_commit_sha, init_repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+use std::path::{Path, PathBuf};
+
+fn object_path(dir: &Path, oid: &str) -> PathBuf {
+    dir.join(".git")
+        .join("objects")
+        .join(&oid[..2])
+        .join(&oid[2..])
+}
+
+/// Keep only the first `len` bytes of a loose object file
+fn truncate_object(dir: &Path, oid: &str, len: impl Fn(usize) -> usize) {
+    let path = object_path(dir, oid);
+    let content = std::fs::read(&path).expect("object file should exist");
+    std::fs::write(&path, &content[..len(content.len())]).expect("object file should be writable");
+}
+
+#[rstest]
+fn report_object_with_truncated_header(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    let oid = "43dd47ea691c90a5fa7827892c70241913351963";
+    truncate_object(dir, oid, |_| 4);
+
+    run_bit_command(dir, &["cat-file", "-t", oid])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "object file {} is corrupt (object {})",
+            object_path(&dir.canonicalize().unwrap(), oid).display(),
+            oid
+        )));
+}
+
+#[rstest]
+fn report_truncated_object_when_reading_its_content(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let head = get_head_commit_sha(dir)?;
+    truncate_object(dir, &head, |len| len / 2);
+
+    run_bit_command(dir, &["log"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is corrupt"))
+        .stderr(predicate::str::contains(head));
+
+    Ok(())
+}
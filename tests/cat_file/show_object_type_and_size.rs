@@ -0,0 +1,74 @@
+use crate::common::command::{
+    get_head_commit_sha, init_repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+const BLOB_OID: &str = "43dd47ea691c90a5fa7827892c70241913351963";
+
+#[rstest]
+fn show_type_and_size_of_known_objects(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let head = get_head_commit_sha(dir)?;
+
+    run_bit_command(dir, &["cat-file", "-t", BLOB_OID])
+        .assert()
+        .success()
+        .stdout("blob\n");
+    run_bit_command(dir, &["cat-file", "-s", &BLOB_OID[..7]])
+        .assert()
+        .success()
+        .stdout("3\n");
+    run_bit_command(dir, &["cat-file", "-t", "HEAD"])
+        .assert()
+        .success()
+        .stdout("commit\n");
+
+    let expected_size = run_git_command(dir, &["cat-file", "-s", &head])
+        .assert()
+        .success();
+    let expected_size = String::from_utf8(expected_size.get_output().stdout.clone())?;
+    run_bit_command(dir, &["cat-file", "-s", &head])
+        .assert()
+        .success()
+        .stdout(expected_size);
+
+    Ok(())
+}
+
+#[rstest]
+fn unknown_object_type_requires_allow_unknown_type(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    write_file(FileSpec::new(dir.join("payload"), "hello".to_string()));
+
+    let output = run_git_command(
+        dir,
+        &["hash-object", "--literally", "-t", "foo", "-w", "payload"],
+    )
+    .assert()
+    .success();
+    let oid = String::from_utf8(output.get_output().stdout.clone())?;
+    let oid = oid.trim();
+
+    run_bit_command(dir, &["cat-file", "-t", oid])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid object type: foo"));
+
+    run_bit_command(dir, &["cat-file", "-t", "--allow-unknown-type", oid])
+        .assert()
+        .success()
+        .stdout("foo\n");
+    run_bit_command(dir, &["cat-file", "-s", "--allow-unknown-type", oid])
+        .assert()
+        .success()
+        .stdout("5\n");
+
+    Ok(())
+}
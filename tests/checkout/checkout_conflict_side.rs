@@ -0,0 +1,110 @@
+use crate::common::command::{
+    bit_commit, bit_merge, repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+use std::fs;
+
+/// `file.txt` conflicts after merging `feature` into `master`
+///
+/// History:
+///   A: file.txt = "shared\n"
+///   B (master):  "ours change\n"
+///   C (feature): "theirs change\n"
+#[fixture]
+fn conflicted_repository(repository_dir: TempDir) -> TempDir {
+    let dir = repository_dir.path();
+
+    run_bit_command(dir, &["init"]).assert().success();
+    write_file(FileSpec::new(dir.join("file.txt"), "shared\n".into()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Commit A").assert().success();
+
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(dir.join("file.txt"), "ours change\n".into()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Commit B - ours").assert().success();
+
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        dir.join("file.txt"),
+        "theirs change\n".into(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Commit C - theirs").assert().success();
+
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge(dir, "feature", "conflict merge")
+        .assert()
+        .failure();
+
+    repository_dir
+}
+
+fn staged_entries(dir: &std::path::Path) -> Result<String, Box<dyn std::error::Error>> {
+    // bit writes a git-compatible index, so real git can list its stages
+    let output = run_git_command(dir, &["ls-files", "--stage"])
+        .assert()
+        .success();
+    Ok(String::from_utf8(output.get_output().stdout.clone())?)
+}
+
+#[rstest]
+fn checkout_theirs_resolves_conflicted_path(
+    conflicted_repository: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = conflicted_repository.path();
+
+    run_bit_command(dir, &["checkout", "--theirs", "--", "file.txt"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(dir.join("file.txt"))?, "theirs change\n");
+
+    let staged = staged_entries(dir)?;
+    let entries = staged.lines().collect::<Vec<_>>();
+    assert_eq!(
+        entries.len(),
+        1,
+        "Expected a single entry, got:\n{}",
+        staged
+    );
+    assert!(
+        entries[0].ends_with(" 0\tfile.txt"),
+        "Expected stage 0, got {}",
+        entries[0]
+    );
+
+    let theirs_blob = run_git_command(dir, &["rev-parse", "feature:file.txt"])
+        .assert()
+        .success();
+    let theirs_blob = String::from_utf8(theirs_blob.get_output().stdout.clone())?;
+    assert!(entries[0].contains(theirs_blob.trim()));
+
+    Ok(())
+}
+
+#[rstest]
+fn checkout_ours_restores_our_version(
+    conflicted_repository: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = conflicted_repository.path();
+
+    run_bit_command(dir, &["checkout", "--ours", "--", "file.txt"])
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(dir.join("file.txt"))?, "ours change\n");
+    assert_eq!(staged_entries(dir)?.lines().count(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, delete_path, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn checkout_restores_deleted_path_from_index(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    // Stage a modification, then delete the whole parent directory
+    let file = FileSpec::new(
+        repository_dir.path().join("a").join("b").join("3.txt"),
+        "staged three".to_string(),
+    );
+    write_file(file);
+    run_bit_command(repository_dir.path(), &["add", "a/b/3.txt"])
+        .assert()
+        .success();
+    delete_path(&repository_dir.path().join("a").join("b"));
+
+    run_bit_command(repository_dir.path(), &["checkout", "--", "a/b/3.txt"])
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(repository_dir.path().join("a/b/3.txt"))?;
+    assert_eq!(content, "staged three");
+
+    run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("M  a/b/3.txt"));
+
+    Ok(())
+}
+
+#[rstest]
+fn checkout_restores_deleted_path_from_head(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    // Stage a modification, then delete the file; HEAD still has the committed version
+    let file = FileSpec::new(
+        repository_dir.path().join("a").join("2.txt"),
+        "staged two".to_string(),
+    );
+    write_file(file);
+    run_bit_command(repository_dir.path(), &["add", "a/2.txt"])
+        .assert()
+        .success();
+    delete_path(&repository_dir.path().join("a"));
+
+    run_bit_command(repository_dir.path(), &["checkout", "HEAD", "--", "a"])
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(repository_dir.path().join("a/2.txt"))?;
+    assert_eq!(content, "two");
+    let content = std::fs::read_to_string(repository_dir.path().join("a/b/3.txt"))?;
+    assert_eq!(content, "three");
+
+    // Restoring from a revision also stages its content
+    run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    Ok(())
+}
+
+#[rstest]
+fn checkout_unknown_path_fails(init_repository_dir: TempDir) {
+    run_bit_command(
+        init_repository_dir.path(),
+        &["checkout", "--", "missing.txt"],
+    )
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains(
+        "pathspec 'missing.txt' did not match any file(s) known to bit",
+    ));
+}
@@ -0,0 +1,107 @@
+use crate::checkout::checkout_with_conflicts::repository_for_conflicts;
+use crate::common::command::run_bit_command;
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn checkout_force_overwrites_locally_modified_files(
+    repository_for_conflicts: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = repository_for_conflicts;
+
+    // file1.txt differs between the commits, file2.txt does not
+    write_file(FileSpec::new(
+        repository_dir.path().join("file1.txt"),
+        "uncommitted workspace changes".to_string(),
+    ));
+    write_file(FileSpec::new(
+        repository_dir.path().join("file2.txt"),
+        "unrelated local edit".to_string(),
+    ));
+
+    run_bit_command(repository_dir.path(), &["checkout", "first-commit"])
+        .assert()
+        .failure();
+
+    run_bit_command(repository_dir.path(), &["checkout", "-f", "first-commit"])
+        .assert()
+        .success();
+
+    let file1_content = std::fs::read_to_string(repository_dir.path().join("file1.txt"))?;
+    assert_eq!(file1_content, "initial content");
+    let file2_content = std::fs::read_to_string(repository_dir.path().join("file2.txt"))?;
+    assert_eq!(file2_content, "another initial content");
+    assert!(!repository_dir.path().join("file3.txt").exists());
+
+    let head_content = std::fs::read_to_string(repository_dir.path().join(".git").join("HEAD"))?;
+    assert_eq!(head_content.trim(), "ref: refs/heads/first-commit");
+
+    run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
+
+#[rstest]
+fn checkout_force_still_protects_untracked_files(
+    repository_for_conflicts: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = repository_for_conflicts;
+
+    run_bit_command(repository_dir.path(), &["checkout", "first-commit"])
+        .assert()
+        .success();
+
+    // file3.txt only exists in the second commit
+    write_file(FileSpec::new(
+        repository_dir.path().join("file3.txt"),
+        "untracked content that would be lost".to_string(),
+    ));
+
+    run_bit_command(
+        repository_dir.path(),
+        &["checkout", "--force", "second-commit"],
+    )
+    .assert()
+    .failure();
+
+    let file3_content = std::fs::read_to_string(repository_dir.path().join("file3.txt"))?;
+    assert_eq!(file3_content, "untracked content that would be lost");
+
+    Ok(())
+}
+
+#[rstest]
+fn checkout_force_leaves_unchanged_files_alone(
+    repository_for_conflicts: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = repository_for_conflicts;
+
+    // file2.txt is the same in both commits: only its timestamp changes
+    let old_mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    std::fs::File::options()
+        .write(true)
+        .open(repository_dir.path().join("file2.txt"))?
+        .set_modified(old_mtime)?;
+
+    run_bit_command(repository_dir.path(), &["checkout", "-f", "first-commit"])
+        .assert()
+        .success();
+
+    let file1_content = std::fs::read_to_string(repository_dir.path().join("file1.txt"))?;
+    assert_eq!(file1_content, "initial content");
+    assert_eq!(
+        std::fs::metadata(repository_dir.path().join("file2.txt"))?.modified()?,
+        old_mtime
+    );
+    run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+
+/// `notes.txt` with five lines on master, and a `feature` branch changing
+/// its last line; master is checked out
+#[fixture]
+fn repository_with_feature_branch(init_repository_dir: TempDir) -> TempDir {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(
+        dir.join("notes.txt"),
+        "one\ntwo\nthree\nfour\nfive\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "notes.txt"])
+        .assert()
+        .success();
+    bit_commit(dir, "Add notes").assert().success();
+
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        dir.join("notes.txt"),
+        "one\ntwo\nthree\nfour\nFIVE\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "notes.txt"])
+        .assert()
+        .success();
+    bit_commit(dir, "Shout the last line").assert().success();
+
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+
+    init_repository_dir
+}
+
+#[rstest]
+fn checkout_merge_carries_local_changes_over(
+    repository_with_feature_branch: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_feature_branch.path();
+    write_file(FileSpec::new(
+        dir.join("notes.txt"),
+        "ONE\ntwo\nthree\nfour\nfive\n".to_string(),
+    ));
+
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .failure();
+
+    run_bit_command(dir, &["checkout", "-m", "feature"])
+        .assert()
+        .success();
+
+    let content = std::fs::read_to_string(dir.join("notes.txt"))?;
+    assert_eq!(content, "ONE\ntwo\nthree\nfour\nFIVE\n");
+    let head_content = std::fs::read_to_string(dir.join(".git").join("HEAD"))?;
+    assert_eq!(head_content.trim(), "ref: refs/heads/feature");
+
+    // The local change stays unstaged on top of the target version
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(" M notes.txt\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn checkout_merge_writes_conflict_markers(
+    repository_with_feature_branch: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_feature_branch.path();
+    write_file(FileSpec::new(
+        dir.join("notes.txt"),
+        "one\ntwo\nthree\nfour\n5\n".to_string(),
+    ));
+
+    run_bit_command(dir, &["checkout", "--merge", "feature"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "CONFLICT (content): Merge conflict in notes.txt",
+        ));
+
+    let content = std::fs::read_to_string(dir.join("notes.txt"))?;
+    assert_eq!(
+        content,
+        "one\ntwo\nthree\nfour\n<<<<<<< feature\nFIVE\n=======\n5\n>>>>>>> local\n"
+    );
+
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("UU notes.txt\n");
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::common::command::{
+    bit_commit, get_branch_commit_sha, init_repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn checkout_orphan_then_commit_creates_root_commit_on_new_branch(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+    let master_sha = get_branch_commit_sha(repository_dir.path(), "master")?;
+
+    run_bit_command(repository_dir.path(), &["checkout", "--orphan", "docs"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Switched to a new branch 'docs'"));
+
+    // HEAD points at the unborn branch, whose ref file does not exist yet
+    let head = std::fs::read_to_string(repository_dir.path().join(".git").join("HEAD"))?;
+    assert_eq!(head.trim(), "ref: refs/heads/docs");
+    assert!(!repository_dir.path().join(".git/refs/heads/docs").exists());
+
+    let readme = FileSpec::new(repository_dir.path().join("README.md"), "docs".to_string());
+    write_file(readme);
+    run_bit_command(repository_dir.path(), &["add", "README.md"])
+        .assert()
+        .success();
+
+    bit_commit(repository_dir.path(), "Start docs")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[(root-commit) "));
+
+    // The commit created the branch and has no parents
+    let docs_sha = get_branch_commit_sha(repository_dir.path(), "docs")?;
+    let output = run_git_command(repository_dir.path(), &["cat-file", "-p", &docs_sha]).output()?;
+    let commit = String::from_utf8(output.stdout)?;
+    assert!(
+        !commit.lines().any(|line| line.starts_with("parent ")),
+        "Expected a root commit, got:\n{}",
+        commit
+    );
+
+    // The original branch is untouched
+    assert_eq!(
+        get_branch_commit_sha(repository_dir.path(), "master")?,
+        master_sha
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn checkout_orphan_with_existing_branch_name_fails(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    run_bit_command(repository_dir.path(), &["checkout", "--orphan", "master"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "a branch named 'master' already exists",
+        ));
+
+    let head = std::fs::read_to_string(repository_dir.path().join(".git").join("HEAD"))?;
+    assert_eq!(head.trim(), "ref: refs/heads/master");
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+use std::path::Path;
+
+/// `master` has the committed `1.txt`, `a/2.txt` and `a/b/3.txt`, while
+/// `other` changes all three and adds `notes.md`
+#[fixture]
+fn repository_with_other_branch(init_repository_dir: TempDir) -> TempDir {
+    let dir = init_repository_dir.path();
+
+    run_bit_command(dir, &["branch", "create", "other"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["checkout", "other"])
+        .assert()
+        .success();
+    for (path, content) in [
+        ("1.txt", "other one"),
+        ("a/2.txt", "other two"),
+        ("a/b/3.txt", "other three"),
+        ("notes.md", "notes"),
+    ] {
+        write_file(FileSpec::new(dir.join(path), content.to_string()));
+    }
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "other changes").assert().success();
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+
+    init_repository_dir
+}
+
+fn read(dir: &Path, path: &str) -> String {
+    std::fs::read_to_string(dir.join(path)).expect("file should exist")
+}
+
+#[rstest]
+fn checkout_glob_restores_matching_files_from_revision(
+    repository_with_other_branch: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_other_branch.path();
+
+    run_bit_command(dir, &["checkout", "other", "--", "*.txt"])
+        .assert()
+        .success();
+
+    assert_eq!(read(dir, "1.txt"), "other one");
+    assert_eq!(read(dir, "a/2.txt"), "other two");
+    assert_eq!(read(dir, "a/b/3.txt"), "other three");
+    assert!(!dir.join("notes.md").exists());
+
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("M  1.txt\nM  a/2.txt\nM  a/b/3.txt\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn checkout_directory_restores_only_its_subtree(
+    repository_with_other_branch: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_other_branch.path();
+
+    run_bit_command(dir, &["checkout", "other", "--", "a/b", "notes.md"])
+        .assert()
+        .success();
+
+    assert_eq!(read(dir, "a/b/3.txt"), "other three");
+    assert_eq!(read(dir, "notes.md"), "notes");
+    assert_eq!(read(dir, "1.txt"), "one");
+    assert_eq!(read(dir, "a/2.txt"), "two");
+
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("M  a/b/3.txt\nA  notes.md\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn checkout_with_unmatched_pathspec_changes_nothing(repository_with_other_branch: TempDir) {
+    let dir = repository_with_other_branch.path();
+
+    run_bit_command(dir, &["checkout", "other", "--", "a/b", "*.rs"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "pathspec '*.rs' did not match any file(s) known to bit",
+        ));
+
+    assert_eq!(read(dir, "a/b/3.txt"), "three");
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+}
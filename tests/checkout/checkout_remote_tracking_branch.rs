@@ -0,0 +1,73 @@
+use crate::common::command::{
+    get_ancestor_commit_id, get_branch_commit_sha, get_head_commit_sha,
+    repository_with_multiple_commits, run_bit_command,
+};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn write_remote_ref(dir: &Path, remote: &str, name: &str, oid: &str) {
+    let remote_dir = dir.join(".git").join("refs").join("remotes").join(remote);
+    std::fs::create_dir_all(&remote_dir).unwrap();
+    std::fs::write(remote_dir.join(name), format!("{}\n", oid)).unwrap();
+}
+
+fn read_config(dir: &Path, key: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let output = run_bit_command(dir, &["config", key]).assert().success();
+    Ok(String::from_utf8(output.get_output().stdout.clone())?
+        .trim()
+        .to_string())
+}
+
+#[rstest]
+fn checkout_creates_branch_tracking_single_remote_match(
+    repository_with_multiple_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_multiple_commits.path();
+    let head = get_head_commit_sha(dir)?;
+    let remote_tip = get_ancestor_commit_id(dir, &head, 1)?;
+    write_remote_ref(dir, "origin", "feature", &remote_tip);
+
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains(
+            "Branch 'feature' set up to track remote branch 'feature' from 'origin'.",
+        ));
+
+    assert_eq!(get_branch_commit_sha(dir, "feature")?, remote_tip);
+    assert_eq!(
+        std::fs::read_to_string(dir.join(".git").join("HEAD"))?.trim(),
+        "ref: refs/heads/feature"
+    );
+    assert_eq!(read_config(dir, "branch.feature.remote")?, "origin");
+    assert_eq!(
+        read_config(dir, "branch.feature.merge")?,
+        "refs/heads/feature"
+    );
+    // The workspace follows the remote tip, which predates the fourth file
+    assert!(!dir.join("file4.txt").exists());
+
+    Ok(())
+}
+
+#[rstest]
+fn checkout_with_ambiguous_remote_matches_fails(
+    repository_with_multiple_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_multiple_commits.path();
+    let head = get_head_commit_sha(dir)?;
+    write_remote_ref(dir, "origin", "feature", &head);
+    write_remote_ref(dir, "upstream", "feature", &head);
+
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "'feature' matched multiple (2) remote tracking branches",
+        ));
+    assert!(!dir.join(".git/refs/heads/feature").exists());
+
+    Ok(())
+}
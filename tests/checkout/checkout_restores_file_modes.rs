@@ -0,0 +1,71 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+fn assert_modes_restored(dir: &Path) {
+    let script = std::fs::metadata(dir.join("run.sh")).unwrap();
+    assert_eq!(script.permissions().mode() & 0o777, 0o755);
+
+    let link = std::fs::symlink_metadata(dir.join("link")).unwrap();
+    assert!(link.file_type().is_symlink());
+    assert_eq!(
+        std::fs::read_link(dir.join("link")).unwrap(),
+        Path::new("run.sh")
+    );
+
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[rstest]
+fn checkout_and_reset_restore_executable_and_symlink_modes(repository_dir: TempDir) {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    write_file(FileSpec::new(
+        dir.join("run.sh"),
+        "#!/bin/sh\necho hi\n".to_string(),
+    ));
+    std::fs::set_permissions(dir.join("run.sh"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::os::unix::fs::symlink("run.sh", dir.join("link")).unwrap();
+    write_file(FileSpec::new(dir.join("plain.txt"), "plain".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add script and link").assert().success();
+    let with_modes = get_head_commit_sha(dir).unwrap();
+
+    let tree = run_git_command(dir, &["ls-tree", "HEAD"]).output().unwrap();
+    let tree = String::from_utf8(tree.stdout).unwrap();
+    assert!(
+        tree.lines()
+            .any(|line| line.starts_with("120000 blob") && line.ends_with("\tlink"))
+    );
+    assert!(
+        tree.lines()
+            .any(|line| line.starts_with("100755 blob") && line.ends_with("\trun.sh"))
+    );
+
+    std::fs::remove_file(dir.join("run.sh")).unwrap();
+    std::fs::remove_file(dir.join("link")).unwrap();
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Remove script and link").assert().success();
+
+    run_bit_command(dir, &["checkout", &with_modes])
+        .assert()
+        .success();
+    assert_modes_restored(dir);
+
+    std::fs::remove_file(dir.join("run.sh")).unwrap();
+    std::fs::remove_file(dir.join("link")).unwrap();
+    run_bit_command(dir, &["reset", "--hard"])
+        .assert()
+        .success();
+    assert_modes_restored(dir);
+}
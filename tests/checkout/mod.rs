@@ -1,3 +1,11 @@
 mod checkout_branch_successfully;
+mod checkout_conflict_side;
+mod checkout_deleted_paths;
+mod checkout_force_discards_local_changes;
+mod checkout_merge_carries_local_changes;
+mod checkout_orphan_branch;
+mod checkout_paths_with_pathspec;
+mod checkout_remote_tracking_branch;
+mod checkout_restores_file_modes;
 mod checkout_with_conflicts;
 mod checkout_with_symbolic_refs;
@@ -0,0 +1,166 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::{fixture, rstest};
+
+/// Committed `1.txt`, `a/2.txt` and `a/b/3.txt` plus untracked files at
+/// the root, inside a tracked directory and inside an untracked directory
+#[fixture]
+fn repository_with_untracked_files(init_repository_dir: TempDir) -> TempDir {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(dir.join("untracked.txt"), "u".to_string()));
+    write_file(FileSpec::new(
+        dir.join("a").join("new.txt"),
+        "n".to_string(),
+    ));
+    write_file(FileSpec::new(
+        dir.join("newdir").join("x.txt"),
+        "x".to_string(),
+    ));
+
+    init_repository_dir
+}
+
+fn assert_tracked_files_kept(dir: &std::path::Path) {
+    assert!(dir.join("1.txt").exists());
+    assert!(dir.join("a").join("2.txt").exists());
+    assert!(dir.join("a").join("b").join("3.txt").exists());
+}
+
+#[rstest]
+fn clean_requires_force(repository_with_untracked_files: TempDir) {
+    let dir = repository_with_untracked_files.path();
+
+    run_bit_command(dir, &["clean"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("refusing to clean without -f"));
+
+    assert!(dir.join("untracked.txt").exists());
+}
+
+#[rstest]
+fn clean_dry_run_lists_untracked_files(repository_with_untracked_files: TempDir) {
+    let dir = repository_with_untracked_files.path();
+
+    run_bit_command(dir, &["clean", "-n"])
+        .assert()
+        .success()
+        .stdout("Would remove a/new.txt\nWould remove untracked.txt\n");
+
+    assert!(dir.join("untracked.txt").exists());
+    assert!(dir.join("a").join("new.txt").exists());
+    assert!(dir.join("newdir").join("x.txt").exists());
+}
+
+#[rstest]
+fn clean_force_removes_untracked_files_and_keeps_tracked_ones(
+    repository_with_untracked_files: TempDir,
+) {
+    let dir = repository_with_untracked_files.path();
+
+    run_bit_command(dir, &["clean", "-f"])
+        .assert()
+        .success()
+        .stdout("Removing a/new.txt\nRemoving untracked.txt\n");
+
+    assert!(!dir.join("untracked.txt").exists());
+    assert!(!dir.join("a").join("new.txt").exists());
+    // Untracked directories are left alone without -d
+    assert!(dir.join("newdir").join("x.txt").exists());
+    assert_tracked_files_kept(dir);
+}
+
+#[rstest]
+fn clean_with_directories_removes_untracked_directory(repository_with_untracked_files: TempDir) {
+    let dir = repository_with_untracked_files.path();
+
+    run_bit_command(dir, &["clean", "-f", "-d"])
+        .assert()
+        .success()
+        .stdout("Removing a/new.txt\nRemoving newdir/\nRemoving untracked.txt\n");
+
+    assert!(!dir.join("newdir").exists());
+    assert_tracked_files_kept(dir);
+
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[rstest]
+fn clean_keeps_ignored_files_unless_x_is_given(repository_with_untracked_files: TempDir) {
+    let dir = repository_with_untracked_files.path();
+
+    write_file(FileSpec::new(
+        dir.join(".git").join("info").join("exclude"),
+        "*.log\nbuild/\n".to_string(),
+    ));
+    write_file(FileSpec::new(dir.join("debug.log"), "log".to_string()));
+    write_file(FileSpec::new(
+        dir.join("build").join("out.o"),
+        "obj".to_string(),
+    ));
+    write_file(FileSpec::new(
+        dir.join("newdir").join("trace.log"),
+        "log".to_string(),
+    ));
+
+    // The untracked directory holds an ignored file, so only its other files go
+    run_bit_command(dir, &["clean", "-f", "-d"])
+        .assert()
+        .success()
+        .stdout("Removing a/new.txt\nRemoving newdir/x.txt\nRemoving untracked.txt\n");
+
+    assert!(dir.join("debug.log").exists());
+    assert!(dir.join("build").join("out.o").exists());
+    assert!(dir.join("newdir").join("trace.log").exists());
+
+    run_bit_command(dir, &["clean", "-f", "-d", "-x"])
+        .assert()
+        .success()
+        .stdout("Removing build/\nRemoving debug.log\nRemoving newdir/\n");
+
+    assert!(!dir.join("debug.log").exists());
+    assert!(!dir.join("build").exists());
+    assert!(!dir.join("newdir").exists());
+    assert_tracked_files_kept(dir);
+}
+
+#[rstest]
+fn clean_reads_ignore_files_of_subdirectories_and_keeps_nested_repositories(
+    repository_with_untracked_files: TempDir,
+) {
+    let dir = repository_with_untracked_files.path();
+
+    write_file(FileSpec::new(
+        dir.join("a").join(".gitignore"),
+        "*.tmp\n".to_string(),
+    ));
+    write_file(FileSpec::new(
+        dir.join("a").join("b").join("cache.tmp"),
+        "tmp".to_string(),
+    ));
+    write_file(FileSpec::new(
+        dir.join("nested").join(".git").join("HEAD"),
+        "ref: refs/heads/master\n".to_string(),
+    ));
+    write_file(FileSpec::new(
+        dir.join("nested").join("file.txt"),
+        "nested".to_string(),
+    ));
+
+    run_bit_command(dir, &["clean", "-f", "-d"])
+        .assert()
+        .success()
+        .stdout(
+            "Removing a/.gitignore\nRemoving a/new.txt\nRemoving newdir/\nRemoving untracked.txt\n",
+        );
+
+    assert!(dir.join("a").join("b").join("cache.tmp").exists());
+    assert!(dir.join("nested").join("file.txt").exists());
+    assert_tracked_files_kept(dir);
+}
@@ -0,0 +1 @@
+mod clean_untracked_files;
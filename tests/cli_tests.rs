@@ -1,8 +1,14 @@
 mod add;
+mod am;
+mod apply;
+mod archive;
+mod bisect;
 mod commit;
 
 mod branch;
+mod cat_file;
 mod checkout;
+mod clean;
 /// Contains common utilities and setup boilerplate, such as
 /// 1. Scaffolding temp git repo
 /// 2. Creating files with random content
@@ -10,10 +16,25 @@ mod checkout;
 /// 4. Running git commands
 /// 5. Comparing index contents
 mod common;
+mod config;
 mod diff;
+mod format_patch;
+mod fsck;
+mod gc;
 mod hash_object;
 mod init;
 mod log;
 mod ls_tree;
 mod merge;
+mod mv;
+mod reflog;
+mod reset;
+mod rev_parse;
+mod rm;
+mod show;
 mod status;
+mod tag;
+mod unpack_objects;
+mod update_index;
+mod verify_commit;
+mod verify_index;
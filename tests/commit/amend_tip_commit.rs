@@ -0,0 +1,108 @@
+use crate::common::command::{
+    bit_commit_with_timestamp, get_head_commit_sha, init_repository_dir, run_bit_command,
+    run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+const AMEND_DATE: &str = "2024-01-01 12:00:00 +0000";
+
+fn git_stdout(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = run_git_command(dir, args).assert().success();
+    String::from_utf8(output.get_output().stdout.clone()).unwrap()
+}
+
+fn bit_amend(dir: &std::path::Path, message: Option<&str>) -> assert_cmd::Command {
+    let mut args = vec!["commit", "--amend"];
+    if let Some(message) = message {
+        args.extend(["-m", message]);
+    }
+    let mut cmd = run_bit_command(dir, &args);
+    cmd.envs(vec![
+        ("GIT_AUTHOR_NAME", "other_user"),
+        ("GIT_AUTHOR_EMAIL", "other_email@email.com"),
+        ("GIT_AUTHOR_DATE", AMEND_DATE),
+    ]);
+    cmd
+}
+
+#[rstest]
+fn amend_replaces_tip_keeping_parent_and_author(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let root_oid = get_head_commit_sha(dir)?;
+    write_file(FileSpec::new(dir.join("1.txt"), "changed".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit_with_timestamp(dir, "Change file", "2023-06-01 12:00:00 +0000")
+        .assert()
+        .success();
+    let old_oid = get_head_commit_sha(dir)?;
+
+    write_file(FileSpec::new(dir.join("forgotten.txt"), "oops".to_string()));
+    run_bit_command(dir, &["add", "forgotten.txt"])
+        .assert()
+        .success();
+    bit_amend(dir, Some("Change file and add the forgotten one"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "] Change file and add the forgotten one",
+        ));
+
+    let new_oid = get_head_commit_sha(dir)?;
+    assert_ne!(new_oid, old_oid);
+    assert_eq!(git_stdout(dir, &["rev-parse", "HEAD^"]).trim(), root_oid);
+
+    let commit = git_stdout(dir, &["cat-file", "-p", "HEAD"]);
+    assert!(commit.contains("author fake_user <fake_email@email.com> 1685620800 +0000\n"));
+    assert!(commit.contains("committer other_user <other_email@email.com> 1704110400 +0000\n"));
+    assert!(
+        commit
+            .trim_end()
+            .ends_with("\nChange file and add the forgotten one")
+    );
+    assert!(git_stdout(dir, &["ls-tree", "HEAD"]).contains("forgotten.txt"));
+
+    // The replaced commit is orphaned, not deleted
+    assert_eq!(git_stdout(dir, &["cat-file", "-t", &old_oid]), "commit\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn amend_root_commit_keeps_message_when_none_given(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let old_oid = get_head_commit_sha(dir)?;
+
+    write_file(FileSpec::new(dir.join("1.txt"), "amended".to_string()));
+    run_bit_command(dir, &["add", "1.txt"]).assert().success();
+    bit_amend(dir, None)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[(root-commit) "));
+
+    assert_ne!(get_head_commit_sha(dir)?, old_oid);
+    let commit = git_stdout(dir, &["cat-file", "-p", "HEAD"]);
+    assert!(!commit.contains("\nparent "));
+    assert!(commit.trim_end().ends_with("\nInitial commit"));
+    assert_eq!(git_stdout(dir, &["show", "HEAD:1.txt"]), "amended");
+
+    Ok(())
+}
+
+#[rstest]
+fn amend_without_commits_fails(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    std::fs::remove_dir_all(dir.join(".git")).unwrap();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    bit_amend(dir, Some("Nothing"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("you have nothing to amend"));
+}
@@ -0,0 +1,73 @@
+use crate::common::command::{
+    bit_commit, bit_merge, get_branch_commit_sha, get_head_commit_sha, init_repository_dir,
+    run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+#[rstest]
+fn commit_refuses_unmerged_files_then_creates_merge_commit(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let git_dir = dir.join(".git");
+
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("1.txt"), "ours".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Change on master").assert().success();
+    let master_oid = get_head_commit_sha(dir)?;
+
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("1.txt"), "theirs".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Change on feature").assert().success();
+    let feature_oid = get_branch_commit_sha(dir, "feature")?;
+
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge(dir, "feature", "Merge feature")
+        .assert()
+        .failure();
+
+    run_bit_command(dir, &["commit", "-m", "Too early"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "committing is not possible because you have unmerged files",
+        ));
+    assert!(git_dir.join("MERGE_HEAD").exists());
+
+    write_file(FileSpec::new(dir.join("1.txt"), "resolved".to_string()));
+    run_bit_command(dir, &["add", "1.txt"]).assert().success();
+    let mut commit = run_bit_command(dir, &["commit"]);
+    commit.envs(vec![
+        ("GIT_AUTHOR_NAME", "fake_user"),
+        ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+        ("GIT_AUTHOR_DATE", "2023-01-01 12:00:00 +0000"),
+    ]);
+    commit.assert().success();
+
+    let output = run_git_command(dir, &["cat-file", "commit", "HEAD"])
+        .assert()
+        .success();
+    let commit = String::from_utf8(output.get_output().stdout.clone())?;
+    let parents = commit
+        .lines()
+        .filter_map(|line| line.strip_prefix("parent "))
+        .collect::<Vec<_>>();
+    assert_eq!(parents, vec![master_oid.trim(), feature_oid.trim()]);
+    assert!(commit.contains("Merge feature"));
+
+    assert!(!git_dir.join("MERGE_HEAD").exists());
+    assert!(!git_dir.join("MERGE_MSG").exists());
+
+    Ok(())
+}
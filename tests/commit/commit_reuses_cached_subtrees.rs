@@ -0,0 +1,64 @@
+use crate::common::command::{bit_commit, repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn git_output(dir: &Path, args: &[&str], index_file: Option<&Path>) -> String {
+    let mut cmd = run_git_command(dir, args);
+    if let Some(index_file) = index_file {
+        cmd.env("GIT_INDEX_FILE", index_file);
+    }
+
+    let output = cmd.output().expect("Failed to run git");
+    assert!(output.status.success(), "git {:?} failed", args);
+
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+#[rstest]
+fn commit_of_one_file_change_matches_git_tree(repository_dir: TempDir) {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    write_file(FileSpec::new(dir.join("README"), "readme".to_string()));
+    for top in 0..5 {
+        for sub in 0..4 {
+            for file in 0..10 {
+                write_file(FileSpec::new(
+                    dir.join(format!("dir{top}/sub{sub}/file{file}.txt")),
+                    format!("{top} {sub} {file}"),
+                ));
+            }
+        }
+    }
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "First commit").assert().success();
+
+    write_file(FileSpec::new(
+        dir.join("dir3/sub2/file7.txt"),
+        "changed".to_string(),
+    ));
+    run_bit_command(dir, &["add", "dir3/sub2/file7.txt"])
+        .assert()
+        .success();
+    bit_commit(dir, "Second commit").assert().success();
+
+    // The index keeps the cache tree for the next commit
+    let index = std::fs::read(dir.join(".git").join("index")).unwrap();
+    assert!(index.windows(4).any(|window| window == b"TREE"));
+
+    let head_tree = git_output(dir, &["rev-parse", "HEAD^{tree}"], None);
+
+    // Git reading the cache tree from bit's index agrees with it
+    assert_eq!(git_output(dir, &["write-tree"], None), head_tree);
+
+    // So does a full rebuild from a fresh index
+    let fresh_index = dir.join(".git").join("fresh-index");
+    git_output(dir, &["add", "-A"], Some(&fresh_index));
+    assert_eq!(
+        git_output(dir, &["write-tree"], Some(&fresh_index)),
+        head_tree
+    );
+}
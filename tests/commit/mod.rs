@@ -1,2 +1,5 @@
+mod amend_tip_commit;
+mod commit_concludes_conflicted_merge;
+mod commit_reuses_cached_subtrees;
 mod write_commit_object_successfully_for_flat_project;
 mod write_commit_object_successfully_for_nested_project;
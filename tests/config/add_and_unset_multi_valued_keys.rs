@@ -0,0 +1,81 @@
+use crate::common::command::{repository_dir, run_bit_command, run_git_command};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+const FETCH_KEY: &str = "remote.origin.fetch";
+const HEADS_REFSPEC: &str = "+refs/heads/*:refs/remotes/origin/*";
+const TAGS_REFSPEC: &str = "+refs/tags/*:refs/tags/*";
+
+#[rstest]
+fn add_and_unset_multi_valued_keys(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    let config_path = dir.join(".git").join("config");
+    std::fs::write(&config_path, "# managed by bit\n[core]\n\tbare = false\n")?;
+
+    run_bit_command(dir, &["config", "user.name", "Jane Doe"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["config", "--add", FETCH_KEY, HEADS_REFSPEC])
+        .assert()
+        .success();
+    run_bit_command(dir, &["config", "--add", FETCH_KEY, TAGS_REFSPEC])
+        .assert()
+        .success();
+
+    // Every value is listed, in insertion order
+    run_bit_command(dir, &["config", "--get-all", FETCH_KEY])
+        .assert()
+        .success()
+        .stdout(format!("{}\n{}\n", HEADS_REFSPEC, TAGS_REFSPEC));
+    // A plain read returns the last value
+    run_bit_command(dir, &["config", FETCH_KEY])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", TAGS_REFSPEC));
+
+    // A multi-valued key cannot be unset one value at a time
+    run_bit_command(dir, &["config", "--unset", FETCH_KEY])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--unset-all"));
+
+    run_bit_command(dir, &["config", "--unset", "user.name"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["config", "user.name"])
+        .assert()
+        .failure();
+
+    pretty_assertions::assert_eq!(
+        std::fs::read_to_string(&config_path)?,
+        format!(
+            "# managed by bit\n[core]\n\tbare = false\n[user]\n\
+            [remote \"origin\"]\n\tfetch = {}\n\tfetch = {}\n",
+            HEADS_REFSPEC, TAGS_REFSPEC
+        )
+    );
+
+    // Git reads the file back with the same values
+    run_git_command(dir, &["config", "--get-all", FETCH_KEY])
+        .assert()
+        .success()
+        .stdout(format!("{}\n{}\n", HEADS_REFSPEC, TAGS_REFSPEC));
+
+    run_bit_command(dir, &["config", "--unset-all", FETCH_KEY])
+        .assert()
+        .success();
+    run_bit_command(dir, &["config", "--get-all", FETCH_KEY])
+        .assert()
+        .failure();
+    run_git_command(dir, &["config", "--list", "--local"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("core.bare=false"));
+
+    Ok(())
+}
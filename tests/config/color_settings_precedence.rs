@@ -0,0 +1,82 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::{PredicateBooleanExt, predicate};
+use rstest::rstest;
+use std::path::Path;
+
+const ESCAPE: &str = "\u{1b}[";
+
+fn set_config(dir: &Path, key: &str, value: &str) {
+    run_bit_command(dir, &["config", key, value])
+        .assert()
+        .success();
+}
+
+fn assert_colored(dir: &Path, args: &[&str], colored: bool) {
+    let escape = predicate::str::contains(ESCAPE);
+    let assert = run_bit_command(dir, args).assert().success();
+
+    if colored {
+        assert.stdout(escape);
+    } else {
+        assert.stdout(escape.not());
+    }
+}
+
+#[rstest]
+fn color_settings_precedence(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    write_file(FileSpec::new(dir.join("1.txt"), "changed".to_string()));
+
+    // Without a terminal, auto leaves the output plain
+    assert_colored(dir, &["diff"], false);
+    set_config(dir, "color.ui", "auto");
+    assert_colored(dir, &["diff"], false);
+
+    set_config(dir, "color.ui", "always");
+    assert_colored(dir, &["diff"], true);
+    assert_colored(dir, &["status"], true);
+
+    // Flags override the config
+    assert_colored(dir, &["diff", "--no-color"], false);
+    assert_colored(dir, &["diff", "--color=never"], false);
+
+    // color.diff overrides color.ui, for diff only
+    set_config(dir, "color.diff", "never");
+    assert_colored(dir, &["diff"], false);
+    assert_colored(dir, &["status"], true);
+    assert_colored(dir, &["diff", "--color=always"], true);
+
+    set_config(dir, "color.status", "false");
+    assert_colored(dir, &["status"], false);
+}
+
+#[rstest]
+fn invalid_color_setting_is_rejected(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    set_config(dir, "color.ui", "sometimes");
+
+    run_bit_command(dir, &["status"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "bad color config value 'sometimes' for 'color.ui'",
+        ));
+
+    // The setting can still be fixed
+    set_config(dir, "color.ui", "never");
+    run_bit_command(dir, &["status"]).assert().success();
+}
+
+#[rstest]
+fn color_setting_applies_in_subdirectories(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    write_file(FileSpec::new(
+        dir.join("a").join("2.txt"),
+        "changed".to_string(),
+    ));
+    set_config(dir, "color.ui", "always");
+
+    assert_colored(&dir.join("a"), &["status"], true);
+}
@@ -0,0 +1,2 @@
+mod add_and_unset_multi_valued_keys;
+mod color_settings_precedence;
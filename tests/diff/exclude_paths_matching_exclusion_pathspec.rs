@@ -0,0 +1,69 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+#[case::long_magic(":(exclude)*.lock")]
+#[case::short_magic(":!*.lock")]
+fn exclude_paths_matching_exclusion_pathspec(
+    init_repository_dir: TempDir,
+    #[case] exclusion: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir;
+
+    write_file(FileSpec::new(
+        dir.path().join("a").join("deps.lock"),
+        "v1\n".into(),
+    ));
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(dir.path(), "Lock dependencies")
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(dir.path().join("1.txt"), "uno\n".into()));
+    write_file(FileSpec::new(
+        dir.path().join("a").join("deps.lock"),
+        "v2\n".into(),
+    ));
+
+    let output = run_bit_command(dir.path(), &["diff", "--", ".", exclusion])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert!(stdout.contains("diff --git a/1.txt b/1.txt\n"));
+    assert!(!stdout.contains("deps.lock"));
+
+    Ok(())
+}
+
+#[rstest]
+fn read_pathspecs_relative_to_subdirectory(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir;
+
+    write_file(FileSpec::new(dir.path().join("1.txt"), "uno\n".into()));
+    write_file(FileSpec::new(
+        dir.path().join("a").join("2.txt"),
+        "dos\n".into(),
+    ));
+    write_file(FileSpec::new(
+        dir.path().join("a").join("b").join("3.txt"),
+        "tres\n".into(),
+    ));
+
+    let output = run_bit_command(&dir.path().join("a"), &["diff", "--", ".", ":!b"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert!(stdout.contains("diff --git a/a/2.txt b/a/2.txt\n"));
+    assert!(!stdout.contains("1.txt"));
+    assert!(!stdout.contains("3.txt"));
+
+    Ok(())
+}
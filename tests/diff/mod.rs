@@ -1,19 +1,34 @@
 mod diff_base_stage_against_workspace;
 mod diff_ours_stage_against_workspace;
 mod diff_theirs_stage_against_workspace;
+mod exclude_paths_matching_exclusion_pathspec;
 mod identify_unmerged_path_on_conflict;
 mod missing_stage_silently_skipped;
 mod mixed_conflict_and_clean_diff;
 mod no_patch_without_stage_flag;
+mod run_external_diff_program_for_changed_files;
 mod show_cached_diff_for_file_added_to_index;
 mod show_cached_diff_for_file_deleted_from_index;
 mod show_cached_diff_for_file_with_both_modified_content_and_mode_in_index;
 mod show_cached_diff_for_file_with_modified_content_in_index;
 mod show_cached_diff_for_file_with_modified_mode_in_index;
+mod show_cached_renames_with_name_status;
 mod show_diff_for_file_deleted_from_workspace;
 mod show_diff_for_file_with_both_modified_content_and_mode_in_workspace;
 mod show_diff_for_file_with_modified_content_in_workspace;
 mod show_diff_for_file_with_modified_mode_in_workspace;
+mod show_diff_with_custom_path_prefixes;
 mod show_diff_with_hunks_for_multiple_files_with_modified_content_in_workspace;
 mod show_full_patch_between_commits;
+mod show_function_context_in_hunk_headers;
+mod show_moved_lines_with_color_moved;
+mod show_name_status_between_empty_tree_commit_and_commit;
 mod show_name_status_for_added_and_deleted_files_between_commits;
+mod show_name_status_with_quoted_paths;
+mod show_name_status_with_rename_threshold;
+mod show_stat_for_workspace_index_and_commits;
+mod show_stat_sizes_of_non_utf8_binary_files;
+mod show_submodule_change_between_commits;
+mod show_textconv_output_for_binary_files;
+mod show_unmerged_paths_in_stat_and_name_status;
+mod write_diff_to_output_file_with_line_prefix;
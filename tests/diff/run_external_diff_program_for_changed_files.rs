@@ -0,0 +1,72 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, delete_path, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::{PredicateBooleanExt, predicate};
+use rstest::rstest;
+use std::path::Path;
+
+fn hash_object(dir: &Path, file: &str) -> String {
+    let output = run_bit_command(dir, &["hash-object", file])
+        .output()
+        .unwrap();
+
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+#[rstest]
+fn run_external_diff_program_for_modified_file(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let old_oid = hash_object(dir, "1.txt");
+    write_file(FileSpec::new(dir.join("1.txt"), "uno".to_string()));
+    let new_oid = hash_object(dir, "1.txt");
+    run_bit_command(dir, &["config", "diff.external", "echo"])
+        .assert()
+        .success();
+
+    // The index version goes through a temporary file, while the workspace
+    // version is passed in place
+    let expected = format!(
+        r"^1\.txt \S+bit-diff-\S+1\.txt {} 100644 \S+ {} 100644\n$",
+        old_oid, new_oid
+    );
+    let workspace_file = dir.canonicalize()?.join("1.txt");
+    run_bit_command(dir, &["diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(expected)?)
+        .stdout(predicate::str::contains(format!(
+            " {} ",
+            workspace_file.display()
+        )));
+
+    run_bit_command(dir, &["diff", "--no-ext-diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-one\n+uno\n"));
+
+    Ok(())
+}
+
+#[rstest]
+fn run_external_diff_program_from_environment_for_deleted_file(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let old_oid = hash_object(dir, "a/2.txt");
+    delete_path(&dir.join("a/2.txt"));
+
+    let expected = format!(
+        r"^a/2\.txt \S+bit-diff-\S+2\.txt {} 100644 /dev/null \. \.\n$",
+        old_oid
+    );
+    run_bit_command(dir, &["diff"])
+        .env("GIT_EXTERNAL_DIFF", "echo")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(expected)?)
+        .stdout(predicate::str::contains("deleted file mode").not());
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, delete_path, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+/// `notes.txt` is moved to `moved.txt` while 2 of its 5 lines change (60% similar)
+#[rstest]
+#[case::without_detection(&[], "A\tmoved.txt\nD\tnotes.txt\n")]
+#[case::strict(&["-M90"], "A\tmoved.txt\nD\tnotes.txt\n")]
+#[case::lenient(&["-M40"], "R060\tnotes.txt\tmoved.txt\n")]
+#[case::filtered_out(&["-M40", "--diff-filter=AD"], "")]
+fn show_staged_rename_with_threshold(
+    init_repository_dir: TempDir,
+    #[case] args: &[&str],
+    #[case] expected_output: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(
+        dir.join("notes.txt"),
+        "one\ntwo\nthree\nfour\nfive\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add notes").assert().success();
+
+    delete_path(&dir.join("notes.txt"));
+    write_file(FileSpec::new(
+        dir.join("moved.txt"),
+        "one\ntwo\nthree\nFOUR\nFIVE\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+
+    let bit_args = [&["diff", "--cached", "--name-status"], args].concat();
+    let output = run_bit_command(dir, &bit_args).assert().success();
+
+    assert_eq!(
+        String::from_utf8(output.get_output().stdout.clone())?,
+        expected_output
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn identical_content_is_a_full_rename_at_any_threshold(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    run_bit_command(dir, &["mv", "1.txt", "renamed.txt"])
+        .assert()
+        .success();
+
+    let output = run_bit_command(dir, &["diff", "--cached", "--name-status", "-M100%"])
+        .assert()
+        .success();
+    assert_eq!(
+        String::from_utf8(output.get_output().stdout.clone())?,
+        "R100\t1.txt\trenamed.txt\n"
+    );
+
+    let output = run_bit_command(dir, &["diff", "--cached", "-M"])
+        .assert()
+        .success();
+    assert_eq!(
+        String::from_utf8(output.get_output().stdout.clone())?,
+        "diff --git a/1.txt b/renamed.txt\n\
+        similarity index 100%\n\
+        rename from 1.txt\n\
+        rename to renamed.txt\n"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn show_diff_with_custom_path_prefixes(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+    let old_commit_sha = get_head_commit_sha(repository_dir.path())?;
+
+    write_file(FileSpec::new(
+        repository_dir.path().join("a").join("2.txt"),
+        "two modified".to_string(),
+    ));
+    run_bit_command(repository_dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(repository_dir.path(), "Modify a/2.txt")
+        .assert()
+        .success();
+    let new_commit_sha = get_head_commit_sha(repository_dir.path())?;
+
+    // --no-prefix prints the paths as-is
+    let output = run_bit_command(
+        repository_dir.path(),
+        &["diff", "--no-prefix", &old_commit_sha, &new_commit_sha],
+    )
+    .assert()
+    .success();
+    let actual_output = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert!(actual_output.contains("diff --git a/2.txt a/2.txt\n"));
+    assert!(actual_output.contains("--- a/2.txt\n"));
+    assert!(actual_output.contains("+++ a/2.txt\n"));
+    assert!(!actual_output.contains("b/a/2.txt"));
+
+    // Custom prefixes replace a/ and b/
+    let output = run_bit_command(
+        repository_dir.path(),
+        &[
+            "diff",
+            "--src-prefix=old/",
+            "--dst-prefix=new/",
+            &old_commit_sha,
+            &new_commit_sha,
+        ],
+    )
+    .assert()
+    .success();
+    let actual_output = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert!(actual_output.contains("diff --git old/a/2.txt new/a/2.txt\n"));
+    assert!(actual_output.contains("--- old/a/2.txt\n"));
+    assert!(actual_output.contains("+++ new/a/2.txt\n"));
+
+    // Prefixes also apply to the workspace diff
+    write_file(FileSpec::new(
+        repository_dir.path().join("1.txt"),
+        "one modified".to_string(),
+    ));
+    let output = run_bit_command(repository_dir.path(), &["diff", "--no-prefix"])
+        .assert()
+        .success();
+    let actual_output = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert!(actual_output.contains("diff --git 1.txt 1.txt\n"));
+    assert!(actual_output.contains("--- 1.txt\n"));
+    assert!(actual_output.contains("+++ 1.txt\n"));
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::common::command::{bit_commit, repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+use std::path::Path;
+
+const SOURCE: &str = "use std::io;
+
+fn helper() -> u32 {
+    1
+}
+
+fn main() {
+    let a = helper();
+    let b = 2;
+    let c = 3;
+    let d = 4;
+    println!(\"{}\", a + b + c + d);
+}
+";
+
+const SCRIPT: &str = "def public(x):
+    a = 1
+    b = 2
+    c = 3
+    d = 4
+    return x
+";
+
+fn commit_and_modify(dir: &Path, file: &str, content: &str, from: &str, to: &str) {
+    write_file(FileSpec::new(dir.join(file), content.to_string()));
+    run_bit_command(dir, &["add", file]).assert().success();
+    bit_commit(dir, "Add source").assert().success();
+
+    write_file(FileSpec::new(dir.join(file), content.replace(from, to)));
+}
+
+#[rstest]
+fn show_enclosing_function_in_hunk_header(repository_dir: TempDir) {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+    commit_and_modify(dir, "lib.rs", SOURCE, "let d = 4;", "let d = 40;");
+
+    run_bit_command(dir, &["diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "@@ -8,6 +8,6 @@ fn main() {\n     let a = helper();\n",
+        ));
+}
+
+#[rstest]
+fn show_function_context_from_xfuncname_of_diff_driver(repository_dir: TempDir) {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+    commit_and_modify(dir, "m.py", SCRIPT, "return x", "return x + d");
+    write_file(FileSpec::new(
+        dir.join(".gitattributes"),
+        "*.py diff=python3\n".to_string(),
+    ));
+    run_bit_command(dir, &["config", "diff.python3.xfuncname", "^def ([a-z_]+)"])
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("@@ -3,4 +3,4 @@ public\n"));
+}
@@ -0,0 +1,82 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::{PredicateBooleanExt, predicate};
+use rstest::rstest;
+use std::path::Path;
+
+const MOVED_BLOCK: &str = "fn moved_function() {\n    do_something_important();\n";
+const STATIONARY_LINES: &str = "stay 1\nstay 2\nstay 3\nstay 4\n";
+
+/// Commit `code.rs`, then move its first block below the other lines and
+/// append a new line in the workspace
+fn move_block(dir: &Path) {
+    write_file(FileSpec::new(
+        dir.join("code.rs"),
+        format!("{MOVED_BLOCK}{STATIONARY_LINES}"),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add code").assert().success();
+
+    write_file(FileSpec::new(
+        dir.join("code.rs"),
+        format!("{STATIONARY_LINES}{MOVED_BLOCK}a genuinely new line\n"),
+    ));
+}
+
+#[rstest]
+fn show_moved_lines_with_color_moved(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    move_block(dir);
+
+    run_bit_command(dir, &["diff", "--color=always", "--color-moved"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\u{1b}[1;35m-fn moved_function() {\u{1b}[0m\n")
+                .and(predicate::str::contains(
+                    "\u{1b}[1;35m-    do_something_important();\u{1b}[0m\n",
+                ))
+                .and(predicate::str::contains(
+                    "\u{1b}[1;36m+fn moved_function() {\u{1b}[0m\n",
+                ))
+                .and(predicate::str::contains(
+                    "\u{1b}[1;36m+    do_something_important();\u{1b}[0m\n",
+                ))
+                .and(predicate::str::contains(
+                    "\u{1b}[32m+a genuinely new line\u{1b}[0m\n",
+                )),
+        );
+}
+
+#[rstest]
+fn moved_lines_keep_add_and_remove_colors_without_color_moved(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    move_block(dir);
+
+    run_bit_command(dir, &["diff", "--color=always"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("\u{1b}[31m-fn moved_function() {\u{1b}[0m\n")
+                .and(predicate::str::contains(
+                    "\u{1b}[32m+fn moved_function() {\u{1b}[0m\n",
+                ))
+                .and(predicate::str::contains("\u{1b}[1;35m").not()),
+        );
+}
+
+#[rstest]
+fn log_patch_colors_moved_lines(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    move_block(dir);
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Move block").assert().success();
+
+    run_bit_command(dir, &["log", "-p", "--color=always", "--color-moved=plain"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\u{1b}[1;36m+fn moved_function() {\u{1b}[0m\n",
+        ));
+}
@@ -0,0 +1,70 @@
+use crate::common::command::{bit_commit, repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+const EMPTY_TREE_OID: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+#[rstest]
+fn show_name_status_between_empty_tree_commit_and_commit(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_bit_command(repository_dir.path(), &["init"])
+        .assert()
+        .success();
+
+    // Committing an empty index records the well-known empty tree
+    bit_commit(repository_dir.path(), "Empty commit")
+        .assert()
+        .success();
+    let output = run_git_command(repository_dir.path(), &["cat-file", "-p", "HEAD"]).output()?;
+    let commit = String::from_utf8(output.stdout)?;
+    pretty_assertions::assert_eq!(
+        commit.lines().next(),
+        Some(format!("tree {EMPTY_TREE_OID}").as_str())
+    );
+
+    // The empty tree must be usable even when it is missing from the object database
+    let (dir, file) = EMPTY_TREE_OID.split_at(2);
+    std::fs::remove_file(
+        repository_dir
+            .path()
+            .join(".git")
+            .join("objects")
+            .join(dir)
+            .join(file),
+    )?;
+
+    write_file(FileSpec::new(
+        repository_dir.path().join("1.txt"),
+        "one".to_string(),
+    ));
+    write_file(FileSpec::new(
+        repository_dir.path().join("a").join("2.txt"),
+        "two".to_string(),
+    ));
+    run_bit_command(repository_dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(repository_dir.path(), "Add files")
+        .assert()
+        .success();
+
+    let actual_output = run_bit_command(
+        repository_dir.path(),
+        &["diff", "--name-status", "HEAD^", "HEAD"],
+    )
+    .assert()
+    .success();
+    let actual_output = String::from_utf8(actual_output.get_output().stdout.clone())?;
+
+    pretty_assertions::assert_eq!(actual_output, "A\t1.txt\nA\ta/2.txt\n");
+
+    // Listing the synthesized empty tree succeeds with no entries
+    run_bit_command(repository_dir.path(), &["ls-tree", EMPTY_TREE_OID])
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn show_name_status_with_quoted_paths(init_repository_dir: TempDir) {
+    let repository_dir = init_repository_dir;
+    write_file(FileSpec::new(
+        repository_dir.path().join("a b\tc.txt"),
+        "special".to_string(),
+    ));
+    run_bit_command(repository_dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(repository_dir.path(), "Add special file")
+        .assert()
+        .success();
+
+    // Spaces alone do not force quoting here, but the tab does
+    run_bit_command(
+        repository_dir.path(),
+        &["diff", "--name-status", "HEAD^", "HEAD"],
+    )
+    .assert()
+    .success()
+    .stdout("A\t\"a b\\tc.txt\"\n");
+}
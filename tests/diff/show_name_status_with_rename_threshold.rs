@@ -0,0 +1,87 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, delete_path, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+/// `notes.txt` is moved to `moved.txt` while 2 of its 5 lines change (60% similar)
+#[rstest]
+#[case::strict("-M90", "A\tmoved.txt\nD\tnotes.txt\n")]
+#[case::lenient("-M40", "R060\tnotes.txt\tmoved.txt\n")]
+fn show_name_status_with_rename_threshold(
+    init_repository_dir: TempDir,
+    #[case] threshold: &str,
+    #[case] expected_output: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(
+        dir.join("notes.txt"),
+        "one\ntwo\nthree\nfour\nfive\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add notes").assert().success();
+    let old_commit_sha = get_head_commit_sha(dir)?;
+
+    delete_path(&dir.join("notes.txt"));
+    write_file(FileSpec::new(
+        dir.join("moved.txt"),
+        "one\ntwo\nthree\nFOUR\nFIVE\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Move notes").assert().success();
+    let new_commit_sha = get_head_commit_sha(dir)?;
+
+    let output = run_bit_command(
+        dir,
+        &[
+            "diff",
+            "--name-status",
+            threshold,
+            &old_commit_sha,
+            &new_commit_sha,
+        ],
+    )
+    .assert()
+    .success();
+
+    assert_eq!(
+        String::from_utf8(output.get_output().stdout.clone())?,
+        expected_output
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn show_rename_patch_headers(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let old_commit_sha = get_head_commit_sha(dir)?;
+
+    delete_path(&dir.join("1.txt"));
+    write_file(FileSpec::new(dir.join("renamed.txt"), "one".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Rename 1.txt").assert().success();
+    let new_commit_sha = get_head_commit_sha(dir)?;
+
+    run_bit_command(dir, &["config", "diff.renames", "true"])
+        .assert()
+        .success();
+    let output = run_bit_command(dir, &["diff", &old_commit_sha, &new_commit_sha])
+        .assert()
+        .success();
+
+    assert_eq!(
+        String::from_utf8(output.get_output().stdout.clone())?,
+        "diff --git a/1.txt b/renamed.txt\n\
+        similarity index 100%\n\
+        rename from 1.txt\n\
+        rename to renamed.txt\n"
+    );
+
+    Ok(())
+}
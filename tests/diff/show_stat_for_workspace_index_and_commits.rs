@@ -0,0 +1,45 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, delete_path, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn show_stat_for_workspace_index_and_commits(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(dir.join("1.txt"), "one\nmore\n".to_string()));
+    delete_path(&dir.join("a").join("2.txt"));
+    write_file(FileSpec::new(dir.join("bin.dat"), "\0\x01\x02".to_string()));
+
+    // The untracked binary file is not part of the workspace diff
+    run_bit_command(dir, &["diff", "--stat"])
+        .assert()
+        .success()
+        .stdout(
+            " 1.txt   | 1 +\n \
+            a/2.txt | 1 -\n \
+            2 files changed, 1 insertion(+), 1 deletion(-)\n",
+        );
+
+    let staged_stat = " 1.txt   |   1 +\n \
+        a/2.txt |   1 -\n \
+        bin.dat | Bin 0 -> 3 bytes\n \
+        3 files changed, 1 insertion(+), 1 deletion(-)\n";
+    run_bit_command(dir, &["add", "."]).assert().success();
+    run_bit_command(dir, &["diff", "--cached", "--stat"])
+        .assert()
+        .success()
+        .stdout(staged_stat);
+
+    bit_commit(dir, "Change files").assert().success();
+    run_bit_command(dir, &["diff", "--stat", "HEAD^", "HEAD"])
+        .assert()
+        .success()
+        .stdout(staged_stat);
+
+    // Nothing changed: no summary line either
+    run_bit_command(dir, &["diff", "--stat"])
+        .assert()
+        .success()
+        .stdout("");
+}
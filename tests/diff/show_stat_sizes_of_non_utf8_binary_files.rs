@@ -0,0 +1,27 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn show_stat_sizes_of_non_utf8_binary_files(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    // bit cannot store content that is not UTF-8 yet, so git stages it
+    std::fs::write(dir.join("image.png"), b"\x89PNG\r\n\x1a\n\x00\xff").unwrap();
+    std::fs::write(dir.join("latin1.txt"), b"caf\xe9\n").unwrap();
+    run_git_command(dir, &["add", "."]).assert().success();
+    std::fs::write(dir.join("image.png"), b"\x89PNG\r\n\x1a\n\x00\xfe\xff\xfd").unwrap();
+    std::fs::write(dir.join("latin1.txt"), b"caf\xe9\nna\xefve\n").unwrap();
+
+    let expected = " image.png  | Bin 10 -> 12 bytes\n \
+        latin1.txt |   1 +\n \
+        2 files changed, 1 insertion(+)\n";
+    run_bit_command(dir, &["diff", "--stat"])
+        .assert()
+        .success()
+        .stdout(expected);
+    run_git_command(dir, &["diff", "--stat"])
+        .assert()
+        .success()
+        .stdout(expected);
+}
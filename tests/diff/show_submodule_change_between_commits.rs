@@ -0,0 +1,76 @@
+use crate::common::command::{
+    get_head_commit_sha, init_repository_dir, run_bit_command, run_git_command,
+};
+use assert_fs::TempDir;
+use rstest::rstest;
+use std::path::Path;
+
+const FIRST_SUBMODULE_COMMIT: &str = "1111111111111111111111111111111111111111";
+const SECOND_SUBMODULE_COMMIT: &str = "2222222222222222222222222222222222222222";
+
+fn git_commit_gitlink(dir: &Path, oid: &str, message: &str) {
+    run_git_command(
+        dir,
+        &[
+            "update-index",
+            "--add",
+            "--cacheinfo",
+            &format!("160000,{},sub", oid),
+        ],
+    )
+    .assert()
+    .success();
+    // Without a final newline, like the messages bit writes
+    run_git_command(dir, &["commit", "--cleanup=verbatim", "-F", "-"])
+        .write_stdin(message)
+        .envs(vec![
+            ("GIT_AUTHOR_NAME", "fake_user"),
+            ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+            ("GIT_COMMITTER_NAME", "fake_user"),
+            ("GIT_COMMITTER_EMAIL", "fake_email@email.com"),
+        ])
+        .assert()
+        .success();
+}
+
+#[rstest]
+fn show_submodule_change_between_commits(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+    let base_sha = get_head_commit_sha(repository_dir.path())?;
+
+    git_commit_gitlink(
+        repository_dir.path(),
+        FIRST_SUBMODULE_COMMIT,
+        "Add submodule",
+    );
+    let added_sha = get_head_commit_sha(repository_dir.path())?;
+
+    git_commit_gitlink(
+        repository_dir.path(),
+        SECOND_SUBMODULE_COMMIT,
+        "Bump submodule",
+    );
+    let bumped_sha = get_head_commit_sha(repository_dir.path())?;
+
+    run_bit_command(repository_dir.path(), &["diff", &base_sha, &added_sha])
+        .assert()
+        .success()
+        .stdout("Submodule sub 0000000..1111111 (new submodule)\n");
+
+    run_bit_command(repository_dir.path(), &["diff", &added_sha, &bumped_sha])
+        .assert()
+        .success()
+        .stdout("Submodule sub 1111111..2222222\n");
+
+    let output = run_bit_command(repository_dir.path(), &["show", &bumped_sha])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    assert!(stdout.contains("Bump submodule"));
+    assert!(stdout.contains("Submodule sub 1111111..2222222\n"));
+    assert!(!stdout.contains("diff --git"));
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::{PredicateBooleanExt, predicate};
+use rstest::rstest;
+use std::path::Path;
+
+/// Commit `data.bin` (which holds a NUL byte) with a `nul` diff driver, then
+/// modify it in the workspace
+fn modify_binary_file(dir: &Path) {
+    write_file(FileSpec::new(
+        dir.join(".gitattributes"),
+        "*.bin diff=nul\n".to_string(),
+    ));
+    write_file(FileSpec::new(dir.join("data.bin"), "a\0b\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add binary data").assert().success();
+
+    write_file(FileSpec::new(dir.join("data.bin"), "a\0c\n".to_string()));
+}
+
+#[rstest]
+fn show_textconv_output_for_binary_files(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    modify_binary_file(dir);
+    run_bit_command(dir, &["config", "diff.nul.textconv", "cat -v"])
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "--- a/data.bin\n+++ b/data.bin\n@@ -1,1 +1,1 @@\n-a^@b\n+a^@c\n",
+        ));
+
+    run_bit_command(dir, &["diff", "--no-textconv"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Binary files a/data.bin and b/data.bin differ\n",
+        ));
+
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Change binary data").assert().success();
+    run_bit_command(dir, &["show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("-a^@b\n+a^@c\n"));
+}
+
+#[rstest]
+fn show_binary_files_differ_without_textconv_command(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    modify_binary_file(dir);
+
+    run_bit_command(dir, &["diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Binary files a/data.bin and b/data.bin differ\n",
+        ))
+        .stdout(predicate::str::contains("+++").not());
+}
+
+#[rstest]
+fn textconv_receives_raw_bytes_of_non_utf8_files(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    write_file(FileSpec::new(
+        dir.join(".gitattributes"),
+        "*.bin diff=hex\n".to_string(),
+    ));
+    // bit cannot store content that is not UTF-8 yet, so git stages it
+    std::fs::write(dir.join("data.bin"), b"\xff\xfe\x80a\n").unwrap();
+    run_git_command(dir, &["add", "."]).assert().success();
+    std::fs::write(dir.join("data.bin"), b"\xff\xfe\x81a\n").unwrap();
+    run_bit_command(dir, &["config", "diff.hex.textconv", "od -An -tx1"])
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "- ff fe 80 61 0a\n+ ff fe 81 61 0a\n",
+        ));
+}
@@ -0,0 +1,51 @@
+use crate::common::command::{bit_commit, bit_merge, repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+/// Unmerged paths get a `U` line with `--name-status` and an `Unmerged` line
+/// with `--stat`, left out of the total, as Git shows them
+#[rstest]
+#[case("--name-status", "U\tf.txt\nM\tg.txt\n")]
+#[case(
+    "--stat",
+    " f.txt | Unmerged\n g.txt | 2 +-\n 1 file changed, 1 insertion(+), 1 deletion(-)\n"
+)]
+fn show_unmerged_paths_in_stat_and_name_status(
+    repository_dir: TempDir,
+    #[case] format: &str,
+    #[case] expected: &str,
+) {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    write_file(FileSpec::new(dir.join("f.txt"), "base\n".into()));
+    write_file(FileSpec::new(dir.join("g.txt"), "original\n".into()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "A").assert().success();
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(dir.join("f.txt"), "ours\n".into()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "B").assert().success();
+
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("f.txt"), "theirs\n".into()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "C").assert().success();
+
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge(dir, "feature", "conflict").assert().failure();
+    write_file(FileSpec::new(dir.join("g.txt"), "modified\n".into()));
+
+    run_bit_command(dir, &["diff", format])
+        .assert()
+        .success()
+        .stdout(expected.to_string());
+}
@@ -0,0 +1,94 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+fn modify_and_commit(dir: &std::path::Path) {
+    write_file(FileSpec::new(dir.join("1.txt"), "one\nmore\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Extend 1.txt").assert().success();
+}
+
+#[rstest]
+fn diff_output_file_matches_stdout(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    write_file(FileSpec::new(dir.join("1.txt"), "changed".to_string()));
+
+    let stdout = run_bit_command(dir, &["diff"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert!(!stdout.is_empty());
+
+    let output = dir.join("changes.diff");
+    run_bit_command(
+        dir,
+        &["diff", &format!("--output={}", output.to_str().unwrap())],
+    )
+    .assert()
+    .success()
+    .stdout("");
+
+    assert_eq!(std::fs::read(&output)?, stdout);
+
+    Ok(())
+}
+
+#[rstest]
+fn show_line_prefix_prepends_every_line(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    modify_and_commit(dir);
+
+    let plain = run_bit_command(dir, &["show"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let prefixed = run_bit_command(dir, &["show", "--line-prefix=> "])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let expected = String::from_utf8(plain)
+        .unwrap()
+        .lines()
+        .map(|line| format!("> {}\n", line))
+        .collect::<String>();
+    assert_eq!(String::from_utf8(prefixed).unwrap(), expected);
+}
+
+#[rstest]
+fn show_output_file_gets_line_prefix(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    modify_and_commit(dir);
+
+    let output = dir.join("commit.patch");
+    run_bit_command(
+        dir,
+        &[
+            "show",
+            "--line-prefix=| ",
+            "--output",
+            output.to_str().unwrap(),
+        ],
+    )
+    .assert()
+    .success()
+    .stdout("");
+
+    let content = std::fs::read_to_string(&output)?;
+    assert!(content.lines().all(|line| line.starts_with("| ")));
+    assert!(content.contains("| +more\n"));
+
+    Ok(())
+}
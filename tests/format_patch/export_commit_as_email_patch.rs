@@ -0,0 +1,44 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn export_commit_as_email_patch(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(dir.join("1.txt"), "uno\nmore\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(
+        dir,
+        "Rewrite one\n\nTranslates 1.txt and adds a second line.",
+    )
+    .assert()
+    .success();
+    let head = get_head_commit_sha(dir)?;
+
+    let output = run_bit_command(dir, &["format-patch"]).assert().success();
+    let patch = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert!(patch.starts_with(&format!("From {} Mon Sep 17 00:00:00 2001\n", head)));
+    assert!(patch.contains("From: fake_user <fake_email@email.com>\n"));
+    assert!(patch.contains("Date: Sun, 1 Jan 2023 12:00:00 +0000\n"));
+    assert!(patch.contains(
+        "Subject: [PATCH] Rewrite one\n\nTranslates 1.txt and adds a second line.\n---\n"
+    ));
+    assert!(patch.contains("diff --git a/1.txt b/1.txt\n"));
+    assert!(patch.contains("-one\n+uno\n+more\n"));
+    // The diff is the one between the commit and its parent
+    let diff = run_bit_command(dir, &["diff", "HEAD~1", "HEAD"])
+        .assert()
+        .success();
+    let diff = String::from_utf8(diff.get_output().stdout.clone())?;
+    assert!(patch.contains(&format!("---\n{}-- \n", diff)));
+    assert!(patch.ends_with(&format!("-- \nbit {}\n\n", env!("CARGO_PKG_VERSION"))));
+
+    Ok(())
+}
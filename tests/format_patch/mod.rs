@@ -0,0 +1 @@
+mod export_commit_as_email_patch;
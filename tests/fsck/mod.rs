@@ -0,0 +1 @@
+mod report_dangling_and_unreachable_objects;
@@ -0,0 +1,78 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn commit_file(dir: &Path, content: &str, message: &str) {
+    write_file(FileSpec::new(dir.join("1.txt"), content.to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, message).assert().success();
+}
+
+fn stdout(dir: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = run_bit_command(dir, args).assert().success();
+
+    Ok(String::from_utf8(output.get_output().stdout.clone())?)
+}
+
+#[rstest]
+fn report_dangling_and_unreachable_objects(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    // Amend a commit away: only the reflog of the reset still knows it
+    commit_file(dir, "first draft", "Draft");
+    let amended = get_head_commit_sha(dir)?;
+    run_bit_command(dir, &["reset", "--soft", "HEAD~1"])
+        .assert()
+        .success();
+    commit_file(dir, "final version", "Final");
+
+    // A blob written to the database but never staged
+    write_file(FileSpec::new(dir.join("orphan.txt"), "orphan".to_string()));
+    let orphan = stdout(dir, &["hash-object", "-w", "orphan.txt"])?
+        .trim()
+        .to_string();
+    std::fs::remove_file(dir.join("orphan.txt"))?;
+
+    // The reflog keeps the amended commit reachable
+    assert_eq!(
+        stdout(dir, &["fsck"])?,
+        format!("dangling blob {}\n", orphan)
+    );
+    assert_eq!(
+        stdout(dir, &["fsck", "--unreachable", "--no-dangling"])?,
+        format!("unreachable blob {}\n", orphan)
+    );
+
+    let mut dangling = vec![
+        (amended.clone(), format!("dangling commit {}\n", amended)),
+        (orphan.clone(), format!("dangling blob {}\n", orphan)),
+    ];
+    dangling.sort();
+    let dangling = dangling
+        .into_iter()
+        .map(|(_, line)| line)
+        .collect::<String>();
+
+    assert_eq!(stdout(dir, &["fsck", "--no-reflogs"])?, dangling);
+
+    // Every dangling object is unreachable as well
+    let unreachable = stdout(
+        dir,
+        &["fsck", "--unreachable", "--no-dangling", "--no-reflogs"],
+    )?;
+    assert!(unreachable.contains(&format!("unreachable commit {}\n", amended)));
+    assert!(unreachable.contains(&format!("unreachable blob {}\n", orphan)));
+    assert_eq!(
+        stdout(dir, &["fsck", "--unreachable", "--no-reflogs"])?,
+        format!("{}{}", unreachable, dangling)
+    );
+
+    Ok(())
+}
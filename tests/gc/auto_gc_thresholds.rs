@@ -0,0 +1,267 @@
+use crate::common::command::{bit_commit, init_repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use sha1::{Digest, Sha1};
+use std::path::Path;
+
+/// Files under the loose object fan-out directories (`.git/objects/xx/`)
+fn count_loose_objects(dir: &Path) -> usize {
+    std::fs::read_dir(dir.join(".git/objects"))
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().len() == 2)
+        .map(|entry| std::fs::read_dir(entry.path()).unwrap().count())
+        .sum()
+}
+
+fn list_pack_files(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir.join(".git/objects/pack")) else {
+        return Vec::new();
+    };
+    let mut names = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Contents of `count` blobs stored under `.git/objects/17/`, the fan-out
+/// directory `gc --auto` samples
+fn blob_contents_in_sampled_directory(count: usize) -> Vec<String> {
+    (0..)
+        .map(|n| format!("sample {n}"))
+        .filter(|content| {
+            let object = format!("blob {}\0{}", content.len(), content);
+            format!("{:x}", Sha1::digest(object.as_bytes())).starts_with("17")
+        })
+        .take(count)
+        .collect()
+}
+
+#[rstest]
+fn auto_gc_is_a_no_op_below_threshold(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    let loose_before = count_loose_objects(dir);
+
+    run_bit_command(dir, &["gc", "--auto"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+
+    assert_eq!(count_loose_objects(dir), loose_before);
+    assert!(list_pack_files(dir).is_empty());
+}
+
+#[rstest]
+fn auto_gc_repacks_above_threshold(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    // 3 blobs, 3 trees and a commit
+    assert_eq!(count_loose_objects(dir), 7);
+
+    // A gc.auto of 5 allows one object in the sampled directory
+    for (i, content) in blob_contents_in_sampled_directory(2)
+        .into_iter()
+        .enumerate()
+    {
+        write_file(FileSpec::new(dir.join(format!("sample-{i}.txt")), content));
+    }
+    run_bit_command(dir, &["add", "."]).assert().success();
+    assert_eq!(count_loose_objects(dir), 9);
+
+    run_bit_command(dir, &["config", "gc.auto", "5"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["gc", "--auto"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("Packed 9 objects into pack-"))
+        .stderr(predicates::str::contains(
+            "Auto packing the repository for optimum performance.",
+        ));
+
+    assert_eq!(count_loose_objects(dir), 0);
+    let packs = list_pack_files(dir);
+    assert_eq!(packs.len(), 2);
+    assert!(packs[0].ends_with(".idx") && packs[1].ends_with(".pack"));
+
+    // Objects are still readable, by bit and by git
+    run_bit_command(dir, &["cat-file", "-t", "HEAD"])
+        .assert()
+        .success()
+        .stdout("commit\n");
+    run_git_command(dir, &["cat-file", "-p", "HEAD:a/b/3.txt"])
+        .assert()
+        .success()
+        .stdout("three");
+    let index_path = format!(".git/objects/pack/{}", packs[0]);
+    run_git_command(dir, &["verify-pack", &index_path])
+        .assert()
+        .success();
+
+    // New objects are written loose next to the pack
+    write_file(FileSpec::new(dir.join("4.txt"), "four".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add 4.txt").assert().success();
+    run_bit_command(dir, &["log", "--oneline"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Initial commit"));
+
+    Ok(())
+}
+
+#[rstest]
+fn auto_gc_disabled_by_zero_threshold(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    run_bit_command(dir, &["config", "gc.auto", "0"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["gc", "--auto"])
+        .assert()
+        .success()
+        .stdout("");
+
+    assert_eq!(count_loose_objects(dir), 7);
+}
+
+fn count_packs(dir: &Path) -> usize {
+    list_pack_files(dir)
+        .iter()
+        .filter(|name| name.ends_with(".pack"))
+        .count()
+}
+
+#[rstest]
+fn auto_gc_packs_loose_objects_until_the_pack_limit(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    run_bit_command(dir, &["gc"]).assert().success();
+    assert_eq!(count_packs(dir), 1);
+
+    for (i, content) in blob_contents_in_sampled_directory(2)
+        .into_iter()
+        .enumerate()
+    {
+        write_file(FileSpec::new(dir.join(format!("sample-{i}.txt")), content));
+    }
+    run_bit_command(dir, &["add", "."]).assert().success();
+    run_bit_command(dir, &["config", "gc.auto", "5"])
+        .assert()
+        .success();
+
+    // Only the loose objects go into a new pack, next to the existing one
+    let loose = count_loose_objects(dir);
+    run_bit_command(dir, &["gc", "--auto"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(format!(
+            "Packed {} objects into pack-",
+            loose
+        )));
+    assert_eq!(count_loose_objects(dir), 0);
+    assert_eq!(count_packs(dir), 2);
+
+    // Past the pack limit, the packs are consolidated into one
+    run_bit_command(dir, &["config", "gc.autoPackLimit", "1"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["gc", "--auto"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("Packed "));
+    assert_eq!(count_packs(dir), 1);
+    run_git_command(dir, &["fsck"]).assert().success();
+}
+
+#[rstest]
+fn gc_leaves_kept_packs_alone(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    run_bit_command(dir, &["gc"]).assert().success();
+    let kept = list_pack_files(dir);
+    let kept_pack = dir.join(".git/objects/pack").join(&kept[1]);
+    std::fs::write(kept_pack.with_extension("keep"), "").unwrap();
+
+    write_file(FileSpec::new(dir.join("4.txt"), "four".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add 4.txt").assert().success();
+
+    // Only the new objects are packed, the kept pack is not read from
+    let loose = count_loose_objects(dir);
+    run_bit_command(dir, &["gc"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with(format!(
+            "Packed {} objects into pack-",
+            loose
+        )));
+    assert!(kept_pack.exists());
+    assert_eq!(count_packs(dir), 2);
+    run_bit_command(dir, &["cat-file", "-t", "HEAD~1"])
+        .assert()
+        .success()
+        .stdout("commit\n");
+}
+
+#[rstest]
+fn commit_succeeds_when_auto_gc_fails(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    run_bit_command(dir, &["config", "gc.auto", "many"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("4.txt"), "four".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add 4.txt")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Add 4.txt"))
+        .stderr(predicates::str::contains("warning: auto gc failed"));
+
+    run_bit_command(dir, &["log", "--format=%s", "-n", "1"])
+        .assert()
+        .success()
+        .stdout("Add 4.txt\n");
+}
+
+#[rstest]
+fn objects_stored_as_deltas_in_a_git_pack_are_readable(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let original = (0..200).map(|n| format!("line {n}\n")).collect::<String>();
+    write_file(FileSpec::new(dir.join("big.txt"), original.clone()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Add big.txt").assert().success();
+    write_file(FileSpec::new(
+        dir.join("big.txt"),
+        format!("{original}end\n"),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Extend big.txt").assert().success();
+
+    // Git stores one version of big.txt as a delta against the other
+    run_git_command(dir, &["repack", "-a", "-d", "-f"])
+        .assert()
+        .success();
+    run_git_command(dir, &["prune-packed"]).assert().success();
+    assert_eq!(count_loose_objects(dir), 0);
+
+    let output = run_git_command(dir, &["rev-parse", "HEAD~1:big.txt"]).output()?;
+    let blob = String::from_utf8(output.stdout)?;
+    run_bit_command(dir, &["cat-file", "-s", blob.trim()])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", original.len()));
+    run_bit_command(dir, &["diff", "HEAD~1", "HEAD"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(" line 199\n+end\n"));
+
+    Ok(())
+}
@@ -0,0 +1 @@
+mod auto_gc_thresholds;
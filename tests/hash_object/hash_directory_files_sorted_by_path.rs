@@ -0,0 +1,64 @@
+use crate::common::command::{repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn git_hash_object(dir: &Path, file: &str) -> String {
+    let output = run_git_command(dir, &["hash-object", file])
+        .output()
+        .unwrap();
+
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+#[rstest]
+fn hash_directory_files_sorted_by_path(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    write_file(FileSpec::new(
+        dir.join("src/main.rs"),
+        "fn main() {}\n".to_string(),
+    ));
+    write_file(FileSpec::new(
+        dir.join("src/lib/util.rs"),
+        "pub fn util() {}\n".to_string(),
+    ));
+    write_file(FileSpec::new(dir.join("src/a.txt"), "a\n".to_string()));
+    write_file(FileSpec::new(
+        dir.join("src/build.log"),
+        "noise\n".to_string(),
+    ));
+    write_file(FileSpec::new(dir.join(".gitignore"), "*.log\n".to_string()));
+
+    let expected = ["a.txt", "build.log", "lib/util.rs", "main.rs"]
+        .iter()
+        .map(|file| {
+            let oid = git_hash_object(dir, &format!("src/{}", file));
+            format!("{}\t{}\n", oid, file)
+        })
+        .collect::<String>();
+    let output = run_bit_command(dir, &["hash-object", "-w", "src"]).output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, expected);
+
+    // The blobs were written
+    let oid = git_hash_object(dir, "src/main.rs");
+    run_git_command(dir, &["cat-file", "-e", &oid])
+        .assert()
+        .success();
+
+    let output = run_bit_command(dir, &["hash-object", "--respect-ignore", "src"]).output()?;
+    assert!(output.status.success());
+    let files = String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| line.split('\t').nth(1).unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(files, ["a.txt", "lib/util.rs", "main.rs"]);
+
+    Ok(())
+}
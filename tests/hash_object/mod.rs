@@ -1 +1,2 @@
+mod hash_directory_files_sorted_by_path;
 mod write_blob_object_successfully;
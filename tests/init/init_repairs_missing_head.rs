@@ -0,0 +1,33 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+#[rstest]
+fn init_repairs_missing_head(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let head_path = init_repository_dir.path().join(".git").join("HEAD");
+    std::fs::remove_file(&head_path)?;
+
+    run_bit_command(init_repository_dir.path(), &["status"])
+        .assert()
+        .failure()
+        .stderr("Error: invalid HEAD\n");
+
+    run_bit_command(init_repository_dir.path(), &["init"])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(&head_path)?,
+        "ref: refs/heads/master"
+    );
+
+    // The existing history is reachable again
+    run_bit_command(init_repository_dir.path(), &["log", "--oneline"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Initial commit"));
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use crate::common::command::{repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+use std::path::PathBuf;
+
+/// A global config file setting `init.defaultBranch = main`
+#[fixture]
+fn global_config() -> (TempDir, PathBuf) {
+    crate::common::redirect_temp_dir();
+    let home_dir = TempDir::new().expect("Failed to create temp dir");
+    let config_path = home_dir.path().join(".gitconfig");
+
+    let config = FileSpec::new(
+        config_path.clone(),
+        "[init]\n\tdefaultBranch = main\n".to_string(),
+    );
+    write_file(config);
+
+    (home_dir, config_path)
+}
+
+#[rstest]
+fn init_uses_configured_default_branch(
+    repository_dir: TempDir,
+    global_config: (TempDir, PathBuf),
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (_home_dir, config_path) = global_config;
+
+    run_bit_command(repository_dir.path(), &["init"])
+        .env("GIT_CONFIG_GLOBAL", &config_path)
+        .assert()
+        .success();
+
+    let head = std::fs::read_to_string(repository_dir.path().join(".git").join("HEAD"))?;
+    assert_eq!(head, "ref: refs/heads/main");
+
+    Ok(())
+}
+
+#[rstest]
+fn init_initial_branch_flag_overrides_configured_default_branch(
+    repository_dir: TempDir,
+    global_config: (TempDir, PathBuf),
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (_home_dir, config_path) = global_config;
+
+    run_bit_command(repository_dir.path(), &["init", "-b", "trunk"])
+        .env("GIT_CONFIG_GLOBAL", &config_path)
+        .assert()
+        .success();
+
+    let head = std::fs::read_to_string(repository_dir.path().join(".git").join("HEAD"))?;
+    assert_eq!(head, "ref: refs/heads/trunk");
+
+    Ok(())
+}
+
+#[rstest]
+fn reinit_warns_that_initial_branch_flag_is_ignored(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_bit_command(repository_dir.path(), &["init"])
+        .assert()
+        .success();
+
+    run_bit_command(repository_dir.path(), &["init", "-b", "trunk"])
+        .assert()
+        .success()
+        .stderr("warning: re-init: ignored --initial-branch=trunk\n");
+
+    let head = std::fs::read_to_string(repository_dir.path().join(".git").join("HEAD"))?;
+    assert_eq!(head, "ref: refs/heads/master");
+
+    Ok(())
+}
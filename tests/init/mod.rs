@@ -1 +1,6 @@
+mod init_repairs_missing_head;
 mod init_repository_successfully;
+mod init_with_default_branch;
+mod reinitialize_existing_repository;
+mod reject_command_outside_repository;
+mod reject_command_with_corrupted_head;
@@ -0,0 +1,45 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn reinitialize_existing_repository(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let head_path = dir.join(".git").join("HEAD");
+
+    run_bit_command(dir, &["config", "user.name", "Jane Doe"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+
+    let output = run_bit_command(dir, &["init", "-b", "main"])
+        .assert()
+        .success();
+    let actual_output = String::from_utf8(output.get_output().stdout.clone())?;
+    assert_eq!(
+        actual_output,
+        format!(
+            "Reinitialized existing Git repository in {}\n",
+            dir.canonicalize()?.display()
+        )
+    );
+
+    run_bit_command(dir, &["config", "user.name"])
+        .assert()
+        .success()
+        .stdout("Jane Doe\n");
+    assert_eq!(
+        std::fs::read_to_string(&head_path)?.trim_end(),
+        "ref: refs/heads/feature"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use crate::common::command::{repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn reject_command_outside_repository(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir_absolute_path = repository_dir.path().canonicalize()?.display().to_string();
+
+    run_bit_command(repository_dir.path(), &["status"])
+        .assert()
+        .failure()
+        .stderr(format!(
+            "Error: not a git repository (or any parent up to {})\n",
+            dir_absolute_path
+        ));
+
+    // Nothing is created behind the user's back
+    assert!(!repository_dir.path().join(".git").exists());
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn reject_command_with_corrupted_head(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let head_path = init_repository_dir.path().join(".git").join("HEAD");
+
+    std::fs::write(&head_path, "garbage\n")?;
+    run_bit_command(init_repository_dir.path(), &["log"])
+        .assert()
+        .failure()
+        .stderr("Error: invalid HEAD\n");
+
+    std::fs::write(&head_path, "")?;
+    run_bit_command(init_repository_dir.path(), &["status"])
+        .assert()
+        .failure()
+        .stderr("Error: invalid HEAD\n");
+
+    Ok(())
+}
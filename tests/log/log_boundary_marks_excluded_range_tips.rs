@@ -0,0 +1,130 @@
+use crate::common::command::{
+    bit_commit_with_timestamp, init_repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+
+/// A linear history of four commits
+#[fixture]
+fn repository_with_history(init_repository_dir: TempDir) -> TempDir {
+    let dir = init_repository_dir.path();
+
+    for (index, timestamp) in [
+        "2024-01-01 10:00:00 +0000",
+        "2024-01-01 11:00:00 +0000",
+        "2024-01-01 12:00:00 +0000",
+        "2024-01-01 13:00:00 +0000",
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        write_file(FileSpec::new(
+            dir.join("file.txt"),
+            format!("version {}\n", index),
+        ));
+        run_bit_command(dir, &["add", "."]).assert().success();
+        bit_commit_with_timestamp(dir, &format!("Commit {}", index), timestamp)
+            .assert()
+            .success();
+    }
+
+    init_repository_dir
+}
+
+fn stdout(output: std::process::Output) -> String {
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[rstest]
+fn log_boundary_marks_excluded_range_tips(repository_with_history: TempDir) {
+    let dir = repository_with_history.path();
+
+    let bit_log = stdout(
+        run_bit_command(
+            dir,
+            &[
+                "log",
+                "--boundary",
+                "--format=oneline",
+                "--abbrev-commit",
+                "--decorate=none",
+                "master~2..master",
+            ],
+        )
+        .output()
+        .unwrap(),
+    );
+    let git_log = stdout(
+        run_git_command(
+            dir,
+            &[
+                "log",
+                "--boundary",
+                "--oneline",
+                "--no-decorate",
+                "master~2..master",
+            ],
+        )
+        .output()
+        .unwrap(),
+    );
+
+    assert_eq!(bit_log, git_log);
+    let marked = bit_log
+        .lines()
+        .map(|line| line.starts_with('-'))
+        .collect::<Vec<_>>();
+    // The two listed commits come first, the excluded tip last
+    assert_eq!(marked, vec![false, false, true]);
+    assert!(bit_log.lines().last().unwrap().ends_with("Commit 1"));
+}
+
+#[rstest]
+fn log_boundary_marks_commit_header_in_medium_format(repository_with_history: TempDir) {
+    let dir = repository_with_history.path();
+
+    let bit_log = stdout(
+        run_bit_command(
+            dir,
+            &["log", "--boundary", "--decorate=none", "master~1..master"],
+        )
+        .output()
+        .unwrap(),
+    );
+    let git_log = stdout(
+        run_git_command(
+            dir,
+            &["log", "--boundary", "--no-decorate", "master~1..master"],
+        )
+        .output()
+        .unwrap(),
+    );
+
+    // bit ends the last medium-style entry with a blank line, Git doesn't
+    assert_eq!(bit_log.trim_end(), git_log.trim_end());
+    assert!(bit_log.contains("\ncommit - "));
+}
+
+#[rstest]
+fn log_without_range_has_no_boundary(repository_with_history: TempDir) {
+    let dir = repository_with_history.path();
+
+    let bit_log = stdout(
+        run_bit_command(dir, &["log", "--boundary", "--format=oneline"])
+            .output()
+            .unwrap(),
+    );
+
+    let commit_count = stdout(
+        run_git_command(dir, &["rev-list", "--count", "HEAD"])
+            .output()
+            .unwrap(),
+    );
+    assert_eq!(
+        bit_log.lines().count(),
+        commit_count.trim().parse::<usize>().unwrap()
+    );
+    assert!(bit_log.lines().all(|line| !line.starts_with('-')));
+}
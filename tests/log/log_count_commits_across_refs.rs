@@ -0,0 +1,58 @@
+use crate::common::command::{bit_commit, repository_with_multiple_commits, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn log_count_commits_across_refs(
+    repository_with_multiple_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_multiple_commits.path();
+
+    run_bit_command(dir, &["log", "--count"])
+        .assert()
+        .success()
+        .stdout("4\n");
+
+    // A side branch sharing all four commits of master, plus one of its own
+    run_bit_command(dir, &["branch", "create", "side"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["checkout", "side"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("side.txt"), "side".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Side commit").assert().success();
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+
+    // An annotated tag on an already counted commit
+    run_bit_command(dir, &["tag", "-a", "v1.0", "-m", "Release 1.0", "HEAD~1"])
+        .envs(vec![
+            ("GIT_AUTHOR_NAME", "fake_user"),
+            ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+        ])
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["log", "--count"])
+        .assert()
+        .success()
+        .stdout("4\n");
+    run_bit_command(dir, &["log", "side", "--count"])
+        .assert()
+        .success()
+        .stdout("5\n");
+    run_bit_command(dir, &["log", "--all", "--count"])
+        .assert()
+        .success()
+        .stdout("5\n");
+    run_bit_command(dir, &["log", "--all", "--count", "^master"])
+        .assert()
+        .success()
+        .stdout("1\n");
+
+    Ok(())
+}
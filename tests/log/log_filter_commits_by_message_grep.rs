@@ -0,0 +1,116 @@
+use crate::common::command::{bit_commit_with_timestamp, repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+use std::path::Path;
+
+const COMMITS: [(&str, &str, &str); 4] = [
+    ("file1.txt", "Add parser", "2023-01-01 10:00:00 +0000"),
+    (
+        "file2.txt",
+        "Fix crash in parser",
+        "2023-01-01 11:00:00 +0000",
+    ),
+    (
+        "file1.txt",
+        "Refactor lexer\n\nThis also fixes the tokenizer",
+        "2023-01-01 12:00:00 +0000",
+    ),
+    ("file2.txt", "Update docs", "2023-01-01 13:00:00 +0000"),
+];
+
+fn commit_history(dir: &Path) {
+    run_bit_command(dir, &["init"]).assert().success();
+
+    for (index, (file, message, timestamp)) in COMMITS.iter().enumerate() {
+        write_file(FileSpec::new(dir.join(file), format!("content {}", index)));
+        run_bit_command(dir, &["add", "."]).assert().success();
+        bit_commit_with_timestamp(dir, message, timestamp)
+            .assert()
+            .success();
+    }
+}
+
+fn log_subjects(dir: &Path, args: &[&str]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut log_args = vec!["log", "--format=%s"];
+    log_args.extend_from_slice(args);
+
+    let output = run_bit_command(dir, &log_args).assert().success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+    Ok(stdout
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[rstest]
+#[case::subject_match(&["--grep", "parser"], &["Fix crash in parser", "Add parser"])]
+#[case::body_match(&["--grep", "fix"], &["Refactor lexer"])]
+#[case::regex_match(&["--grep", "^(Add|Update) "], &["Update docs", "Add parser"])]
+#[case::ignore_case(&["--grep", "fix", "-i"], &["Refactor lexer", "Fix crash in parser"])]
+#[case::invert_grep(
+    &["--grep", "parser", "--invert-grep"],
+    &["Update docs", "Refactor lexer"]
+)]
+#[case::no_match(&["--grep", "release"], &[])]
+#[case::any_of_several_patterns(
+    &["--grep", "Add", "--grep", "lexer"],
+    &["Refactor lexer", "Add parser"]
+)]
+#[case::all_match(
+    &["--grep", "parser", "--grep", "Fix", "--all-match"],
+    &["Fix crash in parser"]
+)]
+#[case::grep_and_author(
+    &["--grep", "parser", "--author", "fake_user"],
+    &["Fix crash in parser", "Add parser"]
+)]
+#[case::grep_and_other_author(&["--grep", "parser", "--author", "someone_else"], &[])]
+fn log_filter_commits_by_message_grep(
+    repository_dir: TempDir,
+    #[case] args: &[&str],
+    #[case] expected: &[&str],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    commit_history(dir);
+
+    pretty_assertions::assert_eq!(log_subjects(dir, args)?, expected);
+
+    Ok(())
+}
+
+#[rstest]
+fn log_invert_grep_returns_complement_of_grep(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    commit_history(dir);
+
+    let all = log_subjects(dir, &[])?;
+    let matching = log_subjects(dir, &["--grep", "parser"])?;
+    let non_matching = log_subjects(dir, &["--grep", "parser", "--invert-grep"])?;
+
+    assert_eq!(matching.len() + non_matching.len(), all.len());
+    for subject in &all {
+        assert_ne!(matching.contains(subject), non_matching.contains(subject));
+    }
+
+    Ok(())
+}
+
+#[rstest]
+fn log_grep_composes_with_path_filter(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    commit_history(dir);
+
+    pretty_assertions::assert_eq!(
+        log_subjects(dir, &["--grep", "parser", "--", "file1.txt"])?,
+        vec!["Add parser"]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use crate::common::command::{
+    bit_commit_with_timestamp, bit_merge_with_timestamp, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::{PredicateBooleanExt, predicate};
+use rstest::{fixture, rstest};
+
+/// Merge a `feature` branch adding `feature.txt` into `master`, which added
+/// `main.txt` in the meantime
+#[fixture]
+fn repository_with_merge(init_repository_dir: TempDir) -> TempDir {
+    let dir = init_repository_dir.path();
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(dir.join("main.txt"), "main\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit_with_timestamp(dir, "Main commit", "2024-01-01 10:00:00 +0000")
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        dir.join("feature.txt"),
+        "feature\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit_with_timestamp(dir, "Feature commit", "2024-01-01 11:00:00 +0000")
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge_with_timestamp(dir, "feature", "Merge feature", "2024-01-01 12:00:00 +0000")
+        .assert()
+        .success();
+
+    init_repository_dir
+}
+
+#[rstest]
+fn log_first_parent_patch_diffs_merge_against_first_parent(repository_with_merge: TempDir) {
+    let dir = repository_with_merge.path();
+
+    run_bit_command(dir, &["log", "--first-parent", "--patch", "--oneline"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains(
+                "Merge feature\n\
+                diff --git a/feature.txt b/feature.txt\n\
+                new file mode 100644\n",
+            )
+            .and(predicate::str::contains("+feature\n"))
+            // The feature branch is not walked
+            .and(predicate::str::contains("Feature commit").not()),
+        );
+}
+
+#[rstest]
+fn log_patch_without_first_parent_shows_no_merge_diff(repository_with_merge: TempDir) {
+    let dir = repository_with_merge.path();
+
+    run_bit_command(dir, &["log", "--patch", "--oneline"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("Merge feature\n")
+                .and(predicate::str::contains("Merge feature\ndiff --git").not())
+                .and(predicate::str::contains("Feature commit")),
+        );
+}
@@ -0,0 +1,67 @@
+use crate::common::command::{bit_commit_with_timestamp, repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn log_subjects(dir: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = run_bit_command(dir, &[&["log", "--format=%s"], args].concat())
+        .assert()
+        .success();
+
+    Ok(String::from_utf8(output.get_output().stdout.clone())?)
+}
+
+#[rstest]
+fn log_limit_commits_with_max_count_and_skip(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    // Only every other commit touches src/
+    for (i, file) in [
+        "src/1.rs", "README", "src/2.rs", "README", "src/3.rs", "README",
+    ]
+    .iter()
+    .enumerate()
+    {
+        write_file(FileSpec::new(dir.join(file), format!("{}\n", i)));
+        run_bit_command(dir, &["add", "."]).assert().success();
+        bit_commit_with_timestamp(
+            dir,
+            &format!("commit {}", i + 1),
+            &format!("2024-01-01 1{}:00:00 +0000", i),
+        )
+        .assert()
+        .success();
+    }
+
+    assert_eq!(log_subjects(dir, &["-n", "2"])?, "commit 6\ncommit 5\n");
+    assert_eq!(
+        log_subjects(dir, &["--skip", "1", "--max-count", "2"])?,
+        "commit 5\ncommit 4\n"
+    );
+    assert_eq!(log_subjects(dir, &["--skip", "6"])?, "");
+
+    // The limit counts only the commits that touched the filtered paths
+    assert_eq!(
+        log_subjects(dir, &["-n", "2", "--", "src/"])?,
+        "commit 5\ncommit 3\n"
+    );
+    assert_eq!(
+        log_subjects(dir, &["--skip", "1", "-n", "5", "--", "src/"])?,
+        "commit 3\ncommit 1\n"
+    );
+
+    let output = run_bit_command(dir, &["log", "--count", "-n", "4"])
+        .assert()
+        .success();
+    assert_eq!(
+        String::from_utf8(output.get_output().stdout.clone())?,
+        "4\n"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use crate::common::command::{
+    bit_commit_with_timestamp, bit_merge_with_timestamp, init_repository_dir, run_bit_command,
+    run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+
+/// Merge a `feature` branch into `master`, which moved on in the meantime
+#[fixture]
+fn repository_with_merge(init_repository_dir: TempDir) -> TempDir {
+    let dir = init_repository_dir.path();
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(dir.join("main.txt"), "main\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit_with_timestamp(dir, "Main commit", "2024-01-01 10:00:00 +0000")
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        dir.join("feature.txt"),
+        "feature\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit_with_timestamp(dir, "Feature commit", "2024-01-01 11:00:00 +0000")
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge_with_timestamp(dir, "feature", "Merge feature", "2024-01-01 12:00:00 +0000")
+        .assert()
+        .success();
+
+    init_repository_dir
+}
+
+fn stdout(output: std::process::Output) -> String {
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[rstest]
+fn log_parents_lists_parent_ids(repository_with_merge: TempDir) {
+    let dir = repository_with_merge.path();
+
+    let bit_log = stdout(
+        run_bit_command(
+            dir,
+            &[
+                "log",
+                "--parents",
+                "--format=oneline",
+                "--abbrev-commit",
+                "--decorate=none",
+            ],
+        )
+        .output()
+        .unwrap(),
+    );
+    let git_log = stdout(
+        run_git_command(dir, &["log", "--parents", "--oneline", "--no-decorate"])
+            .output()
+            .unwrap(),
+    );
+
+    assert_eq!(bit_log, git_log);
+    let id_counts = bit_log
+        .lines()
+        .map(|line| {
+            line.split(' ')
+                .take_while(|word| word.len() == 7 && word.chars().all(|c| c.is_ascii_hexdigit()))
+                .count()
+        })
+        .collect::<Vec<_>>();
+    // The merge lists both parents, the root commit none
+    assert_eq!(id_counts, vec![3, 2, 2, 1]);
+}
+
+#[rstest]
+fn log_parents_with_first_parent_still_lists_every_parent(repository_with_merge: TempDir) {
+    let dir = repository_with_merge.path();
+
+    let bit_log = stdout(
+        run_bit_command(
+            dir,
+            &[
+                "log",
+                "--parents",
+                "--first-parent",
+                "--format=raw",
+                "--decorate=none",
+            ],
+        )
+        .output()
+        .unwrap(),
+    );
+    let git_log = stdout(
+        run_git_command(
+            dir,
+            &[
+                "log",
+                "--parents",
+                "--first-parent",
+                "--format=raw",
+                "--no-decorate",
+            ],
+        )
+        .output()
+        .unwrap(),
+    );
+
+    // bit ends the last medium-style entry with a blank line, Git doesn't
+    assert_eq!(bit_log.trim_end(), git_log.trim_end());
+    // Only the first parent is followed, but the merge names both
+    assert_eq!(bit_log.matches("\ncommit ").count() + 1, 3);
+    assert_eq!(bit_log.lines().next().unwrap().split(' ').count(), 4);
+}
@@ -0,0 +1,85 @@
+/// History:
+/// ```
+/// A - B (v1.0) - D - M - F (master)
+///      \            /
+///       C (feature)
+/// ```
+///
+/// Only the decorated commits B, C and F are shown, plus the merge M joining
+/// the lines leading to B and C; A and D are collapsed.
+use crate::common::command::{
+    bit_commit_with_timestamp, bit_merge_with_timestamp, repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn commit_file(dir: &Path, name: &str, message: &str, timestamp: &str) {
+    write_file(FileSpec::new(dir.join(name), format!("{}\n", message)));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit_with_timestamp(dir, message, timestamp)
+        .assert()
+        .success();
+}
+
+#[rstest]
+fn log_simplify_by_decoration(repository_dir: TempDir) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    commit_file(dir, "a.txt", "A", "2024-01-01 10:00:00 +0000");
+    commit_file(dir, "b.txt", "B", "2024-01-01 11:00:00 +0000");
+    run_bit_command(dir, &["tag", "-a", "v1.0", "-m", "Release 1.0"])
+        .envs(vec![
+            ("GIT_AUTHOR_NAME", "fake_user"),
+            ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+        ])
+        .assert()
+        .success();
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+    commit_file(dir, "d.txt", "D", "2024-01-01 13:00:00 +0000");
+
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    commit_file(dir, "c.txt", "C", "2024-01-01 12:00:00 +0000");
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge_with_timestamp(dir, "feature", "M", "2024-01-01 14:00:00 +0000")
+        .assert()
+        .success();
+    commit_file(dir, "f.txt", "F", "2024-01-01 15:00:00 +0000");
+
+    let output = run_bit_command(
+        dir,
+        &[
+            "log",
+            "--simplify-by-decoration",
+            "--format=oneline",
+            "--decorate=none",
+        ],
+    )
+    .output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+    let subjects = stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .collect::<Vec<_>>();
+    assert_eq!(subjects, vec!["F", "M", "C", "B"]);
+
+    run_bit_command(dir, &["log", "--simplify-by-decoration", "--count"])
+        .assert()
+        .success()
+        .stdout("4\n");
+    run_bit_command(dir, &["log", "--count"])
+        .assert()
+        .success()
+        .stdout("6\n");
+
+    Ok(())
+}
@@ -2,6 +2,7 @@ mod show_commit_with_decoration_full;
 mod show_commit_with_decoration_none;
 mod show_commit_with_decoration_short;
 mod show_commit_with_decoration_short_oneline;
+mod show_decorations_in_git_order;
 mod show_linear_history_in_medium_format;
 mod show_log_from_abbreviated_sha;
 mod show_log_from_branch_reference;
@@ -10,13 +11,21 @@ mod show_log_from_head_commit;
 mod show_log_from_middle_of_history;
 mod show_log_from_nonexistent_branch;
 mod show_log_from_specific_commit_sha;
+mod show_log_graph_with_merge_commit;
+mod show_log_graph_with_octopus_merge;
+mod show_log_with_format_placeholders;
+mod show_log_with_line_numbers_flag;
 mod show_log_with_no_commits;
 mod show_log_with_patch;
 mod show_log_with_patch_oneline;
+mod show_log_with_patch_with_stat;
+mod show_log_with_raw_changes;
 mod show_multiple_branches_with_decoration;
 mod show_multiple_commits_in_medium_format;
 mod show_multiple_commits_in_oneline_format;
 mod show_multiple_commits_with_abbrev_commit_flag;
+mod show_multiple_commits_with_format_raw;
+mod show_signed_commit_with_format_raw;
 mod show_single_commit_in_medium_format;
 mod show_single_commit_in_oneline_format;
 mod show_single_commit_with_abbrev_commit_flag;
@@ -29,6 +38,7 @@ mod verify_medium_format_structure;
 mod log_complex_dag_with_multiple_merge_bases;
 mod log_divergent_histories_with_common_ancestor;
 mod log_interleaved_commits_from_three_branches;
+mod log_limit_commits_with_max_count_and_skip;
 mod log_multiple_branches_with_timestamp_ordering;
 mod log_with_identical_timestamps_maintains_stable_order;
 mod log_with_mixed_revision_formats;
@@ -46,6 +56,12 @@ mod log_multiple_excluded_revisions;
 // Combined range and excluded expression tests
 mod log_combined_range_and_excluded_expressions;
 
+// Commit counting tests (--count, --all)
+mod log_count_commits_across_refs;
+
+// History simplification tests (--simplify-by-decoration)
+mod log_simplify_by_decoration;
+
 // Edge case tests
 mod log_interesting_commits_reachable_from_uninteresting;
 mod log_only_excluded_revisions_defaults_to_head;
@@ -55,14 +71,18 @@ mod log_filter_by_file_with_no_matching_commits;
 mod log_filter_by_file_with_revision_range;
 mod log_filter_by_nested_file_path;
 mod log_filter_commits_by_directory;
+mod log_filter_commits_by_message_grep;
 mod log_filter_commits_by_multiple_files;
 mod log_filter_commits_by_single_file;
 
 // Merge commit traversal tests (feature: merge-aware log)
 // Tests proper traversal of ALL parents in merge commits
+mod log_boundary_marks_excluded_range_tips;
+mod log_first_parent_patch_diffs_merge_against_first_parent;
 mod log_merge_traversal_criss_cross_merge;
 mod log_merge_traversal_diamond_deduplication;
 mod log_merge_traversal_linear_history;
 mod log_merge_traversal_octopus_merge;
 mod log_merge_traversal_sequential_merges;
 mod log_merge_traversal_simple_merge;
+mod log_parents_lists_parent_ids;
@@ -0,0 +1,64 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn first_log_line(dir: &Path, decorate: &str) -> String {
+    let output = run_bit_command(dir, &["log", "--decorate", decorate])
+        .output()
+        .expect("Failed to run log");
+    assert!(output.status.success());
+
+    String::from_utf8(output.stdout)
+        .expect("Invalid UTF-8 in log output")
+        .lines()
+        .next()
+        .expect("Empty log output")
+        .to_string()
+}
+
+#[rstest]
+fn show_decorations_in_git_order(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let head = std::fs::read_to_string(dir.join(".git").join("HEAD"))?;
+    let branch = head
+        .trim_end()
+        .trim_start_matches("ref: refs/heads/")
+        .to_string();
+    let output = run_bit_command(dir, &["rev-parse", "HEAD"]).output()?;
+    let oid = String::from_utf8(output.stdout)?.trim_end().to_string();
+
+    // A local branch sorting before the current one, a remote-tracking
+    // branch and a lightweight tag, all at HEAD
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["tag", "v1.0"]).assert().success();
+    let remote_dir = dir.join(".git").join("refs").join("remotes").join("origin");
+    std::fs::create_dir_all(&remote_dir)?;
+    std::fs::write(remote_dir.join(&branch), format!("{oid}\n"))?;
+
+    assert_eq!(
+        first_log_line(dir, "short"),
+        format!("commit {oid} (HEAD -> {branch}, feature, origin/{branch}, tag: v1.0)")
+    );
+    assert_eq!(
+        first_log_line(dir, "full"),
+        format!(
+            "commit {oid} (HEAD -> refs/heads/{branch}, refs/heads/feature, \
+            refs/remotes/origin/{branch}, tag: refs/tags/v1.0)"
+        )
+    );
+
+    // A detached HEAD is listed on its own, still first
+    run_bit_command(dir, &["checkout", &oid]).assert().success();
+    assert_eq!(
+        first_log_line(dir, "short"),
+        format!("commit {oid} (HEAD, feature, {branch}, origin/{branch}, tag: v1.0)")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+/// Graph rendering of a two-branch merge
+///
+/// History:
+/// ```
+///       A
+///      / \
+///     B   C
+///      \ /
+///       M (merge)
+/// ```
+///
+/// Expected graph (oneline):
+/// ```
+/// *   M
+/// |\
+/// | * C
+/// * | B
+/// |/
+/// * A
+/// ```
+use crate::common::command::{
+    bit_commit_with_timestamp, bit_merge_with_timestamp, repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+use std::path::Path;
+
+fn create_two_branch_merge(dir: &Path) {
+    run_bit_command(dir, &["init"]).assert().success();
+
+    write_file(FileSpec::new(dir.join("base.txt"), "base\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit_with_timestamp(dir, "A", "2024-01-01 10:00:00 +0000")
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(dir.join("main.txt"), "main\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit_with_timestamp(dir, "B", "2024-01-01 11:00:00 +0000")
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(dir.join("feat.txt"), "feat\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit_with_timestamp(
+        dir,
+        "C\n\nFirst body line\nSecond body line",
+        "2024-01-01 12:00:00 +0000",
+    )
+    .assert()
+    .success();
+
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+
+    bit_merge_with_timestamp(dir, "feature", "M", "2024-01-01 13:00:00 +0000")
+        .assert()
+        .success();
+}
+
+#[rstest]
+fn show_log_graph_with_merge_commit_in_oneline_format(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    create_two_branch_merge(dir);
+
+    let output = run_bit_command(
+        dir,
+        &["log", "--graph", "--format=oneline", "--decorate=none"],
+    )
+    .assert()
+    .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+    // Drop the commit IDs to compare the graph structure
+    let sha_regex = regex::Regex::new(r"[0-9a-f]{40} ")?;
+    let graph = sha_regex.replace_all(&stdout, "");
+    let graph = graph.lines().collect::<Vec<_>>();
+
+    pretty_assertions::assert_eq!(graph, vec!["*   M", "|\\", "| * C", "* | B", "|/", "* A"]);
+
+    Ok(())
+}
+
+#[rstest]
+fn show_log_graph_with_merge_commit_in_medium_format(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    create_two_branch_merge(dir);
+
+    let output = run_bit_command(dir, &["log", "--graph", "--decorate=none"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    let lines = stdout.lines().collect::<Vec<_>>();
+
+    // The merge commit line is followed by its two incoming lanes
+    let merge_index = lines
+        .iter()
+        .position(|line| line.starts_with("*   commit "))
+        .expect("merge commit line not found");
+    assert_eq!(lines[merge_index + 1], "|\\");
+    assert!(lines[merge_index + 2].starts_with("| | Merge: "));
+    assert_eq!(
+        lines[merge_index + 2]
+            .trim_start_matches("| | Merge: ")
+            .split(' ')
+            .count(),
+        2
+    );
+
+    // Continuation lines of a multi-line message keep the rail prefix
+    for message_line in ["    C", "    First body line", "    Second body line"] {
+        assert!(
+            lines.contains(&format!("| | {}", message_line).as_str()),
+            "expected `{}` to be prefixed by the rails in:\n{}",
+            message_line,
+            stdout
+        );
+    }
+    assert!(lines.iter().any(|line| line.starts_with("| * commit ")));
+    assert!(lines.iter().any(|line| line.starts_with("* | commit ")));
+    assert!(lines.contains(&"|/"));
+
+    Ok(())
+}
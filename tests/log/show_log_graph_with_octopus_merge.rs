@@ -0,0 +1,136 @@
+/// Graph rendering of an octopus merge
+///
+/// History:
+/// ```
+///         A
+///       / | \
+///      B  C  D
+///       \ | /
+///         M (octopus merge of B, C, D)
+/// ```
+///
+/// Expected graph (oneline):
+/// ```
+/// *-.   M
+/// |\ \
+/// | | * D
+/// | * | C
+/// | |/
+/// * | B
+/// |/
+/// * A
+/// ```
+use crate::common::command::{
+    bit_commit_with_timestamp, repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+use std::path::Path;
+
+fn rev_parse(dir: &Path, revision: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let output = run_git_command(dir, &["rev-parse", revision]).output()?;
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+#[rstest]
+fn show_log_graph_with_octopus_merge(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    write_file(FileSpec::new(dir.join("base.txt"), "base\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit_with_timestamp(dir, "A", "2024-01-01 10:00:00 +0000")
+        .assert()
+        .success();
+
+    for branch in ["branch-1", "branch-2"] {
+        run_bit_command(dir, &["branch", "create", branch])
+            .assert()
+            .success();
+    }
+
+    for (branch, file, message, timestamp) in [
+        ("master", "b.txt", "B", "2024-01-01 11:00:00 +0000"),
+        ("branch-1", "c.txt", "C", "2024-01-01 12:00:00 +0000"),
+        ("branch-2", "d.txt", "D", "2024-01-01 13:00:00 +0000"),
+    ] {
+        run_bit_command(dir, &["checkout", branch])
+            .assert()
+            .success();
+        write_file(FileSpec::new(dir.join(file), format!("{}\n", message)));
+        run_bit_command(dir, &["add", "."]).assert().success();
+        bit_commit_with_timestamp(dir, message, timestamp)
+            .assert()
+            .success();
+    }
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+
+    // bit cannot merge several branches at once, so the octopus merge is
+    // written with Git's plumbing
+    let tree = rev_parse(dir, "master^{tree}")?;
+    let (first, second, third) = (
+        rev_parse(dir, "master")?,
+        rev_parse(dir, "branch-1")?,
+        rev_parse(dir, "branch-2")?,
+    );
+    let output = run_git_command(
+        dir,
+        &[
+            "commit-tree",
+            &tree,
+            "-p",
+            &first,
+            "-p",
+            &second,
+            "-p",
+            &third,
+        ],
+    )
+    // Without a final newline, like the messages bit writes
+    .write_stdin("M")
+    .envs([
+        ("GIT_AUTHOR_NAME", "fake_user"),
+        ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+        ("GIT_AUTHOR_DATE", "2024-01-01 14:00:00 +0000"),
+        ("GIT_COMMITTER_NAME", "fake_user"),
+        ("GIT_COMMITTER_EMAIL", "fake_email@email.com"),
+        ("GIT_COMMITTER_DATE", "2024-01-01 14:00:00 +0000"),
+    ])
+    .output()?;
+    let merge = String::from_utf8(output.stdout)?.trim().to_string();
+    run_git_command(dir, &["update-ref", "refs/heads/master", &merge])
+        .assert()
+        .success();
+
+    let output = run_bit_command(dir, &["log", "--graph", "--oneline", "--decorate=short"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+    // Drop the abbreviated commit IDs to compare the graph structure
+    let sha_regex = regex::Regex::new(r"[0-9a-f]{7,40} ")?;
+    let graph = sha_regex.replace_all(&stdout, "");
+    let graph = graph.lines().collect::<Vec<_>>();
+
+    pretty_assertions::assert_eq!(
+        graph,
+        vec![
+            "*-.   (HEAD -> master) M",
+            "|\\ \\",
+            "| | * (branch-2) D",
+            "| * | (branch-1) C",
+            "| |/",
+            "* | B",
+            "|/",
+            "* A"
+        ]
+    );
+
+    Ok(())
+}
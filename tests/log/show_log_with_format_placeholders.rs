@@ -0,0 +1,47 @@
+use crate::common::command::{repository_with_multiple_commits, run_bit_command};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn show_log_with_format_placeholders(
+    repository_with_multiple_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_multiple_commits.path();
+
+    let output = run_bit_command(dir, &["log", "--format=%h %H", "--abbrev=12"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines.len(),
+        4,
+        "Expected one line per commit, got:\n{}",
+        stdout
+    );
+    for line in lines {
+        let (short, full) = line
+            .split_once(' ')
+            .ok_or_else(|| format!("Expected '<short> <full>', got '{}'", line))?;
+        assert!(
+            short.len() >= 12,
+            "%h should be 12+ digits, got '{}'",
+            short
+        );
+        assert_eq!(full.len(), 40);
+        assert!(full.starts_with(short));
+    }
+
+    // `format:` separates commits instead of terminating each one
+    let output = run_bit_command(dir, &["log", "--pretty=format:%s"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    assert!(!stdout.ends_with('\n'));
+    assert_eq!(stdout.lines().count(), 4);
+    assert!(stdout.contains("First commit"));
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+/// Without a terminal there is no pager, so `--line-numbers` must leave the output untouched
+#[rstest]
+fn show_log_with_line_numbers_flag(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    let plain = run_bit_command(repository_dir.path(), &["log", "--oneline"])
+        .assert()
+        .success();
+    let plain = String::from_utf8(plain.get_output().stdout.clone())?;
+
+    let numbered = run_bit_command(
+        repository_dir.path(),
+        &["log", "--oneline", "--line-numbers"],
+    )
+    .assert()
+    .success();
+    let numbered = String::from_utf8(numbered.get_output().stdout.clone())?;
+
+    assert_eq!(numbered, plain);
+
+    // Only diff and log take the flag
+    run_bit_command(repository_dir.path(), &["diff", "--line-numbers"])
+        .assert()
+        .success();
+    run_bit_command(repository_dir.path(), &["--line-numbers", "show"])
+        .assert()
+        .failure();
+
+    // The config keys are ignored too when not paging
+    for key in ["core.pagerLineNumbers", "core.pagerFollowOutput"] {
+        run_bit_command(repository_dir.path(), &["config", key, "true"])
+            .assert()
+            .success();
+    }
+    let configured = run_bit_command(repository_dir.path(), &["log", "--oneline"])
+        .assert()
+        .success();
+    let configured = String::from_utf8(configured.get_output().stdout.clone())?;
+
+    assert_eq!(configured, plain);
+
+    Ok(())
+}
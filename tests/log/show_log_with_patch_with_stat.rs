@@ -0,0 +1,111 @@
+use crate::common::command::{bit_commit, repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::{fixture, rstest};
+
+/// Two commits: the first adds `file1.txt`, the second changes one of its
+/// lines and adds `file2.txt`
+#[fixture]
+fn repository_with_two_commits(repository_dir: TempDir) -> TempDir {
+    let dir = repository_dir.path();
+
+    run_bit_command(dir, &["init"]).assert().success();
+    write_file(FileSpec::new(
+        dir.join("file1.txt"),
+        "line 1\nline 2\nline 3\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "First commit").assert().success();
+
+    write_file(FileSpec::new(
+        dir.join("file1.txt"),
+        "line 1\nline 2 modified\nline 3\n".to_string(),
+    ));
+    write_file(FileSpec::new(dir.join("file2.txt"), "new\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Second commit").assert().success();
+
+    repository_dir
+}
+
+#[rstest]
+fn show_log_with_patch_with_stat(
+    repository_with_two_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let output = run_bit_command(
+        repository_with_two_commits.path(),
+        &["log", "--patch-with-stat", "--decorate=none"],
+    )
+    .assert()
+    .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+    let second_stat = "    Second commit\n\
+        ---\n \
+        file1.txt | 2 +-\n \
+        file2.txt | 1 +\n \
+        2 files changed, 2 insertions(+), 1 deletion(-)\n\
+        \n\
+        diff --git a/file1.txt b/file1.txt\n";
+    assert!(
+        stdout.contains(second_stat),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    let first_stat = "    First commit\n\
+        ---\n \
+        file1.txt | 3 +++\n \
+        1 file changed, 3 insertions(+)\n\
+        \n\
+        diff --git a/file1.txt b/file1.txt\n";
+    assert!(
+        stdout.contains(first_stat),
+        "Unexpected output:\n{}",
+        stdout
+    );
+
+    // Each commit's hunks follow its stat block
+    let second_hunk = stdout.find("+line 2 modified").unwrap();
+    let first_commit = stdout.find("First commit").unwrap();
+    assert!(stdout.find(second_stat).unwrap() < second_hunk);
+    assert!(second_hunk < first_commit);
+
+    Ok(())
+}
+
+#[rstest]
+fn show_log_no_patch_overrides_patch_flags(
+    repository_with_two_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for flags in [
+        &["log", "--patch", "-s"][..],
+        &["log", "--patch-with-stat", "--no-patch"][..],
+        &["log", "-s", "--raw", "--patch"][..],
+    ] {
+        let output = run_bit_command(repository_with_two_commits.path(), flags)
+            .assert()
+            .success();
+        let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+        assert!(stdout.contains("Second commit"));
+        assert!(stdout.contains("First commit"));
+        assert!(
+            !stdout.contains("diff --git"),
+            "Patch shown for {:?}",
+            flags
+        );
+        assert!(
+            !stdout.contains("file changed"),
+            "Stat shown for {:?}",
+            flags
+        );
+        assert!(
+            !stdout.contains(":100644"),
+            "Raw diff shown for {:?}",
+            flags
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::common::command::{bit_commit, get_head_commit_sha, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+/// Commit `files` and return the new HEAD
+fn commit_files(
+    dir: &Path,
+    files: &[(&str, &str)],
+    message: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    for (name, content) in files {
+        write_file(FileSpec::new(dir.join(name), content.to_string()));
+    }
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, message).assert().success();
+
+    get_head_commit_sha(dir)
+}
+
+/// Split `log --oneline --raw` output into (first line, raw lines) per commit
+fn commit_blocks(stdout: &str) -> Vec<(String, Vec<String>)> {
+    let mut blocks: Vec<(String, Vec<String>)> = Vec::new();
+    for line in stdout.lines() {
+        match blocks.last_mut() {
+            Some((_, raw_lines)) if line.starts_with(':') => raw_lines.push(line.to_string()),
+            _ => blocks.push((line.to_string(), Vec::new())),
+        }
+    }
+
+    blocks
+}
+
+#[rstest]
+fn show_log_with_raw_changes(
+    #[from(crate::common::command::repository_dir)] repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    let first = commit_files(dir, &[("a.txt", "alpha\n")], "Add a")?;
+    let second = commit_files(
+        dir,
+        &[("a.txt", "alpha 2\n"), ("b.txt", "beta\n")],
+        "Change a, add b",
+    )?;
+    let third = commit_files(dir, &[("b.txt", "beta 2\n")], "Change b")?;
+
+    let output = run_bit_command(dir, &["log", "--oneline", "--raw"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    let blocks = commit_blocks(&stdout);
+
+    assert_eq!(blocks.len(), 3);
+    assert!(blocks[0].0.starts_with(&third[..7]));
+    assert_eq!(blocks[0].1, vec![":100644 100644 65b2df8 b08c9b9 M\tb.txt"]);
+    assert!(blocks[1].0.starts_with(&second[..7]));
+    assert_eq!(
+        blocks[1].1,
+        vec![
+            ":100644 100644 4a58007 e4b5094 M\ta.txt",
+            ":000000 100644 0000000 65b2df8 A\tb.txt",
+        ]
+    );
+    assert!(blocks[2].0.starts_with(&first[..7]));
+    assert_eq!(blocks[2].1, vec![":000000 100644 0000000 4a58007 A\ta.txt"]);
+
+    Ok(())
+}
+
+#[rstest]
+fn show_log_with_raw_changes_and_diff_filter(
+    #[from(crate::common::command::repository_dir)] repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    let first = commit_files(dir, &[("a.txt", "alpha\n")], "Add a")?;
+    let second = commit_files(
+        dir,
+        &[("a.txt", "alpha 2\n"), ("b.txt", "beta\n")],
+        "Change a, add b",
+    )?;
+    commit_files(dir, &[("b.txt", "beta 2\n")], "Change b")?;
+
+    // Only commits adding files are listed, with only their additions
+    let output = run_bit_command(dir, &["log", "--oneline", "--raw", "--diff-filter=A"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    let blocks = commit_blocks(&stdout);
+
+    assert_eq!(blocks.len(), 2);
+    assert!(blocks[0].0.starts_with(&second[..7]));
+    assert_eq!(blocks[0].1, vec![":000000 100644 0000000 65b2df8 A\tb.txt"]);
+    assert!(blocks[1].0.starts_with(&first[..7]));
+
+    // Path filtering restricts the raw lines to the given files
+    let output = run_bit_command(dir, &["log", "--oneline", "--raw", "--", "a.txt"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    let blocks = commit_blocks(&stdout);
+
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].1, vec![":100644 100644 4a58007 e4b5094 M\ta.txt"]);
+
+    Ok(())
+}
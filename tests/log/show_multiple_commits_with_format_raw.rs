@@ -0,0 +1,70 @@
+use crate::common::command::{
+    bit_commit_with_timestamp, get_head_commit_sha, init_repository_dir, run_bit_command,
+    run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+/// Expected `--pretty=raw` block for a commit, built from the stored object
+fn raw_commit_block(dir: &Path, sha: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let output = run_git_command(dir, &["cat-file", "commit", sha])
+        .assert()
+        .success();
+    let content = String::from_utf8(output.get_output().stdout.clone())?;
+    let (headers, message) = content
+        .split_once("\n\n")
+        .ok_or("commit object without a message")?;
+    let message = message
+        .lines()
+        .map(|line| format!("    {}\n", line))
+        .collect::<String>();
+
+    Ok(format!("commit {}\n{}\n\n{}", sha, headers, message))
+}
+
+#[rstest]
+fn show_multiple_commits_with_format_raw(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+    let first_sha = get_head_commit_sha(repository_dir.path())?;
+
+    write_file(FileSpec::new(
+        repository_dir.path().join("1.txt"),
+        "one modified".to_string(),
+    ));
+    run_bit_command(repository_dir.path(), &["add", "."])
+        .assert()
+        .success();
+    // A non-UTC timezone must be printed back unchanged
+    bit_commit_with_timestamp(
+        repository_dir.path(),
+        "Second commit\n\nWith a body",
+        "2023-01-02 10:00:00 +0530",
+    )
+    .assert()
+    .success();
+    let second_sha = get_head_commit_sha(repository_dir.path())?;
+
+    let expected = format!(
+        "{}\n{}\n",
+        raw_commit_block(repository_dir.path(), &second_sha)?,
+        raw_commit_block(repository_dir.path(), &first_sha)?
+    );
+    assert!(expected.contains(" +0530\n"));
+    assert!(expected.contains(&format!("parent {}\n", first_sha)));
+
+    for format in ["--format=raw", "--pretty=raw"] {
+        let output = run_bit_command(repository_dir.path(), &["log", format, "--decorate=none"])
+            .assert()
+            .success();
+        let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+        assert_eq!(stdout, expected);
+    }
+
+    Ok(())
+}
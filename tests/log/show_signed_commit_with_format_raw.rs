@@ -0,0 +1,56 @@
+use crate::common::command::{
+    get_head_commit_sha, init_repository_dir, run_bit_command, run_git_command,
+};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+const SIGNATURE: &str = "gpgsig -----BEGIN PGP SIGNATURE-----\n \
+    \n \
+    iQEzBAABCAAdFiEEexample0signature0lines0only0AAoJEFake\n \
+    =AbCd\n \
+    -----END PGP SIGNATURE-----\n";
+
+#[rstest]
+fn show_signed_commit_with_format_raw(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let head = get_head_commit_sha(dir)?;
+    let output = run_git_command(dir, &["cat-file", "commit", &head])
+        .assert()
+        .success();
+    let content = String::from_utf8(output.get_output().stdout.clone())?;
+    let (headers, message) = content
+        .split_once("\n\n")
+        .ok_or("commit object without a message")?;
+
+    // Re-create the commit with a signature header after the committer
+    let signed_content = format!("{}\n{}\n{}", headers, SIGNATURE, message);
+    let output = run_git_command(dir, &["hash-object", "-t", "commit", "-w", "--stdin"])
+        .write_stdin(signed_content)
+        .output()?;
+    assert!(output.status.success());
+    let signed = String::from_utf8(output.stdout)?.trim().to_string();
+
+    let expected = format!(
+        "commit {}\n{}\n{}\n{}\n",
+        signed,
+        headers,
+        SIGNATURE,
+        message
+            .lines()
+            .map(|line| format!("    {}\n", line))
+            .collect::<String>()
+    );
+    let output = run_bit_command(dir, &["log", "--format=raw", "--decorate=none", &signed])
+        .assert()
+        .success();
+
+    assert_eq!(
+        String::from_utf8(output.get_output().stdout.clone())?,
+        expected
+    );
+
+    Ok(())
+}
@@ -8,7 +8,7 @@ fn list_all_blobs_from_head_commit_tree(
 ) -> Result<(), Box<dyn std::error::Error>> {
     let repository_dir = init_repository_dir;
 
-    let expected_output = "100644 43dd47ea691c90a5fa7827892c70241913351963 1.txt\n100644 64c5e5885a4b06010b3a0c20edb7900dd0311025 a/2.txt\n100644 1d19714ffbc272ba0da6eb419d66123c20527174 a/b/3.txt\n".to_string();
+    let expected_output = "100644 blob 43dd47ea691c90a5fa7827892c70241913351963\t1.txt\n100644 blob 64c5e5885a4b06010b3a0c20edb7900dd0311025\ta/2.txt\n100644 blob 1d19714ffbc272ba0da6eb419d66123c20527174\ta/b/3.txt\n".to_string();
     let actual_output = crate::common::command::run_bit_command(
         repository_dir.path(),
         &["ls-tree", "-r", "88484bd9e7919fa9b7dfeb008fb8f6c85743d171"],
@@ -0,0 +1,40 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn list_entries_with_object_sizes(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    let subtree_output = run_git_command(repository_dir.path(), &["rev-parse", "HEAD:a"])
+        .assert()
+        .success();
+    let subtree_oid = String::from_utf8(subtree_output.get_output().stdout.clone())?;
+
+    let expected_output = format!(
+        "100644 blob 43dd47ea691c90a5fa7827892c70241913351963       3\t1.txt\n040000 tree {}       -\ta\n",
+        subtree_oid.trim()
+    );
+    let actual_output = run_bit_command(repository_dir.path(), &["ls-tree", "-l", "HEAD"])
+        .assert()
+        .success();
+    let actual_output = String::from_utf8(actual_output.get_output().stdout.clone())?;
+
+    pretty_assertions::assert_eq!(actual_output, expected_output);
+
+    // Same layout and sizes as git's own reading of the objects
+    let expected_output = run_git_command(repository_dir.path(), &["ls-tree", "-r", "-l", "HEAD"])
+        .assert()
+        .success();
+    let expected_output = String::from_utf8(expected_output.get_output().stdout.clone())?;
+    let actual_output = run_bit_command(repository_dir.path(), &["ls-tree", "-r", "-l", "HEAD"])
+        .assert()
+        .success();
+    let actual_output = String::from_utf8(actual_output.get_output().stdout.clone())?;
+
+    pretty_assertions::assert_eq!(actual_output, expected_output);
+
+    Ok(())
+}
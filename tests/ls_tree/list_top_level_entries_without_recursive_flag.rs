@@ -0,0 +1,36 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn list_top_level_entries_without_recursive_flag(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    let subtree_output = run_git_command(repository_dir.path(), &["rev-parse", "HEAD:a"])
+        .assert()
+        .success();
+    let subtree_oid = String::from_utf8(subtree_output.get_output().stdout.clone())?;
+
+    let expected_output = format!(
+        "100644 blob 43dd47ea691c90a5fa7827892c70241913351963\t1.txt\n040000 tree {}\ta\n",
+        subtree_oid.trim()
+    );
+    let actual_output = run_bit_command(repository_dir.path(), &["ls-tree", "HEAD"])
+        .assert()
+        .success();
+    let actual_output = String::from_utf8(actual_output.get_output().stdout.clone())?;
+
+    pretty_assertions::assert_eq!(actual_output, expected_output);
+
+    // Same layout as git: `<mode> SP <type> SP <oid> TAB <path>`
+    let git_output = run_git_command(repository_dir.path(), &["ls-tree", "HEAD"])
+        .assert()
+        .success();
+    let git_output = String::from_utf8(git_output.get_output().stdout.clone())?;
+
+    pretty_assertions::assert_eq!(actual_output, git_output);
+
+    Ok(())
+}
@@ -1,2 +1,4 @@
 mod list_all_blobs_from_head_commit;
 mod list_all_blobs_from_head_commit_tree;
+mod list_entries_with_object_sizes;
+mod list_top_level_entries_without_recursive_flag;
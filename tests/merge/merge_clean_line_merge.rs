@@ -0,0 +1,101 @@
+use crate::common::command::{
+    bit_commit, bit_merge, repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::fs;
+use std::path::Path;
+
+fn commit_file(dir: &Path, content: &str, message: &str) {
+    write_file(FileSpec::new(dir.join("file.txt"), content.into()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, message).assert().success();
+}
+
+/// Set up a file edited on both branches, leaving master checked out
+fn diverged_edits(dir: &Path, ours: &str, theirs: &str) {
+    run_bit_command(dir, &["init"]).assert().success();
+    commit_file(dir, "one\ntwo\nthree\nfour\nfive\n", "Commit A");
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+    commit_file(dir, ours, "Commit B - ours");
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    commit_file(dir, theirs, "Commit C - theirs");
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+}
+
+/// Edits to different lines of the same file merge without conflict
+///
+/// History:
+///   A: file.txt = "one\ntwo\nthree\nfour\nfive\n"
+///   B (master):  "one" → "ONE"
+///   C (feature): "five" → "FIVE"
+///
+/// Expected: the merge commits both edits, with no conflict left in the index
+#[rstest]
+fn merge_edits_to_different_lines(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    diverged_edits(
+        dir,
+        "ONE\ntwo\nthree\nfour\nfive\n",
+        "one\ntwo\nthree\nfour\nFIVE\n",
+    );
+
+    bit_merge(dir, "feature", "clean merge")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Auto-merging file.txt"));
+
+    assert_eq!(
+        fs::read_to_string(dir.join("file.txt"))?,
+        "ONE\ntwo\nthree\nfour\nFIVE\n"
+    );
+    let ls_files = run_git_command(dir, &["ls-files", "--stage"]).output()?;
+    assert!(String::from_utf8(ls_files.stdout)?.contains(" 0\tfile.txt"));
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+    let parents = run_git_command(dir, &["rev-list", "--parents", "-n", "1", "HEAD"]).output()?;
+    assert_eq!(
+        String::from_utf8(parents.stdout)?
+            .split_whitespace()
+            .count(),
+        3
+    );
+
+    Ok(())
+}
+
+/// Only the hunk changed on both sides gets conflict markers
+#[rstest]
+fn merge_marks_only_conflicting_hunks(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    diverged_edits(
+        dir,
+        "ONE\ntwo\nthree\nfour\nours\n",
+        "one\ntwo\nthree\nfour\ntheirs\n",
+    );
+
+    bit_merge(dir, "feature", "conflict merge")
+        .assert()
+        .failure();
+
+    assert_eq!(
+        fs::read_to_string(dir.join("file.txt"))?,
+        "ONE\ntwo\nthree\nfour\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\n"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::common::command::{bit_commit, bit_merge, repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+use std::fs;
+
+/// Test that `merge.conflictStyle=diff3` writes the base version between the markers
+///
+/// History:
+///   A: file.txt = "shared\n"
+///   B (master):  "ours change\n"
+///   C (feature): "theirs change\n"
+///
+/// Expected: ours, then the base after `|||||||`, then theirs
+#[rstest]
+fn merge_content_conflict_diff3_style(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir;
+
+    run_bit_command(dir.path(), &["init"]).assert().success();
+    run_bit_command(dir.path(), &["config", "merge.conflictStyle", "diff3"])
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(
+        dir.path().join("file.txt"),
+        "shared\n".into(),
+    ));
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(dir.path(), "Commit A").assert().success();
+
+    run_bit_command(dir.path(), &["branch", "create", "feature"])
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(
+        dir.path().join("file.txt"),
+        "ours change\n".into(),
+    ));
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(dir.path(), "Commit B - ours").assert().success();
+
+    run_bit_command(dir.path(), &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        dir.path().join("file.txt"),
+        "theirs change\n".into(),
+    ));
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(dir.path(), "Commit C - theirs")
+        .assert()
+        .success();
+
+    run_bit_command(dir.path(), &["checkout", "master"])
+        .assert()
+        .success();
+
+    bit_merge(dir.path(), "feature", "conflict merge")
+        .assert()
+        .failure();
+
+    let content = fs::read_to_string(dir.path().join("file.txt"))?;
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 7, "Unexpected conflict layout:\n{}", content);
+    assert_eq!(lines[0], "<<<<<<< HEAD");
+    assert_eq!(lines[1], "ours change");
+    assert!(
+        lines[2].starts_with("||||||| "),
+        "Expected base marker, got {}",
+        lines[2]
+    );
+    assert_eq!(lines[3], "shared");
+    assert_eq!(lines[4], "=======");
+    assert_eq!(lines[5], "theirs change");
+    assert_eq!(lines[6], ">>>>>>> feature");
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::common::command::{
+    bit_commit, bit_merge, get_branch_commit_sha, repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+/// Test that `--no-ff` records a merge commit for a descendant branch
+///
+/// History:
+///   A   (master, feature created here)
+///   |
+///   B   (feature)
+///
+/// Merging `feature` into master with `--no-ff` creates a merge commit M
+/// with parents A and B instead of moving master to B.
+#[rstest]
+fn merge_no_fast_forward(repository_dir: TempDir) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+
+    run_bit_command(dir, &["init"]).assert().success();
+    write_file(FileSpec::new(dir.join("anchor.txt"), "anchor\n".into()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Commit A").assert().success();
+    let master_before = get_branch_commit_sha(dir, "master")?;
+
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("feature.txt"), "feature\n".into()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Commit B").assert().success();
+    let feature_sha = get_branch_commit_sha(dir, "feature")?;
+
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge(dir, "feature", "Merge feature")
+        .arg("--no-ff")
+        .assert()
+        .success();
+
+    let master_sha = get_branch_commit_sha(dir, "master")?;
+    assert_ne!(master_sha, feature_sha);
+
+    let cat_output = run_git_command(dir, &["cat-file", "commit", &master_sha])
+        .assert()
+        .success();
+    let cat_stdout = String::from_utf8(cat_output.get_output().stdout.clone())?;
+    let parents = cat_stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("parent "))
+        .collect::<Vec<_>>();
+    assert_eq!(parents, vec![master_before.as_str(), feature_sha.as_str()]);
+
+    assert_eq!(
+        std::fs::read_to_string(dir.join("feature.txt"))?,
+        "feature\n"
+    );
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
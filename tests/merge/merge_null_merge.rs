@@ -5,7 +5,7 @@ use crate::common::file::{FileSpec, write_file};
 use assert_fs::TempDir;
 use rstest::rstest;
 
-/// Test that merging an ancestor branch reports "Already up to date"
+/// Test that merging an ancestor branch reports "Already up to date." and changes nothing
 ///
 /// History:
 ///   A → B → C   (master)
@@ -50,23 +50,36 @@ fn merge_reports_already_up_to_date(
         .success();
     bit_commit(dir.path(), "Commit C").assert().success();
 
+    // An untracked file must survive the merge untouched
+    write_file(FileSpec::new(dir.path().join("notes.txt"), "wip\n".into()));
+
     let head_before = get_head_commit_sha(dir.path())?;
+    let index_before = std::fs::read(dir.path().join(".git").join("index"))?;
 
-    let output = bit_merge(dir.path(), "old", "Null merge")
+    bit_merge(dir.path(), "old", "Null merge")
         .assert()
-        .success();
-    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+        .success()
+        .stdout("Already up to date.\n");
 
-    assert!(
-        stdout.contains("Already up to date"),
-        "Expected 'Already up to date' message, got:\n{}",
-        stdout
-    );
     assert_eq!(
         get_head_commit_sha(dir.path())?,
         head_before,
         "HEAD should not advance for a null merge"
     );
+    assert_eq!(
+        std::fs::read(dir.path().join(".git").join("index"))?,
+        index_before,
+        "The index should not change for a null merge"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("file.txt"))?,
+        "base\nB\nC\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("notes.txt"))?,
+        "wip\n"
+    );
+    assert!(!dir.path().join(".git").join("MERGE_HEAD").exists());
 
     Ok(())
 }
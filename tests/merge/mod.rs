@@ -1,4 +1,5 @@
 mod merge_add_add_conflict;
+mod merge_clean_line_merge;
 mod merge_complex_branching;
 mod merge_conflict_report_add_add;
 mod merge_conflict_report_content;
@@ -7,6 +8,7 @@ mod merge_conflict_report_directory_file;
 mod merge_conflict_report_file_directory;
 mod merge_conflict_report_modify_delete;
 mod merge_content_conflict;
+mod merge_content_conflict_diff3_style;
 mod merge_criss_cross;
 mod merge_diamond_pattern;
 mod merge_dirty_workspace_protection;
@@ -14,6 +16,7 @@ mod merge_fast_forward;
 mod merge_file_directory_conflict;
 mod merge_linear_history;
 mod merge_modify_delete_conflict;
+mod merge_no_fast_forward;
 mod merge_null_merge;
 mod merge_octopus_scenario;
 mod merge_resume_commit;
@@ -0,0 +1 @@
+mod move_tracked_file_like_git;
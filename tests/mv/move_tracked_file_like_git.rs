@@ -0,0 +1,103 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+use std::path::Path;
+
+/// Run `bit mv`, then undo it with renames and replay it with `git mv` from
+/// the same index and files, and compare the indexes
+///
+/// Files are moved back rather than rewritten so that they keep their stat
+/// data. Every rename still bumps the ctime, so it is left out.
+fn assert_mv_matches_git(dir: &Path, args: &[&str], source: &str, destination: &str) {
+    let index_before = std::fs::read(dir.join(".git/index")).unwrap();
+    let overwritten = dir.join(".git/overwritten");
+    let destination_existed = dir.join(destination).exists();
+    if destination_existed {
+        std::fs::hard_link(dir.join(destination), &overwritten).unwrap();
+    }
+
+    let bit_args = [&["mv"], args].concat();
+    run_bit_command(dir, &bit_args).assert().success();
+    std::fs::rename(dir.join(".git/index"), dir.join(".git/bit-index")).unwrap();
+
+    std::fs::write(dir.join(".git/index"), &index_before).unwrap();
+    std::fs::rename(dir.join(destination), dir.join(source)).unwrap();
+    if destination_existed {
+        std::fs::rename(&overwritten, dir.join(destination)).unwrap();
+    }
+    let git_args = [&["mv"], args].concat();
+    run_git_command(dir, &git_args).assert().success();
+
+    pretty_assertions::assert_eq!(
+        index_without_ctimes(dir, ".git/bit-index"),
+        index_without_ctimes(dir, ".git/index")
+    );
+}
+
+/// Entries of an index file with their stat data, except for ctimes
+fn index_without_ctimes(dir: &Path, index_file: &str) -> String {
+    let output = run_git_command(dir, &["ls-files", "--stage", "--debug"])
+        .env("GIT_INDEX_FILE", dir.join(index_file))
+        .output()
+        .unwrap();
+
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("ctime:"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[rstest]
+fn move_file_to_new_name(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    assert_mv_matches_git(dir, &["1.txt", "renamed.txt"], "1.txt", "renamed.txt");
+
+    assert!(!dir.join("1.txt").exists());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("renamed.txt")).unwrap(),
+        "one"
+    );
+}
+
+#[rstest]
+fn move_file_into_existing_directory(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    assert_mv_matches_git(dir, &["1.txt", "a/b"], "1.txt", "a/b/1.txt");
+
+    assert!(dir.join("a/b/1.txt").exists());
+}
+
+#[rstest]
+fn move_onto_tracked_file_requires_force(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    let index_before = std::fs::read(dir.join(".git/index")).unwrap();
+
+    run_bit_command(dir, &["mv", "1.txt", "a/2.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "destination exists, source=1.txt, destination=a/2.txt",
+        ));
+    assert_eq!(std::fs::read(dir.join(".git/index")).unwrap(), index_before);
+    assert!(dir.join("1.txt").exists());
+
+    assert_mv_matches_git(dir, &["-f", "1.txt", "a/2.txt"], "1.txt", "a/2.txt");
+    assert_eq!(std::fs::read_to_string(dir.join("a/2.txt")).unwrap(), "one");
+}
+
+#[rstest]
+fn move_untracked_file_fails(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    std::fs::write(dir.join("untracked.txt"), "new").unwrap();
+
+    run_bit_command(dir, &["mv", "untracked.txt", "moved.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not under version control"));
+    assert!(dir.join("untracked.txt").exists());
+}
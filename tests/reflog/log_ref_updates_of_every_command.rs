@@ -0,0 +1,55 @@
+use crate::common::command::{
+    bit_commit, bit_merge, get_head_commit_sha, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn commit_branch_checkout_and_merge_are_logged(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let initial = get_head_commit_sha(dir)?;
+
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("1.txt"), "feature".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Feature commit").assert().success();
+    let feature = get_head_commit_sha(dir)?;
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge(dir, "feature", "Merge feature")
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["reflog", "show"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "{feature} HEAD@{{0}}: merge feature: Fast-forward\n\
+             {initial} HEAD@{{1}}: checkout: moving from feature to master\n\
+             {feature} HEAD@{{2}}: commit: Feature commit\n\
+             {initial} HEAD@{{3}}: checkout: moving from master to feature\n\
+             {initial} HEAD@{{4}}: commit (initial): Initial commit\n",
+            feature = &feature[..7],
+            initial = &initial[..7],
+        ));
+    run_bit_command(dir, &["reflog", "show", "feature"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "{} feature@{{0}}: commit: Feature commit\n\
+             {} feature@{{1}}: branch: Created from HEAD\n",
+            &feature[..7],
+            &initial[..7],
+        ));
+
+    Ok(())
+}
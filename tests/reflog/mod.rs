@@ -0,0 +1,2 @@
+mod log_ref_updates_of_every_command;
+mod show_branch_reflog_with_date_format;
@@ -0,0 +1,145 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::{PredicateBooleanExt, predicate};
+use rstest::{fixture, rstest};
+use std::path::Path;
+
+/// Commits of a repository whose `master` reflog holds three commits followed
+/// by three resets
+struct History {
+    dir: TempDir,
+    first: String,
+    second: String,
+    third: String,
+}
+
+fn reset_at(dir: &Path, target: &str, date: &str) {
+    run_bit_command(dir, &["reset", "--hard", target])
+        .envs(vec![
+            ("GIT_AUTHOR_NAME", "fake_user"),
+            ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+            ("GIT_AUTHOR_DATE", date),
+        ])
+        .assert()
+        .success();
+}
+
+#[fixture]
+fn history(init_repository_dir: TempDir) -> History {
+    let dir = init_repository_dir.path();
+    let first = get_head_commit_sha(dir).unwrap();
+    write_file(FileSpec::new(dir.join("1.txt"), "second".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Second commit").assert().success();
+    let second = get_head_commit_sha(dir).unwrap();
+    write_file(FileSpec::new(dir.join("1.txt"), "third".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Third commit").assert().success();
+    let third = get_head_commit_sha(dir).unwrap();
+
+    reset_at(dir, "HEAD^", "2023-01-01 12:00:00 +0000");
+    reset_at(dir, "ORIG_HEAD", "2023-01-02 12:00:00 +0200");
+    reset_at(dir, "HEAD~2", "2023-01-03 12:00:00 +0000");
+
+    History {
+        dir: init_repository_dir,
+        first,
+        second,
+        third,
+    }
+}
+
+#[rstest]
+fn reflog_show_lists_branch_updates_newest_first(history: History) {
+    let expected = format!(
+        "{} master@{{0}}: reset: moving to HEAD~2\n\
+         {} master@{{1}}: reset: moving to ORIG_HEAD\n\
+         {} master@{{2}}: reset: moving to HEAD^\n\
+         {} master@{{3}}: commit: Third commit\n\
+         {} master@{{4}}: commit: Second commit\n\
+         {} master@{{5}}: commit (initial): Initial commit\n",
+        &history.first[..7],
+        &history.third[..7],
+        &history.second[..7],
+        &history.third[..7],
+        &history.second[..7],
+        &history.first[..7],
+    );
+
+    run_bit_command(history.dir.path(), &["reflog", "show", "master"])
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[rstest]
+#[case::iso(
+    "iso",
+    ["2023-01-03 12:00:00 +0000", "2023-01-02 12:00:00 +0200", "2023-01-01 12:00:00 +0000"]
+)]
+#[case::unix("unix", ["1672747200", "1672653600", "1672574400"])]
+fn reflog_show_renders_dates_in_requested_format(
+    history: History,
+    #[case] format: &str,
+    #[case] dates: [&str; 3],
+) {
+    // The resets, which the three commits precede
+    let expected = format!(
+        "{} master@{{{}}}: reset: moving to HEAD~2\n\
+         {} master@{{{}}}: reset: moving to ORIG_HEAD\n\
+         {} master@{{{}}}: reset: moving to HEAD^\n",
+        &history.first[..7],
+        dates[0],
+        &history.third[..7],
+        dates[1],
+        &history.second[..7],
+        dates[2],
+    );
+
+    run_bit_command(
+        history.dir.path(),
+        &["reflog", "show", "master", &format!("--date={}", format)],
+    )
+    .assert()
+    .success()
+    .stdout(predicate::str::starts_with(expected));
+}
+
+#[rstest]
+fn reflog_show_relative_dates(history: History) {
+    run_bit_command(
+        history.dir.path(),
+        &["reflog", "show", "master", "--date=relative"],
+    )
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("master@{").and(predicate::str::contains(" ago}: ")));
+}
+
+#[rstest]
+fn branch_reflog_selector_resolves_to_prior_values(history: History) {
+    let dir = history.dir.path();
+
+    for (selector, expected) in [
+        ("master@{0}", &history.first),
+        ("master@{1}", &history.third),
+        ("master@{2}", &history.second),
+        ("master@{5}", &history.first),
+        ("@{1}", &history.third),
+    ] {
+        run_bit_command(dir, &["rev-parse", selector])
+            .assert()
+            .success()
+            .stdout(format!("{}\n", expected));
+    }
+
+    run_bit_command(dir, &["rev-parse", "@{6}"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "log for 'master' only has 6 entries",
+        ));
+}
@@ -0,0 +1,3 @@
+mod record_orig_head_and_reflog;
+mod reset_keep;
+mod reset_soft_mixed_and_hard;
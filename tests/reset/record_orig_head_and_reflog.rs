@@ -0,0 +1,88 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, get_parent_commit_id, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+
+/// The initial commit followed by a second one changing `1.txt`
+#[fixture]
+fn repository_with_two_commits(init_repository_dir: TempDir) -> TempDir {
+    let dir = init_repository_dir.path();
+    write_file(FileSpec::new(dir.join("1.txt"), "changed".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Second commit").assert().success();
+
+    init_repository_dir
+}
+
+#[rstest]
+#[case::soft("--soft")]
+#[case::mixed("--mixed")]
+#[case::hard("--hard")]
+fn reset_writes_pre_reset_head_to_orig_head(
+    repository_with_two_commits: TempDir,
+    #[case] mode: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_two_commits.path();
+    let head = get_head_commit_sha(dir)?;
+
+    run_bit_command(dir, &["reset", mode, "HEAD^"])
+        .assert()
+        .success();
+
+    let orig_head = std::fs::read_to_string(dir.join(".git").join("ORIG_HEAD"))?;
+    assert_eq!(orig_head.trim(), head);
+
+    Ok(())
+}
+
+#[rstest]
+fn reset_hard_to_orig_head_undoes_a_reset(
+    repository_with_two_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_two_commits.path();
+    let head = get_head_commit_sha(dir)?;
+
+    run_bit_command(dir, &["reset", "--hard", "HEAD^"])
+        .assert()
+        .success();
+    assert_eq!(std::fs::read_to_string(dir.join("1.txt"))?, "one");
+
+    run_bit_command(dir, &["reset", "--hard", "ORIG_HEAD"])
+        .assert()
+        .success();
+
+    assert_eq!(get_head_commit_sha(dir)?, head);
+    assert_eq!(std::fs::read_to_string(dir.join("1.txt"))?, "changed");
+
+    Ok(())
+}
+
+#[rstest]
+fn reset_appends_reflog_entries_for_head_and_branch(
+    repository_with_two_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_two_commits.path();
+    let head = get_head_commit_sha(dir)?;
+    let parent = get_parent_commit_id(dir, &head)?;
+
+    run_bit_command(dir, &["reset", "--soft", "HEAD^"])
+        .assert()
+        .success();
+
+    let logs = dir.join(".git").join("logs");
+    for log in [
+        logs.join("HEAD"),
+        logs.join("refs").join("heads").join("master"),
+    ] {
+        let reflog = std::fs::read_to_string(log)?;
+        let entry = reflog.lines().last().expect("reflog has no entries");
+
+        assert!(entry.starts_with(&format!("{} {} ", head, parent)));
+        assert!(entry.ends_with("\treset: moving to HEAD^"));
+    }
+
+    Ok(())
+}
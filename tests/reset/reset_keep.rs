@@ -0,0 +1,127 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, get_parent_commit_id, repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+
+/// Two commits where only `changed.txt` differs and `stable.txt` is untouched
+#[fixture]
+fn repository_with_two_commits(repository_dir: TempDir) -> TempDir {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    write_file(FileSpec::new(dir.join("changed.txt"), "first".to_string()));
+    write_file(FileSpec::new(dir.join("stable.txt"), "stable".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "First commit").assert().success();
+
+    write_file(FileSpec::new(dir.join("changed.txt"), "second".to_string()));
+    write_file(FileSpec::new(
+        dir.join("nested").join("added.txt"),
+        "added".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Second commit").assert().success();
+
+    repository_dir
+}
+
+#[rstest]
+fn reset_keep_preserves_local_changes_to_unchanged_files(
+    repository_with_two_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_two_commits.path();
+    let head = get_head_commit_sha(dir)?;
+    let parent = get_parent_commit_id(dir, &head)?;
+
+    write_file(FileSpec::new(
+        dir.join("stable.txt"),
+        "local edit".to_string(),
+    ));
+
+    run_bit_command(dir, &["reset", "--keep", "HEAD^"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!(
+            "HEAD is now at {} First commit",
+            &parent[..7]
+        )));
+
+    assert_eq!(get_head_commit_sha(dir)?, parent);
+    assert_eq!(std::fs::read_to_string(dir.join("changed.txt"))?, "first");
+    assert!(!dir.join("nested").exists());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("stable.txt"))?,
+        "local edit"
+    );
+
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(" M stable.txt\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn reset_keep_aborts_when_changed_file_has_local_modifications(
+    repository_with_two_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_two_commits.path();
+    let head = get_head_commit_sha(dir)?;
+
+    write_file(FileSpec::new(
+        dir.join("changed.txt"),
+        "local edit".to_string(),
+    ));
+
+    run_bit_command(dir, &["reset", "--keep", "HEAD^"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("changed.txt"));
+
+    // Nothing moved: HEAD, the local edit and the newer files are intact
+    assert_eq!(get_head_commit_sha(dir)?, head);
+    assert_eq!(
+        std::fs::read_to_string(dir.join("changed.txt"))?,
+        "local edit"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join("nested").join("added.txt"))?,
+        "added"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn reset_hard_discards_local_changes(
+    repository_with_two_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_two_commits.path();
+    let head = get_head_commit_sha(dir)?;
+    let parent = get_parent_commit_id(dir, &head)?;
+
+    write_file(FileSpec::new(
+        dir.join("changed.txt"),
+        "local edit".to_string(),
+    ));
+
+    run_bit_command(dir, &["reset", "--hard", "HEAD^"])
+        .assert()
+        .success();
+
+    assert_eq!(get_head_commit_sha(dir)?, parent);
+    assert_eq!(std::fs::read_to_string(dir.join("changed.txt"))?, "first");
+    assert!(!dir.join("nested").exists());
+
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
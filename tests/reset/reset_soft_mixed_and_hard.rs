@@ -0,0 +1,247 @@
+use crate::common::command::{
+    bit_commit, bit_merge, get_head_commit_sha, repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+use std::path::Path;
+
+/// Three commits, each rewriting `file.txt`; the last one adds `added.txt`
+#[fixture]
+fn repository_with_three_commits(repository_dir: TempDir) -> TempDir {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    for (version, message) in ["first", "second", "third"]
+        .iter()
+        .zip(["One", "Two", "Three"])
+    {
+        write_file(FileSpec::new(dir.join("file.txt"), version.to_string()));
+        if *version == "third" {
+            write_file(FileSpec::new(dir.join("added.txt"), "added".to_string()));
+        }
+        run_bit_command(dir, &["add", "."]).assert().success();
+        bit_commit(dir, message).assert().success();
+    }
+
+    repository_dir
+}
+
+fn git_stdout(dir: &Path, args: &[&str]) -> String {
+    let output = run_git_command(dir, args).assert().success();
+    String::from_utf8(output.get_output().stdout.clone()).unwrap()
+}
+
+#[rstest]
+fn soft_reset_only_moves_the_branch(
+    repository_with_three_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_three_commits.path();
+    let target = git_stdout(dir, &["rev-parse", "HEAD~2"]);
+    let index_before = std::fs::read(dir.join(".git/index"))?;
+
+    run_bit_command(dir, &["reset", "--soft", "HEAD~2"])
+        .assert()
+        .success()
+        .stdout("");
+
+    assert_eq!(get_head_commit_sha(dir)?, target.trim());
+    assert_eq!(std::fs::read(dir.join(".git/index"))?, index_before);
+    assert_eq!(std::fs::read_to_string(dir.join("file.txt"))?, "third");
+    assert_eq!(
+        git_stdout(dir, &["diff", "--cached", "--name-status"]),
+        "A\tadded.txt\nM\tfile.txt\n"
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn mixed_reset_rebuilds_the_index_and_keeps_files(
+    repository_with_three_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_three_commits.path();
+
+    run_bit_command(dir, &["reset", "HEAD~1"])
+        .assert()
+        .success();
+
+    assert_eq!(git_stdout(dir, &["diff", "--cached", "--name-only"]), "");
+    assert_eq!(std::fs::read_to_string(dir.join("file.txt"))?, "third");
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(" M file.txt\n?? added.txt\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn mixed_reset_on_detached_head_rebuilds_the_index(
+    repository_with_three_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_three_commits.path();
+    let head = get_head_commit_sha(dir)?;
+    run_bit_command(dir, &["checkout", &head])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("file.txt"), "staged".to_string()));
+    run_bit_command(dir, &["add", "file.txt"])
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["reset", "--mixed"])
+        .assert()
+        .success();
+
+    assert_eq!(std::fs::read_to_string(dir.join(".git/HEAD"))?.trim(), head);
+    assert_eq!(git_stdout(dir, &["diff", "--cached", "--name-only"]), "");
+    assert_eq!(git_stdout(dir, &["diff", "--name-only"]), "file.txt\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn hard_reset_discards_local_changes(
+    repository_with_three_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_three_commits.path();
+    let target = git_stdout(dir, &["rev-parse", "HEAD~1"]);
+    write_file(FileSpec::new(dir.join("file.txt"), "local".to_string()));
+
+    run_bit_command(dir, &["reset", "--hard", "HEAD~1"])
+        .assert()
+        .success()
+        .stdout(format!("HEAD is now at {} Two\n", &target[..7]));
+
+    assert_eq!(std::fs::read_to_string(dir.join("file.txt"))?, "second");
+    assert!(!dir.join("added.txt").exists());
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
+
+#[rstest]
+fn hard_reset_only_rewrites_changed_files(
+    repository_with_three_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_three_commits.path();
+    write_file(FileSpec::new(dir.join("file.txt"), "local".to_string()));
+    // Only the timestamp of added.txt changes: its content still matches
+    let old_mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    std::fs::File::options()
+        .write(true)
+        .open(dir.join("added.txt"))?
+        .set_modified(old_mtime)?;
+
+    run_bit_command(dir, &["reset", "--hard"])
+        .assert()
+        .success();
+
+    assert_eq!(std::fs::read_to_string(dir.join("file.txt"))?, "third");
+    assert_eq!(
+        std::fs::metadata(dir.join("added.txt"))?.modified()?,
+        old_mtime
+    );
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
+
+#[rstest]
+fn hard_reset_refuses_to_overwrite_untracked_files(
+    repository_with_three_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_three_commits.path();
+    let head = get_head_commit_sha(dir)?;
+    run_bit_command(dir, &["reset", "--hard", "HEAD~1"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("added.txt"), "local".to_string()));
+
+    run_bit_command(dir, &["reset", "--hard", &head])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The following untracked working tree files would be overwritten by checkout:\n\
+             \tadded.txt",
+        ));
+    assert_eq!(std::fs::read_to_string(dir.join("added.txt"))?, "local");
+
+    Ok(())
+}
+
+#[rstest]
+fn hard_reset_discards_a_conflicted_merge(
+    repository_with_three_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_three_commits.path();
+    run_bit_command(dir, &["branch", "create", "feature", "HEAD~1"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("file.txt"), "feature".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Feature").assert().success();
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge(dir, "feature", "Merge feature")
+        .assert()
+        .failure();
+
+    run_bit_command(dir, &["reset", "--hard", "HEAD~1"])
+        .assert()
+        .success();
+
+    assert_eq!(std::fs::read_to_string(dir.join("file.txt"))?, "second");
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
+
+#[rstest]
+fn soft_reset_is_refused_during_a_merge(
+    repository_with_three_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_three_commits.path();
+    run_bit_command(dir, &["branch", "create", "feature", "HEAD~1"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(dir.join("file.txt"), "feature".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Feature").assert().success();
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge(dir, "feature", "Merge feature")
+        .assert()
+        .failure();
+    let head = get_head_commit_sha(dir)?;
+
+    run_bit_command(dir, &["reset", "--soft", "HEAD~1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "cannot do a soft reset in the middle of a merge",
+        ));
+    assert_eq!(get_head_commit_sha(dir)?, head);
+
+    Ok(())
+}
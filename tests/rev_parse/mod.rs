@@ -0,0 +1,2 @@
+mod peel_revisions_of_tagged_commit;
+mod rev_parse_from_subdirectory;
@@ -0,0 +1,55 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use assert_fs::TempDir;
+use rstest::rstest;
+use std::path::Path;
+
+fn git_rev_parse(dir: &Path, spec: &str) -> String {
+    let output = run_git_command(dir, &["rev-parse", spec])
+        .output()
+        .expect("Failed to run git rev-parse");
+    assert!(output.status.success(), "git rev-parse {spec} failed");
+
+    String::from_utf8(output.stdout).expect("Invalid UTF-8 in git output")
+}
+
+#[rstest]
+#[case("v1.0")]
+#[case("v1.0^{commit}")]
+#[case("v1.0^{tree}")]
+#[case("v1.0^{}")]
+#[case("v1.0^{tag}")]
+#[case("HEAD^{tree}")]
+#[case("v1.0^{commit}~0")]
+fn peel_revisions_of_tagged_commit(init_repository_dir: TempDir, #[case] spec: &str) {
+    let dir = init_repository_dir.path();
+    run_bit_command(dir, &["tag", "-a", "v1.0", "-m", "Release 1.0"])
+        .envs(vec![
+            ("GIT_AUTHOR_NAME", "fake_user"),
+            ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+            ("GIT_AUTHOR_DATE", "2023-01-01 12:00:00 +0000"),
+        ])
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["rev-parse", spec])
+        .assert()
+        .success()
+        .stdout(git_rev_parse(dir, spec));
+}
+
+#[rstest]
+fn peel_blob_revision(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    let blob_oid = git_rev_parse(dir, "HEAD:1.txt");
+    let blob_oid = blob_oid.trim_end();
+
+    run_bit_command(dir, &["rev-parse", &format!("{blob_oid}^{{blob}}")])
+        .assert()
+        .success()
+        .stdout(format!("{blob_oid}\n"));
+
+    run_bit_command(dir, &["rev-parse", "HEAD^{blob}"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("cannot be peeled to a blob"));
+}
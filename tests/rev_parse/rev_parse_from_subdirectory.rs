@@ -0,0 +1,62 @@
+use crate::common::command::{get_head_commit_sha, init_repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn rev_parse_reports_git_dir_and_toplevel_from_subdirectory(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = init_repository_dir.path().canonicalize()?;
+    let subdirectory = root.join("a").join("b");
+
+    run_bit_command(&subdirectory, &["rev-parse", "--git-dir"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", root.join(".git").display()));
+
+    run_bit_command(&subdirectory, &["rev-parse", "--show-toplevel"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", root.display()));
+
+    Ok(())
+}
+
+#[rstest]
+fn rev_parse_reports_whether_inside_work_tree(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = init_repository_dir.path();
+
+    run_bit_command(&root.join("a"), &["rev-parse", "--is-inside-work-tree"])
+        .assert()
+        .success()
+        .stdout("true\n");
+
+    run_bit_command(
+        &root.join(".git").join("objects"),
+        &["rev-parse", "--is-inside-work-tree"],
+    )
+    .assert()
+    .success()
+    .stdout("false\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn rev_parse_resolves_revisions_from_subdirectory(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let head_sha = get_head_commit_sha(init_repository_dir.path())?;
+
+    run_bit_command(
+        &init_repository_dir.path().join("a"),
+        &["rev-parse", "HEAD"],
+    )
+    .assert()
+    .success()
+    .stdout(format!("{}\n", head_sha));
+
+    Ok(())
+}
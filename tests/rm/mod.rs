@@ -0,0 +1,3 @@
+mod remove_directory_recursively;
+mod remove_files_matching_git_index;
+mod removing_files_with_staged_changes_requires_force;
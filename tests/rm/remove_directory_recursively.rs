@@ -0,0 +1,74 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, write_file};
+use crate::{assert_index_eq, common};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+#[rstest]
+fn removing_a_directory_without_recursive_flag_fails(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let index_before = std::fs::read(dir.join(".git/index"))?;
+
+    run_bit_command(dir, &["rm", "a"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "not removing 'a' recursively without -r",
+        ));
+
+    // Nothing is removed from the index or the workspace
+    assert_eq!(std::fs::read(dir.join(".git/index"))?, index_before);
+    assert!(dir.join("a").join("2.txt").exists());
+    assert!(dir.join("a").join("b").join("3.txt").exists());
+
+    run_bit_command(dir, &["rm", "missing.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "pathspec 'missing.txt' did not match any files",
+        ));
+
+    Ok(())
+}
+
+#[rstest]
+fn removing_a_directory_recursively_prunes_nested_entries(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let index_before = std::fs::read(dir.join(".git/index"))?;
+
+    run_bit_command(dir, &["rm", "-r", "a"])
+        .assert()
+        .success()
+        .stdout("rm 'a/2.txt'\nrm 'a/b/3.txt'\n");
+
+    assert!(dir.join("1.txt").exists());
+    assert!(!dir.join("a").exists());
+    let tracked = run_git_command(dir, &["ls-files"]).output()?;
+    assert_eq!(String::from_utf8(tracked.stdout)?, "1.txt\n");
+
+    let bit_index_content = std::fs::read(dir.join(".git/index"))?;
+
+    // Replay the removal with git from the same index and files
+    std::fs::write(dir.join(".git/index"), &index_before)?;
+    write_file(FileSpec::new(
+        dir.join("a").join("2.txt"),
+        "two".to_string(),
+    ));
+    write_file(FileSpec::new(
+        dir.join("a").join("b").join("3.txt"),
+        "three".to_string(),
+    ));
+    run_git_command(dir, &["rm", "-r", "-q", "a"])
+        .assert()
+        .success();
+
+    let git_index_content = std::fs::read(dir.join(".git/index"))?;
+    assert_index_eq!(&bit_index_content, &git_index_content);
+
+    Ok(())
+}
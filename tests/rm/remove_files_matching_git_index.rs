@@ -0,0 +1,47 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, write_file};
+use crate::{assert_index_eq, common};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+#[case::from_index_and_workspace(&["1.txt"], false)]
+#[case::from_index_only(&["--cached", "1.txt"], true)]
+#[case::nested_file(&["a/b/3.txt"], false)]
+#[case::forced_with_staged_changes(&["-f", "1.txt"], false)]
+fn removing_files_writes_the_same_index_as_git(
+    init_repository_dir: TempDir,
+    #[case] args: &[&str],
+    #[case] keeps_file: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    if args.contains(&"-f") {
+        write_file(FileSpec::new(dir.join("1.txt"), "staged".to_string()));
+        run_bit_command(dir, &["add", "1.txt"]).assert().success();
+    }
+    let index_before = std::fs::read(dir.join(".git/index"))?;
+    let path = args.last().unwrap();
+    let content = std::fs::read(dir.join(path))?;
+
+    let bit_args = [&["rm"], args].concat();
+    run_bit_command(dir, &bit_args).assert().success();
+
+    assert_eq!(dir.join(path).exists(), keeps_file);
+    if !keeps_file && path.contains('/') {
+        // Like git, directories left empty are pruned
+        assert!(!dir.join(path).parent().unwrap().exists());
+    }
+    let bit_index_content = std::fs::read(dir.join(".git/index"))?;
+
+    // Replay the removal with git from the same index and files
+    std::fs::write(dir.join(".git/index"), &index_before)?;
+    std::fs::create_dir_all(dir.join(path).parent().unwrap())?;
+    std::fs::write(dir.join(path), &content)?;
+    let git_args = [&["rm", "-q"], args].concat();
+    run_git_command(dir, &git_args).assert().success();
+
+    let git_index_content = std::fs::read(dir.join(".git/index"))?;
+    assert_index_eq!(&bit_index_content, &git_index_content);
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+#[rstest]
+fn removing_files_with_staged_changes_requires_force(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    write_file(FileSpec::new(
+        dir.join("a").join("2.txt"),
+        "deux".to_string(),
+    ));
+    run_bit_command(dir, &["add", "a/2.txt"]).assert().success();
+
+    run_bit_command(dir, &["rm", "-r", "a"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "the following file has changes staged in the index:\n    a/2.txt\n",
+        ));
+    assert!(dir.join("a").join("b").join("3.txt").exists());
+
+    // Changing the file again leaves content found only in the index
+    write_file(FileSpec::new(
+        dir.join("a").join("2.txt"),
+        "zwei".to_string(),
+    ));
+    run_bit_command(dir, &["rm", "-r", "--cached", "a"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "the following file has staged content different from both the file and the HEAD:\n    \
+            a/2.txt\n",
+        ));
+
+    run_bit_command(dir, &["rm", "-r", "-f", "a"])
+        .assert()
+        .success();
+
+    assert!(!dir.join("a").exists());
+    let tracked = run_git_command(dir, &["ls-files"]).output()?;
+    assert_eq!(String::from_utf8(tracked.stdout)?, "1.txt\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn removing_cached_keeps_files_with_local_modifications(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    write_file(FileSpec::new(dir.join("1.txt"), "uno".to_string()));
+
+    run_bit_command(dir, &["rm", "1.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "the following file has local modifications:\n    1.txt\n",
+        ));
+
+    run_bit_command(dir, &["rm", "--cached", "1.txt"])
+        .assert()
+        .success()
+        .stdout("rm '1.txt'\n");
+
+    assert!(dir.join("1.txt").exists());
+    let tracked = run_git_command(dir, &["ls-files"]).output()?;
+    assert_eq!(String::from_utf8(tracked.stdout)?, "a/2.txt\na/b/3.txt\n");
+
+    Ok(())
+}
@@ -0,0 +1,3 @@
+mod show_annotated_tag_before_tagged_commit;
+mod show_merge_commit_with_combined_diff;
+mod show_regular_commit_with_patch;
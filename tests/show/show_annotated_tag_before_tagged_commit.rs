@@ -0,0 +1,40 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn show_annotated_tag_before_tagged_commit(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir;
+
+    write_file(FileSpec::new(dir.path().join("1.txt"), "uno\n".into()));
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(dir.path(), "Translate 1.txt").assert().success();
+    run_bit_command(dir.path(), &["tag", "-m", "Release 1.0", "v1.0"])
+        .envs(vec![
+            ("GIT_AUTHOR_NAME", "fake_user"),
+            ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+        ])
+        .assert()
+        .success();
+
+    let head_sha = get_head_commit_sha(dir.path())?;
+
+    let output = run_bit_command(dir.path(), &["show", "v1.0"])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert!(stdout.starts_with("tag v1.0\nTagger: fake_user <fake_email@email.com>\nDate:   "));
+    assert!(stdout.contains(&format!("\n\nRelease 1.0\n\ncommit {}\n", head_sha)));
+    assert!(stdout.contains("    Translate 1.txt\n\ndiff --git a/1.txt b/1.txt\n"));
+    assert!(stdout.contains("@@ -1,1 +1,1 @@\n-one\n+uno\n"));
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use crate::common::command::{bit_commit, bit_merge, repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+/// A merge commit that resolved a conflict is shown with a combined diff
+/// containing only the resolved region; changes taken verbatim from one
+/// side of the merge are left out.
+#[rstest]
+fn show_merge_commit_with_combined_diff(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir;
+    run_bit_command(dir.path(), &["init"]).assert().success();
+
+    write_file(FileSpec::new(
+        dir.path().join("file.txt"),
+        "base\nshared\nmore\n".into(),
+    ));
+    write_file(FileSpec::new(
+        dir.path().join("other.txt"),
+        "untouched\n".into(),
+    ));
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(dir.path(), "A").assert().success();
+
+    run_bit_command(dir.path(), &["branch", "create", "feature"])
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(
+        dir.path().join("file.txt"),
+        "base\nours change\nmore\n".into(),
+    ));
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(dir.path(), "B - ours").assert().success();
+
+    run_bit_command(dir.path(), &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        dir.path().join("file.txt"),
+        "base\ntheirs change\nmore\n".into(),
+    ));
+    write_file(FileSpec::new(
+        dir.path().join("other.txt"),
+        "changed on feature\n".into(),
+    ));
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(dir.path(), "C - theirs").assert().success();
+
+    run_bit_command(dir.path(), &["checkout", "master"])
+        .assert()
+        .success();
+
+    bit_merge(dir.path(), "feature", "Merge feature into master")
+        .assert()
+        .failure();
+
+    // Resolve the conflict with content matching neither side
+    write_file(FileSpec::new(
+        dir.path().join("file.txt"),
+        "base\nresolved\nmore\n".into(),
+    ));
+    run_bit_command(dir.path(), &["add", "file.txt"])
+        .assert()
+        .success();
+    bit_commit(dir.path(), "Merge feature into master")
+        .assert()
+        .success();
+
+    let output = run_bit_command(dir.path(), &["show"]).assert().success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert!(stdout.contains("Merge: "));
+    assert!(stdout.contains("    Merge feature into master"));
+    assert!(stdout.contains("diff --cc file.txt\n"));
+    assert!(stdout.contains(
+        "--- a/file.txt\n\
+        +++ b/file.txt\n\
+        @@@ -1,3 -1,3 +1,3 @@@\n  \
+        base\n\
+        - ours change\n \
+        -theirs change\n\
+        ++resolved\n  \
+        more\n"
+    ));
+
+    // other.txt was taken as-is from feature, so it has no interesting hunks
+    assert!(!stdout.contains("other.txt"));
+    assert!(!stdout.contains("diff --git"));
+
+    Ok(())
+}
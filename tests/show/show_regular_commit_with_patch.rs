@@ -0,0 +1,31 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+#[rstest]
+fn show_regular_commit_with_patch(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir;
+
+    write_file(FileSpec::new(dir.path().join("1.txt"), "uno\n".into()));
+    run_bit_command(dir.path(), &["add", "."])
+        .assert()
+        .success();
+    bit_commit(dir.path(), "Translate 1.txt").assert().success();
+
+    let head_sha = get_head_commit_sha(dir.path())?;
+
+    let output = run_bit_command(dir.path(), &["show"]).assert().success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert!(stdout.starts_with(&format!("commit {}\n", head_sha)));
+    assert!(!stdout.contains("Merge: "));
+    assert!(stdout.contains("    Translate 1.txt\n\ndiff --git a/1.txt b/1.txt\n"));
+    assert!(stdout.contains("@@ -1,1 +1,1 @@\n-one\n+uno\n"));
+
+    Ok(())
+}
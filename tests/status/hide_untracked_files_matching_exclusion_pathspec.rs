@@ -0,0 +1,35 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn hide_untracked_files_matching_exclusion_pathspec(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    write_file(FileSpec::new(
+        repository_dir.path().join("deps.lock"),
+        "v1\n".to_string(),
+    ));
+    write_file(FileSpec::new(
+        repository_dir.path().join("notes.txt"),
+        "todo\n".to_string(),
+    ));
+
+    let expected_output = "?? notes.txt\n".to_string();
+    let actual_output = run_bit_command(
+        repository_dir.path(),
+        &["status", "--porcelain", "--", ":(exclude)*.lock"],
+    )
+    .assert()
+    .success();
+    let stdout = actual_output.get_output().stdout.clone();
+    let actual_output = String::from_utf8(stdout)?;
+
+    assert_eq!(actual_output, expected_output);
+
+    Ok(())
+}
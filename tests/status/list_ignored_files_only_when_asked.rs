@@ -0,0 +1,99 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn list_ignored_files_only_when_asked(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    for (path, content) in [
+        (".gitignore", "*.log\nbuild/\n"),
+        ("a/.gitignore", "!keep.log\n"),
+        ("a/keep.log", "keep\n"),
+        ("debug.log", "debug\n"),
+        ("build/out.txt", "out\n"),
+        ("notes.txt", "notes\n"),
+    ] {
+        write_file(FileSpec::new(
+            repository_dir.path().join(path),
+            content.to_string(),
+        ));
+    }
+
+    let untracked = "?? .gitignore\n?? a/.gitignore\n?? a/keep.log\n?? notes.txt\n";
+
+    let output = run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success();
+    let actual_output = String::from_utf8(output.get_output().stdout.clone())?;
+    assert_eq!(actual_output, untracked);
+
+    let output = run_bit_command(
+        repository_dir.path(),
+        &["status", "--porcelain", "--ignored"],
+    )
+    .assert()
+    .success();
+    let actual_output = String::from_utf8(output.get_output().stdout.clone())?;
+    assert_eq!(
+        actual_output,
+        format!("{}!! build/\n!! debug.log\n", untracked)
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn check_tracked_files_inside_ignored_directories(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repository_dir = init_repository_dir;
+
+    write_file(FileSpec::new(
+        repository_dir.path().join("build").join("out.txt"),
+        "out\n".to_string(),
+    ));
+    run_bit_command(repository_dir.path(), &["add", "build"])
+        .assert()
+        .success();
+
+    for (path, content) in [
+        (".git/info/exclude", "build/\n"),
+        ("build/out.txt", "changed\n"),
+        ("build/new.txt", "new\n"),
+    ] {
+        write_file(FileSpec::new(
+            repository_dir.path().join(path),
+            content.to_string(),
+        ));
+    }
+
+    let output = run_bit_command(
+        repository_dir.path(),
+        &["status", "--porcelain", "--ignored"],
+    )
+    .assert()
+    .success();
+    let actual_output = String::from_utf8(output.get_output().stdout.clone())?;
+    assert_eq!(actual_output, "AM build/out.txt\n!! build/new.txt\n");
+
+    // Adding everything updates the tracked file and leaves the ignored one out
+    run_bit_command(repository_dir.path(), &["add", "."])
+        .assert()
+        .success();
+
+    let output = run_bit_command(
+        repository_dir.path(),
+        &["status", "--porcelain", "--ignored"],
+    )
+    .assert()
+    .success();
+    let actual_output = String::from_utf8(output.get_output().stdout.clone())?;
+    assert_eq!(actual_output, "A  build/out.txt\n!! build/new.txt\n");
+
+    Ok(())
+}
@@ -1,16 +1,20 @@
 mod do_not_list_empty_untracked_directories;
+mod hide_untracked_files_matching_exclusion_pathspec;
 mod list_files_as_untracked_if_they_are_not_in_the_index;
+mod list_ignored_files_only_when_asked;
 mod list_untracked_directories_not_their_contents;
 mod list_untracked_directories_that_indirectly_contain_files;
 mod list_untracked_files_in_name_order;
 mod list_untracked_files_inside_tracked_directories;
 mod print_nothing_if_a_file_is_touched;
 mod print_nothing_when_no_files_are_changed;
+mod quote_paths_with_special_characters;
 mod report_a_file_added_to_a_tracked_directory;
 mod report_a_file_added_to_an_untracked_directory;
 mod report_all_deleted_files_inside_directories_from_last_commit;
 mod report_both_added_conflict_long;
 mod report_both_modified_conflict_long;
+mod report_case_only_renames_as_unchanged_with_ignorecase;
 mod report_conflict_alongside_clean_changes;
 mod report_conflict_porcelain_both_modified;
 mod report_conflict_porcelain_deleted_by_us;
@@ -19,8 +23,13 @@ mod report_deleted_by_them_conflict_long;
 mod report_deleted_files;
 mod report_deleted_files_from_last_commit;
 mod report_files_in_deleted_directories;
+mod report_files_modified_in_the_same_second_as_added;
 mod report_files_with_changed_modes;
 mod report_files_with_modified_contents;
 mod report_modified_contents_from_last_commit;
 mod report_modified_files_with_unchanged_size;
 mod report_modified_mods_from_last_commit;
+mod report_porcelain_v2_entries;
+mod report_staged_renames_per_toggle;
+mod report_unmerged_paths_summary_and_hints;
+mod show_paths_relative_to_subdirectory;
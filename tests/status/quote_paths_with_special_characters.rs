@@ -0,0 +1,78 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::{PredicateBooleanExt, predicate};
+use rstest::rstest;
+
+const SPECIAL_NAME: &str = "a b\tc.txt";
+const QUOTED_NAME: &str = r#""a b\tc.txt""#;
+
+#[rstest]
+fn quote_paths_with_special_characters_and_accept_them_back(init_repository_dir: TempDir) {
+    let repository_dir = init_repository_dir;
+    write_file(FileSpec::new(
+        repository_dir.path().join(SPECIAL_NAME),
+        "special".to_string(),
+    ));
+
+    run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(format!("?? {}\n", QUOTED_NAME));
+
+    // The quoted name printed by status can be passed back as an argument
+    run_bit_command(repository_dir.path(), &["add", QUOTED_NAME])
+        .assert()
+        .success();
+
+    run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(format!("A  {}\n", QUOTED_NAME));
+}
+
+#[rstest]
+fn quote_paths_with_spaces_only_in_short_status(init_repository_dir: TempDir) {
+    let repository_dir = init_repository_dir;
+    write_file(FileSpec::new(
+        repository_dir.path().join("a b.txt"),
+        "spaced".to_string(),
+    ));
+
+    run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("?? \"a b.txt\"\n");
+
+    run_bit_command(repository_dir.path(), &["status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("a b.txt"))
+        .stdout(predicate::str::contains("\"a b.txt\"").not());
+}
+
+#[rstest]
+fn quote_non_ascii_paths_unless_quote_path_is_disabled(init_repository_dir: TempDir) {
+    let repository_dir = init_repository_dir;
+    write_file(FileSpec::new(
+        repository_dir.path().join("caf\u{e9}.txt"),
+        "coffee".to_string(),
+    ));
+
+    run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("?? \"caf\\303\\251.txt\"\n");
+
+    run_bit_command(
+        repository_dir.path(),
+        &["config", "core.quotePath", "false"],
+    )
+    .assert()
+    .success();
+
+    run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("?? caf\u{e9}.txt\n");
+}
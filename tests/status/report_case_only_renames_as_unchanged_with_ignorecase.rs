@@ -0,0 +1,47 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn porcelain_status(dir: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let output = run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success();
+
+    Ok(String::from_utf8(output.get_output().stdout.clone())?)
+}
+
+#[rstest]
+fn report_case_only_renames_as_unchanged_with_ignorecase(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    // The index tracks a/2.txt and a/b/3.txt; only the directory case changes
+    std::fs::rename(dir.join("a"), dir.join("A"))?;
+
+    // Only a case-sensitive filesystem can tell the two spellings apart
+    let case_sensitive = !dir.join("a").exists();
+    if case_sensitive {
+        run_bit_command(dir, &["config", "core.ignorecase", "false"])
+            .assert()
+            .success();
+        assert_eq!(porcelain_status(dir)?, " D a/2.txt\n D a/b/3.txt\n?? A/\n");
+    }
+
+    run_bit_command(dir, &["config", "core.ignorecase", "true"])
+        .assert()
+        .success();
+    assert_eq!(porcelain_status(dir)?, "");
+
+    // Changes are still reported, under the index spelling
+    write_file(FileSpec::new(
+        dir.join("A").join("b").join("3.txt"),
+        "three, changed".to_string(),
+    ));
+    assert_eq!(porcelain_status(dir)?, " M a/b/3.txt\n");
+
+    Ok(())
+}
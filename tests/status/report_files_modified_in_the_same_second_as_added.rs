@@ -0,0 +1,38 @@
+use crate::common::command::{repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+#[rstest]
+fn report_files_modified_in_the_same_second_as_added(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_bit_command(repository_dir.path(), &["init"])
+        .assert()
+        .success();
+
+    let file_path = repository_dir.path().join("racy.txt");
+    write_file(FileSpec::new(file_path.clone(), "before".to_string()));
+    let added_mtime = filetime::FileTime::from_last_modification_time(&file_path.metadata()?);
+
+    run_bit_command(repository_dir.path(), &["add", "racy.txt"])
+        .assert()
+        .success();
+
+    // Same size and, as on a file system with coarse timestamps, the same
+    // modification time as when the file was added
+    write_file(FileSpec::new(file_path.clone(), "after!".to_string()));
+    filetime::set_file_mtime(&file_path, added_mtime)?;
+
+    let expected_output = "AM racy.txt\n".to_string();
+    let actual_output = run_bit_command(repository_dir.path(), &["status", "--porcelain"])
+        .assert()
+        .success();
+    let stdout = actual_output.get_output().stdout.clone();
+    let actual_output = String::from_utf8(stdout)?;
+
+    assert_eq!(actual_output, expected_output);
+
+    Ok(())
+}
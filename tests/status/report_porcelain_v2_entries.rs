@@ -0,0 +1,106 @@
+use crate::common::command::{
+    bit_commit, bit_merge, init_repository_dir, repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, delete_path, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn porcelain_v2_outputs(dir: &Path) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let bit_output = run_bit_command(dir, &["status", "--porcelain=v2", "--renames"])
+        .assert()
+        .success();
+    let git_output = run_git_command(dir, &["status", "--porcelain=v2", "--branch"])
+        .assert()
+        .success();
+
+    Ok((
+        String::from_utf8(bit_output.get_output().stdout.clone())?,
+        String::from_utf8(git_output.get_output().stdout.clone())?,
+    ))
+}
+
+#[rstest]
+fn report_porcelain_v2_entries(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    // Staged: a modification, an addition and a rename
+    write_file(FileSpec::new(dir.join("1.txt"), "uno".to_string()));
+    write_file(FileSpec::new(dir.join("new.txt"), "new".to_string()));
+    delete_path(&dir.join("a").join("b").join("3.txt"));
+    write_file(FileSpec::new(dir.join("c.txt"), "three".to_string()));
+    run_bit_command(dir, &["add", "1.txt", "new.txt", "c.txt"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["add", "a/b/3.txt"])
+        .assert()
+        .failure();
+
+    // Unstaged: modifications on top of a staged one and of a clean file
+    write_file(FileSpec::new(dir.join("1.txt"), "uno!".to_string()));
+    write_file(FileSpec::new(
+        dir.join("a").join("2.txt"),
+        "dos".to_string(),
+    ));
+    write_file(FileSpec::new(dir.join("u.txt"), "untracked".to_string()));
+
+    let (bit_stdout, git_stdout) = porcelain_v2_outputs(dir)?;
+
+    let entries = bit_stdout
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.split(' ').take(2).collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>();
+    assert_eq!(entries, vec!["1 MM", "1 .M", "2 R.", "1 A.", "? u.txt"]);
+    assert_eq!(bit_stdout, git_stdout);
+
+    Ok(())
+}
+
+#[rstest]
+fn report_porcelain_v2_unmerged_entries(
+    repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    write_file(FileSpec::new(dir.join("f.txt"), "base\n".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Commit A").assert().success();
+    run_bit_command(dir, &["branch", "create", "topic"])
+        .assert()
+        .success();
+
+    write_file(FileSpec::new(
+        dir.join("f.txt"),
+        "master change\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Commit B - master").assert().success();
+
+    run_bit_command(dir, &["checkout", "topic"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        dir.join("f.txt"),
+        "topic change\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Commit C - topic").assert().success();
+
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    bit_merge(dir, "topic", "merge topic").assert().failure();
+
+    let (bit_stdout, git_stdout) = porcelain_v2_outputs(dir)?;
+
+    assert!(bit_stdout.contains("\nu UU N... 100644 100644 100644 100644 "));
+    assert!(bit_stdout.ends_with(" f.txt\n"));
+    assert_eq!(bit_stdout, git_stdout);
+
+    Ok(())
+}
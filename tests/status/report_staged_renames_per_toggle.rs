@@ -0,0 +1,62 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, delete_path, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+
+/// `1.txt` is moved to `moved.txt` with its content unchanged
+#[rstest]
+#[case::flag_on(None, &["--renames"], "R  1.txt -> moved.txt\n")]
+#[case::flag_off(None, &["--no-renames"], "D  1.txt\nA  moved.txt\n")]
+#[case::default_off(None, &[], "D  1.txt\nA  moved.txt\n")]
+#[case::config_on(Some("true"), &[], "R  1.txt -> moved.txt\n")]
+#[case::flag_overrides_config(Some("true"), &["--no-renames"], "D  1.txt\nA  moved.txt\n")]
+fn report_staged_renames_per_toggle(
+    init_repository_dir: TempDir,
+    #[case] config: Option<&str>,
+    #[case] flags: &[&str],
+    #[case] expected_output: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    delete_path(&dir.join("1.txt"));
+    write_file(FileSpec::new(dir.join("moved.txt"), "one".to_string()));
+    delete_path(&dir.join(".git").join("index"));
+    run_bit_command(dir, &["add", "."]).assert().success();
+
+    if let Some(value) = config {
+        run_bit_command(dir, &["config", "status.renames", value])
+            .assert()
+            .success();
+    }
+
+    let args = [&["status", "--porcelain"][..], flags].concat();
+    let output = run_bit_command(dir, &args).assert().success();
+    let actual_output = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert_eq!(actual_output, expected_output);
+
+    Ok(())
+}
+
+#[rstest]
+fn report_staged_rename_in_long_format(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    delete_path(&dir.join("1.txt"));
+    write_file(FileSpec::new(dir.join("moved.txt"), "one".to_string()));
+    delete_path(&dir.join(".git").join("index"));
+    run_bit_command(dir, &["add", "."]).assert().success();
+
+    let output = run_bit_command(dir, &["status", "--renames"])
+        .assert()
+        .success();
+    let actual_output = String::from_utf8(output.get_output().stdout.clone())?;
+
+    assert!(actual_output.contains("renamed:    1.txt -> moved.txt\n"));
+    assert!(!actual_output.contains("deleted:"));
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use crate::common::command::{bit_commit, bit_merge, repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+use std::path::Path;
+
+fn commit_file(dir: &Path, content: &str, message: &str) {
+    write_file(FileSpec::new(dir.join("f.txt"), content.to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, message).assert().success();
+}
+
+/// Long-format status opens with "You have unmerged paths." and lists each
+/// conflict under "Unmerged paths" with git's resolution hint.
+#[rstest]
+fn report_unmerged_paths_summary_and_hints(repository_dir: TempDir) {
+    let dir = repository_dir.path();
+    run_bit_command(dir, &["init"]).assert().success();
+
+    commit_file(dir, "base\n", "Commit A");
+    run_bit_command(dir, &["branch", "create", "topic"])
+        .assert()
+        .success();
+    commit_file(dir, "master change\n", "Commit B - master");
+    run_bit_command(dir, &["checkout", "topic"])
+        .assert()
+        .success();
+    commit_file(dir, "topic change\n", "Commit C - topic");
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+
+    bit_merge(dir, "topic", "merge topic").assert().failure();
+
+    run_bit_command(dir, &["status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "You have unmerged paths.\n\
+            \x20 (fix conflicts and run \"bit commit\")\n\
+            \n\
+            Unmerged paths:\n\
+            \x20 (use \"bit add <file>...\" to mark resolution)\n\
+            \x20       both modified:   f.txt\n\
+            \n",
+        ));
+}
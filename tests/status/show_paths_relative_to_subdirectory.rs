@@ -0,0 +1,50 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::{PredicateBooleanExt, predicate};
+use rstest::{fixture, rstest};
+
+/// Committed `1.txt`, `a/2.txt` and `a/b/3.txt`, with `1.txt` and `a/2.txt`
+/// modified and `other.txt` and `a/b/new.txt` left untracked
+#[fixture]
+fn repository_with_changes(init_repository_dir: TempDir) -> TempDir {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(dir.join("1.txt"), "changed one".to_string()));
+    write_file(FileSpec::new(
+        dir.join("a").join("2.txt"),
+        "changed two".to_string(),
+    ));
+    write_file(FileSpec::new(dir.join("other.txt"), "other".to_string()));
+    write_file(FileSpec::new(
+        dir.join("a").join("b").join("new.txt"),
+        "new".to_string(),
+    ));
+
+    init_repository_dir
+}
+
+#[rstest]
+fn long_status_shows_paths_relative_to_the_current_directory(repository_with_changes: TempDir) {
+    let subdir = repository_with_changes.path().join("a");
+
+    run_bit_command(&subdir, &["status"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("modified:   ../1.txt"))
+        .stdout(predicate::str::contains("modified:   2.txt"))
+        .stdout(predicate::str::contains("../other.txt"))
+        .stdout(predicate::str::contains("b/new.txt"))
+        .stdout(predicate::str::contains("a/2.txt").not())
+        .stdout(predicate::str::contains("a/b/new.txt").not());
+}
+
+#[rstest]
+fn porcelain_status_keeps_root_relative_paths(repository_with_changes: TempDir) {
+    let subdir = repository_with_changes.path().join("a").join("b");
+
+    run_bit_command(&subdir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(" M 1.txt\n M a/2.txt\n?? a/b/new.txt\n?? other.txt\n");
+}
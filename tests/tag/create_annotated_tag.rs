@@ -0,0 +1,91 @@
+use crate::common::command::{
+    get_head_commit_sha, init_repository_dir, run_bit_command, run_git_command,
+};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+#[rstest]
+fn annotated_tag_is_a_valid_tag_object(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let head = get_head_commit_sha(dir)?;
+
+    run_bit_command(dir, &["tag", "-a", "v1.0", "-m", "Release 1.0"])
+        .envs(vec![
+            ("GIT_AUTHOR_NAME", "fake_user"),
+            ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+            ("GIT_AUTHOR_DATE", "2023-01-01 12:00:00 +0000"),
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    let tag_oid = std::fs::read_to_string(dir.join(".git/refs/tags/v1.0"))?;
+    let tag_oid = tag_oid.trim();
+    assert_ne!(tag_oid, head);
+
+    let expected = format!(
+        "object {head}\n\
+        type commit\n\
+        tag v1.0\n\
+        tagger fake_user <fake_email@email.com> 1672574400 +0000\n\
+        \n\
+        Release 1.0\n"
+    );
+    run_git_command(dir, &["cat-file", "-t", tag_oid])
+        .assert()
+        .success()
+        .stdout("tag\n");
+    run_git_command(dir, &["cat-file", "-p", tag_oid])
+        .assert()
+        .success()
+        .stdout(expected.clone());
+
+    // bit reads the tag back the same way
+    run_bit_command(dir, &["cat-file", "-t", "v1.0"])
+        .assert()
+        .success()
+        .stdout("tag\n");
+    run_bit_command(dir, &["cat-file", "-p", tag_oid])
+        .assert()
+        .success()
+        .stdout(expected);
+
+    Ok(())
+}
+
+#[rstest]
+fn annotate_without_message_fails_when_not_interactive(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    // stdin is not a terminal, so the editor must not be started
+    run_bit_command(dir, &["tag", "-a", "v1.0"])
+        .env("EDITOR", "true")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no tag message given; use -m <message>",
+        ));
+
+    assert!(!dir.join(".git/refs/tags/v1.0").exists());
+}
+
+#[rstest]
+fn tags_are_listed_in_order(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    for name in ["v2.0", "release/candidate", "v1.0"] {
+        run_bit_command(dir, &["tag", name]).assert().success();
+    }
+
+    run_bit_command(dir, &["tag"])
+        .assert()
+        .success()
+        .stdout("release/candidate\nv1.0\nv2.0\n");
+    run_bit_command(dir, &["tag", "--list"])
+        .assert()
+        .success()
+        .stdout("release/candidate\nv1.0\nv2.0\n");
+}
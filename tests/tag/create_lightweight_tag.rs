@@ -0,0 +1,60 @@
+use crate::common::command::{
+    bit_commit, get_head_commit_sha, init_repository_dir, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use std::path::Path;
+
+fn git_stdout(dir: &Path, args: &[&str]) -> String {
+    let output = run_git_command(dir, args).assert().success();
+    String::from_utf8(output.get_output().stdout.clone()).unwrap()
+}
+
+#[rstest]
+fn lightweight_tags_point_at_commits(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let first = get_head_commit_sha(dir)?;
+    write_file(FileSpec::new(dir.join("1.txt"), "changed".to_string()));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Second commit").assert().success();
+    let second = get_head_commit_sha(dir)?;
+
+    run_bit_command(dir, &["tag", "v2.0"])
+        .assert()
+        .success()
+        .stdout("");
+    run_bit_command(dir, &["tag", "v1.0", "HEAD~1"])
+        .assert()
+        .success()
+        .stdout("");
+
+    let tags_dir = dir.join(".git").join("refs").join("tags");
+    assert_eq!(
+        std::fs::read_to_string(tags_dir.join("v1.0"))?.trim(),
+        first
+    );
+    assert_eq!(
+        std::fs::read_to_string(tags_dir.join("v2.0"))?.trim(),
+        second
+    );
+    // No tag object is written: the refs name the commits themselves
+    assert_eq!(git_stdout(dir, &["cat-file", "-t", "v1.0"]), "commit\n");
+    assert_eq!(
+        git_stdout(dir, &["tag", "--list"]),
+        String::from_utf8(run_bit_command(dir, &["tag", "--list"]).output()?.stdout)?
+    );
+
+    let bit_log = run_bit_command(dir, &["log", "--oneline", "--decorate=short"]).output()?;
+    let bit_log = String::from_utf8(bit_log.stdout)?;
+    assert_eq!(
+        bit_log,
+        git_stdout(dir, &["log", "--oneline", "--decorate=short"])
+    );
+    assert!(bit_log.contains("tag: v1.0) Initial commit"));
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::common::command::{get_head_commit_sha, init_repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+#[rstest]
+fn delete_tag_prints_the_old_value(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let head = get_head_commit_sha(dir)?;
+
+    run_bit_command(dir, &["tag", "v1.0"]).assert().success();
+    run_bit_command(dir, &["tag", "release/v2.0"])
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["tag", "-d", "v1.0", "release/v2.0"])
+        .assert()
+        .success()
+        .stdout(format!(
+            "Deleted tag 'v1.0' (was {short})\nDeleted tag 'release/v2.0' (was {short})\n",
+            short = &head[..7]
+        ));
+
+    let tags_dir = dir.join(".git").join("refs").join("tags");
+    assert!(!tags_dir.join("v1.0").exists());
+    assert!(!tags_dir.join("release").exists());
+
+    Ok(())
+}
+
+#[rstest]
+fn delete_missing_tag_fails(init_repository_dir: TempDir) {
+    run_bit_command(init_repository_dir.path(), &["tag", "-d", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("tag 'missing' not found"));
+}
@@ -0,0 +1,80 @@
+use crate::common::command::{
+    get_head_commit_sha, repository_with_multiple_commits, run_bit_command, run_git_command,
+};
+use assert_cmd::Command;
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+use std::path::Path;
+
+fn bit_tag(dir: &Path, args: &[&str]) -> Command {
+    let mut cmd = run_bit_command(dir, &[&["tag"], args].concat());
+    cmd.envs(vec![
+        ("GIT_AUTHOR_NAME", "fake_user"),
+        ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+        ("GIT_AUTHOR_DATE", "2023-01-01 12:00:00 +0000"),
+    ]);
+    cmd
+}
+
+fn read_tag(dir: &Path, name: &str) -> String {
+    std::fs::read_to_string(dir.join(".git").join("refs").join("tags").join(name))
+        .expect("tag ref should exist")
+        .trim()
+        .to_string()
+}
+
+#[rstest]
+fn annotated_tag_is_not_overwritten_without_force(
+    repository_with_multiple_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_multiple_commits.path();
+
+    bit_tag(dir, &["-a", "v1.0", "-m", "First", "HEAD~1"])
+        .assert()
+        .success();
+    let original = read_tag(dir, "v1.0");
+
+    bit_tag(dir, &["-a", "v1.0", "-m", "Second"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("tag 'v1.0' already exists"));
+    assert_eq!(read_tag(dir, "v1.0"), original);
+
+    // A lightweight tag must not replace it either
+    bit_tag(dir, &["v1.0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("tag 'v1.0' already exists"));
+    assert_eq!(read_tag(dir, "v1.0"), original);
+
+    Ok(())
+}
+
+#[rstest]
+fn force_overwrites_existing_annotated_tag(
+    repository_with_multiple_commits: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = repository_with_multiple_commits.path();
+    let head = get_head_commit_sha(dir)?;
+
+    bit_tag(dir, &["-a", "v1.0", "-m", "First", "HEAD~1"])
+        .assert()
+        .success();
+    let original = read_tag(dir, "v1.0");
+
+    bit_tag(dir, &["-f", "-a", "v1.0", "-m", "Second"])
+        .assert()
+        .success()
+        .stdout(format!("Updated tag 'v1.0' (was {})\n", &original[..7]));
+
+    let updated = read_tag(dir, "v1.0");
+    assert_ne!(updated, original);
+    run_git_command(dir, &["cat-file", "-p", &updated])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!("object {head}\n")))
+        .stdout(predicate::str::ends_with("\n\nSecond\n"));
+
+    Ok(())
+}
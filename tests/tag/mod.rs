@@ -0,0 +1,5 @@
+mod create_annotated_tag;
+mod create_lightweight_tag;
+mod delete_tag;
+mod force_overwrite_tag;
+mod use_annotated_tag_as_revision;
@@ -0,0 +1,120 @@
+use crate::common::command::{
+    bit_commit, bit_merge, get_branch_commit_sha, get_head_commit_sha, init_repository_dir,
+    run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+use std::path::Path;
+
+fn annotate(dir: &Path, name: &str) {
+    run_bit_command(dir, &["tag", "-a", name, "-m", "Release"])
+        .envs(vec![
+            ("GIT_AUTHOR_NAME", "fake_user"),
+            ("GIT_AUTHOR_EMAIL", "fake_email@email.com"),
+            ("GIT_AUTHOR_DATE", "2023-01-01 12:00:00 +0000"),
+        ])
+        .assert()
+        .success();
+}
+
+#[rstest]
+fn branch_created_at_annotated_tag_points_at_its_commit(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let head = get_head_commit_sha(dir)?;
+    annotate(dir, "v1.0");
+
+    run_bit_command(dir, &["branch", "create", "release", "v1.0"])
+        .assert()
+        .success();
+
+    assert_eq!(get_branch_commit_sha(dir, "release")?, head);
+
+    Ok(())
+}
+
+#[rstest]
+fn log_of_annotated_tag_starts_at_its_commit(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let head = get_head_commit_sha(dir)?;
+    annotate(dir, "v1.0");
+
+    run_bit_command(dir, &["log", "v1.0"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!("commit {head}")));
+
+    Ok(())
+}
+
+#[rstest]
+fn cat_file_names_the_tag_object_while_log_peels_it(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    let head = get_head_commit_sha(dir)?;
+    annotate(dir, "v1.0");
+
+    run_bit_command(dir, &["cat-file", "-t", "v1.0"])
+        .assert()
+        .success()
+        .stdout("tag\n");
+    run_bit_command(dir, &["log", "v1.0"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!("commit {head}")));
+
+    Ok(())
+}
+
+#[rstest]
+fn merge_of_annotated_tag_merges_its_commit(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+
+    run_bit_command(dir, &["branch", "create", "feature"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["checkout", "feature"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        dir.join("feature.txt"),
+        "feature\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Feature commit").assert().success();
+    let feature = get_head_commit_sha(dir)?;
+    annotate(dir, "v2.0");
+
+    run_bit_command(dir, &["checkout", "master"])
+        .assert()
+        .success();
+    write_file(FileSpec::new(
+        dir.join("master.txt"),
+        "master\n".to_string(),
+    ));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Master commit").assert().success();
+
+    bit_merge(dir, "v2.0", "Merge tag 'v2.0'")
+        .assert()
+        .success();
+
+    run_git_command(dir, &["rev-parse", "HEAD^2"])
+        .assert()
+        .success()
+        .stdout(format!("{feature}\n"));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("feature.txt"))?,
+        "feature\n"
+    );
+
+    Ok(())
+}
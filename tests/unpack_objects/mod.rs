@@ -0,0 +1 @@
+mod unpack_objects_restores_packed_repository;
@@ -0,0 +1,104 @@
+use crate::common::command::{
+    bit_commit, file_b, init_repository_dir_for_diff_hunks, run_bit_command, run_git_command,
+};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use rstest::rstest;
+use std::path::{Path, PathBuf};
+
+fn list_loose_objects(objects_dir: &Path) -> Vec<String> {
+    let mut objects = walkdir::WalkDir::new(objects_dir)
+        .min_depth(2)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let dir = entry.path().parent()?.file_name()?.to_str()?.to_string();
+            let name = entry.file_name().to_str()?.to_string();
+            (dir.len() == 2 && name.len() == 38).then(|| format!("{}{}", dir, name))
+        })
+        .collect::<Vec<_>>();
+    objects.sort();
+    objects
+}
+
+fn find_pack(objects_dir: &Path) -> PathBuf {
+    std::fs::read_dir(objects_dir.join("pack"))
+        .expect("Failed to read pack directory")
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "pack"))
+        .expect("No packfile found")
+}
+
+#[rstest]
+fn unpack_objects_restores_packed_repository(
+    init_repository_dir_for_diff_hunks: TempDir,
+    file_b: String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir_for_diff_hunks.path();
+    let objects_dir = dir.join(".git").join("objects");
+
+    write_file(FileSpec::new(dir.join("1.txt"), file_b));
+    run_bit_command(dir, &["add", "."]).assert().success();
+    bit_commit(dir, "Second commit").assert().success();
+
+    let expected_objects = list_loose_objects(&objects_dir);
+    assert!(!expected_objects.is_empty());
+
+    run_bit_command(dir, &["gc"]).assert().success();
+    assert!(list_loose_objects(&objects_dir).is_empty());
+
+    let pack = find_pack(&objects_dir);
+    run_git_command(dir, &["verify-pack"])
+        .arg(&pack)
+        .assert()
+        .success();
+
+    // Move the pack out of the repository so only loose objects remain
+    let pack_copy = dir.join("objects.pack");
+    std::fs::copy(&pack, &pack_copy)?;
+    std::fs::remove_dir_all(objects_dir.join("pack"))?;
+
+    run_bit_command(dir, &["unpack-objects", "objects.pack"])
+        .assert()
+        .success()
+        .stdout(format!("Unpacked {} objects\n", expected_objects.len()));
+
+    pretty_assertions::assert_eq!(list_loose_objects(&objects_dir), expected_objects);
+
+    run_git_command(dir, &["fsck", "--full", "--strict"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["log", "--oneline"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Second commit"))
+        .stdout(predicates::str::contains("Initial commit"));
+
+    Ok(())
+}
+
+#[rstest]
+fn unpack_objects_reads_pack_from_stdin(
+    init_repository_dir_for_diff_hunks: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir_for_diff_hunks.path();
+    let objects_dir = dir.join(".git").join("objects");
+
+    let expected_objects = list_loose_objects(&objects_dir);
+
+    run_bit_command(dir, &["gc"]).assert().success();
+    let pack = std::fs::read(find_pack(&objects_dir))?;
+    std::fs::remove_dir_all(objects_dir.join("pack"))?;
+
+    run_bit_command(dir, &["unpack-objects"])
+        .write_stdin(pack)
+        .assert()
+        .success();
+
+    pretty_assertions::assert_eq!(list_loose_objects(&objects_dir), expected_objects);
+
+    Ok(())
+}
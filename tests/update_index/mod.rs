@@ -0,0 +1,2 @@
+mod round_trip_index_version_4;
+mod update_index_flags;
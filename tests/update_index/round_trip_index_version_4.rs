@@ -0,0 +1,60 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use crate::{assert_index_eq, common};
+use assert_fs::TempDir;
+use rstest::rstest;
+
+/// Version stored in the header of the index
+fn index_version(index: &[u8]) -> u32 {
+    u32::from_be_bytes(index[4..8].try_into().unwrap())
+}
+
+#[rstest]
+fn removal_from_version_4_index_writes_the_same_index_as_git(
+    init_repository_dir: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir.path();
+    run_git_command(dir, &["update-index", "--index-version", "4"])
+        .assert()
+        .success();
+    let index_before = std::fs::read(dir.join(".git/index"))?;
+    assert_eq!(index_version(&index_before), 4);
+
+    run_bit_command(dir, &["rm", "--cached", "a/2.txt"])
+        .assert()
+        .success();
+    let bit_index_content = std::fs::read(dir.join(".git/index"))?;
+    assert_eq!(index_version(&bit_index_content), 4);
+
+    // Replay the removal with git from the same index
+    std::fs::write(dir.join(".git/index"), &index_before)?;
+    run_git_command(dir, &["rm", "-q", "--cached", "a/2.txt"])
+        .assert()
+        .success();
+
+    let git_index_content = std::fs::read(dir.join(".git/index"))?;
+    assert_index_eq!(&bit_index_content, &git_index_content);
+
+    Ok(())
+}
+
+#[rstest]
+fn version_4_index_keeps_extended_flags_readable_by_git(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    run_git_command(dir, &["update-index", "--index-version", "4"])
+        .assert()
+        .success();
+
+    run_bit_command(dir, &["update-index", "--skip-worktree", "a/b/3.txt"])
+        .assert()
+        .success();
+
+    run_git_command(dir, &["ls-files", "-v"])
+        .assert()
+        .success()
+        .stdout("H 1.txt\nH a/2.txt\nS a/b/3.txt\n");
+    run_bit_command(dir, &["verify-index"]).assert().success();
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+}
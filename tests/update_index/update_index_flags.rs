@@ -0,0 +1,92 @@
+use crate::common::command::{init_repository_dir, run_bit_command, run_git_command};
+use crate::common::file::{FileSpec, write_file};
+use assert_fs::TempDir;
+use predicates::prelude::predicate;
+use rstest::rstest;
+
+#[rstest]
+fn assume_unchanged_hides_modified_file_from_status(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(dir.join("1.txt"), "changed".to_string()));
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(" M 1.txt\n");
+
+    run_bit_command(dir, &["update-index", "--assume-unchanged", "1.txt"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+
+    // Clearing the bit makes the change visible again
+    run_bit_command(dir, &["update-index", "--no-assume-unchanged", "1.txt"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout(" M 1.txt\n");
+}
+
+#[rstest]
+fn skip_worktree_hides_deleted_file_from_status(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    run_bit_command(dir, &["update-index", "--skip-worktree", "a/2.txt"])
+        .assert()
+        .success();
+    std::fs::remove_file(dir.join("a").join("2.txt")).unwrap();
+
+    run_bit_command(dir, &["status", "--porcelain"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[rstest]
+fn update_index_flags_are_readable_by_git(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    run_bit_command(dir, &["update-index", "--assume-unchanged", "1.txt"])
+        .assert()
+        .success();
+    run_bit_command(dir, &["update-index", "--skip-worktree", "a/b/3.txt"])
+        .assert()
+        .success();
+
+    // Lowercase tags mark assume-unchanged entries, `S` marks skip-worktree ones
+    run_git_command(dir, &["ls-files", "-v"])
+        .assert()
+        .success()
+        .stdout("h 1.txt\nH a/2.txt\nS a/b/3.txt\n");
+
+    // Re-writing the index through bit keeps both flags
+    run_bit_command(dir, &["add", "a/2.txt"]).assert().success();
+    run_git_command(dir, &["ls-files", "-v"])
+        .assert()
+        .success()
+        .stdout("h 1.txt\nH a/2.txt\nS a/b/3.txt\n");
+
+    // Staging a flagged path again replaces its entry, flags included
+    run_bit_command(dir, &["add", "1.txt"]).assert().success();
+    run_git_command(dir, &["ls-files", "-v"])
+        .assert()
+        .success()
+        .stdout("H 1.txt\nH a/2.txt\nS a/b/3.txt\n");
+}
+
+#[rstest]
+fn update_index_rejects_untracked_path(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+
+    write_file(FileSpec::new(dir.join("new.txt"), "new".to_string()));
+
+    run_bit_command(dir, &["update-index", "--assume-unchanged", "new.txt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unable to mark file new.txt"));
+}
@@ -0,0 +1 @@
+mod verify_commit_checks_commit_structure;
@@ -0,0 +1,88 @@
+use crate::common::command::{
+    init_repository_dir_for_diff_hunks, run_bit_command, run_git_command,
+};
+use assert_fs::TempDir;
+use rstest::rstest;
+use std::path::Path;
+
+fn write_raw_commit(dir: &Path, content: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let output = run_git_command(
+        dir,
+        &[
+            "hash-object",
+            "-t",
+            "commit",
+            "--literally",
+            "-w",
+            "--stdin",
+        ],
+    )
+    .write_stdin(content.to_string())
+    .output()?;
+    assert!(output.status.success());
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+#[rstest]
+fn verify_commit_accepts_head(init_repository_dir_for_diff_hunks: TempDir) {
+    let dir = init_repository_dir_for_diff_hunks.path();
+
+    run_bit_command(dir, &["verify-commit", "HEAD"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[rstest]
+fn verify_commit_rejects_tree_line_pointing_to_blob(
+    init_repository_dir_for_diff_hunks: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir_for_diff_hunks.path();
+
+    let blob_output = run_git_command(dir, &["hash-object", "-w", "1.txt"]).output()?;
+    let blob_oid = String::from_utf8(blob_output.stdout)?.trim().to_string();
+
+    let commit_oid = write_raw_commit(
+        dir,
+        &format!(
+            "tree {blob_oid}\n\
+            author fake_user <fake_email@email.com> 1672574400 +0000\n\
+            committer fake_user <fake_email@email.com> 1672574400 +0000\n\
+            \n\
+            Tree is a blob\n"
+        ),
+    )?;
+
+    run_bit_command(dir, &["verify-commit", &commit_oid])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(format!(
+            "points to blob {blob_oid} as its tree"
+        )));
+
+    Ok(())
+}
+
+#[rstest]
+fn verify_commit_rejects_unparseable_author_date(
+    init_repository_dir_for_diff_hunks: TempDir,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = init_repository_dir_for_diff_hunks.path();
+
+    let commit_oid = write_raw_commit(
+        dir,
+        "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+        author fake_user <fake_email@email.com> someday +0000\n\
+        committer fake_user <fake_email@email.com> 1672574400 +0000\n\
+        \n\
+        Bad date\n",
+    )?;
+
+    run_bit_command(dir, &["verify-commit", &commit_oid])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("malformed author line"));
+
+    Ok(())
+}
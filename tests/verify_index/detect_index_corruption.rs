@@ -0,0 +1,78 @@
+use crate::common::command::{init_repository_dir, run_bit_command};
+use assert_fs::TempDir;
+use rstest::rstest;
+use std::path::Path;
+
+/// Offset of the first entry, right after the 12-byte header
+const FIRST_ENTRY_OFFSET: usize = 12;
+/// Size of the `1.txt` and `a/2.txt` entries: 62 fixed bytes plus the
+/// name, padded to 72 bytes
+const ENTRY_SIZE: usize = 72;
+
+fn rewrite_index(dir: &Path, corrupt: impl FnOnce(&mut Vec<u8>)) {
+    let index_path = dir.join(".git").join("index");
+    let mut bytes = std::fs::read(&index_path).expect("Failed to read index");
+    corrupt(&mut bytes);
+    std::fs::write(&index_path, bytes).expect("Failed to write index");
+}
+
+#[rstest]
+fn verify_index_accepts_written_index(init_repository_dir: TempDir) {
+    run_bit_command(init_repository_dir.path(), &["verify-index"])
+        .assert()
+        .success()
+        .stdout("");
+}
+
+#[rstest]
+fn verify_index_detects_unsorted_entries(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    // Swap the `1.txt` and `a/2.txt` entries
+    rewrite_index(dir, |bytes| {
+        let (first, second) = bytes[FIRST_ENTRY_OFFSET..].split_at_mut(ENTRY_SIZE);
+        first.swap_with_slice(&mut second[..ENTRY_SIZE]);
+    });
+
+    run_bit_command(dir, &["verify-index"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(format!(
+            "entry '1.txt' at offset {} is out of order (after 'a/2.txt')",
+            FIRST_ENTRY_OFFSET + ENTRY_SIZE
+        )));
+}
+
+#[rstest]
+fn verify_index_detects_non_zero_padding(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    // Overwrite the last padding byte of the `1.txt` entry
+    rewrite_index(dir, |bytes| {
+        bytes[FIRST_ENTRY_OFFSET + ENTRY_SIZE - 1] = b'x'
+    });
+
+    run_bit_command(dir, &["verify-index"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(format!(
+            "entry '1.txt' at offset {} is not NUL-terminated and padded to 8 bytes",
+            FIRST_ENTRY_OFFSET
+        )));
+}
+
+#[rstest]
+fn verify_index_detects_checksum_mismatch(init_repository_dir: TempDir) {
+    let dir = init_repository_dir.path();
+    let mut index_length = 0;
+    rewrite_index(dir, |bytes| {
+        index_length = bytes.len();
+        bytes[index_length - 1] ^= 0xFF;
+    });
+
+    run_bit_command(dir, &["verify-index"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(format!(
+            "index checksum mismatch at offset {}",
+            index_length - 20
+        )));
+}
@@ -0,0 +1 @@
+mod detect_index_corruption;